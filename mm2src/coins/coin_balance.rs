@@ -6,6 +6,7 @@ use crate::{BalanceError, BalanceResult, CoinBalance, CoinWithDerivationMethod,
 use async_trait::async_trait;
 use common::log::{debug, info};
 use crypto::{Bip44Chain, RpcDerivationPath};
+use derive_more::Display;
 use futures::compat::Future01CompatExt;
 use mm2_err_handle::prelude::*;
 use mm2_number::BigDecimal;
@@ -16,6 +17,7 @@ use std::{fmt, iter};
 
 pub type AddressIdRange = Range<u32>;
 
+#[derive(Display)]
 pub enum EnableCoinBalanceError {
     NewAddressDerivingError(NewAddressDerivingError),
     NewAccountCreatingError(NewAccountCreatingError),
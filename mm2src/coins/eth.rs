@@ -71,7 +71,7 @@ use std::sync::{Arc, Mutex};
 use web3::types::{Action as TraceAction, BlockId, BlockNumber, Bytes, CallRequest, FilterBuilder, Log, Trace,
                   TraceFilterBuilder, Transaction as Web3Transaction, TransactionId, U64};
 use web3::{self, Web3};
-use web3_transport::{http_transport::HttpTransportNode, EthFeeHistoryNamespace, Web3Transport};
+use web3_transport::{http_transport::HttpTransportNode, EthFeeHistoryNamespace, Web3NodeSelection, Web3Transport};
 
 cfg_wasm32! {
     use crypto::MetamaskArc;
@@ -107,7 +107,17 @@ pub use rlp;
 mod web3_transport;
 
 #[path = "eth/v2_activation.rs"] pub mod v2_activation;
+
+#[path = "eth/contract_call.rs"] pub mod contract_call;
+#[path = "eth/eip712_sign.rs"] pub mod eip712_sign;
+#[path = "eth/erc20_allowance.rs"] pub mod erc20_allowance;
+#[path = "eth/l1_fee.rs"] pub mod l1_fee;
+#[path = "eth/nft_swap.rs"] pub mod nft_swap;
 use crate::nft::{find_wallet_nft_amount, WithdrawNftResult};
+use l1_fee::EvmChainType;
+pub use nft_swap::NftSwapToken;
+use nft_swap::{bound_nft_token, nft_amount_from_big_decimal, nft_swap_contract_type, nft_swap_functions,
+               NFT_SWAP_CONTRACT};
 use v2_activation::{build_address_and_priv_key_policy, EthActivationV2Error};
 
 mod nonce;
@@ -365,6 +375,15 @@ struct SavedErc20Events {
     latest_block: U64,
 }
 
+/// Optional `protocol_data` of the ETH protocol in the coins config.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct EthProtocolInfo {
+    /// Whether the NFT feature is enabled for the chain of this platform coin.
+    /// If not set, the feature is enabled only for the chains supported before it became configurable.
+    #[serde(default)]
+    pub nft: Option<bool>,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum EthCoinType {
     /// Ethereum itself or it's forks: ETC/others
@@ -372,6 +391,12 @@ pub enum EthCoinType {
     /// ERC20 token with smart contract address
     /// https://github.com/ethereum/EIPs/blob/master/EIPS/eip-20.md
     Erc20 { platform: String, token_addr: Address },
+    /// ERC721/ERC1155 tokens of the platform chain traded with 0 decimals, swapped via the `EtomicSwapNft` contract.
+    /// The token is set only for the coin instances bound to the NFT of a specific order or swap.
+    Nft {
+        platform: String,
+        token: Option<NftSwapToken>,
+    },
 }
 
 /// An alternative to `crate::PrivKeyBuildPolicy`, typical only for ETH coin.
@@ -422,6 +447,12 @@ pub struct EthCoinImpl {
     swap_contract_address: Address,
     fallback_swap_contract: Option<Address>,
     contract_supports_watchers: bool,
+    /// Whether ERC20 swap payments should approve exactly the payment amount
+    /// instead of giving the swap contract an unlimited allowance.
+    use_exact_approve: bool,
+    /// Whether the token reverts `approve` calls that change a non-zero allowance to another non-zero value (e.g. USDT),
+    /// so the allowance has to be reset to zero first.
+    approve_reset_required: bool,
     pub(crate) web3: Web3<Web3Transport>,
     /// The separate web3 instances kept to get nonce, will replace the web3 completely soon
     web3_instances: Vec<Web3Instance>,
@@ -435,6 +466,8 @@ pub struct EthCoinImpl {
     /// Using a weak reference by default in order to avoid circular references and leaks.
     pub ctx: MmWeak,
     chain_id: Option<u64>,
+    /// Determines whether the L1 data fee is added to the transaction fee.
+    evm_chain_type: EvmChainType,
     /// the block range used for eth_getLogs
     logs_block_range: u64,
     nonce_lock: Arc<AsyncMutex<()>>,
@@ -483,6 +516,10 @@ async fn make_gas_station_request(url: &str) -> GasStationResult {
 }
 
 impl EthCoinImpl {
+    /// Returns a `Web3` instance requiring the configured quorum of nodes to return the same response.
+    /// Should be used for security-critical requests like swap payment validation, spend search and confirmations.
+    fn quorum_web3(&self) -> Web3<Web3Transport> { Web3::new(self.web3.transport().with_quorum_reads()) }
+
     /// Gets Transfer events from ERC20 smart contract `addr` between `from_block` and `to_block`
     fn erc20_transfer_events(
         &self,
@@ -640,6 +677,17 @@ impl EthCoinImpl {
         sha256(&input).to_vec()
     }
 
+    /// Returns the swap contract method that sends a payment of this coin.
+    fn swap_payment_function(&self, watcher_reward: bool) -> Result<&'static Function, ethabi::Error> {
+        match &self.coin_type {
+            EthCoinType::Eth => SWAP_CONTRACT.function(&get_function_name("ethPayment", watcher_reward)),
+            EthCoinType::Erc20 { .. } => SWAP_CONTRACT.function(&get_function_name("erc20Payment", watcher_reward)),
+            EthCoinType::Nft { token, .. } => {
+                NFT_SWAP_CONTRACT.function(nft_swap_functions(nft_swap_contract_type(token)).payment)
+            },
+        }
+    }
+
     /// Gets `SenderRefunded` events from etomic swap smart contract since `from_block`
     fn refund_events(
         &self,
@@ -655,7 +703,13 @@ impl EthCoinImpl {
             .address(vec![swap_contract_address])
             .build();
 
-        Box::new(self.web3.eth().logs(filter).compat().map_err(|e| ERRL!("{}", e)))
+        Box::new(
+            self.quorum_web3()
+                .eth()
+                .logs(filter)
+                .compat()
+                .map_err(|e| ERRL!("{}", e)),
+        )
     }
 
     /// Try to parse address from string.
@@ -666,7 +720,7 @@ impl EthCoinImpl {
     pub fn erc20_token_address(&self) -> Option<Address> {
         match self.coin_type {
             EthCoinType::Erc20 { token_addr, .. } => Some(token_addr),
-            EthCoinType::Eth => None,
+            EthCoinType::Eth | EthCoinType::Nft { .. } => None,
         }
     }
 
@@ -753,12 +807,23 @@ async fn withdraw_impl(coin: EthCoin, req: WithdrawRequest) -> WithdrawResult {
             let data = function.encode_input(&[Token::Address(to_addr), Token::Uint(wei_amount)])?;
             (0.into(), data, *token_addr, platform.as_str())
         },
+        EthCoinType::Nft { platform, token } => {
+            let token = bound_nft_token(token).map_to_mm(WithdrawError::InternalError)?;
+            let data = coin.nft_transfer_data(to_addr, wei_amount, token.token_id_u256(), token.contract_type)?;
+            (0.into(), data, token.token_address, platform.as_str())
+        },
     };
     let eth_value_dec = u256_to_big_decimal(eth_value, coin.decimals)?;
 
+    let gas_estimated = req.fee.is_none();
     let (gas, gas_price) =
         get_eth_gas_details(&coin, req.fee, eth_value, data.clone().into(), call_addr, req.max).await?;
-    let total_fee = gas * gas_price;
+    // the L1 fee is estimated before deducting the fee from the max amount, so it can only be a bit overestimated
+    let l1_cost = coin
+        .estimate_l1_cost(call_addr, eth_value, &data, gas, gas_price, gas_estimated)
+        .await?;
+    let gas = gas + l1_cost.gas;
+    let total_fee = gas * gas_price + l1_cost.fee;
     let total_fee_dec = u256_to_big_decimal(total_fee, coin.decimals)?;
 
     if req.max && coin.coin_type == EthCoinType::Eth {
@@ -848,7 +913,7 @@ async fn withdraw_impl(coin: EthCoin, req: WithdrawRequest) -> WithdrawResult {
     } else {
         0.into()
     };
-    let fee_details = EthTxFeeDetails::new(gas, gas_price, fee_coin)?;
+    let fee_details = EthTxFeeDetails::with_l1_fee(gas, gas_price, l1_cost.fee, fee_coin)?;
     if coin.coin_type == EthCoinType::Eth {
         spent_by_me += &fee_details.total_fee;
     }
@@ -920,12 +985,13 @@ pub async fn withdraw_erc1155(ctx: MmArc, withdraw_type: WithdrawErc1155) -> Wit
             ])?;
             (0.into(), data, token_addr, eth_coin.ticker())
         },
-        EthCoinType::Erc20 { .. } => {
+        EthCoinType::Erc20 { .. } | EthCoinType::Nft { .. } => {
             return MmError::err(WithdrawError::InternalError(
-                "Erc20 coin type doesnt support withdraw nft".to_owned(),
+                "Erc20 and Nft coin types dont support withdraw nft".to_owned(),
             ))
         },
     };
+    let gas_estimated = withdraw_type.fee.is_none();
     let (gas, gas_price) = get_eth_gas_details(
         &eth_coin,
         withdraw_type.fee,
@@ -935,6 +1001,10 @@ pub async fn withdraw_erc1155(ctx: MmArc, withdraw_type: WithdrawErc1155) -> Wit
         false,
     )
     .await?;
+    let l1_cost = eth_coin
+        .estimate_l1_cost(call_addr, eth_value, &data, gas, gas_price, gas_estimated)
+        .await?;
+    let gas = gas + l1_cost.gas;
     let _nonce_lock = eth_coin.nonce_lock.lock().await;
     let (nonce, _) = get_addr_nonce(eth_coin.my_address, eth_coin.web3_instances.clone())
         .compat()
@@ -954,7 +1024,7 @@ pub async fn withdraw_erc1155(ctx: MmArc, withdraw_type: WithdrawErc1155) -> Wit
     let secret = eth_coin.priv_key_policy.activated_key_or_err()?.secret();
     let signed = tx.sign(secret, eth_coin.chain_id);
     let signed_bytes = rlp::encode(&signed);
-    let fee_details = EthTxFeeDetails::new(gas, gas_price, fee_coin)?;
+    let fee_details = EthTxFeeDetails::with_l1_fee(gas, gas_price, l1_cost.fee, fee_coin)?;
 
     Ok(TransactionNftDetails {
         tx_hex: BytesJson::from(signed_bytes.to_vec()),
@@ -995,12 +1065,13 @@ pub async fn withdraw_erc721(ctx: MmArc, withdraw_type: WithdrawErc721) -> Withd
             ])?;
             (0.into(), data, token_addr, eth_coin.ticker())
         },
-        EthCoinType::Erc20 { .. } => {
+        EthCoinType::Erc20 { .. } | EthCoinType::Nft { .. } => {
             return MmError::err(WithdrawError::InternalError(
-                "Erc20 coin type doesnt support withdraw nft".to_owned(),
+                "Erc20 and Nft coin types dont support withdraw nft".to_owned(),
             ))
         },
     };
+    let gas_estimated = withdraw_type.fee.is_none();
     let (gas, gas_price) = get_eth_gas_details(
         &eth_coin,
         withdraw_type.fee,
@@ -1010,6 +1081,10 @@ pub async fn withdraw_erc721(ctx: MmArc, withdraw_type: WithdrawErc721) -> Withd
         false,
     )
     .await?;
+    let l1_cost = eth_coin
+        .estimate_l1_cost(call_addr, eth_value, &data, gas, gas_price, gas_estimated)
+        .await?;
+    let gas = gas + l1_cost.gas;
     let _nonce_lock = eth_coin.nonce_lock.lock().await;
     let (nonce, _) = get_addr_nonce(eth_coin.my_address, eth_coin.web3_instances.clone())
         .compat()
@@ -1029,7 +1104,7 @@ pub async fn withdraw_erc721(ctx: MmArc, withdraw_type: WithdrawErc721) -> Withd
     let secret = eth_coin.priv_key_policy.activated_key_or_err()?.secret();
     let signed = tx.sign(secret, eth_coin.chain_id);
     let signed_bytes = rlp::encode(&signed);
-    let fee_details = EthTxFeeDetails::new(gas, gas_price, fee_coin)?;
+    let fee_details = EthTxFeeDetails::with_l1_fee(gas, gas_price, l1_cost.fee, fee_coin)?;
 
     Ok(TransactionNftDetails {
         tx_hex: BytesJson::from(signed_bytes.to_vec()),
@@ -1246,8 +1321,15 @@ impl SwapOps for EthCoin {
         watcher_reward: bool,
     ) -> Result<Vec<u8>, String> {
         let unverified: UnverifiedTransaction = try_s!(rlp::decode(spend_tx));
-        let function_name = get_function_name("receiverSpend", watcher_reward);
-        let function = try_s!(SWAP_CONTRACT.function(&function_name));
+        let function = match &self.coin_type {
+            EthCoinType::Nft { token, .. } => {
+                let contract_type = try_s!(bound_nft_token(token)).contract_type;
+                try_s!(NFT_SWAP_CONTRACT.function(nft_swap_functions(contract_type).spend))
+            },
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => {
+                try_s!(SWAP_CONTRACT.function(&get_function_name("receiverSpend", watcher_reward)))
+            },
+        };
 
         // Validate contract call; expected to be receiverSpend.
         // https://www.4byte.directory/signatures/?bytes4_signature=02ed292b.
@@ -1255,17 +1337,24 @@ impl SwapOps for EthCoin {
         let actual_signature = &unverified.data[0..4];
         if actual_signature != expected_signature {
             return ERR!(
-                "Expected 'receiverSpend' contract call signature: {:?}, found {:?}",
+                "Expected '{}' contract call signature: {:?}, found {:?}",
+                function.name,
                 expected_signature,
                 actual_signature
             );
         };
 
+        // The secret goes after the payment id and the amount in `receiverSpend`, but the amount is absent for ERC721.
+        let secret_index = try_s!(function
+            .inputs
+            .iter()
+            .position(|param| param.name == "_secret")
+            .ok_or_else(|| ERRL!("'{}' has no secret parameter", function.name)));
         let tokens = try_s!(decode_contract_call(function, &unverified.data));
-        if tokens.len() < 3 {
-            return ERR!("Invalid arguments in 'receiverSpend' call: {:?}", tokens);
+        if tokens.len() <= secret_index {
+            return ERR!("Invalid arguments in '{}' call: {:?}", function.name, tokens);
         }
-        match &tokens[2] {
+        match &tokens[secret_index] {
             Token::FixedBytes(secret) => Ok(secret.to_vec()),
             _ => ERR!(
                 "Expected secret to be fixed bytes, decoded function data is {:?}",
@@ -1396,7 +1485,8 @@ impl SwapOps for EthCoin {
     }
 
     fn is_supported_by_watchers(&self) -> bool {
-        std::env::var("USE_WATCHER_REWARD").is_ok()
+        // the NFT swap contract doesn't support watcher rewards
+        std::env::var("USE_WATCHER_REWARD").is_ok() && !matches!(self.coin_type, EthCoinType::Nft { .. })
         //self.contract_supports_watchers
     }
 }
@@ -1709,6 +1799,11 @@ impl WatcherOps for EthCoin {
                         )));
                     }
                 },
+                EthCoinType::Nft { .. } => {
+                    return MmError::err(ValidatePaymentError::InternalError(
+                        "NFT swaps are not supported by watchers".to_owned(),
+                    ))
+                },
             }
 
             Ok(())
@@ -1950,6 +2045,11 @@ impl WatcherOps for EthCoin {
                         )));
                     }
                 },
+                EthCoinType::Nft { .. } => {
+                    return MmError::err(ValidatePaymentError::InternalError(
+                        "NFT swaps are not supported by watchers".to_owned(),
+                    ))
+                },
             }
 
             Ok(())
@@ -2043,6 +2143,11 @@ impl WatcherOps for EthCoin {
                                 })?
                         }
                     },
+                    EthCoinType::Nft { .. } => {
+                        return MmError::err(WatcherRewardError::InvalidCoinType(
+                            "NFT swaps are not supported by watchers".to_owned(),
+                        ))
+                    },
                 }
             },
         };
@@ -2139,7 +2244,7 @@ impl MarketCoinOps for EthCoin {
     fn platform_ticker(&self) -> &str {
         match &self.coin_type {
             EthCoinType::Eth => self.ticker(),
-            EthCoinType::Erc20 { platform, .. } => platform,
+            EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform,
         }
     }
 
@@ -2272,12 +2377,7 @@ impl MarketCoinOps for EthCoin {
             },
         };
 
-        let func_name = match self.coin_type {
-            EthCoinType::Eth => get_function_name("ethPayment", args.watcher_reward),
-            EthCoinType::Erc20 { .. } => get_function_name("erc20Payment", args.watcher_reward),
-        };
-
-        let payment_func = try_tx_fus!(SWAP_CONTRACT.function(&func_name));
+        let payment_func = try_tx_fus!(self.swap_payment_function(args.watcher_reward));
         let decoded = try_tx_fus!(decode_contract_call(payment_func, &tx.data));
         let id = match decoded.first() {
             Some(Token::FixedBytes(bytes)) => bytes.clone(),
@@ -2404,6 +2504,49 @@ lazy_static! {
     static ref NONCE_LOCK: Mutex<HashMap<String, Arc<AsyncMutex<()>>>> = Mutex::new(HashMap::new());
 }
 
+lazy_static! {
+    static ref SWAP_APPROVE_LOCK: Mutex<HashMap<String, Arc<AsyncMutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+/// Returns the lock that serializes swap approvals and payments of the given ERC20 token.
+fn swap_approve_lock(ticker: &str) -> Arc<AsyncMutex<()>> {
+    let mut map = SWAP_APPROVE_LOCK.lock().unwrap();
+    map.entry(ticker.to_owned()).or_insert_with(new_nonce_lock).clone()
+}
+
+/// Returns the amounts that should be approved one after another for the swap contract
+/// before sending an ERC20 payment of `payment_amount`, if the swap contract is `allowed` to spend some tokens already.
+///
+/// Exact approvals are always sent, as the current allowance may be reserved by a pending payment of another swap.
+/// The approve transactions are sent before the payment, so the nonce order guarantees that the pending payment
+/// consumes the old allowance before it's overwritten.
+fn swap_approve_amounts(
+    allowed: U256,
+    payment_amount: U256,
+    use_exact_approve: bool,
+    approve_reset_required: bool,
+) -> Vec<U256> {
+    let approve_amount = if use_exact_approve {
+        payment_amount
+    } else if allowed < payment_amount {
+        U256::max_value()
+    } else {
+        return Vec::new();
+    };
+
+    if is_approve_reset_needed(approve_reset_required, allowed, approve_amount) {
+        vec![U256::zero(), approve_amount]
+    } else {
+        vec![approve_amount]
+    }
+}
+
+/// Checks whether the `allowed` amount has to be reset to zero before approving `new_allowance`
+/// for tokens that revert on changing a non-zero allowance to another non-zero value.
+fn is_approve_reset_needed(approve_reset_required: bool, allowed: U256, new_allowance: U256) -> bool {
+    approve_reset_required && !allowed.is_zero() && !new_allowance.is_zero() && allowed != new_allowance
+}
+
 type EthTxFut = Box<dyn Future<Item = SignedEthTx, Error = TransactionErr> + Send + 'static>;
 
 async fn sign_and_send_transaction_with_keypair(
@@ -2766,7 +2909,7 @@ impl EthCoin {
                 };
                 let fee_coin = match &self.coin_type {
                     EthCoinType::Eth => self.ticker(),
-                    EthCoinType::Erc20 { platform, .. } => platform.as_str(),
+                    EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform.as_str(),
                 };
                 let fee_details: Option<EthTxFeeDetails> = match receipt {
                     Some(r) => {
@@ -3147,7 +3290,7 @@ impl EthCoin {
                 };
                 let fee_coin = match &self.coin_type {
                     EthCoinType::Eth => self.ticker(),
-                    EthCoinType::Erc20 { platform, .. } => platform.as_str(),
+                    EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform.as_str(),
                 };
                 let fee_details = match receipt {
                     Some(r) => {
@@ -3258,9 +3401,23 @@ impl EthCoin {
         Box::new(fut.boxed().compat())
     }
 
+    /// Signs and sends the transaction with the fixed execution `gas`.
+    /// On Arbitrum the gas limit is increased by the L1 gas charged for the transaction data,
+    /// the transactions with the `eth_estimateGas` gas limit should be sent by [`EthCoin::sign_and_send_transaction`].
+    fn sign_and_send_transaction_with_l1_gas(&self, value: U256, action: Action, data: Vec<u8>, gas: U256) -> EthTxFut {
+        let coin = self.clone();
+        let fut = async move {
+            let gas = try_tx_s!(coin.gas_with_l1_gas(&action, &data, gas).await);
+            coin.sign_and_send_transaction(value, action, data, gas).compat().await
+        };
+        Box::new(fut.boxed().compat())
+    }
+
     pub fn send_to_address(&self, address: Address, value: U256) -> EthTxFut {
         match &self.coin_type {
-            EthCoinType::Eth => self.sign_and_send_transaction(value, Action::Call(address), vec![], U256::from(21000)),
+            EthCoinType::Eth => {
+                self.sign_and_send_transaction_with_l1_gas(value, Action::Call(address), vec![], U256::from(21000))
+            },
             EthCoinType::Erc20 {
                 platform: _,
                 token_addr,
@@ -3268,7 +3425,23 @@ impl EthCoin {
                 let abi = try_tx_fus!(Contract::load(ERC20_ABI.as_bytes()));
                 let function = try_tx_fus!(abi.function("transfer"));
                 let data = try_tx_fus!(function.encode_input(&[Token::Address(address), Token::Uint(value)]));
-                self.sign_and_send_transaction(0.into(), Action::Call(*token_addr), data, U256::from(210_000))
+                self.sign_and_send_transaction_with_l1_gas(
+                    0.into(),
+                    Action::Call(*token_addr),
+                    data,
+                    U256::from(210_000),
+                )
+            },
+            EthCoinType::Nft { token, .. } => {
+                let token = try_tx_fus!(bound_nft_token(token));
+                let data =
+                    try_tx_fus!(self.nft_transfer_data(address, value, token.token_id_u256(), token.contract_type));
+                self.sign_and_send_transaction_with_l1_gas(
+                    0.into(),
+                    Action::Call(token.token_address),
+                    data,
+                    U256::from(210_000),
+                )
             },
         }
     }
@@ -3319,7 +3492,7 @@ impl EthCoin {
                     ])),
                 };
 
-                self.sign_and_send_transaction(value, Action::Call(swap_contract_address), data, gas)
+                self.sign_and_send_transaction_with_l1_gas(value, Action::Call(swap_contract_address), data, gas)
             },
             EthCoinType::Erc20 {
                 platform: _,
@@ -3372,45 +3545,53 @@ impl EthCoin {
                 let wait_for_required_allowance_until = args.wait_for_confirmation_until;
 
                 let arc = self.clone();
-                Box::new(allowance_fut.and_then(move |allowed| -> EthTxFut {
-                    if allowed < amount {
-                        Box::new(
-                            arc.approve(swap_contract_address, U256::max_value())
-                                .and_then(move |approved| {
-                                    // make sure the approve tx is confirmed by making sure that the allowed value has been updated
-                                    // this call is cheaper than waiting for confirmation calls
-                                    arc.wait_for_required_allowance(
-                                        swap_contract_address,
-                                        amount,
-                                        wait_for_required_allowance_until,
-                                    )
-                                    .map_err(move |e| {
-                                        TransactionErr::Plain(ERRL!(
-                                            "Allowed value was not updated in time after sending approve transaction {:02x}: {}",
-                                            approved.tx_hash(),
-                                            e
-                                        ))
-                                    })
-                                    .and_then(move |_| {
-                                        arc.sign_and_send_transaction(
-                                            value,
-                                            Action::Call(swap_contract_address),
-                                            data,
-                                            gas,
-                                        )
-                                    })
-                                }),
-                        )
+                let fut = async move {
+                    // With exact approvals, the allowance left by a concurrent swap belongs to its pending payment.
+                    // Approvals and payments have to be sent one by one so that approving for one swap
+                    // doesn't overwrite the allowance of another swap before its payment is sent.
+                    let approve_lock = swap_approve_lock(&arc.ticker);
+                    let _approve_guard = if arc.use_exact_approve {
+                        Some(approve_lock.lock().await)
                     } else {
-                        Box::new(arc.sign_and_send_transaction(
-                            value,
-                            Action::Call(swap_contract_address),
-                            data,
-                            gas,
-                        ))
+                        None
+                    };
+
+                    let allowed = allowance_fut.compat().await?;
+                    let approve_amounts =
+                        swap_approve_amounts(allowed, amount, arc.use_exact_approve, arc.approve_reset_required);
+
+                    let mut last_approve = None;
+                    for approve_amount in approve_amounts {
+                        let approved = arc.approve(swap_contract_address, approve_amount).compat().await?;
+                        last_approve = Some(approved);
                     }
-                }))
+
+                    if let Some(approved) = last_approve {
+                        // make sure the approve tx is confirmed by making sure that the allowed value has been updated
+                        // this call is cheaper than waiting for confirmation calls
+                        arc.wait_for_required_allowance(
+                            swap_contract_address,
+                            amount,
+                            wait_for_required_allowance_until,
+                        )
+                        .compat()
+                        .await
+                        .map_err(|e| {
+                            TransactionErr::Plain(ERRL!(
+                                "Allowed value was not updated in time after sending approve transaction {:02x}: {}",
+                                approved.tx_hash(),
+                                e
+                            ))
+                        })?;
+                    }
+
+                    arc.sign_and_send_transaction_with_l1_gas(value, Action::Call(swap_contract_address), data, gas)
+                        .compat()
+                        .await
+                };
+                Box::new(fut.boxed().compat())
             },
+            EthCoinType::Nft { token, .. } => self.send_nft_swap_payment(args, try_tx_fus!(bound_nft_token(token))),
         }
     }
 
@@ -3470,7 +3651,7 @@ impl EthCoin {
                                 watcher_reward_amount,
                             ]));
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3518,7 +3699,7 @@ impl EthCoin {
                                 sends_contract_reward,
                                 reward_amount
                             ]));
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3527,6 +3708,9 @@ impl EthCoin {
                         }),
                 )
             },
+            EthCoinType::Nft { .. } => Box::new(futures01::future::err(TransactionErr::Plain(ERRL!(
+                "NFT swaps are not supported by watchers"
+            )))),
         }
     }
 
@@ -3587,7 +3771,7 @@ impl EthCoin {
                                 reward_amount
                             ]));
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3638,7 +3822,7 @@ impl EthCoin {
                                 reward_amount
                             ]));
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3647,6 +3831,9 @@ impl EthCoin {
                         }),
                 )
             },
+            EthCoinType::Nft { .. } => Box::new(futures01::future::err(TransactionErr::Plain(ERRL!(
+                "NFT swaps are not supported by watchers"
+            )))),
         }
     }
 
@@ -3703,7 +3890,7 @@ impl EthCoin {
                                 ]))
                             };
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3755,7 +3942,7 @@ impl EthCoin {
                                 ]))
                             };
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3764,6 +3951,10 @@ impl EthCoin {
                         }),
                 )
             },
+            EthCoinType::Nft { ref token, .. } => {
+                let contract_type = try_tx_fus!(bound_nft_token(token)).contract_type;
+                self.spend_nft_swap_payment(payment, swap_contract_address, secret_vec, contract_type)
+            },
         }
     }
 
@@ -3821,7 +4012,7 @@ impl EthCoin {
                                 ]))
                             };
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3873,7 +4064,7 @@ impl EthCoin {
                                 ]))
                             };
 
-                            clone.sign_and_send_transaction(
+                            clone.sign_and_send_transaction_with_l1_gas(
                                 0.into(),
                                 Action::Call(swap_contract_address),
                                 data,
@@ -3882,6 +4073,10 @@ impl EthCoin {
                         }),
                 )
             },
+            EthCoinType::Nft { ref token, .. } => {
+                let contract_type = try_tx_fus!(bound_nft_token(token)).contract_type;
+                self.refund_nft_swap_payment(payment, swap_contract_address, contract_type)
+            },
         }
     }
 
@@ -3904,6 +4099,14 @@ impl EthCoin {
                         },
                     }
                 },
+                // the coin not bound to a token has nothing to trade
+                EthCoinType::Nft { token: None, .. } => Ok(U256::zero()),
+                EthCoinType::Nft {
+                    token: Some(ref token), ..
+                } => {
+                    coin.nft_balance(address, token.token_address, token.token_id_u256(), token.contract_type)
+                        .await
+                },
             }
         };
         Box::new(fut.boxed().compat())
@@ -4026,6 +4229,9 @@ impl EthCoin {
                         },
                     }
                 },
+                EthCoinType::Nft { .. } => MmError::err(Web3RpcError::Internal(
+                    "'allowance' must not be called for NFT coin".to_owned(),
+                )),
             }
         };
         Box::new(fut.boxed().compat())
@@ -4069,7 +4275,9 @@ impl EthCoin {
         let coin = self.clone();
         let fut = async move {
             let token_addr = match coin.coin_type {
-                EthCoinType::Eth => return TX_PLAIN_ERR!("'approve' is expected to be call for ERC20 coins only"),
+                EthCoinType::Eth | EthCoinType::Nft { .. } => {
+                    return TX_PLAIN_ERR!("'approve' is expected to be call for ERC20 coins only")
+                },
                 EthCoinType::Erc20 { token_addr, .. } => token_addr,
             };
             let function = try_tx_s!(ERC20_CONTRACT.function("approve"));
@@ -4103,7 +4311,13 @@ impl EthCoin {
             .address(vec![swap_contract_address])
             .build();
 
-        Box::new(self.web3.eth().logs(filter).compat().map_err(|e| ERRL!("{}", e)))
+        Box::new(
+            self.quorum_web3()
+                .eth()
+                .logs(filter)
+                .compat()
+                .map_err(|e| ERRL!("{}", e)),
+        )
     }
 
     /// Gets `ReceiverSpent` events from etomic swap smart contract since `from_block`
@@ -4162,7 +4376,11 @@ impl EthCoin {
                 )));
             }
 
-            let tx_from_rpc = selfi.web3.eth().transaction(TransactionId::Hash(tx.hash)).await?;
+            let tx_from_rpc = selfi
+                .quorum_web3()
+                .eth()
+                .transaction(TransactionId::Hash(tx.hash))
+                .await?;
             let tx_from_rpc = tx_from_rpc.as_ref().ok_or_else(|| {
                 ValidatePaymentError::TxDoesNotExist(format!("Didn't find provided tx {:?} on ETH node", tx.hash))
             })?;
@@ -4378,6 +4596,13 @@ impl EthCoin {
                         )));
                     }
                 },
+                EthCoinType::Nft { .. } => selfi.validate_nft_swap_payment_tx(
+                    tx_from_rpc,
+                    expected_swap_contract_address,
+                    &swap_id,
+                    &secret_hash,
+                    &input,
+                )?,
             }
 
             Ok(())
@@ -4394,8 +4619,18 @@ impl EthCoin {
 
         let data = try_fus!(function.encode_input(&[token]));
 
-        let coin = self.clone();
-        let fut = async move { coin.call_request(swap_contract_address, None, Some(data.into())).await };
+        let request = CallRequest {
+            from: Some(self.my_address),
+            to: Some(swap_contract_address),
+            data: Some(data.into()),
+            ..CallRequest::default()
+        };
+        let web3 = self.quorum_web3();
+        let fut = async move {
+            web3.eth()
+                .call(request, Some(BlockId::Number(BlockNumber::Latest)))
+                .await
+        };
 
         Box::new(fut.boxed().compat().map_err(|e| ERRL!("{}", e)).and_then(move |bytes| {
             let decoded_tokens = try_s!(function.decode_output(&bytes.0));
@@ -4420,19 +4655,15 @@ impl EthCoin {
         let unverified: UnverifiedTransaction = try_s!(rlp::decode(tx));
         let tx = try_s!(SignedEthTx::new(unverified));
 
-        let func_name = match self.coin_type {
-            EthCoinType::Eth => get_function_name("ethPayment", watcher_reward),
-            EthCoinType::Erc20 { .. } => get_function_name("erc20Payment", watcher_reward),
-        };
-
-        let payment_func = try_s!(SWAP_CONTRACT.function(&func_name));
+        let payment_func = try_s!(self.swap_payment_function(watcher_reward));
         let decoded = try_s!(decode_contract_call(payment_func, &tx.data));
         let id = match decoded.first() {
             Some(Token::FixedBytes(bytes)) => bytes.clone(),
             invalid_token => return ERR!("Expected Token::FixedBytes, got {:?}", invalid_token),
         };
 
-        let mut current_block = try_s!(self.current_block().compat().await);
+        // Logs are requested from the quorum of nodes, so they must have reached the same height.
+        let mut current_block = try_s!(self.quorum_web3().eth().block_number().await).as_u64();
         if current_block < search_from_block {
             current_block = search_from_block;
         }
@@ -4452,13 +4683,16 @@ impl EthCoin {
             if let Some(event) = found {
                 match event.transaction_hash {
                     Some(tx_hash) => {
-                        let transaction = match try_s!(self.web3.eth().transaction(TransactionId::Hash(tx_hash)).await)
-                        {
-                            Some(t) => t,
-                            None => {
-                                return ERR!("Found ReceiverSpent event, but transaction {:02x} is missing", tx_hash)
-                            },
-                        };
+                        let transaction =
+                            match try_s!(self.quorum_web3().eth().transaction(TransactionId::Hash(tx_hash)).await) {
+                                Some(t) => t,
+                                None => {
+                                    return ERR!(
+                                        "Found ReceiverSpent event, but transaction {:02x} is missing",
+                                        tx_hash
+                                    )
+                                },
+                            };
 
                         return Ok(Some(FoundSwapTxSpend::Spent(TransactionEnum::from(try_s!(
                             signed_tx_from_web3_tx(transaction)
@@ -4478,13 +4712,16 @@ impl EthCoin {
             if let Some(event) = found {
                 match event.transaction_hash {
                     Some(tx_hash) => {
-                        let transaction = match try_s!(self.web3.eth().transaction(TransactionId::Hash(tx_hash)).await)
-                        {
-                            Some(t) => t,
-                            None => {
-                                return ERR!("Found SenderRefunded event, but transaction {:02x} is missing", tx_hash)
-                            },
-                        };
+                        let transaction =
+                            match try_s!(self.quorum_web3().eth().transaction(TransactionId::Hash(tx_hash)).await) {
+                                Some(t) => t,
+                                None => {
+                                    return ERR!(
+                                        "Found SenderRefunded event, but transaction {:02x} is missing",
+                                        tx_hash
+                                    )
+                                },
+                            };
 
                         return Ok(Some(FoundSwapTxSpend::Refunded(TransactionEnum::from(try_s!(
                             signed_tx_from_web3_tx(transaction)
@@ -4636,7 +4873,7 @@ impl EthCoin {
                     )));
                 }
 
-                let web3_receipt = match selfi.web3.eth().transaction_receipt(payment_hash).await {
+                let web3_receipt = match selfi.quorum_web3().eth().transaction_receipt(payment_hash).await {
                     Ok(r) => r,
                     Err(e) => {
                         error!(
@@ -4684,7 +4921,7 @@ impl EthCoin {
                     )));
                 }
 
-                match selfi.web3.eth().block_number().await {
+                match selfi.quorum_web3().eth().block_number().await {
                     Ok(current_block) => {
                         if current_block >= block_number {
                             break Ok(());
@@ -4712,15 +4949,32 @@ pub struct EthTxFeeDetails {
     pub gas: u64,
     /// WEI units per 1 gas
     pub gas_price: BigDecimal,
+    /// The L1 data fee charged by L2 rollups in addition to `gas * gas_price`, it's included into `total_fee`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_fee: Option<BigDecimal>,
     pub total_fee: BigDecimal,
 }
 
 impl EthTxFeeDetails {
     pub(crate) fn new(gas: U256, gas_price: U256, coin: &str) -> NumConversResult<EthTxFeeDetails> {
-        let total_fee = gas * gas_price;
+        EthTxFeeDetails::with_l1_fee(gas, gas_price, U256::zero(), coin)
+    }
+
+    pub(crate) fn with_l1_fee(
+        gas: U256,
+        gas_price: U256,
+        l1_fee: U256,
+        coin: &str,
+    ) -> NumConversResult<EthTxFeeDetails> {
+        let total_fee = gas * gas_price + l1_fee;
         // Fees are always paid in ETH, can use 18 decimals by default
         let total_fee = u256_to_big_decimal(total_fee, ETH_DECIMALS)?;
         let gas_price = u256_to_big_decimal(gas_price, ETH_DECIMALS)?;
+        let l1_fee = if l1_fee.is_zero() {
+            None
+        } else {
+            Some(u256_to_big_decimal(l1_fee, ETH_DECIMALS)?)
+        };
 
         let gas_u64 = u64::try_from(gas).map_to_mm(|e| NumConversError::new(e.to_string()))?;
 
@@ -4728,6 +4982,7 @@ impl EthTxFeeDetails {
             coin: coin.to_owned(),
             gas: gas_u64,
             gas_price,
+            l1_fee,
             total_fee,
         })
     }
@@ -4800,6 +5055,7 @@ impl MmCoin for EthCoin {
                 match coin.coin_type {
                     EthCoinType::Eth => coin.process_eth_history(&ctx).await,
                     EthCoinType::Erc20 { ref token_addr, .. } => coin.process_erc20_history(*token_addr, &ctx).await,
+                    EthCoinType::Nft { .. } => warn!("Transaction history is not supported for NFT coins"),
                 }
                 Ok(())
             };
@@ -4811,22 +5067,24 @@ impl MmCoin for EthCoin {
 
     fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> {
         let coin = self.clone();
-        Box::new(
-            self.get_gas_price()
-                .map_err(|e| e.to_string())
-                .and_then(move |gas_price| {
-                    let fee = gas_price * U256::from(ETH_GAS);
-                    let fee_coin = match &coin.coin_type {
-                        EthCoinType::Eth => &coin.ticker,
-                        EthCoinType::Erc20 { platform, .. } => platform,
-                    };
-                    Ok(TradeFee {
-                        coin: fee_coin.into(),
-                        amount: try_s!(u256_to_big_decimal(fee, ETH_DECIMALS)).into(),
-                        paid_from_trading_vol: false,
-                    })
-                }),
-        )
+        let fut = async move {
+            let gas_price = try_s!(coin.get_gas_price().compat().await);
+            let l1_cost = try_s!(
+                coin.estimate_swap_calls_l1_cost(&[coin.swap_payment_function_name()], gas_price)
+                    .await
+            );
+            let fee = gas_price * (U256::from(ETH_GAS) + l1_cost.gas) + l1_cost.fee;
+            let fee_coin = match &coin.coin_type {
+                EthCoinType::Eth => &coin.ticker,
+                EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform,
+            };
+            Ok(TradeFee {
+                coin: fee_coin.into(),
+                amount: try_s!(u256_to_big_decimal(fee, ETH_DECIMALS)).into(),
+                paid_from_trading_vol: false,
+            })
+        };
+        Box::new(fut.boxed().compat())
     }
 
     async fn get_sender_trade_fee(
@@ -4836,6 +5094,12 @@ impl MmCoin for EthCoin {
     ) -> TradePreimageResult<TradeFee> {
         let gas_price = self.get_gas_price().compat().await?;
         let gas_price = increase_gas_price_by_stage(gas_price, &stage);
+        let mut l1_cost = self
+            .estimate_swap_calls_l1_cost(
+                &[self.swap_payment_function_name(), self.swap_refund_function_name()],
+                gas_price,
+            )
+            .await?;
         let gas_limit = match self.coin_type {
             EthCoinType::Eth => {
                 // this gas_limit includes gas for `ethPayment` and `senderRefund` contract calls
@@ -4848,32 +5112,85 @@ impl MmCoin for EthCoin {
                     },
                 };
                 let allowed = self.allowance(self.swap_contract_address).compat().await?;
-                if allowed < value {
-                    // estimate gas for the `approve` contract call
+                let approves_count =
+                    swap_approve_amounts(allowed, value, self.use_exact_approve, self.approve_reset_required).len();
+                if approves_count > 0 {
+                    // estimate gas for the `approve` contract calls
 
                     // Pass a dummy spender. Let's use `my_address`.
                     let spender = self.my_address;
                     let approve_function = ERC20_CONTRACT.function("approve")?;
                     let approve_data = approve_function.encode_input(&[Token::Address(spender), Token::Uint(value)])?;
                     let approve_gas_limit = self
-                        .estimate_gas_for_contract_call(token_addr, Bytes::from(approve_data))
+                        .estimate_gas_for_contract_call(token_addr, Bytes::from(approve_data.clone()))
                         .compat()
                         .await?;
+                    let approve_l1_cost = self
+                        .estimate_l1_cost(
+                            token_addr,
+                            U256::zero(),
+                            &approve_data,
+                            approve_gas_limit,
+                            gas_price,
+                            true,
+                        )
+                        .await?;
+                    l1_cost.gas += approve_l1_cost.gas * approves_count;
+                    l1_cost.fee += approve_l1_cost.fee * approves_count;
 
                     // this gas_limit includes gas for `approve`, `erc20Payment` and `senderRefund` contract calls
-                    U256::from(300_000) + approve_gas_limit
+                    U256::from(300_000) + approve_gas_limit * approves_count
                 } else {
                     // this gas_limit includes gas for `erc20Payment` and `senderRefund` contract calls
                     U256::from(300_000)
                 }
             },
+            EthCoinType::Nft { token: None, .. } => {
+                // the approval can't be checked until the token is chosen by the order
+                U256::from(300_000)
+            },
+            EthCoinType::Nft {
+                token: Some(ref token), ..
+            } => {
+                let token_addr = token.token_address;
+                if self.is_approved_for_all(token_addr, self.swap_contract_address).await? {
+                    // this gas_limit includes gas for the NFT payment and refund contract calls
+                    U256::from(300_000)
+                } else {
+                    // estimate gas for the `setApprovalForAll` contract call
+                    let approve_function = ERC721_CONTRACT.function("setApprovalForAll")?;
+                    let approve_data = approve_function
+                        .encode_input(&[Token::Address(self.swap_contract_address), Token::Bool(true)])?;
+                    let approve_gas_limit = self
+                        .estimate_gas_for_contract_call(token_addr, Bytes::from(approve_data.clone()))
+                        .compat()
+                        .await?;
+                    let approve_l1_cost = self
+                        .estimate_l1_cost(
+                            token_addr,
+                            U256::zero(),
+                            &approve_data,
+                            approve_gas_limit,
+                            gas_price,
+                            true,
+                        )
+                        .await?;
+                    l1_cost.gas += approve_l1_cost.gas;
+                    l1_cost.fee += approve_l1_cost.fee;
+
+                    // this gas_limit includes gas for `setApprovalForAll`, the NFT payment and refund contract calls
+                    U256::from(300_000) + approve_gas_limit
+                }
+            },
         };
 
-        let total_fee = gas_limit * gas_price;
+        // Arbitrum charges the L1 data as an additional gas that is added to the gas limit of the sent transactions
+        let gas_limit = gas_limit + l1_cost.gas;
+        let total_fee = gas_limit * gas_price + l1_cost.fee;
         let amount = u256_to_big_decimal(total_fee, ETH_DECIMALS)?;
         let fee_coin = match &self.coin_type {
             EthCoinType::Eth => &self.ticker,
-            EthCoinType::Erc20 { platform, .. } => platform,
+            EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform,
         };
         Ok(TradeFee {
             coin: fee_coin.into(),
@@ -4887,11 +5204,14 @@ impl MmCoin for EthCoin {
         let fut = async move {
             let gas_price = coin.get_gas_price().compat().await?;
             let gas_price = increase_gas_price_by_stage(gas_price, &stage);
-            let total_fee = gas_price * U256::from(ETH_GAS);
+            let l1_cost = coin
+                .estimate_swap_calls_l1_cost(&[coin.swap_spend_function_name()], gas_price)
+                .await?;
+            let total_fee = gas_price * (U256::from(ETH_GAS) + l1_cost.gas) + l1_cost.fee;
             let amount = u256_to_big_decimal(total_fee, ETH_DECIMALS)?;
             let fee_coin = match &coin.coin_type {
                 EthCoinType::Eth => &coin.ticker,
-                EthCoinType::Erc20 { platform, .. } => platform,
+                EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => platform,
             };
             Ok(TradeFee {
                 coin: fee_coin.into(),
@@ -4919,6 +5239,11 @@ impl MmCoin for EthCoin {
                 let data = function.encode_input(&[Token::Address(to_addr), Token::Uint(dex_fee_amount)])?;
                 (0.into(), data, token_addr, platform)
             },
+            EthCoinType::Nft { .. } => {
+                return MmError::err(TradePreimageError::InternalError(
+                    "NFT coins can't be used to pay the dex fee".to_owned(),
+                ))
+            },
         };
 
         let gas_price = self.get_gas_price().compat().await?;
@@ -4938,7 +5263,10 @@ impl MmCoin for EthCoin {
         // Please note if the wallet's balance is insufficient to withdraw, then `estimate_gas` may fail with the `Exception` error.
         // Ideally we should determine the case when we have the insufficient balance and return `TradePreimageError::NotSufficientBalance` error.
         let gas_limit = self.estimate_gas(estimate_gas_req).compat().await?;
-        let total_fee = gas_limit * gas_price;
+        let l1_cost = self
+            .estimate_l1_cost(*call_addr, eth_value, &data, gas_limit, gas_price, true)
+            .await?;
+        let total_fee = gas_limit * gas_price + l1_cost.fee;
         let amount = u256_to_big_decimal(total_fee, ETH_DECIMALS)?;
         Ok(TradeFee {
             coin: fee_coin.into(),
@@ -4970,16 +5298,44 @@ impl MmCoin for EthCoin {
 
     fn mature_confirmations(&self) -> Option<u32> { None }
 
-    fn coin_protocol_info(&self, _amount_to_receive: Option<MmNumber>) -> Vec<u8> { Vec::new() }
+    fn coin_protocol_info(&self, _amount_to_receive: Option<MmNumber>) -> Vec<u8> {
+        match self.coin_type {
+            // the counterparty checks that it trades the same NFT, the unbound coin is never used by orders
+            EthCoinType::Nft { ref token, .. } => {
+                token.as_ref().map(NftSwapToken::to_protocol_info).unwrap_or_default()
+            },
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => Vec::new(),
+        }
+    }
 
     fn is_coin_protocol_supported(
         &self,
-        _info: &Option<Vec<u8>>,
-        _amount_to_send: Option<MmNumber>,
+        info: &Option<Vec<u8>>,
+        amount_to_send: Option<MmNumber>,
         _locktime: u64,
         _is_maker: bool,
     ) -> bool {
-        true
+        let token = match self.coin_type {
+            EthCoinType::Nft {
+                token: Some(ref token), ..
+            } => token,
+            EthCoinType::Nft { token: None, .. } => return false,
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => return true,
+        };
+        if let Some(amount) = amount_to_send {
+            if let Err(e) = nft_amount_from_big_decimal(&amount.into(), token.contract_type) {
+                error!("{}", e);
+                return false;
+            }
+        }
+        match info.as_deref().map(NftSwapToken::from_protocol_info) {
+            Some(Ok(other)) => other == *token,
+            Some(Err(e)) => {
+                error!("{}", e);
+                false
+            },
+            None => false,
+        }
     }
 
     fn on_disabled(&self) -> Result<(), AbortedError> { AbortableSystem::abort_all(&self.abortable_system) }
@@ -5158,6 +5514,11 @@ fn validate_fee_impl(coin: EthCoin, validate_fee_args: EthValidateFeeArgs<'_>) -
                     },
                 }
             },
+            EthCoinType::Nft { .. } => {
+                return MmError::err(ValidatePaymentError::InternalError(
+                    "NFT coins can't be used to pay the dex fee".to_owned(),
+                ))
+            },
         }
 
         Ok(())
@@ -5390,6 +5751,9 @@ pub async fn eth_coin_from_conf_and_request(
     if urls.is_empty() {
         return ERR!("Enable request for ETH coin must have at least 1 node URL");
     }
+    let node_selection: Web3NodeSelection =
+        try_s!(json::from_value::<Option<_>>(req["node_selection"].clone())).unwrap_or_default();
+    try_s!(node_selection.validate(urls.len()));
     let mut rng = small_rng();
     urls.as_mut_slice().shuffle(&mut rng);
 
@@ -5413,6 +5777,11 @@ pub async fn eth_coin_from_conf_and_request(
         }
     }
     let contract_supports_watchers = req["contract_supports_watchers"].as_bool().unwrap_or_default();
+    // param from request should override the config
+    let use_exact_approve = req["use_exact_approve"]
+        .as_bool()
+        .unwrap_or_else(|| conf["use_exact_approve"].as_bool().unwrap_or_default());
+    let approve_reset_required = conf["approve_reset_required"].as_bool().unwrap_or_default();
 
     let path_to_address = try_s!(json::from_value::<Option<StandardHDCoinAddress>>(
         req["path_to_address"].clone()
@@ -5424,7 +5793,7 @@ pub async fn eth_coin_from_conf_and_request(
     let mut web3_instances = vec![];
     let event_handlers = rpc_event_handlers_for_eth_transport(ctx, ticker.to_string());
     for node in nodes.iter() {
-        let transport = Web3Transport::new_http(vec![node.clone()], event_handlers.clone());
+        let transport = Web3Transport::new_http(vec![node.clone()], event_handlers.clone(), Default::default());
         let web3 = Web3::new(transport);
         let version = match web3.web3().client_version().await {
             Ok(v) => v,
@@ -5443,11 +5812,11 @@ pub async fn eth_coin_from_conf_and_request(
         return ERR!("Failed to get client version for all urls");
     }
 
-    let transport = Web3Transport::new_http(nodes, event_handlers);
+    let transport = Web3Transport::new_http(nodes, event_handlers, node_selection);
     let web3 = Web3::new(transport);
 
     let (coin_type, decimals) = match protocol {
-        CoinProtocol::ETH => (EthCoinType::Eth, ETH_DECIMALS),
+        CoinProtocol::ETH(_) => (EthCoinType::Eth, ETH_DECIMALS),
         CoinProtocol::ERC20 {
             platform,
             contract_address,
@@ -5459,7 +5828,9 @@ pub async fn eth_coin_from_conf_and_request(
             };
             (EthCoinType::Erc20 { platform, token_addr }, decimals)
         },
-        _ => return ERR!("Expect ETH or ERC20 protocol"),
+        // the coin is bound to a specific token by the orders and the swaps, see `EthCoin::bind_nft_token`
+        CoinProtocol::NFT { platform } => (EthCoinType::Nft { platform, token: None }, 0),
+        _ => return ERR!("Expect ETH, ERC20 or NFT protocol"),
     };
 
     // param from request should override the config
@@ -5484,13 +5855,21 @@ pub async fn eth_coin_from_conf_and_request(
         HistorySyncState::NotEnabled
     };
 
+    // tokens run on the chain of the platform coin, so the chain type is taken from the platform config
+    let evm_chain_type = match &coin_type {
+        EthCoinType::Eth => try_s!(EvmChainType::from_conf(conf)),
+        EthCoinType::Erc20 { platform, .. } | EthCoinType::Nft { platform, .. } => {
+            try_s!(EvmChainType::from_conf(&coin_conf(ctx, platform)))
+        },
+    };
+
     let gas_station_decimals: Option<u8> = try_s!(json::from_value(req["gas_station_decimals"].clone()));
     let gas_station_policy: GasStationPricePolicy =
         json::from_value(req["gas_station_policy"].clone()).unwrap_or_default();
 
     let key_lock = match &coin_type {
         EthCoinType::Eth => String::from(ticker),
-        EthCoinType::Erc20 { ref platform, .. } => String::from(platform),
+        EthCoinType::Erc20 { ref platform, .. } | EthCoinType::Nft { ref platform, .. } => String::from(platform),
     };
 
     let mut map = NONCE_LOCK.lock().unwrap();
@@ -5509,6 +5888,8 @@ pub async fn eth_coin_from_conf_and_request(
         swap_contract_address,
         fallback_swap_contract,
         contract_supports_watchers,
+        use_exact_approve,
+        approve_reset_required,
         decimals,
         ticker: ticker.into(),
         gas_station_url: try_s!(json::from_value(req["gas_station_url"].clone())),
@@ -5520,6 +5901,7 @@ pub async fn eth_coin_from_conf_and_request(
         ctx: ctx.weak(),
         required_confirmations,
         chain_id: conf["chain_id"].as_u64(),
+        evm_chain_type,
         logs_block_range: conf["logs_block_range"].as_u64().unwrap_or(DEFAULT_LOGS_BLOCK_RANGE),
         nonce_lock,
         erc20_tokens_infos: Default::default(),
@@ -0,0 +1,636 @@
+//! RPC to call arbitrary smart contract functions by their JSON ABI.
+//! Read-only calls are performed via `eth_call` and return decoded outputs,
+//! other calls are sent as transactions signed with the wallet key.
+
+use super::*;
+use crate::CoinFindError;
+use common::{true_f, HttpStatusCode};
+use ethabi::{Function, ParamType};
+use itertools::Itertools;
+
+pub type ContractCallResult<T> = Result<T, MmError<ContractCallError>>;
+
+#[derive(Clone, Debug, Deserialize, Display, PartialEq, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum ContractCallError {
+    #[display(fmt = "No such coin {}", coin)]
+    NoSuchCoin { coin: String },
+    #[display(fmt = "'{}' coin doesn't support smart contract calls", _0)]
+    CoinDoesntSupportContractCalls(String),
+    #[display(fmt = "Invalid ABI: {}", _0)]
+    InvalidAbi(String),
+    #[display(fmt = "Function '{}' is not found in the ABI", _0)]
+    FunctionNotFound(String),
+    #[display(
+        fmt = "Function '{}' is ambiguous, specify its signature, e.g. 'transfer(address,uint256)'",
+        _0
+    )]
+    AmbiguousFunction(String),
+    #[display(fmt = "Invalid argument '{}': {}", name, reason)]
+    InvalidArgument { name: String, reason: String },
+    #[display(fmt = "Invalid fee policy: {}", _0)]
+    InvalidFeePolicy(String),
+    #[display(
+        fmt = "Not enough {} to send the transaction: available {}, required at least {}",
+        coin,
+        available,
+        required
+    )]
+    NotSufficientBalance {
+        coin: String,
+        available: BigDecimal,
+        required: BigDecimal,
+    },
+    #[display(fmt = "Signing is not supported: {}", _0)]
+    SigningNotSupported(String),
+    #[display(fmt = "Transport error: {}", _0)]
+    Transport(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    InternalError(String),
+}
+
+impl HttpStatusCode for ContractCallError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ContractCallError::NoSuchCoin { .. } => StatusCode::NOT_FOUND,
+            ContractCallError::CoinDoesntSupportContractCalls(_)
+            | ContractCallError::InvalidAbi(_)
+            | ContractCallError::FunctionNotFound(_)
+            | ContractCallError::AmbiguousFunction(_)
+            | ContractCallError::InvalidArgument { .. }
+            | ContractCallError::InvalidFeePolicy(_)
+            | ContractCallError::NotSufficientBalance { .. }
+            | ContractCallError::SigningNotSupported(_) => StatusCode::BAD_REQUEST,
+            ContractCallError::Transport(_) | ContractCallError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for ContractCallError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => ContractCallError::NoSuchCoin { coin },
+        }
+    }
+}
+
+impl From<web3::Error> for ContractCallError {
+    fn from(e: web3::Error) -> Self { ContractCallError::Transport(e.to_string()) }
+}
+
+impl From<Web3RpcError> for ContractCallError {
+    fn from(e: Web3RpcError) -> Self {
+        match e {
+            Web3RpcError::Transport(e) | Web3RpcError::InvalidResponse(e) => ContractCallError::Transport(e),
+            Web3RpcError::Internal(e) | Web3RpcError::Timeout(e) => ContractCallError::InternalError(e),
+        }
+    }
+}
+
+impl From<EthGasDetailsErr> for ContractCallError {
+    fn from(e: EthGasDetailsErr) -> Self {
+        match e {
+            EthGasDetailsErr::InvalidFeePolicy(e) => ContractCallError::InvalidFeePolicy(e),
+            EthGasDetailsErr::Internal(e) => ContractCallError::InternalError(e),
+            EthGasDetailsErr::Transport(e) => ContractCallError::Transport(e),
+        }
+    }
+}
+
+impl From<BalanceError> for ContractCallError {
+    fn from(e: BalanceError) -> Self {
+        match e {
+            BalanceError::Transport(e) | BalanceError::InvalidResponse(e) => ContractCallError::Transport(e),
+            e => ContractCallError::InternalError(e.to_string()),
+        }
+    }
+}
+
+impl From<NumConversError> for ContractCallError {
+    fn from(e: NumConversError) -> Self { ContractCallError::InternalError(e.to_string()) }
+}
+
+impl From<ethabi::Error> for ContractCallError {
+    fn from(e: ethabi::Error) -> Self { ContractCallError::InternalError(e.to_string()) }
+}
+
+impl From<PrivKeyPolicyNotAllowed> for ContractCallError {
+    fn from(e: PrivKeyPolicyNotAllowed) -> Self { ContractCallError::SigningNotSupported(e.to_string()) }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractCallAction {
+    /// Read the contract state via `eth_call` without sending a transaction.
+    Call,
+    /// Sign a transaction calling the function and send it if `broadcast` is true.
+    Send,
+}
+
+impl Default for ContractCallAction {
+    fn default() -> Self { ContractCallAction::Call }
+}
+
+#[derive(Deserialize)]
+pub struct ContractCallRequest {
+    /// EVM platform coin or token ticker. Its nodes and the wallet key are used.
+    coin: String,
+    contract_address: Address,
+    /// JSON ABI of the contract or a single function ABI entry.
+    abi: Json,
+    /// Function name or signature like `transfer(address,uint256)` if the function is overloaded.
+    function: String,
+    /// Function arguments in the order of the ABI inputs.
+    /// Integers can be given as numbers, decimal or `0x` prefixed hex strings,
+    /// arrays and tuples as JSON arrays, bytes and addresses as hex strings.
+    #[serde(default)]
+    args: Vec<Json>,
+    #[serde(default)]
+    action: ContractCallAction,
+    /// The amount of the platform coin transferred to the contract.
+    #[serde(default)]
+    value: BigDecimal,
+    fee: Option<WithdrawFee>,
+    /// Whether to broadcast the signed transaction. Used with the `send` action only.
+    #[serde(default = "true_f")]
+    broadcast: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DecodedOutput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: Json,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ContractCallResponse {
+    Call {
+        outputs: Vec<DecodedOutput>,
+    },
+    Send {
+        #[serde(flatten)]
+        tx_details: TransactionDetails,
+        broadcast: bool,
+    },
+}
+
+/// Calls a function of an arbitrary smart contract described by the JSON ABI from the request.
+pub async fn contract_call(ctx: MmArc, req: ContractCallRequest) -> ContractCallResult<ContractCallResponse> {
+    let coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::EthCoin(coin) => coin,
+        _ => return MmError::err(ContractCallError::CoinDoesntSupportContractCalls(req.coin)),
+    };
+
+    let function = find_function(&req.abi, &req.function, req.args.len())?;
+    if function.inputs.len() != req.args.len() {
+        let error = format!(
+            "'{}' expects {} arguments, found {}",
+            function.name,
+            function.inputs.len(),
+            req.args.len()
+        );
+        return MmError::err(ContractCallError::InvalidArgument {
+            name: "args".to_owned(),
+            reason: error,
+        });
+    }
+    let tokens = function
+        .inputs
+        .iter()
+        .zip(req.args.iter())
+        .map(|(param, arg)| {
+            json_to_token(&param.kind, arg).map_to_mm(|reason| ContractCallError::InvalidArgument {
+                name: param.name.clone(),
+                reason,
+            })
+        })
+        .collect::<ContractCallResult<Vec<_>>>()?;
+    let data = function.encode_input(&tokens)?;
+    let value = wei_from_big_decimal(&req.value, ETH_DECIMALS)?;
+
+    match req.action {
+        ContractCallAction::Call => {
+            let output = coin
+                .call_request(req.contract_address, Some(value), Some(data.into()))
+                .await?;
+            let decoded = function.decode_output(&output.0)?;
+            let outputs = function
+                .outputs
+                .iter()
+                .zip(decoded)
+                .map(|(param, token)| DecodedOutput {
+                    name: param.name.clone(),
+                    kind: param.kind.to_string(),
+                    value: token_to_json(token),
+                })
+                .collect();
+            Ok(ContractCallResponse::Call { outputs })
+        },
+        ContractCallAction::Send => {
+            let tx_details =
+                send_contract_transaction(&coin, req.contract_address, value, data, req.fee, req.broadcast).await?;
+            Ok(ContractCallResponse::Send {
+                tx_details,
+                broadcast: req.broadcast,
+            })
+        },
+    }
+}
+
+async fn send_contract_transaction(
+    coin: &EthCoin,
+    contract_address: Address,
+    value: U256,
+    data: Vec<u8>,
+    fee: Option<WithdrawFee>,
+    broadcast: bool,
+) -> ContractCallResult<TransactionDetails> {
+    let secret = match coin.priv_key_policy {
+        EthPrivKeyPolicy::Iguana(_) | EthPrivKeyPolicy::HDWallet { .. } => {
+            coin.priv_key_policy.activated_key_or_err()?.secret().clone()
+        },
+        EthPrivKeyPolicy::Trezor => {
+            return MmError::err(ContractCallError::SigningNotSupported(
+                "Trezor is not supported for EVM yet".to_owned(),
+            ))
+        },
+        #[cfg(target_arch = "wasm32")]
+        EthPrivKeyPolicy::Metamask(_) => {
+            return MmError::err(ContractCallError::SigningNotSupported(
+                "Contract transactions should be sent by Metamask directly".to_owned(),
+            ))
+        },
+    };
+
+    let gas_estimated = fee.is_none();
+    let (gas, gas_price) = get_eth_gas_details(coin, fee, value, data.clone().into(), contract_address, false).await?;
+    let l1_cost = coin
+        .estimate_l1_cost(contract_address, value, &data, gas, gas_price, gas_estimated)
+        .await?;
+    let gas = gas + l1_cost.gas;
+    let total_fee = gas * gas_price + l1_cost.fee;
+    let required = value + total_fee;
+    let eth_balance = coin.eth_balance().compat().await?;
+    if eth_balance < required {
+        return MmError::err(ContractCallError::NotSufficientBalance {
+            coin: coin.platform_ticker().to_owned(),
+            available: u256_to_big_decimal(eth_balance, ETH_DECIMALS)?,
+            required: u256_to_big_decimal(required, ETH_DECIMALS)?,
+        });
+    }
+
+    let _nonce_lock = coin.nonce_lock.lock().await;
+    let (nonce, web3_instances_with_latest_nonce) = get_addr_nonce(coin.my_address, coin.web3_instances.clone())
+        .compat()
+        .timeout_secs(30.)
+        .await
+        .map_to_mm(|e| ContractCallError::Transport(e.to_string()))?
+        .map_to_mm(ContractCallError::Transport)?;
+
+    let tx = UnSignedEthTx {
+        nonce,
+        value,
+        action: Action::Call(contract_address),
+        data,
+        gas,
+        gas_price,
+    };
+    let signed = tx.sign(&secret, coin.chain_id);
+    let signed_bytes = rlp::encode(&signed);
+
+    if broadcast {
+        let bytes = Bytes(signed_bytes.to_vec());
+        let futures = web3_instances_with_latest_nonce
+            .into_iter()
+            .map(|web3_instance| web3_instance.web3.eth().send_raw_transaction(bytes.clone()));
+        select_ok(futures).await?;
+    }
+
+    let fee_details = EthTxFeeDetails::with_l1_fee(gas, gas_price, l1_cost.fee, coin.platform_ticker())?;
+    let amount = u256_to_big_decimal(value, ETH_DECIMALS)?;
+    let spent_by_me = &amount + &fee_details.total_fee;
+    Ok(TransactionDetails {
+        to: vec![checksum_address(&eth_addr_to_hex(&contract_address))],
+        from: vec![checksum_address(&eth_addr_to_hex(&coin.my_address))],
+        total_amount: amount,
+        my_balance_change: -spent_by_me.clone(),
+        spent_by_me,
+        received_by_me: 0.into(),
+        tx_hex: BytesJson::from(signed_bytes.to_vec()),
+        tx_hash: format!("{:02x}", signed.tx_hash()),
+        block_height: 0,
+        fee_details: Some(fee_details.into()),
+        coin: coin.platform_ticker().to_owned(),
+        internal_id: vec![].into(),
+        timestamp: now_sec(),
+        kmd_rewards: None,
+        transaction_type: TransactionType::ContractCall,
+        memo: None,
+    })
+}
+
+/// Finds the function by its name or signature.
+/// Overloaded functions are distinguished by the number of arguments if the signature is not specified.
+fn find_function(abi: &Json, function: &str, args_count: usize) -> ContractCallResult<Function> {
+    let abi = match abi {
+        Json::Array(_) => abi.clone(),
+        Json::Object(_) => Json::Array(vec![abi.clone()]),
+        _ => {
+            return MmError::err(ContractCallError::InvalidAbi(
+                "Expected an array or an object".to_owned(),
+            ))
+        },
+    };
+    let abi_bytes = json::to_vec(&abi).map_to_mm(|e| ContractCallError::InvalidAbi(e.to_string()))?;
+    let contract = Contract::load(abi_bytes.as_slice()).map_to_mm(|e| ContractCallError::InvalidAbi(e.to_string()))?;
+
+    let signature: String = function.chars().filter(|c| !c.is_whitespace()).collect();
+    let name = signature.split('(').next().unwrap_or_default();
+    let functions = contract
+        .functions_by_name(name)
+        .map_to_mm(|_| ContractCallError::FunctionNotFound(function.to_owned()))?;
+
+    let mut candidates = functions.iter().filter(|f| {
+        if signature.contains('(') {
+            function_signature(f) == signature
+        } else {
+            functions.len() == 1 || f.inputs.len() == args_count
+        }
+    });
+    match (candidates.next(), candidates.next()) {
+        (Some(f), None) => Ok(f.clone()),
+        (Some(_), Some(_)) => MmError::err(ContractCallError::AmbiguousFunction(function.to_owned())),
+        (None, _) => MmError::err(ContractCallError::FunctionNotFound(function.to_owned())),
+    }
+}
+
+/// Returns the function signature without outputs, e.g. `transfer(address,uint256)`.
+fn function_signature(function: &Function) -> String {
+    let inputs = function.inputs.iter().map(|param| param.kind.to_string()).join(",");
+    format!("{}({})", function.name, inputs)
+}
+
+fn json_to_token(kind: &ParamType, value: &Json) -> Result<Token, String> {
+    use ethabi::token::{LenientTokenizer, Tokenizer};
+
+    match (kind, value) {
+        (ParamType::Array(item_kind), Json::Array(items)) => items
+            .iter()
+            .map(|item| json_to_token(item_kind, item))
+            .collect::<Result<_, _>>()
+            .map(Token::Array),
+        (ParamType::FixedArray(item_kind, len), Json::Array(items)) => {
+            if items.len() != *len {
+                return Err(format!("Expected {} items, found {}", len, items.len()));
+            }
+            items
+                .iter()
+                .map(|item| json_to_token(item_kind, item))
+                .collect::<Result<_, _>>()
+                .map(Token::FixedArray)
+        },
+        (ParamType::Tuple(kinds), Json::Array(items)) => {
+            if items.len() != kinds.len() {
+                return Err(format!("Expected {} tuple items, found {}", kinds.len(), items.len()));
+            }
+            kinds
+                .iter()
+                .zip(items)
+                .map(|(kind, item)| json_to_token(kind, item))
+                .collect::<Result<_, _>>()
+                .map(Token::Tuple)
+        },
+        (ParamType::Uint(bits), _) => {
+            let (is_negative, number) = json_to_integer(value)?;
+            if is_negative && !number.is_zero() {
+                return Err(format!("Negative number -{} can't be 'uint{}'", number, bits));
+            }
+            if number.bits() > *bits {
+                return Err(format!("{} doesn't fit into 'uint{}'", number, bits));
+            }
+            Ok(Token::Uint(number))
+        },
+        (ParamType::Int(bits), _) => {
+            let (is_negative, number) = json_to_integer(value)?;
+            // `int<M>` values are in the range `[-2^(M-1), 2^(M-1) - 1]`.
+            let limit = U256::one() << (bits - 1);
+            if is_negative && number > limit {
+                return Err(format!("-{} doesn't fit into 'int{}'", number, bits));
+            }
+            if !is_negative && number >= limit {
+                return Err(format!("{} doesn't fit into 'int{}'", number, bits));
+            }
+            if is_negative {
+                // Negative numbers are encoded in two's complement sign-extended to 256 bits.
+                Ok(Token::Int((!number).overflowing_add(U256::one()).0))
+            } else {
+                Ok(Token::Int(number))
+            }
+        },
+        (ParamType::Bool, Json::Bool(b)) => Ok(Token::Bool(*b)),
+        (ParamType::Array(_) | ParamType::FixedArray(..) | ParamType::Tuple(_), _) => {
+            Err(format!("Expected a JSON array, found {}", value))
+        },
+        (_, Json::String(s)) => LenientTokenizer::tokenize(kind, s).map_err(|e| e.to_string()),
+        (_, _) => Err(format!("Expected a string, found {}", value)),
+    }
+}
+
+/// Parses an integer from a JSON number, a decimal string or a `0x` prefixed hex string.
+/// Returns whether the number is negative and its absolute value.
+fn json_to_integer(value: &Json) -> Result<(bool, U256), String> {
+    let s = match value {
+        Json::Number(n) => n.to_string(),
+        Json::String(s) => s.trim().to_owned(),
+        _ => return Err(format!("Expected an integer, found {}", value)),
+    };
+    let (is_negative, abs) = match s.strip_prefix('-') {
+        Some(abs) => (true, abs),
+        None => (false, s.as_str()),
+    };
+    let number = match abs.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| e.to_string())?,
+        None => U256::from_dec_str(abs).map_err(|e| format!("Invalid integer '{}': {:?}", s, e))?,
+    };
+    Ok((is_negative, number))
+}
+
+fn token_to_json(token: Token) -> Json {
+    match token {
+        Token::Address(address) => Json::String(checksum_address(&eth_addr_to_hex(&address))),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => Json::String(format!("0x{}", hex::encode(bytes))),
+        Token::Uint(number) => Json::String(number.to_string()),
+        Token::Int(number) => {
+            // Negative numbers are encoded in two's complement.
+            if number.bit(255) {
+                Json::String(format!("-{}", (!number).overflowing_add(U256::one()).0))
+            } else {
+                Json::String(number.to_string())
+            }
+        },
+        Token::Bool(b) => Json::Bool(b),
+        Token::String(s) => Json::String(s),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Json::Array(tokens.into_iter().map(token_to_json).collect())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"[
+        {"type": "function", "name": "balanceOf", "stateMutability": "view",
+         "inputs": [{"name": "owner", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+         "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}], "outputs": []},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+         "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                    {"name": "id", "type": "uint256"}], "outputs": []},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+         "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                    {"name": "id", "type": "uint256"}, {"name": "data", "type": "bytes"}], "outputs": []},
+        {"type": "function", "name": "burn", "stateMutability": "nonpayable",
+         "inputs": [{"name": "id", "type": "uint256"}], "outputs": []},
+        {"type": "function", "name": "burn", "stateMutability": "nonpayable",
+         "inputs": [{"name": "account", "type": "address"}], "outputs": []}
+    ]"#;
+
+    fn int_token(kind: &str, value: Json) -> Result<Token, String> {
+        let kind = ethabi::param_type::Reader::read(kind).unwrap();
+        json_to_token(&kind, &value)
+    }
+
+    #[test]
+    fn test_find_function() {
+        let abi: Json = json::from_str(ABI).unwrap();
+
+        assert_eq!(find_function(&abi, "balanceOf", 1).unwrap().name, "balanceOf");
+        // a single function object is accepted too
+        find_function(&abi[0], "balanceOf", 1).unwrap();
+
+        // overloaded functions are chosen by the number of arguments
+        let function = find_function(&abi, "safeTransferFrom", 4).unwrap();
+        assert_eq!(function.inputs.len(), 4);
+        let function = find_function(&abi, "safeTransferFrom", 3).unwrap();
+        assert_eq!(function.inputs.len(), 3);
+
+        // or by the signature
+        let function = find_function(&abi, "burn(address)", 1).unwrap();
+        assert_eq!(function.inputs[0].kind, ParamType::Address);
+        let function = find_function(&abi, "burn( uint256 )", 1).unwrap();
+        assert_eq!(function.inputs[0].kind, ParamType::Uint(256));
+
+        match find_function(&abi, "burn", 1).unwrap_err().into_inner() {
+            ContractCallError::AmbiguousFunction(name) => assert_eq!(name, "burn"),
+            e => panic!("Unexpected error {}", e),
+        }
+        match find_function(&abi, "mint", 1).unwrap_err().into_inner() {
+            ContractCallError::FunctionNotFound(name) => assert_eq!(name, "mint"),
+            e => panic!("Unexpected error {}", e),
+        }
+        match find_function(&abi, "burn(bytes)", 1).unwrap_err().into_inner() {
+            ContractCallError::FunctionNotFound(name) => assert_eq!(name, "burn(bytes)"),
+            e => panic!("Unexpected error {}", e),
+        }
+        match find_function(&json!("balanceOf"), "balanceOf", 1)
+            .unwrap_err()
+            .into_inner()
+        {
+            ContractCallError::InvalidAbi(_) => (),
+            e => panic!("Unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn test_json_to_token_uint() {
+        assert_eq!(int_token("uint256", json!(1000)).unwrap(), Token::Uint(1000.into()));
+        assert_eq!(int_token("uint256", json!("1000")).unwrap(), Token::Uint(1000.into()));
+        assert_eq!(int_token("uint256", json!("0x3e8")).unwrap(), Token::Uint(1000.into()));
+        assert_eq!(int_token("uint8", json!(255)).unwrap(), Token::Uint(255.into()));
+        assert_eq!(
+            int_token("uint256", json!(U256::max_value().to_string())).unwrap(),
+            Token::Uint(U256::max_value())
+        );
+
+        int_token("uint8", json!(256)).unwrap_err();
+        int_token("uint256", json!(-1)).unwrap_err();
+        int_token("uint256", json!("1.5")).unwrap_err();
+        int_token("uint256", json!(true)).unwrap_err();
+    }
+
+    #[test]
+    fn test_json_to_token_int() {
+        assert_eq!(int_token("int256", json!(1000)).unwrap(), Token::Int(1000.into()));
+        assert_eq!(int_token("int256", json!("-1")).unwrap(), Token::Int(U256::max_value()));
+        assert_eq!(int_token("int256", json!(-0)).unwrap(), Token::Int(U256::zero()));
+        // negative numbers are sign-extended to 256 bits
+        assert_eq!(
+            int_token("int8", json!(-2)).unwrap(),
+            Token::Int(U256::max_value() - U256::one())
+        );
+        assert_eq!(
+            int_token("int8", json!("-0x80")).unwrap(),
+            Token::Int(!U256::from(0x7f))
+        );
+
+        // the range of `int8` is [-128, 127]
+        int_token("int8", json!(127)).unwrap();
+        int_token("int8", json!(-128)).unwrap();
+        int_token("int8", json!(128)).unwrap_err();
+        int_token("int8", json!(-129)).unwrap_err();
+        let max_int256 = U256::max_value() >> 1;
+        int_token("int256", json!(max_int256.to_string())).unwrap();
+        int_token("int256", json!((max_int256 + 1).to_string())).unwrap_err();
+        int_token("int256", json!(format!("-{}", max_int256 + 1))).unwrap();
+    }
+
+    #[test]
+    fn test_json_to_token_compound() {
+        let kind = ethabi::param_type::Reader::read("(address,uint256[],bool,bytes)").unwrap();
+        let value = json!(["0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29", [1, "2"], true, "0x0102"]);
+        let expected = Token::Tuple(vec![
+            Token::Address(Address::from_str("0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29").unwrap()),
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::Bool(true),
+            Token::Bytes(vec![1, 2]),
+        ]);
+        assert_eq!(json_to_token(&kind, &value).unwrap(), expected);
+
+        let kind = ethabi::param_type::Reader::read("uint8[2]").unwrap();
+        json_to_token(&kind, &json!([1, 2])).unwrap();
+        json_to_token(&kind, &json!([1, 2, 3])).unwrap_err();
+        json_to_token(&kind, &json!("1,2")).unwrap_err();
+    }
+
+    #[test]
+    fn test_token_to_json() {
+        let address = Address::from_str("0xbab36286672fbdc7b250804bf6d14be0df69fa29").unwrap();
+        assert_eq!(
+            token_to_json(Token::Address(address)),
+            json!("0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29")
+        );
+        assert_eq!(token_to_json(Token::Bytes(vec![1, 2])), json!("0x0102"));
+        assert_eq!(
+            token_to_json(Token::Uint(U256::max_value())),
+            json!(U256::max_value().to_string())
+        );
+        assert_eq!(token_to_json(Token::Int(1000.into())), json!("1000"));
+        assert_eq!(token_to_json(Token::Int(U256::max_value())), json!("-1"));
+        assert_eq!(token_to_json(Token::Int(!U256::from(0x7f))), json!("-128"));
+        assert_eq!(
+            token_to_json(Token::Tuple(vec![Token::Bool(true), Token::String("test".to_owned())])),
+            json!([true, "test"])
+        );
+
+        // signed integers are decoded back to the same value
+        for value in ["-128", "-1", "0", "127"] {
+            let token = int_token("int8", json!(value)).unwrap();
+            assert_eq!(token_to_json(token), json!(value));
+        }
+    }
+}
@@ -0,0 +1,507 @@
+//! RPCs to sign and verify [EIP-712](https://eips.ethereum.org/EIPS/eip-712) typed data with the node keys,
+//! and to generate [EIP-2612](https://eips.ethereum.org/EIPS/eip-2612) `permit` signatures for ERC20 tokens.
+
+use super::*;
+use crate::CoinFindError;
+use common::HttpStatusCode;
+use mm2_eth::eip712_encode::{domain_separator_json, hash_typed_data_json};
+
+const ERC20_PERMIT_ABI: &str = include_str!("erc20_permit_abi.json");
+/// The version of the permit domain that is used if the token doesn't expose the `version()` method.
+const DEFAULT_PERMIT_VERSION: &str = "1";
+/// The default lifetime of a permit in seconds if `deadline` is not specified.
+const DEFAULT_PERMIT_LIFETIME: u64 = 3600;
+/// Ethereum signatures have a recovery id shifted by this value, see `eth_signTypedData_v4`.
+const RECOVERY_ID_OFFSET: u8 = 27;
+
+lazy_static! {
+    pub static ref ERC20_PERMIT_CONTRACT: Contract = Contract::load(ERC20_PERMIT_ABI.as_bytes()).unwrap();
+}
+
+pub type TypedDataResult<T> = Result<T, MmError<TypedDataError>>;
+
+#[derive(Clone, Debug, Deserialize, Display, PartialEq, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum TypedDataError {
+    #[display(fmt = "No such coin {}", coin)]
+    NoSuchCoin { coin: String },
+    #[display(fmt = "'{}' coin doesn't support EIP-712 typed data", _0)]
+    CoinDoesntSupportTypedData(String),
+    #[display(fmt = "Invalid typed data: {}", _0)]
+    InvalidTypedData(String),
+    #[display(fmt = "Invalid signature: {}", _0)]
+    InvalidSignature(String),
+    #[display(fmt = "Token '{}' doesn't support EIP-2612 permit: {}", ticker, reason)]
+    PermitNotSupported { ticker: String, reason: String },
+    #[display(
+        fmt = "Permit domain separator mismatch: expected {}, token reports {}",
+        expected,
+        actual
+    )]
+    DomainSeparatorMismatch { expected: String, actual: String },
+    #[display(fmt = "Signing is not supported: {}", _0)]
+    SigningNotSupported(String),
+    #[display(fmt = "Transport error: {}", _0)]
+    Transport(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    InternalError(String),
+}
+
+impl HttpStatusCode for TypedDataError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TypedDataError::NoSuchCoin { .. } => StatusCode::NOT_FOUND,
+            TypedDataError::CoinDoesntSupportTypedData(_)
+            | TypedDataError::InvalidTypedData(_)
+            | TypedDataError::InvalidSignature(_)
+            | TypedDataError::PermitNotSupported { .. }
+            | TypedDataError::DomainSeparatorMismatch { .. }
+            | TypedDataError::SigningNotSupported(_) => StatusCode::BAD_REQUEST,
+            TypedDataError::Transport(_) | TypedDataError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for TypedDataError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => TypedDataError::NoSuchCoin { coin },
+        }
+    }
+}
+
+impl From<Web3RpcError> for TypedDataError {
+    fn from(e: Web3RpcError) -> Self {
+        match e {
+            Web3RpcError::Transport(err) | Web3RpcError::InvalidResponse(err) => TypedDataError::Transport(err),
+            Web3RpcError::Internal(internal) | Web3RpcError::Timeout(internal) => {
+                TypedDataError::InternalError(internal)
+            },
+        }
+    }
+}
+
+impl From<web3::Error> for TypedDataError {
+    fn from(e: web3::Error) -> Self { TypedDataError::Transport(e.to_string()) }
+}
+
+impl From<ethabi::Error> for TypedDataError {
+    fn from(e: ethabi::Error) -> Self { TypedDataError::InternalError(e.to_string()) }
+}
+
+impl From<PrivKeyPolicyNotAllowed> for TypedDataError {
+    fn from(e: PrivKeyPolicyNotAllowed) -> Self { TypedDataError::SigningNotSupported(e.to_string()) }
+}
+
+impl From<NumConversError> for TypedDataError {
+    fn from(e: NumConversError) -> Self { TypedDataError::InternalError(e.to_string()) }
+}
+
+#[derive(Deserialize)]
+pub struct SignTypedDataRequest {
+    coin: String,
+    /// Typed data in the `eth_signTypedData_v4` format: `types`, `primaryType`, `domain` and `message`.
+    typed_data: Json,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignTypedDataResponse {
+    /// The EIP-712 hash that has been signed.
+    pub hash: String,
+    /// 65 bytes signature `r || s || v` where `v` is 27 or 28.
+    pub signature: String,
+    pub address: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyTypedDataRequest {
+    coin: String,
+    typed_data: Json,
+    signature: String,
+    address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyTypedDataResponse {
+    pub is_valid: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SignErc20PermitRequest {
+    /// ERC20 token ticker.
+    coin: String,
+    /// The address allowed to spend the tokens. The swap contract is used by default.
+    spender: Option<Address>,
+    #[serde(default)]
+    amount: BigDecimal,
+    /// Permit an unlimited allowance. If true, then `amount` will be ignored.
+    #[serde(default)]
+    max: bool,
+    /// UNIX timestamp until which the permit is valid.
+    /// Defaults to [`DEFAULT_PERMIT_LIFETIME`] seconds from now.
+    deadline: Option<u64>,
+    /// The permit domain version, if the token doesn't expose the `version()` method
+    /// and uses a version different from [`DEFAULT_PERMIT_VERSION`].
+    version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignErc20PermitResponse {
+    pub owner: String,
+    pub spender: String,
+    pub value: BigDecimal,
+    pub nonce: String,
+    pub deadline: u64,
+    pub v: u8,
+    pub r: String,
+    pub s: String,
+    pub signature: String,
+    /// The typed data that has been signed, can be used to verify the signature.
+    pub typed_data: Json,
+}
+
+/// Signs EIP-712 typed data with the activated key of the coin, same as `eth_signTypedData_v4` does.
+pub async fn sign_typed_data(ctx: MmArc, req: SignTypedDataRequest) -> TypedDataResult<SignTypedDataResponse> {
+    let coin = eth_coin_for_typed_data(&ctx, &req.coin).await?;
+    let hash = hash_typed_data_json(req.typed_data).map_to_mm(|e| TypedDataError::InvalidTypedData(e.to_string()))?;
+    let signature = coin.sign_typed_data_hash(hash)?;
+
+    Ok(SignTypedDataResponse {
+        hash: format!("0x{}", hex::encode(hash.as_bytes())),
+        signature: format!("0x{}", hex::encode(signature_to_eth_bytes(&signature))),
+        address: checksum_address(&eth_addr_to_hex(&coin.my_address)),
+    })
+}
+
+/// Checks whether the typed data has been signed by the given address.
+pub async fn verify_typed_data(ctx: MmArc, req: VerifyTypedDataRequest) -> TypedDataResult<VerifyTypedDataResponse> {
+    let coin = eth_coin_for_typed_data(&ctx, &req.coin).await?;
+    let address = coin
+        .address_from_str(&req.address)
+        .map_to_mm(TypedDataError::InvalidTypedData)?;
+    let signature = signature_from_eth_str(&req.signature)?;
+    let hash = hash_typed_data_json(req.typed_data).map_to_mm(|e| TypedDataError::InvalidTypedData(e.to_string()))?;
+
+    let is_valid = verify_address(&address, &signature, &H256::from(hash.0))
+        .map_to_mm(|e| TypedDataError::InvalidSignature(e.to_string()))?;
+    Ok(VerifyTypedDataResponse { is_valid })
+}
+
+/// Generates an EIP-2612 `permit` signature that allows the `spender` to transfer the tokens of this wallet.
+/// Since the signature is submitted on-chain by the spender, the approval doesn't cost any gas to the wallet owner.
+/// Note the swap contract doesn't accept permits yet, so ERC20 swap payments still send `approve` transactions.
+pub async fn sign_erc20_permit(ctx: MmArc, req: SignErc20PermitRequest) -> TypedDataResult<SignErc20PermitResponse> {
+    let coin = eth_coin_for_typed_data(&ctx, &req.coin).await?;
+    let token_addr = match coin.coin_type {
+        EthCoinType::Erc20 { token_addr, .. } => token_addr,
+        EthCoinType::Eth | EthCoinType::Nft { .. } => {
+            return MmError::err(TypedDataError::CoinDoesntSupportTypedData(req.coin))
+        },
+    };
+
+    let spender = req.spender.unwrap_or(coin.swap_contract_address);
+    let value = if req.max {
+        U256::max_value()
+    } else {
+        wei_from_big_decimal(&req.amount, coin.decimals)?
+    };
+    let deadline = req.deadline.unwrap_or_else(|| now_sec() + DEFAULT_PERMIT_LIFETIME);
+
+    let permit = coin
+        .erc20_permit(token_addr, spender, value, deadline, req.version)
+        .await?;
+
+    Ok(SignErc20PermitResponse {
+        owner: checksum_address(&eth_addr_to_hex(&coin.my_address)),
+        spender: checksum_address(&eth_addr_to_hex(&spender)),
+        value: u256_to_big_decimal(value, coin.decimals)?,
+        nonce: permit.nonce.to_string(),
+        deadline: permit.deadline,
+        v: permit.v(),
+        r: format!("0x{}", hex::encode(permit.r())),
+        s: format!("0x{}", hex::encode(permit.s())),
+        signature: format!("0x{}", hex::encode(permit.signature)),
+        typed_data: permit.typed_data,
+    })
+}
+
+/// A signed EIP-2612 permit.
+pub(crate) struct Erc20Permit {
+    pub(crate) nonce: U256,
+    pub(crate) deadline: u64,
+    /// `r || s || v` signature where `v` is 27 or 28.
+    pub(crate) signature: [u8; 65],
+    pub(crate) typed_data: Json,
+}
+
+impl Erc20Permit {
+    pub(crate) fn r(&self) -> &[u8] { &self.signature[..32] }
+
+    pub(crate) fn s(&self) -> &[u8] { &self.signature[32..64] }
+
+    pub(crate) fn v(&self) -> u8 { self.signature[64] }
+}
+
+/// Builds the EIP-712 typed data of the EIP-2612 `Permit` message.
+#[allow(clippy::too_many_arguments)]
+fn permit_typed_data(
+    name: &str,
+    version: &str,
+    chain_id: U256,
+    token_addr: Address,
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: U256,
+    deadline: u64,
+) -> Json {
+    json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Permit": [
+                { "name": "owner", "type": "address" },
+                { "name": "spender", "type": "address" },
+                { "name": "value", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" }
+            ]
+        },
+        "primaryType": "Permit",
+        "domain": {
+            "name": name,
+            "version": version,
+            "chainId": chain_id.to_string(),
+            "verifyingContract": eth_addr_to_hex(&token_addr)
+        },
+        "message": {
+            "owner": eth_addr_to_hex(&owner),
+            "spender": eth_addr_to_hex(&spender),
+            "value": value.to_string(),
+            "nonce": nonce.to_string(),
+            "deadline": deadline.to_string()
+        }
+    })
+}
+
+impl EthCoin {
+    /// Signs a permit that allows the `spender` to transfer `value` tokens of this wallet until the `deadline`.
+    pub(crate) async fn erc20_permit(
+        &self,
+        token_addr: Address,
+        spender: Address,
+        value: U256,
+        deadline: u64,
+        version: Option<String>,
+    ) -> TypedDataResult<Erc20Permit> {
+        let nonce = match self
+            .permit_call(token_addr, "nonces", &[Token::Address(self.my_address)])
+            .await
+        {
+            Ok(Token::Uint(nonce)) => nonce,
+            Ok(token) => {
+                let error = format!("Expected U256 as nonces result but got {:?}", token);
+                return MmError::err(TypedDataError::Transport(error));
+            },
+            Err(e) => {
+                return MmError::err(TypedDataError::PermitNotSupported {
+                    ticker: self.ticker.clone(),
+                    reason: e.to_string(),
+                })
+            },
+        };
+        let name = match self.permit_call(token_addr, "name", &[]).await? {
+            Token::String(name) => name,
+            token => {
+                let error = format!("Expected String as name result but got {:?}", token);
+                return MmError::err(TypedDataError::Transport(error));
+            },
+        };
+        let version = match version {
+            Some(version) => version,
+            None => match self.permit_call(token_addr, "version", &[]).await {
+                Ok(Token::String(version)) => version,
+                _ => DEFAULT_PERMIT_VERSION.to_owned(),
+            },
+        };
+        let chain_id = match self.chain_id {
+            Some(chain_id) => U256::from(chain_id),
+            None => self.web3.eth().chain_id().await?,
+        };
+
+        let typed_data = permit_typed_data(
+            &name,
+            &version,
+            chain_id,
+            token_addr,
+            self.my_address,
+            spender,
+            value,
+            nonce,
+            deadline,
+        );
+
+        // Make sure the token will accept the signature, otherwise the spender would get a useless permit.
+        let expected =
+            domain_separator_json(typed_data.clone()).map_to_mm(|e| TypedDataError::InternalError(e.to_string()))?;
+        if let Ok(Token::FixedBytes(actual)) = self.permit_call(token_addr, "DOMAIN_SEPARATOR", &[]).await {
+            if actual.as_slice() != expected.as_bytes() {
+                return MmError::err(TypedDataError::DomainSeparatorMismatch {
+                    expected: format!("0x{}", hex::encode(expected.as_bytes())),
+                    actual: format!("0x{}", hex::encode(actual)),
+                });
+            }
+        }
+
+        let hash =
+            hash_typed_data_json(typed_data.clone()).map_to_mm(|e| TypedDataError::InternalError(e.to_string()))?;
+        let signature = signature_to_eth_bytes(&self.sign_typed_data_hash(hash)?);
+
+        Ok(Erc20Permit {
+            nonce,
+            deadline,
+            signature,
+            typed_data,
+        })
+    }
+
+    fn sign_typed_data_hash(&self, hash: web3::types::H256) -> TypedDataResult<Signature> {
+        match self.priv_key_policy {
+            PrivKeyPolicy::Iguana(_) | PrivKeyPolicy::HDWallet { .. } => (),
+            PrivKeyPolicy::Trezor => {
+                return MmError::err(TypedDataError::SigningNotSupported(
+                    "Trezor doesn't support EIP-712 typed data".to_owned(),
+                ))
+            },
+            #[cfg(target_arch = "wasm32")]
+            PrivKeyPolicy::Metamask(_) => {
+                return MmError::err(TypedDataError::SigningNotSupported(
+                    "Typed data should be signed by Metamask directly".to_owned(),
+                ))
+            },
+        }
+        let secret = self.priv_key_policy.activated_key_or_err()?.secret();
+        sign(secret, &H256::from(hash.0)).map_to_mm(|e| TypedDataError::InternalError(e.to_string()))
+    }
+
+    /// Calls a view method of the [`ERC20_PERMIT_CONTRACT`] and returns its single output.
+    async fn permit_call(&self, token_addr: Address, method: &str, params: &[Token]) -> TypedDataResult<Token> {
+        let function = ERC20_PERMIT_CONTRACT
+            .function(method)
+            .or_else(|_| ERC20_CONTRACT.function(method))?;
+        let data = function.encode_input(params)?;
+        let res = self.call_request(token_addr, None, Some(data.into())).await?;
+        let mut decoded = function.decode_output(&res.0)?;
+        if decoded.is_empty() {
+            let error = format!("Empty '{}' output", method);
+            return MmError::err(TypedDataError::Transport(error));
+        }
+        Ok(decoded.remove(0))
+    }
+}
+
+async fn eth_coin_for_typed_data(ctx: &MmArc, ticker: &str) -> TypedDataResult<EthCoin> {
+    match lp_coinfind_or_err(ctx, ticker).await? {
+        MmCoinEnum::EthCoin(coin) => Ok(coin),
+        _ => MmError::err(TypedDataError::CoinDoesntSupportTypedData(ticker.to_owned())),
+    }
+}
+
+/// Converts the signature to `r || s || v` bytes where `v` is shifted by [`RECOVERY_ID_OFFSET`].
+fn signature_to_eth_bytes(signature: &Signature) -> [u8; 65] {
+    let mut bytes = [0; 65];
+    bytes[..32].copy_from_slice(signature.r());
+    bytes[32..64].copy_from_slice(signature.s());
+    bytes[64] = signature.v() + RECOVERY_ID_OFFSET;
+    bytes
+}
+
+/// Parses an `r || s || v` hex signature, where `v` is either a raw recovery id or shifted by [`RECOVERY_ID_OFFSET`].
+fn signature_from_eth_str(signature: &str) -> TypedDataResult<Signature> {
+    let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .map_to_mm(|e| TypedDataError::InvalidSignature(e.to_string()))?;
+    if bytes.len() != 65 {
+        let error = format!("Expected 65 bytes, found {}", bytes.len());
+        return MmError::err(TypedDataError::InvalidSignature(error));
+    }
+    let v = match bytes[64] {
+        v @ 0..=1 => v,
+        v => v.wrapping_sub(RECOVERY_ID_OFFSET),
+    };
+    let mut r = [0; 32];
+    let mut s = [0; 32];
+    r.copy_from_slice(&bytes[..32]);
+    s.copy_from_slice(&bytes[32..64]);
+    let signature = Signature::from_rsv(&r.into(), &s.into(), v);
+    if !signature.is_valid() {
+        return MmError::err(TypedDataError::InvalidSignature("Invalid r, s or v values".to_owned()));
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_eth_bytes_roundtrip() {
+        let key_pair = KeyPair::from_secret_slice(&[1; 32]).unwrap();
+        let signature = sign(key_pair.secret(), &H256::from([2; 32])).unwrap();
+
+        let bytes = signature_to_eth_bytes(&signature);
+        assert_eq!(bytes[64], signature.v() + RECOVERY_ID_OFFSET);
+        let actual = signature_from_eth_str(&format!("0x{}", hex::encode(bytes))).unwrap();
+        assert_eq!(actual, signature);
+
+        // the raw recovery id is accepted too
+        let mut raw = bytes;
+        raw[64] = signature.v();
+        let actual = signature_from_eth_str(&hex::encode(raw)).unwrap();
+        assert_eq!(actual, signature);
+    }
+
+    #[test]
+    fn test_signature_from_eth_str_invalid() {
+        signature_from_eth_str("0x1234").unwrap_err();
+        signature_from_eth_str(&"zz".repeat(65)).unwrap_err();
+
+        // invalid recovery id
+        let mut bytes = [1; 65];
+        bytes[64] = 30;
+        signature_from_eth_str(&hex::encode(bytes)).unwrap_err();
+    }
+
+    #[test]
+    fn test_permit_typed_data_hash() {
+        let key_pair = KeyPair::from_secret_slice(&[1; 32]).unwrap();
+        let token_addr = Address::from_low_u64_be(1);
+        let spender = Address::from_low_u64_be(2);
+        let typed_data = permit_typed_data(
+            "Test Token",
+            "1",
+            1.into(),
+            token_addr,
+            key_pair.address(),
+            spender,
+            U256::max_value(),
+            0.into(),
+            1700000000,
+        );
+        assert_eq!(typed_data["message"]["value"], U256::max_value().to_string());
+        assert_eq!(typed_data["domain"]["verifyingContract"], eth_addr_to_hex(&token_addr));
+
+        let hash = hash_typed_data_json(typed_data.clone()).unwrap();
+        // the nonce is a part of the signed message
+        let mut other_nonce = typed_data.clone();
+        other_nonce["message"]["nonce"] = json!("1");
+        assert_ne!(hash_typed_data_json(other_nonce).unwrap(), hash);
+        // the token is a part of the domain
+        let mut other_token = typed_data;
+        other_token["domain"]["verifyingContract"] = json!(eth_addr_to_hex(&spender));
+        assert_ne!(hash_typed_data_json(other_token).unwrap(), hash);
+    }
+}
@@ -0,0 +1,303 @@
+//! RPCs to inspect and manage ERC20 allowances that the wallet has given to third-party spenders.
+
+use super::*;
+use crate::nft::is_nft_chain;
+use crate::nft::nft_structs::{Chain, NftCtx};
+use crate::nft::storage::NftTransferHistoryStorageOps;
+use crate::{lp_coinfind, CoinFindError};
+use common::HttpStatusCode;
+use std::collections::HashSet;
+
+pub type TokenAllowanceResult<T> = Result<T, MmError<TokenAllowanceError>>;
+
+#[derive(Clone, Debug, Deserialize, Display, PartialEq, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum TokenAllowanceError {
+    #[display(fmt = "No such coin {}", coin)]
+    NoSuchCoin { coin: String },
+    #[display(fmt = "'{}' coin doesn't support ERC20 allowances", _0)]
+    CoinDoesntSupportAllowances(String),
+    #[display(fmt = "Transport error: {}", _0)]
+    Transport(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    InternalError(String),
+}
+
+impl HttpStatusCode for TokenAllowanceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            TokenAllowanceError::NoSuchCoin { .. } => StatusCode::NOT_FOUND,
+            TokenAllowanceError::CoinDoesntSupportAllowances(_) => StatusCode::BAD_REQUEST,
+            TokenAllowanceError::Transport(_) | TokenAllowanceError::InternalError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            },
+        }
+    }
+}
+
+impl From<CoinFindError> for TokenAllowanceError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => TokenAllowanceError::NoSuchCoin { coin },
+        }
+    }
+}
+
+impl From<Web3RpcError> for TokenAllowanceError {
+    fn from(e: Web3RpcError) -> Self {
+        match e {
+            Web3RpcError::Transport(err) | Web3RpcError::InvalidResponse(err) => TokenAllowanceError::Transport(err),
+            Web3RpcError::Internal(internal) | Web3RpcError::Timeout(internal) => {
+                TokenAllowanceError::InternalError(internal)
+            },
+        }
+    }
+}
+
+impl From<NumConversError> for TokenAllowanceError {
+    fn from(e: NumConversError) -> Self { TokenAllowanceError::InternalError(e.to_string()) }
+}
+
+/// The reason why a spender is checked for allowances.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpenderType {
+    SwapContract,
+    FallbackSwapContract,
+    NftContract,
+    Custom,
+}
+
+#[derive(Deserialize)]
+pub struct GetTokenAllowancesRequest {
+    /// Either an ERC20 token ticker or a platform coin ticker.
+    /// If a platform coin is given, all ERC20 tokens enabled on top of it are checked.
+    coin: String,
+    /// Additional spender addresses to check along with the swap and NFT contracts.
+    #[serde(default)]
+    spenders: Vec<Address>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenAllowance {
+    pub ticker: String,
+    pub token_address: String,
+    pub spender: String,
+    pub spender_type: SpenderType,
+    pub allowance: BigDecimal,
+    /// Whether the spender is allowed to transfer any amount of the token.
+    pub unlimited: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetTokenAllowancesResponse {
+    /// Non-zero allowances of the requested tokens.
+    pub allowances: Vec<TokenAllowance>,
+}
+
+/// Sets an allowance of the given ERC20 token for the `spender`.
+/// Setting `amount` to zero revokes the allowance.
+#[derive(Deserialize)]
+pub struct SetTokenAllowanceRequest {
+    coin: String,
+    spender: Address,
+    #[serde(default)]
+    amount: BigDecimal,
+    /// Approve an unlimited allowance. If true, then `amount` will be ignored.
+    #[serde(default)]
+    max: bool,
+    fee: Option<WithdrawFee>,
+}
+
+/// Lists non-zero allowances of enabled ERC20 tokens for the known spenders:
+/// the swap contracts, NFT contracts the wallet has interacted with and the spenders from the request.
+pub async fn get_token_allowances(
+    ctx: MmArc,
+    req: GetTokenAllowancesRequest,
+) -> TokenAllowanceResult<GetTokenAllowancesResponse> {
+    let coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::EthCoin(coin) => coin,
+        _ => return MmError::err(TokenAllowanceError::CoinDoesntSupportAllowances(req.coin)),
+    };
+
+    let tokens = match coin.coin_type {
+        EthCoinType::Erc20 { .. } => vec![coin.clone()],
+        EthCoinType::Nft { .. } => return MmError::err(TokenAllowanceError::CoinDoesntSupportAllowances(req.coin)),
+        EthCoinType::Eth => {
+            let mut tokens = Vec::new();
+            for ticker in coin.get_erc_tokens_infos().keys() {
+                if let Ok(Some(MmCoinEnum::EthCoin(token))) = lp_coinfind(&ctx, ticker).await {
+                    tokens.push(token);
+                }
+            }
+            tokens
+        },
+    };
+
+    let spenders = known_spenders(&ctx, &coin, req.spenders).await?;
+
+    let mut allowances = Vec::new();
+    for token in tokens {
+        let token_address = match token.erc20_token_address() {
+            Some(addr) => addr,
+            None => continue,
+        };
+        for (spender, spender_type) in spenders.iter() {
+            let allowed = token.allowance(*spender).compat().await?;
+            if allowed.is_zero() {
+                continue;
+            }
+            allowances.push(TokenAllowance {
+                ticker: token.ticker.clone(),
+                token_address: checksum_address(&eth_addr_to_hex(&token_address)),
+                spender: checksum_address(&eth_addr_to_hex(spender)),
+                spender_type: *spender_type,
+                allowance: u256_to_big_decimal(allowed, token.decimals)?,
+                unlimited: allowed == U256::max_value(),
+            });
+        }
+    }
+
+    Ok(GetTokenAllowancesResponse { allowances })
+}
+
+/// Generates a signed `approve` transaction that sets the ERC20 token allowance for the `spender`.
+/// The transaction should be sent to `send_raw_transaction` RPC to broadcast it.
+pub async fn set_token_allowance(ctx: MmArc, req: SetTokenAllowanceRequest) -> WithdrawResult {
+    let coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::EthCoin(coin) => coin,
+        _ => return MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
+    };
+    let (token_addr, fee_coin) = match coin.coin_type {
+        EthCoinType::Erc20 {
+            ref platform,
+            token_addr,
+        } => (token_addr, platform.clone()),
+        EthCoinType::Eth | EthCoinType::Nft { .. } => return MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
+    };
+
+    let amount = if req.max {
+        U256::max_value()
+    } else {
+        wei_from_big_decimal(&req.amount, coin.decimals)?
+    };
+
+    if coin.approve_reset_required {
+        let allowed = coin.allowance(req.spender).compat().await?;
+        if is_approve_reset_needed(coin.approve_reset_required, allowed, amount) {
+            let error = format!(
+                "'{}' doesn't allow changing a non-zero allowance, the allowance of '{}' should be set to 0 first",
+                coin.ticker,
+                checksum_address(&eth_addr_to_hex(&req.spender))
+            );
+            return MmError::err(WithdrawError::UnsupportedError(error));
+        }
+    }
+
+    let function = ERC20_CONTRACT.function("approve")?;
+    let data = function.encode_input(&[Token::Address(req.spender), Token::Uint(amount)])?;
+
+    let gas_estimated = req.fee.is_none();
+    let (gas, gas_price) =
+        get_eth_gas_details(&coin, req.fee, 0.into(), data.clone().into(), token_addr, false).await?;
+    let l1_cost = coin
+        .estimate_l1_cost(token_addr, 0.into(), &data, gas, gas_price, gas_estimated)
+        .await?;
+    let gas = gas + l1_cost.gas;
+
+    let _nonce_lock = coin.nonce_lock.lock().await;
+    let (nonce, _) = get_addr_nonce(coin.my_address, coin.web3_instances.clone())
+        .compat()
+        .timeout_secs(30.)
+        .await?
+        .map_to_mm(WithdrawError::Transport)?;
+
+    let tx = UnSignedEthTx {
+        nonce,
+        value: 0.into(),
+        action: Action::Call(token_addr),
+        data,
+        gas,
+        gas_price,
+    };
+
+    let secret = coin.priv_key_policy.activated_key_or_err()?.secret();
+    let signed = tx.sign(secret, coin.chain_id);
+    let signed_bytes = rlp::encode(&signed);
+    let fee_details = EthTxFeeDetails::with_l1_fee(gas, gas_price, l1_cost.fee, &fee_coin)?;
+
+    Ok(TransactionDetails {
+        to: vec![checksum_address(&eth_addr_to_hex(&token_addr))],
+        from: vec![checksum_address(&eth_addr_to_hex(&coin.my_address))],
+        total_amount: 0.into(),
+        spent_by_me: 0.into(),
+        received_by_me: 0.into(),
+        my_balance_change: 0.into(),
+        tx_hex: BytesJson::from(signed_bytes.to_vec()),
+        tx_hash: format!("{:02x}", signed.tx_hash()),
+        block_height: 0,
+        fee_details: Some(fee_details.into()),
+        coin: coin.ticker.clone(),
+        internal_id: vec![].into(),
+        timestamp: now_sec(),
+        kmd_rewards: None,
+        transaction_type: TransactionType::TokenApprove,
+        memo: None,
+    })
+}
+
+/// Collects the spenders whose allowances should be checked.
+async fn known_spenders(
+    ctx: &MmArc,
+    coin: &EthCoin,
+    custom_spenders: Vec<Address>,
+) -> TokenAllowanceResult<Vec<(Address, SpenderType)>> {
+    let mut seen = HashSet::new();
+    let mut spenders = Vec::new();
+    let mut push_spender = |address: Address, spender_type: SpenderType| {
+        if seen.insert(address) {
+            spenders.push((address, spender_type));
+        }
+    };
+
+    push_spender(coin.swap_contract_address, SpenderType::SwapContract);
+    if let Some(fallback) = coin.fallback_swap_contract {
+        push_spender(fallback, SpenderType::FallbackSwapContract);
+    }
+    for address in nft_contract_addresses(ctx, coin).await? {
+        push_spender(address, SpenderType::NftContract);
+    }
+    for address in custom_spenders {
+        push_spender(address, SpenderType::Custom);
+    }
+
+    Ok(spenders)
+}
+
+/// Returns addresses of the NFT contracts found in the NFT transfer history of the coin's platform chain.
+async fn nft_contract_addresses(ctx: &MmArc, coin: &EthCoin) -> TokenAllowanceResult<HashSet<Address>> {
+    let platform = match coin.coin_type {
+        EthCoinType::Eth => coin.ticker.as_str(),
+        EthCoinType::Erc20 { ref platform, .. } | EthCoinType::Nft { ref platform, .. } => platform.as_str(),
+    };
+    let chain = Chain::from_ticker(platform);
+    if !is_nft_chain(ctx, &chain) {
+        return Ok(HashSet::new());
+    }
+
+    let nft_ctx = NftCtx::from_ctx(ctx).map_to_mm(TokenAllowanceError::InternalError)?;
+    let storage = nft_ctx
+        .lock_db()
+        .await
+        .mm_err(|e| TokenAllowanceError::InternalError(e.to_string()))?;
+    let is_initialized = NftTransferHistoryStorageOps::is_initialized(&storage, &chain)
+        .await
+        .mm_err(|e| TokenAllowanceError::InternalError(format!("{:?}", e)))?;
+    if !is_initialized {
+        return Ok(HashSet::new());
+    }
+    storage
+        .get_token_addresses(chain)
+        .await
+        .mm_err(|e| TokenAllowanceError::InternalError(format!("{:?}", e)))
+}
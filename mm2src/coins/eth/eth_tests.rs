@@ -4,6 +4,7 @@ use common::{block_on, now_sec, wait_until_sec};
 use crypto::privkey::key_pair_from_seed;
 use ethkey::{Generator, Random};
 use mm2_core::mm_ctx::{MmArc, MmCtxBuilder};
+use mm2_number::BigUint;
 use mm2_test_helpers::{for_tests::{eth_jst_testnet_conf, eth_testnet_conf, ETH_DEV_NODE, ETH_DEV_NODES,
                                    ETH_DEV_SWAP_CONTRACT, ETH_DEV_TOKEN_CONTRACT, ETH_MAINNET_NODE,
                                    ETH_MAINNET_SWAP_CONTRACT},
@@ -47,7 +48,7 @@ pub fn eth_distributor() -> EthCoin {
         "ETH",
         &eth_testnet_conf(),
         &req,
-        CoinProtocol::ETH,
+        CoinProtocol::ETH(None),
         priv_key_policy,
     ))
     .unwrap()
@@ -126,6 +127,7 @@ fn eth_coin_from_keypair(
     let ticker = match coin_type {
         EthCoinType::Eth => "ETH".to_string(),
         EthCoinType::Erc20 { .. } => "JST".to_string(),
+        EthCoinType::Nft { .. } => "NFT_ETH".to_string(),
     };
 
     let eth_coin = EthCoin(Arc::new(EthCoinImpl {
@@ -141,6 +143,8 @@ fn eth_coin_from_keypair(
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         ticker,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
@@ -150,6 +154,7 @@ fn eth_coin_from_keypair(
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -324,6 +329,8 @@ fn send_and_refund_erc20_payment() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
             is_parity: false,
@@ -337,6 +344,7 @@ fn send_and_refund_erc20_payment() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -410,6 +418,8 @@ fn send_and_refund_eth_payment() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
             is_parity: false,
@@ -423,6 +433,7 @@ fn send_and_refund_eth_payment() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -508,6 +519,8 @@ fn test_nonce_several_urls() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![
             Web3Instance {
                 web3: web3_devnet.clone(),
@@ -531,6 +544,7 @@ fn test_nonce_several_urls() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -574,6 +588,8 @@ fn test_wait_for_payment_spend_timeout() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         ticker: "ETH".into(),
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
@@ -583,6 +599,7 @@ fn test_wait_for_payment_spend_timeout() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -644,6 +661,8 @@ fn test_search_for_swap_tx_spend_was_spent() {
         swap_contract_address,
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         ticker: "ETH".into(),
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
@@ -653,6 +672,7 @@ fn test_search_for_swap_tx_spend_was_spent() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -755,6 +775,8 @@ fn test_search_for_swap_tx_spend_was_refunded() {
         swap_contract_address,
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         ticker: "BAT".into(),
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
@@ -764,6 +786,7 @@ fn test_search_for_swap_tx_spend_was_refunded() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -839,6 +862,7 @@ fn test_withdraw_impl_manual_fee() {
             coin: "ETH".into(),
             gas_price: "0.000000001".parse().unwrap(),
             gas: ETH_GAS,
+            l1_fee: None,
             total_fee: "0.00015".parse().unwrap(),
         }
         .into(),
@@ -883,6 +907,7 @@ fn test_withdraw_impl_fee_details() {
             coin: "ETH".into(),
             gas_price: "0.000000001".parse().unwrap(),
             gas: ETH_GAS,
+            l1_fee: None,
             total_fee: "0.00015".parse().unwrap(),
         }
         .into(),
@@ -1084,6 +1109,232 @@ fn get_receiver_trade_preimage() {
     assert_eq!(actual, expected_fee);
 }
 
+#[test]
+fn get_receiver_trade_preimage_op_stack_l1_fee() {
+    EthCoin::get_gas_price.mock_safe(|_| MockResult::Return(Box::new(futures01::future::ok(GAS_PRICE.into()))));
+    EthCoin::call_request.mock_safe(|_, _, _, data| {
+        let get_l1_fee = l1_fee::L2_GAS_ORACLE_CONTRACT.function("getL1Fee").unwrap();
+        let data = data.expect("getL1Fee call data must be set");
+        assert_eq!(data.0[..4], get_l1_fee.short_signature());
+        let output = ethabi::encode(&[Token::Uint(L1_FEE.into())]);
+        MockResult::Return(Box::pin(async move { Ok(Bytes(output)) }))
+    });
+
+    let (_ctx, coin) = eth_coin_for_test(EthCoinType::Eth, &["http://dummy.dummy"], None);
+    let coin_impl = Arc::try_unwrap(coin.0).ok().unwrap();
+    let coin = EthCoin(Arc::new(EthCoinImpl {
+        evm_chain_type: EvmChainType::OpStack,
+        ..coin_impl
+    }));
+    let amount = u256_to_big_decimal((ETH_GAS * GAS_PRICE + L1_FEE).into(), 18).expect("!u256_to_big_decimal");
+    let expected_fee = TradeFee {
+        coin: "ETH".to_owned(),
+        amount: amount.into(),
+        paid_from_trading_vol: false,
+    };
+
+    let actual = coin
+        .get_receiver_trade_fee(FeeApproxStage::WithoutApprox)
+        .wait()
+        .expect("!get_receiver_trade_fee");
+    assert_eq!(actual, expected_fee);
+}
+
+const L1_FEE: u64 = 1_000_000_000_000;
+const L1_GAS: u64 = 50_000;
+/// 1 gwei that is set by the `WithdrawFee::EthGas` of the withdraw tests.
+const WITHDRAW_GAS_PRICE: u64 = 1_000_000_000;
+
+fn eth_coin_with_chain_type_for_test(evm_chain_type: EvmChainType) -> EthCoin {
+    let (_ctx, coin) = eth_coin_for_test(EthCoinType::Eth, &["http://dummy.dummy"], None);
+    let coin_impl = Arc::try_unwrap(coin.0).ok().unwrap();
+    EthCoin(Arc::new(EthCoinImpl {
+        evm_chain_type,
+        ..coin_impl
+    }))
+}
+
+/// Mocks the `GasPriceOracle.getL1Fee` and `NodeInterface.gasEstimateL1Component` calls.
+fn mock_l1_cost_calls() {
+    EthCoin::call_request.mock_safe(|_, to, _, data| {
+        let data = data.expect("L1 cost call data must be set");
+        let get_l1_fee = l1_fee::L2_GAS_ORACLE_CONTRACT.function("getL1Fee").unwrap();
+        let gas_estimate_l1 = l1_fee::L2_GAS_ORACLE_CONTRACT
+            .function("gasEstimateL1Component")
+            .unwrap();
+        let output = if data.0[..4] == get_l1_fee.short_signature() {
+            ethabi::encode(&[Token::Uint(L1_FEE.into())])
+        } else {
+            assert_eq!(data.0[..4], gas_estimate_l1.short_signature());
+            assert_eq!(to, Address::from_low_u64_be(0xc8));
+            ethabi::encode(&[
+                Token::Uint(L1_GAS.into()),
+                Token::Uint(GAS_PRICE.into()),
+                Token::Uint(GAS_PRICE.into()),
+            ])
+        };
+        MockResult::Return(Box::pin(async move { Ok(Bytes(output)) }))
+    });
+}
+
+#[test]
+fn get_sender_trade_preimage_op_stack_l1_fee() {
+    EthCoin::get_gas_price.mock_safe(|_| MockResult::Return(Box::new(futures01::future::ok(GAS_PRICE.into()))));
+    mock_l1_cost_calls();
+
+    let coin = eth_coin_with_chain_type_for_test(EvmChainType::OpStack);
+    // the L1 fee is charged for both `ethPayment` and `senderRefund` calls
+    let amount = u256_to_big_decimal((2 * ETH_GAS * GAS_PRICE + 2 * L1_FEE).into(), 18).expect("!u256_to_big_decimal");
+    let expected_fee = TradeFee {
+        coin: "ETH".to_owned(),
+        amount: amount.into(),
+        paid_from_trading_vol: false,
+    };
+
+    let actual = block_on(coin.get_sender_trade_fee(
+        TradePreimageValue::UpperBound(150.into()),
+        FeeApproxStage::WithoutApprox,
+    ))
+    .expect("!get_sender_trade_fee");
+    assert_eq!(actual, expected_fee);
+}
+
+#[test]
+fn get_sender_trade_preimage_arbitrum_l1_gas() {
+    EthCoin::get_gas_price.mock_safe(|_| MockResult::Return(Box::new(futures01::future::ok(GAS_PRICE.into()))));
+    mock_l1_cost_calls();
+
+    let coin = eth_coin_with_chain_type_for_test(EvmChainType::Arbitrum);
+    // the L1 gas of both `ethPayment` and `senderRefund` calls is added to the gas limit
+    let amount =
+        u256_to_big_decimal(((2 * ETH_GAS + 2 * L1_GAS) * GAS_PRICE).into(), 18).expect("!u256_to_big_decimal");
+    let expected_fee = TradeFee {
+        coin: "ETH".to_owned(),
+        amount: amount.into(),
+        paid_from_trading_vol: false,
+    };
+
+    let actual = block_on(coin.get_sender_trade_fee(
+        TradePreimageValue::UpperBound(150.into()),
+        FeeApproxStage::WithoutApprox,
+    ))
+    .expect("!get_sender_trade_fee");
+    assert_eq!(actual, expected_fee);
+}
+
+#[test]
+fn test_withdraw_impl_max_op_stack_l1_fee() {
+    mock_l1_cost_calls();
+    EthCoin::my_balance.mock_safe(|_| {
+        let balance = wei_from_big_decimal(&1.into(), 18).unwrap();
+        MockResult::Return(Box::new(futures01::future::ok(balance)))
+    });
+    get_addr_nonce.mock_safe(|_, _| MockResult::Return(Box::new(futures01::future::ok((0.into(), vec![])))));
+
+    let coin = eth_coin_with_chain_type_for_test(EvmChainType::OpStack);
+    let withdraw_req = WithdrawRequest {
+        amount: 0.into(),
+        from: None,
+        to: "0x7Bc1bBDD6A0a722fC9bffC49c921B685ECB84b94".to_string(),
+        coin: "ETH".to_string(),
+        max: true,
+        fee: Some(WithdrawFee::EthGas {
+            gas: ETH_GAS,
+            gas_price: 1.into(),
+        }),
+        memo: None,
+    };
+
+    let tx_details = block_on(withdraw_impl(coin, withdraw_req)).unwrap();
+    let total_fee = ETH_GAS * WITHDRAW_GAS_PRICE + L1_FEE;
+    let expected_fee = Some(
+        EthTxFeeDetails {
+            coin: "ETH".into(),
+            gas_price: "0.000000001".parse().unwrap(),
+            gas: ETH_GAS,
+            l1_fee: Some(u256_to_big_decimal(L1_FEE.into(), 18).unwrap()),
+            total_fee: u256_to_big_decimal(total_fee.into(), 18).unwrap(),
+        }
+        .into(),
+    );
+    assert_eq!(expected_fee, tx_details.fee_details);
+    // the whole balance is spent: the amount and the fee including the L1 fee
+    let expected_amount = BigDecimal::from(1) - u256_to_big_decimal(total_fee.into(), 18).unwrap();
+    assert_eq!(tx_details.total_amount, expected_amount);
+    assert_eq!(tx_details.spent_by_me, BigDecimal::from(1));
+}
+
+#[test]
+fn test_withdraw_impl_arbitrum_l1_gas() {
+    mock_l1_cost_calls();
+    EthCoin::my_balance.mock_safe(|_| {
+        let balance = wei_from_big_decimal(&1.into(), 18).unwrap();
+        MockResult::Return(Box::new(futures01::future::ok(balance)))
+    });
+    get_addr_nonce.mock_safe(|_, _| MockResult::Return(Box::new(futures01::future::ok((0.into(), vec![])))));
+
+    let coin = eth_coin_with_chain_type_for_test(EvmChainType::Arbitrum);
+    let withdraw_req = WithdrawRequest {
+        amount: "0.1".parse().unwrap(),
+        from: None,
+        to: "0x7Bc1bBDD6A0a722fC9bffC49c921B685ECB84b94".to_string(),
+        coin: "ETH".to_string(),
+        max: false,
+        fee: Some(WithdrawFee::EthGas {
+            gas: ETH_GAS,
+            gas_price: 1.into(),
+        }),
+        memo: None,
+    };
+
+    let tx_details = block_on(withdraw_impl(coin, withdraw_req)).unwrap();
+    // the L1 gas is added to the user supplied gas limit and the fee is `gas_limit * gas_price`
+    let expected_fee = Some(
+        EthTxFeeDetails {
+            coin: "ETH".into(),
+            gas_price: "0.000000001".parse().unwrap(),
+            gas: ETH_GAS + L1_GAS,
+            l1_fee: None,
+            total_fee: u256_to_big_decimal(((ETH_GAS + L1_GAS) * WITHDRAW_GAS_PRICE).into(), 18).unwrap(),
+        }
+        .into(),
+    );
+    assert_eq!(expected_fee, tx_details.fee_details);
+
+    let signed: UnverifiedTransaction = rlp::decode(&tx_details.tx_hex.0).unwrap();
+    assert_eq!(signed.gas, U256::from(ETH_GAS + L1_GAS));
+}
+
+#[test]
+fn test_send_arbitrum_l1_gas() {
+    static SENT_GAS: AtomicU64 = AtomicU64::new(0);
+
+    mock_l1_cost_calls();
+    EthCoin::sign_and_send_transaction.mock_safe(|_, _, _, _, gas| {
+        SENT_GAS.store(gas.as_u64(), AtomicOrdering::Relaxed);
+        MockResult::Return(Box::new(futures01::future::err(TransactionErr::Plain(
+            "Not sent".to_owned(),
+        ))))
+    });
+
+    let coin = eth_coin_with_chain_type_for_test(EvmChainType::Arbitrum);
+    coin.sign_and_send_transaction_with_l1_gas(
+        0.into(),
+        Action::Call(coin.swap_contract_address),
+        vec![0xff; 100],
+        ETH_GAS.into(),
+    )
+    .wait()
+    .unwrap_err();
+    assert_eq!(SENT_GAS.load(AtomicOrdering::Relaxed), ETH_GAS + L1_GAS);
+
+    // the gas of the transactions to a new contract isn't increased
+    coin.sign_and_send_transaction_with_l1_gas(0.into(), Action::Create, vec![0xff; 100], ETH_GAS.into())
+        .wait()
+        .unwrap_err();
+    assert_eq!(SENT_GAS.load(AtomicOrdering::Relaxed), ETH_GAS);
+}
+
 #[test]
 fn test_get_fee_to_send_taker_fee() {
     const DEX_FEE_AMOUNT: u64 = 100_000;
@@ -1388,7 +1639,7 @@ fn polygon_check_if_my_payment_sent() {
         "MATIC",
         &conf,
         &request,
-        CoinProtocol::ETH,
+        CoinProtocol::ETH(None),
         priv_key_policy,
     ))
     .unwrap();
@@ -1434,6 +1685,8 @@ fn test_message_hash() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
             is_parity: false,
@@ -1447,6 +1700,7 @@ fn test_message_hash() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -1479,6 +1733,8 @@ fn test_sign_verify_message() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
             is_parity: false,
@@ -1492,6 +1748,7 @@ fn test_sign_verify_message() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -1535,6 +1792,8 @@ fn test_eth_extract_secret() {
         swap_contract_address,
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         ticker: "ETH".into(),
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
@@ -1544,6 +1803,7 @@ fn test_eth_extract_secret() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -1627,7 +1887,7 @@ fn test_eth_validate_valid_and_invalid_pubkey() {
         "MATIC",
         &conf,
         &request,
-        CoinProtocol::ETH,
+        CoinProtocol::ETH(None),
         priv_key_policy,
     ))
     .unwrap();
@@ -1642,3 +1902,278 @@ fn test_eth_validate_valid_and_invalid_pubkey() {
     assert!(coin.validate_other_pubkey(&[1u8; 20]).is_err());
     assert!(coin.validate_other_pubkey(&[1u8; 8]).is_err());
 }
+
+#[test]
+fn test_nft_amount_from_big_decimal() {
+    let amount = nft_amount_from_big_decimal(&BigDecimal::from(1), ContractType::Erc721).unwrap();
+    assert_eq!(amount, U256::from(1));
+    let amount = nft_amount_from_big_decimal(&"3.0".parse().unwrap(), ContractType::Erc1155).unwrap();
+    assert_eq!(amount, U256::from(3));
+
+    nft_amount_from_big_decimal(&BigDecimal::from(2), ContractType::Erc721).unwrap_err();
+    nft_amount_from_big_decimal(&BigDecimal::from(0), ContractType::Erc1155).unwrap_err();
+    nft_amount_from_big_decimal(&"1.5".parse().unwrap(), ContractType::Erc1155).unwrap_err();
+}
+
+#[test]
+fn test_swap_approve_amounts_unlimited() {
+    let max = U256::max_value();
+
+    // the allowance is enough, so no approve is needed
+    assert!(swap_approve_amounts(1000.into(), 1000.into(), false, false).is_empty());
+    assert!(swap_approve_amounts(max, 1000.into(), false, true).is_empty());
+
+    assert_eq!(swap_approve_amounts(0.into(), 1000.into(), false, false), vec![max]);
+    assert_eq!(swap_approve_amounts(999.into(), 1000.into(), false, false), vec![max]);
+    // the non-zero allowance has to be reset first
+    assert_eq!(swap_approve_amounts(999.into(), 1000.into(), false, true), vec![
+        U256::zero(),
+        max
+    ]);
+    assert_eq!(swap_approve_amounts(0.into(), 1000.into(), false, true), vec![max]);
+}
+
+#[test]
+fn test_swap_approve_amounts_exact() {
+    // the current allowance may be reserved by a pending payment of a concurrent swap,
+    // so the exact amount is approved anyway
+    assert_eq!(swap_approve_amounts(0.into(), 1000.into(), true, false), vec![
+        1000.into()
+    ]);
+    assert_eq!(swap_approve_amounts(500.into(), 1000.into(), true, false), vec![
+        1000.into()
+    ]);
+    assert_eq!(swap_approve_amounts(2000.into(), 1000.into(), true, false), vec![
+        1000.into()
+    ]);
+
+    assert_eq!(swap_approve_amounts(0.into(), 1000.into(), true, true), vec![
+        1000.into()
+    ]);
+    assert_eq!(swap_approve_amounts(500.into(), 1000.into(), true, true), vec![
+        U256::zero(),
+        1000.into()
+    ]);
+    // the allowance is already equal to the approved amount, so it doesn't change
+    assert_eq!(swap_approve_amounts(1000.into(), 1000.into(), true, true), vec![
+        1000.into()
+    ]);
+}
+
+#[test]
+fn test_is_approve_reset_needed() {
+    assert!(is_approve_reset_needed(true, 500.into(), 1000.into()));
+    assert!(is_approve_reset_needed(true, 1000.into(), U256::max_value()));
+
+    assert!(!is_approve_reset_needed(false, 500.into(), 1000.into()));
+    // revoking is always allowed
+    assert!(!is_approve_reset_needed(true, 500.into(), U256::zero()));
+    // there is no allowance to reset
+    assert!(!is_approve_reset_needed(true, U256::zero(), 1000.into()));
+    assert!(!is_approve_reset_needed(true, 1000.into(), 1000.into()));
+}
+
+#[test]
+fn get_erc20_sender_trade_preimage_exact_approve_with_reset() {
+    const APPROVE_GAS_LIMIT: u64 = 60_000;
+
+    EthCoin::allowance.mock_safe(|_, _| MockResult::Return(Box::new(futures01::future::ok(1000.into()))));
+    EthCoin::get_gas_price.mock_safe(|_| MockResult::Return(Box::new(futures01::future::ok(GAS_PRICE.into()))));
+    EthCoin::estimate_gas
+        .mock_safe(|_, _| MockResult::Return(Box::new(futures01::future::ok(APPROVE_GAS_LIMIT.into()))));
+
+    let conf = json!({
+        "coin": "JST",
+        "name": "jst",
+        "decimals": 18,
+        "approve_reset_required": true,
+        "protocol": {
+            "type": "ERC20",
+            "protocol_data": {
+                "platform": "ETH",
+                "contract_address": "0x2b294F029Fde858b2c62184e8390591755521d8E"
+            }
+        }
+    });
+    let req = json!({
+        "urls": ["http://dummy.dummy"],
+        "swap_contract_address": "0x7Bc1bBDD6A0a722fC9bffC49c921B685ECB84b94",
+        "use_exact_approve": true,
+    });
+    let ctx = MmCtxBuilder::new().into_mm_arc();
+    let coin = block_on(eth_coin_from_conf_and_request(
+        &ctx,
+        "JST",
+        &conf,
+        &req,
+        CoinProtocol::ERC20 {
+            platform: "ETH".to_string(),
+            contract_address: "0x2b294F029Fde858b2c62184e8390591755521d8E".to_string(),
+        },
+        PrivKeyBuildPolicy::IguanaPrivKey(IguanaPrivKey::from([1; 32])),
+    ))
+    .unwrap();
+
+    // the allowance is reset to zero and then set to the exact amount
+    let value = u256_to_big_decimal(500.into(), 18).expect("u256_to_big_decimal");
+    let actual = block_on(coin.get_sender_trade_fee(TradePreimageValue::Exact(value), FeeApproxStage::WithoutApprox))
+        .expect("!get_sender_trade_fee");
+    let expected = u256_to_big_decimal(((300_000 + 2 * APPROVE_GAS_LIMIT) * GAS_PRICE).into(), 18).unwrap();
+    assert_eq!(actual.amount, expected.into());
+}
+
+fn jst_coin_with_chain_id_for_test() -> EthCoin {
+    let conf = json!({
+        "coin": "JST",
+        "name": "jst",
+        "decimals": 18,
+        "chain_id": 1,
+        "protocol": {
+            "type": "ERC20",
+            "protocol_data": {
+                "platform": "ETH",
+                "contract_address": "0x2b294F029Fde858b2c62184e8390591755521d8E"
+            }
+        }
+    });
+    let req = json!({
+        "urls": ["http://dummy.dummy"],
+        "swap_contract_address": "0x7Bc1bBDD6A0a722fC9bffC49c921B685ECB84b94",
+    });
+    let ctx = MmCtxBuilder::new().into_mm_arc();
+    block_on(eth_coin_from_conf_and_request(
+        &ctx,
+        "JST",
+        &conf,
+        &req,
+        CoinProtocol::ERC20 {
+            platform: "ETH".to_string(),
+            contract_address: "0x2b294F029Fde858b2c62184e8390591755521d8E".to_string(),
+        },
+        PrivKeyBuildPolicy::IguanaPrivKey(IguanaPrivKey::from([1; 32])),
+    ))
+    .unwrap()
+}
+
+/// Mocks the ERC20 permit view methods. `domain_separator` is returned by `DOMAIN_SEPARATOR()` if set.
+fn mock_permit_calls(domain_separator: Option<[u8; 32]>) {
+    static mut DOMAIN_SEPARATOR: Option<[u8; 32]> = None;
+    unsafe { DOMAIN_SEPARATOR = domain_separator };
+
+    EthCoin::call_request.mock_safe(|_, _, _, data| {
+        let data = data.expect("call data must be set");
+        let selector = &data.0[..4];
+        let permit_fn = |name: &str| {
+            eip712_sign::ERC20_PERMIT_CONTRACT
+                .function(name)
+                .unwrap()
+                .short_signature()
+        };
+        let output = if selector == permit_fn("nonces") {
+            Ok(ethabi::encode(&[Token::Uint(5.into())]))
+        } else if selector == permit_fn("version") {
+            Ok(ethabi::encode(&[Token::String("2".to_owned())]))
+        } else if selector == ERC20_CONTRACT.function("name").unwrap().short_signature() {
+            Ok(ethabi::encode(&[Token::String("Test Token".to_owned())]))
+        } else if selector == permit_fn("DOMAIN_SEPARATOR") {
+            match unsafe { DOMAIN_SEPARATOR } {
+                Some(separator) => Ok(ethabi::encode(&[Token::FixedBytes(separator.to_vec())])),
+                None => Err(web3::Error::InvalidResponse("execution reverted".to_owned())),
+            }
+        } else {
+            panic!("Unexpected call {:?}", data);
+        };
+        MockResult::Return(Box::pin(async move { output.map(Bytes) }))
+    });
+}
+
+#[test]
+fn test_erc20_permit() {
+    mock_permit_calls(None);
+    let coin = jst_coin_with_chain_id_for_test();
+    let token_addr = coin.erc20_token_address().unwrap();
+    let spender = coin.swap_contract_address;
+
+    let permit = block_on(coin.erc20_permit(token_addr, spender, 1000.into(), 1700000000, None)).unwrap();
+    assert_eq!(permit.nonce, 5.into());
+    assert_eq!(permit.deadline, 1700000000);
+    assert_eq!(permit.typed_data["domain"]["name"], "Test Token");
+    assert_eq!(permit.typed_data["domain"]["version"], "2");
+    assert_eq!(permit.typed_data["domain"]["chainId"], "1");
+    assert_eq!(permit.typed_data["message"]["value"], "1000");
+    assert_eq!(permit.typed_data["message"]["nonce"], "5");
+    assert!(permit.v() == 27 || permit.v() == 28);
+
+    // the signature must be recoverable to the owner address
+    let hash = mm2_eth::eip712_encode::hash_typed_data_json(permit.typed_data.clone()).unwrap();
+    let mut r = [0; 32];
+    let mut s = [0; 32];
+    r.copy_from_slice(permit.r());
+    s.copy_from_slice(permit.s());
+    let signature = Signature::from_rsv(&r.into(), &s.into(), permit.v() - 27);
+    assert!(verify_address(&coin.my_address, &signature, &H256::from(hash.0)).unwrap());
+
+    // the version from the request takes precedence
+    let permit =
+        block_on(coin.erc20_permit(token_addr, spender, 1000.into(), 1700000000, Some("1".to_owned()))).unwrap();
+    assert_eq!(permit.typed_data["domain"]["version"], "1");
+}
+
+#[test]
+fn test_erc20_permit_domain_separator_mismatch() {
+    mock_permit_calls(Some([1; 32]));
+    let coin = jst_coin_with_chain_id_for_test();
+    let token_addr = coin.erc20_token_address().unwrap();
+
+    let error = block_on(coin.erc20_permit(token_addr, coin.swap_contract_address, 1000.into(), 1700000000, None))
+        .unwrap_err()
+        .into_inner();
+    match error {
+        eip712_sign::TypedDataError::DomainSeparatorMismatch { actual, .. } => {
+            assert_eq!(actual, format!("0x{}", hex::encode([1; 32])))
+        },
+        e => panic!("Unexpected error {}", e),
+    }
+}
+
+#[test]
+fn test_bind_nft_token() {
+    let coin_type = EthCoinType::Nft {
+        platform: "ETH".to_owned(),
+        token: None,
+    };
+    let (_ctx, coin) = eth_coin_for_test(coin_type, &[ETH_DEV_NODE], None);
+    let token = NftSwapToken {
+        token_address: Address::from_str("0xfb53b8764be6033d89ceacafa36631b09d60a1d2").unwrap(),
+        token_id: 1u32.into(),
+        contract_type: ContractType::Erc721,
+    };
+
+    // the unbound coin has no token to trade
+    assert!(coin.coin_protocol_info(None).is_empty());
+    assert!(!coin.is_coin_protocol_supported(&Some(token.to_protocol_info()), None, 0, false));
+
+    let bound = coin.bind_nft_token(token.clone()).unwrap();
+    assert_eq!(bound.nft_token(), Some(&token));
+    assert_eq!(bound.ticker(), coin.ticker());
+    assert_eq!(
+        NftSwapToken::from_protocol_info(&bound.coin_protocol_info(None)).unwrap(),
+        token
+    );
+    assert!(bound.is_coin_protocol_supported(&Some(token.to_protocol_info()), Some(MmNumber::from(1u64)), 0, false));
+    // ERC721 token is unique
+    assert!(!bound.is_coin_protocol_supported(&Some(token.to_protocol_info()), Some(MmNumber::from(2u64)), 0, false));
+
+    let other_token = NftSwapToken {
+        token_id: 2u32.into(),
+        ..token.clone()
+    };
+    assert!(!bound.is_coin_protocol_supported(&Some(other_token.to_protocol_info()), None, 0, false));
+    assert!(!bound.is_coin_protocol_supported(&None, None, 0, false));
+
+    let too_big_id = NftSwapToken {
+        token_id: BigUint::from(1u32) << 256,
+        ..token
+    };
+    coin.bind_nft_token(too_big_id).unwrap_err();
+}
@@ -33,6 +33,8 @@ async fn test_send() {
         swap_contract_address: Address::from_str(ETH_DEV_SWAP_CONTRACT).unwrap(),
         fallback_swap_contract: None,
         contract_supports_watchers: false,
+        use_exact_approve: false,
+        approve_reset_required: false,
         web3_instances: vec![Web3Instance {
             web3: web3.clone(),
             is_parity: false,
@@ -46,6 +48,7 @@ async fn test_send() {
         ctx: ctx.weak(),
         required_confirmations: 1.into(),
         chain_id: None,
+        evm_chain_type: EvmChainType::Standard,
         logs_block_range: DEFAULT_LOGS_BLOCK_RANGE,
         nonce_lock: new_nonce_lock(),
         erc20_tokens_infos: Default::default(),
@@ -0,0 +1,249 @@
+//! Estimation of the L1 data fee that L2 rollups charge in addition to the execution fee `gas * gas_price`.
+
+use super::*;
+use ethabi::ParamType;
+
+const L2_GAS_ORACLE_ABI: &str = include_str!("l2_gas_oracle_abi.json");
+/// The nonce is unknown until the transaction is signed, so a large one is used to not underestimate the fee.
+const DUMMY_NONCE: u64 = u32::MAX as u64;
+/// The length of the dummy dynamic arguments (`bytes`, `string`) used to estimate the fee of not built transactions.
+const DUMMY_DYNAMIC_ARG_LEN: usize = 32;
+
+lazy_static! {
+    pub static ref L2_GAS_ORACLE_CONTRACT: Contract = Contract::load(L2_GAS_ORACLE_ABI.as_bytes()).unwrap();
+    /// The `GasPriceOracle` predeploy of the OP Stack chains.
+    static ref OP_STACK_GAS_PRICE_ORACLE: Address =
+        Address::from_str("420000000000000000000000000000000000000F").unwrap();
+    /// The `NodeInterface` virtual contract of the Arbitrum Nitro chains, it's available through `eth_call` only.
+    static ref ARBITRUM_NODE_INTERFACE: Address = Address::from_low_u64_be(0xc8);
+}
+
+/// The type of the EVM chain that determines how the transaction fee is calculated.
+/// Set by the `chain_type` field of the platform coin config, ERC20 tokens inherit it from the platform coin.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvmChainType {
+    /// The fee is `gas * gas_price` only: L1 chains and sidechains.
+    Standard,
+    /// Optimism, Base and other OP Stack rollups charge the L1 data fee on top of the execution fee.
+    /// The fee is quoted by the `GasPriceOracle.getL1Fee` method.
+    OpStack,
+    /// Arbitrum Nitro rollups charge the L1 data fee as an additional L2 gas.
+    /// The gas is quoted by the `NodeInterface.gasEstimateL1Component` method.
+    Arbitrum,
+}
+
+impl Default for EvmChainType {
+    fn default() -> Self { EvmChainType::Standard }
+}
+
+impl EvmChainType {
+    pub fn from_conf(conf: &Json) -> Result<EvmChainType, String> {
+        json::from_value::<Option<EvmChainType>>(conf["chain_type"].clone())
+            .map(Option::unwrap_or_default)
+            .map_err(|e| format!("Invalid 'chain_type': {}", e))
+    }
+}
+
+/// The L1 data cost of a transaction on a rollup.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct L1Cost {
+    /// The L2 gas that Arbitrum charges for the L1 data, it should be added to the gas limit of the transaction.
+    pub gas: U256,
+    /// The fee in wei that OP Stack rollups charge in addition to `gas * gas_price`.
+    pub fee: U256,
+}
+
+impl EthCoin {
+    /// Estimates the L1 data cost of the given transaction.
+    ///
+    /// Arbitrum nodes already include the L1 component into the `eth_estimateGas` result,
+    /// so `gas_estimated` should be set if `gas` is obtained from [`EthCoin::estimate_gas`] to not count it twice.
+    pub(crate) async fn estimate_l1_cost(
+        &self,
+        to: Address,
+        value: U256,
+        data: &[u8],
+        gas: U256,
+        gas_price: U256,
+        gas_estimated: bool,
+    ) -> Web3RpcResult<L1Cost> {
+        match self.evm_chain_type {
+            EvmChainType::Standard => Ok(L1Cost::default()),
+            EvmChainType::OpStack => {
+                let fee = self.estimate_op_stack_l1_fee(to, value, data, gas, gas_price).await?;
+                Ok(L1Cost { gas: U256::zero(), fee })
+            },
+            EvmChainType::Arbitrum if gas_estimated => Ok(L1Cost::default()),
+            EvmChainType::Arbitrum => {
+                let gas = self.estimate_arbitrum_l1_gas(to, data).await?;
+                Ok(L1Cost { gas, fee: U256::zero() })
+            },
+        }
+    }
+
+    /// Estimates the L1 data cost of the swap contract calls that are not built yet, e.g. to calculate the trade fee.
+    pub(crate) async fn estimate_swap_calls_l1_cost(
+        &self,
+        functions: &[&str],
+        gas_price: U256,
+    ) -> Web3RpcResult<L1Cost> {
+        if self.evm_chain_type == EvmChainType::Standard {
+            return Ok(L1Cost::default());
+        }
+
+        let contract: &Contract = match self.coin_type {
+            EthCoinType::Nft { .. } => &NFT_SWAP_CONTRACT,
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => &SWAP_CONTRACT,
+        };
+        let mut total = L1Cost::default();
+        for name in functions {
+            let function = contract.function(name)?;
+            let data = dummy_call_data(function)?;
+            let cost = self
+                .estimate_l1_cost(
+                    self.swap_contract_address,
+                    U256::zero(),
+                    &data,
+                    U256::from(ETH_GAS),
+                    gas_price,
+                    false,
+                )
+                .await?;
+            total.gas += cost.gas;
+            total.fee += cost.fee;
+        }
+        Ok(total)
+    }
+
+    /// Returns the gas limit of a transaction with the fixed execution `gas`
+    /// increased by the L1 gas that Arbitrum charges for the transaction data.
+    pub(crate) async fn gas_with_l1_gas(&self, action: &Action, data: &[u8], gas: U256) -> Web3RpcResult<U256> {
+        match (self.evm_chain_type, action) {
+            (EvmChainType::Arbitrum, Action::Call(to)) => Ok(gas + self.estimate_arbitrum_l1_gas(*to, data).await?),
+            _ => Ok(gas),
+        }
+    }
+
+    /// Quotes the L1 data fee by the `GasPriceOracle.getL1Fee` method of the OP Stack rollups.
+    async fn estimate_op_stack_l1_fee(
+        &self,
+        to: Address,
+        value: U256,
+        data: &[u8],
+        gas: U256,
+        gas_price: U256,
+    ) -> Web3RpcResult<U256> {
+        let unsigned_tx = unsigned_tx_rlp(self.chain_id, to, value, data, gas, gas_price);
+        let function = L2_GAS_ORACLE_CONTRACT.function("getL1Fee")?;
+        let call_data = function.encode_input(&[Token::Bytes(unsigned_tx)])?;
+        let output = self
+            .call_request(*OP_STACK_GAS_PRICE_ORACLE, None, Some(call_data.into()))
+            .await?;
+        match function.decode_output(&output.0)?.into_iter().next() {
+            Some(Token::Uint(l1_fee)) => Ok(l1_fee),
+            other => MmError::err(Web3RpcError::InvalidResponse(format!(
+                "Expected U256 as getL1Fee result but got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Quotes the L1 gas by the `NodeInterface.gasEstimateL1Component` method of the Arbitrum Nitro rollups.
+    async fn estimate_arbitrum_l1_gas(&self, to: Address, data: &[u8]) -> Web3RpcResult<U256> {
+        let function = L2_GAS_ORACLE_CONTRACT.function("gasEstimateL1Component")?;
+        let call_data =
+            function.encode_input(&[Token::Address(to), Token::Bool(false), Token::Bytes(data.to_vec())])?;
+        // The value isn't passed as the call fails if it exceeds the balance, and it doesn't affect the L1 gas.
+        let output = self
+            .call_request(*ARBITRUM_NODE_INTERFACE, None, Some(call_data.into()))
+            .await?;
+        match function.decode_output(&output.0)?.into_iter().next() {
+            Some(Token::Uint(l1_gas)) => Ok(l1_gas),
+            other => MmError::err(Web3RpcError::InvalidResponse(format!(
+                "Expected U256 as gasEstimateL1Component result but got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Returns the name of the swap contract method that sends a payment of this coin.
+    pub(crate) fn swap_payment_function_name(&self) -> &'static str {
+        match self.coin_type {
+            EthCoinType::Eth => "ethPayment",
+            EthCoinType::Erc20 { .. } => "erc20Payment",
+            EthCoinType::Nft { ref token, .. } => nft_swap_functions(nft_swap_contract_type(token)).payment,
+        }
+    }
+
+    /// Returns the name of the swap contract method that spends a payment of this coin.
+    pub(crate) fn swap_spend_function_name(&self) -> &'static str {
+        match self.coin_type {
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => "receiverSpend",
+            EthCoinType::Nft { ref token, .. } => nft_swap_functions(nft_swap_contract_type(token)).spend,
+        }
+    }
+
+    /// Returns the name of the swap contract method that refunds a payment of this coin.
+    pub(crate) fn swap_refund_function_name(&self) -> &'static str {
+        match self.coin_type {
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => "senderRefund",
+            EthCoinType::Nft { ref token, .. } => nft_swap_functions(nft_swap_contract_type(token)).refund,
+        }
+    }
+}
+
+/// Encodes the unsigned legacy transaction as `GasPriceOracle.getL1Fee` expects, the oracle accounts the signature itself.
+fn unsigned_tx_rlp(
+    chain_id: Option<u64>,
+    to: Address,
+    value: U256,
+    data: &[u8],
+    gas: U256,
+    gas_price: U256,
+) -> Vec<u8> {
+    let mut stream = rlp::RlpStream::new_list(if chain_id.is_some() { 9 } else { 6 });
+    stream.append(&DUMMY_NONCE);
+    append_u256(&mut stream, gas_price);
+    append_u256(&mut stream, gas);
+    stream.append(&to.as_bytes());
+    append_u256(&mut stream, value);
+    stream.append(&data);
+    // EIP-155 replay protection fields.
+    if let Some(chain_id) = chain_id {
+        stream.append(&chain_id);
+        stream.append(&0u8);
+        stream.append(&0u8);
+    }
+    stream.out().to_vec()
+}
+
+/// RLP encodes integers as big endian bytes without leading zeros.
+fn append_u256(stream: &mut rlp::RlpStream, value: U256) {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    stream.append(&&bytes[leading_zeros..]);
+}
+
+/// Generates the call data of the given function with all the arguments filled with non-zero bytes.
+/// Rollups charge zero bytes cheaper, so the fee of the real transaction doesn't exceed the estimated one.
+fn dummy_call_data(function: &Function) -> Result<Vec<u8>, ethabi::Error> {
+    let tokens: Vec<Token> = function.inputs.iter().map(|param| dummy_token(&param.kind)).collect();
+    function.encode_input(&tokens)
+}
+
+fn dummy_token(kind: &ParamType) -> Token {
+    match kind {
+        ParamType::Address => Token::Address(Address::repeat_byte(0xff)),
+        ParamType::Bytes => Token::Bytes(vec![0xff; DUMMY_DYNAMIC_ARG_LEN]),
+        ParamType::FixedBytes(size) => Token::FixedBytes(vec![0xff; *size]),
+        ParamType::Int(_) => Token::Int(U256::MAX),
+        ParamType::Uint(_) => Token::Uint(U256::MAX),
+        ParamType::Bool => Token::Bool(true),
+        ParamType::String => Token::String("f".repeat(DUMMY_DYNAMIC_ARG_LEN)),
+        ParamType::Array(kind) => Token::Array(vec![dummy_token(kind)]),
+        ParamType::FixedArray(kind, size) => Token::FixedArray(vec![dummy_token(kind); *size]),
+        ParamType::Tuple(kinds) => Token::Tuple(kinds.iter().map(dummy_token).collect()),
+    }
+}
@@ -0,0 +1,470 @@
+//! Atomic swaps of ERC721/ERC1155 tokens.
+//! The NFT is locked in the `EtomicSwapNft` contract by a hashlock and a timelock the same way as ETH/ERC20 payments
+//! are locked in the `EtomicSwap` contract, so the payment id, the payment states and the events are the same.
+//!
+//! A single NFT coin is activated per chain, the contract and the id of the traded token are carried by the order.
+//! The swap runs with a coin instance bound to that token, see [`EthCoin::bind_nft_token`].
+
+use super::*;
+use crate::nft::nft_structs::{deserialize_token_id, serialize_token_id};
+use mm2_number::BigUint;
+
+const NFT_SWAP_CONTRACT_ABI: &str = include_str!("nft_swap_contract_abi.json");
+/// How often the approval of the swap contract is checked after the `setApprovalForAll` transaction is sent.
+const CHECK_APPROVAL_EVERY: f64 = 5.;
+
+lazy_static! {
+    pub static ref NFT_SWAP_CONTRACT: Contract = Contract::load(NFT_SWAP_CONTRACT_ABI.as_bytes()).unwrap();
+}
+
+/// The NFT traded by an order: the token contract, the token id and the contract standard.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NftSwapToken {
+    pub token_address: Address,
+    #[serde(serialize_with = "serialize_token_id", deserialize_with = "deserialize_token_id")]
+    pub token_id: BigUint,
+    pub contract_type: ContractType,
+}
+
+impl NftSwapToken {
+    /// The token id is checked to fit into `uint256` when the coin is bound to the token.
+    pub(crate) fn token_id_u256(&self) -> U256 { U256::from_big_endian(&self.token_id.to_bytes_be()) }
+
+    /// Serializes the token to be sent as the protocol info of the NFT coin side of an order.
+    pub fn to_protocol_info(&self) -> Vec<u8> { rmp_serde::to_vec(self).expect("Serialization should not fail") }
+
+    pub fn from_protocol_info(info: &[u8]) -> Result<NftSwapToken, String> {
+        rmp_serde::from_slice(info).map_err(|e| ERRL!("Error {} on deserializing NFT protocol info", e))
+    }
+}
+
+/// Returns the token the NFT coin instance is bound to.
+pub(crate) fn bound_nft_token(token: &Option<NftSwapToken>) -> Result<&NftSwapToken, String> {
+    token
+        .as_ref()
+        .ok_or_else(|| ERRL!("NFT coin is not bound to a token, the token must be set by the order"))
+}
+
+/// Returns the contract type of the swap contract calls.
+/// The calls of the coin not bound to a token, e.g. when the trade fee is estimated before the order is created,
+/// are estimated as the ERC1155 ones since they take more arguments.
+pub(crate) fn nft_swap_contract_type(token: &Option<NftSwapToken>) -> ContractType {
+    token
+        .as_ref()
+        .map_or(ContractType::Erc1155, |token| token.contract_type)
+}
+
+/// The names of the `EtomicSwapNft` contract methods called for the NFT of a specific contract type.
+pub(crate) struct NftSwapFunctions {
+    pub(crate) payment: &'static str,
+    pub(crate) spend: &'static str,
+    pub(crate) refund: &'static str,
+}
+
+pub(crate) fn nft_swap_functions(contract_type: ContractType) -> NftSwapFunctions {
+    match contract_type {
+        ContractType::Erc721 => NftSwapFunctions {
+            payment: "erc721Payment",
+            spend: "receiverSpendErc721",
+            refund: "senderRefundErc721",
+        },
+        ContractType::Erc1155 => NftSwapFunctions {
+            payment: "erc1155Payment",
+            spend: "receiverSpendErc1155",
+            refund: "senderRefundErc1155",
+        },
+    }
+}
+
+/// Converts the amount of the NFT coin to the contract units.
+/// The amount must be an integer, and exactly 1 for ERC721 tokens since they are unique.
+pub(crate) fn nft_amount_from_big_decimal(amount: &BigDecimal, contract_type: ContractType) -> Result<U256, String> {
+    if amount.with_scale(0) != *amount || *amount <= BigDecimal::from(0) {
+        return ERR!("NFT amount must be a positive integer, got {}", amount);
+    }
+    if contract_type == ContractType::Erc721 && *amount != BigDecimal::from(1) {
+        return ERR!("ERC721 amount must be 1, got {}", amount);
+    }
+    U256::from_dec_str(&amount.with_scale(0).to_string()).map_err(|e| ERRL!("{:?}", e))
+}
+
+/// Returns the argument of the decoded `function` call by the parameter name,
+/// since the parameters order differs between the ERC721 and ERC1155 methods.
+fn input_by_name(decoded: &[Token], function: &Function, name: &str) -> Result<Token, String> {
+    let index = function
+        .inputs
+        .iter()
+        .position(|param| param.name == name)
+        .ok_or_else(|| ERRL!("Function {} has no '{}' parameter", function.name, name))?;
+    get_function_input_data(decoded, function, index)
+}
+
+impl EthCoin {
+    /// Returns a coin instance trading the given NFT. The instance shares the web3 transport, the nonce lock
+    /// and the keys with this coin, so swaps of different tokens of the same chain can run concurrently.
+    pub fn bind_nft_token(&self, token: NftSwapToken) -> Result<EthCoin, String> {
+        let platform = match &self.coin_type {
+            EthCoinType::Nft { platform, .. } => platform.clone(),
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => return ERR!("{} is not an NFT coin", self.ticker),
+        };
+        if token.token_id.bits() > 256 {
+            return ERR!("NFT token id {} doesn't fit into uint256", token.token_id);
+        }
+        let ctx = try_s!(MmArc::from_weak(&self.ctx).ok_or("No context"));
+        let abortable_system = try_s!(ctx.abortable_system.create_subsystem());
+
+        let coin = EthCoinImpl {
+            ticker: self.ticker.clone(),
+            coin_type: EthCoinType::Nft {
+                platform,
+                token: Some(token),
+            },
+            priv_key_policy: self.priv_key_policy.clone(),
+            my_address: self.my_address,
+            sign_message_prefix: self.sign_message_prefix.clone(),
+            swap_contract_address: self.swap_contract_address,
+            fallback_swap_contract: self.fallback_swap_contract,
+            contract_supports_watchers: self.contract_supports_watchers,
+            use_exact_approve: self.use_exact_approve,
+            approve_reset_required: self.approve_reset_required,
+            web3: self.web3.clone(),
+            web3_instances: self.web3_instances.clone(),
+            decimals: self.decimals,
+            gas_station_url: self.gas_station_url.clone(),
+            gas_station_decimals: self.gas_station_decimals,
+            gas_station_policy: self.gas_station_policy.clone(),
+            history_sync_state: Mutex::new(HistorySyncState::NotEnabled),
+            required_confirmations: AtomicU64::new(self.required_confirmations.load(AtomicOrdering::Relaxed)),
+            ctx: self.ctx.clone(),
+            chain_id: self.chain_id,
+            evm_chain_type: self.evm_chain_type,
+            logs_block_range: self.logs_block_range,
+            nonce_lock: self.nonce_lock.clone(),
+            erc20_tokens_infos: Default::default(),
+            abortable_system,
+        };
+        Ok(EthCoin(Arc::new(coin)))
+    }
+
+    /// Returns the NFT this coin instance is bound to if it's an NFT coin.
+    pub fn nft_token(&self) -> Option<&NftSwapToken> {
+        match &self.coin_type {
+            EthCoinType::Nft { token, .. } => token.as_ref(),
+            EthCoinType::Eth | EthCoinType::Erc20 { .. } => None,
+        }
+    }
+
+    /// Returns the amount of the NFT owned by `address`: 0 or 1 for ERC721, the token balance for ERC1155.
+    pub(super) async fn nft_balance(
+        &self,
+        address: Address,
+        token_addr: Address,
+        token_id: U256,
+        contract_type: ContractType,
+    ) -> BalanceResult<U256> {
+        let (function, args) = match contract_type {
+            ContractType::Erc721 => (ERC721_CONTRACT.function("ownerOf")?, vec![Token::Uint(token_id)]),
+            ContractType::Erc1155 => (ERC1155_CONTRACT.function("balanceOf")?, vec![
+                Token::Address(address),
+                Token::Uint(token_id),
+            ]),
+        };
+        let data = function.encode_input(&args)?;
+        let res = self.call_request(token_addr, None, Some(data.into())).await?;
+        let decoded = function.decode_output(&res.0)?;
+        match decoded.first() {
+            Some(Token::Address(owner)) if *owner == address => Ok(1.into()),
+            Some(Token::Address(_)) => Ok(0.into()),
+            Some(Token::Uint(number)) => Ok(*number),
+            _ => {
+                let error = format!("Unexpected {} result: {:?}", function.name, decoded);
+                MmError::err(BalanceError::InvalidResponse(error))
+            },
+        }
+    }
+
+    /// Encodes the `safeTransferFrom` call that transfers `amount` of the NFT from this wallet to `to`.
+    pub(super) fn nft_transfer_data(
+        &self,
+        to: Address,
+        amount: U256,
+        token_id: U256,
+        contract_type: ContractType,
+    ) -> Result<Vec<u8>, ethabi::Error> {
+        match contract_type {
+            ContractType::Erc721 => ERC721_CONTRACT.function("safeTransferFrom")?.encode_input(&[
+                Token::Address(self.my_address),
+                Token::Address(to),
+                Token::Uint(token_id),
+            ]),
+            ContractType::Erc1155 => ERC1155_CONTRACT.function("safeTransferFrom")?.encode_input(&[
+                Token::Address(self.my_address),
+                Token::Address(to),
+                Token::Uint(token_id),
+                Token::Uint(amount),
+                Token::Bytes("0x".into()),
+            ]),
+        }
+    }
+
+    /// `setApprovalForAll` and `isApprovedForAll` have the same signatures in ERC721 and ERC1155.
+    pub(super) async fn is_approved_for_all(&self, token_addr: Address, operator: Address) -> Web3RpcResult<bool> {
+        let function = ERC721_CONTRACT.function("isApprovedForAll")?;
+        let data = function.encode_input(&[Token::Address(self.my_address), Token::Address(operator)])?;
+        let res = self.call_request(token_addr, None, Some(data.into())).await?;
+        match function.decode_output(&res.0)?.first() {
+            Some(Token::Bool(approved)) => Ok(*approved),
+            token => {
+                let error = format!("Expected Bool as isApprovedForAll result but got {:?}", token);
+                MmError::err(Web3RpcError::InvalidResponse(error))
+            },
+        }
+    }
+
+    /// Approves the swap contract to transfer the NFTs of `token_addr` if it's not approved yet,
+    /// and waits until the approval is applied.
+    async fn approve_nft_for_swap_contract(
+        &self,
+        token_addr: Address,
+        swap_contract_address: Address,
+        wait_until: u64,
+    ) -> Result<(), TransactionErr> {
+        if try_tx_s!(self.is_approved_for_all(token_addr, swap_contract_address).await) {
+            return Ok(());
+        }
+
+        let function = try_tx_s!(ERC721_CONTRACT.function("setApprovalForAll"));
+        let data = try_tx_s!(function.encode_input(&[Token::Address(swap_contract_address), Token::Bool(true)]));
+        let gas_limit = try_tx_s!(
+            self.estimate_gas_for_contract_call(token_addr, Bytes::from(data.clone()))
+                .compat()
+                .await
+        );
+        let approve_tx = self
+            .sign_and_send_transaction(0.into(), Action::Call(token_addr), data, gas_limit)
+            .compat()
+            .await?;
+
+        loop {
+            if now_sec() > wait_until {
+                return TX_PLAIN_ERR!(
+                    "Waited too long until {} for the swap contract to be approved by tx {:02x}",
+                    wait_until,
+                    approve_tx.tx_hash()
+                );
+            }
+            match self.is_approved_for_all(token_addr, swap_contract_address).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => (),
+                Err(e) => error!("Error {} on checking the approval of the swap contract", e),
+            }
+            Timer::sleep(CHECK_APPROVAL_EVERY).await;
+        }
+    }
+
+    pub(super) fn send_nft_swap_payment(&self, args: SendPaymentArgs<'_>, token: &NftSwapToken) -> EthTxFut {
+        if args.watcher_reward.is_some() {
+            return Box::new(futures01::future::err(TransactionErr::Plain(ERRL!(
+                "NFT swaps are not supported by watchers"
+            ))));
+        }
+        let (token_addr, token_id, contract_type) = (token.token_address, token.token_id_u256(), token.contract_type);
+        let receiver_addr = try_tx_fus!(addr_from_raw_pubkey(args.other_pubkey));
+        let swap_contract_address = try_tx_fus!(args.swap_contract_address.try_to_address());
+        let id = self.etomic_swap_id(try_tx_fus!(args.time_lock.try_into()), args.secret_hash);
+        let amount = try_tx_fus!(nft_amount_from_big_decimal(&args.amount, contract_type));
+        let secret_hash = if args.secret_hash.len() == 32 {
+            ripemd160(args.secret_hash).to_vec()
+        } else {
+            args.secret_hash.to_vec()
+        };
+
+        let function = try_tx_fus!(NFT_SWAP_CONTRACT.function(nft_swap_functions(contract_type).payment));
+        let mut tokens = vec![Token::FixedBytes(id)];
+        if contract_type == ContractType::Erc1155 {
+            tokens.push(Token::Uint(amount));
+        }
+        tokens.extend([
+            Token::Address(token_addr),
+            Token::Uint(token_id),
+            Token::Address(receiver_addr),
+            Token::FixedBytes(secret_hash),
+            Token::Uint(U256::from(args.time_lock)),
+        ]);
+        let data = try_tx_fus!(function.encode_input(&tokens));
+
+        let coin = self.clone();
+        let wait_until = args.wait_for_confirmation_until;
+        let fut = async move {
+            coin.approve_nft_for_swap_contract(token_addr, swap_contract_address, wait_until)
+                .await?;
+            coin.sign_and_send_transaction_with_l1_gas(
+                0.into(),
+                Action::Call(swap_contract_address),
+                data,
+                U256::from(ETH_GAS),
+            )
+            .compat()
+            .await
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn spend_nft_swap_payment(
+        &self,
+        payment: SignedEthTx,
+        swap_contract_address: Address,
+        secret: Vec<u8>,
+        contract_type: ContractType,
+    ) -> EthTxFut {
+        let functions = nft_swap_functions(contract_type);
+        let payment_func = try_tx_fus!(NFT_SWAP_CONTRACT.function(functions.payment));
+        let spend_func = try_tx_fus!(NFT_SWAP_CONTRACT.function(functions.spend));
+        let decoded = try_tx_fus!(decode_contract_call(payment_func, &payment.data));
+
+        let coin = self.clone();
+        let fut = async move {
+            let id = try_tx_s!(input_by_name(&decoded, payment_func, "_id"));
+            let state = try_tx_s!(coin.payment_status(swap_contract_address, id.clone()).compat().await);
+            if state != U256::from(PaymentState::Sent as u8) {
+                return TX_PLAIN_ERR!("Payment {:?} state is not PAYMENT_STATE_SENT, got {}", payment, state);
+            }
+
+            let mut tokens = vec![id];
+            if contract_type == ContractType::Erc1155 {
+                tokens.push(try_tx_s!(input_by_name(&decoded, payment_func, "_amount")));
+            }
+            tokens.extend([
+                Token::FixedBytes(secret),
+                try_tx_s!(input_by_name(&decoded, payment_func, "_tokenAddress")),
+                try_tx_s!(input_by_name(&decoded, payment_func, "_tokenId")),
+                Token::Address(payment.sender()),
+            ]);
+            let data = try_tx_s!(spend_func.encode_input(&tokens));
+
+            coin.sign_and_send_transaction_with_l1_gas(
+                0.into(),
+                Action::Call(swap_contract_address),
+                data,
+                U256::from(ETH_GAS),
+            )
+            .compat()
+            .await
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn refund_nft_swap_payment(
+        &self,
+        payment: SignedEthTx,
+        swap_contract_address: Address,
+        contract_type: ContractType,
+    ) -> EthTxFut {
+        let functions = nft_swap_functions(contract_type);
+        let payment_func = try_tx_fus!(NFT_SWAP_CONTRACT.function(functions.payment));
+        let refund_func = try_tx_fus!(NFT_SWAP_CONTRACT.function(functions.refund));
+        let decoded = try_tx_fus!(decode_contract_call(payment_func, &payment.data));
+
+        let coin = self.clone();
+        let fut = async move {
+            let id = try_tx_s!(input_by_name(&decoded, payment_func, "_id"));
+            let state = try_tx_s!(coin.payment_status(swap_contract_address, id.clone()).compat().await);
+            if state != U256::from(PaymentState::Sent as u8) {
+                return TX_PLAIN_ERR!("Payment {:?} state is not PAYMENT_STATE_SENT, got {}", payment, state);
+            }
+
+            let mut tokens = vec![id];
+            if contract_type == ContractType::Erc1155 {
+                tokens.push(try_tx_s!(input_by_name(&decoded, payment_func, "_amount")));
+            }
+            tokens.extend([
+                try_tx_s!(input_by_name(&decoded, payment_func, "_secretHash")),
+                try_tx_s!(input_by_name(&decoded, payment_func, "_tokenAddress")),
+                try_tx_s!(input_by_name(&decoded, payment_func, "_tokenId")),
+                try_tx_s!(input_by_name(&decoded, payment_func, "_receiver")),
+            ]);
+            let data = try_tx_s!(refund_func.encode_input(&tokens));
+
+            coin.sign_and_send_transaction_with_l1_gas(
+                0.into(),
+                Action::Call(swap_contract_address),
+                data,
+                U256::from(ETH_GAS),
+            )
+            .compat()
+            .await
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    /// Validates the arguments of the NFT payment call sent to us.
+    /// The payment state and the sender are expected to be validated by the caller.
+    pub(super) fn validate_nft_swap_payment_tx(
+        &self,
+        tx_from_rpc: &Web3Transaction,
+        expected_swap_contract_address: Address,
+        swap_id: &[u8],
+        secret_hash: &[u8],
+        input: &ValidatePaymentInput,
+    ) -> MmResult<(), ValidatePaymentError> {
+        let token = match &self.coin_type {
+            EthCoinType::Nft { token, .. } => bound_nft_token(token).map_to_mm(ValidatePaymentError::InternalError)?,
+            coin_type => {
+                return MmError::err(ValidatePaymentError::InternalError(format!(
+                    "Expected NFT coin type, found {:?}",
+                    coin_type
+                )))
+            },
+        };
+        let (token_addr, token_id, contract_type) = (token.token_address, token.token_id_u256(), token.contract_type);
+        if input.watcher_reward.is_some() {
+            return MmError::err(ValidatePaymentError::WatcherRewardError(
+                "NFT swaps are not supported by watchers".to_owned(),
+            ));
+        }
+
+        if tx_from_rpc.to != Some(expected_swap_contract_address) {
+            return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                "Payment tx {:?} was sent to wrong address, expected {:?}",
+                tx_from_rpc, expected_swap_contract_address,
+            )));
+        }
+        if !tx_from_rpc.value.is_zero() {
+            return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                "Payment tx value {:?} is invalid, expected 0",
+                tx_from_rpc.value
+            )));
+        }
+
+        let function = NFT_SWAP_CONTRACT
+            .function(nft_swap_functions(contract_type).payment)
+            .map_to_mm(|err| ValidatePaymentError::InternalError(err.to_string()))?;
+        let decoded = decode_contract_call(function, &tx_from_rpc.input.0)
+            .map_to_mm(|err| ValidatePaymentError::TxDeserializationError(err.to_string()))?;
+
+        let amount = nft_amount_from_big_decimal(&input.amount, contract_type)
+            .map_to_mm(ValidatePaymentError::InvalidParameter)?;
+        let mut expected_args = vec![
+            ("_id", Token::FixedBytes(swap_id.to_vec())),
+            ("_tokenAddress", Token::Address(token_addr)),
+            ("_tokenId", Token::Uint(token_id)),
+            ("_receiver", Token::Address(self.my_address)),
+            ("_secretHash", Token::FixedBytes(secret_hash.to_vec())),
+            ("_lockTime", Token::Uint(U256::from(input.time_lock))),
+        ];
+        if contract_type == ContractType::Erc1155 {
+            expected_args.push(("_amount", Token::Uint(amount)));
+        }
+        for (name, expected) in expected_args {
+            let actual =
+                input_by_name(&decoded, function, name).map_to_mm(ValidatePaymentError::TxDeserializationError)?;
+            if actual != expected {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Payment tx {} arg {:?} is invalid, expected {:?}",
+                    name, actual, expected
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -85,12 +85,19 @@ impl Default for EthRpcMode {
 pub struct EthActivationV2Request {
     #[serde(default)]
     pub nodes: Vec<EthNode>,
+    /// How the requests are distributed between the `nodes`
+    /// and how many of them must agree on the security-critical responses.
+    #[serde(default)]
+    pub node_selection: Web3NodeSelection,
     #[serde(default)]
     pub rpc_mode: EthRpcMode,
     pub swap_contract_address: Address,
     pub fallback_swap_contract: Option<Address>,
     #[serde(default)]
     pub contract_supports_watchers: bool,
+    /// Approve exactly the swap amount instead of an unlimited allowance before ERC20 swap payments.
+    /// Overrides `use_exact_approve` from the coin config if set.
+    pub use_exact_approve: Option<bool>,
     pub gas_station_url: Option<String>,
     pub gas_station_decimals: Option<u8>,
     #[serde(default)]
@@ -128,6 +135,9 @@ impl From<MyAddressError> for Erc20TokenActivationError {
 #[derive(Clone, Deserialize)]
 pub struct Erc20TokenActivationRequest {
     pub required_confirmations: Option<u64>,
+    /// Overrides `use_exact_approve` from the token config.
+    /// If neither is set, the platform coin setting is used.
+    pub use_exact_approve: Option<bool>,
 }
 
 pub struct Erc20Protocol {
@@ -186,6 +196,12 @@ impl EthCoin {
             .unwrap_or_else(|| conf["required_confirmations"].as_u64().unwrap_or(1))
             .into();
 
+        let use_exact_approve = activation_params
+            .use_exact_approve
+            .or_else(|| conf["use_exact_approve"].as_bool())
+            .unwrap_or(self.use_exact_approve);
+        let approve_reset_required = conf["approve_reset_required"].as_bool().unwrap_or_default();
+
         // Create an abortable system linked to the `MmCtx` so if the app is stopped on `MmArc::stop`,
         // all spawned futures related to `ERC20` coin will be aborted as well.
         let abortable_system = ctx.abortable_system.create_subsystem()?;
@@ -201,6 +217,8 @@ impl EthCoin {
             swap_contract_address: self.swap_contract_address,
             fallback_swap_contract: self.fallback_swap_contract,
             contract_supports_watchers: self.contract_supports_watchers,
+            use_exact_approve,
+            approve_reset_required,
             decimals,
             ticker,
             gas_station_url: self.gas_station_url.clone(),
@@ -212,6 +230,7 @@ impl EthCoin {
             ctx: self.ctx.clone(),
             required_confirmations,
             chain_id: self.chain_id,
+            evm_chain_type: self.evm_chain_type,
             logs_block_range: self.logs_block_range,
             nonce_lock: self.nonce_lock.clone(),
             erc20_tokens_infos: Default::default(),
@@ -261,7 +280,17 @@ pub async fn eth_coin_from_conf_and_request_v2(
                 activated_key: key_pair,
                 ..
             },
-        ) => build_http_transport(ctx, ticker.clone(), my_address_str, key_pair, &req.nodes).await?,
+        ) => {
+            build_http_transport(
+                ctx,
+                ticker.clone(),
+                my_address_str,
+                key_pair,
+                &req.nodes,
+                &req.node_selection,
+            )
+            .await?
+        },
         (EthRpcMode::Http, EthPrivKeyPolicy::Trezor) => {
             return MmError::err(EthActivationV2Error::PrivKeyPolicyNotAllowed(
                 PrivKeyPolicyNotAllowed::HardwareWalletNotSupported,
@@ -293,6 +322,12 @@ pub async fn eth_coin_from_conf_and_request_v2(
 
     let sign_message_prefix: Option<String> = json::from_value(conf["sign_message_prefix"].clone()).ok();
 
+    let use_exact_approve = req
+        .use_exact_approve
+        .unwrap_or_else(|| conf["use_exact_approve"].as_bool().unwrap_or_default());
+
+    let evm_chain_type = EvmChainType::from_conf(conf).map_to_mm(EthActivationV2Error::InvalidPayload)?;
+
     let mut map = NONCE_LOCK.lock().unwrap();
     let nonce_lock = map.entry(ticker.clone()).or_insert_with(new_nonce_lock).clone();
 
@@ -308,6 +343,8 @@ pub async fn eth_coin_from_conf_and_request_v2(
         swap_contract_address: req.swap_contract_address,
         fallback_swap_contract: req.fallback_swap_contract,
         contract_supports_watchers: req.contract_supports_watchers,
+        use_exact_approve,
+        approve_reset_required: false,
         decimals: ETH_DECIMALS,
         ticker,
         gas_station_url: req.gas_station_url,
@@ -319,6 +356,7 @@ pub async fn eth_coin_from_conf_and_request_v2(
         ctx: ctx.weak(),
         required_confirmations,
         chain_id,
+        evm_chain_type,
         logs_block_range: conf["logs_block_range"].as_u64().unwrap_or(DEFAULT_LOGS_BLOCK_RANGE),
         nonce_lock,
         erc20_tokens_infos: Default::default(),
@@ -380,10 +418,14 @@ async fn build_http_transport(
     address: String,
     key_pair: &KeyPair,
     eth_nodes: &[EthNode],
+    node_selection: &Web3NodeSelection,
 ) -> MmResult<(Web3<Web3Transport>, Vec<Web3Instance>), EthActivationV2Error> {
     if eth_nodes.is_empty() {
         return MmError::err(EthActivationV2Error::AtLeastOneNodeRequired);
     }
+    node_selection
+        .validate(eth_nodes.len())
+        .map_to_mm(EthActivationV2Error::InvalidPayload)?;
 
     let mut http_nodes = vec![];
     for node in eth_nodes {
@@ -412,6 +454,7 @@ async fn build_http_transport(
             key_pair,
             vec![node.clone()],
             event_handlers.clone(),
+            Web3NodeSelection::default(),
         );
 
         let web3 = Web3::new(transport);
@@ -434,7 +477,14 @@ async fn build_http_transport(
         );
     }
 
-    let transport = build_single_http_transport(coin_ticker, address, key_pair, http_nodes, event_handlers);
+    let transport = build_single_http_transport(
+        coin_ticker,
+        address,
+        key_pair,
+        http_nodes,
+        event_handlers,
+        node_selection.clone(),
+    );
     let web3 = Web3::new(transport);
 
     Ok((web3, web3_instances))
@@ -446,10 +496,12 @@ fn build_single_http_transport(
     key_pair: &KeyPair,
     nodes: Vec<HttpTransportNode>,
     event_handlers: Vec<RpcTransportEventHandlerShared>,
+    node_selection: Web3NodeSelection,
 ) -> Web3Transport {
     use crate::eth::web3_transport::http_transport::HttpTransport;
 
     let mut http_transport = HttpTransport::with_event_handlers(nodes, event_handlers);
+    http_transport.node_selection = node_selection;
     http_transport.gui_auth_validation_generator = Some(GuiAuthValidationGenerator {
         coin_ticker,
         secret: key_pair.secret().clone(),
@@ -1,14 +1,19 @@
+use super::node_selection::{quorum_response, sort_nodes, NodeHealth, Web3NodeSelection};
 use crate::eth::{web3_transport::Web3SendOut, EthCoin, GuiAuthMessages, RpcTransportEventHandler,
                  RpcTransportEventHandlerShared, Web3RpcError};
-use common::APPLICATION_JSON;
+use common::{now_ms, APPLICATION_JSON};
+use futures::future::join_all;
 use futures::lock::Mutex as AsyncMutex;
 use http::header::CONTENT_TYPE;
 use jsonrpc_core::{Call, Response};
 use mm2_net::transport::{GuiAuthValidation, GuiAuthValidationGenerator};
+#[cfg(test)] use mocktopus::macros::*;
 use serde_json::Value as Json;
+use std::collections::HashMap;
 #[cfg(not(target_arch = "wasm32"))] use std::ops::Deref;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use web3::error::{Error, TransportError};
 use web3::helpers::{build_request, to_result_from_output, to_string};
 use web3::{RequestId, Transport};
@@ -45,9 +50,52 @@ where
 #[derive(Debug)]
 struct HttpTransportRpcClient(AsyncMutex<HttpTransportRpcClientImpl>);
 
+impl HttpTransportRpcClient {
+    fn new(nodes: Vec<HttpTransportNode>) -> Self {
+        HttpTransportRpcClient(AsyncMutex::new(HttpTransportRpcClientImpl {
+            nodes,
+            health: HashMap::new(),
+        }))
+    }
+
+    /// Returns the nodes in the order they should be requested according to the given `node_selection`.
+    async fn ordered_nodes(&self, node_selection: &Web3NodeSelection) -> Vec<HttpTransportNode> {
+        let client_impl = self.0.lock().await;
+        let mut nodes = client_impl.nodes.clone();
+        sort_nodes(&mut nodes, node_selection.strategy, now_ms(), |node| {
+            client_impl.health.get(&node.uri.to_string())
+        });
+        nodes
+    }
+
+    async fn on_node_success(&self, node: &HttpTransportNode, latency_ms: u64) {
+        let mut client_impl = self.0.lock().await;
+        client_impl
+            .health
+            .entry(node.uri.to_string())
+            .or_default()
+            .on_success(latency_ms);
+        // Keep requesting the node that responded last.
+        if let Some(i) = client_impl.nodes.iter().position(|n| n.uri == node.uri) {
+            client_impl.nodes.rotate_left(i);
+        }
+    }
+
+    async fn on_node_failure(&self, node: &HttpTransportNode) {
+        let mut client_impl = self.0.lock().await;
+        client_impl
+            .health
+            .entry(node.uri.to_string())
+            .or_default()
+            .on_failure(now_ms());
+    }
+}
+
 #[derive(Debug)]
 struct HttpTransportRpcClientImpl {
     nodes: Vec<HttpTransportNode>,
+    /// Health of the nodes by their URIs.
+    health: HashMap<String, NodeHealth>,
 }
 
 #[derive(Clone, Debug)]
@@ -56,6 +104,9 @@ pub struct HttpTransport {
     client: Arc<HttpTransportRpcClient>,
     event_handlers: Vec<RpcTransportEventHandlerShared>,
     pub(crate) gui_auth_validation_generator: Option<GuiAuthValidationGenerator>,
+    pub(crate) node_selection: Web3NodeSelection,
+    /// Whether the responses must be confirmed by [`Web3NodeSelection::quorum`] nodes.
+    quorum_reads: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -68,12 +119,13 @@ impl HttpTransport {
     #[cfg(test)]
     #[inline]
     pub fn new(nodes: Vec<HttpTransportNode>) -> Self {
-        let client_impl = HttpTransportRpcClientImpl { nodes };
         HttpTransport {
             id: Arc::new(AtomicUsize::new(0)),
-            client: Arc::new(HttpTransportRpcClient(AsyncMutex::new(client_impl))),
+            client: Arc::new(HttpTransportRpcClient::new(nodes)),
             event_handlers: Default::default(),
             gui_auth_validation_generator: None,
+            node_selection: Web3NodeSelection::default(),
+            quorum_reads: false,
         }
     }
 
@@ -82,12 +134,13 @@ impl HttpTransport {
         nodes: Vec<HttpTransportNode>,
         event_handlers: Vec<RpcTransportEventHandlerShared>,
     ) -> Self {
-        let client_impl = HttpTransportRpcClientImpl { nodes };
         HttpTransport {
             id: Arc::new(AtomicUsize::new(0)),
-            client: Arc::new(HttpTransportRpcClient(AsyncMutex::new(client_impl))),
+            client: Arc::new(HttpTransportRpcClient::new(nodes)),
             event_handlers,
             gui_auth_validation_generator: None,
+            node_selection: Web3NodeSelection::default(),
+            quorum_reads: false,
         }
     }
 
@@ -97,13 +150,23 @@ impl HttpTransport {
             uri: url.parse().unwrap(),
             gui_auth,
         }];
-        let client_impl = HttpTransportRpcClientImpl { nodes };
 
         HttpTransport {
             id: Arc::new(AtomicUsize::new(0)),
-            client: Arc::new(HttpTransportRpcClient(AsyncMutex::new(client_impl))),
+            client: Arc::new(HttpTransportRpcClient::new(nodes)),
             event_handlers: Default::default(),
             gui_auth_validation_generator: None,
+            node_selection: Web3NodeSelection::default(),
+            quorum_reads: false,
+        }
+    }
+
+    /// Returns a transport sharing the same nodes, but requiring [`Web3NodeSelection::quorum`] nodes
+    /// to return the same response. It's a no-op if the quorum is not configured.
+    pub fn with_quorum_reads(&self) -> HttpTransport {
+        HttpTransport {
+            quorum_reads: self.node_selection.is_quorum_required(),
+            ..self.clone()
         }
     }
 }
@@ -118,23 +181,14 @@ impl Transport for HttpTransport {
         (id, request)
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn send(&self, _id: RequestId, request: Call) -> Self::Out {
-        Box::pin(send_request(
-            request,
-            self.client.clone(),
-            self.event_handlers.clone(),
-            self.gui_auth_validation_generator.clone(),
-        ))
-    }
-
-    #[cfg(target_arch = "wasm32")]
     fn send(&self, _id: RequestId, request: Call) -> Self::Out {
         Box::pin(send_request(
             request,
             self.client.clone(),
             self.event_handlers.clone(),
             self.gui_auth_validation_generator.clone(),
+            self.node_selection.clone(),
+            self.quorum_reads,
         ))
     }
 }
@@ -178,152 +232,173 @@ fn handle_gui_auth_payload_if_activated(
     Ok(Some(to_string(&auth_request)))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 async fn send_request(
     request: Call,
     client: Arc<HttpTransportRpcClient>,
     event_handlers: Vec<RpcTransportEventHandlerShared>,
     gui_auth_validation_generator: Option<GuiAuthValidationGenerator>,
+    node_selection: Web3NodeSelection,
+    quorum_reads: bool,
 ) -> Result<Json, Error> {
-    use common::executor::Timer;
-    use common::log::warn;
-    use futures::future::{select, Either};
-    use gstuff::binprint;
-    use http::header::HeaderValue;
-    use mm2_net::transport::slurp_req;
+    let nodes = client.ordered_nodes(&node_selection).await;
+    let sender = NodeRequestSender {
+        request: &request,
+        client: &client,
+        event_handlers: &event_handlers,
+        gui_auth_validation_generator: &gui_auth_validation_generator,
+    };
 
-    const REQUEST_TIMEOUT_S: f64 = 20.;
+    if quorum_reads {
+        return send_quorum_request(sender, nodes, node_selection.quorum).await;
+    }
 
     let mut errors = Vec::new();
+    for node in nodes.iter() {
+        match sender.send_to_node(node).await {
+            Ok(res) => return Ok(res),
+            Err(e) => errors.push(e),
+        }
+    }
 
-    let serialized_request = to_string(&request);
+    Err(request_failed_error(&request, &errors))
+}
 
-    let mut client_impl = client.0.lock().await;
+/// Sends the request to all nodes at once and returns the response that at least `quorum` nodes agree on.
+async fn send_quorum_request(
+    sender: NodeRequestSender<'_>,
+    nodes: Vec<HttpTransportNode>,
+    quorum: usize,
+) -> Result<Json, Error> {
+    let results = join_all(nodes.iter().map(|node| sender.send_to_node(node))).await;
 
-    for (i, node) in client_impl.nodes.clone().iter().enumerate() {
-        let serialized_request =
-            match handle_gui_auth_payload_if_activated(&gui_auth_validation_generator, node, &request) {
-                Ok(Some(r)) => r,
-                Ok(None) => serialized_request.clone(),
-                Err(e) => {
-                    errors.push(e);
-                    continue;
-                },
-            };
+    let mut responses = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(response) => responses.push(response),
+            Err(e) => errors.push(e),
+        }
+    }
 
-        event_handlers.on_outgoing_request(serialized_request.as_bytes());
-
-        let mut req = http::Request::new(serialized_request.into_bytes());
-        *req.method_mut() = http::Method::POST;
-        *req.uri_mut() = node.uri.clone();
-        req.headers_mut()
-            .insert(CONTENT_TYPE, HeaderValue::from_static(APPLICATION_JSON));
-        let timeout = Timer::sleep(REQUEST_TIMEOUT_S);
-        let req = Box::pin(slurp_req(req));
-        let rc = select(req, timeout).await;
-        let res = match rc {
-            Either::Left((r, _t)) => r,
-            Either::Right((_t, _r)) => {
-                let (method, id) = match &request {
-                    Call::MethodCall(m) => (m.method.clone(), m.id.clone()),
-                    Call::Notification(n) => (n.method.clone(), jsonrpc_core::Id::Null),
-                    Call::Invalid { id } => ("Invalid call".to_string(), id.clone()),
-                };
-                let error = format!(
-                    "Error requesting '{}': {}s timeout expired, method: '{}', id: {:?}",
-                    node.uri, REQUEST_TIMEOUT_S, method, id
-                );
-                warn!("{}", error);
-                errors.push(Web3RpcError::Transport(error));
-                continue;
-            },
-        };
+    if responses.len() < quorum {
+        errors.push(Web3RpcError::Transport(format!(
+            "Only {} of {} nodes responded, required quorum is {}",
+            responses.len(),
+            nodes.len(),
+            quorum
+        )));
+        return Err(request_failed_error(sender.request, &errors));
+    }
 
-        let (status, _headers, body) = match res {
-            Ok(r) => r,
-            Err(err) => {
-                errors.push(Web3RpcError::Transport(err.to_string()));
-                continue;
-            },
-        };
+    let method = match sender.request {
+        Call::MethodCall(m) => m.method.as_str(),
+        Call::Notification(n) => n.method.as_str(),
+        Call::Invalid { .. } => "",
+    };
+    quorum_response(method, responses, quorum).ok_or_else(|| {
+        Error::InvalidResponse(format!(
+            "request {:?} failed: less than {} nodes returned the same response",
+            sender.request, quorum
+        ))
+    })
+}
 
-        event_handlers.on_incoming_response(&body);
+struct NodeRequestSender<'a> {
+    request: &'a Call,
+    client: &'a HttpTransportRpcClient,
+    event_handlers: &'a Vec<RpcTransportEventHandlerShared>,
+    gui_auth_validation_generator: &'a Option<GuiAuthValidationGenerator>,
+}
 
-        if !status.is_success() {
-            errors.push(Web3RpcError::Transport(format!(
-                "Server: '{}', response !200: {}, {}",
-                node.uri,
-                status,
-                binprint(&body, b'.')
-            )));
-            continue;
-        }
+impl<'a> NodeRequestSender<'a> {
+    /// Sends the request to the given node, updates the node health and reports the per-node metrics.
+    async fn send_to_node(&self, node: &HttpTransportNode) -> Result<Json, Web3RpcError> {
+        let serialized_request =
+            match handle_gui_auth_payload_if_activated(self.gui_auth_validation_generator, node, self.request)? {
+                Some(r) => r,
+                None => to_string(self.request),
+            };
 
-        let res = match single_response(body, &node.uri.to_string()) {
-            Ok(r) => r,
-            Err(err) => {
-                errors.push(Web3RpcError::InvalidResponse(format!(
-                    "Server: '{}', error: {}",
-                    node.uri, err
-                )));
-                continue;
+        let started_at = now_ms();
+        let result = send_request_once(serialized_request, &node.uri, self.event_handlers.clone()).await;
+        let latency_ms = now_ms().saturating_sub(started_at);
+        let node_uri = node.uri.to_string();
+        self.event_handlers
+            .on_node_response(&node_uri, Duration::from_millis(latency_ms), result.is_ok());
+
+        match result {
+            Ok(response) => {
+                self.client.on_node_success(node, latency_ms).await;
+                Ok(response)
             },
-        };
-
-        client_impl.nodes.rotate_left(i);
-
-        return Ok(res);
+            Err(e) => {
+                self.client.on_node_failure(node).await;
+                match e {
+                    Error::Transport(e) => {
+                        Err(Web3RpcError::Transport(format!("Server: '{}', error: {}", node_uri, e)))
+                    },
+                    e => Err(Web3RpcError::InvalidResponse(format!(
+                        "Server: '{}', error: {}",
+                        node_uri, e
+                    ))),
+                }
+            },
+        }
     }
-
-    Err(request_failed_error(&request, &errors))
 }
 
-#[cfg(target_arch = "wasm32")]
-async fn send_request(
-    request: Call,
-    client: Arc<HttpTransportRpcClient>,
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg_attr(test, mockable)]
+async fn send_request_once(
+    request_payload: String,
+    uri: &http::Uri,
     event_handlers: Vec<RpcTransportEventHandlerShared>,
-    gui_auth_validation_generator: Option<GuiAuthValidationGenerator>,
 ) -> Result<Json, Error> {
-    let serialized_request = to_string(&request);
+    use common::executor::Timer;
+    use common::log::warn;
+    use futures::future::{select, Either};
+    use gstuff::binprint;
+    use http::header::HeaderValue;
+    use mm2_net::transport::slurp_req;
 
-    let mut errors = Vec::new();
-    let mut client_impl = client.0.lock().await;
+    const REQUEST_TIMEOUT_S: f64 = 20.;
 
-    for (i, node) in client_impl.nodes.clone().iter().enumerate() {
-        let serialized_request =
-            match handle_gui_auth_payload_if_activated(&gui_auth_validation_generator, node, &request) {
-                Ok(Some(r)) => r,
-                Ok(None) => serialized_request.clone(),
-                Err(e) => {
-                    errors.push(e);
-                    continue;
-                },
-            };
+    event_handlers.on_outgoing_request(request_payload.as_bytes());
 
-        match send_request_once(serialized_request, &node.uri, &event_handlers).await {
-            Ok(response_json) => {
-                client_impl.nodes.rotate_left(i);
-                return Ok(response_json);
-            },
-            Err(Error::Transport(e)) => {
-                errors.push(Web3RpcError::Transport(format!("Server: '{}', error: {}", node.uri, e)))
-            },
-            Err(e) => errors.push(Web3RpcError::InvalidResponse(format!(
-                "Server: '{}', error: {}",
-                node.uri, e
-            ))),
-        }
+    let mut req = http::Request::new(request_payload.into_bytes());
+    *req.method_mut() = http::Method::POST;
+    *req.uri_mut() = uri.clone();
+    req.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(APPLICATION_JSON));
+    let timeout = Timer::sleep(REQUEST_TIMEOUT_S);
+    let req = Box::pin(slurp_req(req));
+    let rc = select(req, timeout).await;
+    let res = match rc {
+        Either::Left((r, _t)) => r,
+        Either::Right((_t, _r)) => {
+            let error = format!("Error requesting '{}': {}s timeout expired", uri, REQUEST_TIMEOUT_S);
+            warn!("{}", error);
+            return Err(Error::Transport(TransportError::Message(error)));
+        },
+    };
+
+    let (status, _headers, body) = res.map_err(|e| Error::Transport(TransportError::Message(e.to_string())))?;
+
+    event_handlers.on_incoming_response(&body);
+
+    if !status.is_success() {
+        let error = format!("response !200: {}, {}", status, binprint(&body, b'.'));
+        return Err(Error::Transport(TransportError::Message(error)));
     }
 
-    Err(request_failed_error(&request, &errors))
+    single_response(body, &uri.to_string())
 }
 
 #[cfg(target_arch = "wasm32")]
 async fn send_request_once(
     request_payload: String,
     uri: &http::Uri,
-    event_handlers: &Vec<RpcTransportEventHandlerShared>,
+    event_handlers: Vec<RpcTransportEventHandlerShared>,
 ) -> Result<Json, Error> {
     use http::header::ACCEPT;
     use mm2_net::wasm_http::FetchRequest;
@@ -365,3 +440,249 @@ fn request_failed_error(request: &Call, errors: &[Web3RpcError]) -> Error {
     let error = format!("request {:?} failed: {}", request, errors);
     Error::Transport(TransportError::Message(error))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::web3_transport::node_selection::NodeSelectionStrategy;
+    use common::block_on;
+    use mocktopus::mocking::*;
+    use std::sync::Mutex;
+
+    const NODE_1: &str = "http://node1.test/";
+    const NODE_2: &str = "http://node2.test/";
+    const NODE_3: &str = "http://node3.test/";
+
+    fn transport_for_test(urls: &[&str], node_selection: Web3NodeSelection) -> HttpTransport {
+        let nodes = urls
+            .iter()
+            .map(|url| HttpTransportNode {
+                uri: url.parse().unwrap(),
+                gui_auth: false,
+            })
+            .collect();
+        let mut transport = HttpTransport::new(nodes);
+        transport.node_selection = node_selection;
+        transport
+    }
+
+    /// Mocks the node responses, `None` means the node fails to respond.
+    /// Returns the URIs of the requested nodes.
+    fn mock_nodes(responses: Vec<(&'static str, Option<Json>)>) -> Arc<Mutex<Vec<String>>> {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let requested_clone = requested.clone();
+        send_request_once.mock_safe(move |_, uri, _| {
+            let uri = uri.to_string();
+            requested_clone.lock().unwrap().push(uri.clone());
+            let result = match responses.iter().find(|(url, _)| *url == uri) {
+                Some((_, Some(response))) => Ok(response.clone()),
+                _ => Err(Error::Transport(TransportError::Message(
+                    "Connection refused".to_owned(),
+                ))),
+            };
+            MockResult::Return(Box::pin(async move { result }))
+        });
+        requested
+    }
+
+    #[test]
+    fn test_failover_to_next_node() {
+        let requested = mock_nodes(vec![(NODE_1, None), (NODE_2, Some(json!("0x10")))]);
+        let transport = transport_for_test(&[NODE_1, NODE_2], Web3NodeSelection::default());
+
+        let res = block_on(transport.execute("eth_blockNumber", vec![])).unwrap();
+        assert_eq!(res, json!("0x10"));
+        assert_eq!(*requested.lock().unwrap(), vec![NODE_1.to_owned(), NODE_2.to_owned()]);
+
+        // The node that responded last should be requested first.
+        requested.lock().unwrap().clear();
+        block_on(transport.execute("eth_blockNumber", vec![])).unwrap();
+        assert_eq!(*requested.lock().unwrap(), vec![NODE_2.to_owned()]);
+    }
+
+    #[test]
+    fn test_all_nodes_failed() {
+        mock_nodes(vec![(NODE_1, None), (NODE_2, None)]);
+        let transport = transport_for_test(&[NODE_1, NODE_2], Web3NodeSelection::default());
+
+        let err = block_on(transport.execute("eth_blockNumber", vec![])).unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains(NODE_1) && err.contains(NODE_2), "{}", err);
+    }
+
+    #[test]
+    fn test_unhealthy_node_requested_last() {
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::LatencyScored,
+            quorum: 1,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection.clone());
+        block_on(async {
+            let node_1 = HttpTransportNode {
+                uri: NODE_1.parse().unwrap(),
+                gui_auth: false,
+            };
+            let node_2 = HttpTransportNode {
+                uri: NODE_2.parse().unwrap(),
+                gui_auth: false,
+            };
+            let node_3 = HttpTransportNode {
+                uri: NODE_3.parse().unwrap(),
+                gui_auth: false,
+            };
+            for _ in 0..3 {
+                transport.client.on_node_failure(&node_1).await;
+            }
+            transport.client.on_node_success(&node_2, 500).await;
+            transport.client.on_node_success(&node_3, 100).await;
+        });
+
+        let ordered: Vec<String> = block_on(transport.client.ordered_nodes(&node_selection))
+            .into_iter()
+            .map(|node| node.uri.to_string())
+            .collect();
+        assert_eq!(ordered, vec![NODE_3.to_owned(), NODE_2.to_owned(), NODE_1.to_owned()]);
+    }
+
+    #[test]
+    fn test_quorum_reads() {
+        let tx = json!({
+            "hash": "0x5c3c8b9e84e9f0a6f6a3a9cd8d7d5c0c4d9a1b6de6b2d43fd0c6c0f0b0a0c0d0",
+            "from": "0xbab36286672fbdc7b250804bf6d14be0df69fa29",
+            "value": "0xde0b6b3a7640000",
+        });
+        // Clients may return checksum addresses and `null` fields, that doesn't change the response meaning.
+        let mut tx_other_client = tx.clone();
+        tx_other_client["from"] = json!("0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29");
+        tx_other_client["blockHash"] = Json::Null;
+        let mut malicious_tx = tx.clone();
+        malicious_tx["value"] = json!("0x8ac7230489e80000");
+
+        mock_nodes(vec![
+            (NODE_1, Some(malicious_tx)),
+            (NODE_2, Some(tx.clone())),
+            (NODE_3, Some(tx_other_client)),
+        ]);
+
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 2,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection);
+
+        // Regular requests are not cross-checked.
+        let res = block_on(transport.execute("eth_getTransactionByHash", vec![])).unwrap();
+        assert_eq!(res["value"], json!("0x8ac7230489e80000"));
+
+        let res = block_on(
+            transport
+                .with_quorum_reads()
+                .execute("eth_getTransactionByHash", vec![]),
+        )
+        .unwrap();
+        assert_eq!(res["value"], tx["value"]);
+
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 3,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection);
+        block_on(
+            transport
+                .with_quorum_reads()
+                .execute("eth_getTransactionByHash", vec![]),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_quorum_reads_compare_all_fields() {
+        let receipt = json!({
+            "transactionHash": "0x5c3c8b9e84e9f0a6f6a3a9cd8d7d5c0c4d9a1b6de6b2d43fd0c6c0f0b0a0c0d0",
+            "status": "0x1",
+            "logs": [{ "data": "0x00", "topics": ["0x01"] }],
+        });
+        // The fields missing in the honest response must be compared too.
+        let mut receipt_with_extra_field = receipt.clone();
+        receipt_with_extra_field["logs"][0]["removed"] = json!(true);
+
+        mock_nodes(vec![
+            (NODE_1, Some(receipt_with_extra_field.clone())),
+            (NODE_2, Some(receipt)),
+            (NODE_3, Some(receipt_with_extra_field.clone())),
+        ]);
+
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 2,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection);
+        let res = block_on(
+            transport
+                .with_quorum_reads()
+                .execute("eth_getTransactionReceipt", vec![]),
+        )
+        .unwrap();
+        assert_eq!(res, receipt_with_extra_field);
+
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 3,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection);
+        block_on(
+            transport
+                .with_quorum_reads()
+                .execute("eth_getTransactionReceipt", vec![]),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_quorum_reads_not_enough_responses() {
+        mock_nodes(vec![(NODE_1, Some(json!(null))), (NODE_2, None)]);
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 2,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2], node_selection);
+        block_on(
+            transport
+                .with_quorum_reads()
+                .execute("eth_getTransactionReceipt", vec![]),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_quorum_block_number() {
+        mock_nodes(vec![
+            (NODE_1, Some(json!("0x10"))),
+            (NODE_2, Some(json!("0x12"))),
+            (NODE_3, Some(json!("0x11"))),
+        ]);
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 2,
+        };
+        let transport = transport_for_test(&[NODE_1, NODE_2, NODE_3], node_selection);
+
+        // The highest block that at least 2 nodes have reached.
+        let res = block_on(transport.with_quorum_reads().execute("eth_blockNumber", vec![])).unwrap();
+        assert_eq!(res, json!("0x11"));
+    }
+
+    #[test]
+    fn test_validate_node_selection() {
+        let node_selection = Web3NodeSelection {
+            strategy: NodeSelectionStrategy::Failover,
+            quorum: 3,
+        };
+        node_selection.validate(2).unwrap_err();
+        node_selection.validate(3).unwrap();
+
+        let node_selection: Web3NodeSelection = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(node_selection.strategy, NodeSelectionStrategy::Failover);
+        assert!(!node_selection.is_quorum_required());
+    }
+}
@@ -13,6 +13,9 @@ use web3::{Error, RequestId, Transport};
 
 pub(crate) mod http_transport;
 #[cfg(target_arch = "wasm32")] pub(crate) mod metamask_transport;
+pub mod node_selection;
+
+pub use node_selection::Web3NodeSelection;
 
 type Web3SendOut = BoxFuture<'static, Result<Json, Error>>;
 
@@ -27,8 +30,11 @@ impl Web3Transport {
     pub fn new_http(
         nodes: Vec<http_transport::HttpTransportNode>,
         event_handlers: Vec<RpcTransportEventHandlerShared>,
+        node_selection: Web3NodeSelection,
     ) -> Web3Transport {
-        http_transport::HttpTransport::with_event_handlers(nodes, event_handlers).into()
+        let mut http_transport = http_transport::HttpTransport::with_event_handlers(nodes, event_handlers);
+        http_transport.node_selection = node_selection;
+        http_transport.into()
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -49,6 +55,16 @@ impl Web3Transport {
         http_transport::HttpTransport::single_node(url, gui_auth).into()
     }
 
+    /// Returns a transport that requires the configured quorum of nodes to return the same response.
+    /// Should be used for security-critical requests, so a single lagging or malicious node can't affect them.
+    pub fn with_quorum_reads(&self) -> Web3Transport {
+        match self {
+            Web3Transport::Http(http) => http.with_quorum_reads().into(),
+            #[cfg(target_arch = "wasm32")]
+            Web3Transport::Metamask(_) => self.clone(),
+        }
+    }
+
     pub fn gui_auth_validation_generator_as_mut(&mut self) -> Option<&mut GuiAuthValidationGenerator> {
         match self {
             Web3Transport::Http(http) => http.gui_auth_validation_generator.as_mut(),
@@ -0,0 +1,166 @@
+//! Strategies of distributing the requests between Web3 RPC nodes and cross-checking their responses.
+
+use serde_json::{self as json, Value as Json};
+use web3::types::U64;
+
+/// The node is considered unhealthy after this number of failed requests in a row.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+/// An unhealthy node is moved to the end of the queue for this period since its last failure.
+const UNHEALTHY_NODE_COOLDOWN_MS: u64 = 60_000;
+/// The weight of the last request latency in the latency moving average.
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.3;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSelectionStrategy {
+    /// Keep sending requests to the node that responded last, switch to the next one on errors.
+    Failover,
+    /// Prefer healthy nodes with the lowest average response time.
+    LatencyScored,
+}
+
+impl Default for NodeSelectionStrategy {
+    fn default() -> Self { NodeSelectionStrategy::Failover }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Web3NodeSelection {
+    #[serde(default)]
+    pub strategy: NodeSelectionStrategy,
+    /// The number of nodes that must return the same response to the security-critical requests:
+    /// swap payment validation, swap spend search and confirmations.
+    /// `1` means that the response of any node is trusted.
+    #[serde(default = "default_quorum")]
+    pub quorum: usize,
+}
+
+fn default_quorum() -> usize { 1 }
+
+impl Default for Web3NodeSelection {
+    fn default() -> Self {
+        Web3NodeSelection {
+            strategy: NodeSelectionStrategy::default(),
+            quorum: default_quorum(),
+        }
+    }
+}
+
+impl Web3NodeSelection {
+    pub fn validate(&self, nodes_count: usize) -> Result<(), String> {
+        if self.quorum == 0 {
+            return Err("'quorum' must be greater than zero".to_owned());
+        }
+        if self.quorum > nodes_count {
+            return Err(format!(
+                "'quorum' {} is greater than the number of nodes {}",
+                self.quorum, nodes_count
+            ));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn is_quorum_required(&self) -> bool { self.quorum > 1 }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NodeHealth {
+    /// The moving average of the response time.
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub last_failure_ms: u64,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+impl NodeHealth {
+    pub fn on_success(&mut self, latency_ms: u64) {
+        let latency_ms = latency_ms as f64;
+        self.requests += 1;
+        self.consecutive_failures = 0;
+        self.latency_ms = Some(match self.latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING_FACTOR * (latency_ms - avg),
+            None => latency_ms,
+        });
+    }
+
+    pub fn on_failure(&mut self, now_ms: u64) {
+        self.requests += 1;
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        self.last_failure_ms = now_ms;
+    }
+
+    /// Unhealthy nodes are given another chance after [`UNHEALTHY_NODE_COOLDOWN_MS`].
+    pub fn is_healthy(&self, now_ms: u64) -> bool {
+        self.consecutive_failures < MAX_CONSECUTIVE_FAILURES
+            || now_ms.saturating_sub(self.last_failure_ms) >= UNHEALTHY_NODE_COOLDOWN_MS
+    }
+}
+
+/// Sorts the nodes in the order they should be requested.
+/// Unhealthy nodes are moved to the end instead of being excluded, so requests still have a chance to succeed.
+pub(crate) fn sort_nodes<'a, T, F>(nodes: &mut [T], strategy: NodeSelectionStrategy, now_ms: u64, health_of: F)
+where
+    F: Fn(&T) -> Option<&'a NodeHealth>,
+{
+    let is_unhealthy = |node: &T| health_of(node).map_or(false, |health| !health.is_healthy(now_ms));
+    match strategy {
+        // The sort is stable, so the last responded node stays first unless it's unhealthy.
+        NodeSelectionStrategy::Failover => nodes.sort_by_key(&is_unhealthy),
+        // Nodes without measurements go first to get their latency measured.
+        NodeSelectionStrategy::LatencyScored => nodes.sort_by_key(|node| {
+            let latency = health_of(node).and_then(|health| health.latency_ms).unwrap_or_default();
+            (is_unhealthy(node), latency as u64)
+        }),
+    }
+}
+
+/// Returns the response that at least `quorum` nodes agree on.
+pub(crate) fn quorum_response(method: &str, responses: Vec<Json>, quorum: usize) -> Option<Json> {
+    if quorum == 0 {
+        return None;
+    }
+
+    // Nodes are rarely at the same height, so take the highest block that at least `quorum` nodes have reached.
+    if method == "eth_blockNumber" {
+        let mut heights: Vec<U64> = responses
+            .into_iter()
+            .filter_map(|response| json::from_value(response).ok())
+            .collect();
+        heights.sort_unstable_by(|a, b| b.cmp(a));
+        return heights.get(quorum - 1).and_then(|height| json::to_value(height).ok());
+    }
+
+    let mut groups: Vec<(Json, usize)> = Vec::new();
+    for response in responses {
+        match groups.iter_mut().find(|(group, _)| responses_agree(group, &response)) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((response, 1)),
+        }
+    }
+    groups
+        .into_iter()
+        .find(|(_, count)| *count >= quorum)
+        .map(|(response, _)| response)
+}
+
+/// Checks whether the responses are equal after normalization, see [`normalize_response`].
+fn responses_agree(left: &Json, right: &Json) -> bool { normalize_response(left) == normalize_response(right) }
+
+/// Normalizes the response representation that may differ between node clients without changing its meaning:
+/// `null` fields are omitted and hex strings are lowercased.
+fn normalize_response(response: &Json) -> Json {
+    match response {
+        Json::Object(object) => Json::Object(
+            object
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key.clone(), normalize_response(value)))
+                .collect(),
+        ),
+        Json::Array(array) => Json::Array(array.iter().map(normalize_response).collect()),
+        Json::String(string) if string.starts_with("0x") => Json::String(string.to_lowercase()),
+        other => other.clone(),
+    }
+}
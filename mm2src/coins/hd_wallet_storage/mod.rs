@@ -222,7 +222,8 @@ impl HDWalletCoinStorage {
         })
     }
 
-    #[cfg(any(test, target_arch = "wasm32"))]
+    /// Initializes the storage of an HD wallet that is identified by the given `hd_wallet_rmd160`
+    /// rather than by the connected Hardware Wallet device.
     pub async fn init_with_rmd160(
         ctx: &MmArc,
         coin: String,
@@ -1,3 +1,4 @@
+pub mod ln_channel_backup;
 pub mod ln_conf;
 pub(crate) mod ln_db;
 pub mod ln_errors;
@@ -8,6 +9,7 @@ pub mod ln_platform;
 pub(crate) mod ln_serialization;
 mod ln_sql;
 pub mod ln_storage;
+mod ln_tx_history;
 pub mod ln_utils;
 
 use crate::coin_errors::MyAddressError;
@@ -73,7 +75,7 @@ use secp256k1v24::PublicKey;
 use serde::Deserialize;
 use serde_json::Value as Json;
 use std::collections::{HashMap, HashSet};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::io::Cursor;
 use std::net::SocketAddr;
@@ -955,6 +957,14 @@ fn payment_hash_from_slice(data: &[u8]) -> Result<PaymentHash, PaymentHashFromSl
     Ok(PaymentHash(hash))
 }
 
+/// The fee a node charges for forwarding `amount_msat` according to its channel's forwarding info.
+/// Calculated with `u128` since `amount_msat * fee_proportional_millionths` can exceed `u64::MAX`.
+fn forwarding_fee_msat(amount_msat: u64, fee_base_msat: u32, fee_proportional_millionths: u32) -> u64 {
+    let proportional_fee_msat = amount_msat as u128 * fee_proportional_millionths as u128 / 1_000_000;
+    let fee_msat = fee_base_msat as u128 + proportional_fee_msat;
+    u64::try_from(fee_msat).unwrap_or(u64::MAX)
+}
+
 #[async_trait]
 impl WatcherOps for LightningCoin {
     fn create_maker_payment_spend_preimage(
@@ -1308,14 +1318,41 @@ impl MmCoin for LightningCoin {
         }
     }
 
-    // Todo: Implement this when implementing payments history for lightning
-    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> { unimplemented!() }
+    // The history is read directly from the lightning DB by `my_tx_history` v2, so there is nothing to sync.
+    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> {
+        Box::new(futures01::future::ok(()))
+    }
 
-    // Todo: Implement this when implementing payments history for lightning
-    fn history_sync_status(&self) -> HistorySyncState { unimplemented!() }
+    fn history_sync_status(&self) -> HistorySyncState { HistorySyncState::Finished }
 
-    // Todo: Implement this when implementing swaps for lightning as it's is used only for swaps
-    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> { unimplemented!() }
+    /// Estimates the routing fee of a payment as the highest fee a direct counterparty would charge
+    /// for forwarding the whole outbound capacity of one of our usable channels.
+    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> {
+        let coin = self.clone();
+        let fut = async move {
+            let max_fee_msat = coin
+                .list_channels()
+                .await
+                .into_iter()
+                .filter(|channel| channel.is_usable)
+                .filter_map(|channel| {
+                    let forwarding_info = channel.counterparty.forwarding_info?;
+                    Some(forwarding_fee_msat(
+                        channel.outbound_capacity_msat,
+                        forwarding_info.fee_base_msat,
+                        forwarding_info.fee_proportional_millionths,
+                    ))
+                })
+                .max()
+                .unwrap_or_default();
+            Ok(TradeFee {
+                coin: coin.ticker().to_owned(),
+                amount: big_decimal_from_sat_unsigned(max_fee_msat, coin.decimals()).into(),
+                paid_from_trading_vol: false,
+            })
+        };
+        Box::new(fut.boxed().compat())
+    }
 
     // Todo: This uses dummy data for now for the sake of swap P.O.C., this should be implemented probably after agreeing on how fees will work for lightning
     async fn get_sender_trade_fee(
@@ -1458,3 +1495,19 @@ impl MmCoin for LightningCoin {
 
     fn on_token_deactivated(&self, _ticker: &str) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarding_fee_msat() {
+        assert_eq!(forwarding_fee_msat(0, 1000, 100), 1000);
+        assert_eq!(forwarding_fee_msat(1_000_000, 1000, 100), 1100);
+        // 1 BTC with 1% proportional fee.
+        assert_eq!(forwarding_fee_msat(100_000_000_000, 0, 10_000), 1_000_000_000);
+        // `amount_msat * fee_proportional_millionths` overflows u64.
+        assert_eq!(forwarding_fee_msat(u64::MAX / 2, 1000, 1_000_000), u64::MAX / 2 + 1000);
+        assert_eq!(forwarding_fee_msat(u64::MAX, u32::MAX, u32::MAX), u64::MAX);
+    }
+}
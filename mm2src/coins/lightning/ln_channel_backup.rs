@@ -0,0 +1,120 @@
+//! Static channel backup (SCB) of the lightning node.
+//!
+//! The backup contains the addresses of the channels' counterparties and the serialized channel monitors,
+//! it's rewritten by the persister on every channel monitor update and encrypted with a key derived from the node's seed,
+//! so it can be safely copied to a remote location and restored only by the same node.
+
+use bitcoin_hashes::sha256::Hash as Sha256;
+use bitcoin_hashes::Hash;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use mm2_io::fs::invalid_data_err;
+use rpc::v1::types::Bytes as BytesJson;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+const CHANNEL_BACKUP_VERSION: u8 = 1;
+const CHANNEL_BACKUP_KEY_PREFIX: &[u8] = b"lightning_static_channel_backup";
+const NONCE_LEN: usize = 12;
+
+pub type ChannelBackupKey = [u8; 32];
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ChannelBackup {
+    /// The funding outpoint of the channel in the `txid:index` format.
+    pub funding_txo: String,
+    /// The serialized `(BlockHash, ChannelMonitor)` as it's written to the monitors directory.
+    pub monitor: BytesJson,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StaticChannelBackup {
+    pub version: u8,
+    /// The addresses of the nodes that the lightning node has channels with, used for reconnecting on restore.
+    pub peers: HashMap<String, SocketAddr>,
+    pub channels: Vec<ChannelBackup>,
+}
+
+impl StaticChannelBackup {
+    pub fn new(peers: HashMap<String, SocketAddr>, channels: Vec<ChannelBackup>) -> Self {
+        StaticChannelBackup {
+            version: CHANNEL_BACKUP_VERSION,
+            peers,
+            channels,
+        }
+    }
+}
+
+/// Derives the key the static channel backup is encrypted with from the seed of the lightning node.
+pub fn channel_backup_key(node_seed: &[u8; 32]) -> ChannelBackupKey {
+    let mut preimage = CHANNEL_BACKUP_KEY_PREFIX.to_vec();
+    preimage.extend_from_slice(node_seed);
+    Sha256::hash(&preimage).into_inner()
+}
+
+/// Serializes and encrypts the backup, the random nonce is prepended to the ciphertext.
+pub fn encrypt_channel_backup(key: &ChannelBackupKey, backup: &StaticChannelBackup) -> std::io::Result<Vec<u8>> {
+    let plaintext = serde_json::to_vec(backup).map_err(|e| invalid_data_err("Error serializing channel backup", e))?;
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| invalid_data_err("Error encrypting channel backup", "encryption failure"))?;
+
+    let mut encrypted = nonce.to_vec();
+    encrypted.extend(ciphertext);
+    Ok(encrypted)
+}
+
+pub fn decrypt_channel_backup(key: &ChannelBackupKey, encrypted: &[u8]) -> std::io::Result<StaticChannelBackup> {
+    if encrypted.len() <= NONCE_LEN {
+        return Err(invalid_data_err("Invalid channel backup", "the backup is too short"));
+    }
+    let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            invalid_data_err(
+                "Error decrypting channel backup",
+                "the backup is corrupted or belongs to another node",
+            )
+        })?;
+    let backup: StaticChannelBackup =
+        serde_json::from_slice(&plaintext).map_err(|e| invalid_data_err("Error deserializing channel backup", e))?;
+    if backup.version != CHANNEL_BACKUP_VERSION {
+        return Err(invalid_data_err("Unsupported channel backup version", backup.version));
+    }
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_channel_backup() {
+        let key = channel_backup_key(&[1; 32]);
+        let mut peers = HashMap::new();
+        peers.insert(
+            "038863cf8ab91046230f561cd5b386cbff8309fa02e3f0c3ed161a3aeb64a643b9".to_string(),
+            "203.132.94.196:9735".parse().unwrap(),
+        );
+        let channels = vec![ChannelBackup {
+            funding_txo: "9c43b1e2e6ef1f1ebb7ca6ab0e2f6d8fcfd8f9b3a1fcd1b2c3d4e5f60718293a:0".to_string(),
+            monitor: vec![1, 2, 3, 4].into(),
+        }];
+        let backup = StaticChannelBackup::new(peers, channels);
+
+        let encrypted = encrypt_channel_backup(&key, &backup).unwrap();
+        let decrypted = decrypt_channel_backup(&key, &encrypted).unwrap();
+        assert_eq!(decrypted, backup);
+
+        let other_key = channel_backup_key(&[2; 32]);
+        decrypt_channel_backup(&other_key, &encrypted).unwrap_err();
+
+        let mut corrupted = encrypted;
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        decrypt_channel_backup(&key, &corrupted).unwrap_err();
+    }
+}
@@ -196,6 +196,61 @@ pub struct GetPaymentsResult {
     pub total: usize,
 }
 
+/// A payment that was forwarded through our node from `prev_channel_id` to `next_channel_id`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DBForwardedPayment {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_earned_msat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_forwarded_msat: Option<i64>,
+    pub claim_from_onchain_tx: bool,
+    pub forwarded_at: i64,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ForwardedPaymentsFilter {
+    /// Matches the forwarded payments that went in or out through this channel.
+    pub channel_id: Option<String>,
+    pub from_timestamp: Option<i64>,
+    pub to_timestamp: Option<i64>,
+}
+
+pub struct GetForwardedPaymentsResult {
+    pub forwarded_payments: Vec<DBForwardedPayment>,
+    pub skipped: usize,
+    pub total: usize,
+}
+
+/// The routing statistics of a channel, the fee of a forwarded payment is counted for its outgoing channel.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChannelForwardingEarnings {
+    pub channel_id: String,
+    pub forwarded_in: u64,
+    pub forwarded_out: u64,
+    pub amount_forwarded_out_msat: i64,
+    pub fee_earned_msat: i64,
+}
+
+/// An event of the lightning node's history.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DBHistoryItem {
+    Payment(PaymentInfo),
+    /// The channel's funding transaction is known.
+    ChannelOpened(DBChannelDetails),
+    ChannelClosed(DBChannelDetails),
+}
+
+pub struct GetHistoryResult {
+    pub items: Vec<DBHistoryItem>,
+    pub skipped: usize,
+    pub total: usize,
+}
+
 #[async_trait]
 pub trait LightningDB {
     type Error;
@@ -305,4 +360,41 @@ pub trait LightningDB {
         paging: PagingOptionsEnum<PaymentHash>,
         limit: usize,
     ) -> Result<GetPaymentsResult, Self::Error>;
+
+    /// Inserts a new forwarded payment record in the DB.
+    async fn add_forwarded_payment_to_db(
+        &self,
+        prev_channel_id: Option<[u8; 32]>,
+        next_channel_id: Option<[u8; 32]>,
+        fee_earned_msat: Option<u64>,
+        amount_forwarded_msat: Option<u64>,
+        claim_from_onchain_tx: bool,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the list of forwarded payments that match the provided filter criteria, the most recent first.
+    /// The starting record to list from is specified by its id in the paging parameter.
+    async fn get_forwarded_payments_by_filter(
+        &self,
+        filter: Option<ForwardedPaymentsFilter>,
+        paging: PagingOptionsEnum<i64>,
+        limit: usize,
+    ) -> Result<GetForwardedPaymentsResult, Self::Error>;
+
+    /// Gets the routing statistics of every channel that forwarded payments in the optional time range,
+    /// the channels with the highest earned fees first.
+    async fn get_forwarding_earnings(
+        &self,
+        from_timestamp: Option<i64>,
+        to_timestamp: Option<i64>,
+    ) -> Result<Vec<ChannelForwardingEarnings>, Self::Error>;
+
+    /// Gets the payments, the channels openings and closings ordered by their time, the most recent first.
+    /// An item is identified by the hex of its payment hash, the channel's funding tx hash for an opening,
+    /// or the channel's uuid without hyphens for a closing, the starting record to list from is specified
+    /// by this id in the paging parameter.
+    async fn get_history(
+        &self,
+        paging: PagingOptionsEnum<String>,
+        limit: usize,
+    ) -> Result<GetHistoryResult, Self::Error>;
 }
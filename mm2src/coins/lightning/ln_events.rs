@@ -75,15 +75,9 @@ impl EventHandler for LightningEventHandler {
 
             Event::SpendableOutputs { outputs } => self.handle_spendable_outputs(outputs),
 
-            // Todo: an RPC for total amount earned
-            Event::PaymentForwarded { fee_earned_msat, claim_from_onchain_tx,  prev_channel_id, next_channel_id} => info!(
-                "Received a fee of {} milli-satoshis for a successfully forwarded payment from {} to {} through our {} lightning node. Was the forwarded HTLC claimed by our counterparty via an on-chain transaction?: {}",
-                fee_earned_msat.unwrap_or_default(),
-                prev_channel_id.map(hex::encode).unwrap_or_else(|| "unknown".into()),
-                next_channel_id.map(hex::encode).unwrap_or_else(|| "unknown".into()),
-                self.platform.coin.ticker(),
-                claim_from_onchain_tx,
-            ),
+            Event::PaymentForwarded { fee_earned_msat, claim_from_onchain_tx,  prev_channel_id, next_channel_id} => {
+                self.handle_payment_forwarded(prev_channel_id, next_channel_id, fee_earned_msat, claim_from_onchain_tx)
+            },
 
             Event::ChannelClosed {
                 channel_id,
@@ -497,6 +491,51 @@ impl LightningEventHandler {
         self.platform.spawner().spawn_with_settings(fut, settings);
     }
 
+    fn handle_payment_forwarded(
+        &self,
+        prev_channel_id: Option<[u8; 32]>,
+        next_channel_id: Option<[u8; 32]>,
+        fee_earned_msat: Option<u64>,
+        claim_from_onchain_tx: bool,
+    ) {
+        info!(
+            "Received a fee of {} milli-satoshis for a successfully forwarded payment from {} to {} through our {} lightning node. Was the forwarded HTLC claimed by our counterparty via an on-chain transaction?: {}",
+            fee_earned_msat.unwrap_or_default(),
+            prev_channel_id.map(hex::encode).unwrap_or_else(|| "unknown".into()),
+            next_channel_id.map(hex::encode).unwrap_or_else(|| "unknown".into()),
+            self.platform.coin.ticker(),
+            claim_from_onchain_tx,
+        );
+        // The fee is charged according to the forwarding config of the outbound channel.
+        let amount_forwarded_msat = next_channel_id.zip(fee_earned_msat).and_then(|(next_channel_id, fee_msat)| {
+            let channel = self
+                .channel_manager
+                .list_channels()
+                .into_iter()
+                .find(|channel| channel.channel_id == next_channel_id)?;
+            let config = channel.config?;
+            amount_forwarded_msat(
+                fee_msat,
+                config.forwarding_fee_base_msat,
+                config.forwarding_fee_proportional_millionths,
+            )
+        });
+        let db = self.db.clone();
+        let fut = async move {
+            db.add_forwarded_payment_to_db(
+                prev_channel_id,
+                next_channel_id,
+                fee_earned_msat,
+                amount_forwarded_msat,
+                claim_from_onchain_tx,
+            )
+            .await
+            .error_log_with_msg("Unable to add forwarded payment to DB!");
+        };
+        let settings = AbortSettings::default().critical_timout_s(CRITICAL_FUTURE_TIMEOUT);
+        self.platform.spawner().spawn_with_settings(fut, settings);
+    }
+
     fn handle_pending_htlcs_forwards(&self, time_forwardable: Duration) {
         info!("Handling PendingHTLCsForwardable event!");
         let min_wait_time = time_forwardable.as_millis() as u64;
@@ -681,3 +720,35 @@ impl LightningEventHandler {
         self.platform.spawner().spawn(fut);
     }
 }
+
+/// `Event::PaymentForwarded` of rust-lightning 0.0.113 doesn't contain the forwarded amount,
+/// so it's restored from the earned fee and the forwarding fees of the outbound channel.
+/// The senders pay exactly `fee_base_msat + amount_msat * fee_proportional_millionths / 1_000_000`,
+/// so the smallest amount matching the proportional part of the fee is returned.
+/// The amount can't be restored if the proportional fee is not charged.
+fn amount_forwarded_msat(fee_earned_msat: u64, fee_base_msat: u32, fee_proportional_millionths: u32) -> Option<u64> {
+    if fee_proportional_millionths == 0 {
+        return None;
+    }
+    let proportional_fee_msat = fee_earned_msat.checked_sub(fee_base_msat as u64)? as u128;
+    let millionths = fee_proportional_millionths as u128;
+    let amount_msat = (proportional_fee_msat * 1_000_000 + millionths - 1) / millionths;
+    u64::try_from(amount_msat).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_forwarded_msat() {
+        // 1000 msat base fee and 0.01% proportional fee of 100_000_000 msat.
+        // The proportional fee of up to 100_009_999 msat is rounded down to the same value.
+        assert_eq!(amount_forwarded_msat(11_000, 1000, 100), Some(100_000_000));
+        assert_eq!(amount_forwarded_msat(11_001, 1000, 100), Some(100_010_000));
+        assert_eq!(amount_forwarded_msat(1000, 1000, 100), Some(0));
+        assert_eq!(amount_forwarded_msat(999, 1000, 100), None);
+        assert_eq!(amount_forwarded_msat(11_000, 1000, 0), None);
+        assert_eq!(amount_forwarded_msat(u64::MAX, 0, 1), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::lightning::ln_channel_backup::{encrypt_channel_backup, ChannelBackup, ChannelBackupKey, StaticChannelBackup};
 use crate::lightning::ln_storage::{LightningStorage, NetworkGraph, NodesAddressesMap, NodesAddressesMapShared, Scorer,
                                    TrustedNodesShared};
 use async_trait::async_trait;
@@ -10,7 +11,7 @@ use lightning::chain::channelmonitor::ChannelMonitor;
 use lightning::chain::keysinterface::{KeysInterface, Sign};
 use lightning::routing::scoring::{ProbabilisticScorer, ProbabilisticScoringParameters};
 use lightning::util::persist::KVStorePersister;
-use lightning::util::ser::{ReadableArgs, Writeable};
+use lightning::util::ser::{ReadableArgs, Writeable, Writer};
 use mm2_io::fs::{check_dir_operations, invalid_data_err, read_json, write_json};
 use secp256k1v24::PublicKey;
 use std::collections::{HashMap, HashSet};
@@ -32,13 +33,20 @@ const USE_TMP_FILE: bool = true;
 pub struct LightningFilesystemPersister {
     main_path: PathBuf,
     backup_path: Option<PathBuf>,
+    channel_backup_key: ChannelBackupKey,
 }
 
 impl LightningFilesystemPersister {
     /// Initialize a new LightningPersister and set the path to the individual channels'
     /// files.
     #[inline]
-    pub fn new(main_path: PathBuf, backup_path: Option<PathBuf>) -> Self { Self { main_path, backup_path } }
+    pub fn new(main_path: PathBuf, backup_path: Option<PathBuf>, channel_backup_key: ChannelBackupKey) -> Self {
+        Self {
+            main_path,
+            backup_path,
+            channel_backup_key,
+        }
+    }
 
     /// Get the directory which was provided when this persister was initialized.
     #[inline]
@@ -61,6 +69,22 @@ impl LightningFilesystemPersister {
         })
     }
 
+    pub fn channel_backup_path(&self) -> PathBuf {
+        let mut path = self.main_path();
+        path.push("channel_backup");
+        path
+    }
+
+    pub fn channel_backup_backup_path(&self) -> Option<PathBuf> {
+        self.backup_path().map(|mut backup_path| {
+            backup_path.push("channel_backup");
+            backup_path
+        })
+    }
+
+    #[inline]
+    pub fn channel_backup_key(&self) -> &ChannelBackupKey { &self.channel_backup_key }
+
     pub fn network_graph_path(&self) -> PathBuf {
         let mut path = self.main_path();
         path.push("network_graph");
@@ -155,6 +179,65 @@ impl LightningFilesystemPersister {
         }
         Ok(res)
     }
+
+    /// Reads the channel monitors and the channels nodes addresses from disk without deserializing the monitors.
+    pub fn read_static_channel_backup(&self) -> Result<StaticChannelBackup, std::io::Error> {
+        let nodes_addresses_path = self.nodes_addresses_path();
+        let peers = if nodes_addresses_path.exists() {
+            let contents = fs::read(nodes_addresses_path)?;
+            serde_json::from_slice(&contents).map_err(|e| invalid_data_err("Error", e))?
+        } else {
+            HashMap::new()
+        };
+
+        let mut channels = Vec::new();
+        let path = self.monitors_path();
+        if path.exists() {
+            for file_option in fs::read_dir(path)? {
+                let file = file_option?;
+                let owned_file_name = file.file_name();
+                let filename = owned_file_name.to_str().ok_or_else(|| {
+                    invalid_data_err("Invalid ChannelMonitor file name", format!("{:?}", owned_file_name))
+                })?;
+                if filename == "checkval" || filename.ends_with(".tmp") {
+                    continue;
+                }
+                if !filename.is_ascii() || filename.len() < 65 {
+                    return Err(invalid_data_err("Invalid ChannelMonitor file name", filename));
+                }
+                let (txid, index) = filename.split_at(64);
+                channels.push(ChannelBackup {
+                    funding_txo: format!("{}:{}", txid, &index[1..]),
+                    monitor: fs::read(file.path())?.into(),
+                });
+            }
+        }
+
+        Ok(StaticChannelBackup::new(peers, channels))
+    }
+
+    /// Returns the encrypted static channel backup built from the current channels state.
+    pub fn export_static_channel_backup(&self) -> Result<Vec<u8>, std::io::Error> {
+        let backup = self.read_static_channel_backup()?;
+        encrypt_channel_backup(&self.channel_backup_key, &backup)
+    }
+
+    /// Rewrites the encrypted static channel backup in the main directory and in the backup directory if it's provided.
+    fn persist_static_channel_backup(&self) -> Result<(), std::io::Error> {
+        let encrypted = RawBytes(self.export_static_channel_backup()?);
+        write_to_file(self.channel_backup_path(), &encrypted)?;
+        if let Some(path) = self.channel_backup_backup_path() {
+            write_to_file(path, &encrypted)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the bytes as they are, unlike `Vec<u8>` which is written with its length prefix.
+struct RawBytes(Vec<u8>);
+
+impl Writeable for RawBytes {
+    fn write<W: Writer>(&self, writer: &mut W) -> Result<(), std::io::Error> { writer.write_all(&self.0) }
 }
 
 impl KVStorePersister for LightningFilesystemPersister {
@@ -172,6 +255,11 @@ impl KVStorePersister for LightningFilesystemPersister {
             }
         }
 
+        // Channel monitors are persisted with the `monitors/{funding_txid}_{funding_index}` keys.
+        if key.starts_with("monitors/") {
+            self.persist_static_channel_backup()?;
+        }
+
         Ok(())
     }
 }
@@ -333,7 +421,7 @@ impl LightningStorage for LightningFilesystemPersister {
                 .map_err(|e| invalid_data_err("Error", e))?;
         }
 
-        Ok(())
+        self.persist_static_channel_backup()
     }
 
     async fn get_network_graph(&self, network: Network, logger: Arc<LogState>) -> Result<NetworkGraph, Self::Error> {
@@ -1,8 +1,9 @@
 #![allow(deprecated)] // TODO: remove this once rusqlite is >= 0.29
 
-use crate::lightning::ln_db::{ChannelType, ChannelVisibility, ClosedChannelsFilter, DBChannelDetails,
-                              DBPaymentsFilter, GetClosedChannelsResult, GetPaymentsResult, HTLCStatus, LightningDB,
-                              PaymentInfo, PaymentType};
+use crate::lightning::ln_db::{ChannelForwardingEarnings, ChannelType, ChannelVisibility, ClosedChannelsFilter,
+                              DBChannelDetails, DBForwardedPayment, DBHistoryItem, DBPaymentsFilter,
+                              ForwardedPaymentsFilter, GetClosedChannelsResult, GetForwardedPaymentsResult,
+                              GetHistoryResult, GetPaymentsResult, HTLCStatus, LightningDB, PaymentInfo, PaymentType};
 use async_trait::async_trait;
 use common::{async_blocking, now_sec_i64, PagingOptionsEnum};
 use db_common::owned_named_params;
@@ -18,10 +19,16 @@ use std::convert::TryInto;
 use std::str::FromStr;
 use uuid::Uuid;
 
+const HISTORY_PAYMENT: &str = "payment";
+const HISTORY_CHANNEL_OPENED: &str = "channel_opened";
+const HISTORY_CHANNEL_CLOSED: &str = "channel_closed";
+
 fn channels_history_table(ticker: &str) -> String { ticker.to_owned() + "_channels_history" }
 
 fn payments_history_table(ticker: &str) -> String { ticker.to_owned() + "_payments_history" }
 
+fn forwarded_payments_table(ticker: &str) -> String { ticker.to_owned() + "_forwarded_payments" }
+
 fn create_channels_history_table_sql(for_coin: &str) -> Result<String, SqlError> {
     let table_name = channels_history_table(for_coin);
     validate_table_name(&table_name)?;
@@ -75,6 +82,26 @@ fn create_payments_history_table_sql(for_coin: &str) -> Result<String, SqlError>
     Ok(sql)
 }
 
+fn create_forwarded_payments_table_sql(for_coin: &str) -> Result<String, SqlError> {
+    let table_name = forwarded_payments_table(for_coin);
+    validate_table_name(&table_name)?;
+
+    let sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            id INTEGER NOT NULL PRIMARY KEY,
+            prev_channel_id VARCHAR(255),
+            next_channel_id VARCHAR(255),
+            fee_earned_msat INTEGER,
+            amount_forwarded_msat INTEGER,
+            claim_from_onchain_tx INTEGER NOT NULL,
+            forwarded_at INTEGER NOT NULL
+        );",
+        table_name
+    );
+
+    Ok(sql)
+}
+
 fn insert_channel_sql(
     for_coin: &str,
     channel_detail: &DBChannelDetails,
@@ -352,6 +379,40 @@ fn payment_info_from_row(row: &Row<'_>) -> Result<PaymentInfo, SqlError> {
     Ok(payment_info)
 }
 
+fn insert_forwarded_payment_sql(for_coin: &str) -> Result<String, SqlError> {
+    let table_name = forwarded_payments_table(for_coin);
+    validate_table_name(&table_name)?;
+
+    let sql = format!(
+        "INSERT INTO {} (
+            prev_channel_id,
+            next_channel_id,
+            fee_earned_msat,
+            amount_forwarded_msat,
+            claim_from_onchain_tx,
+            forwarded_at
+        ) VALUES (
+            ?1, ?2, ?3, ?4, ?5, ?6
+        );",
+        table_name
+    );
+
+    Ok(sql)
+}
+
+fn forwarded_payment_from_row(row: &Row<'_>) -> Result<DBForwardedPayment, SqlError> {
+    let forwarded_payment = DBForwardedPayment {
+        id: row.get(0)?,
+        prev_channel_id: row.get(1)?,
+        next_channel_id: row.get(2)?,
+        fee_earned_msat: row.get(3)?,
+        amount_forwarded_msat: row.get(4)?,
+        claim_from_onchain_tx: row.get(5)?,
+        forwarded_at: row.get(6)?,
+    };
+    Ok(forwarded_payment)
+}
+
 fn update_funding_tx_sql(for_coin: &str) -> Result<String, SqlError> {
     let table_name = channels_history_table(for_coin);
     validate_table_name(&table_name)?;
@@ -590,6 +651,105 @@ fn apply_get_payments_filter<'a>(
     }
 }
 
+fn get_forwarded_payments_builder_preimage(for_coin: &str) -> Result<SqlBuilder, SqlError> {
+    let table_name = forwarded_payments_table(for_coin);
+    validate_table_name(&table_name)?;
+
+    Ok(SqlBuilder::select_from(table_name))
+}
+
+fn finalize_get_forwarded_payments_sql_builder(sql_builder: &mut SqlBuilder, offset: usize, limit: usize) {
+    sql_builder
+        .field("id")
+        .field("prev_channel_id")
+        .field("next_channel_id")
+        .field("fee_earned_msat")
+        .field("amount_forwarded_msat")
+        .field("claim_from_onchain_tx")
+        .field("forwarded_at");
+    sql_builder.offset(offset);
+    sql_builder.limit(limit);
+    sql_builder.order_desc("id");
+}
+
+fn apply_get_forwarded_payments_filter<'a>(
+    builder: &mut SqlBuilder,
+    params: &mut SqlNamedParams<'a>,
+    filter: &'a ForwardedPaymentsFilter,
+) {
+    if let Some(channel_id) = &filter.channel_id {
+        builder.and_where("(prev_channel_id = :channel_id OR next_channel_id = :channel_id)");
+        params.push((":channel_id", channel_id));
+    }
+
+    if let Some(from_time) = &filter.from_timestamp {
+        builder.and_where("forwarded_at >= :from_time");
+        params.push((":from_time", from_time));
+    }
+
+    if let Some(to_time) = &filter.to_timestamp {
+        builder.and_where("forwarded_at <= :to_time");
+        params.push((":to_time", to_time));
+    }
+}
+
+/// Counts every forwarded payment for both its incoming and outgoing channels,
+/// the forwarded amount and the earned fee are counted for the outgoing channel only.
+fn get_forwarding_earnings_sql(
+    for_coin: &str,
+    from_timestamp: Option<i64>,
+    to_timestamp: Option<i64>,
+) -> Result<String, SqlError> {
+    let table_name = forwarded_payments_table(for_coin);
+    validate_table_name(&table_name)?;
+
+    let mut time_range = String::new();
+    if from_timestamp.is_some() {
+        time_range.push_str(" AND forwarded_at >= :from_time");
+    }
+    if to_timestamp.is_some() {
+        time_range.push_str(" AND forwarded_at <= :to_time");
+    }
+
+    let sql = format!(
+        "SELECT channel_id, SUM(forwarded_in), SUM(forwarded_out), SUM(amount_forwarded_msat), SUM(fee_earned_msat) FROM (
+            SELECT prev_channel_id AS channel_id, 1 AS forwarded_in, 0 AS forwarded_out, 0 AS amount_forwarded_msat,
+            0 AS fee_earned_msat FROM {table} WHERE prev_channel_id IS NOT NULL{time_range}
+            UNION ALL
+            SELECT next_channel_id, 0, 1, COALESCE(amount_forwarded_msat, 0), COALESCE(fee_earned_msat, 0)
+            FROM {table} WHERE next_channel_id IS NOT NULL{time_range}
+        ) GROUP BY channel_id ORDER BY SUM(fee_earned_msat) DESC, channel_id;",
+        table = table_name,
+        time_range = time_range,
+    );
+
+    Ok(sql)
+}
+
+/// Selects the ids, the kinds, the references to the payments or channels records, and the timestamps
+/// of all the history items.
+fn get_history_builder_preimage(for_coin: &str) -> Result<SqlBuilder, SqlError> {
+    let channels_table = channels_history_table(for_coin);
+    validate_table_name(&channels_table)?;
+    let payments_table = payments_history_table(for_coin);
+    validate_table_name(&payments_table)?;
+
+    let history = format!(
+        "(SELECT payment_hash AS id, '{payment}' AS kind, payment_hash AS item_ref, created_at AS timestamp FROM {payments}
+        UNION ALL
+        SELECT funding_tx, '{opened}', uuid, created_at FROM {channels} WHERE funding_tx IS NOT NULL
+        UNION ALL
+        SELECT REPLACE(uuid, '-', ''), '{closed}', uuid, closed_at FROM {channels} WHERE is_closed = 1) AS history",
+        payment = HISTORY_PAYMENT,
+        opened = HISTORY_CHANNEL_OPENED,
+        closed = HISTORY_CHANNEL_CLOSED,
+        payments = payments_table,
+        channels = channels_table,
+    );
+
+    Ok(SqlBuilder::select_from(history))
+}
+
 fn update_claiming_tx_sql(for_coin: &str) -> Result<String, SqlError> {
     let table_name = channels_history_table(for_coin);
     validate_table_name(&table_name)?;
@@ -626,10 +786,12 @@ impl LightningDB for SqliteLightningDB {
 
         let sql_channels_history = create_channels_history_table_sql(self.db_ticker.as_str())?;
         let sql_payments_history = create_payments_history_table_sql(self.db_ticker.as_str())?;
+        let sql_forwarded_payments = create_forwarded_payments_table_sql(self.db_ticker.as_str())?;
         async_blocking(move || {
             let conn = sqlite_connection.lock().unwrap();
             conn.execute(&sql_channels_history, []).map(|_| ())?;
             conn.execute(&sql_payments_history, []).map(|_| ())?;
+            conn.execute(&sql_forwarded_payments, []).map(|_| ())?;
             Ok(())
         })
         .await
@@ -640,6 +802,8 @@ impl LightningDB for SqliteLightningDB {
         validate_table_name(&channels_history_table)?;
         let payments_history_table = payments_history_table(self.db_ticker.as_str());
         validate_table_name(&payments_history_table)?;
+        let forwarded_payments_table = forwarded_payments_table(self.db_ticker.as_str());
+        validate_table_name(&forwarded_payments_table)?;
 
         let sqlite_connection = self.sqlite_connection.clone();
         async_blocking(move || {
@@ -648,7 +812,15 @@ impl LightningDB for SqliteLightningDB {
                 query_single_row(&conn, CHECK_TABLE_EXISTS_SQL, [channels_history_table], string_from_row)?;
             let payments_history_initialized =
                 query_single_row(&conn, CHECK_TABLE_EXISTS_SQL, [payments_history_table], string_from_row)?;
-            Ok(channels_history_initialized.is_some() && payments_history_initialized.is_some())
+            let forwarded_payments_initialized = query_single_row(
+                &conn,
+                CHECK_TABLE_EXISTS_SQL,
+                [forwarded_payments_table],
+                string_from_row,
+            )?;
+            Ok(channels_history_initialized.is_some()
+                && payments_history_initialized.is_some()
+                && forwarded_payments_initialized.is_some())
         })
         .await
     }
@@ -1040,6 +1212,206 @@ impl LightningDB for SqliteLightningDB {
         })
         .await
     }
+
+    async fn add_forwarded_payment_to_db(
+        &self,
+        prev_channel_id: Option<[u8; 32]>,
+        next_channel_id: Option<[u8; 32]>,
+        fee_earned_msat: Option<u64>,
+        amount_forwarded_msat: Option<u64>,
+        claim_from_onchain_tx: bool,
+    ) -> Result<(), Self::Error> {
+        let for_coin = self.db_ticker.clone();
+        let prev_channel_id = prev_channel_id.map(hex::encode);
+        let next_channel_id = next_channel_id.map(hex::encode);
+        let fee_earned_msat = fee_earned_msat.map(|fee| fee as i64);
+        let amount_forwarded_msat = amount_forwarded_msat.map(|amount| amount as i64);
+        let forwarded_at = now_sec_i64();
+
+        let sqlite_connection = self.sqlite_connection.clone();
+        async_blocking(move || {
+            let params = params![
+                prev_channel_id,
+                next_channel_id,
+                fee_earned_msat,
+                amount_forwarded_msat,
+                claim_from_onchain_tx,
+                forwarded_at
+            ];
+            let conn = sqlite_connection.lock().unwrap();
+            conn.execute(&insert_forwarded_payment_sql(&for_coin)?, params)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_forwarded_payments_by_filter(
+        &self,
+        filter: Option<ForwardedPaymentsFilter>,
+        paging: PagingOptionsEnum<i64>,
+        limit: usize,
+    ) -> Result<GetForwardedPaymentsResult, Self::Error> {
+        let mut sql_builder = get_forwarded_payments_builder_preimage(self.db_ticker.as_str())?;
+
+        let sqlite_connection = self.sqlite_connection.clone();
+        async_blocking(move || {
+            let conn = sqlite_connection.lock().unwrap();
+
+            let mut total_builder = sql_builder.clone();
+            total_builder.count("id");
+            let total_sql = total_builder.sql().expect("valid sql");
+            let total: isize = conn.query_row(&total_sql, [], |row| row.get(0))?;
+            let total = total.try_into().expect("count should be always above zero");
+
+            let offset = match paging {
+                PagingOptionsEnum::PageNumber(page) => (page.get() - 1) * limit,
+                PagingOptionsEnum::FromId(id) => {
+                    let params = [id];
+                    let maybe_offset = offset_by_id(&conn, &sql_builder, params, "id", "id DESC", "id = ?1")?;
+                    match maybe_offset {
+                        Some(offset) => offset,
+                        None => {
+                            return Ok(GetForwardedPaymentsResult {
+                                forwarded_payments: vec![],
+                                skipped: 0,
+                                total,
+                            })
+                        },
+                    }
+                },
+            };
+
+            let mut params = vec![];
+            if let Some(f) = &filter {
+                apply_get_forwarded_payments_filter(&mut sql_builder, &mut params, f);
+            }
+            let params_as_trait: Vec<_> = params.iter().map(|(key, value)| (*key, value as &dyn ToSql)).collect();
+            finalize_get_forwarded_payments_sql_builder(&mut sql_builder, offset, limit);
+
+            let sql = sql_builder.sql().expect("valid sql");
+            let mut stmt = conn.prepare(&sql)?;
+            let forwarded_payments = stmt
+                .query_map_named(params_as_trait.as_slice(), forwarded_payment_from_row)?
+                .collect::<Result<_, _>>()?;
+            let result = GetForwardedPaymentsResult {
+                forwarded_payments,
+                skipped: offset,
+                total,
+            };
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn get_forwarding_earnings(
+        &self,
+        from_timestamp: Option<i64>,
+        to_timestamp: Option<i64>,
+    ) -> Result<Vec<ChannelForwardingEarnings>, Self::Error> {
+        let sql = get_forwarding_earnings_sql(self.db_ticker.as_str(), from_timestamp, to_timestamp)?;
+
+        let sqlite_connection = self.sqlite_connection.clone();
+        async_blocking(move || {
+            let mut params: SqlNamedParams = vec![];
+            if let Some(from_time) = &from_timestamp {
+                params.push((":from_time", from_time));
+            }
+            if let Some(to_time) = &to_timestamp {
+                params.push((":to_time", to_time));
+            }
+
+            let conn = sqlite_connection.lock().unwrap();
+            let mut stmt = conn.prepare(&sql)?;
+            let earnings = stmt
+                .query_map_named(params.as_slice(), |row| {
+                    let forwarded_in: i64 = row.get(1)?;
+                    let forwarded_out: i64 = row.get(2)?;
+                    Ok(ChannelForwardingEarnings {
+                        channel_id: row.get(0)?,
+                        forwarded_in: forwarded_in as u64,
+                        forwarded_out: forwarded_out as u64,
+                        amount_forwarded_out_msat: row.get(3)?,
+                        fee_earned_msat: row.get(4)?,
+                    })
+                })?
+                .collect::<Result<_, _>>()?;
+            Ok(earnings)
+        })
+        .await
+    }
+
+    async fn get_history(
+        &self,
+        paging: PagingOptionsEnum<String>,
+        limit: usize,
+    ) -> Result<GetHistoryResult, Self::Error> {
+        let mut sql_builder = get_history_builder_preimage(self.db_ticker.as_str())?;
+        let select_payment_sql = select_payment_by_hash_sql(self.db_ticker.as_str())?;
+        let select_channel_sql = select_channel_by_uuid_sql(self.db_ticker.as_str())?;
+
+        let sqlite_connection = self.sqlite_connection.clone();
+        async_blocking(move || {
+            let conn = sqlite_connection.lock().unwrap();
+
+            let mut total_builder = sql_builder.clone();
+            total_builder.count("id");
+            let total_sql = total_builder.sql().expect("valid sql");
+            let total: isize = conn.query_row(&total_sql, [], |row| row.get(0))?;
+            let total = total.try_into().expect("count should be always above zero");
+
+            let offset = match paging {
+                PagingOptionsEnum::PageNumber(page) => (page.get() - 1) * limit,
+                PagingOptionsEnum::FromId(id) => {
+                    let params = [id];
+                    let maybe_offset = offset_by_id(&conn, &sql_builder, params, "id", "timestamp DESC", "id = ?1")?;
+                    match maybe_offset {
+                        Some(offset) => offset,
+                        None => {
+                            return Ok(GetHistoryResult {
+                                items: vec![],
+                                skipped: 0,
+                                total,
+                            })
+                        },
+                    }
+                },
+            };
+
+            sql_builder.field("kind").field("item_ref");
+            sql_builder.offset(offset);
+            sql_builder.limit(limit);
+            sql_builder.order_desc("timestamp");
+
+            let sql = sql_builder.sql().expect("valid sql");
+            let mut stmt = conn.prepare(&sql)?;
+            let item_refs: Vec<(String, String)> = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+
+            let mut items = Vec::with_capacity(item_refs.len());
+            for (kind, item_ref) in item_refs {
+                let item = match kind.as_str() {
+                    HISTORY_PAYMENT => query_single_row(&conn, &select_payment_sql, [item_ref], payment_info_from_row)?
+                        .map(DBHistoryItem::Payment),
+                    HISTORY_CHANNEL_OPENED => {
+                        query_single_row(&conn, &select_channel_sql, [item_ref], channel_details_from_row)?
+                            .map(DBHistoryItem::ChannelOpened)
+                    },
+                    _ => query_single_row(&conn, &select_channel_sql, [item_ref], channel_details_from_row)?
+                        .map(DBHistoryItem::ChannelClosed),
+                };
+                items.extend(item);
+            }
+
+            let result = GetHistoryResult {
+                items,
+                skipped: offset,
+                total,
+            };
+            Ok(result)
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -1609,4 +1981,124 @@ mod tests {
 
         assert_eq!(expected_channels, actual_channels);
     }
+
+    #[test]
+    fn test_get_history() {
+        let db = SqliteLightningDB::new(
+            "test_get_history".into(),
+            Arc::new(Mutex::new(Connection::open_in_memory().unwrap())),
+        );
+
+        block_on(db.init_db()).unwrap();
+
+        let mut payments = generate_random_payments(3);
+        for (i, payment) in payments.iter_mut().enumerate() {
+            payment.created_at = 100 + i as i64 * 10;
+            block_on(db.add_payment_to_db(payment)).unwrap();
+        }
+
+        let mut channels = generate_random_channels(2);
+        for (i, channel) in channels.iter_mut().enumerate() {
+            channel.created_at = 105 + i as i64 * 10;
+            channel.is_closed = false;
+            block_on(db.add_channel_to_db(channel)).unwrap();
+            block_on(db.add_funding_tx_to_db(
+                channel.uuid,
+                channel.funding_tx.clone().unwrap(),
+                channel.funding_value.unwrap(),
+                channel.funding_generated_in_block.unwrap(),
+            ))
+            .unwrap();
+        }
+        // Only the first channel is closed, and it's the most recent event.
+        block_on(db.update_channel_to_closed(channels[0].uuid, "reason".into(), 200)).unwrap();
+
+        let result = block_on(db.get_history(PagingOptionsEnum::default(), 10)).unwrap();
+        assert_eq!(6, result.total);
+        assert_eq!(0, result.skipped);
+
+        let closed_channel = block_on(db.get_channel_from_db(channels[0].uuid)).unwrap().unwrap();
+        let opened_channel = block_on(db.get_channel_from_db(channels[1].uuid)).unwrap().unwrap();
+        let expected = vec![
+            DBHistoryItem::ChannelClosed(closed_channel.clone()),
+            DBHistoryItem::Payment(payments[2].clone()),
+            DBHistoryItem::ChannelOpened(opened_channel),
+            DBHistoryItem::Payment(payments[1].clone()),
+            DBHistoryItem::ChannelOpened(closed_channel),
+            DBHistoryItem::Payment(payments[0].clone()),
+        ];
+        assert_eq!(expected, result.items);
+
+        let from_id = hex::encode(payments[1].payment_hash.0);
+        let result = block_on(db.get_history(PagingOptionsEnum::FromId(from_id), 1)).unwrap();
+        assert_eq!(4, result.skipped);
+        assert_eq!(expected[4..5].to_vec(), result.items);
+    }
+
+    #[test]
+    fn test_forwarded_payments_and_earnings() {
+        let db = SqliteLightningDB::new(
+            "test_forwarded_payments".into(),
+            Arc::new(Mutex::new(Connection::open_in_memory().unwrap())),
+        );
+
+        block_on(db.init_db()).unwrap();
+
+        block_on(db.add_forwarded_payment_to_db(Some([1; 32]), Some([2; 32]), Some(1000), None, false)).unwrap();
+        block_on(db.add_forwarded_payment_to_db(Some([2; 32]), Some([3; 32]), Some(500), Some(100000), false)).unwrap();
+        block_on(db.add_forwarded_payment_to_db(Some([1; 32]), Some([3; 32]), None, None, true)).unwrap();
+
+        let result = block_on(db.get_forwarded_payments_by_filter(None, PagingOptionsEnum::default(), 10)).unwrap();
+        assert_eq!(3, result.total);
+        let ids: Vec<_> = result.forwarded_payments.iter().map(|payment| payment.id).collect();
+        assert_eq!(vec![3, 2, 1], ids);
+        assert_eq!(Some(hex::encode([1; 32])), result.forwarded_payments[0].prev_channel_id);
+        assert!(result.forwarded_payments[0].claim_from_onchain_tx);
+        assert_eq!(Some(100000), result.forwarded_payments[1].amount_forwarded_msat);
+
+        let filter = ForwardedPaymentsFilter {
+            channel_id: Some(hex::encode([3; 32])),
+            from_timestamp: None,
+            to_timestamp: None,
+        };
+        let result =
+            block_on(db.get_forwarded_payments_by_filter(Some(filter), PagingOptionsEnum::default(), 10)).unwrap();
+        let ids: Vec<_> = result.forwarded_payments.iter().map(|payment| payment.id).collect();
+        assert_eq!(vec![3, 2], ids);
+
+        let result = block_on(db.get_forwarded_payments_by_filter(None, PagingOptionsEnum::FromId(3), 10)).unwrap();
+        assert_eq!(1, result.skipped);
+        let ids: Vec<_> = result.forwarded_payments.iter().map(|payment| payment.id).collect();
+        assert_eq!(vec![2, 1], ids);
+
+        let earnings = block_on(db.get_forwarding_earnings(None, None)).unwrap();
+        let expected = vec![
+            ChannelForwardingEarnings {
+                channel_id: hex::encode([2; 32]),
+                forwarded_in: 1,
+                forwarded_out: 1,
+                amount_forwarded_out_msat: 0,
+                fee_earned_msat: 1000,
+            },
+            ChannelForwardingEarnings {
+                channel_id: hex::encode([3; 32]),
+                forwarded_in: 0,
+                forwarded_out: 2,
+                amount_forwarded_out_msat: 100000,
+                fee_earned_msat: 500,
+            },
+            ChannelForwardingEarnings {
+                channel_id: hex::encode([1; 32]),
+                forwarded_in: 2,
+                forwarded_out: 0,
+                amount_forwarded_out_msat: 0,
+                fee_earned_msat: 0,
+            },
+        ];
+        assert_eq!(expected, earnings);
+
+        let from_timestamp = now_sec_i64() + 1000;
+        let earnings = block_on(db.get_forwarding_earnings(Some(from_timestamp), None)).unwrap();
+        assert!(earnings.is_empty());
+    }
 }
@@ -0,0 +1,196 @@
+//! Presents the lightning payments and the channels openings and closings as `TransactionDetails`
+//! for `my_tx_history` v2. The items are read directly from the lightning DB since they are already saved there
+//! by the events handler, so there is no history storage to sync for `LightningCoin`.
+
+use super::ln_db::{DBChannelDetails, DBHistoryItem, HTLCStatus, LightningDB, PaymentInfo, PaymentType};
+use super::LightningCoin;
+use crate::my_tx_history_v2::{MyTxHistoryDetails, MyTxHistoryErrorV2, MyTxHistoryRequestV2, MyTxHistoryResponseV2,
+                              MyTxHistoryTarget};
+use crate::utxo::utxo_common::big_decimal_from_sat;
+use crate::utxo::UtxoFeeDetails;
+use crate::{HistorySyncState, MarketCoinOps, MmCoin, TransactionDetails, TransactionType, TxFeeDetails};
+use common::{calc_total_pages, PagingOptionsEnum};
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use rpc::v1::types::Bytes as BytesJson;
+
+impl LightningCoin {
+    pub(crate) async fn tx_history(
+        &self,
+        request: MyTxHistoryRequestV2<BytesJson>,
+    ) -> Result<MyTxHistoryResponseV2<MyTxHistoryDetails, BytesJson>, MmError<MyTxHistoryErrorV2>> {
+        if !matches!(request.target, MyTxHistoryTarget::Iguana) {
+            return MmError::err(MyTxHistoryErrorV2::with_expected_target(request.target, "Iguana"));
+        }
+
+        let paging = match &request.paging_options {
+            PagingOptionsEnum::PageNumber(page) => PagingOptionsEnum::PageNumber(*page),
+            PagingOptionsEnum::FromId(id) => PagingOptionsEnum::FromId(hex::encode(&id.0)),
+        };
+        let history = self
+            .db
+            .get_history(paging, request.limit)
+            .await
+            .map_to_mm(|e| MyTxHistoryErrorV2::StorageError(e.to_string()))?;
+
+        let current_block = self.platform.best_block_height();
+        let transactions = history
+            .items
+            .into_iter()
+            .map(|item| {
+                let details = match item {
+                    DBHistoryItem::Payment(payment) => self.payment_tx_details(payment),
+                    DBHistoryItem::ChannelOpened(channel) => self.channel_opened_tx_details(channel),
+                    DBHistoryItem::ChannelClosed(channel) => self.channel_closed_tx_details(channel),
+                };
+                let confirmations = if details.block_height == 0 || details.block_height > current_block {
+                    0
+                } else {
+                    current_block + 1 - details.block_height
+                };
+                MyTxHistoryDetails { details, confirmations }
+            })
+            .collect();
+
+        Ok(MyTxHistoryResponseV2 {
+            coin: self.ticker().into(),
+            target: request.target,
+            current_block,
+            transactions,
+            // Payments and channels are saved to the DB as soon as their events are handled
+            sync_status: HistorySyncState::Finished,
+            limit: request.limit,
+            skipped: history.skipped,
+            total: history.total,
+            total_pages: calc_total_pages(history.total, request.limit),
+            paging_options: request.paging_options,
+        })
+    }
+
+    /// Only succeeded payments change the balance, the payment status is available in the `transaction_type`.
+    fn payment_tx_details(&self, payment: PaymentInfo) -> TransactionDetails {
+        let decimals = self.decimals();
+        let amount = big_decimal_from_sat(payment.amt_msat.unwrap_or_default(), decimals);
+        let fee = payment
+            .fee_paid_msat
+            .map(|fee_msat| big_decimal_from_sat(fee_msat, decimals));
+        let is_succeeded = payment.status == HTLCStatus::Succeeded;
+
+        let (from, to, spent_by_me, received_by_me) = match payment.payment_type {
+            PaymentType::OutboundPayment { destination } => {
+                let spent_by_me = if is_succeeded {
+                    &amount + fee.clone().unwrap_or_default()
+                } else {
+                    BigDecimal::default()
+                };
+                let to = vec![destination.to_string()];
+                (vec![self.my_node_id()], to, spent_by_me, BigDecimal::default())
+            },
+            PaymentType::InboundPayment => {
+                let received_by_me = if is_succeeded {
+                    amount.clone()
+                } else {
+                    BigDecimal::default()
+                };
+                (
+                    Vec::new(),
+                    vec![self.my_node_id()],
+                    BigDecimal::default(),
+                    received_by_me,
+                )
+            },
+        };
+
+        let payment_hash = payment.payment_hash.0.to_vec();
+        TransactionDetails {
+            tx_hex: BytesJson::default(),
+            tx_hash: hex::encode(&payment_hash),
+            from,
+            to,
+            total_amount: amount,
+            my_balance_change: &received_by_me - &spent_by_me,
+            spent_by_me,
+            received_by_me,
+            block_height: 0,
+            timestamp: payment.created_at as u64,
+            fee_details: fee.map(|amount| {
+                TxFeeDetails::Utxo(UtxoFeeDetails {
+                    coin: Some(self.ticker().to_owned()),
+                    amount,
+                })
+            }),
+            coin: self.ticker().to_owned(),
+            internal_id: payment_hash.into(),
+            kmd_rewards: None,
+            transaction_type: TransactionType::LightningPayment { status: payment.status },
+            memo: Some(payment.description).filter(|description| !description.is_empty()),
+        }
+    }
+
+    /// The channel's funding is counted as received to the lightning balance if the channel was opened by us.
+    fn channel_opened_tx_details(&self, channel: DBChannelDetails) -> TransactionDetails {
+        let funding_tx = channel.funding_tx.unwrap_or_default();
+        // funding_value is saved in sats of the platform coin while the lightning coin uses msats
+        let funding_amount = big_decimal_from_sat(channel.funding_value.unwrap_or_default() * 1000, self.decimals());
+        let (from, to, received_by_me) = if channel.is_outbound {
+            (self.my_node_id(), channel.counterparty_node_id, funding_amount.clone())
+        } else {
+            (channel.counterparty_node_id, self.my_node_id(), BigDecimal::default())
+        };
+
+        TransactionDetails {
+            tx_hex: BytesJson::default(),
+            internal_id: hex::decode(&funding_tx).unwrap_or_default().into(),
+            tx_hash: funding_tx,
+            from: vec![from],
+            to: vec![to],
+            total_amount: funding_amount,
+            spent_by_me: BigDecimal::default(),
+            my_balance_change: received_by_me.clone(),
+            received_by_me,
+            block_height: channel.funding_generated_in_block.unwrap_or_default() as u64,
+            timestamp: channel.created_at as u64,
+            fee_details: None,
+            coin: self.ticker().to_owned(),
+            kmd_rewards: None,
+            transaction_type: TransactionType::LightningChannelOpen {
+                channel_id: channel.channel_id,
+            },
+            memo: None,
+        }
+    }
+
+    /// The balance claimed back on-chain after the channel's closing is counted as spent from the lightning balance.
+    fn channel_closed_tx_details(&self, channel: DBChannelDetails) -> TransactionDetails {
+        // claimed_balance is saved in sats of the platform coin while the lightning coin uses msats
+        let claimed_msat = (channel.claimed_balance.unwrap_or_default() * 1000.).round() as i64;
+        let spent_by_me = big_decimal_from_sat(claimed_msat, self.decimals());
+        let (from, to) = if channel.is_outbound {
+            (self.my_node_id(), channel.counterparty_node_id)
+        } else {
+            (channel.counterparty_node_id, self.my_node_id())
+        };
+
+        TransactionDetails {
+            tx_hex: BytesJson::default(),
+            tx_hash: channel.closing_tx.unwrap_or_default(),
+            from: vec![from],
+            to: vec![to],
+            total_amount: spent_by_me.clone(),
+            my_balance_change: -spent_by_me.clone(),
+            spent_by_me,
+            received_by_me: BigDecimal::default(),
+            block_height: 0,
+            timestamp: channel.closed_at.unwrap_or_default() as u64,
+            fee_details: None,
+            coin: self.ticker().to_owned(),
+            internal_id: channel.uuid.as_bytes().to_vec().into(),
+            kmd_rewards: None,
+            transaction_type: TransactionType::LightningChannelClose {
+                channel_id: channel.channel_id,
+                closure_reason: channel.closure_reason,
+            },
+            memo: None,
+        }
+    }
+}
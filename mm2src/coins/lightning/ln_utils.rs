@@ -1,4 +1,5 @@
 use super::*;
+use crate::lightning::ln_channel_backup::channel_backup_key;
 use crate::lightning::ln_db::LightningDB;
 use crate::lightning::ln_platform::{get_best_header, ln_best_block_update_loop, update_best_block};
 use crate::lightning::ln_sql::SqliteLightningDB;
@@ -53,12 +54,18 @@ fn ln_data_backup_dir(ctx: &MmArc, path: Option<String>, ticker: &str) -> Option
 
 pub async fn init_persister(
     ctx: &MmArc,
+    platform: &Platform,
     ticker: String,
     backup_path: Option<String>,
 ) -> EnableLightningResult<Arc<LightningFilesystemPersister>> {
     let ln_data_dir = ln_data_dir(ctx, &ticker);
     let ln_data_backup_dir = ln_data_backup_dir(ctx, backup_path, &ticker);
-    let persister = Arc::new(LightningFilesystemPersister::new(ln_data_dir, ln_data_backup_dir));
+    let channel_backup_key = channel_backup_key(&node_seed(platform)?);
+    let persister = Arc::new(LightningFilesystemPersister::new(
+        ln_data_dir,
+        ln_data_backup_dir,
+        channel_backup_key,
+    ));
 
     let is_initialized = persister.is_fs_initialized().await?;
     if !is_initialized {
@@ -85,17 +92,21 @@ pub async fn init_db(ctx: &MmArc, ticker: String) -> EnableLightningResult<Sqlit
     Ok(db)
 }
 
-pub fn init_keys_manager(platform: &Platform) -> EnableLightningResult<Arc<KeysManager>> {
-    // The current time is used to derive random numbers from the seed where required, to ensure all random generation is unique across restarts.
-    // TODO validate that this is right
-    let seed: [u8; 32] = platform
+fn node_seed(platform: &Platform) -> EnableLightningResult<[u8; 32]> {
+    Ok(platform
         .coin
         .as_ref()
         .priv_key_policy
         .activated_key_or_err()?
         .private()
         .secret
-        .into();
+        .into())
+}
+
+pub fn init_keys_manager(platform: &Platform) -> EnableLightningResult<Arc<KeysManager>> {
+    // The current time is used to derive random numbers from the seed where required, to ensure all random generation is unique across restarts.
+    // TODO validate that this is right
+    let seed = node_seed(platform)?;
     let cur = get_local_duration_since_epoch().map_to_mm(|e| EnableLightningError::SystemTimeError(e.to_string()))?;
 
     Ok(Arc::new(KeysManager::new(&seed, cur.as_secs(), cur.subsec_nanos())))
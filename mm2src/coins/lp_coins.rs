@@ -88,6 +88,7 @@ use zcash_primitives::transaction::Transaction as ZTransaction;
 cfg_native! {
     use crate::lightning::LightningCoin;
     use crate::lightning::ln_conf::PlatformCoinConfirmationTargets;
+    use crate::lightning::ln_db::HTLCStatus;
     use ::lightning::ln::PaymentHash as LightningPayment;
     use async_std::fs;
     use futures::AsyncWriteExt;
@@ -100,6 +101,7 @@ cfg_wasm32! {
     use ethereum_types::{H264 as EthH264, H520 as EthH520};
     use hd_wallet_storage::HDWalletDb;
     use mm2_db::indexed_db::{ConstructibleDb, DbLocked, SharedDb};
+    use tendermint::IBCTransferDb;
     use tx_history_storage::wasm::{clear_tx_history, load_tx_history, save_tx_history, TxHistoryDb};
     pub type TxHistoryDbLocked<'a> = DbLocked<'a, TxHistoryDb>;
 }
@@ -215,8 +217,8 @@ pub mod coins_tests;
 
 pub mod eth;
 use eth::GetValidEthWithdrawAddError;
-use eth::{eth_coin_from_conf_and_request, get_eth_address, EthCoin, EthGasDetailsErr, EthTxFeeDetails,
-          GetEthAddressError, SignedEthTx};
+use eth::{eth_coin_from_conf_and_request, get_eth_address, EthCoin, EthCoinType, EthGasDetailsErr, EthProtocolInfo,
+          EthTxFeeDetails, GetEthAddressError, NftSwapToken, SignedEthTx};
 
 pub mod hd_confirm_address;
 pub mod hd_pubkey;
@@ -233,6 +235,8 @@ pub mod qrc20;
 use qrc20::{qrc20_coin_with_policy, Qrc20ActivationParams, Qrc20Coin, Qrc20FeeDetails};
 
 pub mod rpc_command;
+#[cfg(not(target_arch = "wasm32"))]
+use rpc_command::init_z_coin_rescan::{ZCoinRescanTaskManager, ZCoinRescanTaskManagerShared};
 use rpc_command::{get_new_address::{GetNewAddressTaskManager, GetNewAddressTaskManagerShared},
                   init_account_balance::{AccountBalanceTaskManager, AccountBalanceTaskManagerShared},
                   init_create_account::{CreateAccountTaskManager, CreateAccountTaskManagerShared},
@@ -240,8 +244,9 @@ use rpc_command::{get_new_address::{GetNewAddressTaskManager, GetNewAddressTaskM
                   init_withdraw::{WithdrawTaskManager, WithdrawTaskManagerShared}};
 
 pub mod tendermint;
-use tendermint::{CosmosTransaction, CustomTendermintMsgType, TendermintCoin, TendermintFeeDetails,
-                 TendermintProtocolInfo, TendermintToken, TendermintTokenProtocolInfo};
+use tendermint::{CosmosDelegationRequest, CosmosStakingInfosDetails, CosmosTransaction, CustomTendermintMsgType,
+                 IBCTransferStatus, TendermintCoin, TendermintFeeDetails, TendermintProtocolInfo, TendermintToken,
+                 TendermintTokenProtocolInfo};
 
 #[doc(hidden)]
 #[allow(unused_variables)]
@@ -272,10 +277,11 @@ pub use solana::spl::SplToken;
     not(target_os = "android"),
     not(target_arch = "wasm32")
 ))]
-pub use solana::{SolanaActivationParams, SolanaCoin, SolanaFeeDetails};
+pub use solana::{SolanaActivationParams, SolanaCoin, SolanaFeeDetails, SolanaTransaction};
 
 pub mod utxo;
 use utxo::bch::{bch_coin_with_policy, BchActivationRequest, BchCoin};
+use utxo::cash_token::CashToken;
 use utxo::qtum::{self, qtum_coin_with_policy, Qrc20AddressError, QtumCoin, QtumDelegationOps, QtumDelegationRequest,
                  QtumStakingInfosDetails, ScriptHashTypeNotSupported};
 use utxo::rpc_clients::UtxoRpcError;
@@ -504,6 +510,13 @@ pub enum TransactionEnum {
     CosmosTransaction(CosmosTransaction),
     #[cfg(not(target_arch = "wasm32"))]
     LightningPayment(LightningPayment),
+    #[cfg(all(
+        feature = "enable-solana",
+        not(target_os = "ios"),
+        not(target_os = "android"),
+        not(target_arch = "wasm32")
+    ))]
+    SolanaTransaction(SolanaTransaction),
 }
 
 ifrom!(TransactionEnum, UtxoTx);
@@ -511,6 +524,13 @@ ifrom!(TransactionEnum, SignedEthTx);
 ifrom!(TransactionEnum, ZTransaction);
 #[cfg(not(target_arch = "wasm32"))]
 ifrom!(TransactionEnum, LightningPayment);
+#[cfg(all(
+    feature = "enable-solana",
+    not(target_os = "ios"),
+    not(target_os = "android"),
+    not(target_arch = "wasm32")
+))]
+ifrom!(TransactionEnum, SolanaTransaction);
 
 impl TransactionEnum {
     #[cfg(not(target_arch = "wasm32"))]
@@ -531,6 +551,13 @@ impl Deref for TransactionEnum {
             TransactionEnum::CosmosTransaction(ref t) => t,
             #[cfg(not(target_arch = "wasm32"))]
             TransactionEnum::LightningPayment(ref p) => p,
+            #[cfg(all(
+                feature = "enable-solana",
+                not(target_os = "ios"),
+                not(target_os = "android"),
+                not(target_arch = "wasm32")
+            ))]
+            TransactionEnum::SolanaTransaction(ref t) => t,
         }
     }
 }
@@ -1547,6 +1574,7 @@ pub struct WithdrawRequest {
 #[serde(tag = "type")]
 pub enum StakingDetails {
     Qtum(QtumDelegationRequest),
+    Cosmos(CosmosDelegationRequest),
 }
 
 #[allow(dead_code)]
@@ -1560,6 +1588,9 @@ pub struct AddDelegateRequest {
 #[derive(Deserialize)]
 pub struct RemoveDelegateRequest {
     pub coin: String,
+    /// Required for the coins that can delegate to multiple validators at once, e.g. Cosmos.
+    #[serde(default)]
+    pub staking_details: Option<StakingDetails>,
 }
 
 #[derive(Deserialize)]
@@ -1601,12 +1632,19 @@ impl WithdrawRequest {
 #[serde(tag = "type")]
 pub enum StakingInfosDetails {
     Qtum(QtumStakingInfosDetails),
+    Cosmos(CosmosStakingInfosDetails),
 }
 
 impl From<QtumStakingInfosDetails> for StakingInfosDetails {
     fn from(qtum_staking_infos: QtumStakingInfosDetails) -> Self { StakingInfosDetails::Qtum(qtum_staking_infos) }
 }
 
+impl From<CosmosStakingInfosDetails> for StakingInfosDetails {
+    fn from(cosmos_staking_infos: CosmosStakingInfosDetails) -> Self {
+        StakingInfosDetails::Cosmos(cosmos_staking_infos)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct StakingInfos {
     pub staking_infos_details: StakingInfosDetails,
@@ -1724,6 +1762,7 @@ impl KmdRewardsDetails {
 pub enum TransactionType {
     StakingDelegation,
     RemoveDelegation,
+    ClaimDelegationRewards,
     #[default]
     StandardTransfer,
     TokenTransfer(BytesJson),
@@ -1732,7 +1771,28 @@ pub enum TransactionType {
         msg_type: CustomTendermintMsgType,
         token_id: Option<BytesJson>,
     },
+    /// An outgoing ICS-20 transfer along with its last known packet status.
+    TendermintIBCTransfer {
+        token_id: Option<BytesJson>,
+        status: IBCTransferStatus,
+    },
     NftTransfer,
+    TokenApprove,
+    ContractCall,
+    /// A lightning payment along with its HTLC status, e.g. `pending` or `succeeded`.
+    #[cfg(not(target_arch = "wasm32"))]
+    LightningPayment {
+        status: HTLCStatus,
+    },
+    /// The on-chain funding of a lightning channel.
+    LightningChannelOpen {
+        channel_id: String,
+    },
+    /// The closing of a lightning channel, the closing tx hash is used as `tx_hash` once it's known.
+    LightningChannelClose {
+        channel_id: String,
+        closure_reason: Option<String>,
+    },
 }
 
 /// Transaction details
@@ -2093,6 +2153,17 @@ pub enum DelegationError {
         available: BigDecimal,
         required: BigDecimal,
     },
+    #[display(
+        fmt = "Not enough coins delegated to {}: available {}, required {}",
+        validator,
+        available,
+        required
+    )]
+    NotSufficientDelegation {
+        validator: String,
+        available: BigDecimal,
+        required: BigDecimal,
+    },
     #[display(fmt = "The amount {} is too small, required at least {}", amount, threshold)]
     AmountTooLow { amount: BigDecimal, threshold: BigDecimal },
     #[display(fmt = "Delegation not available for: {}", coin)]
@@ -2183,6 +2254,10 @@ impl From<ScriptHashTypeNotSupported> for DelegationError {
     fn from(e: ScriptHashTypeNotSupported) -> Self { DelegationError::AddressError(e.to_string()) }
 }
 
+impl From<NumConversError> for DelegationError {
+    fn from(e: NumConversError) -> Self { DelegationError::InternalError(e.to_string()) }
+}
+
 impl HttpStatusCode for DelegationError {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -2280,6 +2355,8 @@ pub enum WithdrawError {
     InvalidFeePolicy(String),
     #[display(fmt = "Invalid memo field: {}", _0)]
     InvalidMemo(String),
+    #[display(fmt = "Invalid viewing key: {}", _0)]
+    InvalidViewingKey(String),
     #[display(fmt = "No such coin {}", coin)]
     NoSuchCoin {
         coin: String,
@@ -2355,6 +2432,7 @@ impl HttpStatusCode for WithdrawError {
             | WithdrawError::InvalidAddress(_)
             | WithdrawError::InvalidFeePolicy(_)
             | WithdrawError::InvalidMemo(_)
+            | WithdrawError::InvalidViewingKey(_)
             | WithdrawError::FromAddressNotFound
             | WithdrawError::UnexpectedFromAddress(_)
             | WithdrawError::UnknownAccount { .. }
@@ -2765,6 +2843,7 @@ pub enum MmCoinEnum {
     ZCoin(ZCoin),
     Bch(BchCoin),
     SlpToken(SlpToken),
+    CashToken(CashToken),
     Tendermint(TendermintCoin),
     TendermintToken(TendermintToken),
     #[cfg(all(
@@ -2834,6 +2913,10 @@ impl From<SlpToken> for MmCoinEnum {
     fn from(c: SlpToken) -> MmCoinEnum { MmCoinEnum::SlpToken(c) }
 }
 
+impl From<CashToken> for MmCoinEnum {
+    fn from(c: CashToken) -> MmCoinEnum { MmCoinEnum::CashToken(c) }
+}
+
 impl From<TendermintCoin> for MmCoinEnum {
     fn from(c: TendermintCoin) -> Self { MmCoinEnum::Tendermint(c) }
 }
@@ -2862,6 +2945,7 @@ impl Deref for MmCoinEnum {
             MmCoinEnum::EthCoin(ref c) => c,
             MmCoinEnum::Bch(ref c) => c,
             MmCoinEnum::SlpToken(ref c) => c,
+            MmCoinEnum::CashToken(ref c) => c,
             MmCoinEnum::Tendermint(ref c) => c,
             MmCoinEnum::TendermintToken(ref c) => c,
             #[cfg(not(target_arch = "wasm32"))]
@@ -2894,6 +2978,7 @@ impl MmCoinEnum {
             MmCoinEnum::Qrc20Coin(ref c) => c.as_ref().rpc_client.is_native(),
             MmCoinEnum::Bch(ref c) => c.as_ref().rpc_client.is_native(),
             MmCoinEnum::SlpToken(ref c) => c.as_ref().rpc_client.is_native(),
+            MmCoinEnum::CashToken(ref c) => c.as_ref().rpc_client.is_native(),
             #[cfg(all(not(target_arch = "wasm32"), feature = "zhtlc"))]
             MmCoinEnum::ZCoin(ref c) => c.as_ref().rpc_client.is_native(),
             _ => false,
@@ -2902,6 +2987,30 @@ impl MmCoinEnum {
 
     pub fn is_eth(&self) -> bool { matches!(self, MmCoinEnum::EthCoin(_)) }
 
+    /// Whether the coin is an ERC721/ERC1155 token that can be swapped as a coin.
+    pub fn is_nft(&self) -> bool {
+        matches!(self, MmCoinEnum::EthCoin(coin) if matches!(coin.coin_type, EthCoinType::Nft { .. }))
+    }
+
+    /// Returns the coin bound to the NFT traded by an order or a swap.
+    /// The NFT coin can't be traded without a token, other coins are returned as is and can't take a token.
+    pub fn bind_nft_token(self, token: Option<NftSwapToken>) -> Result<MmCoinEnum, String> {
+        match (self, token) {
+            (MmCoinEnum::EthCoin(coin), Some(token)) => Ok(try_s!(coin.bind_nft_token(token)).into()),
+            (coin, None) if coin.is_nft() => ERR!("The NFT token must be set to trade {}", coin.ticker()),
+            (coin, None) => Ok(coin),
+            (coin, Some(_)) => ERR!("{} is not an NFT coin", coin.ticker()),
+        }
+    }
+
+    /// Returns the NFT the coin is bound to, see [`MmCoinEnum::bind_nft_token`].
+    pub fn nft_token(&self) -> Option<NftSwapToken> {
+        match self {
+            MmCoinEnum::EthCoin(coin) => coin.nft_token().cloned(),
+            _ => None,
+        }
+    }
+
     fn is_platform_coin(&self) -> bool { self.ticker() == self.platform_ticker() }
 }
 
@@ -3028,10 +3137,14 @@ pub struct CoinsContext {
     platform_coin_tokens: PaMutex<HashMap<String, HashSet<String>>>,
     scan_addresses_manager: ScanAddressesTaskManagerShared,
     withdraw_task_manager: WithdrawTaskManagerShared,
+    #[cfg(not(target_arch = "wasm32"))]
+    z_coin_rescan_manager: ZCoinRescanTaskManagerShared,
     #[cfg(target_arch = "wasm32")]
     tx_history_db: SharedDb<TxHistoryDb>,
     #[cfg(target_arch = "wasm32")]
     hd_wallet_db: SharedDb<HDWalletDb>,
+    #[cfg(target_arch = "wasm32")]
+    ibc_transfer_db: SharedDb<IBCTransferDb>,
 }
 
 #[derive(Debug)]
@@ -3052,10 +3165,14 @@ impl CoinsContext {
                 get_new_address_manager: GetNewAddressTaskManager::new_shared(),
                 scan_addresses_manager: ScanAddressesTaskManager::new_shared(),
                 withdraw_task_manager: WithdrawTaskManager::new_shared(),
+                #[cfg(not(target_arch = "wasm32"))]
+                z_coin_rescan_manager: ZCoinRescanTaskManager::new_shared(),
                 #[cfg(target_arch = "wasm32")]
                 tx_history_db: ConstructibleDb::new(ctx).into_shared(),
                 #[cfg(target_arch = "wasm32")]
                 hd_wallet_db: ConstructibleDb::new_shared_db(ctx).into_shared(),
+                #[cfg(target_arch = "wasm32")]
+                ibc_transfer_db: ConstructibleDb::new(ctx).into_shared(),
             })
         })))
     }
@@ -3372,17 +3489,29 @@ pub enum CoinProtocol {
         platform: String,
         contract_address: String,
     },
-    ETH,
+    ETH(Option<EthProtocolInfo>),
     ERC20 {
         platform: String,
         contract_address: String,
     },
+    /// ERC721/ERC1155 tokens of the platform chain traded with 0 decimals.
+    /// The contract and the id of the traded token are set by the order.
+    NFT {
+        platform: String,
+    },
     SLPTOKEN {
         platform: String,
         token_id: H256Json,
         decimals: u8,
         required_confirmations: Option<u64>,
     },
+    /// A BCH CashTokens fungible token, `category` is the token category id as it's shown by the explorers.
+    CASHTOKEN {
+        platform: String,
+        category: H256Json,
+        decimals: u8,
+        required_confirmations: Option<u64>,
+    },
     BCH {
         slp_prefix: String,
     },
@@ -3418,6 +3547,9 @@ pub trait RpcTransportEventHandler {
     fn on_connected(&self, address: String) -> Result<(), String>;
 
     fn on_disconnected(&self, address: String) -> Result<(), String>;
+
+    /// Called when the particular node of a multi-node transport responded or failed to respond.
+    fn on_node_response(&self, _node: &str, _latency: Duration, _is_success: bool) {}
 }
 
 impl fmt::Debug for dyn RpcTransportEventHandler + Send + Sync {
@@ -3434,6 +3566,10 @@ impl RpcTransportEventHandler for RpcTransportEventHandlerShared {
     fn on_connected(&self, address: String) -> Result<(), String> { self.as_ref().on_connected(address) }
 
     fn on_disconnected(&self, address: String) -> Result<(), String> { self.as_ref().on_disconnected(address) }
+
+    fn on_node_response(&self, node: &str, latency: Duration, is_success: bool) {
+        self.as_ref().on_node_response(node, latency, is_success)
+    }
 }
 
 impl<T: RpcTransportEventHandler> RpcTransportEventHandler for Vec<T> {
@@ -3467,6 +3603,12 @@ impl<T: RpcTransportEventHandler> RpcTransportEventHandler for Vec<T> {
         }
         Ok(())
     }
+
+    fn on_node_response(&self, node: &str, latency: Duration, is_success: bool) {
+        for handler in self {
+            handler.on_node_response(node, latency, is_success)
+        }
+    }
 }
 
 pub enum RpcClientType {
@@ -3535,6 +3677,18 @@ impl RpcTransportEventHandler for CoinTransportMetrics {
         // Now just return the Ok
         Ok(())
     }
+
+    fn on_node_response(&self, node: &str, latency: Duration, is_success: bool) {
+        mm_counter!(self.metrics, "rpc_client.node.request.count", 1,
+            "coin" => self.ticker.to_owned(), "client" => self.client.to_owned(), "node" => node);
+        if is_success {
+            mm_timing!(self.metrics, "rpc_client.node.latency", latency,
+                "coin" => self.ticker.to_owned(), "client" => self.client.to_owned(), "node" => node);
+        } else {
+            mm_counter!(self.metrics, "rpc_client.node.failure.count", 1,
+                "coin" => self.ticker.to_owned(), "client" => self.client.to_owned(), "node" => node);
+        }
+    }
 }
 
 #[async_trait]
@@ -3598,7 +3752,7 @@ pub async fn lp_coininit(ctx: &MmArc, ticker: &str, req: &Json) -> Result<MmCoin
             let params = try_s!(UtxoActivationParams::from_legacy_req(req));
             try_s!(qtum_coin_with_policy(ctx, ticker, &coins_en, &params, priv_key_policy).await).into()
         },
-        CoinProtocol::ETH | CoinProtocol::ERC20 { .. } => {
+        CoinProtocol::ETH(_) | CoinProtocol::ERC20 { .. } | CoinProtocol::NFT { .. } => {
             try_s!(eth_coin_from_conf_and_request(ctx, ticker, &coins_en, req, protocol, priv_key_policy).await).into()
         },
         CoinProtocol::QRC20 {
@@ -3652,6 +3806,9 @@ pub async fn lp_coininit(ctx: &MmArc, ticker: &str, req: &Json) -> Result<MmCoin
             ));
             token.into()
         },
+        CoinProtocol::CASHTOKEN { .. } => {
+            return ERR!("CASHTOKEN protocol is not supported by lp_coininit - use enable_bch_with_tokens instead")
+        },
         CoinProtocol::TENDERMINT { .. } => return ERR!("TENDERMINT protocol is not supported by lp_coininit"),
         CoinProtocol::TENDERMINTTOKEN(_) => return ERR!("TENDERMINTTOKEN protocol is not supported by lp_coininit"),
         CoinProtocol::ZHTLC { .. } => return ERR!("ZHTLC protocol is not supported by lp_coininit"),
@@ -3878,6 +4035,12 @@ pub async fn remove_delegation(ctx: MmArc, req: RemoveDelegateRequest) -> Delega
     let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
     match coin {
         MmCoinEnum::QtumCoin(qtum) => qtum.remove_delegation().compat().await,
+        MmCoinEnum::Tendermint(tendermint) => match req.staking_details {
+            Some(StakingDetails::Cosmos(cosmos_staking)) => tendermint.undelegate(cosmos_staking).await,
+            _ => MmError::err(DelegationError::DelegationOpsNotSupported {
+                reason: "Cosmos staking details with the validator address are required".to_owned(),
+            }),
+        },
         _ => {
             return MmError::err(DelegationError::CoinDoesntSupportDelegation {
                 coin: coin.ticker().to_string(),
@@ -3890,6 +4053,7 @@ pub async fn get_staking_infos(ctx: MmArc, req: GetStakingInfosRequest) -> Staki
     let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
     match coin {
         MmCoinEnum::QtumCoin(qtum) => qtum.get_delegation_infos().compat().await,
+        MmCoinEnum::Tendermint(tendermint) => tendermint.get_staking_infos().await,
         _ => {
             return MmError::err(StakingInfosError::CoinDoesntSupportStakingInfos {
                 coin: coin.ticker().to_string(),
@@ -3900,17 +4064,16 @@ pub async fn get_staking_infos(ctx: MmArc, req: GetStakingInfosRequest) -> Staki
 
 pub async fn add_delegation(ctx: MmArc, req: AddDelegateRequest) -> DelegationResult {
     let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
-    // Need to find a way to do a proper dispatch
-    let coin_concrete = match coin {
-        MmCoinEnum::QtumCoin(qtum) => qtum,
-        _ => {
-            return MmError::err(DelegationError::CoinDoesntSupportDelegation {
-                coin: coin.ticker().to_string(),
-            })
+    match (coin, req.staking_details) {
+        (MmCoinEnum::QtumCoin(qtum), StakingDetails::Qtum(qtum_staking)) => {
+            qtum.add_delegation(qtum_staking).compat().await
         },
-    };
-    match req.staking_details {
-        StakingDetails::Qtum(qtum_staking) => coin_concrete.add_delegation(qtum_staking).compat().await,
+        (MmCoinEnum::Tendermint(tendermint), StakingDetails::Cosmos(cosmos_staking)) => {
+            tendermint.delegate(cosmos_staking).await
+        },
+        (coin, _) => MmError::err(DelegationError::CoinDoesntSupportDelegation {
+            coin: coin.ticker().to_string(),
+        }),
     }
 }
 
@@ -4139,7 +4302,7 @@ pub fn update_coins_config(mut config: Json) -> Result<Json, String> {
                     .as_str()
                     .ok_or(ERRL!("Expected etomic as string, found {:?}", etomic))?;
                 if etomic == "0x0000000000000000000000000000000000000000" {
-                    CoinProtocol::ETH
+                    CoinProtocol::ETH(None)
                 } else {
                     let contract_address = etomic.to_owned();
                     CoinProtocol::ERC20 {
@@ -4194,7 +4357,9 @@ pub fn address_by_coin_conf_and_pubkey_str(
 ) -> Result<String, String> {
     let protocol: CoinProtocol = try_s!(json::from_value(conf["protocol"].clone()));
     match protocol {
-        CoinProtocol::ERC20 { .. } | CoinProtocol::ETH => eth::addr_from_pubkey_str(pubkey),
+        CoinProtocol::ERC20 { .. } | CoinProtocol::ETH(_) | CoinProtocol::NFT { .. } => {
+            eth::addr_from_pubkey_str(pubkey)
+        },
         CoinProtocol::UTXO | CoinProtocol::QTUM | CoinProtocol::QRC20 { .. } | CoinProtocol::BCH { .. } => {
             utxo::address_by_conf_and_pubkey_str(coin, conf, pubkey, addr_format)
         },
@@ -4212,6 +4377,14 @@ pub fn address_by_coin_conf_and_pubkey_str(
                 _ => ERR!("Platform protocol {:?} is not BCH", platform_protocol),
             }
         },
+        CoinProtocol::CASHTOKEN { platform, .. } => {
+            let platform_conf = coin_conf(ctx, &platform);
+            if platform_conf.is_null() {
+                return ERR!("platform {} conf is null", platform);
+            }
+            // CashTokens are held by the regular BCH addresses
+            utxo::address_by_conf_and_pubkey_str(&platform, &platform_conf, pubkey, addr_format)
+        },
         CoinProtocol::TENDERMINT(protocol) => tendermint::account_id_from_pubkey_hex(&protocol.account_prefix, pubkey)
             .map(|id| id.to_string())
             .map_err(|e| e.to_string()),
@@ -4474,7 +4647,7 @@ pub async fn get_my_address(ctx: MmArc, req: MyAddressReq) -> MmResult<MyWalletA
     let protocol: CoinProtocol = json::from_value(conf["protocol"].clone())?;
 
     let my_address = match protocol {
-        CoinProtocol::ETH => get_eth_address(&ctx, &conf, ticker, &req.path_to_address).await?,
+        CoinProtocol::ETH(_) => get_eth_address(&ctx, &conf, ticker, &req.path_to_address).await?,
         _ => {
             return MmError::err(GetMyAddressError::CoinIsNotSupported(format!(
                 "{} doesn't support get_my_address",
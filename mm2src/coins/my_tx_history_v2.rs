@@ -221,7 +221,8 @@ impl<'a, Addr: Clone + DisplayAddress + Eq + std::hash::Hash, Tx: Transaction> T
                 bytes_for_hash.extend_from_slice(&token_id.0);
                 sha256(&bytes_for_hash).to_vec().into()
             },
-            TransactionType::CustomTendermintMsg { token_id, .. } => {
+            TransactionType::CustomTendermintMsg { token_id, .. }
+            | TransactionType::TendermintIBCTransfer { token_id, .. } => {
                 if let Some(token_id) = token_id {
                     let mut bytes_for_hash = tx_hash.0.clone();
                     bytes_for_hash.extend_from_slice(&token_id.0);
@@ -232,9 +233,16 @@ impl<'a, Addr: Clone + DisplayAddress + Eq + std::hash::Hash, Tx: Transaction> T
             },
             TransactionType::StakingDelegation
             | TransactionType::RemoveDelegation
+            | TransactionType::ClaimDelegationRewards
             | TransactionType::FeeForTokenTx
             | TransactionType::StandardTransfer
-            | TransactionType::NftTransfer => tx_hash.clone(),
+            | TransactionType::NftTransfer
+            | TransactionType::TokenApprove
+            | TransactionType::ContractCall
+            | TransactionType::LightningChannelOpen { .. }
+            | TransactionType::LightningChannelClose { .. } => tx_hash.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            TransactionType::LightningPayment { .. } => tx_hash.clone(),
         };
 
         TransactionDetails {
@@ -391,10 +399,29 @@ pub async fn my_tx_history_v2_rpc(
     match lp_coinfind_or_err(&ctx, &request.coin).await? {
         MmCoinEnum::Bch(bch) => my_tx_history_v2_impl(ctx, &bch, request).await,
         MmCoinEnum::SlpToken(slp_token) => my_tx_history_v2_impl(ctx, &slp_token, request).await,
+        MmCoinEnum::CashToken(cash_token) => my_tx_history_v2_impl(ctx, &cash_token, request).await,
         MmCoinEnum::UtxoCoin(utxo) => my_tx_history_v2_impl(ctx, &utxo, request).await,
         MmCoinEnum::QtumCoin(qtum) => my_tx_history_v2_impl(ctx, &qtum, request).await,
+        MmCoinEnum::Qrc20Coin(qrc20) => my_tx_history_v2_impl(ctx, &qrc20, request).await,
         MmCoinEnum::Tendermint(tendermint) => my_tx_history_v2_impl(ctx, &tendermint, request).await,
         MmCoinEnum::TendermintToken(tendermint_token) => my_tx_history_v2_impl(ctx, &tendermint_token, request).await,
+        // Lightning payments and channels events are read from the lightning DB instead of the history storage.
+        #[cfg(not(target_arch = "wasm32"))]
+        MmCoinEnum::LightningCoin(lightning) => lightning.tx_history(request).await,
+        #[cfg(all(
+            feature = "enable-solana",
+            not(target_os = "ios"),
+            not(target_os = "android"),
+            not(target_arch = "wasm32")
+        ))]
+        MmCoinEnum::SolanaCoin(solana) => my_tx_history_v2_impl(ctx, &solana, request).await,
+        #[cfg(all(
+            feature = "enable-solana",
+            not(target_os = "ios"),
+            not(target_os = "android"),
+            not(target_arch = "wasm32")
+        ))]
+        MmCoinEnum::SplToken(spl_token) => my_tx_history_v2_impl(ctx, &spl_token, request).await,
         other => MmError::err(MyTxHistoryErrorV2::NotSupportedFor(other.ticker().to_owned())),
     }
 }
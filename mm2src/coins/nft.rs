@@ -1,23 +1,27 @@
 use mm2_core::mm_ctx::MmArc;
-use mm2_err_handle::prelude::{MmError, MmResult};
+use mm2_err_handle::prelude::{MmError, MmResult, NotMmError};
 use url::Url;
 
 pub(crate) mod nft_errors;
+pub(crate) mod nft_indexer;
 pub(crate) mod nft_structs;
 pub(crate) mod storage;
 
 #[cfg(any(test, target_arch = "wasm32"))] mod nft_tests;
 
-use crate::{coin_conf, get_my_address, lp_coinfind_or_err, MarketCoinOps, MmCoinEnum, MyAddressReq, WithdrawError};
+use crate::{coin_conf, get_my_address, lp_coinfind_or_err, CoinProtocol, MarketCoinOps, MmCoinEnum, MyAddressReq,
+            WithdrawError};
 use nft_errors::{GetNftInfoError, UpdateNftError};
-use nft_structs::{Chain, ContractType, ConvertChain, Nft, NftFromMoralis, NftList, NftListReq, NftMetadataReq,
-                  NftTransferHistory, NftTransferHistoryFromMoralis, NftTransfersReq, NftsTransferHistoryList,
-                  TransactionNftDetails, UpdateNftReq, WithdrawNftReq};
+use nft_structs::{Chain, ContractType, ConvertChain, Nft, NftFromMoralis, NftIndexer, NftList, NftListReq,
+                  NftMetadataReq, NftTransferHistory, NftTransferHistoryFromMoralis, NftTransfersReq,
+                  NftsTransferHistoryList, TransactionNftDetails, UpdateNftReq, WithdrawNftReq};
 
 use crate::eth::{eth_addr_to_hex, get_eth_address, withdraw_erc1155, withdraw_erc721, EthCoin, EthCoinType,
                  EthTxFeeDetails};
 use crate::nft::nft_errors::{MetaFromUrlError, ProtectFromSpamError, TransferConfirmationsError,
                              UpdateSpamPhishingError};
+use crate::nft::nft_indexer::{get_last_confirmed_block, get_nft_metadata_from_contract, get_nft_transfers_from_logs,
+                              LOGS_BLOCKS_PER_REQUEST};
 use crate::nft::nft_structs::{build_nft_with_empty_meta, BuildNftFields, NftCommon, NftCtx, NftTransferCommon,
                               PhishingDomainReq, PhishingDomainRes, RefreshMetadataReq, SpamContractReq,
                               SpamContractRes, TransferMeta, TransferStatus, UriMeta};
@@ -78,6 +82,7 @@ pub type WithdrawNftResult = Result<TransactionNftDetails, MmError<WithdrawError
 /// Returns `GetNftInfoError` variants for issues like invalid requests, transport failures,
 /// database errors, and spam protection errors.
 pub async fn get_nft_list(ctx: MmArc, req: NftListReq) -> MmResult<NftList, GetNftInfoError> {
+    check_nft_chains(&ctx, &req.chains, |coin| GetNftInfoError::CoinDoesntSupportNft { coin })?;
     let nft_ctx = NftCtx::from_ctx(&ctx).map_to_mm(GetNftInfoError::Internal)?;
 
     let storage = nft_ctx.lock_db().await?;
@@ -117,6 +122,9 @@ pub async fn get_nft_list(ctx: MmArc, req: NftListReq) -> MmResult<NftList, GetN
 /// Returns `GetNftInfoError` variants for issues like invalid requests, transport failures,
 /// database errors, and spam protection errors.
 pub async fn get_nft_metadata(ctx: MmArc, req: NftMetadataReq) -> MmResult<Nft, GetNftInfoError> {
+    check_nft_chains(&ctx, [&req.chain], |coin| GetNftInfoError::CoinDoesntSupportNft {
+        coin,
+    })?;
     let nft_ctx = NftCtx::from_ctx(&ctx).map_to_mm(GetNftInfoError::Internal)?;
 
     let storage = nft_ctx.lock_db().await?;
@@ -137,6 +145,32 @@ pub async fn get_nft_metadata(ctx: MmArc, req: NftMetadataReq) -> MmResult<Nft,
     Ok(nft)
 }
 
+/// Whether NFT feature is enabled for the `chain` in the coins config,
+/// i.e. its platform coin has `{"type": "ETH", "protocol_data": {"nft": true}}` protocol.
+/// The legacy chains, which supported NFT before it became configurable, are enabled unless `nft` is set to false.
+pub(crate) fn is_nft_chain(ctx: &MmArc, chain: &Chain) -> bool {
+    let protocol = coin_conf(ctx, chain.to_ticker())["protocol"].clone();
+    match serde_json::from_value::<CoinProtocol>(protocol) {
+        Ok(CoinProtocol::ETH(protocol_info)) => protocol_info
+            .and_then(|info| info.nft)
+            .unwrap_or_else(|| chain.is_legacy()),
+        _ => false,
+    }
+}
+
+/// Returns an error built by `on_err` from the ticker of the first chain which NFT feature is not enabled for.
+fn check_nft_chains<'a, I, E, F>(ctx: &MmArc, chains: I, on_err: F) -> MmResult<(), E>
+where
+    I: IntoIterator<Item = &'a Chain>,
+    E: NotMmError,
+    F: FnOnce(String) -> E,
+{
+    match chains.into_iter().find(|chain| !is_nft_chain(ctx, chain)) {
+        Some(chain) => MmError::err(on_err(chain.to_ticker().to_owned())),
+        None => Ok(()),
+    }
+}
+
 /// Fetches the transfer history of user-owned NFTs across specified chains.
 ///
 /// The function aggregates NFT transfers based on provided chains, offers pagination,
@@ -158,6 +192,7 @@ pub async fn get_nft_metadata(ctx: MmArc, req: NftMetadataReq) -> MmResult<Nft,
 /// Returns `GetNftInfoError` variants for issues like invalid requests, transport failures,
 /// database errors, and spam protection errors.
 pub async fn get_nft_transfers(ctx: MmArc, req: NftTransfersReq) -> MmResult<NftsTransferHistoryList, GetNftInfoError> {
+    check_nft_chains(&ctx, &req.chains, |coin| GetNftInfoError::CoinDoesntSupportNft { coin })?;
     let nft_ctx = NftCtx::from_ctx(&ctx).map_to_mm(GetNftInfoError::Internal)?;
 
     let storage = nft_ctx.lock_db().await?;
@@ -192,12 +227,11 @@ async fn process_transfers_confirmations(
     }
 
     let futures = chains.into_iter().map(|chain| async move {
-        let ticker = chain.to_ticker();
-        let coin_enum = lp_coinfind_or_err(ctx, ticker).await?;
+        let coin_enum = lp_coinfind_or_err(ctx, chain.to_ticker()).await?;
         match coin_enum {
             MmCoinEnum::EthCoin(eth_coin) => {
                 let current_block = current_block_impl(eth_coin).await?;
-                Ok((ticker, current_block))
+                Ok((chain, current_block))
             },
             _ => MmError::err(TransferConfirmationsError::CoinDoesntSupportNft {
                 coin: coin_enum.ticker().to_owned(),
@@ -207,7 +241,7 @@ async fn process_transfers_confirmations(
     let blocks_map = try_join_all(futures).await?.into_iter().collect::<HashMap<_, _>>();
 
     for transfer in history_list.transfer_history.iter_mut() {
-        let current_block = match blocks_map.get(transfer.chain.to_ticker()) {
+        let current_block = match blocks_map.get(&transfer.chain) {
             Some(block) => *block,
             None => 0,
         };
@@ -235,6 +269,7 @@ async fn process_transfers_confirmations(
 ///
 /// * `MmResult<(), UpdateNftError>`: A result indicating success or an error.
 pub async fn update_nft(ctx: MmArc, req: UpdateNftReq) -> MmResult<(), UpdateNftError> {
+    check_nft_chains(&ctx, &req.chains, |coin| UpdateNftError::CoinDoesntSupportNft { coin })?;
     let nft_ctx = NftCtx::from_ctx(&ctx).map_to_mm(GetNftInfoError::Internal)?;
 
     let storage = nft_ctx.lock_db().await?;
@@ -257,8 +292,26 @@ pub async fn update_nft(ctx: MmArc, req: UpdateNftReq) -> MmResult<(), UpdateNft
                 })
             },
         };
+        if let NftIndexer::Logs {
+            from_block: start_block,
+            confirmations,
+        } = req.indexers.get(chain).copied().unwrap_or_default()
+        {
+            update_nft_from_logs(
+                &ctx,
+                &storage,
+                chain,
+                start_block,
+                confirmations,
+                &eth_coin,
+                &req.url_antispam,
+            )
+            .await?;
+            continue;
+        }
+        let source = MetadataSource::Moralis(&req.url);
         let nft_transfers = get_moralis_nft_transfers(&ctx, chain, from_block, &req.url, eth_coin).await?;
-        storage.add_transfers_to_history(*chain, nft_transfers).await?;
+        storage.add_transfers_to_history(chain.clone(), nft_transfers).await?;
 
         let nft_block = match NftListStorageOps::get_last_block_number(&storage, chain).await {
             Ok(Some(block)) => block,
@@ -266,8 +319,8 @@ pub async fn update_nft(ctx: MmArc, req: UpdateNftReq) -> MmResult<(), UpdateNft
                 // if there are no rows in NFT LIST table we can try to get nft list from moralis.
                 let nft_list = cache_nfts_from_moralis(&ctx, &storage, chain, &req.url, &req.url_antispam).await?;
                 update_meta_in_transfers(&storage, chain, nft_list).await?;
-                update_transfers_with_empty_meta(&storage, chain, &req.url, &req.url_antispam).await?;
-                update_spam(&storage, *chain, &req.url_antispam).await?;
+                update_transfers_with_empty_meta(&storage, chain, source, &req.url_antispam).await?;
+                update_spam(&storage, chain, &req.url_antispam).await?;
                 update_phishing(&storage, chain, &req.url_antispam).await?;
                 continue;
             },
@@ -276,8 +329,8 @@ pub async fn update_nft(ctx: MmArc, req: UpdateNftReq) -> MmResult<(), UpdateNft
                 NftListStorageOps::init(&storage, chain).await?;
                 let nft_list = cache_nfts_from_moralis(&ctx, &storage, chain, &req.url, &req.url_antispam).await?;
                 update_meta_in_transfers(&storage, chain, nft_list).await?;
-                update_transfers_with_empty_meta(&storage, chain, &req.url, &req.url_antispam).await?;
-                update_spam(&storage, *chain, &req.url_antispam).await?;
+                update_transfers_with_empty_meta(&storage, chain, source, &req.url_antispam).await?;
+                update_spam(&storage, chain, &req.url_antispam).await?;
                 update_phishing(&storage, chain, &req.url_antispam).await?;
                 continue;
             },
@@ -302,38 +355,128 @@ pub async fn update_nft(ctx: MmArc, req: UpdateNftReq) -> MmResult<(), UpdateNft
             &storage,
             chain,
             scanned_block + 1,
-            &req.url,
+            source,
             &req.url_antispam,
         )
         .await?;
-        update_transfers_with_empty_meta(&storage, chain, &req.url, &req.url_antispam).await?;
-        update_spam(&storage, *chain, &req.url_antispam).await?;
+        update_transfers_with_empty_meta(&storage, chain, source, &req.url_antispam).await?;
+        update_spam(&storage, chain, &req.url_antispam).await?;
         update_phishing(&storage, chain, &req.url_antispam).await?;
     }
     Ok(())
 }
 
+/// `update_nft_from_logs` function updates NFT transfer history and NFT list of the chain
+/// using the self-hosted indexer instead of Moralis.
+///
+/// The blocks are scanned by chunks starting from `start_block` on the first update.
+/// Every transfer is stored and applied to the NFT list as soon as its chunk is scanned,
+/// and the last scanned block is stored after every chunk, so an interrupted update doesn't lose the scanned chunks.
+/// The next update scans the last scanned block again, as it may be stored in the middle of the block,
+/// and skips the already stored transfers.
+///
+/// Only the blocks having at least `confirmations` confirmations are scanned,
+/// so the transfers of the blocks which can still be reorganized aren't stored.
+async fn update_nft_from_logs<T>(
+    ctx: &MmArc,
+    storage: &T,
+    chain: &Chain,
+    start_block: u64,
+    confirmations: u64,
+    eth_coin: &EthCoin,
+    url_antispam: &Url,
+) -> MmResult<(), UpdateNftError>
+where
+    T: NftListStorageOps + NftTransferHistoryStorageOps,
+{
+    if !NftListStorageOps::is_initialized(storage, chain).await? {
+        NftListStorageOps::init(storage, chain).await?;
+    }
+    let req = MyAddressReq {
+        coin: chain.to_ticker().to_string(),
+        path_to_address: StandardHDCoinAddress::default(),
+    };
+    let my_address = get_my_address(ctx.clone(), req).await?.wallet_address.to_lowercase();
+    let source = MetadataSource::Contract(eth_coin);
+
+    let last_confirmed_block = get_last_confirmed_block(eth_coin, confirmations).await?;
+    let mut from_block = storage.get_last_scanned_block(chain).await?.unwrap_or(start_block);
+    while let Some(to_block) = last_confirmed_block.filter(|b| *b >= from_block) {
+        let to_block = std::cmp::min(from_block + LOGS_BLOCKS_PER_REQUEST - 1, to_block);
+        for transfer in get_nft_transfers_from_logs(eth_coin, chain, from_block, to_block).await? {
+            let stored = storage
+                .get_transfer_by_tx_hash_and_log_index(
+                    chain,
+                    transfer.common.transaction_hash.clone(),
+                    transfer.common.log_index,
+                )
+                .await?;
+            if stored.is_some() {
+                continue;
+            }
+            storage
+                .add_transfers_to_history(chain.clone(), vec![transfer.clone()])
+                .await?;
+            // Unlike Moralis, the indexer can't provide a snapshot of the wallet NFTs,
+            // so the NFT list is updated with the transfers of the newly scanned blocks only.
+            handle_nft_transfer(storage, chain, source, url_antispam, transfer, &my_address).await?;
+        }
+        storage.add_nfts_to_list(chain.clone(), Vec::new(), to_block).await?;
+        from_block = to_block + 1;
+    }
+    update_transfers_with_empty_meta(storage, chain, source, url_antispam).await?;
+    update_spam(storage, chain, url_antispam).await?;
+    update_phishing(storage, chain, url_antispam).await?;
+    Ok(())
+}
+
+/// The source of NFT metadata used while updating NFT list and transfers.
+#[derive(Clone, Copy)]
+enum MetadataSource<'a> {
+    /// Moralis-compatible proxy.
+    Moralis(&'a Url),
+    /// Token contracts requested through the web3 nodes of the coin.
+    Contract(&'a EthCoin),
+}
+
+async fn get_nft_metadata_from_source(
+    source: MetadataSource<'_>,
+    token_address: String,
+    token_id: BigUint,
+    chain: &Chain,
+    url_antispam: &Url,
+) -> MmResult<Nft, GetNftInfoError> {
+    match source {
+        MetadataSource::Moralis(url) => get_moralis_metadata(token_address, token_id, chain, url, url_antispam).await,
+        MetadataSource::Contract(eth_coin) => {
+            let token_address =
+                Address::from_str(&token_address).map_to_mm(|e| GetNftInfoError::InvalidRequest(e.to_string()))?;
+            get_nft_metadata_from_contract(eth_coin, chain, token_address, token_id, url_antispam).await
+        },
+    }
+}
+
 /// `update_spam` function updates spam contracts info in NFT list and NFT transfers.
-async fn update_spam<T>(storage: &T, chain: Chain, url_antispam: &Url) -> MmResult<(), UpdateSpamPhishingError>
+async fn update_spam<T>(storage: &T, chain: &Chain, url_antispam: &Url) -> MmResult<(), UpdateSpamPhishingError>
 where
     T: NftListStorageOps + NftTransferHistoryStorageOps,
 {
-    let token_addresses = storage.get_token_addresses(chain).await?;
+    let token_addresses = storage.get_token_addresses(chain.clone()).await?;
     if !token_addresses.is_empty() {
         let addresses = token_addresses
             .iter()
             .map(eth_addr_to_hex)
             .collect::<Vec<_>>()
             .join(",");
-        let spam_res = send_spam_request(&chain, url_antispam, addresses).await?;
+        let spam_res = send_spam_request(chain, url_antispam, addresses).await?;
         for (address, is_spam) in spam_res.result.into_iter() {
             if is_spam {
                 let address_hex = eth_addr_to_hex(&address);
                 storage
-                    .update_nft_spam_by_token_address(&chain, address_hex.clone(), is_spam)
+                    .update_nft_spam_by_token_address(chain, address_hex.clone(), is_spam)
                     .await?;
                 storage
-                    .update_transfer_spam_by_token_address(&chain, address_hex, is_spam)
+                    .update_transfer_spam_by_token_address(chain, address_hex, is_spam)
                     .await?;
             }
         }
@@ -373,7 +516,7 @@ async fn send_spam_request(
 ) -> MmResult<SpamContractRes, UpdateSpamPhishingError> {
     let scan_contract_uri = prepare_uri_for_blocklist_endpoint(url_antispam, BLOCKLIST_CONTRACT, BLOCKLIST_SCAN)?;
     let req_spam = SpamContractReq {
-        network: *chain,
+        network: chain.clone(),
         addresses,
     };
     let req_spam_json = serde_json::to_string(&req_spam)?;
@@ -428,6 +571,7 @@ fn prepare_uri_for_blocklist_endpoint(
 ///
 /// * `MmResult<(), UpdateNftError>`: A result indicating success or an error.
 pub async fn refresh_nft_metadata(ctx: MmArc, req: RefreshMetadataReq) -> MmResult<(), UpdateNftError> {
+    check_nft_chains(&ctx, [&req.chain], |coin| UpdateNftError::CoinDoesntSupportNft { coin })?;
     let nft_ctx = NftCtx::from_ctx(&ctx).map_to_mm(GetNftInfoError::Internal)?;
 
     let storage = nft_ctx.lock_db().await?;
@@ -611,7 +755,7 @@ async fn get_moralis_nft_list(
                     Some(contract_type) => contract_type,
                     None => continue,
                 };
-                let mut nft = build_nft_from_moralis(*chain, nft_moralis, contract_type, url_antispam).await;
+                let mut nft = build_nft_from_moralis(chain.clone(), nft_moralis, contract_type, url_antispam).await;
                 protect_from_nft_spam_links(&mut nft, false)?;
                 // collect NFTs from the page
                 res_list.push(nft);
@@ -694,7 +838,7 @@ async fn get_moralis_nft_transfers(
                         operator: transfer_moralis.common.operator,
                         possible_spam: transfer_moralis.common.possible_spam,
                     },
-                    chain: *chain,
+                    chain: chain.clone(),
                     token_id: transfer_moralis.token_id.0,
                     block_number: *transfer_moralis.block_number,
                     block_timestamp,
@@ -732,7 +876,7 @@ async fn get_fee_details(eth_coin: &EthCoin, transaction_hash: &str) -> Option<E
     let receipt = eth_coin.web3.eth().transaction_receipt(hash).await.ok()?;
     let fee_coin = match eth_coin.coin_type {
         EthCoinType::Eth => eth_coin.ticker(),
-        EthCoinType::Erc20 { .. } => return None,
+        EthCoinType::Erc20 { .. } | EthCoinType::Nft { .. } => return None,
     };
 
     match receipt {
@@ -790,7 +934,7 @@ async fn get_moralis_metadata(
         Some(contract_type) => contract_type,
         None => return MmError::err(GetNftInfoError::ContractTypeIsNull),
     };
-    let mut nft_metadata = build_nft_from_moralis(*chain, nft_moralis, contract_type, url_antispam).await;
+    let mut nft_metadata = build_nft_from_moralis(chain.clone(), nft_moralis, contract_type, url_antispam).await;
     protect_from_nft_spam_links(&mut nft_metadata, false)?;
     Ok(nft_metadata)
 }
@@ -799,6 +943,13 @@ async fn get_moralis_metadata(
 /// from my address to recipient's address.
 /// This method generates a raw transaction which should then be broadcast using `send_raw_transaction`.
 pub async fn withdraw_nft(ctx: MmArc, req: WithdrawNftReq) -> WithdrawNftResult {
+    let chain = match &req {
+        WithdrawNftReq::WithdrawErc1155(erc1155_withdraw) => &erc1155_withdraw.chain,
+        WithdrawNftReq::WithdrawErc721(erc721_withdraw) => &erc721_withdraw.chain,
+    };
+    check_nft_chains(&ctx, [chain], |coin| WithdrawError::CoinDoesntSupportNftWithdraw {
+        coin,
+    })?;
     match req {
         WithdrawNftReq::WithdrawErc1155(erc1155_withdraw) => withdraw_erc1155(ctx, erc1155_withdraw).await,
         WithdrawNftReq::WithdrawErc721(erc721_withdraw) => withdraw_erc721(ctx, erc721_withdraw).await,
@@ -883,17 +1034,17 @@ async fn update_nft_list<T: NftListStorageOps + NftTransferHistoryStorageOps>(
     storage: &T,
     chain: &Chain,
     scan_from_block: u64,
-    url: &Url,
+    source: MetadataSource<'_>,
     url_antispam: &Url,
 ) -> MmResult<(), UpdateNftError> {
-    let transfers = storage.get_transfers_from_block(*chain, scan_from_block).await?;
+    let transfers = storage.get_transfers_from_block(chain.clone(), scan_from_block).await?;
     let req = MyAddressReq {
         coin: chain.to_ticker().to_string(),
         path_to_address: StandardHDCoinAddress::default(),
     };
     let my_address = get_my_address(ctx.clone(), req).await?.wallet_address.to_lowercase();
     for transfer in transfers.into_iter() {
-        handle_nft_transfer(storage, chain, url, url_antispam, transfer, &my_address).await?;
+        handle_nft_transfer(storage, chain, source, url_antispam, transfer, &my_address).await?;
     }
     Ok(())
 }
@@ -901,7 +1052,7 @@ async fn update_nft_list<T: NftListStorageOps + NftTransferHistoryStorageOps>(
 async fn handle_nft_transfer<T: NftListStorageOps + NftTransferHistoryStorageOps>(
     storage: &T,
     chain: &Chain,
-    url: &Url,
+    source: MetadataSource<'_>,
     url_antispam: &Url,
     transfer: NftTransferHistory,
     my_address: &str,
@@ -909,11 +1060,11 @@ async fn handle_nft_transfer<T: NftListStorageOps + NftTransferHistoryStorageOps
     match (transfer.status, transfer.contract_type) {
         (TransferStatus::Send, ContractType::Erc721) => handle_send_erc721(storage, chain, transfer).await,
         (TransferStatus::Receive, ContractType::Erc721) => {
-            handle_receive_erc721(storage, chain, transfer, url, url_antispam, my_address).await
+            handle_receive_erc721(storage, chain, transfer, source, url_antispam, my_address).await
         },
         (TransferStatus::Send, ContractType::Erc1155) => handle_send_erc1155(storage, chain, transfer).await,
         (TransferStatus::Receive, ContractType::Erc1155) => {
-            handle_receive_erc1155(storage, chain, transfer, url, url_antispam, my_address).await
+            handle_receive_erc1155(storage, chain, transfer, source, url_antispam, my_address).await
         },
     }
 }
@@ -949,7 +1100,7 @@ async fn handle_receive_erc721<T: NftListStorageOps + NftTransferHistoryStorageO
     storage: &T,
     chain: &Chain,
     transfer: NftTransferHistory,
-    url: &Url,
+    source: MetadataSource<'_>,
     url_antispam: &Url,
     my_address: &str,
 ) -> MmResult<(), UpdateNftError> {
@@ -973,11 +1124,11 @@ async fn handle_receive_erc721<T: NftListStorageOps + NftTransferHistoryStorageO
             update_transfer_meta_using_nft(storage, chain, &mut nft_db).await?;
         },
         None => {
-            let mut nft = match get_moralis_metadata(
+            let mut nft = match get_nft_metadata_from_source(
+                source,
                 token_address_str.clone(),
                 transfer.token_id.clone(),
                 chain,
-                url,
                 url_antispam,
             )
             .await
@@ -995,7 +1146,7 @@ async fn handle_receive_erc721<T: NftListStorageOps + NftTransferHistoryStorageO
                 },
             };
             storage
-                .add_nfts_to_list(*chain, vec![nft.clone()], transfer.block_number)
+                .add_nfts_to_list(chain.clone(), vec![nft.clone()], transfer.block_number)
                 .await?;
             update_transfer_meta_using_nft(storage, chain, &mut nft).await?;
         },
@@ -1042,7 +1193,7 @@ async fn handle_receive_erc1155<T: NftListStorageOps + NftTransferHistoryStorage
     storage: &T,
     chain: &Chain,
     transfer: NftTransferHistory,
-    url: &Url,
+    source: MetadataSource<'_>,
     url_antispam: &Url,
     my_address: &str,
 ) -> MmResult<(), UpdateNftError> {
@@ -1066,24 +1217,32 @@ async fn handle_receive_erc1155<T: NftListStorageOps + NftTransferHistoryStorage
         },
         // If token isn't in NFT LIST table then add nft to the table.
         None => {
-            let nft = match get_moralis_metadata(
+            let nft = match get_nft_metadata_from_source(
+                source,
                 token_address_str.clone(),
                 transfer.token_id.clone(),
                 chain,
-                url,
                 url_antispam,
             )
             .await
             {
-                Ok(moralis_meta) => {
+                Ok(moralis_meta) if matches!(source, MetadataSource::Moralis(_)) => {
                     create_nft_from_moralis_metadata(moralis_meta, &transfer, my_address, chain, url_antispam).await?
                 },
+                Ok(mut contract_meta) => {
+                    // metadata requested from the contract is already processed, so only the wallet related fields are set
+                    contract_meta.common.amount = transfer.common.amount.clone();
+                    contract_meta.common.owner_of =
+                        Address::from_str(my_address).map_to_mm(|e| UpdateNftError::InvalidHexString(e.to_string()))?;
+                    contract_meta.block_number = transfer.block_number;
+                    contract_meta
+                },
                 Err(_) => {
                     mark_as_spam_and_build_empty_meta(storage, chain, token_address_str, &transfer, my_address).await?
                 },
             };
             storage
-                .add_nfts_to_list(*chain, [nft.clone()], transfer.block_number)
+                .add_nfts_to_list(chain.clone(), [nft.clone()], transfer.block_number)
                 .await?;
             nft
         },
@@ -1123,7 +1282,7 @@ async fn create_nft_from_moralis_metadata(
             minter_address: moralis_meta.common.minter_address,
             possible_spam: moralis_meta.common.possible_spam,
         },
-        chain: *chain,
+        chain: chain.clone(),
         token_id: moralis_meta.token_id,
         block_number_minted: moralis_meta.block_number_minted,
         block_number: transfer.block_number,
@@ -1196,7 +1355,7 @@ async fn cache_nfts_from_moralis<T: NftListStorageOps + NftTransferHistoryStorag
         .await?
         .unwrap_or(0);
     storage
-        .add_nfts_to_list(*chain, nft_list.clone(), last_scanned_block)
+        .add_nfts_to_list(chain.clone(), nft_list.clone(), last_scanned_block)
         .await?;
     Ok(nft_list)
 }
@@ -1216,19 +1375,19 @@ where
 async fn update_transfers_with_empty_meta<T>(
     storage: &T,
     chain: &Chain,
-    url: &Url,
+    source: MetadataSource<'_>,
     url_antispam: &Url,
 ) -> MmResult<(), UpdateNftError>
 where
     T: NftListStorageOps + NftTransferHistoryStorageOps,
 {
-    let nft_token_addr_id = storage.get_transfers_with_empty_meta(*chain).await?;
+    let nft_token_addr_id = storage.get_transfers_with_empty_meta(chain.clone()).await?;
     for addr_id_pair in nft_token_addr_id.into_iter() {
-        let mut nft_meta = match get_moralis_metadata(
+        let mut nft_meta = match get_nft_metadata_from_source(
+            source,
             addr_id_pair.token_address.clone(),
             addr_id_pair.token_id,
             chain,
-            url,
             url_antispam,
         )
         .await
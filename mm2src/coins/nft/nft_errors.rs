@@ -43,6 +43,10 @@ pub enum GetNftInfoError {
     ContractTypeIsNull,
     ProtectFromSpamError(ProtectFromSpamError),
     TransferConfirmationsError(TransferConfirmationsError),
+    #[display(fmt = "{} coin doesn't support NFT", coin)]
+    CoinDoesntSupportNft {
+        coin: String,
+    },
 }
 
 impl From<GetNftInfoError> for WithdrawError {
@@ -112,7 +116,9 @@ impl From<TransferConfirmationsError> for GetNftInfoError {
 impl HttpStatusCode for GetNftInfoError {
     fn status_code(&self) -> StatusCode {
         match self {
-            GetNftInfoError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            GetNftInfoError::InvalidRequest(_) | GetNftInfoError::CoinDoesntSupportNft { .. } => {
+                StatusCode::BAD_REQUEST
+            },
             GetNftInfoError::InvalidResponse(_) | GetNftInfoError::ParseRfc3339Err(_) => StatusCode::FAILED_DEPENDENCY,
             GetNftInfoError::ContractTypeIsNull => StatusCode::NOT_FOUND,
             GetNftInfoError::Transport(_)
@@ -315,7 +321,7 @@ impl<T: NftStorageError> From<T> for UpdateSpamPhishingError {
 /// Errors encountered when parsing a `Chain` from a string.
 #[derive(Debug, Display)]
 pub enum ParseChainTypeError {
-    /// The provided string does not correspond to any of the supported blockchain types.
+    /// The provided string is neither a known chain name nor a valid platform coin ticker.
     UnsupportedChainType,
 }
 
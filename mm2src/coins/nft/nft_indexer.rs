@@ -0,0 +1,433 @@
+//! Self-hosted NFT indexer which doesn't depend on the Moralis-compatible proxy.
+//!
+//! NFT transfers are collected from the ERC721 `Transfer` and ERC1155 `TransferSingle`/`TransferBatch` logs
+//! that involve our address, requested from the web3 nodes of the enabled `EthCoin`.
+//! The metadata is requested from the token contracts (`tokenURI`/`uri`) and then fetched directly from the token URI.
+
+use crate::eth::{eth_addr_to_hex, u256_to_big_decimal, EthCoin, ERC1155_CONTRACT, ERC721_CONTRACT};
+use crate::nft::nft_errors::GetNftInfoError;
+use crate::nft::nft_structs::{Chain, ContractType, Nft, NftCommon, NftTransferCommon, NftTransferHistory};
+use crate::nft::{get_domain_from_url, get_fee_details, get_transfer_status, get_uri_meta, protect_from_nft_spam_links};
+use crate::MarketCoinOps;
+use ethabi::{RawLog, Token};
+use ethereum_types::{Address, H256, U256};
+use mm2_err_handle::prelude::*;
+use mm2_number::{BigDecimal, BigUint};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use url::Url;
+use web3::types::{BlockId, BlockNumber, Bytes, CallRequest, FilterBuilder, Log};
+
+#[cfg(not(target_arch = "wasm32"))]
+use mm2_net::native_http::send_request_to_uri;
+
+#[cfg(target_arch = "wasm32")]
+use mm2_net::wasm_http::send_request_to_uri;
+
+/// The number of blocks requested by a single `eth_getLogs` call.
+/// Most public nodes limit the range of the logs request.
+pub(crate) const LOGS_BLOCKS_PER_REQUEST: u64 = 2000;
+/// ERC165 interface id of ERC721.
+const ERC721_INTERFACE_ID: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+/// ERC165 interface id of ERC1155.
+const ERC1155_INTERFACE_ID: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+const IPFS_SCHEME_PREFIX: &str = "ipfs://";
+const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+fn internal_err<E: ToString>(e: E) -> GetNftInfoError { GetNftInfoError::Internal(e.to_string()) }
+
+fn address_from_topic(topic: &H256) -> Address { Address::from_slice(&topic.as_bytes()[12..]) }
+
+fn u256_to_biguint(number: U256) -> MmResult<BigUint, GetNftInfoError> {
+    BigUint::from_str(&number.to_string()).map_to_mm(internal_err)
+}
+
+/// Returns the last block having at least `confirmations` confirmations,
+/// or `None` if the chain is shorter than that.
+pub(crate) async fn get_last_confirmed_block(
+    eth_coin: &EthCoin,
+    confirmations: u64,
+) -> MmResult<Option<u64>, GetNftInfoError> {
+    let current_block = eth_coin.web3.eth().block_number().await?.as_u64();
+    Ok(current_block.checked_sub(confirmations))
+}
+
+/// Returns the NFT transfers of our address in the `[from_block, to_block]` range sorted by block number and log index.
+/// The range should not exceed [`LOGS_BLOCKS_PER_REQUEST`] blocks.
+pub(crate) async fn get_nft_transfers_from_logs(
+    eth_coin: &EthCoin,
+    chain: &Chain,
+    from_block: u64,
+    to_block: u64,
+) -> MmResult<Vec<NftTransferHistory>, GetNftInfoError> {
+    let my_address_str = eth_coin.my_address().map_to_mm(internal_err)?;
+    let my_address = Address::from_str(&my_address_str).map_to_mm(internal_err)?;
+    let my_topic = H256::from(my_address);
+
+    let transfer_721 = ERC721_CONTRACT.event("Transfer").map_to_mm(internal_err)?.signature();
+    let transfer_single = ERC1155_CONTRACT
+        .event("TransferSingle")
+        .map_to_mm(internal_err)?
+        .signature();
+    let transfer_batch = ERC1155_CONTRACT
+        .event("TransferBatch")
+        .map_to_mm(internal_err)?
+        .signature();
+    // Indexed addresses can't be filtered with OR, so the sent and received transfers are requested separately.
+    let topic_filters = [
+        (vec![transfer_721], Some(vec![my_topic]), None, None),
+        (vec![transfer_721], None, Some(vec![my_topic]), None),
+        (vec![transfer_single, transfer_batch], None, Some(vec![my_topic]), None),
+        (vec![transfer_single, transfer_batch], None, None, Some(vec![my_topic])),
+    ];
+
+    let mut transfers = Vec::new();
+    let mut processed_logs = HashSet::new();
+    let mut block_timestamps = HashMap::new();
+    for (topic0, topic1, topic2, topic3) in topic_filters.iter().cloned() {
+        let filter = FilterBuilder::default()
+            .topics(Some(topic0), topic1, topic2, topic3)
+            .from_block(BlockNumber::Number(from_block.into()))
+            .to_block(BlockNumber::Number(to_block.into()))
+            .build();
+        for log in eth_coin.web3.eth().logs(filter).await? {
+            // The transfers to ourselves are returned by both sent and received filters.
+            if !processed_logs.insert((log.transaction_hash, log.log_index)) {
+                continue;
+            }
+            let block_number = match log.block_number {
+                Some(number) => number.as_u64(),
+                None => continue,
+            };
+            let block_timestamp = match block_timestamps.get(&block_number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let timestamp = get_block_timestamp(eth_coin, block_number).await?;
+                    block_timestamps.insert(block_number, timestamp);
+                    timestamp
+                },
+            };
+            let log_transfers = if log.topics[0] == transfer_721 {
+                transfer_721_from_log(log, chain, block_number, block_timestamp, &my_address_str)?
+            } else {
+                transfers_1155_from_log(log, chain, block_number, block_timestamp, &my_address_str)?
+            };
+            for mut transfer in log_transfers {
+                transfer.fee_details = get_fee_details(eth_coin, &transfer.common.transaction_hash).await;
+                transfers.push(transfer);
+            }
+        }
+    }
+
+    transfers.sort_by_key(|transfer| (transfer.block_number, transfer.common.log_index));
+    Ok(transfers)
+}
+
+async fn get_block_timestamp(eth_coin: &EthCoin, block_number: u64) -> MmResult<u64, GetNftInfoError> {
+    let block = eth_coin
+        .web3
+        .eth()
+        .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+        .await?
+        .or_mm_err(|| GetNftInfoError::InvalidResponse(format!("Block {} not found", block_number)))?;
+    Ok(block.timestamp.as_u64())
+}
+
+struct TransferFromLog {
+    contract_type: ContractType,
+    transaction_type: &'static str,
+    operator: Option<Address>,
+    from: Address,
+    to: Address,
+    token_id: BigUint,
+    amount: BigDecimal,
+}
+
+fn build_transfer(
+    log: &Log,
+    chain: &Chain,
+    block_number: u64,
+    block_timestamp: u64,
+    my_address: &str,
+    transfer: TransferFromLog,
+) -> MmResult<NftTransferHistory, GetNftInfoError> {
+    let transaction_hash = log
+        .transaction_hash
+        .or_mm_err(|| GetNftInfoError::InvalidResponse("Log without transaction hash".to_string()))?;
+    let log_index = log
+        .log_index
+        .or_mm_err(|| GetNftInfoError::InvalidResponse("Log without log index".to_string()))?;
+    Ok(NftTransferHistory {
+        common: NftTransferCommon {
+            block_hash: log.block_hash.map(|hash| format!("{:#02x}", hash)),
+            transaction_hash: format!("{:#02x}", transaction_hash),
+            transaction_index: log.transaction_index.map(|index| index.as_u32()),
+            log_index: log_index.as_u32(),
+            value: None,
+            transaction_type: Some(transfer.transaction_type.to_string()),
+            token_address: log.address,
+            from_address: transfer.from,
+            to_address: transfer.to,
+            amount: transfer.amount,
+            verified: None,
+            operator: transfer.operator.as_ref().map(eth_addr_to_hex),
+            possible_spam: false,
+        },
+        chain: chain.clone(),
+        token_id: transfer.token_id,
+        block_number,
+        block_timestamp,
+        contract_type: transfer.contract_type,
+        token_uri: None,
+        token_domain: None,
+        collection_name: None,
+        image_url: None,
+        image_domain: None,
+        token_name: None,
+        status: get_transfer_status(my_address, &eth_addr_to_hex(&transfer.to)),
+        possible_phishing: false,
+        fee_details: None,
+        confirmations: 0,
+    })
+}
+
+pub(crate) fn transfer_721_from_log(
+    log: Log,
+    chain: &Chain,
+    block_number: u64,
+    block_timestamp: u64,
+    my_address: &str,
+) -> MmResult<Vec<NftTransferHistory>, GetNftInfoError> {
+    // ERC20 `Transfer` has the same signature, but its amount isn't indexed.
+    if log.topics.len() != 4 {
+        return Ok(Vec::new());
+    }
+    let transfer = TransferFromLog {
+        contract_type: ContractType::Erc721,
+        transaction_type: "Single",
+        operator: None,
+        from: address_from_topic(&log.topics[1]),
+        to: address_from_topic(&log.topics[2]),
+        token_id: u256_to_biguint(U256::from_big_endian(log.topics[3].as_bytes()))?,
+        amount: 1.into(),
+    };
+    Ok(vec![build_transfer(
+        &log,
+        chain,
+        block_number,
+        block_timestamp,
+        my_address,
+        transfer,
+    )?])
+}
+
+pub(crate) fn transfers_1155_from_log(
+    log: Log,
+    chain: &Chain,
+    block_number: u64,
+    block_timestamp: u64,
+    my_address: &str,
+) -> MmResult<Vec<NftTransferHistory>, GetNftInfoError> {
+    if log.topics.len() != 4 {
+        return Ok(Vec::new());
+    }
+    let is_batch = log.topics[0]
+        == ERC1155_CONTRACT
+            .event("TransferBatch")
+            .map_to_mm(internal_err)?
+            .signature();
+    let event = if is_batch {
+        ERC1155_CONTRACT.event("TransferBatch")
+    } else {
+        ERC1155_CONTRACT.event("TransferSingle")
+    }
+    .map_to_mm(internal_err)?;
+    let parsed = event
+        .parse_log(RawLog {
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        })
+        .map_to_mm(|e| GetNftInfoError::InvalidResponse(e.to_string()))?;
+    let param = |name: &str| {
+        parsed
+            .params
+            .iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value.clone())
+            .or_mm_err(|| GetNftInfoError::InvalidResponse(format!("Missing '{}' param", name)))
+    };
+
+    let ids_values = if is_batch {
+        match (param("ids")?, param("values")?) {
+            (Token::Array(ids), Token::Array(values)) => ids.into_iter().zip(values.into_iter()).collect(),
+            _ => {
+                return MmError::err(GetNftInfoError::InvalidResponse(
+                    "Invalid TransferBatch log".to_string(),
+                ))
+            },
+        }
+    } else {
+        vec![(param("id")?, param("value")?)]
+    };
+
+    let mut transfers = Vec::with_capacity(ids_values.len());
+    for (id, value) in ids_values {
+        let (id, value) = match (id, value) {
+            (Token::Uint(id), Token::Uint(value)) => (id, value),
+            _ => {
+                return MmError::err(GetNftInfoError::InvalidResponse(
+                    "Invalid ERC1155 transfer log".to_string(),
+                ))
+            },
+        };
+        let transfer = TransferFromLog {
+            contract_type: ContractType::Erc1155,
+            transaction_type: if is_batch { "Batch" } else { "Single" },
+            operator: Some(address_from_topic(&log.topics[1])),
+            from: address_from_topic(&log.topics[2]),
+            to: address_from_topic(&log.topics[3]),
+            token_id: u256_to_biguint(id)?,
+            amount: u256_to_big_decimal(value, 0).map_to_mm(internal_err)?,
+        };
+        transfers.push(build_transfer(
+            &log,
+            chain,
+            block_number,
+            block_timestamp,
+            my_address,
+            transfer,
+        )?);
+    }
+    Ok(transfers)
+}
+
+async fn call_contract(
+    eth_coin: &EthCoin,
+    contract: &ethabi::Contract,
+    token_address: Address,
+    function_name: &str,
+    args: &[Token],
+) -> MmResult<Vec<Token>, GetNftInfoError> {
+    let function = contract.function(function_name).map_to_mm(internal_err)?;
+    let data = function.encode_input(args).map_to_mm(internal_err)?;
+    let request = CallRequest {
+        to: Some(token_address),
+        data: Some(Bytes(data)),
+        ..CallRequest::default()
+    };
+    let response = eth_coin
+        .web3
+        .eth()
+        .call(request, Some(BlockId::Number(BlockNumber::Latest)))
+        .await?;
+    function
+        .decode_output(&response.0)
+        .map_to_mm(|e| GetNftInfoError::InvalidResponse(e.to_string()))
+}
+
+async fn call_string_function(
+    eth_coin: &EthCoin,
+    contract: &ethabi::Contract,
+    token_address: Address,
+    function_name: &str,
+    args: &[Token],
+) -> MmResult<String, GetNftInfoError> {
+    match call_contract(eth_coin, contract, token_address, function_name, args)
+        .await?
+        .pop()
+    {
+        Some(Token::String(value)) => Ok(value),
+        _ => MmError::err(GetNftInfoError::InvalidResponse(format!(
+            "Expected string output of '{}'",
+            function_name
+        ))),
+    }
+}
+
+async fn supports_interface(eth_coin: &EthCoin, token_address: Address, interface_id: [u8; 4]) -> bool {
+    let args = [Token::FixedBytes(interface_id.to_vec())];
+    matches!(
+        call_contract(eth_coin, &ERC721_CONTRACT, token_address, "supportsInterface", &args)
+            .await
+            .as_deref(),
+        Ok([Token::Bool(true)])
+    )
+}
+
+/// Converts `ipfs://` URIs to the URIs of the public IPFS gateway.
+pub(crate) fn resolve_ipfs_uri(uri: &str) -> String {
+    match uri.strip_prefix(IPFS_SCHEME_PREFIX) {
+        Some(path) => format!("{}{}", IPFS_GATEWAY, path.trim_start_matches("ipfs/")),
+        None => uri.to_string(),
+    }
+}
+
+/// Requests the NFT metadata from the token contract and its token URI.
+///
+/// The returned `Nft` is not related to our wallet: the caller is responsible for setting
+/// `owner_of`, `amount` and `block_number` from the transfer.
+pub(crate) async fn get_nft_metadata_from_contract(
+    eth_coin: &EthCoin,
+    chain: &Chain,
+    token_address: Address,
+    token_id: BigUint,
+    url_antispam: &Url,
+) -> MmResult<Nft, GetNftInfoError> {
+    let token_id_u256 = U256::from_dec_str(&token_id.to_string()).map_to_mm(internal_err)?;
+    let (contract_type, raw_token_uri) = if supports_interface(eth_coin, token_address, ERC721_INTERFACE_ID).await {
+        let args = [Token::Uint(token_id_u256)];
+        let uri = call_string_function(eth_coin, &ERC721_CONTRACT, token_address, "tokenURI", &args).await?;
+        (ContractType::Erc721, uri)
+    } else if supports_interface(eth_coin, token_address, ERC1155_INTERFACE_ID).await {
+        let args = [Token::Uint(token_id_u256)];
+        let uri = call_string_function(eth_coin, &ERC1155_CONTRACT, token_address, "uri", &args).await?;
+        // https://eips.ethereum.org/EIPS/eip-1155#metadata
+        let uri = uri.replace("{id}", &format!("{:064x}", token_id_u256));
+        (ContractType::Erc1155, uri)
+    } else {
+        return MmError::err(GetNftInfoError::ContractTypeIsNull);
+    };
+
+    // `name` and `symbol` are optional for both standards.
+    let collection_name = call_string_function(eth_coin, &ERC721_CONTRACT, token_address, "name", &[])
+        .await
+        .ok();
+    let symbol = call_string_function(eth_coin, &ERC721_CONTRACT, token_address, "symbol", &[])
+        .await
+        .ok();
+
+    let token_uri = (!raw_token_uri.is_empty()).then(|| resolve_ipfs_uri(&raw_token_uri));
+    let metadata = match token_uri {
+        Some(ref uri) => send_request_to_uri(uri).await.ok().map(|json| json.to_string()),
+        None => None,
+    };
+    // The token URI is already fetched, so the metadata is only parsed here.
+    let uri_meta = get_uri_meta(None, metadata.as_deref(), url_antispam).await;
+
+    let mut nft = Nft {
+        common: NftCommon {
+            token_address,
+            amount: 1.into(),
+            owner_of: Address::zero(),
+            token_hash: None,
+            collection_name,
+            symbol,
+            token_domain: get_domain_from_url(token_uri.as_deref()),
+            token_uri,
+            metadata,
+            last_token_uri_sync: None,
+            last_metadata_sync: None,
+            minter_address: None,
+            possible_spam: false,
+        },
+        chain: chain.clone(),
+        token_id,
+        block_number_minted: None,
+        block_number: 0,
+        contract_type,
+        possible_phishing: false,
+        uri_meta,
+    };
+    protect_from_nft_spam_links(&mut nft, false)?;
+    Ok(nft)
+}
@@ -5,7 +5,7 @@ use mm2_err_handle::prelude::*;
 use mm2_number::{BigDecimal, BigUint};
 use rpc::v1::types::Bytes as BytesJson;
 use serde::de::{self, Deserializer};
-use serde::{Deserialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value as Json;
 use std::collections::HashMap;
 use std::fmt;
@@ -101,43 +101,49 @@ pub struct RefreshMetadataReq {
     pub(crate) url_antispam: Url,
 }
 
-/// Represents blockchains which are supported by NFT feature.
-/// Currently there are only EVM based chains.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum Chain {
-    Avalanche,
-    Bsc,
-    Eth,
-    Fantom,
-    Polygon,
+/// Chain names which were used by the NFT RPCs before the chains became configurable,
+/// mapped to the tickers of their platform coins.
+/// They are still accepted in requests and are kept in the responses and the NFT cache for backward compatibility.
+const LEGACY_CHAIN_NAMES: [(&str, &str); 5] = [
+    ("AVALANCHE", "AVAX"),
+    ("BSC", "BNB"),
+    ("ETH", "ETH"),
+    ("FANTOM", "FTM"),
+    ("POLYGON", "MATIC"),
+];
+
+/// Represents an EVM blockchain supported by NFT feature, identified by the ticker of its platform coin.
+///
+/// The set of chains is taken from the coins config: a platform coin supports NFT
+/// if its protocol is `{"type": "ETH", "protocol_data": {"nft": true}}`, see [`crate::nft::is_nft_chain`].
+///
+/// The ticker is used in the names of the NFT cache tables, where `-` is replaced with `_`,
+/// so the tickers containing `_` are not supported to keep the table names of different chains distinct.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Chain(String);
+
+impl Chain {
+    pub(crate) fn from_ticker(ticker: &str) -> Chain { Chain(ticker.to_owned()) }
+
+    /// Whether the chain was supported before the chains became configurable.
+    pub(crate) fn is_legacy(&self) -> bool { LEGACY_CHAIN_NAMES.iter().any(|(_, ticker)| *ticker == self.0) }
 }
 
 pub(crate) trait ConvertChain {
-    fn to_ticker(&self) -> &'static str;
+    fn to_ticker(&self) -> &str;
 }
 
 impl ConvertChain for Chain {
-    fn to_ticker(&self) -> &'static str {
-        match self {
-            Chain::Avalanche => "AVAX",
-            Chain::Bsc => "BNB",
-            Chain::Eth => "ETH",
-            Chain::Fantom => "FTM",
-            Chain::Polygon => "MATIC",
-        }
-    }
+    fn to_ticker(&self) -> &str { &self.0 }
 }
 
 impl fmt::Display for Chain {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Chain::Avalanche => write!(f, "AVALANCHE"),
-            Chain::Bsc => write!(f, "BSC"),
-            Chain::Eth => write!(f, "ETH"),
-            Chain::Fantom => write!(f, "FANTOM"),
-            Chain::Polygon => write!(f, "POLYGON"),
-        }
+        let name = LEGACY_CHAIN_NAMES
+            .iter()
+            .find(|(_, ticker)| *ticker == self.0)
+            .map_or(self.0.as_str(), |(name, _)| *name);
+        write!(f, "{}", name)
     }
 }
 
@@ -146,19 +152,24 @@ impl FromStr for Chain {
 
     #[inline]
     fn from_str(s: &str) -> Result<Chain, ParseChainTypeError> {
-        match s {
-            "AVALANCHE" => Ok(Chain::Avalanche),
-            "avalanche" => Ok(Chain::Avalanche),
-            "BSC" => Ok(Chain::Bsc),
-            "bsc" => Ok(Chain::Bsc),
-            "ETH" => Ok(Chain::Eth),
-            "eth" => Ok(Chain::Eth),
-            "FANTOM" => Ok(Chain::Fantom),
-            "fantom" => Ok(Chain::Fantom),
-            "POLYGON" => Ok(Chain::Polygon),
-            "polygon" => Ok(Chain::Polygon),
-            _ => Err(ParseChainTypeError::UnsupportedChainType),
+        if let Some((_, ticker)) = LEGACY_CHAIN_NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(s)) {
+            return Ok(Chain::from_ticker(ticker));
+        }
+        // the ticker is used in the names of the NFT cache tables
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(ParseChainTypeError::UnsupportedChainType);
         }
+        Ok(Chain::from_ticker(s))
+    }
+}
+
+/// This implementation will use `Display` to serialize `Chain`.
+impl Serialize for Chain {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
     }
 }
 
@@ -178,9 +189,9 @@ pub(crate) enum ParseContractTypeError {
     UnsupportedContractType,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
-pub(crate) enum ContractType {
+pub enum ContractType {
     Erc1155,
     Erc721,
 }
@@ -598,12 +609,37 @@ pub struct NftTransferHistoryFilters {
 /// * `url`: URL to fetch the NFT data.
 /// * `url_antispam`: URL used to validate if the fetched contract addresses are associated
 /// with spam contracts or if domain fields in the fetched metadata match known phishing domains.
+/// * `indexers`: NFT indexer backends selected per chain. Chains which are not listed use Moralis.
 #[derive(Debug, Deserialize)]
 pub struct UpdateNftReq {
     pub(crate) chains: Vec<Chain>,
     pub(crate) url: Url,
     pub(crate) url_antispam: Url,
-}
+    #[serde(default)]
+    pub(crate) indexers: HashMap<Chain, NftIndexer>,
+}
+
+/// The backend used to index NFT transfers and NFT list of a chain.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(tag = "type")]
+pub enum NftIndexer {
+    /// Transfers and metadata are requested from the Moralis-compatible proxy set in `UpdateNftReq::url`.
+    #[default]
+    Moralis,
+    /// Transfers are collected from the ERC721/ERC1155 transfer logs of the enabled coin's web3 nodes
+    /// and metadata is requested from the token contracts directly.
+    Logs {
+        /// The block to start scanning from if the chain has never been scanned,
+        /// e.g. the block of the wallet's first transaction or of the first NFT contract deployment.
+        from_block: u64,
+        /// The number of confirmations a block needs to be scanned.
+        /// The newer blocks are left to the next update, so the transfers of reorganized blocks aren't stored.
+        #[serde(default = "default_logs_confirmations")]
+        confirmations: u64,
+    },
+}
+
+fn default_logs_confirmations() -> u64 { 12 }
 
 #[derive(Debug, Deserialize, Eq, Hash, PartialEq)]
 pub struct NftTokenAddrId {
@@ -717,7 +753,7 @@ pub(crate) struct PhishingDomainRes {
     pub(crate) result: HashMap<String, bool>,
 }
 
-fn serialize_token_id<S>(token_id: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_token_id<S>(token_id: &BigUint, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -725,7 +761,7 @@ where
     serializer.serialize_str(&token_id_str)
 }
 
-fn deserialize_token_id<'de, D>(deserializer: D) -> Result<BigUint, D::Error>
+pub(crate) fn deserialize_token_id<'de, D>(deserializer: D) -> Result<BigUint, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -1,13 +1,17 @@
 use crate::eth::eth_addr_to_hex;
-use crate::nft::nft_structs::{Chain, NftFromMoralis, NftListFilters, NftTransferHistoryFilters,
-                              NftTransferHistoryFromMoralis, PhishingDomainReq, PhishingDomainRes, SpamContractReq,
-                              SpamContractRes, TransferMeta, UriMeta};
+use crate::nft::nft_indexer::{resolve_ipfs_uri, transfer_721_from_log, transfers_1155_from_log};
+use crate::nft::nft_structs::{Chain, ContractType, ConvertChain, NftFromMoralis, NftIndexer, NftListFilters,
+                              NftTransferHistoryFilters, NftTransferHistoryFromMoralis, PhishingDomainReq,
+                              PhishingDomainRes, SpamContractReq, SpamContractRes, TransferMeta, TransferStatus,
+                              UriMeta};
 use crate::nft::storage::db_test_helpers::{get_nft_ctx, nft, nft_list, nft_transfer_history};
 use crate::nft::storage::{NftListStorageOps, NftTransferHistoryStorageOps, RemoveNftResult};
-use crate::nft::{check_moralis_ipfs_bafy, get_domain_from_url, process_metadata_for_spam_link,
+use crate::nft::{check_moralis_ipfs_bafy, get_domain_from_url, is_nft_chain, process_metadata_for_spam_link,
                  process_text_for_spam_link};
 use common::cross_test;
-use ethereum_types::Address;
+use ethabi::Token;
+use ethereum_types::{Address, H256, U256};
+use mm2_core::mm_ctx::MmCtxBuilder;
 use mm2_net::transport::send_post_request_to_uri;
 use mm2_number::{BigDecimal, BigUint};
 use std::num::NonZeroUsize;
@@ -37,6 +41,44 @@ cross_test!(test_moralis_ipfs_bafy, {
     assert_eq!(expected, res_uri.unwrap());
 });
 
+cross_test!(test_chain_from_str, {
+    // legacy chain names are mapped to the platform coin tickers
+    let bsc = Chain::from_str("bsc").unwrap();
+    assert_eq!(bsc.to_ticker(), "BNB");
+    assert_eq!(bsc.to_string(), "BSC");
+    assert_eq!(Chain::from_str("BNB").unwrap(), bsc);
+
+    let custom = Chain::from_str("ETH-ARB20").unwrap();
+    assert_eq!(custom.to_ticker(), "ETH-ARB20");
+    assert_eq!(custom.to_string(), "ETH-ARB20");
+
+    Chain::from_str("").unwrap_err();
+    Chain::from_str("ETH;DROP").unwrap_err();
+    // "ETH_ARB20" would share the NFT cache tables with "ETH-ARB20"
+    Chain::from_str("ETH_ARB20").unwrap_err();
+});
+
+cross_test!(test_is_nft_chain, {
+    let ctx = MmCtxBuilder::new()
+        .with_conf(json!({
+            "coins": [
+                {"coin": "ETH", "protocol": {"type": "ETH"}},
+                {"coin": "BNB", "protocol": {"type": "ETH", "protocol_data": {"nft": false}}},
+                {"coin": "ETH-ARB20", "protocol": {"type": "ETH"}},
+                {"coin": "ETH-DEV", "protocol": {"type": "ETH", "protocol_data": {"nft": true}}},
+            ]
+        }))
+        .into_mm_arc();
+    // the legacy chains are enabled unless the config disables them
+    assert!(is_nft_chain(&ctx, &Chain::from_str("ETH").unwrap()));
+    assert!(!is_nft_chain(&ctx, &Chain::from_str("BSC").unwrap()));
+    // the other chains are enabled only by the config
+    assert!(!is_nft_chain(&ctx, &Chain::from_str("ETH-ARB20").unwrap()));
+    assert!(is_nft_chain(&ctx, &Chain::from_str("ETH-DEV").unwrap()));
+    // the chains missing in the config are not enabled
+    assert!(!is_nft_chain(&ctx, &Chain::from_str("POLYGON").unwrap()));
+});
+
 cross_test!(test_get_domain_from_url, {
     let image_url = "https://public.nftstatic.com/static/nft/res/4df0a5da04174e1e9be04b22a805f605.png";
     let res_domain = get_domain_from_url(Some(image_url));
@@ -50,6 +92,102 @@ cross_test!(test_invalid_moralis_ipfs_link, {
     assert_eq!(uri, res_uri.unwrap());
 });
 
+cross_test!(test_resolve_ipfs_uri, {
+    let uri = "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/1.json";
+    let expected = "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/1.json";
+    assert_eq!(expected, resolve_ipfs_uri(uri));
+    let uri = "https://example.com/1.json";
+    assert_eq!(uri, resolve_ipfs_uri(uri));
+});
+
+cross_test!(test_logs_indexer_from_json, {
+    let indexer: NftIndexer = serde_json::from_str(r#"{"type": "Logs", "from_block": 100}"#).unwrap();
+    assert!(matches!(indexer, NftIndexer::Logs {
+        from_block: 100,
+        confirmations: 12
+    }));
+    let indexer: NftIndexer =
+        serde_json::from_str(r#"{"type": "Logs", "from_block": 100, "confirmations": 0}"#).unwrap();
+    assert!(matches!(indexer, NftIndexer::Logs {
+        from_block: 100,
+        confirmations: 0
+    }));
+    // scanning from the genesis block by mistake would take too long
+    serde_json::from_str::<NftIndexer>(r#"{"type": "Logs"}"#).unwrap_err();
+});
+
+cross_test!(test_nft_transfers_from_logs, {
+    let my_address = Address::from_str(TEST_WALLET_ADDR_EVM).unwrap();
+    let other_address = Address::from_str(TOKEN_ADD).unwrap();
+    let log = |topics: Vec<H256>, data: Vec<u8>| web3::types::Log {
+        address: other_address,
+        topics,
+        data: web3::types::Bytes(data),
+        block_hash: Some(H256::repeat_byte(1)),
+        block_number: Some(100u64.into()),
+        transaction_hash: Some(H256::from_str(TX_HASH).unwrap()),
+        transaction_index: Some(2u64.into()),
+        log_index: Some(LOG_INDEX.into()),
+        transaction_log_index: None,
+        log_type: None,
+        removed: None,
+    };
+
+    let transfer_signature = crate::eth::ERC721_CONTRACT.event("Transfer").unwrap().signature();
+    let erc721_log = log(
+        vec![
+            transfer_signature,
+            H256::from(other_address),
+            H256::from(my_address),
+            H256::from_low_u64_be(7),
+        ],
+        Vec::new(),
+    );
+    let transfers =
+        transfer_721_from_log(erc721_log, &Chain::from_ticker("ETH"), 100, 1000, TEST_WALLET_ADDR_EVM).unwrap();
+    assert_eq!(transfers.len(), 1);
+    assert!(matches!(transfers[0].contract_type, ContractType::Erc721));
+    assert_eq!(transfers[0].status, TransferStatus::Receive);
+    assert_eq!(transfers[0].token_id, BigUint::from(7u32));
+    assert_eq!(transfers[0].common.transaction_hash, TX_HASH);
+
+    // ERC20 transfer has the same signature, but the amount is not indexed
+    let erc20_log = log(
+        vec![transfer_signature, H256::from(other_address), H256::from(my_address)],
+        ethabi::encode(&[Token::Uint(7u64.into())]),
+    );
+    assert!(
+        transfer_721_from_log(erc20_log, &Chain::from_ticker("ETH"), 100, 1000, TEST_WALLET_ADDR_EVM)
+            .unwrap()
+            .is_empty()
+    );
+
+    let batch_signature = crate::eth::ERC1155_CONTRACT.event("TransferBatch").unwrap().signature();
+    let data = ethabi::encode(&[
+        Token::Array(vec![Token::Uint(1u64.into()), Token::Uint(2u64.into())]),
+        Token::Array(vec![Token::Uint(3u64.into()), Token::Uint(U256::from(4))]),
+    ]);
+    let batch_log = log(
+        vec![
+            batch_signature,
+            H256::from(other_address),
+            H256::from(my_address),
+            H256::from(other_address),
+        ],
+        data,
+    );
+    let transfers =
+        transfers_1155_from_log(batch_log, &Chain::from_ticker("ETH"), 100, 1000, TEST_WALLET_ADDR_EVM).unwrap();
+    assert_eq!(transfers.len(), 2);
+    assert!(transfers
+        .iter()
+        .all(|t| t.status == TransferStatus::Send && matches!(t.contract_type, ContractType::Erc1155)));
+    assert_eq!(transfers[0].token_id, BigUint::from(1u32));
+    assert_eq!(transfers[0].common.amount, BigDecimal::from(3));
+    assert_eq!(transfers[1].token_id, BigUint::from(2u32));
+    assert_eq!(transfers[1].common.amount, BigDecimal::from(4));
+});
+
 cross_test!(test_check_for_spam_links, {
     let mut spam_text = Some("https://arweave.net".to_string());
     assert!(process_text_for_spam_link(&mut spam_text, true).unwrap());
@@ -118,7 +256,7 @@ cross_test!(test_moralis_requests, {
 
 cross_test!(test_antispam_scan_endpoints, {
     let req_spam = SpamContractReq {
-        network: Chain::Eth,
+        network: Chain::from_ticker("ETH"),
         addresses: "0x0ded8542fc8b2b4e781b96e99fee6406550c9b7c,0x8d1355b65da254f2cc4611453adfa8b7a13f60ee".to_string(),
     };
     let uri_contract = format!("{}/api/blocklist/contract/scan", BLOCKLIST_API_ENDPOINT);
@@ -156,12 +294,15 @@ cross_test!(test_camo, {
 });
 
 cross_test!(test_add_get_nfts, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let token_id = BigUint::from_str(TOKEN_ID).unwrap();
     let nft = storage
@@ -173,12 +314,15 @@ cross_test!(test_add_get_nfts, {
 });
 
 cross_test!(test_last_nft_block, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let last_block = NftListStorageOps::get_last_block_number(&storage, &chain)
         .await
@@ -188,12 +332,15 @@ cross_test!(test_last_nft_block, {
 });
 
 cross_test!(test_nft_list, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let nft_list = storage
         .get_nft_list(vec![chain], false, 1, Some(NonZeroUsize::new(3).unwrap()), None)
@@ -207,12 +354,15 @@ cross_test!(test_nft_list, {
 });
 
 cross_test!(test_remove_nft, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let token_id = BigUint::from_str(TOKEN_ID).unwrap();
     let remove_rslt = storage
@@ -221,7 +371,7 @@ cross_test!(test_remove_nft, {
         .unwrap();
     assert_eq!(remove_rslt, RemoveNftResult::NftRemoved);
     let list_len = storage
-        .get_nft_list(vec![chain], true, 1, None, None)
+        .get_nft_list(vec![chain.clone()], true, 1, None, None)
         .await
         .unwrap()
         .nfts
@@ -232,13 +382,13 @@ cross_test!(test_remove_nft, {
 });
 
 cross_test!(test_nft_amount, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let mut nft = nft();
     storage
-        .add_nfts_to_list(chain, vec![nft.clone()], 25919780)
+        .add_nfts_to_list(chain.clone(), vec![nft.clone()], 25919780)
         .await
         .unwrap();
 
@@ -270,14 +420,14 @@ cross_test!(test_nft_amount, {
 });
 
 cross_test!(test_refresh_metadata, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let new_symbol = "NEW_SYMBOL";
     let mut nft = nft();
     storage
-        .add_nfts_to_list(chain, vec![nft.clone()], 25919780)
+        .add_nfts_to_list(chain.clone(), vec![nft.clone()], 25919780)
         .await
         .unwrap();
     nft.common.symbol = Some(new_symbol.to_string());
@@ -290,12 +440,15 @@ cross_test!(test_refresh_metadata, {
 });
 
 cross_test!(test_update_nft_spam_by_token_address, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     storage
         .update_nft_spam_by_token_address(&chain, TOKEN_ADD.to_string(), true)
@@ -311,12 +464,15 @@ cross_test!(test_update_nft_spam_by_token_address, {
 });
 
 cross_test!(test_exclude_nft_spam, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let filters = NftListFilters {
         exclude_spam: true,
@@ -330,12 +486,15 @@ cross_test!(test_exclude_nft_spam, {
 });
 
 cross_test!(test_get_animation_external_domains, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let domains = storage.get_animation_external_domains(&chain).await.unwrap();
     assert_eq!(2, domains.len());
@@ -344,12 +503,15 @@ cross_test!(test_get_animation_external_domains, {
 });
 
 cross_test!(test_update_nft_phishing_by_domain, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     let domains = vec![
         "tikimetadata.s3.amazonaws.com".to_string(),
@@ -372,12 +534,15 @@ cross_test!(test_update_nft_phishing_by_domain, {
 });
 
 cross_test!(test_exclude_nft_phishing_spam, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftListStorageOps::init(&storage, &chain).await.unwrap();
     let nft_list = nft_list();
-    storage.add_nfts_to_list(chain, nft_list, 28056726).await.unwrap();
+    storage
+        .add_nfts_to_list(chain.clone(), nft_list, 28056726)
+        .await
+        .unwrap();
 
     storage
         .update_nft_phishing_by_domain(&chain, "tikimetadata.s3.amazonaws.com".to_string(), true)
@@ -396,16 +561,19 @@ cross_test!(test_exclude_nft_phishing_spam, {
 });
 
 cross_test!(test_add_get_transfers, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let token_id = BigUint::from_str(TOKEN_ID).unwrap();
     let transfer1 = storage
-        .get_transfers_by_token_addr_id(chain, TOKEN_ADD.to_string(), token_id)
+        .get_transfers_by_token_addr_id(chain.clone(), TOKEN_ADD.to_string(), token_id)
         .await
         .unwrap()
         .get(0)
@@ -423,12 +591,15 @@ cross_test!(test_add_get_transfers, {
 });
 
 cross_test!(test_last_transfer_block, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let last_block = NftTransferHistoryStorageOps::get_last_block_number(&storage, &chain)
         .await
@@ -438,12 +609,15 @@ cross_test!(test_last_transfer_block, {
 });
 
 cross_test!(test_transfer_history, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let transfer_history = storage
         .get_transfer_history(vec![chain], false, 1, Some(NonZeroUsize::new(3).unwrap()), None)
@@ -457,12 +631,15 @@ cross_test!(test_transfer_history, {
 });
 
 cross_test!(test_transfer_history_filters, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let filters = NftTransferHistoryFilters {
         receive: true,
@@ -492,7 +669,7 @@ cross_test!(test_transfer_history_filters, {
     };
 
     let transfer_history = storage
-        .get_transfer_history(vec![chain], true, 1, None, Some(filters))
+        .get_transfer_history(vec![chain.clone()], true, 1, None, Some(filters))
         .await
         .unwrap();
     assert_eq!(transfer_history.transfer_history.len(), 4);
@@ -500,7 +677,7 @@ cross_test!(test_transfer_history_filters, {
     assert_eq!(transfer.block_number, 28056726);
 
     let transfer_history1 = storage
-        .get_transfer_history(vec![chain], true, 1, None, Some(filters1))
+        .get_transfer_history(vec![chain.clone()], true, 1, None, Some(filters1))
         .await
         .unwrap();
     assert_eq!(transfer_history1.transfer_history.len(), 1);
@@ -519,14 +696,17 @@ cross_test!(test_transfer_history_filters, {
 });
 
 cross_test!(test_get_update_transfer_meta, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
-    let vec_token_add_id = storage.get_transfers_with_empty_meta(chain).await.unwrap();
+    let vec_token_add_id = storage.get_transfers_with_empty_meta(chain.clone()).await.unwrap();
     assert_eq!(vec_token_add_id.len(), 3);
 
     let token_add = "0x5c7d6712dfaf0cb079d48981781c8705e8417ca0".to_string();
@@ -554,12 +734,15 @@ cross_test!(test_get_update_transfer_meta, {
 });
 
 cross_test!(test_update_transfer_spam_by_token_address, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     storage
         .update_transfer_spam_by_token_address(&chain, TOKEN_ADD.to_string(), true)
@@ -575,24 +758,30 @@ cross_test!(test_update_transfer_spam_by_token_address, {
 });
 
 cross_test!(test_get_token_addresses, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let token_addresses = storage.get_token_addresses(chain).await.unwrap();
     assert_eq!(token_addresses.len(), 2);
 });
 
 cross_test!(test_exclude_transfer_spam, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let filters = NftTransferHistoryFilters {
         receive: true,
@@ -610,12 +799,15 @@ cross_test!(test_exclude_transfer_spam, {
 });
 
 cross_test!(test_get_domains, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let domains = storage.get_domains(&chain).await.unwrap();
     assert_eq!(2, domains.len());
@@ -624,12 +816,15 @@ cross_test!(test_get_domains, {
 });
 
 cross_test!(test_update_transfer_phishing_by_domain, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     let domains = vec![
         "tikimetadata.s3.amazonaws.com".to_string(),
@@ -652,12 +847,15 @@ cross_test!(test_update_transfer_phishing_by_domain, {
 });
 
 cross_test!(test_exclude_transfer_phishing_spam, {
-    let chain = Chain::Bsc;
+    let chain = Chain::from_ticker("BNB");
     let nft_ctx = get_nft_ctx(&chain).await;
     let storage = nft_ctx.lock_db().await.unwrap();
     NftTransferHistoryStorageOps::init(&storage, &chain).await.unwrap();
     let transfers = nft_transfer_history();
-    storage.add_transfers_to_history(chain, transfers).await.unwrap();
+    storage
+        .add_transfers_to_history(chain.clone(), transfers)
+        .await
+        .unwrap();
 
     storage
         .update_transfer_phishing_by_domain(&chain, "tikimetadata.s3.amazonaws.com".to_string(), true)
@@ -672,7 +870,7 @@ cross_test!(test_exclude_transfer_phishing_spam, {
         exclude_phishing: true,
     };
     let transfers = storage
-        .get_transfer_history(vec![chain], true, 1, None, Some(filters))
+        .get_transfer_history(vec![chain.clone()], true, 1, None, Some(filters))
         .await
         .unwrap()
         .transfer_history;
@@ -29,7 +29,7 @@ pub(crate) fn nft() -> Nft {
             minter_address: Some("ERC1155 tokens don't have a single minter".to_string()),
             possible_spam: true,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: Default::default(),
         block_number_minted: Some(25465916),
         block_number: 25919780,
@@ -68,7 +68,7 @@ pub(crate) fn nft_list() -> Vec<Nft> {
             minter_address: Some("ERC1155 tokens don't have a single minter".to_string()),
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: Default::default(),
         block_number_minted: Some(25465916),
         block_number: 25919780,
@@ -108,7 +108,7 @@ pub(crate) fn nft_list() -> Vec<Nft> {
             minter_address: Some("0xdbdeb0895f3681b87fb3654b5cf3e05546ba24a9".to_string()),
             possible_spam: true,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300047252").unwrap(),
         block_number_minted: Some(25721963),
         block_number: 28056726,
@@ -150,7 +150,7 @@ pub(crate) fn nft_list() -> Vec<Nft> {
             minter_address: Some("0xdbdeb0895f3681b87fb3654b5cf3e05546ba24a9".to_string()),
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300047253").unwrap(),
         block_number_minted: Some(25721963),
         block_number: 28056726,
@@ -192,7 +192,7 @@ pub(crate) fn nft_list() -> Vec<Nft> {
             minter_address: Some("0xdbdeb0895f3681b87fb3654b5cf3e05546ba24a9".to_string()),
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300044414").unwrap(),
         block_number_minted: Some(25810308),
         block_number: 28056721,
@@ -234,7 +234,7 @@ pub(crate) fn nft_transfer_history() -> Vec<NftTransferHistory> {
             operator: Some("0x4ff0bbc9b64d635a4696d1a38554fb2529c103ff".to_string()),
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: Default::default(),
         block_number: 25919780,
         block_timestamp: 1677166110,
@@ -267,7 +267,7 @@ pub(crate) fn nft_transfer_history() -> Vec<NftTransferHistory> {
             operator: None,
             possible_spam: true,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300047252").unwrap(),
         block_number: 28056726,
         block_timestamp: 1683627432,
@@ -301,7 +301,7 @@ pub(crate) fn nft_transfer_history() -> Vec<NftTransferHistory> {
             operator: None,
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300047253").unwrap(),
         block_number: 28056726,
         block_timestamp: 1683627432,
@@ -334,7 +334,7 @@ pub(crate) fn nft_transfer_history() -> Vec<NftTransferHistory> {
             operator: None,
             possible_spam: false,
         },
-        chain: Chain::Bsc,
+        chain: Chain::from_ticker("BNB"),
         token_id: BigUint::from_str("214300044414").unwrap(),
         block_number: 28056721,
         block_timestamp: 1683627417,
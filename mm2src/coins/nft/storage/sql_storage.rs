@@ -24,13 +24,13 @@ use std::str::FromStr;
 
 impl Chain {
     fn nft_list_table_name(&self) -> SqlResult<String> {
-        let name = self.to_ticker().to_owned() + "_nft_list";
+        let name = self.to_ticker().replace('-', "_") + "_nft_list";
         validate_table_name(&name)?;
         Ok(name)
     }
 
     fn transfer_history_table_name(&self) -> SqlResult<String> {
-        let name = self.to_ticker().to_owned() + "_nft_transfer_history";
+        let name = self.to_ticker().replace('-', "_") + "_nft_transfer_history";
         validate_table_name(&name)?;
         Ok(name)
     }
@@ -127,6 +127,10 @@ impl Qrc20GenTxError {
 pub struct Qrc20ActivationParams {
     swap_contract_address: H160,
     fallback_swap_contract: Option<H160>,
+    /// Whether the transaction history is fetched by the V2 loop serving `my_tx_history_v2`
+    /// instead of the legacy one serving `my_tx_history`, if `tx_history` is enabled.
+    #[serde(default)]
+    tx_history_v2: bool,
     #[serde(flatten)]
     utxo_params: UtxoActivationParams,
 }
@@ -148,10 +152,12 @@ impl Qrc20ActivationParams {
             .map_to_mm(Qrc20FromLegacyReqErr::InvalidSwapContractAddr)?;
         let fallback_swap_contract = json::from_value(req["fallback_swap_contract"].clone())
             .map_to_mm(Qrc20FromLegacyReqErr::InvalidFallbackSwapContract)?;
+        let tx_history_v2 = req["tx_history_v2"].as_bool().unwrap_or_default();
         let utxo_params = UtxoActivationParams::from_legacy_req(req)?;
         Ok(Qrc20ActivationParams {
             swap_contract_address,
             fallback_swap_contract,
+            tx_history_v2,
             utxo_params,
         })
     }
@@ -297,6 +303,7 @@ impl<'a> UtxoCoinBuilder for Qrc20CoinBuilder<'a> {
             contract_address: self.token_contract_address,
             swap_contract_address: self.activation_params.swap_contract_address,
             fallback_swap_contract: self.activation_params.fallback_swap_contract,
+            tx_history_v2: self.activation_params.tx_history_v2,
         };
         Ok(Qrc20Coin(Arc::new(inner)))
     }
@@ -342,6 +349,8 @@ pub struct Qrc20CoinFields {
     pub contract_address: H160,
     pub swap_contract_address: H160,
     pub fallback_swap_contract: Option<H160>,
+    /// Whether the transaction history is fetched by the V2 loop instead of the legacy one.
+    pub tx_history_v2: bool,
 }
 
 #[derive(Clone)]
@@ -371,6 +380,7 @@ impl From<ContractCallOutput> for TransactionOutput {
         TransactionOutput {
             value: out.value,
             script_pubkey: out.script_pubkey,
+            token: None,
         }
     }
 }
@@ -1335,8 +1345,18 @@ impl MmCoin for Qrc20Coin {
 
     fn validate_address(&self, address: &str) -> ValidateAddressResult { utxo_common::validate_address(self, address) }
 
+    /// Runs the V2 history loop serving `my_tx_history_v2` if it's enabled by `tx_history_v2` activation param,
+    /// or the legacy one serving `my_tx_history` otherwise.
     fn process_history_loop(&self, ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> {
-        Box::new(self.clone().history_loop(ctx).map(|_| Ok(())).boxed().compat())
+        let coin = self.clone();
+        let fut = async move {
+            if coin.tx_history_v2 {
+                coin.tx_history_v2_loop(ctx).await
+            } else {
+                coin.history_loop(ctx).await
+            }
+        };
+        Box::new(fut.map(|_| Ok(())).boxed().compat())
     }
 
     fn history_sync_status(&self) -> HistorySyncState { utxo_common::history_sync_status(&self.utxo) }
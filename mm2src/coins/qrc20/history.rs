@@ -1,6 +1,10 @@
 use super::*;
-use crate::utxo::{RequestTxHistoryResult, UtxoFeeDetails};
-use crate::{CoinsContext, TxFeeDetails, TxHistoryResult};
+use crate::my_tx_history_v2::{CoinWithTxHistoryV2, MyTxHistoryErrorV2, MyTxHistoryTarget, TxHistoryStorage};
+use crate::tx_history_storage::{GetTxHistoryFilters, TxHistoryStorageBuilder, WalletId};
+use crate::utxo::utxo_tx_history_v2::{utxo_history_loop, UtxoMyAddressesHistoryError, UtxoTxDetailsError,
+                                      UtxoTxDetailsParams, UtxoTxHistoryOps};
+use crate::utxo::{GetBlockHeaderError, RequestTxHistoryResult, UtxoFeeDetails};
+use crate::{BalanceResult, BlockHeightAndTime, CoinsContext, TxFeeDetails, TxHistoryResult};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use common::jsonrpc_client::JsonRpcErrorType;
 use itertools::Itertools;
@@ -188,6 +192,29 @@ impl Qrc20Coin {
         }
     }
 
+    /// Runs the [`utxo_history_loop`] state machine that fetches the token transfers
+    /// and stores them in the transaction history V2 storage.
+    pub async fn tx_history_v2_loop(self, ctx: MmArc) {
+        let storage = match TxHistoryStorageBuilder::new(&ctx).build() {
+            Ok(storage) => storage,
+            Err(e) => {
+                error!("Error {} on creating the tx history storage for {}", e, self.ticker());
+                return;
+            },
+        };
+        let balances = match UtxoTxHistoryOps::my_addresses_balances(&self).await {
+            Ok(balances) => balances,
+            Err(e) => {
+                error!("Error {} on balance fetching for the coin {}", e, self.ticker());
+                HashMap::new()
+            },
+        };
+        utxo_history_loop(self, storage, ctx.metrics.clone(), balances).await
+    }
+
+    /// The token contract address the transfers are keyed by in the transaction history V2 storage.
+    fn history_token_id(&self) -> BytesJson { self.contract_address.0.to_vec().into() }
+
     pub async fn transfer_details_by_hash(&self, tx_hash: H256Json) -> Result<TxTransferMap, String> {
         let receipts = try_s!(self.utxo.rpc_client.get_transaction_receipts(&tx_hash).compat().await);
         // request Qtum transaction details to get a tx_hex, timestamp, block_height and calculate a miner_fee
@@ -338,34 +365,7 @@ impl Qrc20Coin {
         let history_res = TransferHistoryBuilder::new(self.clone()).build_tx_idents().await;
         let history = match history_res {
             Ok(h) => h,
-            Err(e) => match e.into_inner() {
-                UtxoRpcError::Transport(json_rpc_e) | UtxoRpcError::ResponseParseError(json_rpc_e) => {
-                    match json_rpc_e.error {
-                        JsonRpcErrorType::Response(_addr, err) => {
-                            return if HISTORY_TOO_LARGE_ERROR.eq(&err) {
-                                RequestTxHistoryResult::HistoryTooLarge
-                            } else {
-                                RequestTxHistoryResult::Retry {
-                                    error: ERRL!("Error {:?} on blockchain_contract_event_get_history", err),
-                                }
-                            }
-                        },
-                        JsonRpcErrorType::InvalidRequest(err)
-                        | JsonRpcErrorType::Transport(err)
-                        | JsonRpcErrorType::Parse(_, err)
-                        | JsonRpcErrorType::Internal(err) => {
-                            return RequestTxHistoryResult::Retry {
-                                error: ERRL!("Error {} on blockchain_contract_event_get_history", err),
-                            };
-                        },
-                    }
-                },
-                UtxoRpcError::InvalidResponse(e) | UtxoRpcError::Internal(e) => {
-                    return RequestTxHistoryResult::Retry {
-                        error: ERRL!("Error {} on blockchain_contract_event_get_history", e),
-                    }
-                },
-            },
+            Err(e) => return request_tx_history_error(e.into_inner()),
         };
         mm_counter!(metrics, "tx.history.response.count", 1,
                     "coin" => self.utxo.conf.ticker.clone(), "client" => "electrum", "method" => "blockchain.contract.event.get_history");
@@ -562,6 +562,101 @@ impl Qrc20Coin {
     }
 }
 
+#[async_trait]
+impl CoinWithTxHistoryV2 for Qrc20Coin {
+    /// QRC20 tokens are stored separately from the platform coin,
+    /// because the history of the platform coin is fetched by another state machine.
+    fn history_wallet_id(&self) -> WalletId { WalletId::new(self.ticker().to_owned()) }
+
+    async fn get_tx_history_filters(
+        &self,
+        target: MyTxHistoryTarget,
+    ) -> MmResult<GetTxHistoryFilters, MyTxHistoryErrorV2> {
+        match target {
+            MyTxHistoryTarget::Iguana => (),
+            target => return MmError::err(MyTxHistoryErrorV2::with_expected_target(target, "Iguana")),
+        }
+        let my_address = self.my_address()?;
+        let token_id = format!("{:02x}", self.history_token_id());
+        Ok(GetTxHistoryFilters::for_address(my_address).with_token_id(token_id))
+    }
+}
+
+#[async_trait]
+impl UtxoTxHistoryOps for Qrc20Coin {
+    async fn my_addresses(&self) -> MmResult<HashSet<Address>, UtxoMyAddressesHistoryError> {
+        let my_address = self.utxo.derivation_method.single_addr_or_err()?;
+        Ok(std::iter::once(my_address.clone()).collect())
+    }
+
+    /// Returns the details of every `Transfer` event of the token contract that the transaction contains.
+    async fn tx_details_by_hash<Storage>(
+        &self,
+        params: UtxoTxDetailsParams<'_, Storage>,
+    ) -> MmResult<Vec<TransactionDetails>, UtxoTxDetailsError>
+    where
+        Storage: TxHistoryStorage,
+    {
+        let transfer_map = self
+            .transfer_details_by_hash(*params.hash)
+            .await
+            .map_to_mm(UtxoTxDetailsError::Internal)?;
+        let token_id = self.history_token_id();
+
+        let details = transfer_map
+            .into_iter()
+            .sorted_by(|(id_x, _), (id_y, _)| id_x.cmp(id_y))
+            .map(|(_id, mut tx)| {
+                if let Some(BlockHeightAndTime { height, timestamp }) = params.block_height_and_time {
+                    tx.block_height = height;
+                    tx.timestamp = timestamp;
+                }
+                tx.transaction_type = TransactionType::TokenTransfer(token_id.clone());
+                tx
+            })
+            .collect();
+        Ok(details)
+    }
+
+    async fn tx_from_storage_or_rpc<Storage: TxHistoryStorage>(
+        &self,
+        tx_hash: &H256Json,
+        storage: &Storage,
+    ) -> MmResult<UtxoTx, UtxoTxDetailsError> {
+        utxo_common::utxo_tx_history_v2_common::tx_from_storage_or_rpc(self, tx_hash, storage).await
+    }
+
+    /// Requests the ids of the transactions containing `Transfer` events of the token contract
+    /// the same way as the legacy history loop does.
+    async fn request_tx_history(
+        &self,
+        metrics: MetricsArc,
+        _for_addresses: &HashSet<Address>,
+    ) -> RequestTxHistoryResult {
+        Qrc20Coin::request_tx_history(self, metrics).await
+    }
+
+    async fn get_block_timestamp(&self, height: u64) -> MmResult<u64, GetBlockHeaderError> {
+        self.utxo.rpc_client.get_block_timestamp(height).await
+    }
+
+    async fn my_addresses_balances(&self) -> BalanceResult<HashMap<String, BigDecimal>> {
+        let my_address = self
+            .my_address()
+            .map_err(|err| BalanceError::Internal(err.to_string()))?;
+        let my_balance = self.my_balance().compat().await?;
+        Ok(std::iter::once((my_address, my_balance.into_total())).collect())
+    }
+
+    fn address_from_str(&self, address: &str) -> MmResult<Address, AddrFromStrError> {
+        utxo_common::checked_address_from_str(self, address)
+    }
+
+    fn set_history_sync_state(&self, new_state: HistorySyncState) {
+        *self.as_ref().history_sync_state.lock().unwrap() = new_state;
+    }
+}
+
 pub struct TransferHistoryBuilder {
     coin: Qrc20Coin,
     from_block: u64,
@@ -755,6 +850,32 @@ impl BuildTransferHistory for NativeClient {
     }
 }
 
+/// Maps the `blockchain.contract.event.get_history` error to the history loop action.
+fn request_tx_history_error(error: UtxoRpcError) -> RequestTxHistoryResult {
+    match error {
+        UtxoRpcError::Transport(json_rpc_e) | UtxoRpcError::ResponseParseError(json_rpc_e) => match json_rpc_e.error {
+            JsonRpcErrorType::Response(_addr, err) => {
+                if HISTORY_TOO_LARGE_ERROR.eq(&err) {
+                    RequestTxHistoryResult::HistoryTooLarge
+                } else {
+                    RequestTxHistoryResult::Retry {
+                        error: ERRL!("Error {:?} on blockchain_contract_event_get_history", err),
+                    }
+                }
+            },
+            JsonRpcErrorType::InvalidRequest(err)
+            | JsonRpcErrorType::Transport(err)
+            | JsonRpcErrorType::Parse(_, err)
+            | JsonRpcErrorType::Internal(err) => RequestTxHistoryResult::Retry {
+                error: ERRL!("Error {} on blockchain_contract_event_get_history", err),
+            },
+        },
+        UtxoRpcError::InvalidResponse(e) | UtxoRpcError::Internal(e) => RequestTxHistoryResult::Retry {
+            error: ERRL!("Error {} on blockchain_contract_event_get_history", e),
+        },
+    }
+}
+
 fn is_transferred_from_contract(script_pubkey: &Script) -> bool {
     let contract_call_bytes = match extract_contract_call_from_script(script_pubkey) {
         Ok(bytes) => bytes,
@@ -814,6 +935,7 @@ fn is_transfer_event_log(log: &LogEntry) -> bool {
 mod tests {
     use super::*;
     use common::block_on;
+    use common::jsonrpc_client::{JsonRpcError, JsonRpcRequest, JsonRpcRequestEnum};
     use mm2_metrics::{MetricType, MetricsJson, MetricsOps};
     use mm2_test_helpers::for_tests::find_metrics_in_json;
     use qrc20_tests::qrc20_coin_for_test;
@@ -1030,4 +1152,50 @@ mod tests {
         assert!(updated);
         assert_eq!(history_map, history_map_expected);
     }
+
+    #[test]
+    fn test_request_tx_history_error() {
+        let json_rpc_error = |error| JsonRpcError {
+            client_info: "coin: QRC20".to_owned(),
+            request: JsonRpcRequestEnum::Single(JsonRpcRequest {
+                jsonrpc: "2.0".to_owned(),
+                id: "1".to_owned(),
+                method: "blockchain.contract.event.get_history".to_owned(),
+                params: Vec::new(),
+            }),
+            error,
+        };
+
+        let error = UtxoRpcError::ResponseParseError(json_rpc_error(JsonRpcErrorType::Response(
+            "electrum1.cipig.net:10071".to_owned().into(),
+            HISTORY_TOO_LARGE_ERROR.clone(),
+        )));
+        assert!(matches!(
+            request_tx_history_error(error),
+            RequestTxHistoryResult::HistoryTooLarge
+        ));
+
+        let error = UtxoRpcError::ResponseParseError(json_rpc_error(JsonRpcErrorType::Response(
+            "electrum1.cipig.net:10071".to_owned().into(),
+            json!({"code": 2, "message": "daemon error"}),
+        )));
+        assert!(matches!(
+            request_tx_history_error(error),
+            RequestTxHistoryResult::Retry { .. }
+        ));
+
+        let error = UtxoRpcError::Transport(json_rpc_error(JsonRpcErrorType::Transport(
+            "connection refused".to_owned(),
+        )));
+        assert!(matches!(
+            request_tx_history_error(error),
+            RequestTxHistoryResult::Retry { .. }
+        ));
+
+        let error = UtxoRpcError::InvalidResponse("unexpected response".to_owned());
+        assert!(matches!(
+            request_tx_history_error(error),
+            RequestTxHistoryResult::Retry { .. }
+        ));
+    }
 }
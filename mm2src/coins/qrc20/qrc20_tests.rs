@@ -1,6 +1,8 @@
 use super::*;
+use crate::my_tx_history_v2::for_tests::init_storage_for;
 use crate::utxo::rpc_clients::UnspentInfo;
-use crate::{DexFee, TxFeeDetails, WaitForHTLCTxSpendArgs};
+use crate::utxo::utxo_tx_history_v2::{UtxoTxDetailsParams, UtxoTxHistoryOps};
+use crate::{BlockHeightAndTime, DexFee, TxFeeDetails, WaitForHTLCTxSpendArgs};
 use chain::OutPoint;
 use common::{block_on, wait_until_sec, DEX_FEE_ADDR_RAW_PUBKEY};
 use crypto::Secp256k1Secret;
@@ -734,6 +736,51 @@ fn test_transfer_details_by_hash() {
     assert!(it.next().is_none());
 }
 
+#[test]
+fn test_tx_details_by_hash_v2() {
+    // priv_key of qXxsj5RtciAby9T7m98AgAATL4zTi4UwDG
+    let priv_key = [
+        3, 98, 177, 3, 108, 39, 234, 144, 131, 178, 103, 103, 127, 80, 230, 166, 53, 68, 147, 215, 42, 216, 144, 72,
+        172, 110, 180, 13, 123, 179, 10, 49,
+    ];
+    let (_ctx, coin) = qrc20_coin_for_test(priv_key, None);
+    let (_ctx, storage) = init_storage_for(&coin);
+    let tx_hash: H256Json = hex::decode("85ede12ccc12fb1709c4d9e403e96c0c394b0916f2f6098d41d8dfa00013fcdb")
+        .unwrap()
+        .as_slice()
+        .into();
+
+    let transfer_map = block_on(coin.transfer_details_by_hash(tx_hash)).unwrap();
+    let expected_ids: Vec<_> = transfer_map
+        .into_iter()
+        .sorted_by(|(id_x, _), (id_y, _)| id_x.cmp(id_y))
+        .map(|(id, _)| BytesJson::from(id))
+        .collect();
+
+    let my_addresses = block_on(coin.my_addresses()).unwrap();
+    let params = UtxoTxDetailsParams {
+        hash: &tx_hash,
+        block_height_and_time: Some(BlockHeightAndTime {
+            height: 699545,
+            timestamp: 1602997840,
+        }),
+        storage: &storage,
+        my_addresses: &my_addresses,
+    };
+    let details = block_on(UtxoTxHistoryOps::tx_details_by_hash(&coin, params)).unwrap();
+    let actual_ids: Vec<_> = details.iter().map(|tx| tx.internal_id.clone()).collect();
+    // the transaction contains several transfers, every transfer is stored as a separate history item
+    assert!(actual_ids.len() > 1);
+    assert_eq!(actual_ids, expected_ids);
+
+    let expected_tx_type = TransactionType::TokenTransfer("d362e096e873eb7907e205fadc6175c6fec7bc44".into());
+    for tx in details {
+        assert_eq!(tx.transaction_type, expected_tx_type);
+        assert_eq!(tx.block_height, 699545);
+        assert_eq!(tx.timestamp, 1602997840);
+    }
+}
+
 #[test]
 fn test_get_trade_fee() {
     // priv_key of qXxsj5RtciAby9T7m98AgAATL4zTi4UwDG
@@ -1182,3 +1229,36 @@ fn test_send_contract_calls_recoverable_tx() {
         discriminant(&TransactionErr::TxRecoverable(tx, String::new()))
     );
 }
+
+#[test]
+fn test_tx_history_v2_from_legacy_req() {
+    let req = json!({
+        "method": "electrum",
+        "servers": [{"url":"electrum1.cipig.net:10071"}],
+        "swap_contract_address": "0xba8b71f3544b93e2f681f996da519a98ace0107a",
+        "tx_history": true,
+    });
+    let params = Qrc20ActivationParams::from_legacy_req(&req).unwrap();
+    assert!(!params.tx_history_v2);
+
+    let mut req = req;
+    req["tx_history_v2"] = true.into();
+    let params = Qrc20ActivationParams::from_legacy_req(&req).unwrap();
+    assert!(params.tx_history_v2);
+}
+
+#[test]
+fn test_tx_history_v2_set_history_sync_state() {
+    let priv_key = [
+        3, 98, 177, 3, 108, 39, 234, 144, 131, 178, 103, 103, 127, 80, 230, 166, 53, 68, 147, 215, 42, 216, 144, 72,
+        172, 110, 180, 13, 123, 179, 10, 49,
+    ];
+    let (_ctx, coin) = qrc20_coin_for_test(priv_key, None);
+
+    let in_progress = HistorySyncState::InProgress(json!({ "blocks_left": 10 }));
+    UtxoTxHistoryOps::set_history_sync_state(&coin, in_progress.clone());
+    assert_eq!(coin.history_sync_status(), in_progress);
+
+    UtxoTxHistoryOps::set_history_sync_state(&coin, HistorySyncState::Finished);
+    assert_eq!(coin.history_sync_status(), HistorySyncState::Finished);
+}
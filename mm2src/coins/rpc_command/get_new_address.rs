@@ -209,7 +209,7 @@ pub struct GetNewAddressParams {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct GetNewAddressResponse {
-    new_address: HDAddressBalance,
+    pub(crate) new_address: HDAddressBalance,
 }
 
 #[derive(Clone, Serialize)]
@@ -303,6 +303,14 @@ impl RpcTask for InitGetNewAddressTask {
             MmCoinEnum::QtumCoin(ref qtum) => {
                 get_new_address_helper(&self.ctx, qtum, self.req.params.clone(), task_handle).await
             },
+            // Tendermint addresses are derived from the activated seed, so there is no device to confirm them on.
+            MmCoinEnum::Tendermint(ref tendermint) => {
+                tendermint
+                    .get_new_address_rpc_without_conf(self.req.params.clone())
+                    .await
+            },
+            // Diversified Sapling addresses are derived from the activated spending keys as well.
+            MmCoinEnum::ZCoin(ref zcoin) => zcoin.get_new_address_rpc_without_conf(self.req.params.clone()).await,
             _ => MmError::err(GetNewAddressRpcError::CoinIsActivatedNotWithHDWallet),
         }
     }
@@ -318,6 +326,8 @@ pub async fn get_new_address(
     match coin {
         MmCoinEnum::UtxoCoin(utxo) => utxo.get_new_address_rpc_without_conf(req.params).await,
         MmCoinEnum::QtumCoin(qtum) => qtum.get_new_address_rpc_without_conf(req.params).await,
+        MmCoinEnum::Tendermint(tendermint) => tendermint.get_new_address_rpc_without_conf(req.params).await,
+        MmCoinEnum::ZCoin(zcoin) => zcoin.get_new_address_rpc_without_conf(req.params).await,
         _ => MmError::err(GetNewAddressRpcError::CoinIsActivatedNotWithHDWallet),
     }
 }
@@ -236,12 +236,25 @@ impl RpcTask for InitCreateAccountTask {
             match self.coin {
                 MmCoinEnum::UtxoCoin(utxo) => utxo.revert_creating_account(account_id).await,
                 MmCoinEnum::QtumCoin(qtum) => qtum.revert_creating_account(account_id).await,
+                MmCoinEnum::Tendermint(tendermint) => tendermint.revert_creating_account(account_id).await,
                 _ => (),
             }
         };
     }
 
     async fn run(&mut self, task_handle: &CreateAccountTaskHandle) -> Result<Self::Item, MmError<Self::Error>> {
+        fn hw_statuses() -> HwConnectStatuses<CreateAccountInProgressStatus, CreateAccountAwaitingStatus> {
+            HwConnectStatuses {
+                on_connect: CreateAccountInProgressStatus::WaitingForTrezorToConnect,
+                on_connected: CreateAccountInProgressStatus::Preparing,
+                on_connection_failed: CreateAccountInProgressStatus::Finishing,
+                on_button_request: CreateAccountInProgressStatus::FollowHwDeviceInstructions,
+                on_pin_request: CreateAccountAwaitingStatus::EnterTrezorPin,
+                on_passphrase_request: CreateAccountAwaitingStatus::EnterTrezorPassphrase,
+                on_ready: CreateAccountInProgressStatus::RequestingAccountBalance,
+            }
+        }
+
         async fn create_new_account_helper<Coin>(
             ctx: &MmArc,
             coin: &Coin,
@@ -252,16 +265,7 @@ impl RpcTask for InitCreateAccountTask {
         where
             Coin: InitCreateAccountRpcOps + Send + Sync,
         {
-            let hw_statuses = HwConnectStatuses {
-                on_connect: CreateAccountInProgressStatus::WaitingForTrezorToConnect,
-                on_connected: CreateAccountInProgressStatus::Preparing,
-                on_connection_failed: CreateAccountInProgressStatus::Finishing,
-                on_button_request: CreateAccountInProgressStatus::FollowHwDeviceInstructions,
-                on_pin_request: CreateAccountAwaitingStatus::EnterTrezorPin,
-                on_passphrase_request: CreateAccountAwaitingStatus::EnterTrezorPassphrase,
-                on_ready: CreateAccountInProgressStatus::RequestingAccountBalance,
-            };
-            let xpub_extractor = CreateAccountXPubExtractor::new(ctx, task_handle, hw_statuses)?;
+            let xpub_extractor = CreateAccountXPubExtractor::new(ctx, task_handle, hw_statuses())?;
             coin.init_create_account_rpc(params, state, &xpub_extractor).await
         }
 
@@ -286,6 +290,13 @@ impl RpcTask for InitCreateAccountTask {
                 )
                 .await
             },
+            MmCoinEnum::Tendermint(ref tendermint) => {
+                // Tendermint HD accounts are derived from the activated seed, so a Hardware Wallet isn't required.
+                let xpub_extractor = CreateAccountXPubExtractor::new_unchecked(&self.ctx, task_handle, hw_statuses());
+                tendermint
+                    .init_create_account_rpc(self.req.params.clone(), self.task_state.clone(), &xpub_extractor)
+                    .await
+            },
             _ => MmError::err(CreateAccountRpcError::CoinIsActivatedNotWithHDWallet),
         }
     }
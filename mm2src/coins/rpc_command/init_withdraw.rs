@@ -1,3 +1,4 @@
+use crate::z_coin::ZWithdrawParams;
 use crate::{lp_coinfind_or_err, CoinsContext, MmCoinEnum, WithdrawError};
 use crate::{TransactionDetails, WithdrawRequest};
 use async_trait::async_trait;
@@ -32,8 +33,20 @@ pub trait CoinWithdrawInit {
     ) -> WithdrawInitResult<TransactionDetails>;
 }
 
-pub async fn init_withdraw(ctx: MmArc, request: WithdrawRequest) -> WithdrawInitResult<InitWithdrawResponse> {
-    let coin = lp_coinfind_or_err(&ctx, &request.coin).await?;
+/// The `task::withdraw::init` request.
+/// The protocol specific params are nested, so [`WithdrawRequest`] stays the same for every coin.
+#[derive(Clone, Deserialize)]
+pub struct InitWithdrawRequest {
+    #[serde(flatten)]
+    withdraw: WithdrawRequest,
+    /// The shielded outputs params, used by ZCoin **only**.
+    #[serde(default)]
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    z_params: ZWithdrawParams,
+}
+
+pub async fn init_withdraw(ctx: MmArc, request: InitWithdrawRequest) -> WithdrawInitResult<InitWithdrawResponse> {
+    let coin = lp_coinfind_or_err(&ctx, &request.withdraw.coin).await?;
     let spawner = coin.spawner();
     let task = WithdrawTask {
         ctx: ctx.clone(),
@@ -108,7 +121,7 @@ pub trait InitWithdrawCoin {
 pub struct WithdrawTask {
     ctx: MmArc,
     coin: MmCoinEnum,
-    request: WithdrawRequest,
+    request: InitWithdrawRequest,
 }
 
 impl RpcTaskTypes for WithdrawTask {
@@ -130,13 +143,58 @@ impl RpcTask for WithdrawTask {
         let ctx = self.ctx.clone();
         let request = self.request.clone();
         match self.coin {
-            MmCoinEnum::UtxoCoin(ref standard_utxo) => standard_utxo.init_withdraw(ctx, request, task_handle).await,
-            MmCoinEnum::QtumCoin(ref qtum) => qtum.init_withdraw(ctx, request, task_handle).await,
+            MmCoinEnum::UtxoCoin(ref standard_utxo) => {
+                standard_utxo.init_withdraw(ctx, request.withdraw, task_handle).await
+            },
+            MmCoinEnum::QtumCoin(ref qtum) => qtum.init_withdraw(ctx, request.withdraw, task_handle).await,
             #[cfg(not(target_arch = "wasm32"))]
-            MmCoinEnum::ZCoin(ref z) => z.init_withdraw(ctx, request, task_handle).await,
+            MmCoinEnum::ZCoin(ref z) => z.init_withdraw(request.withdraw, request.z_params, task_handle).await,
             _ => MmError::err(WithdrawError::CoinDoesntSupportInitWithdraw {
                 coin: self.coin.ticker().to_owned(),
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::z_coin::ZOutgoingViewingKey;
+
+    #[test]
+    fn test_init_withdraw_request_deserialize() {
+        let request: InitWithdrawRequest = serde_json::from_value(json!({
+            "coin": "RICK",
+            "to": "RJTYiYeJ8eVvJ53n2YbrVmxWNNMVZjDGLh",
+            "amount": 1.5,
+            "memo": "a memo",
+        }))
+        .unwrap();
+        assert_eq!(request.withdraw.coin, "RICK");
+        assert_eq!(request.withdraw.amount, "1.5".parse().unwrap());
+        assert_eq!(request.withdraw.memo.as_deref(), Some("a memo"));
+        assert_eq!(request.z_params.viewing_key, ZOutgoingViewingKey::Sender);
+        assert!(request.z_params.z_outputs.is_empty());
+
+        let request: InitWithdrawRequest = serde_json::from_value(json!({
+            "coin": "ARRR",
+            "to": "zs1funuwrjr2stlr6fnhkdh7fyz3p7n0p8rxase9jnezdhc286v5mhs6q3myw0phzvad5mvqgfxpam",
+            "max": true,
+            "z_params": {
+                "z_account": 1,
+                "viewing_key": "none",
+                "z_outputs": [{
+                    "to": "zs182ht30wnnnr8jjhj2j9v5dkx3qsknnr5r00jfwk2nczdtqy7w0v836kyy840kv2r8xle5gcl549",
+                    "amount": "0.1",
+                    "memo": "0x68656c6c6f",
+                }],
+            },
+        }))
+        .unwrap();
+        assert!(request.withdraw.max);
+        assert_eq!(request.z_params.z_account, Some(1));
+        assert_eq!(request.z_params.viewing_key, ZOutgoingViewingKey::None);
+        assert_eq!(request.z_params.z_outputs.len(), 1);
+        assert_eq!(request.z_params.z_outputs[0].memo.as_deref(), Some("0x68656c6c6f"));
+    }
+}
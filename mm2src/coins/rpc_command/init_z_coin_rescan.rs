@@ -0,0 +1,209 @@
+use crate::z_coin::{FirstSyncBlock, SyncStatus, UpdateBlocksCacheErr, ZCoin};
+use crate::{lp_coinfind_or_err, CoinFindError, CoinsContext, MmCoin, MmCoinEnum};
+use async_trait::async_trait;
+use common::{HttpStatusCode, SerdeInfallible, StatusCode, SuccessResponse};
+use derive_more::Display;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use rpc_task::rpc_common::{CancelRpcTaskError, CancelRpcTaskRequest, InitRpcTaskResponse, RpcTaskStatusError,
+                           RpcTaskStatusRequest};
+use rpc_task::{RpcTask, RpcTaskError, RpcTaskHandle, RpcTaskManager, RpcTaskManagerShared, RpcTaskStatus, RpcTaskTypes};
+
+pub type ZCoinRescanUserAction = SerdeInfallible;
+pub type ZCoinRescanAwaitingStatus = SerdeInfallible;
+pub type ZCoinRescanTaskManager = RpcTaskManager<ZCoinRescanTask>;
+pub type ZCoinRescanTaskManagerShared = RpcTaskManagerShared<ZCoinRescanTask>;
+pub type ZCoinRescanTaskHandle = RpcTaskHandle<ZCoinRescanTask>;
+pub type ZCoinRescanRpcTaskStatus =
+    RpcTaskStatus<ZCoinRescanResponse, ZCoinRescanError, ZCoinRescanInProgressStatus, ZCoinRescanAwaitingStatus>;
+
+#[derive(Clone, Debug, Display, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum ZCoinRescanError {
+    #[display(fmt = "No such coin {}", _0)]
+    NoSuchCoin(String),
+    #[display(fmt = "Coin {} is not a ZCoin", _0)]
+    CoinIsNotZCoin(String),
+    #[display(fmt = "Failed to reset the wallet: {}", _0)]
+    ResetFailed(String),
+    #[display(fmt = "Blockchain scan process stopped")]
+    BlockchainScanStopped,
+    #[display(fmt = "Rescan timed out {:?}", _0)]
+    Timeout(std::time::Duration),
+    #[display(fmt = "Internal error: {}", _0)]
+    Internal(String),
+}
+
+impl HttpStatusCode for ZCoinRescanError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ZCoinRescanError::NoSuchCoin(_) | ZCoinRescanError::CoinIsNotZCoin(_) => StatusCode::BAD_REQUEST,
+            ZCoinRescanError::Timeout(_) => StatusCode::REQUEST_TIMEOUT,
+            ZCoinRescanError::ResetFailed(_)
+            | ZCoinRescanError::BlockchainScanStopped
+            | ZCoinRescanError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for ZCoinRescanError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => ZCoinRescanError::NoSuchCoin(coin),
+        }
+    }
+}
+
+impl From<UpdateBlocksCacheErr> for ZCoinRescanError {
+    fn from(e: UpdateBlocksCacheErr) -> Self { ZCoinRescanError::ResetFailed(e.to_string()) }
+}
+
+impl From<RpcTaskError> for ZCoinRescanError {
+    fn from(e: RpcTaskError) -> Self {
+        let error = e.to_string();
+        match e {
+            RpcTaskError::Cancelled => ZCoinRescanError::Internal("Cancelled".to_owned()),
+            RpcTaskError::Timeout(timeout) => ZCoinRescanError::Timeout(timeout),
+            RpcTaskError::NoSuchTask(_)
+            | RpcTaskError::UnexpectedTaskStatus { .. }
+            | RpcTaskError::UnexpectedUserAction { .. } => ZCoinRescanError::Internal(error),
+            RpcTaskError::Internal(internal) => ZCoinRescanError::Internal(internal),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ZCoinRescanRequest {
+    coin: String,
+    /// The height to rescan the chain from.
+    /// The Sapling activation height is used if the given height is below it.
+    birthday_height: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ZCoinRescanResponse {
+    first_sync_block: FirstSyncBlock,
+    current_block: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", content = "details")]
+pub enum ZCoinRescanInProgressStatus {
+    ResettingWallet,
+    UpdatingBlocksCache {
+        current_scanned_block: u64,
+        latest_block: u64,
+    },
+    BuildingWalletDb {
+        current_scanned_block: u64,
+        latest_block: u64,
+    },
+    TemporaryError(String),
+}
+
+pub struct ZCoinRescanTask {
+    req: ZCoinRescanRequest,
+    coin: ZCoin,
+}
+
+impl RpcTaskTypes for ZCoinRescanTask {
+    type Item = ZCoinRescanResponse;
+    type Error = ZCoinRescanError;
+    type InProgressStatus = ZCoinRescanInProgressStatus;
+    type AwaitingStatus = ZCoinRescanAwaitingStatus;
+    type UserAction = ZCoinRescanUserAction;
+}
+
+#[async_trait]
+impl RpcTask for ZCoinRescanTask {
+    #[inline]
+    fn initial_status(&self) -> Self::InProgressStatus { ZCoinRescanInProgressStatus::ResettingWallet }
+
+    // Do nothing if the task has been cancelled, the sync loop keeps rescanning from the birthday height.
+    async fn cancel(self) {}
+
+    async fn run(&mut self, task_handle: &ZCoinRescanTaskHandle) -> Result<Self::Item, MmError<Self::Error>> {
+        let first_sync_block = self.coin.rescan_from_height(self.req.birthday_height).await?;
+
+        // The sync status channel may still keep the `Finished` status of the sync preceding the reset,
+        // so skip the statuses until the rescan progress is reported.
+        let mut rescan_started = false;
+        loop {
+            let in_progress_status = match self
+                .coin
+                .sync_status()
+                .await
+                .mm_err(|_| ZCoinRescanError::BlockchainScanStopped)?
+            {
+                SyncStatus::UpdatingBlocksCache {
+                    current_scanned_block,
+                    latest_block,
+                    ..
+                } => ZCoinRescanInProgressStatus::UpdatingBlocksCache {
+                    current_scanned_block,
+                    latest_block,
+                },
+                SyncStatus::BuildingWalletDb {
+                    current_scanned_block,
+                    latest_block,
+                    ..
+                } => ZCoinRescanInProgressStatus::BuildingWalletDb {
+                    current_scanned_block,
+                    latest_block,
+                },
+                SyncStatus::TemporaryError(e) => ZCoinRescanInProgressStatus::TemporaryError(e),
+                SyncStatus::Finished { block_number, .. } if rescan_started => {
+                    return Ok(ZCoinRescanResponse {
+                        first_sync_block,
+                        current_block: block_number,
+                    });
+                },
+                SyncStatus::Finished { .. } => continue,
+            };
+            rescan_started = true;
+            task_handle.update_in_progress_status(in_progress_status)?;
+        }
+    }
+}
+
+pub async fn init_z_coin_rescan(
+    ctx: MmArc,
+    req: ZCoinRescanRequest,
+) -> MmResult<InitRpcTaskResponse, ZCoinRescanError> {
+    let coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::ZCoin(coin) => coin,
+        _ => return MmError::err(ZCoinRescanError::CoinIsNotZCoin(req.coin)),
+    };
+    let spawner = coin.spawner();
+    let coins_ctx = CoinsContext::from_ctx(&ctx).map_to_mm(ZCoinRescanError::Internal)?;
+    let task = ZCoinRescanTask { req, coin };
+    let task_id = ZCoinRescanTaskManager::spawn_rpc_task(&coins_ctx.z_coin_rescan_manager, &spawner, task)?;
+    Ok(InitRpcTaskResponse { task_id })
+}
+
+pub async fn z_coin_rescan_status(
+    ctx: MmArc,
+    req: RpcTaskStatusRequest,
+) -> MmResult<ZCoinRescanRpcTaskStatus, RpcTaskStatusError> {
+    let coins_ctx = CoinsContext::from_ctx(&ctx).map_to_mm(RpcTaskStatusError::Internal)?;
+    let mut task_manager = coins_ctx
+        .z_coin_rescan_manager
+        .lock()
+        .map_to_mm(|e| RpcTaskStatusError::Internal(e.to_string()))?;
+    task_manager
+        .task_status(req.task_id, req.forget_if_finished)
+        .or_mm_err(|| RpcTaskStatusError::NoSuchTask(req.task_id))
+}
+
+pub async fn cancel_z_coin_rescan(
+    ctx: MmArc,
+    req: CancelRpcTaskRequest,
+) -> MmResult<SuccessResponse, CancelRpcTaskError> {
+    let coins_ctx = CoinsContext::from_ctx(&ctx).map_to_mm(CancelRpcTaskError::Internal)?;
+    let mut task_manager = coins_ctx
+        .z_coin_rescan_manager
+        .lock()
+        .map_to_mm(|e| CancelRpcTaskError::Internal(e.to_string()))?;
+    task_manager.cancel_task(req.task_id)?;
+    Ok(SuccessResponse::new())
+}
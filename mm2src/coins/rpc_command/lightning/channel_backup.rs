@@ -0,0 +1,194 @@
+use crate::lightning::ln_channel_backup::decrypt_channel_backup;
+use crate::lightning::ln_p2p::connect_to_ln_node;
+use crate::lightning::ln_serialization::PublicKeyForRPC;
+use crate::lightning::ln_storage::LightningStorage;
+use crate::utxo::rpc_clients::UtxoRpcClientEnum;
+use crate::{lp_coinfind_or_err, CoinFindError, MmCoinEnum};
+use bitcoin::BlockHash;
+use common::log::{error, info};
+use common::{async_blocking, HttpStatusCode};
+use http::StatusCode;
+use lightning::chain::channelmonitor::ChannelMonitor;
+use lightning::chain::keysinterface::InMemorySigner;
+use lightning::chain::{ChannelMonitorUpdateStatus, Watch};
+use lightning::util::ser::ReadableArgs;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use rpc::v1::types::Bytes as BytesJson;
+use secp256k1v24::PublicKey;
+use std::io::Cursor;
+use std::str::FromStr;
+
+type ChannelBackupResult<T> = Result<T, MmError<ChannelBackupError>>;
+
+#[derive(Debug, Deserialize, Display, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum ChannelBackupError {
+    #[display(fmt = "Lightning network is not supported for {}", _0)]
+    UnsupportedCoin(String),
+    #[display(fmt = "No such coin {}", _0)]
+    NoSuchCoin(String),
+    #[display(fmt = "Invalid channel backup: {}", _0)]
+    InvalidBackup(String),
+    #[display(fmt = "Restoring channel error: {}", _0)]
+    RestoreError(String),
+    #[display(fmt = "I/O error {}", _0)]
+    IOError(String),
+}
+
+impl HttpStatusCode for ChannelBackupError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChannelBackupError::UnsupportedCoin(_) | ChannelBackupError::InvalidBackup(_) => StatusCode::BAD_REQUEST,
+            ChannelBackupError::NoSuchCoin(_) => StatusCode::NOT_FOUND,
+            ChannelBackupError::RestoreError(_) | ChannelBackupError::IOError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for ChannelBackupError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => ChannelBackupError::NoSuchCoin(coin),
+        }
+    }
+}
+
+impl From<std::io::Error> for ChannelBackupError {
+    fn from(err: std::io::Error) -> ChannelBackupError { ChannelBackupError::IOError(err.to_string()) }
+}
+
+#[derive(Deserialize)]
+pub struct ExportChannelBackupReq {
+    pub coin: String,
+}
+
+#[derive(Serialize)]
+pub struct ExportChannelBackupResponse {
+    /// The encrypted static channel backup, it can be restored only by the node that exported it.
+    pub backup: BytesJson,
+}
+
+pub async fn export_channel_backup(
+    ctx: MmArc,
+    req: ExportChannelBackupReq,
+) -> ChannelBackupResult<ExportChannelBackupResponse> {
+    let ln_coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::LightningCoin(c) => c,
+        e => return MmError::err(ChannelBackupError::UnsupportedCoin(e.ticker().to_string())),
+    };
+
+    let persister = ln_coin.persister.clone();
+    let backup = async_blocking(move || persister.export_static_channel_backup()).await?;
+
+    Ok(ExportChannelBackupResponse { backup: backup.into() })
+}
+
+#[derive(Deserialize)]
+pub struct RestoreChannelBackupReq {
+    pub coin: String,
+    pub backup: BytesJson,
+    /// Broadcast the latest commitment transactions from the backup instead of waiting for the counterparties
+    /// to close the channels after reconnecting.
+    /// Should be used only if the backup is up to date, broadcasting a revoked commitment transaction leads to losing
+    /// the channel's funds.
+    #[serde(default)]
+    pub force_close: bool,
+}
+
+#[derive(Serialize)]
+pub struct RestoreChannelBackupResponse {
+    pub restored_channels: Vec<String>,
+    pub peers: Vec<PublicKeyForRPC>,
+}
+
+/// Gives the channel monitors from the backup to the chain monitor so that the channels' funds are claimed on-chain
+/// once the channels are closed, then reconnects to the channels' counterparties. The channels are unknown
+/// to the channel manager of the restored node, so the counterparties close them when they try to reestablish them.
+pub async fn restore_channel_backup(
+    ctx: MmArc,
+    req: RestoreChannelBackupReq,
+) -> ChannelBackupResult<RestoreChannelBackupResponse> {
+    let ln_coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::LightningCoin(c) => c,
+        e => return MmError::err(ChannelBackupError::UnsupportedCoin(e.ticker().to_string())),
+    };
+
+    let backup = decrypt_channel_backup(ln_coin.persister.channel_backup_key(), &req.backup.0)
+        .map_to_mm(|e| ChannelBackupError::InvalidBackup(e.to_string()))?;
+
+    let chain_monitor = ln_coin.chain_monitor.clone();
+    let existing_monitors = async_blocking(move || chain_monitor.list_monitors()).await;
+
+    let mut restored_channels = Vec::new();
+    for channel in backup.channels {
+        let (_, monitor) = <(BlockHash, ChannelMonitor<InMemorySigner>)>::read(
+            &mut Cursor::new(&channel.monitor.0),
+            &*ln_coin.keys_manager,
+        )
+        .map_to_mm(|e| {
+            ChannelBackupError::InvalidBackup(format!(
+                "Failed to deserialize the monitor of {}: {}",
+                channel.funding_txo, e
+            ))
+        })?;
+
+        let funding_txo = monitor.get_funding_txo().0;
+        if existing_monitors.contains(&funding_txo) {
+            continue;
+        }
+        if req.force_close {
+            monitor.broadcast_latest_holder_commitment_txn(&ln_coin.platform, &ln_coin.logger);
+        }
+
+        let channel_id = hex::encode(funding_txo.to_channel_id());
+        let chain_monitor = ln_coin.chain_monitor.clone();
+        if let ChannelMonitorUpdateStatus::PermanentFailure =
+            async_blocking(move || chain_monitor.watch_channel(funding_txo, monitor)).await
+        {
+            return MmError::err(ChannelBackupError::RestoreError(format!(
+                "Failure to persist channel: {}!",
+                channel_id
+            )));
+        }
+        info!("Restored channel {} from the static channel backup", channel_id);
+        restored_channels.push(channel_id);
+    }
+
+    // Check if the funding or the closing transactions of the restored channels are already confirmed
+    if let UtxoRpcClientEnum::Electrum(client) = &ln_coin.platform.coin.as_ref().rpc_client {
+        ln_coin
+            .platform
+            .process_txs_confirmations(
+                client,
+                &ln_coin.db,
+                ln_coin.chain_monitor.clone(),
+                ln_coin.channel_manager.clone(),
+            )
+            .await;
+    }
+
+    let mut peers = Vec::with_capacity(backup.peers.len());
+    for (node_id, node_addr) in backup.peers {
+        let pubkey = PublicKey::from_str(&node_id).map_to_mm(|e| ChannelBackupError::InvalidBackup(e.to_string()))?;
+        ln_coin.open_channels_nodes.lock().insert(pubkey, node_addr);
+        peers.push((pubkey, node_addr));
+    }
+    ln_coin
+        .persister
+        .save_nodes_addresses(ln_coin.open_channels_nodes.clone())
+        .await?;
+
+    // The nodes are also reconnected to periodically by connect_to_ln_nodes_loop if the connection fails now
+    for (pubkey, node_addr) in peers.iter() {
+        match connect_to_ln_node(*pubkey, *node_addr, ln_coin.peer_manager.clone()).await {
+            Ok(res) => info!("{}", res.to_string()),
+            Err(e) => error!("{}", e.to_string()),
+        }
+    }
+
+    Ok(RestoreChannelBackupResponse {
+        restored_channels,
+        peers: peers.into_iter().map(|(pubkey, _)| PublicKeyForRPC(pubkey)).collect(),
+    })
+}
@@ -0,0 +1,120 @@
+use crate::lightning::ln_db::{ChannelForwardingEarnings, DBForwardedPayment, ForwardedPaymentsFilter, LightningDB};
+use crate::{lp_coinfind_or_err, CoinFindError, MmCoinEnum};
+use common::{calc_total_pages, ten, HttpStatusCode, PagingOptionsEnum};
+use db_common::sqlite::rusqlite::Error as SqlError;
+use http::StatusCode;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+
+type ForwardingStatsResult<T> = Result<T, MmError<ForwardingStatsError>>;
+
+#[derive(Debug, Deserialize, Display, Serialize, SerializeErrorType)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum ForwardingStatsError {
+    #[display(fmt = "Lightning network is not supported for {}", _0)]
+    UnsupportedCoin(String),
+    #[display(fmt = "No such coin {}", _0)]
+    NoSuchCoin(String),
+    #[display(fmt = "DB error {}", _0)]
+    DbError(String),
+}
+
+impl HttpStatusCode for ForwardingStatsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ForwardingStatsError::UnsupportedCoin(_) => StatusCode::BAD_REQUEST,
+            ForwardingStatsError::NoSuchCoin(_) => StatusCode::NOT_FOUND,
+            ForwardingStatsError::DbError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for ForwardingStatsError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => ForwardingStatsError::NoSuchCoin(coin),
+        }
+    }
+}
+
+impl From<SqlError> for ForwardingStatsError {
+    fn from(err: SqlError) -> ForwardingStatsError { ForwardingStatsError::DbError(err.to_string()) }
+}
+
+#[derive(Deserialize)]
+pub struct ListForwardedPaymentsReq {
+    pub coin: String,
+    pub filter: Option<ForwardedPaymentsFilter>,
+    #[serde(default = "ten")]
+    limit: usize,
+    #[serde(default)]
+    paging_options: PagingOptionsEnum<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ListForwardedPaymentsResponse {
+    forwarded_payments: Vec<DBForwardedPayment>,
+    limit: usize,
+    skipped: usize,
+    total: usize,
+    total_pages: usize,
+    paging_options: PagingOptionsEnum<i64>,
+}
+
+pub async fn list_forwarded_payments(
+    ctx: MmArc,
+    req: ListForwardedPaymentsReq,
+) -> ForwardingStatsResult<ListForwardedPaymentsResponse> {
+    let ln_coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::LightningCoin(c) => c,
+        e => return MmError::err(ForwardingStatsError::UnsupportedCoin(e.ticker().to_string())),
+    };
+    let get_forwarded_payments_res = ln_coin
+        .db
+        .get_forwarded_payments_by_filter(req.filter, req.paging_options.clone(), req.limit)
+        .await?;
+
+    Ok(ListForwardedPaymentsResponse {
+        forwarded_payments: get_forwarded_payments_res.forwarded_payments,
+        limit: req.limit,
+        skipped: get_forwarded_payments_res.skipped,
+        total: get_forwarded_payments_res.total,
+        total_pages: calc_total_pages(get_forwarded_payments_res.total, req.limit),
+        paging_options: req.paging_options,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct GetForwardingEarningsReq {
+    pub coin: String,
+    pub from_timestamp: Option<i64>,
+    pub to_timestamp: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct GetForwardingEarningsResponse {
+    total_forwarded: u64,
+    total_fee_earned_msat: i64,
+    channels: Vec<ChannelForwardingEarnings>,
+}
+
+/// Reports the number of payments forwarded through every channel and the fees they earned in the time range.
+pub async fn get_forwarding_earnings(
+    ctx: MmArc,
+    req: GetForwardingEarningsReq,
+) -> ForwardingStatsResult<GetForwardingEarningsResponse> {
+    let ln_coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::LightningCoin(c) => c,
+        e => return MmError::err(ForwardingStatsError::UnsupportedCoin(e.ticker().to_string())),
+    };
+    let channels = ln_coin
+        .db
+        .get_forwarding_earnings(req.from_timestamp, req.to_timestamp)
+        .await?;
+
+    Ok(GetForwardingEarningsResponse {
+        total_forwarded: channels.iter().map(|channel| channel.forwarded_out).sum(),
+        total_fee_earned_msat: channels.iter().map(|channel| channel.fee_earned_msat).sum(),
+        channels,
+    })
+}
@@ -1,5 +1,7 @@
+mod channel_backup;
 mod close_channel;
 mod connect_to_node;
+mod forwarding_stats;
 mod generate_invoice;
 mod get_channel_details;
 mod get_claimable_balances;
@@ -12,6 +14,7 @@ mod trusted_nodes;
 mod update_channel;
 
 pub mod channels {
+    pub use super::channel_backup::*;
     pub use super::close_channel::*;
     pub use super::get_channel_details::*;
     pub use super::get_claimable_balances::*;
@@ -20,6 +23,10 @@ pub mod channels {
     pub use super::update_channel::*;
 }
 
+pub mod forwarding {
+    pub use super::forwarding_stats::*;
+}
+
 pub mod nodes {
     pub use super::connect_to_node::*;
     pub use super::trusted_nodes::*;
@@ -163,7 +163,11 @@ pub async fn open_channel(ctx: MmArc, req: OpenChannelRequest) -> OpenChannelRes
     // output script from the other node when the channel is accepted
     let script_pubkey =
         Builder::build_witness_script(&AddressHashEnum::WitnessScriptHash(Default::default())).to_bytes();
-    let outputs = vec![TransactionOutput { value, script_pubkey }];
+    let outputs = vec![TransactionOutput {
+        value,
+        script_pubkey,
+        token: None,
+    }];
 
     let mut tx_builder = UtxoTxBuilder::new(&platform_coin)
         .add_available_inputs(unspents)
@@ -1,9 +1,14 @@
 use crate::lightning::ln_conf::ChannelOptions;
+use crate::lightning::LightningCoin;
 use crate::{lp_coinfind_or_err, CoinFindError, MmCoinEnum};
 use common::{async_blocking, HttpStatusCode};
 use http::StatusCode;
+use lightning::ln::channelmanager::ChannelDetails;
+use lightning::util::config::ChannelConfig;
 use mm2_core::mm_ctx::MmArc;
 use mm2_err_handle::prelude::*;
+use secp256k1v24::PublicKey;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 type UpdateChannelResult<T> = Result<T, MmError<UpdateChannelError>>;
@@ -64,14 +69,7 @@ pub async fn update_channel(ctx: MmArc, req: UpdateChannelReq) -> UpdateChannelR
         .ok_or(UpdateChannelError::NoSuchChannel(req.uuid))?;
 
     async_blocking(move || {
-        let mut channel_options = ln_coin
-            .conf
-            .channel_options
-            .unwrap_or_else(|| req.channel_options.clone());
-        if channel_options != req.channel_options {
-            channel_options.update_according_to(req.channel_options.clone());
-        }
-        drop_mutability!(channel_options);
+        let channel_options = channel_options_to_apply(&ln_coin, req.channel_options);
         let channel_ids = &[channel_details.channel_id];
         let counterparty_node_id = channel_details.counterparty.node_id;
         ln_coin
@@ -82,3 +80,92 @@ pub async fn update_channel(ctx: MmArc, req: UpdateChannelReq) -> UpdateChannelR
     })
     .await
 }
+
+#[derive(Deserialize)]
+pub struct UpdateAllChannelsReq {
+    pub coin: String,
+    pub channel_options: ChannelOptions,
+}
+
+#[derive(Serialize)]
+pub struct UpdateAllChannelsResponse {
+    channel_options: ChannelOptions,
+    updated_channels: Vec<Uuid>,
+}
+
+/// Updates configuration for all open channels at once, e.g. to change the routing fees of a forwarding node.
+pub async fn update_all_channels(
+    ctx: MmArc,
+    req: UpdateAllChannelsReq,
+) -> UpdateChannelResult<UpdateAllChannelsResponse> {
+    let ln_coin = match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::LightningCoin(c) => c,
+        e => return MmError::err(UpdateChannelError::UnsupportedCoin(e.ticker().to_string())),
+    };
+
+    let channels = ln_coin.list_channels().await;
+
+    async_blocking(move || {
+        let channel_options = channel_options_to_apply(&ln_coin, req.channel_options);
+        let channel_config: ChannelConfig = channel_options.clone().into();
+
+        // `ChannelManager::update_channel_config` checks all the channels of a counterparty before updating any of them,
+        // so the channels are updated per counterparty and the already updated ones are restored if any update fails.
+        let mut channels_by_counterparty: HashMap<PublicKey, Vec<ChannelDetails>> = HashMap::new();
+        for channel_details in channels {
+            channels_by_counterparty
+                .entry(channel_details.counterparty.node_id)
+                .or_default()
+                .push(channel_details);
+        }
+
+        let mut updated: Vec<&ChannelDetails> = Vec::new();
+        for (counterparty_node_id, counterparty_channels) in channels_by_counterparty.iter() {
+            let channel_ids: Vec<_> = counterparty_channels.iter().map(|c| c.channel_id).collect();
+            if let Err(e) =
+                ln_coin
+                    .channel_manager
+                    .update_channel_config(counterparty_node_id, &channel_ids, &channel_config)
+            {
+                for channel_details in updated {
+                    if let Some(previous_config) = channel_details.config {
+                        ln_coin
+                            .channel_manager
+                            .update_channel_config(
+                                &channel_details.counterparty.node_id,
+                                &[channel_details.channel_id],
+                                &previous_config,
+                            )
+                            .ok();
+                    }
+                }
+                let uuid = Uuid::from_u128(counterparty_channels[0].user_channel_id);
+                return MmError::err(UpdateChannelError::FailureToUpdateChannel(uuid, format!("{:?}", e)));
+            }
+            updated.extend(counterparty_channels.iter());
+        }
+
+        let updated_channels = updated
+            .into_iter()
+            .map(|channel_details| Uuid::from_u128(channel_details.user_channel_id))
+            .collect();
+        Ok(UpdateAllChannelsResponse {
+            channel_options,
+            updated_channels,
+        })
+    })
+    .await
+}
+
+/// The options from the request override the ones from the coin config, if any.
+fn channel_options_to_apply(ln_coin: &LightningCoin, req_options: ChannelOptions) -> ChannelOptions {
+    let mut channel_options = ln_coin
+        .conf
+        .channel_options
+        .clone()
+        .unwrap_or_else(|| req_options.clone());
+    if channel_options != req_options {
+        channel_options.update_according_to(req_options);
+    }
+    channel_options
+}
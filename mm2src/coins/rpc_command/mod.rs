@@ -7,5 +7,7 @@ pub mod init_account_balance;
 pub mod init_create_account;
 pub mod init_scan_for_new_addresses;
 pub mod init_withdraw;
+#[cfg(not(target_arch = "wasm32"))] pub mod init_z_coin_rescan;
 #[cfg(not(target_arch = "wasm32"))] pub mod lightning;
 pub mod tendermint;
+#[cfg(not(target_arch = "wasm32"))] pub mod z_coin_shield;
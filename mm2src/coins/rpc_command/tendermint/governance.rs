@@ -0,0 +1,262 @@
+use common::HttpStatusCode;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+
+use crate::tendermint::TendermintCoinRpcError;
+use crate::{lp_coinfind_or_err, CoinFindError, MmCoinEnum, PrivKeyPolicyNotAllowed, TransactionDetails, WithdrawFee};
+
+/// The default number of the latest proposals returned by `tendermint_proposals`.
+const DEFAULT_PROPOSALS_LIMIT: u64 = 20;
+
+pub type GovernanceResult<T> = Result<T, MmError<GovernanceError>>;
+
+#[derive(Clone, Debug, Display, Serialize, SerializeErrorType, PartialEq)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum GovernanceError {
+    #[display(fmt = "No such coin {}", _0)]
+    NoSuchCoin(String),
+    #[display(fmt = "Governance is not available for: {}", _0)]
+    UnsupportedCoin(String),
+    #[display(fmt = "Invalid vote: {}", _0)]
+    InvalidVote(String),
+    #[display(fmt = "Proposal {} is not in the voting period", _0)]
+    ProposalNotInVotingPeriod(u64),
+    #[display(
+        fmt = "Not enough {} to pay the fee: available {}, required {}",
+        coin,
+        available,
+        required
+    )]
+    NotSufficientBalance {
+        coin: String,
+        available: BigDecimal,
+        required: BigDecimal,
+    },
+    #[display(fmt = "Transport error: {}", _0)]
+    Transport(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    InternalError(String),
+}
+
+impl HttpStatusCode for GovernanceError {
+    fn status_code(&self) -> common::StatusCode {
+        match self {
+            GovernanceError::NoSuchCoin(_)
+            | GovernanceError::UnsupportedCoin(_)
+            | GovernanceError::InvalidVote(_)
+            | GovernanceError::ProposalNotInVotingPeriod(_)
+            | GovernanceError::NotSufficientBalance { .. } => common::StatusCode::BAD_REQUEST,
+            GovernanceError::Transport(_) => common::StatusCode::SERVICE_UNAVAILABLE,
+            GovernanceError::InternalError(_) => common::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<CoinFindError> for GovernanceError {
+    fn from(e: CoinFindError) -> Self {
+        match e {
+            CoinFindError::NoSuchCoin { coin } => GovernanceError::NoSuchCoin(coin),
+        }
+    }
+}
+
+impl From<TendermintCoinRpcError> for GovernanceError {
+    fn from(err: TendermintCoinRpcError) -> Self {
+        match err {
+            TendermintCoinRpcError::InvalidResponse(e)
+            | TendermintCoinRpcError::PerformError(e)
+            | TendermintCoinRpcError::RpcClientError(e) => GovernanceError::Transport(e),
+            TendermintCoinRpcError::Prost(e) => GovernanceError::Transport(e.to_string()),
+            TendermintCoinRpcError::InternalError(e) => GovernanceError::InternalError(e),
+        }
+    }
+}
+
+impl From<PrivKeyPolicyNotAllowed> for GovernanceError {
+    fn from(e: PrivKeyPolicyNotAllowed) -> Self { GovernanceError::InternalError(e.to_string()) }
+}
+
+/// Proposal status as it's encoded in the `cosmos.gov.v1beta1.ProposalStatus`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Unspecified,
+    DepositPeriod,
+    VotingPeriod,
+    Passed,
+    Rejected,
+    Failed,
+}
+
+impl From<i32> for ProposalStatus {
+    fn from(status: i32) -> Self {
+        match status {
+            1 => ProposalStatus::DepositPeriod,
+            2 => ProposalStatus::VotingPeriod,
+            3 => ProposalStatus::Passed,
+            4 => ProposalStatus::Rejected,
+            5 => ProposalStatus::Failed,
+            _ => ProposalStatus::Unspecified,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatusFilter {
+    #[default]
+    All,
+    DepositPeriod,
+    VotingPeriod,
+    Passed,
+    Rejected,
+    Failed,
+}
+
+impl ProposalStatusFilter {
+    pub(crate) fn as_proposal_status(&self) -> i32 {
+        match self {
+            // Unspecified status isn't filtered by the node.
+            ProposalStatusFilter::All => 0,
+            ProposalStatusFilter::DepositPeriod => 1,
+            ProposalStatusFilter::VotingPeriod => 2,
+            ProposalStatusFilter::Passed => 3,
+            ProposalStatusFilter::Rejected => 4,
+            ProposalStatusFilter::Failed => 5,
+        }
+    }
+}
+
+/// Vote option as it's encoded in the `cosmos.gov.v1beta1.VoteOption`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Unspecified,
+    Yes,
+    Abstain,
+    No,
+    NoWithVeto,
+}
+
+impl VoteOption {
+    pub(crate) fn as_proto(&self) -> i32 {
+        match self {
+            VoteOption::Unspecified => 0,
+            VoteOption::Yes => 1,
+            VoteOption::Abstain => 2,
+            VoteOption::No => 3,
+            VoteOption::NoWithVeto => 4,
+        }
+    }
+}
+
+impl From<i32> for VoteOption {
+    fn from(option: i32) -> Self {
+        match option {
+            1 => VoteOption::Yes,
+            2 => VoteOption::Abstain,
+            3 => VoteOption::No,
+            4 => VoteOption::NoWithVeto,
+            _ => VoteOption::Unspecified,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WeightedVoteOption {
+    pub(crate) option: VoteOption,
+    /// The share of the voting power, e.g. `0.7` is 70%.
+    pub(crate) weight: BigDecimal,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TendermintVote {
+    /// Casts the whole voting power for one option, sent as `MsgVote`.
+    Option(VoteOption),
+    /// Splits the voting power between the options, sent as `MsgVoteWeighted`.
+    /// The weights must sum up to 1.
+    Weighted(Vec<WeightedVoteOption>),
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TendermintProposalsRequest {
+    pub(crate) coin: String,
+    #[serde(default)]
+    pub(crate) filter_by_status: ProposalStatusFilter,
+    /// The number of the latest proposals to return.
+    #[serde(default = "default_proposals_limit")]
+    pub(crate) limit: u64,
+}
+
+fn default_proposals_limit() -> u64 { DEFAULT_PROPOSALS_LIMIT }
+
+#[derive(Clone, Serialize)]
+pub struct TendermintProposalsResponse {
+    pub(crate) proposals: Vec<TendermintProposal>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TendermintTally {
+    pub(crate) yes: BigDecimal,
+    pub(crate) abstain: BigDecimal,
+    pub(crate) no: BigDecimal,
+    pub(crate) no_with_veto: BigDecimal,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TendermintProposal {
+    pub(crate) id: u64,
+    /// `None` if the proposal content can't be decoded.
+    pub(crate) title: Option<String>,
+    /// `None` if the proposal content can't be decoded.
+    pub(crate) description: Option<String>,
+    /// The type of the proposal content, e.g. `/cosmos.gov.v1beta1.TextProposal`.
+    pub(crate) content_type_url: Option<String>,
+    pub(crate) status: ProposalStatus,
+    /// The final tally of the finished proposals or the current tally of the proposals in the voting period.
+    pub(crate) tally: TendermintTally,
+    /// The total deposit in the platform coin.
+    pub(crate) total_deposit: BigDecimal,
+    /// As seconds
+    pub(crate) submit_time: Option<i64>,
+    /// As seconds
+    pub(crate) deposit_end_time: Option<i64>,
+    /// As seconds
+    pub(crate) voting_start_time: Option<i64>,
+    /// As seconds
+    pub(crate) voting_end_time: Option<i64>,
+    /// Our own vote. It's only known during the voting period, since the votes are pruned after the tally.
+    pub(crate) my_vote: Option<Vec<WeightedVoteOption>>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TendermintVoteRequest {
+    pub(crate) coin: String,
+    pub(crate) proposal_id: u64,
+    pub(crate) vote: TendermintVote,
+    pub(crate) memo: Option<String>,
+    pub(crate) fee: Option<WithdrawFee>,
+}
+
+pub async fn tendermint_proposals(
+    ctx: MmArc,
+    req: TendermintProposalsRequest,
+) -> GovernanceResult<TendermintProposalsResponse> {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
+    match coin {
+        MmCoinEnum::Tendermint(coin) => Ok(TendermintProposalsResponse {
+            proposals: coin.proposals(req.filter_by_status, req.limit).await?,
+        }),
+        _ => MmError::err(GovernanceError::UnsupportedCoin(req.coin)),
+    }
+}
+
+pub async fn tendermint_vote(ctx: MmArc, req: TendermintVoteRequest) -> GovernanceResult<TransactionDetails> {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
+    match coin {
+        MmCoinEnum::Tendermint(coin) => coin.vote(req).await,
+        _ => MmError::err(GovernanceError::UnsupportedCoin(req.coin)),
+    }
+}
@@ -0,0 +1,65 @@
+use common::HttpStatusCode;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+
+use crate::tendermint::{IBCTransferRecord, IBCTransferStorageError};
+use crate::{lp_coinfind_or_err, MmCoinEnum};
+
+pub type IBCTransferStatusResult = Result<IBCTransferRecord, MmError<IBCTransferStatusRequestError>>;
+
+#[derive(Clone, Deserialize)]
+pub struct IBCTransferStatusRequest {
+    pub(crate) coin: String,
+    /// The hash of the transfer tx returned by `ibc_withdraw`.
+    pub(crate) tx_hash: String,
+}
+
+#[derive(Clone, Debug, Display, Serialize, SerializeErrorType, PartialEq)]
+#[serde(tag = "error_type", content = "error_data")]
+pub enum IBCTransferStatusRequestError {
+    #[display(fmt = "No such coin {}", _0)]
+    NoSuchCoin(String),
+    #[display(
+        fmt = "Only tendermint based coins are allowed for `ibc_transfer_status` operation. Current coin: {}",
+        _0
+    )]
+    UnsupportedCoin(String),
+    #[display(fmt = "IBC transfer '{}' is not tracked", _0)]
+    TransferNotFound(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    InternalError(String),
+}
+
+impl HttpStatusCode for IBCTransferStatusRequestError {
+    fn status_code(&self) -> common::StatusCode {
+        match self {
+            IBCTransferStatusRequestError::UnsupportedCoin(_) | IBCTransferStatusRequestError::NoSuchCoin(_) => {
+                common::StatusCode::BAD_REQUEST
+            },
+            IBCTransferStatusRequestError::TransferNotFound(_) => common::StatusCode::NOT_FOUND,
+            IBCTransferStatusRequestError::InternalError(_) => common::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<IBCTransferStorageError> for IBCTransferStatusRequestError {
+    fn from(e: IBCTransferStorageError) -> Self { IBCTransferStatusRequestError::InternalError(e.to_string()) }
+}
+
+pub async fn ibc_transfer_status(ctx: MmArc, req: IBCTransferStatusRequest) -> IBCTransferStatusResult {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin)
+        .await
+        .map_err(|_| IBCTransferStatusRequestError::NoSuchCoin(req.coin.clone()))?;
+
+    let platform_coin = match coin {
+        MmCoinEnum::Tendermint(coin) => coin,
+        MmCoinEnum::TendermintToken(token) => token.platform_coin.clone(),
+        _ => return MmError::err(IBCTransferStatusRequestError::UnsupportedCoin(req.coin)),
+    };
+
+    let tx_hash = req.tx_hash.to_uppercase();
+    platform_coin
+        .ibc_transfer(&tx_hash)
+        .await?
+        .or_mm_err(|| IBCTransferStatusRequestError::TransferNotFound(req.tx_hash))
+}
@@ -1,6 +1,6 @@
 use common::Future01CompatExt;
 use mm2_core::mm_ctx::MmArc;
-use mm2_err_handle::prelude::MmError;
+use mm2_err_handle::prelude::*;
 use mm2_number::BigDecimal;
 
 use crate::{lp_coinfind_or_err, MmCoinEnum, WithdrawError, WithdrawFee, WithdrawFrom, WithdrawResult};
@@ -21,9 +21,23 @@ pub struct IBCWithdrawRequest {
 
 pub async fn ibc_withdraw(ctx: MmArc, req: IBCWithdrawRequest) -> WithdrawResult {
     let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
-    match coin {
-        MmCoinEnum::Tendermint(coin) => coin.ibc_withdraw(req).compat().await,
-        MmCoinEnum::TendermintToken(token) => token.ibc_withdraw(req).compat().await,
-        _ => MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
-    }
+    let (platform_coin, decimals, tx) = match coin {
+        MmCoinEnum::Tendermint(coin) => {
+            let tx = coin.ibc_withdraw(req).compat().await?;
+            let decimals = coin.decimals;
+            (coin, decimals, tx)
+        },
+        MmCoinEnum::TendermintToken(token) => {
+            let tx = token.ibc_withdraw(req).compat().await?;
+            (token.platform_coin.clone(), token.decimals, tx)
+        },
+        _ => return MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
+    };
+
+    // Track the packet from the moment it's signed, so the status is known even if the tx is never broadcasted.
+    platform_coin
+        .save_ibc_transfer(&tx.coin, decimals, &tx)
+        .await
+        .mm_err(|e| WithdrawError::InternalError(e.to_string()))?;
+    Ok(tx)
 }
@@ -1,10 +1,16 @@
+mod governance;
 mod ibc_chains;
 mod ibc_transfer_channels;
+mod ibc_transfer_status;
 mod ibc_withdraw;
+mod staking;
 
+pub use governance::*;
 pub use ibc_chains::*;
 pub use ibc_transfer_channels::*;
+pub use ibc_transfer_status::*;
 pub use ibc_withdraw::*;
+pub use staking::*;
 
 // Global constants for interacting with https://github.com/KomodoPlatform/chain-registry repository
 // using `mm2_git` crate.
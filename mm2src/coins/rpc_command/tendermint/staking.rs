@@ -0,0 +1,128 @@
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::MmError;
+use mm2_number::BigDecimal;
+
+use crate::{lp_coinfind_or_err, DelegationError, DelegationResult, MmCoinEnum, StakingInfosError, WithdrawFee};
+
+/// Bond status of validators as it's encoded in the `QueryValidatorsRequest`.
+const BOND_STATUS_UNBONDED: &str = "BOND_STATUS_UNBONDED";
+const BOND_STATUS_UNBONDING: &str = "BOND_STATUS_UNBONDING";
+const BOND_STATUS_BONDED: &str = "BOND_STATUS_BONDED";
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatusFilter {
+    /// Validators in the active set.
+    #[default]
+    Bonded,
+    Unbonding,
+    Unbonded,
+    All,
+}
+
+impl ValidatorStatusFilter {
+    pub(crate) fn as_bond_status(&self) -> &'static str {
+        match self {
+            ValidatorStatusFilter::Bonded => BOND_STATUS_BONDED,
+            ValidatorStatusFilter::Unbonding => BOND_STATUS_UNBONDING,
+            ValidatorStatusFilter::Unbonded => BOND_STATUS_UNBONDED,
+            // Empty status isn't filtered by the node.
+            ValidatorStatusFilter::All => "",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    Unspecified,
+    Unbonded,
+    Unbonding,
+    Bonded,
+}
+
+impl From<i32> for ValidatorStatus {
+    fn from(status: i32) -> Self {
+        match status {
+            1 => ValidatorStatus::Unbonded,
+            2 => ValidatorStatus::Unbonding,
+            3 => ValidatorStatus::Bonded,
+            _ => ValidatorStatus::Unspecified,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TendermintValidatorsRequest {
+    pub(crate) coin: String,
+    #[serde(default)]
+    pub(crate) filter_by_status: ValidatorStatusFilter,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TendermintValidatorsResponse {
+    pub(crate) validators: Vec<TendermintValidator>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TendermintValidator {
+    pub(crate) operator_address: String,
+    pub(crate) moniker: String,
+    pub(crate) website: String,
+    pub(crate) jailed: bool,
+    pub(crate) status: ValidatorStatus,
+    /// The amount of the platform coin bonded to the validator.
+    pub(crate) tokens: BigDecimal,
+    /// The share of the delegators' rewards taken by the validator, e.g. `0.05` is 5%.
+    pub(crate) commission_rate: BigDecimal,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TendermintRedelegateRequest {
+    pub(crate) coin: String,
+    pub(crate) validator_src_address: String,
+    pub(crate) validator_dst_address: String,
+    #[serde(default)]
+    pub(crate) amount: BigDecimal,
+    #[serde(default)]
+    pub(crate) max: bool,
+    pub(crate) memo: Option<String>,
+    pub(crate) fee: Option<WithdrawFee>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TendermintClaimRewardsRequest {
+    pub(crate) coin: String,
+    pub(crate) validator_address: String,
+    pub(crate) memo: Option<String>,
+    pub(crate) fee: Option<WithdrawFee>,
+}
+
+pub async fn tendermint_validators(
+    ctx: MmArc,
+    req: TendermintValidatorsRequest,
+) -> Result<TendermintValidatorsResponse, MmError<StakingInfosError>> {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
+    match coin {
+        MmCoinEnum::Tendermint(coin) => Ok(TendermintValidatorsResponse {
+            validators: coin.validators(req.filter_by_status).await?,
+        }),
+        _ => MmError::err(StakingInfosError::CoinDoesntSupportStakingInfos { coin: req.coin }),
+    }
+}
+
+pub async fn tendermint_redelegate(ctx: MmArc, req: TendermintRedelegateRequest) -> DelegationResult {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
+    match coin {
+        MmCoinEnum::Tendermint(coin) => coin.redelegate(req).await,
+        _ => MmError::err(DelegationError::CoinDoesntSupportDelegation { coin: req.coin }),
+    }
+}
+
+pub async fn tendermint_claim_rewards(ctx: MmArc, req: TendermintClaimRewardsRequest) -> DelegationResult {
+    let coin = lp_coinfind_or_err(&ctx, &req.coin).await?;
+    match coin {
+        MmCoinEnum::Tendermint(coin) => coin.claim_delegation_rewards(req).await,
+        _ => MmError::err(DelegationError::CoinDoesntSupportDelegation { coin: req.coin }),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{lp_coinfind_or_err, MmCoinEnum, WithdrawError, WithdrawResult};
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+
+#[derive(Clone, Deserialize)]
+pub struct ZCoinShieldRequest {
+    pub(crate) coin: String,
+    /// The ZIP32 account to shield the funds to, the primary account is used if not set.
+    #[serde(default)]
+    pub(crate) z_account: Option<u32>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ZCoinDeshieldRequest {
+    pub(crate) coin: String,
+    pub(crate) to: String,
+    #[serde(default)]
+    pub(crate) amount: BigDecimal,
+    #[serde(default)]
+    pub(crate) max: bool,
+    /// The ZIP32 account to spend the notes from, the primary account is used if not set.
+    #[serde(default)]
+    pub(crate) z_account: Option<u32>,
+}
+
+/// Sweeps all the transparent UTXOs of the ZCoin into its shielded account.
+pub async fn z_coin_shield(ctx: MmArc, req: ZCoinShieldRequest) -> WithdrawResult {
+    match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::ZCoin(coin) => coin.shield(req).await,
+        _ => MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
+    }
+}
+
+/// Sends the shielded funds of the ZCoin to a transparent address.
+pub async fn z_coin_deshield(ctx: MmArc, req: ZCoinDeshieldRequest) -> WithdrawResult {
+    match lp_coinfind_or_err(&ctx, &req.coin).await? {
+        MmCoinEnum::ZCoin(coin) => coin.deshield(req).await,
+        _ => MmError::err(WithdrawError::ActionNotAllowed(req.coin)),
+    }
+}
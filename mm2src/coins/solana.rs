@@ -1,24 +1,29 @@
 use super::{CoinBalance, HistorySyncState, MarketCoinOps, MmCoin, SwapOps, TradeFee, TransactionEnum, WatcherOps};
 use crate::coin_errors::MyAddressError;
 use crate::solana::solana_common::{lamports_to_sol, PrepareTransferData, SufficientBalanceError};
+use crate::solana::solana_htlc::{check_tx_signed_by_pub, htlc_pubkey, pubkey_from_htlc_pubkey, watchers_not_supported,
+                                 watchers_not_supported_tx_fut, watchers_not_supported_validation_fut};
 use crate::solana::spl::SplTokenInfo;
 use crate::{BalanceError, BalanceFut, CheckIfMyPaymentSentArgs, CoinFutSpawner, ConfirmPaymentInput, DexFee,
             FeeApproxStage, FoundSwapTxSpend, MakerSwapTakerCoin, MmCoinEnum, NegotiateSwapContractAddrErr,
             PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr, PrivKeyBuildPolicy,
             PrivKeyPolicyNotAllowed, RawTransactionFut, RawTransactionRequest, RefundError, RefundPaymentArgs,
             RefundResult, SearchForSwapTxSpendInput, SendMakerPaymentSpendPreimageInput, SendPaymentArgs,
-            SignatureResult, SpendPaymentArgs, TakerSwapMakerCoin, TradePreimageFut, TradePreimageResult,
-            TradePreimageValue, TransactionDetails, TransactionFut, TransactionResult, TransactionType,
-            TxMarshalingErr, UnexpectedDerivationMethod, ValidateAddressResult, ValidateFeeArgs,
-            ValidateInstructionsErr, ValidateOtherPubKeyErr, ValidatePaymentError, ValidatePaymentFut,
-            ValidatePaymentInput, ValidateWatcherSpendInput, VerificationResult, WaitForHTLCTxSpendArgs,
-            WatcherReward, WatcherRewardError, WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput,
-            WatcherValidateTakerFeeInput, WithdrawError, WithdrawFut, WithdrawRequest, WithdrawResult};
+            SignatureResult, SpendPaymentArgs, TakerSwapMakerCoin, TradePreimageError, TradePreimageFut,
+            TradePreimageResult, TradePreimageValue, Transaction as TransactionTrait, TransactionDetails,
+            TransactionFut, TransactionResult, TransactionType, TxMarshalingErr, UnexpectedDerivationMethod,
+            ValidateAddressResult, ValidateFeeArgs, ValidateInstructionsErr, ValidateOtherPubKeyErr,
+            ValidatePaymentError, ValidatePaymentFut, ValidatePaymentInput, ValidateWatcherSpendInput,
+            VerificationResult, WaitForHTLCTxSpendArgs, WatcherReward, WatcherRewardError,
+            WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput, WatcherValidateTakerFeeInput,
+            WithdrawError, WithdrawFut, WithdrawRequest, WithdrawResult};
 use async_trait::async_trait;
 use base58::ToBase58;
 use bincode::{deserialize, serialize};
 use common::executor::{abortable_queue::AbortableQueue, AbortableSystem, AbortedError};
+use common::log::warn;
 use common::{async_blocking, now_sec};
+use crypto::privkey::key_pair_from_secret;
 use crypto::{StandardHDCoinAddress, StandardHDPathToCoin};
 use derive_more::Display;
 use futures::{FutureExt, TryFutureExt};
@@ -45,6 +50,8 @@ use std::{convert::TryFrom, fmt::Debug, ops::Deref, sync::Arc};
 
 pub mod solana_common;
 mod solana_decode_tx_helpers;
+mod solana_htlc;
+pub mod solana_tx_history_v2;
 pub mod spl;
 
 #[cfg(test)] mod solana_common_tests;
@@ -54,6 +61,20 @@ pub mod spl;
 pub const SOLANA_DEFAULT_DECIMALS: u64 = 9;
 pub const LAMPORTS_DUMMY_AMOUNT: u64 = 10;
 
+pub type SolanaTransaction = Transaction;
+
+impl TransactionTrait for SolanaTransaction {
+    fn tx_hex(&self) -> Vec<u8> { serialize(self).expect("Serialization should not fail") }
+
+    fn tx_hash(&self) -> BytesJson {
+        self.signatures
+            .first()
+            .map(|sig| sig.as_ref().to_vec())
+            .unwrap_or_default()
+            .into()
+    }
+}
+
 #[async_trait]
 pub trait SolanaCommonOps {
     fn rpc(&self) -> &RpcClient;
@@ -141,6 +162,12 @@ pub struct SolanaActivationParams {
     client_url: String,
     #[serde(default)]
     path_to_address: StandardHDCoinAddress,
+    /// The address of the HTLC program, swaps are disabled if it's not set.
+    #[serde(default)]
+    swap_program_id: Option<String>,
+    /// Whether the transaction history of the coin and its tokens should be fetched.
+    #[serde(default)]
+    pub tx_history: bool,
 }
 
 #[derive(Debug, Display)]
@@ -194,9 +221,23 @@ pub async fn solana_coin_with_policy(
         PrivKeyBuildPolicy::Trezor => return ERR!("{}", PrivKeyPolicyNotAllowed::HardwareWalletNotSupported),
     };
 
+    let swap_program_id = match params.swap_program_id {
+        Some(ref program_id) => Some(try_s!(Pubkey::from_str(program_id))),
+        None => None,
+    };
+    let dex_fee_address = match conf["dex_fee_address"].as_str() {
+        Some(address) => Some(try_s!(Pubkey::from_str(address))),
+        None => None,
+    };
+
     let key_pair = try_s!(generate_keypair_from_slice(priv_key.as_slice()));
     let my_address = key_pair.pubkey().to_string();
     let spl_tokens_infos = Arc::new(Mutex::new(HashMap::new()));
+    let history_sync_state = if params.tx_history {
+        HistorySyncState::NotStarted
+    } else {
+        HistorySyncState::NotEnabled
+    };
 
     // Create an abortable system linked to the `MmCtx` so if the context is stopped via `MmArc::stop`,
     // all spawned futures related to `SolanaCoin` will be aborted as well.
@@ -209,6 +250,9 @@ pub async fn solana_coin_with_policy(
         client,
         decimals,
         spl_tokens_infos,
+        swap_program_id,
+        dex_fee_address,
+        history_sync_state: Mutex::new(history_sync_state),
         abortable_system,
     }));
     Ok(solana_coin)
@@ -222,6 +266,11 @@ pub struct SolanaCoinImpl {
     decimals: u8,
     my_address: String,
     spl_tokens_infos: Arc<Mutex<HashMap<String, SplTokenInfo>>>,
+    swap_program_id: Option<Pubkey>,
+    /// The address the dex fee of swaps is sent to, it's set in the coin config since
+    /// the dex fee pubkey is secp256k1 and can't be used as a Solana address.
+    dex_fee_address: Option<Pubkey>,
+    history_sync_state: Mutex<HistorySyncState>,
     /// This spawner is used to spawn coin's related futures that should be aborted on coin deactivation
     /// and on [`MmArc::stop`].
     pub abortable_system: AbortableQueue,
@@ -365,6 +414,16 @@ impl SolanaCoin {
         Box::new(fut.boxed().compat())
     }
 
+    /// The fee of a swap transaction, it's always paid in SOL.
+    pub(crate) async fn swap_trade_fee(&self) -> TradePreimageResult<TradeFee> {
+        let amount = self.swap_tx_fee().await.map_to_mm(TradePreimageError::Transport)?;
+        Ok(TradeFee {
+            coin: self.ticker.clone(),
+            amount: amount.into(),
+            paid_from_trading_vol: false,
+        })
+    }
+
     pub fn add_spl_token_info(&self, ticker: String, info: SplTokenInfo) {
         self.spl_tokens_infos.lock().unwrap().insert(ticker, info);
     }
@@ -376,6 +435,65 @@ impl SolanaCoin {
         let guard = self.spl_tokens_infos.lock().unwrap();
         (*guard).clone()
     }
+
+    pub(crate) fn set_history_sync_state(&self, new_state: HistorySyncState) {
+        *self.history_sync_state.lock().unwrap() = new_state;
+    }
+}
+
+#[cfg(feature = "run-docker-tests")]
+impl SolanaCoin {
+    /// Creates a new SPL token with the coin's address as the mint authority and mints the `amount` of it to the `owner`.
+    pub async fn create_spl_token_for_tests(&self, decimals: u8, owner: &str, amount: u64) -> Result<Pubkey, String> {
+        use solana_sdk::program_pack::Pack;
+        use solana_sdk::system_instruction;
+        use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+        let owner = try_s!(Pubkey::from_str(owner));
+        let payer = self.key_pair.pubkey();
+        let mint = Keypair::new();
+        let mint_pubkey = mint.pubkey();
+
+        let coin = self.clone();
+        async_blocking(move || {
+            let rent = coin
+                .rpc()
+                .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+                .map_err(|e| format!("{:?}", e))?;
+            let instructions = vec![
+                system_instruction::create_account(
+                    &payer,
+                    &mint_pubkey,
+                    rent,
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                try_s!(spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint_pubkey,
+                    &payer,
+                    None,
+                    decimals
+                )),
+                create_associated_token_account(&payer, &owner, &mint_pubkey),
+                try_s!(spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    &mint_pubkey,
+                    &get_associated_token_address(&owner, &mint_pubkey),
+                    &payer,
+                    &[],
+                    amount
+                )),
+            ];
+            let hash = coin.rpc().get_latest_blockhash().map_err(|e| format!("{:?}", e))?;
+            let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer), &[&coin.key_pair, &mint], hash);
+            coin.rpc()
+                .send_and_confirm_transaction(&tx)
+                .map_err(|e| format!("{:?}", e))?;
+            Ok(mint_pubkey)
+        })
+        .await
+    }
 }
 
 impl MarketCoinOps for SolanaCoin {
@@ -441,16 +559,18 @@ impl MarketCoinOps for SolanaCoin {
         Box::new(fut.boxed().compat())
     }
 
-    fn wait_for_confirmations(&self, _input: ConfirmPaymentInput) -> Box<dyn Future<Item = (), Error = String> + Send> {
-        unimplemented!()
+    fn wait_for_confirmations(&self, input: ConfirmPaymentInput) -> Box<dyn Future<Item = (), Error = String> + Send> {
+        self.wait_for_tx_confirmations(input.payment_tx, input.wait_until, input.check_every)
     }
 
-    fn wait_for_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut { unimplemented!() }
+    fn wait_for_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        self.wait_for_htlc_spend(args)
+    }
 
-    fn tx_enum_from_bytes(&self, _bytes: &[u8]) -> Result<TransactionEnum, MmError<TxMarshalingErr>> {
-        MmError::err(TxMarshalingErr::NotSupported(
-            "tx_enum_from_bytes is not supported for Solana yet.".to_string(),
-        ))
+    fn tx_enum_from_bytes(&self, bytes: &[u8]) -> Result<TransactionEnum, MmError<TxMarshalingErr>> {
+        deserialize(bytes)
+            .map(TransactionEnum::SolanaTransaction)
+            .map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))
     }
 
     fn current_block(&self) -> Box<dyn Future<Item = u64, Error = String> + Send> {
@@ -468,72 +588,78 @@ impl MarketCoinOps for SolanaCoin {
 
 #[async_trait]
 impl SwapOps for SolanaCoin {
-    fn send_taker_fee(&self, _fee_addr: &[u8], dex_fee: DexFee, _uuid: &[u8]) -> TransactionFut { unimplemented!() }
+    fn send_taker_fee(&self, _fee_addr: &[u8], dex_fee: DexFee, _uuid: &[u8]) -> TransactionFut {
+        self.send_dex_fee(dex_fee, None)
+    }
 
-    fn send_maker_payment(&self, _maker_payment_args: SendPaymentArgs) -> TransactionFut { unimplemented!() }
+    fn send_maker_payment(&self, maker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.send_htlc_payment(maker_payment_args, None)
+    }
 
-    fn send_taker_payment(&self, _taker_payment_args: SendPaymentArgs) -> TransactionFut { unimplemented!() }
+    fn send_taker_payment(&self, taker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.send_htlc_payment(taker_payment_args, None)
+    }
 
-    fn send_maker_spends_taker_payment(&self, _maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
-        unimplemented!()
+    fn send_maker_spends_taker_payment(&self, maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.spend_htlc_payment(maker_spends_payment_args)
     }
 
-    fn send_taker_spends_maker_payment(&self, _taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
-        unimplemented!()
+    fn send_taker_spends_maker_payment(&self, taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.spend_htlc_payment(taker_spends_payment_args)
     }
 
-    async fn send_taker_refunds_payment(
-        &self,
-        _taker_refunds_payment_args: RefundPaymentArgs<'_>,
-    ) -> TransactionResult {
-        unimplemented!()
+    async fn send_taker_refunds_payment(&self, taker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        self.refund_htlc_payment(taker_refunds_payment_args).await
     }
 
-    async fn send_maker_refunds_payment(
-        &self,
-        _maker_refunds_payment_args: RefundPaymentArgs<'_>,
-    ) -> TransactionResult {
-        unimplemented!()
+    async fn send_maker_refunds_payment(&self, maker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        self.refund_htlc_payment(maker_refunds_payment_args).await
     }
 
-    fn validate_fee(&self, _validate_fee_args: ValidateFeeArgs) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_fee(&self, validate_fee_args: ValidateFeeArgs) -> ValidatePaymentFut<()> {
+        self.validate_dex_fee(validate_fee_args, None)
+    }
 
-    fn validate_maker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_maker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.validate_htlc_payment(input, None)
+    }
 
-    fn validate_taker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_taker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.validate_htlc_payment(input, None)
+    }
 
     fn check_if_my_payment_sent(
         &self,
-        _if_my_payment_sent_args: CheckIfMyPaymentSentArgs,
+        if_my_payment_sent_args: CheckIfMyPaymentSentArgs,
     ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
-        unimplemented!()
+        self.check_if_my_htlc_payment_sent(if_my_payment_sent_args, None)
     }
 
     async fn search_for_swap_tx_spend_my(
         &self,
-        _: SearchForSwapTxSpendInput<'_>,
+        input: SearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!()
+        self.search_for_htlc_spend(input.tx).await
     }
 
     async fn search_for_swap_tx_spend_other(
         &self,
-        _: SearchForSwapTxSpendInput<'_>,
+        input: SearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!()
+        self.search_for_htlc_spend(input.tx).await
     }
 
     fn check_tx_signed_by_pub(&self, tx: &[u8], expected_pub: &[u8]) -> Result<bool, MmError<ValidatePaymentError>> {
-        unimplemented!();
+        check_tx_signed_by_pub(tx, expected_pub)
     }
 
     async fn extract_secret(
         &self,
         secret_hash: &[u8],
         spend_tx: &[u8],
-        watcher_reward: bool,
+        _watcher_reward: bool,
     ) -> Result<Vec<u8>, String> {
-        unimplemented!()
+        self.extract_htlc_secret(secret_hash, spend_tx)
     }
 
     fn is_auto_refundable(&self) -> bool { false }
@@ -546,33 +672,52 @@ impl SwapOps for SolanaCoin {
 
     fn negotiate_swap_contract_addr(
         &self,
-        _other_side_address: Option<&[u8]>,
+        other_side_address: Option<&[u8]>,
     ) -> Result<Option<BytesJson>, MmError<NegotiateSwapContractAddrErr>> {
-        unimplemented!()
+        let swap_program_id = self
+            .swap_program_id
+            .or_mm_err(|| NegotiateSwapContractAddrErr::NoOtherAddrAndNoFallback)?;
+        match other_side_address {
+            Some(bytes) => {
+                if bytes.len() != 32 {
+                    return MmError::err(NegotiateSwapContractAddrErr::InvalidOtherAddrLen(bytes.into()));
+                }
+                if Pubkey::new(bytes) != swap_program_id {
+                    return MmError::err(NegotiateSwapContractAddrErr::UnexpectedOtherAddr(bytes.into()));
+                }
+                Ok(Some(swap_program_id.to_bytes().to_vec().into()))
+            },
+            None => MmError::err(NegotiateSwapContractAddrErr::NoOtherAddrAndNoFallback),
+        }
     }
 
-    #[inline]
-    fn derive_htlc_key_pair(&self, _swap_unique_data: &[u8]) -> KeyPair { todo!() }
+    /// The secp256k1 key pair is only used to sign the watcher messages, and the ed25519 key pair is generated
+    /// from the same private key, so it can't fail.
+    fn derive_htlc_key_pair(&self, _swap_unique_data: &[u8]) -> KeyPair {
+        key_pair_from_secret(self.key_pair.secret().as_bytes()).expect("valid secp256k1 priv key")
+    }
 
+    /// The ed25519 pubkey of the coin's key pair is used in the swap instead of a derived secp256k1 pubkey.
     #[inline]
-    fn derive_htlc_pubkey(&self, swap_unique_data: &[u8]) -> Vec<u8> {
-        self.derive_htlc_key_pair(swap_unique_data).public_slice().to_vec()
-    }
+    fn derive_htlc_pubkey(&self, _swap_unique_data: &[u8]) -> Vec<u8> { htlc_pubkey(&self.key_pair.pubkey()) }
 
-    fn validate_other_pubkey(&self, _raw_pubkey: &[u8]) -> MmResult<(), ValidateOtherPubKeyErr> { unimplemented!() }
+    fn validate_other_pubkey(&self, raw_pubkey: &[u8]) -> MmResult<(), ValidateOtherPubKeyErr> {
+        pubkey_from_htlc_pubkey(raw_pubkey).map_to_mm(ValidateOtherPubKeyErr::InvalidPubKey)?;
+        Ok(())
+    }
 
     async fn maker_payment_instructions(
         &self,
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
-        unimplemented!()
+        Ok(None)
     }
 
     async fn taker_payment_instructions(
         &self,
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
-        unimplemented!()
+        Ok(None)
     }
 
     fn validate_maker_payment_instructions(
@@ -580,7 +725,7 @@ impl SwapOps for SolanaCoin {
         _instructions: &[u8],
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
-        unimplemented!()
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
     }
 
     fn validate_taker_payment_instructions(
@@ -588,7 +733,7 @@ impl SwapOps for SolanaCoin {
         _instructions: &[u8],
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
-        unimplemented!()
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
     }
 }
 
@@ -616,11 +761,11 @@ impl WatcherOps for SolanaCoin {
         _secret_hash: &[u8],
         _swap_unique_data: &[u8],
     ) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn send_maker_payment_spend_preimage(&self, _input: SendMakerPaymentSpendPreimageInput) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn create_taker_payment_refund_preimage(
@@ -632,50 +777,50 @@ impl WatcherOps for SolanaCoin {
         _swap_contract_address: &Option<BytesJson>,
         _swap_unique_data: &[u8],
     ) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn send_taker_payment_refund_preimage(&self, _watcher_refunds_payment_args: RefundPaymentArgs) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
-    fn watcher_validate_taker_fee(&self, input: WatcherValidateTakerFeeInput) -> ValidatePaymentFut<()> {
-        unimplemented!();
+    fn watcher_validate_taker_fee(&self, _input: WatcherValidateTakerFeeInput) -> ValidatePaymentFut<()> {
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     fn watcher_validate_taker_payment(&self, _input: WatcherValidatePaymentInput) -> ValidatePaymentFut<()> {
-        unimplemented!();
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     fn taker_validates_payment_spend_or_refund(&self, _input: ValidateWatcherSpendInput) -> ValidatePaymentFut<()> {
-        unimplemented!()
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     async fn watcher_search_for_swap_tx_spend(
         &self,
-        input: WatcherSearchForSwapTxSpendInput<'_>,
+        _input: WatcherSearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!();
+        Err(watchers_not_supported(self.ticker()))
     }
 
     async fn get_taker_watcher_reward(
         &self,
-        other_coin: &MmCoinEnum,
-        coin_amount: Option<BigDecimal>,
-        other_coin_amount: Option<BigDecimal>,
-        reward_amount: Option<BigDecimal>,
-        wait_until: u64,
+        _other_coin: &MmCoinEnum,
+        _coin_amount: Option<BigDecimal>,
+        _other_coin_amount: Option<BigDecimal>,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
     ) -> Result<WatcherReward, MmError<WatcherRewardError>> {
-        unimplemented!();
+        MmError::err(WatcherRewardError::InternalError(watchers_not_supported(self.ticker())))
     }
 
     async fn get_maker_watcher_reward(
         &self,
-        other_coin: &MmCoinEnum,
-        reward_amount: Option<BigDecimal>,
-        wait_until: u64,
+        _other_coin: &MmCoinEnum,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
     ) -> Result<Option<WatcherReward>, MmError<WatcherRewardError>> {
-        unimplemented!();
+        MmError::err(WatcherRewardError::InternalError(watchers_not_supported(self.ticker())))
     }
 }
 
@@ -726,42 +871,57 @@ impl MmCoin for SolanaCoin {
         }
     }
 
-    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> { unimplemented!() }
+    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> {
+        warn!("process_history_loop is deprecated, solana uses tx_history_v2");
+        Box::new(futures01::future::err(()))
+    }
 
-    fn history_sync_status(&self) -> HistorySyncState { unimplemented!() }
+    fn history_sync_status(&self) -> HistorySyncState { self.history_sync_state.lock().unwrap().clone() }
 
     /// Get fee to be paid per 1 swap transaction
-    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> { unimplemented!() }
+    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> {
+        let coin = self.clone();
+        let fut = async move { coin.swap_trade_fee().await.map_err(|e| ERRL!("{}", e)) };
+        Box::new(fut.boxed().compat())
+    }
 
     async fn get_sender_trade_fee(
         &self,
         _value: TradePreimageValue,
         _stage: FeeApproxStage,
     ) -> TradePreimageResult<TradeFee> {
-        unimplemented!()
+        self.swap_trade_fee().await
     }
 
-    fn get_receiver_trade_fee(&self, _stage: FeeApproxStage) -> TradePreimageFut<TradeFee> { unimplemented!() }
+    fn get_receiver_trade_fee(&self, _stage: FeeApproxStage) -> TradePreimageFut<TradeFee> {
+        let coin = self.clone();
+        let fut = async move { coin.swap_trade_fee().await };
+        Box::new(fut.boxed().compat())
+    }
 
     async fn get_fee_to_send_taker_fee(
         &self,
         _dex_fee_amount: DexFee,
         _stage: FeeApproxStage,
     ) -> TradePreimageResult<TradeFee> {
-        unimplemented!()
+        self.swap_trade_fee().await
     }
 
     fn required_confirmations(&self) -> u64 { 1 }
 
     fn requires_notarization(&self) -> bool { false }
 
-    fn set_required_confirmations(&self, _confirmations: u64) { unimplemented!() }
+    /// The transactions are awaited with the confirmation commitment of the RPC client instead.
+    fn set_required_confirmations(&self, _confirmations: u64) {}
 
     fn set_requires_notarization(&self, _requires_nota: bool) { unimplemented!() }
 
-    fn swap_contract_address(&self) -> Option<BytesJson> { unimplemented!() }
+    fn swap_contract_address(&self) -> Option<BytesJson> {
+        self.swap_program_id
+            .map(|program_id| program_id.to_bytes().to_vec().into())
+    }
 
-    fn fallback_swap_contract(&self) -> Option<BytesJson> { unimplemented!() }
+    fn fallback_swap_contract(&self) -> Option<BytesJson> { None }
 
     fn mature_confirmations(&self) -> Option<u32> { None }
 
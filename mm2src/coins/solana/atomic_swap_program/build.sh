@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+# Builds the program into `target/deploy/atomic_swap.so`.
+# Requires the Solana tool suite of the version the docker tests run the validator with (v1.9.20).
+set -e
+cd "$(dirname "$0")"
+cargo build-bpf --bpf-out-dir target/deploy
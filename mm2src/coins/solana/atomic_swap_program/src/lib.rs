@@ -0,0 +1,351 @@
+//! The HTLC program of the SOL and SPL tokens atomic swaps.
+//!
+//! The payment is locked in an account derived from the swap parameters (a program derived address),
+//! the receiver claims it by revealing the secret whose SHA256 hash the payment is locked with,
+//! and the sender gets it back once the lock time has passed.
+//!
+//! The instructions and the payment address derivation must match the ones of `coins::solana::solana_htlc`.
+
+use serde::{Deserialize, Serialize};
+use solana_program::account_info::{next_account_info, AccountInfo};
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::hash::hashv;
+use solana_program::msg;
+use solana_program::program::{invoke, invoke_signed};
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction;
+use solana_program::system_program;
+use solana_program::sysvar::{self, Sysvar};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::state::{Account as TokenAccount, Mint};
+use std::convert::TryFrom;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+const PAYMENT_SEED: &[u8] = b"swap_payment";
+
+/// The parameters that the payment is locked with, they are also the seeds of the payment account address.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HtlcParams {
+    pub secret_hash: [u8; 32],
+    pub lock_time: u64,
+    /// The amount in lamports or in the token's base units.
+    pub amount: u64,
+    pub sender: Pubkey,
+    pub receiver: Pubkey,
+    /// The mint of the SPL token, `None` for SOL payments.
+    pub token_mint: Option<Pubkey>,
+}
+
+impl HtlcParams {
+    fn with_payment_seeds<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+        let lock_time = self.lock_time.to_le_bytes();
+        let amount = self.amount.to_le_bytes();
+        let token_mint = self.token_mint.unwrap_or_default();
+        f(&[
+            PAYMENT_SEED,
+            self.sender.as_ref(),
+            self.receiver.as_ref(),
+            &self.secret_hash,
+            &lock_time,
+            &amount,
+            token_mint.as_ref(),
+        ])
+    }
+
+    /// Returns the payment account address and its bump seed.
+    fn find_payment_address(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        self.with_payment_seeds(|seeds| Pubkey::find_program_address(seeds, program_id))
+    }
+
+    /// Invokes the instruction signed by the payment account.
+    fn invoke_signed_by_payment(
+        &self,
+        bump_seed: u8,
+        instruction: &solana_program::instruction::Instruction,
+        account_infos: &[AccountInfo],
+    ) -> ProgramResult {
+        self.with_payment_seeds(|seeds| {
+            let bump_seed = [bump_seed];
+            let mut signer_seeds = seeds.to_vec();
+            signer_seeds.push(&bump_seed);
+            invoke_signed(instruction, account_infos, &[&signer_seeds])
+        })
+    }
+}
+
+/// The instructions of the program, serialized with bincode.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum HtlcInstruction {
+    /// Transfers the amount from the sender to the payment account.
+    Payment(HtlcParams),
+    /// Transfers the locked amount to the receiver if the SHA256 hash of the secret equals the secret hash.
+    ReceiverSpend { params: HtlcParams, secret: [u8; 32] },
+    /// Transfers the locked amount back to the sender if the lock time has passed.
+    SenderRefund(HtlcParams),
+}
+
+/// The accounts of an instruction:
+/// 0. `[signer, writable]` The sender for `Payment` and `SenderRefund`, the receiver for `ReceiverSpend`.
+/// 1. `[writable]` The payment account.
+/// 2. `[]` The system program.
+///
+/// And for SPL token payments:
+/// 3. `[]` The token mint.
+/// 4. `[writable]` The associated token account of the payment account.
+/// 5. `[writable]` The associated token account of the signer.
+/// 6. `[]` The SPL token program.
+/// 7. `[]` The associated token account program.
+/// 8. `[]` The rent sysvar.
+struct HtlcAccounts<'a, 'b> {
+    signer: &'a AccountInfo<'b>,
+    payment: &'a AccountInfo<'b>,
+    payment_bump_seed: u8,
+    system_program: &'a AccountInfo<'b>,
+    token: Option<TokenAccounts<'a, 'b>>,
+}
+
+struct TokenAccounts<'a, 'b> {
+    mint: &'a AccountInfo<'b>,
+    payment_token_account: &'a AccountInfo<'b>,
+    signer_token_account: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+    associated_token_program: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> HtlcAccounts<'a, 'b> {
+    fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+        params: &HtlcParams,
+        expected_signer: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let signer = next_account_info(accounts_iter)?;
+        if signer.key != expected_signer || !signer.is_signer {
+            msg!("The instruction must be signed by {}", expected_signer);
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let payment = next_account_info(accounts_iter)?;
+        let (payment_address, payment_bump_seed) = params.find_payment_address(program_id);
+        if *payment.key != payment_address {
+            msg!("Invalid payment account {}", payment.key);
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let system_program = next_account_info(accounts_iter)?;
+        check_program(system_program, &system_program::id())?;
+
+        let token = match params.token_mint {
+            Some(token_mint) => {
+                let mint = next_account_info(accounts_iter)?;
+                if *mint.key != token_mint {
+                    msg!("Invalid token mint {}", mint.key);
+                    return Err(ProgramError::InvalidArgument);
+                }
+                let payment_token_account = next_account_info(accounts_iter)?;
+                check_associated_token_account(payment_token_account, &payment_address, &token_mint)?;
+                let signer_token_account = next_account_info(accounts_iter)?;
+                check_associated_token_account(signer_token_account, signer.key, &token_mint)?;
+                let token_program = next_account_info(accounts_iter)?;
+                check_program(token_program, &spl_token::id())?;
+                let associated_token_program = next_account_info(accounts_iter)?;
+                check_program(associated_token_program, &spl_associated_token_account::id())?;
+                let rent_sysvar = next_account_info(accounts_iter)?;
+                check_program(rent_sysvar, &sysvar::rent::id())?;
+                Some(TokenAccounts {
+                    mint,
+                    payment_token_account,
+                    signer_token_account,
+                    token_program,
+                    associated_token_program,
+                    rent_sysvar,
+                })
+            },
+            None => None,
+        };
+
+        Ok(HtlcAccounts {
+            signer,
+            payment,
+            payment_bump_seed,
+            system_program,
+            token,
+        })
+    }
+
+    /// Creates the associated token account of the `owner` paid by the signer if it doesn't exist yet.
+    fn create_token_account_if_missing(
+        &self,
+        token: &TokenAccounts<'a, 'b>,
+        token_account: &'a AccountInfo<'b>,
+        owner: &'a AccountInfo<'b>,
+    ) -> ProgramResult {
+        if !token_account.data_is_empty() {
+            return Ok(());
+        }
+        invoke(
+            &create_associated_token_account(self.signer.key, owner.key, token.mint.key),
+            &[
+                self.signer.clone(),
+                token_account.clone(),
+                owner.clone(),
+                token.mint.clone(),
+                self.system_program.clone(),
+                token.token_program.clone(),
+                token.rent_sysvar.clone(),
+                token.associated_token_program.clone(),
+            ],
+        )
+    }
+}
+
+fn check_program(account: &AccountInfo, expected: &Pubkey) -> ProgramResult {
+    if account.key != expected {
+        msg!("Expected {} account, found {}", expected, account.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+fn check_associated_token_account(account: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    if *account.key != get_associated_token_address(owner, mint) {
+        msg!("Invalid associated token account {} of {}", account.key, owner);
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+fn token_balance(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(TokenAccount::unpack(&token_account.data.borrow())?.amount)
+}
+
+fn token_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> { Ok(Mint::unpack(&mint.data.borrow())?.decimals) }
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let instruction: HtlcInstruction =
+        bincode::deserialize(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        HtlcInstruction::Payment(params) => {
+            let accounts = HtlcAccounts::parse(program_id, accounts, &params, &params.sender)?;
+            process_payment(&accounts, &params)
+        },
+        HtlcInstruction::ReceiverSpend { params, secret } => {
+            if hashv(&[&secret]).to_bytes() != params.secret_hash {
+                msg!("The secret doesn't match the secret hash");
+                return Err(ProgramError::InvalidArgument);
+            }
+            let accounts = HtlcAccounts::parse(program_id, accounts, &params, &params.receiver)?;
+            release_payment(&accounts, &params)
+        },
+        HtlcInstruction::SenderRefund(params) => {
+            let lock_time = i64::try_from(params.lock_time).unwrap_or(i64::MAX);
+            if Clock::get()?.unix_timestamp < lock_time {
+                msg!("The payment can't be refunded before {}", params.lock_time);
+                return Err(ProgramError::InvalidArgument);
+            }
+            let accounts = HtlcAccounts::parse(program_id, accounts, &params, &params.sender)?;
+            release_payment(&accounts, &params)
+        },
+    }
+}
+
+/// Locks the amount in the payment account, the same payment can't be sent twice.
+fn process_payment(accounts: &HtlcAccounts, params: &HtlcParams) -> ProgramResult {
+    match &accounts.token {
+        Some(token) => {
+            accounts.create_token_account_if_missing(token, token.payment_token_account, accounts.payment)?;
+            if token_balance(token.payment_token_account)? != 0 {
+                msg!("The payment is already sent");
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            invoke(
+                &spl_token::instruction::transfer_checked(
+                    token.token_program.key,
+                    token.signer_token_account.key,
+                    token.mint.key,
+                    token.payment_token_account.key,
+                    accounts.signer.key,
+                    &[],
+                    params.amount,
+                    token_decimals(token.mint)?,
+                )?,
+                &[
+                    token.signer_token_account.clone(),
+                    token.mint.clone(),
+                    token.payment_token_account.clone(),
+                    accounts.signer.clone(),
+                    token.token_program.clone(),
+                ],
+            )
+        },
+        None => {
+            if accounts.payment.lamports() != 0 {
+                msg!("The payment is already sent");
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            invoke(
+                &system_instruction::transfer(accounts.signer.key, accounts.payment.key, params.amount),
+                &[
+                    accounts.signer.clone(),
+                    accounts.payment.clone(),
+                    accounts.system_program.clone(),
+                ],
+            )
+        },
+    }
+}
+
+/// Transfers everything locked in the payment account to the signer.
+fn release_payment(accounts: &HtlcAccounts, params: &HtlcParams) -> ProgramResult {
+    match &accounts.token {
+        Some(token) => {
+            let locked_amount = token_balance(token.payment_token_account)?;
+            if locked_amount < params.amount {
+                msg!("The payment is not sent or already spent");
+                return Err(ProgramError::InsufficientFunds);
+            }
+            accounts.create_token_account_if_missing(token, token.signer_token_account, accounts.signer)?;
+            params.invoke_signed_by_payment(
+                accounts.payment_bump_seed,
+                &spl_token::instruction::transfer_checked(
+                    token.token_program.key,
+                    token.payment_token_account.key,
+                    token.mint.key,
+                    token.signer_token_account.key,
+                    accounts.payment.key,
+                    &[],
+                    locked_amount,
+                    token_decimals(token.mint)?,
+                )?,
+                &[
+                    token.payment_token_account.clone(),
+                    token.mint.clone(),
+                    token.signer_token_account.clone(),
+                    accounts.payment.clone(),
+                    token.token_program.clone(),
+                ],
+            )
+        },
+        None => {
+            let locked_amount = accounts.payment.lamports();
+            if locked_amount < params.amount {
+                msg!("The payment is not sent or already spent");
+                return Err(ProgramError::InsufficientFunds);
+            }
+            params.invoke_signed_by_payment(
+                accounts.payment_bump_seed,
+                &system_instruction::transfer(accounts.payment.key, accounts.signer.key, locked_amount),
+                &[
+                    accounts.payment.clone(),
+                    accounts.signer.clone(),
+                    accounts.system_program.clone(),
+                ],
+            )
+        },
+    }
+}
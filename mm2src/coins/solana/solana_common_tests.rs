@@ -93,6 +93,9 @@ pub fn solana_coin_for_test(seed: String, net_type: SolanaNet) -> (MmArc, Solana
         ticker,
         client,
         spl_tokens_infos,
+        swap_program_id: None,
+        dex_fee_address: None,
+        history_sync_state: Mutex::new(HistorySyncState::NotEnabled),
         abortable_system: spawner,
     }));
     (ctx, solana_coin)
@@ -1,78 +1,224 @@
 extern crate serde_derive;
 
+use crate::solana::spl::SplTokenInfo;
+use crate::utxo::utxo_common::big_decimal_from_sat_unsigned;
 use crate::{NumConversResult, SolanaCoin, SolanaFeeDetails, TransactionDetails, TransactionType};
+use bitcrypto::sha256;
 use mm2_number::BigDecimal;
+use rpc::v1::types::Bytes as BytesJson;
 use solana_sdk::native_token::lamports_to_sol;
 use std::convert::TryFrom;
 
+/// The instruction index used to build the internal id of the fee entry of a token transaction.
+const FEE_FOR_TOKEN_TX_INDEX: usize = usize::MAX;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SolanaConfirmedTransaction {
     slot: u64,
     transaction: Transaction,
     meta: Meta,
     #[serde(rename = "blockTime")]
-    block_time: u64,
+    block_time: Option<u64>,
+}
+
+/// Builds a unique id of the transfer made by the `index`-th instruction of the transaction.
+/// The `token_mint` is used to distinguish SPL token transfers from the SOL transfers of the same transaction.
+fn transfer_internal_id(signature: &str, token_mint: Option<&str>, index: usize) -> BytesJson {
+    let mut preimage = signature.as_bytes().to_vec();
+    if let Some(mint) = token_mint {
+        preimage.extend_from_slice(mint.as_bytes());
+    }
+    preimage.extend_from_slice(&(index as u64).to_le_bytes());
+    sha256(&preimage).to_vec().into()
 }
 
-#[allow(dead_code)]
 impl SolanaConfirmedTransaction {
-    pub fn extract_account_index(&self, address: String) -> usize {
-        // find the equivalent of index_of(needle) in rust, and return result later
-        let mut idx = 0_usize;
-        for account in self.transaction.message.account_keys.iter() {
-            if account.pubkey == address {
-                return idx;
+    pub fn signature(&self) -> &str {
+        self.transaction
+            .signatures
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    pub fn is_failed(&self) -> bool { self.meta.err.is_some() }
+
+    /// Whether the fee of the transaction is paid by the given `address`.
+    fn is_fee_payer(&self, address: &str) -> bool {
+        self.transaction
+            .message
+            .account_keys
+            .first()
+            .map(|account| account.pubkey == address)
+            .unwrap_or_default()
+    }
+
+    /// Returns both top-level and inner (invoked by other programs, e.g. by the HTLC program) instructions.
+    fn all_instructions(&self) -> impl Iterator<Item = &Instruction> {
+        let inner = self
+            .meta
+            .inner_instructions
+            .iter()
+            .flatten()
+            .flat_map(|inner| inner.instructions.iter());
+        self.transaction.message.instructions.iter().chain(inner)
+    }
+
+    fn details_template(&self, ticker: &str) -> NumConversResult<TransactionDetails> {
+        let fee = BigDecimal::try_from(lamports_to_sol(self.meta.fee))?;
+        Ok(TransactionDetails {
+            tx_hex: Default::default(),
+            tx_hash: self.signature().to_string(),
+            from: Vec::new(),
+            to: Vec::new(),
+            total_amount: Default::default(),
+            spent_by_me: Default::default(),
+            received_by_me: Default::default(),
+            my_balance_change: Default::default(),
+            block_height: self.slot,
+            timestamp: self.block_time.unwrap_or_default(),
+            fee_details: Some(SolanaFeeDetails { amount: fee }.into()),
+            coin: ticker.to_string(),
+            internal_id: Default::default(),
+            kmd_rewards: None,
+            transaction_type: TransactionType::StandardTransfer,
+            memo: None,
+        })
+    }
+
+    /// Extracts the SOL transfers that were sent or received by the `solana_coin` address.
+    /// The transaction fee is added to the first transfer sent by us if we are the fee payer.
+    pub fn extract_solana_transactions(&self, solana_coin: &SolanaCoin) -> NumConversResult<Vec<TransactionDetails>> {
+        let my_address = solana_coin.my_address.as_str();
+        let mut fee_to_pay = if self.is_fee_payer(my_address) {
+            BigDecimal::try_from(lamports_to_sol(self.meta.fee))?
+        } else {
+            BigDecimal::default()
+        };
+
+        let mut transactions = Vec::new();
+        for (index, instruction) in self.all_instructions().enumerate() {
+            if !instruction.is_solana_transfer() {
+                continue;
+            }
+            let info = match instruction.parsed_info() {
+                Some(info) => info,
+                None => continue,
+            };
+            let am_i_sender = info.source == my_address;
+            let am_i_receiver = info.destination == my_address;
+            if !am_i_sender && !am_i_receiver {
+                continue;
             }
-            idx += 1;
+
+            let amount = BigDecimal::try_from(lamports_to_sol(info.lamports.unwrap_or_default()))?;
+            let spent_by_me = if am_i_sender {
+                &amount + &std::mem::take(&mut fee_to_pay)
+            } else {
+                BigDecimal::default()
+            };
+            let received_by_me = if am_i_receiver {
+                amount.clone()
+            } else {
+                BigDecimal::default()
+            };
+
+            let mut tx = self.details_template(&solana_coin.ticker)?;
+            tx.from = vec![info.source.clone()];
+            tx.to = vec![info.destination.clone()];
+            tx.total_amount = amount;
+            tx.my_balance_change = &received_by_me - &spent_by_me;
+            tx.spent_by_me = spent_by_me;
+            tx.received_by_me = received_by_me;
+            tx.internal_id = transfer_internal_id(self.signature(), None, index);
+            transactions.push(tx);
         }
-        idx
+        Ok(transactions)
     }
 
-    pub fn extract_solana_transactions(&self, solana_coin: &SolanaCoin) -> NumConversResult<Vec<TransactionDetails>> {
+    /// Extracts the transfers of the given SPL token that were sent from or received to `my_token_account`
+    /// (the associated token account of the `solana_coin` address).
+    /// If we paid the fee of such a transaction, a [`TransactionType::FeeForTokenTx`] entry of the platform coin is added.
+    pub fn extract_spl_transactions(
+        &self,
+        solana_coin: &SolanaCoin,
+        token_ticker: &str,
+        token_info: &SplTokenInfo,
+        my_token_account: &str,
+    ) -> NumConversResult<Vec<TransactionDetails>> {
+        let my_address = solana_coin.my_address.as_str();
+        let mint = token_info.token_contract_address.to_string();
+        let token_id: BytesJson = token_info.token_contract_address.to_bytes().to_vec().into();
+
         let mut transactions = Vec::new();
-        let account_idx = self.extract_account_index(solana_coin.my_address.clone());
-        for instruction in self.transaction.message.instructions.iter() {
-            if instruction.is_solana_transfer() {
-                let lamports = instruction.parsed.info.lamports.unwrap_or_default();
-                let amount = BigDecimal::try_from(lamports_to_sol(lamports))?;
-                let is_self_transfer = instruction.parsed.info.source == instruction.parsed.info.destination;
-                let am_i_sender = instruction.parsed.info.source == solana_coin.my_address;
-                let spent_by_me = if am_i_sender && !is_self_transfer {
-                    amount.clone()
-                } else {
-                    0.into()
-                };
-                let received_by_me = if is_self_transfer { amount.clone() } else { 0.into() };
-                let my_balance_change = if am_i_sender {
-                    BigDecimal::try_from(lamports_to_sol(
-                        self.meta.pre_balances[account_idx] - self.meta.post_balances[account_idx],
-                    ))?
-                } else {
-                    BigDecimal::try_from(lamports_to_sol(
-                        self.meta.post_balances[account_idx] - self.meta.pre_balances[account_idx],
-                    ))?
-                };
-                let fee = BigDecimal::try_from(lamports_to_sol(self.meta.fee))?;
-                let tx = TransactionDetails {
-                    tx_hex: Default::default(),
-                    tx_hash: self.transaction.signatures[0].to_string(),
-                    from: vec![instruction.parsed.info.source.clone()],
-                    to: vec![instruction.parsed.info.destination.clone()],
-                    total_amount: amount,
-                    spent_by_me,
-                    received_by_me,
-                    my_balance_change,
-                    block_height: self.slot,
-                    timestamp: self.block_time,
-                    fee_details: Some(SolanaFeeDetails { amount: fee }.into()),
-                    coin: solana_coin.ticker.clone(),
-                    internal_id: Default::default(),
-                    kmd_rewards: None,
-                    transaction_type: TransactionType::StandardTransfer,
-                    memo: None,
-                };
-                transactions.push(tx);
+        for (index, instruction) in self.all_instructions().enumerate() {
+            if !instruction.is_spl_transfer() {
+                continue;
             }
+            let info = match instruction.parsed_info() {
+                Some(info) => info,
+                None => continue,
+            };
+            // `transferChecked` instructions contain the mint, plain `transfer` ones can be identified by our token account.
+            if info.mint.as_ref().map(|m| *m != mint).unwrap_or_default() {
+                continue;
+            }
+            let am_i_sender = info.source == my_token_account;
+            let am_i_receiver = info.destination == my_token_account;
+            if !am_i_sender && !am_i_receiver {
+                continue;
+            }
+
+            let base_units = match (&info.token_amount, &info.amount) {
+                (Some(token_amount), _) => token_amount.amount.parse::<u64>().unwrap_or_default(),
+                (None, Some(amount)) => amount.parse::<u64>().unwrap_or_default(),
+                (None, None) => continue,
+            };
+            let amount = big_decimal_from_sat_unsigned(base_units, token_info.decimals);
+            let spent_by_me = if am_i_sender {
+                amount.clone()
+            } else {
+                BigDecimal::default()
+            };
+            let received_by_me = if am_i_receiver {
+                amount.clone()
+            } else {
+                BigDecimal::default()
+            };
+
+            let sender = info
+                .authority
+                .clone()
+                .or_else(|| info.multisig_authority.clone())
+                .unwrap_or_else(|| info.source.clone());
+            let receiver = if am_i_receiver {
+                my_address.to_string()
+            } else {
+                info.destination.clone()
+            };
+
+            let mut tx = self.details_template(token_ticker)?;
+            tx.from = vec![sender];
+            tx.to = vec![receiver];
+            tx.total_amount = amount;
+            tx.my_balance_change = &received_by_me - &spent_by_me;
+            tx.spent_by_me = spent_by_me;
+            tx.received_by_me = received_by_me;
+            tx.internal_id = transfer_internal_id(self.signature(), Some(&mint), index);
+            tx.transaction_type = TransactionType::TokenTransfer(token_id.clone());
+            transactions.push(tx);
+        }
+
+        if !transactions.is_empty() && self.is_fee_payer(my_address) {
+            let fee = BigDecimal::try_from(lamports_to_sol(self.meta.fee))?;
+            let mut fee_tx = self.details_template(&solana_coin.ticker)?;
+            fee_tx.from = vec![my_address.to_string()];
+            fee_tx.total_amount = fee.clone();
+            fee_tx.my_balance_change = BigDecimal::default() - &fee;
+            fee_tx.spent_by_me = fee;
+            fee_tx.internal_id = transfer_internal_id(self.signature(), Some(&mint), FEE_FOR_TOKEN_TX_INDEX);
+            fee_tx.transaction_type = TransactionType::FeeForTokenTx;
+            transactions.push(fee_tx);
         }
         Ok(transactions)
     }
@@ -87,15 +233,22 @@ pub struct Meta {
     pre_balances: Vec<u64>,
     #[serde(rename = "postBalances")]
     post_balances: Vec<u64>,
-    #[serde(rename = "innerInstructions")]
-    inner_instructions: Vec<Option<serde_json::Value>>,
-    #[serde(rename = "logMessages")]
-    log_messages: Vec<String>,
-    #[serde(rename = "preTokenBalances")]
-    pre_token_balances: Vec<TokenBalance>,
-    #[serde(rename = "postTokenBalances")]
-    post_token_balances: Vec<TokenBalance>,
-    rewards: Vec<Option<serde_json::Value>>,
+    #[serde(rename = "innerInstructions", default)]
+    inner_instructions: Option<Vec<InnerInstructions>>,
+    #[serde(rename = "logMessages", default)]
+    log_messages: Option<Vec<String>>,
+    #[serde(rename = "preTokenBalances", default)]
+    pre_token_balances: Option<Vec<TokenBalance>>,
+    #[serde(rename = "postTokenBalances", default)]
+    post_token_balances: Option<Vec<TokenBalance>>,
+    #[serde(default)]
+    rewards: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InnerInstructions {
+    index: u8,
+    instructions: Vec<Instruction>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -110,7 +263,7 @@ pub struct TokenBalance {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenAmount {
     #[serde(rename = "uiAmount")]
-    ui_amount: f64,
+    ui_amount: Option<f64>,
     decimals: u64,
     amount: String,
     #[serde(rename = "uiAmountString")]
@@ -145,51 +298,58 @@ pub struct AccountKey {
     signer: bool,
 }
 
+/// Either a parsed instruction of a known program or a partially decoded one (`program` and `parsed` are missing then).
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Instruction {
-    program: Program,
+    #[serde(default)]
+    program: Option<Program>,
     #[serde(rename = "programId")]
     program_id: String,
-    parsed: Parsed,
+    /// Is not always an object, e.g. it's a string for the memo program.
+    #[serde(default)]
+    parsed: Option<serde_json::Value>,
 }
 
-#[allow(dead_code)]
 impl Instruction {
+    fn parsed_transfer(&self) -> Option<Parsed> {
+        let parsed: Parsed = serde_json::from_value(self.parsed.clone()?).ok()?;
+        match parsed.parsed_type {
+            Type::Transfer | Type::TransferChecked => Some(parsed),
+            Type::Unknown => None,
+        }
+    }
+
+    fn parsed_info(&self) -> Option<Info> { self.parsed_transfer().and_then(|parsed| parsed.info) }
+
     pub fn is_solana_transfer(&self) -> bool {
-        let is_system = match self.program {
-            Program::SplToken => return false,
-            Program::System => true,
-        };
-        let is_transfer = match self.parsed.parsed_type {
-            Type::Transfer => true,
-            Type::TransferChecked => true,
-            Type::Unknown => false,
-        };
-        is_system && is_transfer && self.parsed.info.lamports.is_some()
+        match (&self.program, self.parsed_info()) {
+            (Some(Program::System), Some(info)) => info.lamports.is_some(),
+            _ => false,
+        }
     }
 
-    // Will be used later
     pub fn is_spl_transfer(&self) -> bool {
-        let is_spl_token = match self.program {
-            Program::SplToken => true,
-            Program::System => return false,
-        };
-        let is_transfer = match self.parsed.parsed_type {
-            Type::Transfer => true,
-            Type::TransferChecked => true,
-            Type::Unknown => false,
-        };
-        is_spl_token && is_transfer
+        matches!(self.program, Some(Program::SplToken)) && self.parsed_info().is_some()
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Parsed {
-    info: Info,
+    /// Transfer infos are deserialized only, other instructions have different layouts.
+    #[serde(default, deserialize_with = "deserialize_info_or_none")]
+    info: Option<Info>,
     #[serde(rename = "type")]
     parsed_type: Type,
 }
 
+fn deserialize_info_or_none<'de, D>(deserializer: D) -> Result<Option<Info>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Info {
     destination: String,
@@ -211,6 +371,7 @@ pub enum Type {
     Transfer,
     #[serde(rename = "transferChecked")]
     TransferChecked,
+    #[serde(other)]
     Unknown,
 }
 
@@ -220,4 +381,6 @@ pub enum Program {
     SplToken,
     #[serde(rename = "system")]
     System,
+    #[serde(other)]
+    Unknown,
 }
@@ -0,0 +1,733 @@
+//! Atomic swaps of SOL and SPL tokens through the HTLC program.
+//!
+//! The program locks a payment in an account derived from the swap parameters (a program derived address),
+//! the receiver claims it by revealing the secret whose SHA256 hash the payment is locked with,
+//! and the sender gets it back once the lock time has passed.
+//! Since the payment account address depends on all the parameters of the payment, the sent payments and their spends
+//! are found by the signatures of the transactions that involved this address.
+//! The source of the program is in `atomic_swap_program`, see its `build.sh`.
+
+use super::solana_common::{lamports_to_sol, sol_to_lamports, ui_amount_to_amount};
+use super::spl::SplTokenInfo;
+use super::{SolanaCoin, SolanaCommonOps};
+use crate::{CheckIfMyPaymentSentArgs, DexFee, FoundSwapTxSpend, NumConversResult, RefundPaymentArgs, SendPaymentArgs,
+            SpendPaymentArgs, TransactionEnum, TransactionErr, TransactionFut, TransactionResult, ValidateFeeArgs,
+            ValidatePaymentError, ValidatePaymentFut, ValidatePaymentInput, WaitForHTLCTxSpendArgs};
+use bincode::deserialize;
+use common::executor::Timer;
+use common::log::error;
+use common::{async_blocking, now_sec};
+use futures::{FutureExt, TryFutureExt};
+use futures01::Future;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::system_instruction::{self, SystemInstruction};
+use solana_sdk::system_program;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{EncodedTransaction, UiTransactionEncoding};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::instruction::TokenInstruction;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const PAYMENT_SEED: &[u8] = b"swap_payment";
+/// The swap protocol exchanges 33 bytes HTLC pubkeys, so the ed25519 pubkey is sent with this prefix.
+const HTLC_PUBKEY_PREFIX: u8 = 0;
+
+/// The parameters that the payment is locked with, they are also the seeds of the payment account address.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HtlcParams {
+    pub secret_hash: [u8; 32],
+    pub lock_time: u64,
+    /// The amount in lamports or in the token's base units.
+    pub amount: u64,
+    pub sender: Pubkey,
+    pub receiver: Pubkey,
+    /// The mint of the SPL token, `None` for SOL payments.
+    pub token_mint: Option<Pubkey>,
+}
+
+impl HtlcParams {
+    pub fn payment_address(&self, program_id: &Pubkey) -> Pubkey {
+        let lock_time = self.lock_time.to_le_bytes();
+        let amount = self.amount.to_le_bytes();
+        let token_mint = self.token_mint.unwrap_or_default();
+        let seeds: &[&[u8]] = &[
+            PAYMENT_SEED,
+            self.sender.as_ref(),
+            self.receiver.as_ref(),
+            &self.secret_hash,
+            &lock_time,
+            &amount,
+            token_mint.as_ref(),
+        ];
+        Pubkey::find_program_address(seeds, program_id).0
+    }
+}
+
+/// The instructions of the HTLC program, serialized with bincode.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum HtlcInstruction {
+    /// Transfers the amount from the sender to the payment account.
+    Payment(HtlcParams),
+    /// Transfers the locked amount to the receiver if the SHA256 hash of the secret equals the secret hash.
+    ReceiverSpend { params: HtlcParams, secret: [u8; 32] },
+    /// Transfers the locked amount back to the sender if the lock time has passed.
+    SenderRefund(HtlcParams),
+}
+
+impl HtlcInstruction {
+    fn params(&self) -> &HtlcParams {
+        match self {
+            HtlcInstruction::Payment(params) | HtlcInstruction::SenderRefund(params) => params,
+            HtlcInstruction::ReceiverSpend { params, .. } => params,
+        }
+    }
+
+    /// Builds the program instruction, the accounts are:
+    /// 0. `[signer, writable]` The sender for `Payment` and `SenderRefund`, the receiver for `ReceiverSpend`.
+    /// 1. `[writable]` The payment account.
+    /// 2. `[]` The system program.
+    ///
+    /// And for SPL token payments:
+    /// 3. `[]` The token mint.
+    /// 4. `[writable]` The associated token account of the payment account.
+    /// 5. `[writable]` The associated token account of the signer.
+    /// 6. `[]` The SPL token program.
+    /// 7. `[]` The associated token account program.
+    /// 8. `[]` The rent sysvar.
+    pub fn into_instruction(self, program_id: &Pubkey) -> Instruction {
+        let params = self.params();
+        let signer = match self {
+            HtlcInstruction::ReceiverSpend { .. } => params.receiver,
+            HtlcInstruction::Payment(_) | HtlcInstruction::SenderRefund(_) => params.sender,
+        };
+        let payment_address = params.payment_address(program_id);
+
+        let mut accounts = vec![
+            AccountMeta::new(signer, true),
+            AccountMeta::new(payment_address, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        if let Some(mint) = params.token_mint {
+            accounts.extend([
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(get_associated_token_address(&payment_address, &mint), false),
+                AccountMeta::new(get_associated_token_address(&signer, &mint), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ]);
+        }
+        Instruction::new_with_bincode(*program_id, &self, accounts)
+    }
+}
+
+/// There is no `OP_RETURN` analogue to burn a part of the dex fee with, so only the standard dex fee is supported.
+fn standard_dex_fee_amount(dex_fee: &DexFee) -> Result<BigDecimal, String> {
+    match dex_fee {
+        DexFee::Standard(amount) => Ok(amount.to_decimal()),
+        DexFee::WithBurn { .. } => ERR!("Burning a part of the dex fee is not supported by Solana"),
+    }
+}
+
+pub fn htlc_pubkey(pubkey: &Pubkey) -> Vec<u8> {
+    let mut htlc_pubkey = vec![HTLC_PUBKEY_PREFIX];
+    htlc_pubkey.extend_from_slice(pubkey.as_ref());
+    htlc_pubkey
+}
+
+pub fn pubkey_from_htlc_pubkey(htlc_pubkey: &[u8]) -> Result<Pubkey, String> {
+    match htlc_pubkey {
+        [HTLC_PUBKEY_PREFIX, pubkey @ ..] if pubkey.len() == 32 => Ok(Pubkey::new(pubkey)),
+        pubkey if pubkey.len() == 32 => Ok(Pubkey::new(pubkey)),
+        _ => ERR!("Invalid Solana HTLC pubkey {}", hex::encode(htlc_pubkey)),
+    }
+}
+
+/// Checks that the serialized transaction is validly signed by the ed25519 key of the `expected_pub` HTLC pubkey.
+pub(super) fn check_tx_signed_by_pub(tx: &[u8], expected_pub: &[u8]) -> MmResult<bool, ValidatePaymentError> {
+    let tx: Transaction = deserialize(tx).map_to_mm(|e| ValidatePaymentError::TxDeserializationError(e.to_string()))?;
+    let expected_pub = pubkey_from_htlc_pubkey(expected_pub).map_to_mm(ValidatePaymentError::InvalidParameter)?;
+    let is_signer = tx
+        .message
+        .account_keys
+        .iter()
+        .take(tx.message.header.num_required_signatures as usize)
+        .any(|signer| *signer == expected_pub);
+    Ok(is_signer && tx.verify().is_ok())
+}
+
+pub(super) fn watchers_not_supported(ticker: &str) -> String { format!("{} doesn't support watchers", ticker) }
+
+pub(super) fn watchers_not_supported_tx_fut(ticker: &str) -> TransactionFut {
+    Box::new(futures01::future::err(TransactionErr::Plain(watchers_not_supported(
+        ticker,
+    ))))
+}
+
+pub(super) fn watchers_not_supported_validation_fut(ticker: &str) -> ValidatePaymentFut<()> {
+    Box::new(futures01::future::err(MmError::new(
+        ValidatePaymentError::InternalError(watchers_not_supported(ticker)),
+    )))
+}
+
+/// Returns the first instruction of the HTLC program in the transaction.
+pub fn find_htlc_instruction(tx: &Transaction, program_id: &Pubkey) -> Option<HtlcInstruction> {
+    tx.message.instructions.iter().find_map(|instruction| {
+        if tx.message.account_keys.get(instruction.program_id_index as usize) != Some(program_id) {
+            return None;
+        }
+        deserialize(&instruction.data).ok()
+    })
+}
+
+fn payment_params(payment_tx: &Transaction, program_id: &Pubkey) -> Result<HtlcParams, String> {
+    match find_htlc_instruction(payment_tx, program_id) {
+        Some(HtlcInstruction::Payment(params)) => Ok(params),
+        _ => ERR!("Transaction {} is not an HTLC payment", tx_signature(payment_tx)),
+    }
+}
+
+fn tx_signature(tx: &Transaction) -> Signature { tx.signatures.first().copied().unwrap_or_default() }
+
+impl SolanaCoin {
+    fn swap_program_id(&self) -> Result<Pubkey, String> {
+        self.swap_program_id.ok_or_else(|| {
+            format!(
+                "Swaps are not supported for {}: swap_program_id is not set",
+                self.ticker
+            )
+        })
+    }
+
+    fn dex_fee_address(&self) -> Result<Pubkey, String> {
+        self.dex_fee_address.ok_or_else(|| {
+            format!(
+                "Swaps are not supported for {}: dex_fee_address is not set in the coin config",
+                self.ticker
+            )
+        })
+    }
+
+    fn swap_amount_to_base_units(&self, amount: &BigDecimal, token: &Option<SplTokenInfo>) -> NumConversResult<u64> {
+        match token {
+            Some(info) => ui_amount_to_amount(amount.clone(), info.decimals),
+            None => sol_to_lamports(amount),
+        }
+    }
+
+    /// The fee of a swap transaction in SOL.
+    pub(super) async fn swap_tx_fee(&self) -> Result<BigDecimal, String> {
+        let (_, fee) = self.estimate_withdraw_fees().await.map_err(|e| format!("{:?}", e))?;
+        Ok(lamports_to_sol(fee))
+    }
+
+    async fn sign_and_send_instructions(&self, instructions: Vec<Instruction>) -> Result<Transaction, String> {
+        let coin = self.clone();
+        async_blocking(move || {
+            // this is blocking IO
+            let hash = coin.rpc().get_latest_blockhash().map_err(|e| format!("{:?}", e))?;
+            let message = Message::new(&instructions, Some(&coin.key_pair.pubkey()));
+            let tx = Transaction::new(&[&coin.key_pair], message, hash);
+            coin.rpc()
+                .send_and_confirm_transaction(&tx)
+                .map_err(|e| format!("{:?}", e))?;
+            Ok(tx)
+        })
+        .await
+    }
+
+    async fn get_tx_by_signature(&self, signature: Signature) -> Result<Transaction, String> {
+        let coin = self.clone();
+        let confirmed_tx =
+            async_blocking(move || coin.rpc().get_transaction(&signature, UiTransactionEncoding::Base64))
+                .await
+                .map_err(|e| format!("{:?}", e))?;
+        match confirmed_tx.transaction.transaction {
+            EncodedTransaction::Binary(blob, _) => {
+                let bytes = try_s!(base64::decode(&blob));
+                Ok(try_s!(deserialize(&bytes)))
+            },
+            _ => ERR!("Unexpected encoding of the transaction {}", signature),
+        }
+    }
+
+    /// Returns the succeeded transactions that involved the `address` ordered from the oldest to the newest.
+    async fn get_txs_for_address(&self, address: Pubkey) -> Result<Vec<Transaction>, String> {
+        let coin = self.clone();
+        let statuses = async_blocking(move || coin.rpc().get_signatures_for_address(&address))
+            .await
+            .map_err(|e| format!("{:?}", e))?;
+
+        let mut txs = Vec::with_capacity(statuses.len());
+        // The signatures are returned from the newest to the oldest
+        for status in statuses.into_iter().rev() {
+            if status.err.is_some() {
+                continue;
+            }
+            let signature = try_s!(Signature::from_str(&status.signature));
+            txs.push(self.get_tx_by_signature(signature).await?);
+        }
+        Ok(txs)
+    }
+
+    /// Returns the amount that is still locked in the payment account.
+    async fn locked_amount(&self, params: &HtlcParams, program_id: &Pubkey) -> Result<u64, String> {
+        let payment_address = params.payment_address(program_id);
+        let coin = self.clone();
+        match params.token_mint {
+            Some(mint) => {
+                let token_account = get_associated_token_address(&payment_address, &mint);
+                let balance = async_blocking(move || coin.rpc().get_token_account_balance(&token_account))
+                    .await
+                    .map_err(|e| format!("{:?}", e))?;
+                Ok(try_s!(balance.amount.parse()))
+            },
+            None => async_blocking(move || coin.rpc().get_balance(&payment_address))
+                .await
+                .map_err(|e| format!("{:?}", e)),
+        }
+    }
+
+    pub(super) fn send_dex_fee(&self, dex_fee: DexFee, token: Option<SplTokenInfo>) -> TransactionFut {
+        let fee_address = try_tx_fus!(self.dex_fee_address());
+        let fee_amount = try_tx_fus!(standard_dex_fee_amount(&dex_fee));
+        let amount = try_tx_fus!(self.swap_amount_to_base_units(&fee_amount, &token));
+        let my_pubkey = self.key_pair.pubkey();
+
+        let coin = self.clone();
+        let fut = async move {
+            let instructions = match token {
+                Some(info) => {
+                    let mint = info.token_contract_address;
+                    let fee_token_account = get_associated_token_address(&fee_address, &mint);
+                    let mut instructions = Vec::with_capacity(2);
+                    let rpc_coin = coin.clone();
+                    if async_blocking(move || rpc_coin.rpc().get_account(&fee_token_account))
+                        .await
+                        .is_err()
+                    {
+                        instructions.push(create_associated_token_account(&my_pubkey, &fee_address, &mint));
+                    }
+                    instructions.push(try_tx_s!(spl_token::instruction::transfer_checked(
+                        &spl_token::id(),
+                        &get_associated_token_address(&my_pubkey, &mint),
+                        &mint,
+                        &fee_token_account,
+                        &my_pubkey,
+                        &[&my_pubkey],
+                        amount,
+                        info.decimals,
+                    )));
+                    instructions
+                },
+                None => vec![system_instruction::transfer(&my_pubkey, &fee_address, amount)],
+            };
+            let tx = try_tx_s!(coin.sign_and_send_instructions(instructions).await);
+            Ok(TransactionEnum::SolanaTransaction(tx))
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn validate_dex_fee(
+        &self,
+        validate_fee_args: ValidateFeeArgs<'_>,
+        token: Option<SplTokenInfo>,
+    ) -> ValidatePaymentFut<()> {
+        let fee_tx = match validate_fee_args.fee_tx {
+            TransactionEnum::SolanaTransaction(tx) => tx.clone(),
+            fee_tx => {
+                return Box::new(futures01::future::err(
+                    ValidatePaymentError::InternalError(format!("Invalid fee tx type fee tx: {:?}", fee_tx)).into(),
+                ))
+            },
+        };
+        let expected_sender = pubkey_from_htlc_pubkey(validate_fee_args.expected_sender);
+        let fee_amount = standard_dex_fee_amount(validate_fee_args.dex_fee);
+
+        let coin = self.clone();
+        let fut = async move {
+            let expected_sender = expected_sender.map_to_mm(ValidatePaymentError::InvalidParameter)?;
+            let fee_amount = fee_amount.map_to_mm(ValidatePaymentError::InvalidParameter)?;
+            let expected_amount = coin.swap_amount_to_base_units(&fee_amount, &token)?;
+            let fee_address = coin.dex_fee_address().map_to_mm(ValidatePaymentError::InternalError)?;
+            let fee_destination = match &token {
+                Some(info) => get_associated_token_address(&fee_address, &info.token_contract_address),
+                None => fee_address,
+            };
+
+            let is_fee_transfer = fee_tx.message.instructions.iter().any(|instruction| {
+                let program = fee_tx.message.account_keys.get(instruction.program_id_index as usize);
+                let account = |index: usize| {
+                    instruction
+                        .accounts
+                        .get(index)
+                        .and_then(|i| fee_tx.message.account_keys.get(*i as usize))
+                };
+                match &token {
+                    Some(info) => {
+                        program == Some(&spl_token::id())
+                            && matches!(TokenInstruction::unpack(&instruction.data),
+                                Ok(TokenInstruction::TransferChecked { amount, .. }) if amount >= expected_amount)
+                            && account(1) == Some(&info.token_contract_address)
+                            && account(2) == Some(&fee_destination)
+                            && account(3) == Some(&expected_sender)
+                    },
+                    None => {
+                        program == Some(&system_program::id())
+                            && matches!(deserialize::<SystemInstruction>(&instruction.data),
+                                Ok(SystemInstruction::Transfer { lamports }) if lamports >= expected_amount)
+                            && account(0) == Some(&expected_sender)
+                            && account(1) == Some(&fee_destination)
+                    },
+                }
+            });
+            if !is_fee_transfer {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Transaction {} doesn't transfer the dex fee {} from {} to {}",
+                    tx_signature(&fee_tx),
+                    expected_amount,
+                    expected_sender,
+                    fee_destination
+                )));
+            }
+            coin.validate_tx_succeeded(tx_signature(&fee_tx)).await
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    async fn validate_tx_succeeded(&self, signature: Signature) -> MmResult<(), ValidatePaymentError> {
+        let coin = self.clone();
+        let status = async_blocking(move || coin.rpc().get_signature_status(&signature))
+            .await
+            .map_to_mm(|e| ValidatePaymentError::Transport(format!("{:?}", e)))?;
+        match status {
+            Some(Ok(())) => Ok(()),
+            Some(Err(e)) => MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                "Transaction {} failed: {}",
+                signature, e
+            ))),
+            None => MmError::err(ValidatePaymentError::TxDoesNotExist(signature.to_string())),
+        }
+    }
+
+    pub(super) fn send_htlc_payment(&self, args: SendPaymentArgs<'_>, token: Option<SplTokenInfo>) -> TransactionFut {
+        let program_id = try_tx_fus!(self.swap_program_id());
+        let params = HtlcParams {
+            secret_hash: try_tx_fus!(<[u8; 32]>::try_from(args.secret_hash)),
+            lock_time: args.time_lock,
+            amount: try_tx_fus!(self.swap_amount_to_base_units(&args.amount, &token)),
+            sender: self.key_pair.pubkey(),
+            receiver: try_tx_fus!(pubkey_from_htlc_pubkey(args.other_pubkey)),
+            token_mint: token.map(|info| info.token_contract_address),
+        };
+        let instruction = HtlcInstruction::Payment(params).into_instruction(&program_id);
+
+        let coin = self.clone();
+        let fut = async move {
+            let tx = try_tx_s!(coin.sign_and_send_instructions(vec![instruction]).await);
+            Ok(TransactionEnum::SolanaTransaction(tx))
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn spend_htlc_payment(&self, args: SpendPaymentArgs<'_>) -> TransactionFut {
+        let program_id = try_tx_fus!(self.swap_program_id());
+        let payment_tx: Transaction = try_tx_fus!(deserialize(args.other_payment_tx));
+        let params = try_tx_fus!(payment_params(&payment_tx, &program_id));
+        if params.receiver != self.key_pair.pubkey() {
+            return Box::new(futures01::future::err(TransactionErr::Plain(ERRL!(
+                "The payment {} can't be spent by {}",
+                tx_signature(&payment_tx),
+                self.my_address
+            ))));
+        }
+        let secret = try_tx_fus!(<[u8; 32]>::try_from(args.secret));
+        let instruction = HtlcInstruction::ReceiverSpend { params, secret }.into_instruction(&program_id);
+
+        let coin = self.clone();
+        let fut = async move {
+            let tx = try_tx_s!(coin.sign_and_send_instructions(vec![instruction]).await);
+            Ok(TransactionEnum::SolanaTransaction(tx))
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) async fn refund_htlc_payment(&self, args: RefundPaymentArgs<'_>) -> TransactionResult {
+        let program_id = try_tx_s!(self.swap_program_id());
+        let payment_tx: Transaction = try_tx_s!(deserialize(args.payment_tx));
+        let params = try_tx_s!(payment_params(&payment_tx, &program_id));
+        if params.sender != self.key_pair.pubkey() {
+            return Err(TransactionErr::Plain(ERRL!(
+                "The payment {} can't be refunded by {}",
+                tx_signature(&payment_tx),
+                self.my_address
+            )));
+        }
+        let instruction = HtlcInstruction::SenderRefund(params).into_instruction(&program_id);
+        let tx = try_tx_s!(self.sign_and_send_instructions(vec![instruction]).await);
+        Ok(TransactionEnum::SolanaTransaction(tx))
+    }
+
+    pub(super) fn validate_htlc_payment(
+        &self,
+        input: ValidatePaymentInput,
+        token: Option<SplTokenInfo>,
+    ) -> ValidatePaymentFut<()> {
+        let coin = self.clone();
+        let fut = async move {
+            let program_id = coin.swap_program_id().map_to_mm(ValidatePaymentError::InternalError)?;
+            let payment_tx: Transaction = deserialize(&input.payment_tx)
+                .map_to_mm(|e| ValidatePaymentError::TxDeserializationError(e.to_string()))?;
+            let params = payment_params(&payment_tx, &program_id).map_to_mm(ValidatePaymentError::WrongPaymentTx)?;
+
+            let expected_params = HtlcParams {
+                secret_hash: <[u8; 32]>::try_from(input.secret_hash.as_slice())
+                    .map_to_mm(|e| ValidatePaymentError::InvalidParameter(e.to_string()))?,
+                lock_time: input.time_lock,
+                amount: coin.swap_amount_to_base_units(&input.amount, &token)?,
+                sender: pubkey_from_htlc_pubkey(&input.other_pub).map_to_mm(ValidatePaymentError::InvalidParameter)?,
+                receiver: coin.key_pair.pubkey(),
+                token_mint: token.map(|info| info.token_contract_address),
+            };
+            if params != expected_params {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Payment params {:?} don't match the expected {:?}",
+                    params, expected_params
+                )));
+            }
+
+            coin.validate_tx_succeeded(tx_signature(&payment_tx)).await?;
+            let locked_amount = coin
+                .locked_amount(&params, &program_id)
+                .await
+                .map_to_mm(ValidatePaymentError::Transport)?;
+            if locked_amount < params.amount {
+                return MmError::err(ValidatePaymentError::UnexpectedPaymentState(format!(
+                    "Payment account holds {} while {} is expected, the payment was spent or refunded",
+                    locked_amount, params.amount
+                )));
+            }
+            Ok(())
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn check_if_my_htlc_payment_sent(
+        &self,
+        args: CheckIfMyPaymentSentArgs<'_>,
+        token: Option<SplTokenInfo>,
+    ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
+        let program_id = try_fus!(self.swap_program_id());
+        let params = HtlcParams {
+            secret_hash: try_fus!(<[u8; 32]>::try_from(args.secret_hash)),
+            lock_time: args.time_lock,
+            amount: try_fus!(self.swap_amount_to_base_units(args.amount, &token)),
+            sender: self.key_pair.pubkey(),
+            receiver: try_fus!(pubkey_from_htlc_pubkey(args.other_pub)),
+            token_mint: token.map(|info| info.token_contract_address),
+        };
+
+        let coin = self.clone();
+        let fut = async move {
+            for tx in coin.get_txs_for_address(params.payment_address(&program_id)).await? {
+                if let Some(HtlcInstruction::Payment(sent_params)) = find_htlc_instruction(&tx, &program_id) {
+                    if sent_params == params {
+                        return Ok(Some(TransactionEnum::SolanaTransaction(tx)));
+                    }
+                }
+            }
+            Ok(None)
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) async fn search_for_htlc_spend(&self, payment_tx: &[u8]) -> Result<Option<FoundSwapTxSpend>, String> {
+        let program_id = self.swap_program_id()?;
+        let payment_tx: Transaction = try_s!(deserialize(payment_tx));
+        let params = payment_params(&payment_tx, &program_id)?;
+
+        for tx in self.get_txs_for_address(params.payment_address(&program_id)).await? {
+            match find_htlc_instruction(&tx, &program_id) {
+                Some(HtlcInstruction::ReceiverSpend {
+                    params: spent_params, ..
+                }) if spent_params == params => {
+                    return Ok(Some(FoundSwapTxSpend::Spent(TransactionEnum::SolanaTransaction(tx))))
+                },
+                Some(HtlcInstruction::SenderRefund(refunded_params)) if refunded_params == params => {
+                    return Ok(Some(FoundSwapTxSpend::Refunded(TransactionEnum::SolanaTransaction(tx))))
+                },
+                _ => (),
+            }
+        }
+        Ok(None)
+    }
+
+    pub(super) fn wait_for_htlc_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        let payment_tx = args.tx_bytes.to_vec();
+        let wait_until = args.wait_until;
+        let check_every = args.check_every;
+
+        let coin = self.clone();
+        let fut = async move {
+            loop {
+                match coin.search_for_htlc_spend(&payment_tx).await {
+                    Ok(Some(FoundSwapTxSpend::Spent(tx))) => return Ok(tx),
+                    Ok(Some(FoundSwapTxSpend::Refunded(tx))) => {
+                        return Err(TransactionErr::Plain(ERRL!(
+                            "The payment was refunded by {:?}",
+                            tx.tx_hash()
+                        )))
+                    },
+                    Ok(None) => (),
+                    Err(e) => error!("Error on searching for the payment spend: {}", e),
+                }
+                if now_sec() > wait_until {
+                    return Err(TransactionErr::Plain(ERRL!(
+                        "Waited too long until {} for the payment to be spent",
+                        wait_until
+                    )));
+                }
+                Timer::sleep(check_every).await;
+            }
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn extract_htlc_secret(&self, secret_hash: &[u8], spend_tx: &[u8]) -> Result<Vec<u8>, String> {
+        let program_id = self.swap_program_id()?;
+        let spend_tx: Transaction = try_s!(deserialize(spend_tx));
+        match find_htlc_instruction(&spend_tx, &program_id) {
+            Some(HtlcInstruction::ReceiverSpend { params, secret }) if params.secret_hash.as_slice() == secret_hash => {
+                Ok(secret.to_vec())
+            },
+            _ => ERR!(
+                "Transaction {} doesn't spend a payment locked with the secret hash {}",
+                tx_signature(&spend_tx),
+                hex::encode(secret_hash)
+            ),
+        }
+    }
+
+    pub(super) fn wait_for_tx_confirmations(
+        &self,
+        payment_tx: Vec<u8>,
+        wait_until: u64,
+        check_every: u64,
+    ) -> Box<dyn Future<Item = (), Error = String> + Send> {
+        let coin = self.clone();
+        let fut = async move {
+            let tx: Transaction = try_s!(deserialize(&payment_tx));
+            let signature = tx_signature(&tx);
+            loop {
+                let rpc_coin = coin.clone();
+                // The confirmation commitment of the RPC client is used for the status
+                match async_blocking(move || rpc_coin.rpc().get_signature_status(&signature)).await {
+                    Ok(Some(Ok(()))) => return Ok(()),
+                    Ok(Some(Err(e))) => return ERR!("Transaction {} failed: {}", signature, e),
+                    Ok(None) => (),
+                    Err(e) => error!("Error on getting the status of {}: {:?}", signature, e),
+                }
+                if now_sec() > wait_until {
+                    return ERR!(
+                        "Waited too long until {} for transaction {} to be confirmed",
+                        wait_until,
+                        signature
+                    );
+                }
+                Timer::sleep(check_every as f64).await;
+            }
+        };
+        Box::new(fut.boxed().compat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::Keypair;
+
+    fn htlc_params(token_mint: Option<Pubkey>) -> HtlcParams {
+        HtlcParams {
+            secret_hash: [1; 32],
+            lock_time: 1_690_000_000,
+            amount: 1_000_000,
+            sender: Pubkey::new(&[2; 32]),
+            receiver: Pubkey::new(&[3; 32]),
+            token_mint,
+        }
+    }
+
+    #[test]
+    fn test_htlc_instruction_encode_decode() {
+        let program_id = Pubkey::from_str("3v2BJxMetprpVHjXuBehaZGo41k84Q8uxFsuatTnntgZ").unwrap();
+        let params = htlc_params(None);
+        let spend = HtlcInstruction::ReceiverSpend {
+            params: params.clone(),
+            secret: [4; 32],
+        };
+        let instruction = spend.clone().into_instruction(&program_id);
+        assert_eq!(instruction.accounts.len(), 3);
+        assert_eq!(instruction.accounts[0].pubkey, params.receiver);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[1].pubkey, params.payment_address(&program_id));
+
+        let tx = Transaction::new_unsigned(Message::new(&[instruction], Some(&params.receiver)));
+        assert_eq!(find_htlc_instruction(&tx, &program_id), Some(spend));
+        assert_eq!(find_htlc_instruction(&tx, &Pubkey::new(&[5; 32])), None);
+
+        let token_params = htlc_params(Some(Pubkey::new(&[6; 32])));
+        let instruction = HtlcInstruction::Payment(token_params.clone()).into_instruction(&program_id);
+        assert_eq!(instruction.accounts.len(), 9);
+        assert_eq!(instruction.accounts[0].pubkey, token_params.sender);
+        // The payment address depends on every payment param
+        assert_ne!(
+            token_params.payment_address(&program_id),
+            params.payment_address(&program_id)
+        );
+    }
+
+    #[test]
+    fn test_standard_dex_fee_amount() {
+        let amount = standard_dex_fee_amount(&DexFee::Standard("0.01".into())).unwrap();
+        assert_eq!(amount, BigDecimal::from_str("0.01").unwrap());
+        standard_dex_fee_amount(&DexFee::with_burn("0.0075".into(), "0.0025".into())).unwrap_err();
+    }
+
+    #[test]
+    fn test_check_tx_signed_by_pub() {
+        let signer = Keypair::new();
+        let other = Keypair::new();
+        let instruction = system_instruction::transfer(&signer.pubkey(), &other.pubkey(), 1);
+        let message = Message::new(&[instruction], Some(&signer.pubkey()));
+        let tx = Transaction::new(&[&signer], message, Hash::default());
+        let tx_bytes = bincode::serialize(&tx).unwrap();
+
+        assert!(check_tx_signed_by_pub(&tx_bytes, &htlc_pubkey(&signer.pubkey())).unwrap());
+        assert!(!check_tx_signed_by_pub(&tx_bytes, &htlc_pubkey(&other.pubkey())).unwrap());
+
+        let mut forged = tx;
+        forged.signatures[0] = Signature::default();
+        let forged_bytes = bincode::serialize(&forged).unwrap();
+        assert!(!check_tx_signed_by_pub(&forged_bytes, &htlc_pubkey(&signer.pubkey())).unwrap());
+
+        check_tx_signed_by_pub(&[1, 2, 3], &htlc_pubkey(&signer.pubkey())).unwrap_err();
+    }
+
+    #[test]
+    fn test_htlc_pubkey() {
+        let pubkey = Pubkey::new(&[7; 32]);
+        let htlc_pubkey = htlc_pubkey(&pubkey);
+        assert_eq!(htlc_pubkey.len(), 33);
+        assert_eq!(pubkey_from_htlc_pubkey(&htlc_pubkey).unwrap(), pubkey);
+        assert_eq!(pubkey_from_htlc_pubkey(pubkey.as_ref()).unwrap(), pubkey);
+        pubkey_from_htlc_pubkey(&[1; 33]).unwrap_err();
+    }
+}
@@ -326,3 +326,103 @@ fn solana_test_tx_history() {
     }
     println!("{}", serde_json::to_string(&history).unwrap());
 }
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn solana_extract_sol_and_spl_transfers() {
+    let passphrase = "federal stay trigger hour exist success game vapor become comfort action phone bright ill target wild nasty crumble dune close rare fabric hen iron".to_string();
+    let (_, sol_coin) = solana_coin_for_test(passphrase, SolanaNet::Devnet);
+    let my_address = sol_coin.my_address.clone();
+    let other_address = Pubkey::new_unique().to_string();
+    let mint = Pubkey::new_unique();
+    let my_token_account =
+        spl_associated_token_account::get_associated_token_address(&sol_coin.key_pair.pubkey(), &mint).to_string();
+    let token_info = SplTokenInfo {
+        token_contract_address: mint,
+        decimals: 6,
+    };
+
+    let tx_json = json!({
+        "slot": 100,
+        "blockTime": 1690000000,
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [2000000000, 0],
+            "postBalances": [1998995000, 1000000],
+            "innerInstructions": [{
+                "index": 1,
+                "instructions": [{
+                    "program": "spl-token",
+                    "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                    "parsed": {
+                        "type": "transferChecked",
+                        "info": {
+                            "source": Pubkey::new_unique().to_string(),
+                            "destination": my_token_account,
+                            "mint": mint.to_string(),
+                            "authority": other_address,
+                            "tokenAmount": { "uiAmount": 1.5, "decimals": 6, "amount": "1500000", "uiAmountString": "1.5" }
+                        }
+                    }
+                }]
+            }],
+            "logMessages": null,
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+            "rewards": []
+        },
+        "transaction": {
+            "signatures": ["5j7s1QzqC9JJNWTQpUjvfYgYaL1YEP3EmG7SFs5Kx9JmKNBVHMv8bVrJ8ZaKfnAEAyfXG9KcTq4pzFLGn3qhXV9R"],
+            "message": {
+                "accountKeys": [
+                    { "pubkey": my_address, "writable": true, "signer": true },
+                    { "pubkey": other_address, "writable": true, "signer": false }
+                ],
+                "recentBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                "instructions": [
+                    {
+                        "program": "system",
+                        "programId": "11111111111111111111111111111111",
+                        "parsed": {
+                            "type": "transfer",
+                            "info": { "source": my_address, "destination": other_address, "lamports": 1000000 }
+                        }
+                    },
+                    {
+                        "programId": Pubkey::new_unique().to_string(),
+                        "accounts": [my_address],
+                        "data": "3Bxs4h24hBtQy9rw"
+                    },
+                    {
+                        "program": "spl-memo",
+                        "programId": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+                        "parsed": "memo"
+                    }
+                ]
+            }
+        }
+    });
+    let tx: SolanaConfirmedTransaction = serde_json::from_value(tx_json).unwrap();
+
+    let sol_transfers = tx.extract_solana_transactions(&sol_coin).unwrap();
+    assert_eq!(sol_transfers.len(), 1);
+    let expected_spent = BigDecimal::from_str("0.001005").unwrap();
+    assert_eq!(sol_transfers[0].spent_by_me, expected_spent);
+    assert_eq!(sol_transfers[0].my_balance_change, expected_spent.neg());
+    assert_eq!(sol_transfers[0].block_height, 100);
+
+    let spl_transfers = tx
+        .extract_spl_transactions(&sol_coin, "USDC-SOL", &token_info, &my_token_account)
+        .unwrap();
+    assert_eq!(spl_transfers.len(), 2);
+    assert_eq!(spl_transfers[0].received_by_me, BigDecimal::from_str("1.5").unwrap());
+    assert_eq!(spl_transfers[0].to, vec![my_address]);
+    assert_eq!(
+        spl_transfers[0].transaction_type,
+        TransactionType::TokenTransfer(mint.to_bytes().to_vec().into())
+    );
+    assert_eq!(spl_transfers[1].transaction_type, TransactionType::FeeForTokenTx);
+    assert_eq!(spl_transfers[1].coin, sol_coin.ticker);
+}
@@ -0,0 +1,557 @@
+use super::solana_decode_tx_helpers::SolanaConfirmedTransaction;
+use super::spl::{SplToken, SplTokenInfo};
+use super::{SolanaCoin, SolanaCommonOps};
+
+use crate::my_tx_history_v2::{CoinWithTxHistoryV2, MyTxHistoryErrorV2, MyTxHistoryTarget, TxHistoryStorage};
+use crate::tx_history_storage::{GetTxHistoryFilters, WalletId};
+use crate::{HistorySyncState, MarketCoinOps, TransactionDetails, TransactionType};
+use async_trait::async_trait;
+use common::executor::Timer;
+use common::{async_blocking, log, PagingOptionsEnum};
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::MmResult;
+use mm2_number::BigDecimal;
+use mm2_state_machine::prelude::*;
+use mm2_state_machine::state_machine::StateMachineTrait;
+use rpc::v1::types::Bytes as BytesJson;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_transaction_status::UiTransactionEncoding;
+use spl_associated_token_account::get_associated_token_address;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+/// The maximum number of signatures `getSignaturesForAddress` returns at once.
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+/// The number of stored transactions requested at once when looking for the newest scanned signature.
+const STORED_HISTORY_PAGE_SIZE: usize = 100;
+
+macro_rules! try_or_continue {
+    ($exp:expr, $fmt:literal) => {
+        match $exp {
+            Ok(t) => t,
+            Err(e) => {
+                log::warn!("{}: {:?}", $fmt, e);
+                continue;
+            },
+        }
+    };
+}
+
+#[async_trait]
+impl CoinWithTxHistoryV2 for SolanaCoin {
+    fn history_wallet_id(&self) -> WalletId { WalletId::new(self.ticker().into()) }
+
+    async fn get_tx_history_filters(
+        &self,
+        _target: MyTxHistoryTarget,
+    ) -> MmResult<GetTxHistoryFilters, MyTxHistoryErrorV2> {
+        Ok(GetTxHistoryFilters::for_address(self.my_address.clone()))
+    }
+}
+
+#[async_trait]
+impl CoinWithTxHistoryV2 for SplToken {
+    fn history_wallet_id(&self) -> WalletId { WalletId::new(self.platform_coin.ticker().into()) }
+
+    async fn get_tx_history_filters(
+        &self,
+        _target: MyTxHistoryTarget,
+    ) -> MmResult<GetTxHistoryFilters, MyTxHistoryErrorV2> {
+        let token_id = BytesJson::from(self.conf.token_contract_address.to_bytes().to_vec());
+        Ok(GetTxHistoryFilters::for_address(self.platform_coin.my_address.clone())
+            .with_token_id(format!("{:02x}", token_id)))
+    }
+}
+
+/// The account whose signatures are fetched.
+/// SPL token transfers don't mention the owner address, so every associated token account is scanned separately.
+enum ScannedAccount {
+    Platform,
+    Token {
+        ticker: String,
+        info: SplTokenInfo,
+        token_account: Pubkey,
+    },
+}
+
+impl ScannedAccount {
+    fn all_for_coin(coin: &SolanaCoin) -> Vec<ScannedAccount> {
+        let my_pubkey = coin.key_pair.pubkey();
+        let tokens = coin
+            .get_spl_tokens_infos()
+            .into_iter()
+            .map(|(ticker, info)| ScannedAccount::Token {
+                token_account: get_associated_token_address(&my_pubkey, &info.token_contract_address),
+                ticker,
+                info,
+            });
+        std::iter::once(ScannedAccount::Platform).chain(tokens).collect()
+    }
+
+    /// The history filters of the transfers that are extracted for this account.
+    fn history_filters(&self, coin: &SolanaCoin) -> GetTxHistoryFilters {
+        let filters = GetTxHistoryFilters::for_address(coin.my_address.clone());
+        match self {
+            ScannedAccount::Platform => filters,
+            ScannedAccount::Token { info, .. } => {
+                let token_id = BytesJson::from(info.token_contract_address.to_bytes().to_vec());
+                filters.with_token_id(format!("{:02x}", token_id))
+            },
+        }
+    }
+
+    fn address(&self, coin: &SolanaCoin) -> Pubkey {
+        match self {
+            ScannedAccount::Platform => coin.key_pair.pubkey(),
+            ScannedAccount::Token { token_account, .. } => *token_account,
+        }
+    }
+
+    fn extract_transactions(
+        &self,
+        coin: &SolanaCoin,
+        tx: &SolanaConfirmedTransaction,
+    ) -> Result<Vec<TransactionDetails>, String> {
+        let res = match self {
+            ScannedAccount::Platform => tx.extract_solana_transactions(coin),
+            ScannedAccount::Token {
+                ticker,
+                info,
+                token_account,
+            } => tx.extract_spl_transactions(coin, ticker, info, &token_account.to_string()),
+        };
+        res.map_err(|e| format!("{:?}", e))
+    }
+}
+
+struct SolanaTxHistoryStateMachine<Storage: TxHistoryStorage> {
+    coin: SolanaCoin,
+    storage: Storage,
+    /// The newest signature fetched for every scanned account.
+    /// It's used as the lower bound of the next fetching so the known transactions aren't requested again.
+    /// After a restart, it's restored from the newest stored transfer of the account.
+    last_signatures: HashMap<Pubkey, Signature>,
+}
+
+impl<Storage: TxHistoryStorage> StateMachineTrait for SolanaTxHistoryStateMachine<Storage> {
+    type Result = ();
+    type Error = Infallible;
+}
+
+impl<Storage: TxHistoryStorage> StandardStateMachine for SolanaTxHistoryStateMachine<Storage> {}
+
+struct SolanaInit<Storage> {
+    phantom: std::marker::PhantomData<Storage>,
+}
+
+impl<Storage> SolanaInit<Storage> {
+    fn new() -> Self {
+        SolanaInit {
+            phantom: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum StopReason {
+    StorageError(String),
+    RpcClient(String),
+}
+
+struct Stopped<Storage> {
+    phantom: std::marker::PhantomData<Storage>,
+    stop_reason: StopReason,
+}
+
+impl<Storage> Stopped<Storage> {
+    fn storage_error<E>(e: E) -> Self
+    where
+        E: std::fmt::Debug,
+    {
+        Stopped {
+            phantom: Default::default(),
+            stop_reason: StopReason::StorageError(format!("{:?}", e)),
+        }
+    }
+
+    fn rpc_error<E>(e: E) -> Self
+    where
+        E: std::fmt::Debug,
+    {
+        Stopped {
+            phantom: Default::default(),
+            stop_reason: StopReason::RpcClient(format!("{:?}", e)),
+        }
+    }
+}
+
+struct FetchingTransactions<Storage> {
+    phantom: std::marker::PhantomData<Storage>,
+}
+
+impl<Storage> FetchingTransactions<Storage> {
+    fn new() -> Self {
+        FetchingTransactions {
+            phantom: Default::default(),
+        }
+    }
+}
+
+struct WaitForHistoryUpdateTrigger<Storage> {
+    phantom: std::marker::PhantomData<Storage>,
+}
+
+impl<Storage> WaitForHistoryUpdateTrigger<Storage> {
+    fn new() -> Self {
+        WaitForHistoryUpdateTrigger {
+            phantom: Default::default(),
+        }
+    }
+}
+
+struct OnIoErrorCooldown<Storage> {
+    phantom: std::marker::PhantomData<Storage>,
+}
+
+impl<Storage> OnIoErrorCooldown<Storage> {
+    fn new() -> Self {
+        OnIoErrorCooldown {
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl<Storage> TransitionFrom<SolanaInit<Storage>> for Stopped<Storage> {}
+impl<Storage> TransitionFrom<SolanaInit<Storage>> for FetchingTransactions<Storage> {}
+impl<Storage> TransitionFrom<FetchingTransactions<Storage>> for Stopped<Storage> {}
+impl<Storage> TransitionFrom<FetchingTransactions<Storage>> for OnIoErrorCooldown<Storage> {}
+impl<Storage> TransitionFrom<FetchingTransactions<Storage>> for WaitForHistoryUpdateTrigger<Storage> {}
+impl<Storage> TransitionFrom<OnIoErrorCooldown<Storage>> for FetchingTransactions<Storage> {}
+impl<Storage> TransitionFrom<WaitForHistoryUpdateTrigger<Storage>> for FetchingTransactions<Storage> {}
+
+#[async_trait]
+impl<Storage: TxHistoryStorage> State for SolanaInit<Storage> {
+    type StateMachine = SolanaTxHistoryStateMachine<Storage>;
+
+    async fn on_changed(
+        self: Box<Self>,
+        ctx: &mut SolanaTxHistoryStateMachine<Storage>,
+    ) -> StateResult<SolanaTxHistoryStateMachine<Storage>> {
+        ctx.coin.set_history_sync_state(HistorySyncState::NotStarted);
+
+        if let Err(e) = ctx.storage.init(&ctx.coin.history_wallet_id()).await {
+            return Self::change_state(Stopped::storage_error(e));
+        }
+
+        Self::change_state(FetchingTransactions::new())
+    }
+}
+
+/// Returns the signature of the newest stored transfer of the `account`.
+/// The fee records of the token transfers are skipped since their transactions are scanned with the token accounts,
+/// so they don't mean that the platform account is scanned up to them.
+async fn newest_stored_signature<Storage: TxHistoryStorage>(
+    coin: &SolanaCoin,
+    storage: &Storage,
+    account: &ScannedAccount,
+) -> Result<Option<Signature>, Stopped<Storage>> {
+    let wallet_id = coin.history_wallet_id();
+    let filters = account.history_filters(coin);
+    for page in 1.. {
+        let paging = PagingOptionsEnum::PageNumber(NonZeroUsize::new(page).expect("page is never 0"));
+        let history = storage
+            .get_history(&wallet_id, filters.clone(), paging, STORED_HISTORY_PAGE_SIZE)
+            .await
+            .map_err(Stopped::storage_error)?;
+        let newest_transfer = history
+            .transactions
+            .iter()
+            .find(|tx| tx.transaction_type != TransactionType::FeeForTokenTx);
+        if let Some(tx) = newest_transfer {
+            return Ok(Signature::from_str(&tx.tx_hash).ok());
+        }
+        if history.transactions.len() < STORED_HISTORY_PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+/// Pages the signatures of the `account` from the newest to the oldest until the `until` signature
+/// or the very first transaction of the account is reached.
+/// The transactions are then processed from the oldest to the newest, so if the fetching is interrupted,
+/// the stored history never has gaps.
+/// Returns the newest fetched signature.
+async fn fetch_and_insert_txs<Storage: TxHistoryStorage>(
+    coin: &SolanaCoin,
+    storage: &Storage,
+    account: &ScannedAccount,
+    until: Option<Signature>,
+) -> Result<Option<Signature>, Stopped<Storage>> {
+    let wallet_id = coin.history_wallet_id();
+    let address = account.address(coin);
+
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit: Some(SIGNATURES_PAGE_SIZE),
+            commitment: None,
+        };
+        let rpc_coin = coin.clone();
+        let statuses = async_blocking(move || rpc_coin.rpc().get_signatures_for_address_with_config(&address, config))
+            .await
+            .map_err(Stopped::rpc_error)?;
+
+        // A transaction can be stored by another account already, e.g. the fee of a token transfer,
+        // so the stored transfers are skipped one by one instead of stopping at the first stored transaction.
+        let page_len = statuses.len();
+        for status in statuses {
+            let signature = try_or_continue!(Signature::from_str(&status.signature), "Invalid signature");
+            before = Some(signature);
+            signatures.push((signature, status.err.is_none()));
+        }
+
+        if page_len < SIGNATURES_PAGE_SIZE {
+            break;
+        }
+    }
+
+    let newest_signature = signatures.first().map(|(signature, _)| *signature);
+    for (signature, succeeded) in signatures.into_iter().rev() {
+        if !succeeded {
+            continue;
+        }
+
+        let rpc_coin = coin.clone();
+        let encoded_tx = async_blocking(move || {
+            rpc_coin
+                .rpc()
+                .get_transaction(&signature, UiTransactionEncoding::JsonParsed)
+        })
+        .await
+        .map_err(Stopped::rpc_error)?;
+        let json_tx = try_or_continue!(serde_json::to_value(&encoded_tx), "Error on serializing tx");
+        let tx: SolanaConfirmedTransaction = try_or_continue!(serde_json::from_value(json_tx), "Error on decoding tx");
+        if tx.is_failed() {
+            continue;
+        }
+
+        let transfers = try_or_continue!(account.extract_transactions(coin, &tx), "Error on extracting transfers");
+
+        let mut new_transfers = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            let stored = storage
+                .get_tx_from_history(&wallet_id, &transfer.internal_id)
+                .await
+                .map_err(Stopped::storage_error)?;
+            if stored.is_none() {
+                new_transfers.push(transfer);
+            }
+        }
+        if new_transfers.is_empty() {
+            continue;
+        }
+
+        storage
+            .add_transactions_to_history(&wallet_id, new_transfers)
+            .await
+            .map_err(Stopped::storage_error)?;
+        log::debug!("Tx '{}' successfully parsed.", signature);
+    }
+
+    Ok(newest_signature)
+}
+
+#[async_trait]
+impl<Storage: TxHistoryStorage> State for FetchingTransactions<Storage> {
+    type StateMachine = SolanaTxHistoryStateMachine<Storage>;
+
+    async fn on_changed(
+        self: Box<Self>,
+        ctx: &mut SolanaTxHistoryStateMachine<Storage>,
+    ) -> StateResult<SolanaTxHistoryStateMachine<Storage>> {
+        // SPL tokens can be activated after the platform coin, so the accounts are collected on every iteration.
+        for account in ScannedAccount::all_for_coin(&ctx.coin) {
+            let address = account.address(&ctx.coin);
+            let until = match ctx.last_signatures.get(&address) {
+                Some(signature) => Some(*signature),
+                None => match newest_stored_signature(&ctx.coin, &ctx.storage, &account).await {
+                    Ok(signature) => signature,
+                    Err(stopped) => return Self::change_state(stopped),
+                },
+            };
+
+            match fetch_and_insert_txs(&ctx.coin, &ctx.storage, &account, until).await {
+                Ok(newest_signature) => {
+                    if let Some(signature) = newest_signature.or(until) {
+                        ctx.last_signatures.insert(address, signature);
+                    }
+                },
+                Err(stopped) => {
+                    if let StopReason::RpcClient(e) = &stopped.stop_reason {
+                        log::error!("Tx history process turned into cooldown mode due to rpc error: {e}");
+                        return Self::change_state(OnIoErrorCooldown::new());
+                    }
+
+                    return Self::change_state(stopped);
+                },
+            }
+        }
+
+        log::info!("Tx history fetching finished for {}", ctx.coin.ticker());
+
+        ctx.coin.set_history_sync_state(HistorySyncState::Finished);
+        Self::change_state(WaitForHistoryUpdateTrigger::new())
+    }
+}
+
+#[async_trait]
+impl<Storage: TxHistoryStorage> State for WaitForHistoryUpdateTrigger<Storage> {
+    type StateMachine = SolanaTxHistoryStateMachine<Storage>;
+
+    async fn on_changed(
+        self: Box<Self>,
+        _ctx: &mut SolanaTxHistoryStateMachine<Storage>,
+    ) -> StateResult<SolanaTxHistoryStateMachine<Storage>> {
+        // Fetching the signatures newer than the last known ones is as cheap as requesting the balances.
+        Timer::sleep(30.).await;
+
+        Self::change_state(FetchingTransactions::new())
+    }
+}
+
+#[async_trait]
+impl<Storage: TxHistoryStorage> State for OnIoErrorCooldown<Storage> {
+    type StateMachine = SolanaTxHistoryStateMachine<Storage>;
+
+    async fn on_changed(
+        self: Box<Self>,
+        _ctx: &mut SolanaTxHistoryStateMachine<Storage>,
+    ) -> StateResult<SolanaTxHistoryStateMachine<Storage>> {
+        Timer::sleep(30.).await;
+
+        // retry history fetching process from the last fetched signatures
+        Self::change_state(FetchingTransactions::new())
+    }
+}
+
+#[async_trait]
+impl<Storage: TxHistoryStorage> LastState for Stopped<Storage> {
+    type StateMachine = SolanaTxHistoryStateMachine<Storage>;
+
+    async fn on_changed(self: Box<Self>, ctx: &mut SolanaTxHistoryStateMachine<Storage>) -> () {
+        log::info!(
+            "Stopping tx history fetching for {}. Reason: {:?}",
+            ctx.coin.ticker(),
+            self.stop_reason
+        );
+
+        let new_state_json = json!({
+            "message": format!("{:?}", self.stop_reason),
+        });
+
+        ctx.coin.set_history_sync_state(HistorySyncState::Error(new_state_json));
+    }
+}
+
+pub async fn solana_history_loop(
+    coin: SolanaCoin,
+    storage: impl TxHistoryStorage,
+    _ctx: MmArc,
+    _current_balance: Option<BigDecimal>,
+) {
+    let mut state_machine = SolanaTxHistoryStateMachine {
+        coin,
+        storage,
+        last_signatures: HashMap::new(),
+    };
+
+    state_machine
+        .run(Box::new(SolanaInit::new()))
+        .await
+        .expect("The error of this machine is Infallible");
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::my_tx_history_v2::for_tests::init_storage_for;
+    use crate::solana::solana_common_tests::{solana_coin_for_test, SolanaNet};
+    use common::block_on;
+
+    fn stored_transfer(
+        coin: &SolanaCoin,
+        signature: &Signature,
+        block_height: u64,
+        internal_id: &str,
+        transaction_type: TransactionType,
+    ) -> TransactionDetails {
+        serde_json::from_value(json!({
+            "tx_hex": "",
+            "tx_hash": signature.to_string(),
+            "from": [coin.my_address],
+            "to": [],
+            "total_amount": "1",
+            "spent_by_me": "1",
+            "received_by_me": "0",
+            "my_balance_change": "-1",
+            "block_height": block_height,
+            "timestamp": 1690000000 + block_height,
+            "fee_details": null,
+            "coin": coin.ticker,
+            "internal_id": internal_id,
+            "transaction_type": transaction_type,
+            "memo": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_newest_stored_signature() {
+        let passphrase = "federal stay trigger hour exist success game vapor become comfort action phone bright ill target wild nasty crumble dune close rare fabric hen iron".to_string();
+        let (_, coin) = solana_coin_for_test(passphrase, SolanaNet::Devnet);
+        let (_ctx, storage) = init_storage_for(&coin);
+        let mint = Pubkey::new_unique();
+        let token_account = ScannedAccount::Token {
+            ticker: "USDC-SOL".to_owned(),
+            info: SplTokenInfo {
+                token_contract_address: mint,
+                decimals: 6,
+            },
+            token_account: get_associated_token_address(&coin.key_pair.pubkey(), &mint),
+        };
+
+        let newest = block_on(newest_stored_signature(&coin, &storage, &ScannedAccount::Platform)).ok();
+        assert_eq!(newest, Some(None));
+
+        let sol_signature = Signature::new(&[1; 64]);
+        let token_signature = Signature::new(&[2; 64]);
+        let token_id = BytesJson::from(mint.to_bytes().to_vec());
+        let transfers = vec![
+            stored_transfer(&coin, &sol_signature, 1, "01", TransactionType::StandardTransfer),
+            stored_transfer(
+                &coin,
+                &token_signature,
+                2,
+                "02",
+                TransactionType::TokenTransfer(token_id),
+            ),
+            stored_transfer(&coin, &token_signature, 2, "03", TransactionType::FeeForTokenTx),
+        ];
+        block_on(storage.add_transactions_to_history(&coin.history_wallet_id(), transfers)).unwrap();
+
+        // The fee of the token transfer doesn't move the platform account lower bound
+        let newest = block_on(newest_stored_signature(&coin, &storage, &ScannedAccount::Platform)).ok();
+        assert_eq!(newest, Some(Some(sol_signature)));
+
+        let newest = block_on(newest_stored_signature(&coin, &storage, &token_account)).ok();
+        assert_eq!(newest, Some(Some(token_signature)));
+    }
+}
@@ -1,6 +1,8 @@
 use super::{CoinBalance, HistorySyncState, MarketCoinOps, MmCoin, SwapOps, TradeFee, TransactionEnum, WatcherOps};
 use crate::coin_errors::MyAddressError;
 use crate::solana::solana_common::{ui_amount_to_amount, PrepareTransferData, SufficientBalanceError};
+use crate::solana::solana_htlc::{watchers_not_supported, watchers_not_supported_tx_fut,
+                                 watchers_not_supported_validation_fut};
 use crate::solana::{solana_common, AccountError, SolanaCommonOps, SolanaFeeDetails};
 use crate::{BalanceFut, CheckIfMyPaymentSentArgs, CoinFutSpawner, ConfirmPaymentInput, DexFee, FeeApproxStage,
             FoundSwapTxSpend, MakerSwapTakerCoin, MmCoinEnum, NegotiateSwapContractAddrErr, PaymentInstructionArgs,
@@ -16,6 +18,7 @@ use crate::{BalanceFut, CheckIfMyPaymentSentArgs, CoinFutSpawner, ConfirmPayment
 use async_trait::async_trait;
 use bincode::serialize;
 use common::executor::{abortable_queue::AbortableQueue, AbortableSystem, AbortedError};
+use common::log::warn;
 use common::{async_blocking, now_sec};
 use futures::{FutureExt, TryFutureExt};
 use futures01::Future;
@@ -266,16 +269,16 @@ impl MarketCoinOps for SplToken {
         self.platform_coin.send_raw_tx_bytes(tx)
     }
 
-    fn wait_for_confirmations(&self, _input: ConfirmPaymentInput) -> Box<dyn Future<Item = (), Error = String> + Send> {
-        unimplemented!()
+    fn wait_for_confirmations(&self, input: ConfirmPaymentInput) -> Box<dyn Future<Item = (), Error = String> + Send> {
+        self.platform_coin.wait_for_confirmations(input)
     }
 
-    fn wait_for_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut { unimplemented!() }
+    fn wait_for_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        self.platform_coin.wait_for_htlc_tx_spend(args)
+    }
 
-    fn tx_enum_from_bytes(&self, _bytes: &[u8]) -> Result<TransactionEnum, MmError<TxMarshalingErr>> {
-        MmError::err(TxMarshalingErr::NotSupported(
-            "tx_enum_from_bytes is not supported for Spl yet.".to_string(),
-        ))
+    fn tx_enum_from_bytes(&self, bytes: &[u8]) -> Result<TransactionEnum, MmError<TxMarshalingErr>> {
+        self.platform_coin.tx_enum_from_bytes(bytes)
     }
 
     fn current_block(&self) -> Box<dyn Future<Item = u64, Error = String> + Send> { self.platform_coin.current_block() }
@@ -289,72 +292,82 @@ impl MarketCoinOps for SplToken {
 
 #[async_trait]
 impl SwapOps for SplToken {
-    fn send_taker_fee(&self, _fee_addr: &[u8], dex_fee: DexFee, _uuid: &[u8]) -> TransactionFut { unimplemented!() }
+    fn send_taker_fee(&self, _fee_addr: &[u8], dex_fee: DexFee, _uuid: &[u8]) -> TransactionFut {
+        self.platform_coin.send_dex_fee(dex_fee, Some(self.get_info()))
+    }
 
-    fn send_maker_payment(&self, _maker_payment_args: SendPaymentArgs) -> TransactionFut { unimplemented!() }
+    fn send_maker_payment(&self, maker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.platform_coin
+            .send_htlc_payment(maker_payment_args, Some(self.get_info()))
+    }
 
-    fn send_taker_payment(&self, _taker_payment_args: SendPaymentArgs) -> TransactionFut { unimplemented!() }
+    fn send_taker_payment(&self, taker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.platform_coin
+            .send_htlc_payment(taker_payment_args, Some(self.get_info()))
+    }
 
-    fn send_maker_spends_taker_payment(&self, _maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
-        unimplemented!()
+    fn send_maker_spends_taker_payment(&self, maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.platform_coin.spend_htlc_payment(maker_spends_payment_args)
     }
 
-    fn send_taker_spends_maker_payment(&self, _taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
-        unimplemented!()
+    fn send_taker_spends_maker_payment(&self, taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.platform_coin.spend_htlc_payment(taker_spends_payment_args)
     }
 
-    async fn send_taker_refunds_payment(
-        &self,
-        _taker_refunds_payment_args: RefundPaymentArgs<'_>,
-    ) -> TransactionResult {
-        unimplemented!()
+    async fn send_taker_refunds_payment(&self, taker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        self.platform_coin.refund_htlc_payment(taker_refunds_payment_args).await
     }
 
-    async fn send_maker_refunds_payment(
-        &self,
-        _maker_refunds_payment_args: RefundPaymentArgs<'_>,
-    ) -> TransactionResult {
-        todo!()
+    async fn send_maker_refunds_payment(&self, maker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        self.platform_coin.refund_htlc_payment(maker_refunds_payment_args).await
     }
 
-    fn validate_fee(&self, _validate_fee_args: ValidateFeeArgs) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_fee(&self, validate_fee_args: ValidateFeeArgs) -> ValidatePaymentFut<()> {
+        self.platform_coin
+            .validate_dex_fee(validate_fee_args, Some(self.get_info()))
+    }
 
-    fn validate_maker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_maker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.platform_coin.validate_htlc_payment(input, Some(self.get_info()))
+    }
 
-    fn validate_taker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> { unimplemented!() }
+    fn validate_taker_payment(&self, input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.platform_coin.validate_htlc_payment(input, Some(self.get_info()))
+    }
 
     fn check_if_my_payment_sent(
         &self,
-        _if_my_payment_sent_args: CheckIfMyPaymentSentArgs,
+        if_my_payment_sent_args: CheckIfMyPaymentSentArgs,
     ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
-        unimplemented!()
+        self.platform_coin
+            .check_if_my_htlc_payment_sent(if_my_payment_sent_args, Some(self.get_info()))
     }
 
     async fn search_for_swap_tx_spend_my(
         &self,
-        _: SearchForSwapTxSpendInput<'_>,
+        input: SearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!()
+        self.platform_coin.search_for_htlc_spend(input.tx).await
     }
 
     async fn search_for_swap_tx_spend_other(
         &self,
-        _: SearchForSwapTxSpendInput<'_>,
+        input: SearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!()
+        self.platform_coin.search_for_htlc_spend(input.tx).await
     }
 
     fn check_tx_signed_by_pub(&self, tx: &[u8], expected_pub: &[u8]) -> Result<bool, MmError<ValidatePaymentError>> {
-        unimplemented!();
+        self.platform_coin.check_tx_signed_by_pub(tx, expected_pub)
     }
 
     async fn extract_secret(
         &self,
         secret_hash: &[u8],
         spend_tx: &[u8],
-        watcher_reward: bool,
+        _watcher_reward: bool,
     ) -> Result<Vec<u8>, String> {
-        unimplemented!()
+        self.platform_coin.extract_htlc_secret(secret_hash, spend_tx)
     }
 
     fn is_auto_refundable(&self) -> bool { false }
@@ -367,33 +380,37 @@ impl SwapOps for SplToken {
 
     fn negotiate_swap_contract_addr(
         &self,
-        _other_side_address: Option<&[u8]>,
+        other_side_address: Option<&[u8]>,
     ) -> Result<Option<BytesJson>, MmError<NegotiateSwapContractAddrErr>> {
-        unimplemented!()
+        self.platform_coin.negotiate_swap_contract_addr(other_side_address)
     }
 
     #[inline]
-    fn derive_htlc_key_pair(&self, _swap_unique_data: &[u8]) -> KeyPair { todo!() }
+    fn derive_htlc_key_pair(&self, swap_unique_data: &[u8]) -> KeyPair {
+        self.platform_coin.derive_htlc_key_pair(swap_unique_data)
+    }
 
     #[inline]
     fn derive_htlc_pubkey(&self, swap_unique_data: &[u8]) -> Vec<u8> {
-        self.derive_htlc_key_pair(swap_unique_data).public_slice().to_vec()
+        self.platform_coin.derive_htlc_pubkey(swap_unique_data)
     }
 
-    fn validate_other_pubkey(&self, _raw_pubkey: &[u8]) -> MmResult<(), ValidateOtherPubKeyErr> { unimplemented!() }
+    fn validate_other_pubkey(&self, raw_pubkey: &[u8]) -> MmResult<(), ValidateOtherPubKeyErr> {
+        self.platform_coin.validate_other_pubkey(raw_pubkey)
+    }
 
     async fn maker_payment_instructions(
         &self,
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
-        unimplemented!()
+        Ok(None)
     }
 
     async fn taker_payment_instructions(
         &self,
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
-        unimplemented!()
+        Ok(None)
     }
 
     fn validate_maker_payment_instructions(
@@ -401,7 +418,7 @@ impl SwapOps for SplToken {
         _instructions: &[u8],
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
-        unimplemented!()
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
     }
 
     fn validate_taker_payment_instructions(
@@ -409,7 +426,7 @@ impl SwapOps for SplToken {
         _instructions: &[u8],
         _args: PaymentInstructionArgs<'_>,
     ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
-        unimplemented!()
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
     }
 }
 
@@ -430,7 +447,7 @@ impl MakerSwapTakerCoin for SplToken {
 #[async_trait]
 impl WatcherOps for SplToken {
     fn send_maker_payment_spend_preimage(&self, _input: SendMakerPaymentSpendPreimageInput) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn create_taker_payment_refund_preimage(
@@ -442,7 +459,7 @@ impl WatcherOps for SplToken {
         _swap_contract_address: &Option<BytesJson>,
         _swap_unique_data: &[u8],
     ) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn create_maker_payment_spend_preimage(
@@ -453,50 +470,50 @@ impl WatcherOps for SplToken {
         _secret_hash: &[u8],
         _swap_unique_data: &[u8],
     ) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn send_taker_payment_refund_preimage(&self, _watcher_refunds_payment_args: RefundPaymentArgs) -> TransactionFut {
-        unimplemented!();
+        watchers_not_supported_tx_fut(self.ticker())
     }
 
     fn watcher_validate_taker_fee(&self, _input: WatcherValidateTakerFeeInput) -> ValidatePaymentFut<()> {
-        unimplemented!();
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     fn watcher_validate_taker_payment(&self, _input: WatcherValidatePaymentInput) -> ValidatePaymentFut<()> {
-        unimplemented!();
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     fn taker_validates_payment_spend_or_refund(&self, _input: ValidateWatcherSpendInput) -> ValidatePaymentFut<()> {
-        unimplemented!()
+        watchers_not_supported_validation_fut(self.ticker())
     }
 
     async fn watcher_search_for_swap_tx_spend(
         &self,
-        input: WatcherSearchForSwapTxSpendInput<'_>,
+        _input: WatcherSearchForSwapTxSpendInput<'_>,
     ) -> Result<Option<FoundSwapTxSpend>, String> {
-        unimplemented!();
+        Err(watchers_not_supported(self.ticker()))
     }
 
     async fn get_taker_watcher_reward(
         &self,
-        other_coin: &MmCoinEnum,
-        coin_amount: Option<BigDecimal>,
-        other_coin_amount: Option<BigDecimal>,
-        reward_amount: Option<BigDecimal>,
-        wait_until: u64,
+        _other_coin: &MmCoinEnum,
+        _coin_amount: Option<BigDecimal>,
+        _other_coin_amount: Option<BigDecimal>,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
     ) -> Result<WatcherReward, MmError<WatcherRewardError>> {
-        unimplemented!();
+        MmError::err(WatcherRewardError::InternalError(watchers_not_supported(self.ticker())))
     }
 
     async fn get_maker_watcher_reward(
         &self,
-        other_coin: &MmCoinEnum,
-        reward_amount: Option<BigDecimal>,
-        wait_until: u64,
+        _other_coin: &MmCoinEnum,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
     ) -> Result<Option<WatcherReward>, MmError<WatcherRewardError>> {
-        unimplemented!();
+        MmError::err(WatcherRewardError::InternalError(watchers_not_supported(self.ticker())))
     }
 }
 
@@ -520,42 +537,51 @@ impl MmCoin for SplToken {
 
     fn validate_address(&self, address: &str) -> ValidateAddressResult { self.platform_coin.validate_address(address) }
 
-    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> { unimplemented!() }
+    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> {
+        warn!("process_history_loop is deprecated, solana uses tx_history_v2");
+        Box::new(futures01::future::err(()))
+    }
 
-    fn history_sync_status(&self) -> HistorySyncState { unimplemented!() }
+    fn history_sync_status(&self) -> HistorySyncState { self.platform_coin.history_sync_status() }
 
     /// Get fee to be paid per 1 swap transaction
-    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> { unimplemented!() }
+    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> {
+        self.platform_coin.get_trade_fee()
+    }
 
     async fn get_sender_trade_fee(
         &self,
-        _value: TradePreimageValue,
-        _stage: FeeApproxStage,
+        value: TradePreimageValue,
+        stage: FeeApproxStage,
     ) -> TradePreimageResult<TradeFee> {
-        unimplemented!()
+        self.platform_coin.get_sender_trade_fee(value, stage).await
     }
 
-    fn get_receiver_trade_fee(&self, _stage: FeeApproxStage) -> TradePreimageFut<TradeFee> { unimplemented!() }
+    fn get_receiver_trade_fee(&self, stage: FeeApproxStage) -> TradePreimageFut<TradeFee> {
+        self.platform_coin.get_receiver_trade_fee(stage)
+    }
 
     async fn get_fee_to_send_taker_fee(
         &self,
-        _dex_fee_amount: DexFee,
-        _stage: FeeApproxStage,
+        dex_fee_amount: DexFee,
+        stage: FeeApproxStage,
     ) -> TradePreimageResult<TradeFee> {
-        unimplemented!()
+        self.platform_coin
+            .get_fee_to_send_taker_fee(dex_fee_amount, stage)
+            .await
     }
 
     fn required_confirmations(&self) -> u64 { 1 }
 
     fn requires_notarization(&self) -> bool { false }
 
-    fn set_required_confirmations(&self, _confirmations: u64) { unimplemented!() }
+    fn set_required_confirmations(&self, _confirmations: u64) {}
 
     fn set_requires_notarization(&self, _requires_nota: bool) { unimplemented!() }
 
-    fn swap_contract_address(&self) -> Option<BytesJson> { unimplemented!() }
+    fn swap_contract_address(&self) -> Option<BytesJson> { self.platform_coin.swap_contract_address() }
 
-    fn fallback_swap_contract(&self) -> Option<BytesJson> { unimplemented!() }
+    fn fallback_swap_contract(&self) -> Option<BytesJson> { self.platform_coin.fallback_swap_contract() }
 
     fn mature_confirmations(&self) -> Option<u32> { Some(1) }
 
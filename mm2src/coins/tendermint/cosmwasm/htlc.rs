@@ -0,0 +1,218 @@
+// CosmWasm HTLC implementation for AtomicDEX on top of Cosmos SDK(cosmrs).
+//
+// Chains without the Iris HTLC module (Osmosis, Juno, Neutron...) can still perform atomic swaps
+// using a deployed instance of the `cw20-atomic-swap` contract:
+// https://github.com/CosmWasm/cw-tokens/tree/main/contracts/cw20-atomic-swap
+//
+// The contract keeps every HTLC by its ID until it is released or refunded, so the open HTLCs
+// can be queried from the contract state, while the closed ones are found by the `wasm` events.
+//
+// ** Local testing **
+//
+// Run a local `wasmd` dev chain (e.g. `cosmwasm/wasmd` docker image), store and instantiate
+// `cw20_atomic_swap.wasm` with an empty init message `{}`, and then set the resulting contract address
+// into the `htlc_backend` field of the coin's `protocol_data`.
+
+use super::wasm_proto::MsgExecuteContractProto;
+
+use crate::tendermint::type_urls::EXECUTE_CONTRACT_TYPE_URL;
+use cosmrs::{tx::{Msg, MsgProto},
+             AccountId, Coin, ErrorReport};
+use std::convert::TryFrom;
+
+pub(crate) const CW_HTLC_CREATE_ACTION: &str = "create";
+pub(crate) const CW_HTLC_RELEASE_ACTION: &str = "release";
+pub(crate) const CW_HTLC_REFUND_ACTION: &str = "refund";
+/// The contract accepts the HTLC IDs of 3..=20 bytes only:
+/// https://github.com/CosmWasm/cw-tokens/blob/main/contracts/cw20-atomic-swap/src/contract.rs
+pub(crate) const CW_HTLC_ID_LEN: usize = 20;
+
+#[allow(dead_code)]
+pub(crate) struct CosmWasmHtlc {
+    /// Generated HTLC's ID.
+    pub(crate) id: String,
+
+    /// Message payload to be sent
+    pub(crate) msg_payload: cosmrs::Any,
+}
+
+/// https://github.com/CosmWasm/cw-utils/blob/v1.0.1/src/expiration.rs#L14-L22
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CosmWasmExpiration {
+    AtHeight(u64),
+    /// Timestamp in nanoseconds encoded as a string.
+    AtTime(String),
+    Never {},
+}
+
+impl CosmWasmExpiration {
+    #[inline]
+    pub(crate) fn at_timestamp_secs(timestamp: u64) -> Self {
+        CosmWasmExpiration::AtTime((timestamp as u128 * 1_000_000_000).to_string())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct CosmWasmCreateHtlc {
+    pub(crate) id: String,
+    /// Hex encoded sha256 hash of the secret.
+    pub(crate) hash: String,
+    pub(crate) recipient: String,
+    pub(crate) expires: CosmWasmExpiration,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CosmWasmHtlcExecuteMsg {
+    Create(CosmWasmCreateHtlc),
+    Release {
+        id: String,
+        /// Hex encoded secret.
+        preimage: String,
+    },
+    Refund {
+        id: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CosmWasmHtlcQueryMsg {
+    Details { id: String },
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct CosmWasmNativeCoin {
+    pub(crate) denom: String,
+    pub(crate) amount: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CosmWasmHtlcBalance {
+    Native(Vec<CosmWasmNativeCoin>),
+    Cw20(serde_json::Value),
+}
+
+/// The response of the `details` query of an open HTLC.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct CosmWasmHtlcDetails {
+    pub(crate) id: String,
+    pub(crate) hash: String,
+    pub(crate) recipient: String,
+    pub(crate) source: String,
+    pub(crate) expires: CosmWasmExpiration,
+    pub(crate) balance: CosmWasmHtlcBalance,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct MsgExecuteContract {
+    /// Sender's address.
+    pub(crate) sender: AccountId,
+
+    /// HTLC contract address.
+    pub(crate) contract: AccountId,
+
+    /// JSON encoded `CosmWasmHtlcExecuteMsg`.
+    pub(crate) msg: Vec<u8>,
+
+    /// Amount to be locked in the contract.
+    pub(crate) funds: Vec<Coin>,
+}
+
+impl MsgExecuteContract {
+    #[inline]
+    pub(crate) fn htlc_msg(&self) -> serde_json::Result<CosmWasmHtlcExecuteMsg> { serde_json::from_slice(&self.msg) }
+}
+
+impl Msg for MsgExecuteContract {
+    type Proto = MsgExecuteContractProto;
+}
+
+impl TryFrom<MsgExecuteContractProto> for MsgExecuteContract {
+    type Error = ErrorReport;
+
+    fn try_from(proto: MsgExecuteContractProto) -> Result<MsgExecuteContract, Self::Error> {
+        MsgExecuteContract::try_from(&proto)
+    }
+}
+
+impl TryFrom<&MsgExecuteContractProto> for MsgExecuteContract {
+    type Error = ErrorReport;
+
+    fn try_from(proto: &MsgExecuteContractProto) -> Result<MsgExecuteContract, Self::Error> {
+        Ok(MsgExecuteContract {
+            sender: proto.sender.parse()?,
+            contract: proto.contract.parse()?,
+            msg: proto.msg.clone(),
+            funds: proto.funds.iter().map(TryFrom::try_from).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<MsgExecuteContract> for MsgExecuteContractProto {
+    fn from(msg: MsgExecuteContract) -> MsgExecuteContractProto { MsgExecuteContractProto::from(&msg) }
+}
+
+impl From<&MsgExecuteContract> for MsgExecuteContractProto {
+    fn from(msg: &MsgExecuteContract) -> MsgExecuteContractProto {
+        MsgExecuteContractProto {
+            sender: msg.sender.to_string(),
+            contract: msg.contract.to_string(),
+            msg: msg.msg.clone(),
+            funds: msg.funds.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl MsgProto for MsgExecuteContractProto {
+    const TYPE_URL: &'static str = EXECUTE_CONTRACT_TYPE_URL;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_htlc_execute_msg_json() {
+        let create = CosmWasmHtlcExecuteMsg::Create(CosmWasmCreateHtlc {
+            id: "ID".into(),
+            hash: "ab".into(),
+            recipient: "wasm1recipient".into(),
+            expires: CosmWasmExpiration::at_timestamp_secs(1_700_000_000),
+        });
+        let expected = r#"{"create":{"id":"ID","hash":"ab","recipient":"wasm1recipient","expires":{"at_time":"1700000000000000000"}}}"#;
+        assert_eq!(serde_json::to_string(&create).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_str::<CosmWasmHtlcExecuteMsg>(expected).unwrap(),
+            create
+        );
+
+        let release = CosmWasmHtlcExecuteMsg::Release {
+            id: "ID".into(),
+            preimage: "cd".into(),
+        };
+        assert_eq!(
+            serde_json::to_string(&release).unwrap(),
+            r#"{"release":{"id":"ID","preimage":"cd"}}"#
+        );
+
+        let refund = CosmWasmHtlcExecuteMsg::Refund { id: "ID".into() };
+        assert_eq!(serde_json::to_string(&refund).unwrap(), r#"{"refund":{"id":"ID"}}"#);
+    }
+
+    #[test]
+    fn test_htlc_details_deserialize() {
+        let json = r#"{"id":"ID","hash":"ab","recipient":"wasm1recipient","source":"wasm1source","expires":{"at_time":"1700000000000000000"},"balance":{"native":[{"denom":"ustake","amount":"1000"}]}}"#;
+        let details: CosmWasmHtlcDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            details.balance,
+            CosmWasmHtlcBalance::Native(vec![CosmWasmNativeCoin {
+                denom: "ustake".into(),
+                amount: "1000".into()
+            }])
+        );
+        assert_eq!(details.expires, CosmWasmExpiration::at_timestamp_secs(1_700_000_000));
+    }
+}
@@ -0,0 +1,2 @@
+pub(crate) mod htlc;
+pub(crate) mod wasm_proto;
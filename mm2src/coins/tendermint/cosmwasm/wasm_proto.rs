@@ -0,0 +1,27 @@
+// https://github.com/CosmWasm/wasmd/blob/v0.40.0/proto/cosmwasm/wasm/v1/tx.proto#L93-L105
+#[derive(prost::Message)]
+pub(crate) struct MsgExecuteContractProto {
+    #[prost(string, tag = "1")]
+    pub(crate) sender: prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub(crate) contract: prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub(crate) msg: prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "5")]
+    pub(crate) funds: prost::alloc::vec::Vec<cosmrs::proto::cosmos::base::v1beta1::Coin>,
+}
+
+// https://github.com/CosmWasm/wasmd/blob/v0.40.0/proto/cosmwasm/wasm/v1/query.proto#L171-L177
+#[derive(prost::Message)]
+pub(crate) struct QuerySmartContractStateRequestProto {
+    #[prost(string, tag = "1")]
+    pub(crate) address: prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub(crate) query_data: prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(prost::Message)]
+pub(crate) struct QuerySmartContractStateResponseProto {
+    #[prost(bytes = "vec", tag = "1")]
+    pub(crate) data: prost::alloc::vec::Vec<u8>,
+}
@@ -1,6 +1,13 @@
 mod ibc_proto;
+pub(crate) mod transfer_storage;
+mod transfer_tracker;
 pub(crate) mod transfer_v1;
 
+#[cfg(target_arch = "wasm32")]
+pub use transfer_storage::IBCTransferDb;
+pub use transfer_storage::{IBCTransferRecord, IBCTransferStatus, IBCTransferStorageError};
+pub use transfer_tracker::IBC_TRANSFER_STATUS_EVENT;
+
 pub(crate) const IBC_OUT_SOURCE_PORT: &str = "transfer";
 pub(crate) const IBC_OUT_TIMEOUT_IN_NANOS: u64 = 60000000000 * 15; // 15 minutes
 pub(crate) const IBC_GAS_LIMIT_DEFAULT: u64 = 150_000;
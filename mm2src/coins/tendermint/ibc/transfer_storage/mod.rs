@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use derive_more::Display;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+
+#[cfg(not(target_arch = "wasm32"))] mod sqlite_storage;
+#[cfg(target_arch = "wasm32")] mod wasm_storage;
+
+cfg_wasm32! {
+    pub(crate) use wasm_storage::IBCTransferIndexedDbStorage as IBCTransferStorage;
+
+    pub use wasm_storage::IBCTransferDb;
+}
+
+cfg_native! {
+    pub(crate) use sqlite_storage::IBCTransferSqliteStorage as IBCTransferStorage;
+}
+
+pub type IBCTransferStorageResult<T> = MmResult<T, IBCTransferStorageError>;
+
+#[derive(Debug, Display)]
+pub enum IBCTransferStorageError {
+    #[display(fmt = "Error saving changes in IBC transfer storage: {}", _0)]
+    ErrorSaving(String),
+    #[display(fmt = "Error loading from IBC transfer storage: {}", _0)]
+    ErrorLoading(String),
+    #[display(fmt = "Error deserializing an IBC transfer: {}", _0)]
+    ErrorDeserializing(String),
+    #[display(fmt = "Error serializing an IBC transfer: {}", _0)]
+    ErrorSerializing(String),
+    #[display(fmt = "Internal error: {}", _0)]
+    Internal(String),
+}
+
+/// The lifecycle of an outgoing ICS-20 transfer packet.
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum IBCTransferStatus {
+    /// The transfer is signed, but it hasn't been found on the source chain yet.
+    Pending,
+    /// The packet is committed on the source chain and is waiting to be relayed.
+    Sent,
+    /// The packet is received on the counterparty chain,
+    /// the acknowledgement hasn't been relayed back to the source chain yet.
+    Received,
+    /// The counterparty chain has successfully acknowledged the packet.
+    Acknowledged,
+    /// The counterparty chain has returned an error acknowledgement, the funds are refunded to the sender.
+    Failed,
+    /// The packet has timed out, the funds are refunded to the sender once the timeout is relayed to the source chain.
+    TimedOut,
+    /// The transfer has not been broadcasted before the packet timeout.
+    Expired,
+}
+
+impl IBCTransferStatus {
+    /// Whether the transfer can't change its status anymore.
+    #[inline]
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            IBCTransferStatus::Acknowledged
+                | IBCTransferStatus::Failed
+                | IBCTransferStatus::TimedOut
+                | IBCTransferStatus::Expired
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IBCTransferRecord {
+    pub tx_hash: String,
+    /// The ticker of the transferred coin or token.
+    pub coin: String,
+    /// The ticker of the platform coin which tracks the transfer.
+    pub platform_coin: String,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    pub amount: BigDecimal,
+    pub source_port: String,
+    pub source_channel: String,
+    /// Known once the `send_packet` event is found on the source chain.
+    pub destination_port: Option<String>,
+    /// Known once the `send_packet` event is found on the source chain.
+    pub destination_channel: Option<String>,
+    /// Known once the `send_packet` event is found on the source chain.
+    pub sequence: Option<u64>,
+    /// Packet timeout timestamp in absolute nanoseconds since unix epoch.
+    pub timeout_timestamp: u64,
+    pub status: IBCTransferStatus,
+    /// The counterparty chain tx that has received the packet.
+    pub recv_tx_hash: Option<String>,
+    /// The source chain tx that has acknowledged or timed out the packet.
+    pub finalize_tx_hash: Option<String>,
+    /// The error acknowledgement or the failure reason of the transfer tx.
+    pub error: Option<String>,
+    /// As seconds
+    pub created_at: u64,
+    /// As seconds
+    pub updated_at: u64,
+}
+
+#[async_trait]
+pub trait IBCTransferStorageOps {
+    async fn init(ctx: &MmArc) -> IBCTransferStorageResult<Self>
+    where
+        Self: Sized;
+
+    /// Inserts the given transfer or replaces the existing one with the same `tx_hash`.
+    async fn save_transfer(&self, transfer: IBCTransferRecord) -> IBCTransferStorageResult<()>;
+
+    async fn load_transfer(&self, tx_hash: &str) -> IBCTransferStorageResult<Option<IBCTransferRecord>>;
+
+    /// Loads the transfers tracked by the given platform coin whose status is not final yet.
+    async fn load_unfinished_transfers(&self, platform_coin: &str) -> IBCTransferStorageResult<Vec<IBCTransferRecord>>;
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use mm2_test_helpers::for_tests::mm_ctx_with_custom_db;
+
+    cfg_wasm32! {
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+    }
+
+    cfg_native! {
+        use common::block_on;
+    }
+
+    fn transfer_for_test(tx_hash: &str, platform_coin: &str, status: IBCTransferStatus) -> IBCTransferRecord {
+        IBCTransferRecord {
+            tx_hash: tx_hash.to_owned(),
+            coin: platform_coin.to_owned(),
+            platform_coin: platform_coin.to_owned(),
+            sender: "cosmos1sender".to_owned(),
+            receiver: "osmo1receiver".to_owned(),
+            denom: "uatom".to_owned(),
+            amount: "0.1".parse().unwrap(),
+            source_port: "transfer".to_owned(),
+            source_channel: "channel-141".to_owned(),
+            destination_port: None,
+            destination_channel: None,
+            sequence: None,
+            timeout_timestamp: 1_700_000_000_000_000_000,
+            status,
+            recv_tx_hash: None,
+            finalize_tx_hash: None,
+            error: None,
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_000,
+        }
+    }
+
+    async fn test_unfinished_transfers_impl() {
+        let ctx = mm_ctx_with_custom_db();
+        let storage = IBCTransferStorage::init(&ctx).await.expect("!IBCTransferStorage::init");
+
+        let atom_pending = transfer_for_test("AA", "ATOM", IBCTransferStatus::Pending);
+        let atom_acknowledged = transfer_for_test("BB", "ATOM", IBCTransferStatus::Acknowledged);
+        let iris_sent = transfer_for_test("CC", "IRIS", IBCTransferStatus::Sent);
+        for transfer in [&atom_pending, &atom_acknowledged, &iris_sent] {
+            storage.save_transfer(transfer.clone()).await.expect("!save_transfer");
+        }
+
+        let actual = storage.load_unfinished_transfers("ATOM").await.unwrap();
+        assert_eq!(actual, vec![atom_pending.clone()]);
+
+        let mut atom_sent = atom_pending;
+        atom_sent.status = IBCTransferStatus::Sent;
+        atom_sent.sequence = Some(42);
+        atom_sent.destination_channel = Some("channel-0".to_owned());
+        storage.save_transfer(atom_sent.clone()).await.expect("!save_transfer");
+
+        let actual = storage.load_transfer("AA").await.unwrap();
+        assert_eq!(actual, Some(atom_sent.clone()));
+
+        let mut atom_timed_out = atom_sent;
+        atom_timed_out.status = IBCTransferStatus::TimedOut;
+        storage.save_transfer(atom_timed_out).await.expect("!save_transfer");
+
+        let actual = storage.load_unfinished_transfers("ATOM").await.unwrap();
+        assert!(actual.is_empty());
+        assert_eq!(storage.load_transfer("DD").await.unwrap(), None);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    async fn test_unfinished_transfers() { test_unfinished_transfers_impl().await }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_unfinished_transfers() { block_on(test_unfinished_transfers_impl()) }
+}
@@ -0,0 +1,155 @@
+#![allow(deprecated)] // TODO: remove this once rusqlite is >= 0.29
+
+use super::{IBCTransferRecord, IBCTransferStorageError, IBCTransferStorageOps, IBCTransferStorageResult};
+use async_trait::async_trait;
+use common::async_blocking;
+use db_common::owned_named_params;
+use db_common::sqlite::rusqlite::{Connection, Error as SqlError, Row};
+use db_common::sqlite::{query_single_row_with_named_params, AsSqlNamedParams, SqliteConnShared, SqliteConnWeak};
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use std::sync::MutexGuard;
+
+const CREATE_IBC_TRANSFER_TABLE: &str = "CREATE TABLE IF NOT EXISTS ibc_transfer (
+    tx_hash VARCHAR(255) NOT NULL PRIMARY KEY,
+    platform_coin VARCHAR(255) NOT NULL,
+    is_final INTEGER NOT NULL,
+    details_json TEXT NOT NULL
+);";
+
+const INSERT_OR_REPLACE_TRANSFER: &str = "INSERT OR REPLACE INTO ibc_transfer
+    (tx_hash, platform_coin, is_final, details_json)
+    VALUES (:tx_hash, :platform_coin, :is_final, :details_json);";
+
+const SELECT_TRANSFER: &str = "SELECT details_json FROM ibc_transfer WHERE tx_hash=:tx_hash;";
+
+const SELECT_UNFINISHED_TRANSFERS: &str =
+    "SELECT details_json FROM ibc_transfer WHERE platform_coin=:platform_coin AND is_final=0;";
+
+impl From<SqlError> for IBCTransferStorageError {
+    fn from(e: SqlError) -> Self {
+        let error = e.to_string();
+        match e {
+            SqlError::FromSqlConversionFailure(_, _, _)
+            | SqlError::IntegralValueOutOfRange(_, _)
+            | SqlError::InvalidColumnIndex(_)
+            | SqlError::InvalidColumnType(_, _, _) => IBCTransferStorageError::ErrorDeserializing(error),
+            SqlError::Utf8Error(_) | SqlError::NulError(_) | SqlError::ToSqlConversionFailure(_) => {
+                IBCTransferStorageError::ErrorSerializing(error)
+            },
+            _ => IBCTransferStorageError::Internal(error),
+        }
+    }
+}
+
+fn transfer_from_row(row: &Row<'_>) -> Result<String, SqlError> { row.get(0) }
+
+fn parse_transfer(details_json: &str) -> IBCTransferStorageResult<IBCTransferRecord> {
+    serde_json::from_str(details_json).map_to_mm(|e| IBCTransferStorageError::ErrorDeserializing(e.to_string()))
+}
+
+#[derive(Clone)]
+pub struct IBCTransferSqliteStorage {
+    conn: SqliteConnWeak,
+}
+
+#[async_trait]
+impl IBCTransferStorageOps for IBCTransferSqliteStorage {
+    async fn init(ctx: &MmArc) -> IBCTransferStorageResult<Self>
+    where
+        Self: Sized,
+    {
+        let shared = ctx.shared_sqlite_conn.as_option().or_mm_err(|| {
+            IBCTransferStorageError::Internal("'MmCtx::shared_sqlite_conn' is not initialized".to_owned())
+        })?;
+        let storage = IBCTransferSqliteStorage {
+            conn: SqliteConnShared::downgrade(shared),
+        };
+        storage.init_tables().await?;
+        Ok(storage)
+    }
+
+    async fn save_transfer(&self, transfer: IBCTransferRecord) -> IBCTransferStorageResult<()> {
+        let details_json =
+            serde_json::to_string(&transfer).map_to_mm(|e| IBCTransferStorageError::ErrorSerializing(e.to_string()))?;
+
+        let selfi = self.clone();
+        async_blocking(move || {
+            let conn_shared = selfi.get_shared_conn()?;
+            let conn = Self::lock_conn_mutex(&conn_shared)?;
+
+            let params = owned_named_params! {
+                ":tx_hash": transfer.tx_hash,
+                ":platform_coin": transfer.platform_coin,
+                ":is_final": transfer.status.is_final(),
+                ":details_json": details_json,
+            };
+            conn.execute_named(INSERT_OR_REPLACE_TRANSFER, &params.as_sql_named_params())
+                .map(|_| ())
+                .map_to_mm(IBCTransferStorageError::from)
+        })
+        .await
+    }
+
+    async fn load_transfer(&self, tx_hash: &str) -> IBCTransferStorageResult<Option<IBCTransferRecord>> {
+        let tx_hash = tx_hash.to_owned();
+        let selfi = self.clone();
+        async_blocking(move || {
+            let conn_shared = selfi.get_shared_conn()?;
+            let conn = Self::lock_conn_mutex(&conn_shared)?;
+
+            let params = owned_named_params! {
+                ":tx_hash": tx_hash,
+            };
+            let maybe_json = query_single_row_with_named_params(
+                &conn,
+                SELECT_TRANSFER,
+                &params.as_sql_named_params(),
+                transfer_from_row,
+            )?;
+            maybe_json.as_deref().map(parse_transfer).transpose()
+        })
+        .await
+    }
+
+    async fn load_unfinished_transfers(&self, platform_coin: &str) -> IBCTransferStorageResult<Vec<IBCTransferRecord>> {
+        let platform_coin = platform_coin.to_owned();
+        let selfi = self.clone();
+        async_blocking(move || {
+            let conn_shared = selfi.get_shared_conn()?;
+            let conn = Self::lock_conn_mutex(&conn_shared)?;
+
+            let mut statement = conn.prepare(SELECT_UNFINISHED_TRANSFERS)?;
+
+            let params = owned_named_params! {
+                ":platform_coin": platform_coin,
+            };
+            let rows = statement
+                .query_map_named(&params.as_sql_named_params(), transfer_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.iter().map(|details_json| parse_transfer(details_json)).collect()
+        })
+        .await
+    }
+}
+
+impl IBCTransferSqliteStorage {
+    fn get_shared_conn(&self) -> IBCTransferStorageResult<SqliteConnShared> {
+        self.conn.upgrade().or_mm_err(|| {
+            IBCTransferStorageError::Internal("'IBCTransferSqliteStorage::conn' doesn't exist".to_owned())
+        })
+    }
+
+    fn lock_conn_mutex(conn: &SqliteConnShared) -> IBCTransferStorageResult<MutexGuard<Connection>> {
+        conn.lock()
+            .map_to_mm(|e| IBCTransferStorageError::Internal(format!("Error locking sqlite connection: {}", e)))
+    }
+
+    async fn init_tables(&self) -> IBCTransferStorageResult<()> {
+        let conn_shared = self.get_shared_conn()?;
+        let conn = Self::lock_conn_mutex(&conn_shared)?;
+        conn.execute(CREATE_IBC_TRANSFER_TABLE, [])
+            .map(|_| ())
+            .map_to_mm(IBCTransferStorageError::from)
+    }
+}
@@ -0,0 +1,177 @@
+use super::{IBCTransferRecord, IBCTransferStorageError, IBCTransferStorageOps, IBCTransferStorageResult};
+use crate::CoinsContext;
+use async_trait::async_trait;
+use mm2_core::mm_ctx::MmArc;
+use mm2_db::indexed_db::{DbIdentifier, DbInstance, DbLocked, DbTransactionError, DbUpgrader, IndexedDb,
+                         IndexedDbBuilder, InitDbError, InitDbResult, MultiIndex, OnUpgradeResult, SharedDb,
+                         TableSignature, WeakDb};
+use mm2_err_handle::prelude::*;
+
+const DB_VERSION: u32 = 1;
+/// A **unique** index of the `IBCTransferTable` table.
+const TX_HASH_INDEX: &str = "tx_hash";
+/// An index of the `IBCTransferTable` table that consists of the following properties:
+/// * platform_coin - the ticker of the platform coin which tracks the transfer
+/// * is_final - whether the transfer status is final, `0` or `1`
+const PLATFORM_COIN_IS_FINAL_INDEX: &str = "platform_coin_is_final";
+
+pub type IBCTransferDbLocked<'a> = DbLocked<'a, IBCTransferDb>;
+
+impl From<DbTransactionError> for IBCTransferStorageError {
+    fn from(e: DbTransactionError) -> Self {
+        let desc = e.to_string();
+        match e {
+            DbTransactionError::NoSuchTable { .. }
+            | DbTransactionError::ErrorCreatingTransaction(_)
+            | DbTransactionError::ErrorOpeningTable { .. }
+            | DbTransactionError::ErrorSerializingIndex { .. }
+            | DbTransactionError::MultipleItemsByUniqueIndex { .. }
+            | DbTransactionError::NoSuchIndex { .. }
+            | DbTransactionError::InvalidIndex { .. }
+            | DbTransactionError::UnexpectedState(_)
+            | DbTransactionError::TransactionAborted => IBCTransferStorageError::Internal(desc),
+            DbTransactionError::ErrorDeserializingItem(_) => IBCTransferStorageError::ErrorDeserializing(desc),
+            DbTransactionError::ErrorSerializingItem(_) => IBCTransferStorageError::ErrorSerializing(desc),
+            DbTransactionError::ErrorGettingItems(_) | DbTransactionError::ErrorCountingItems(_) => {
+                IBCTransferStorageError::ErrorLoading(desc)
+            },
+            DbTransactionError::ErrorUploadingItem(_) | DbTransactionError::ErrorDeletingItems(_) => {
+                IBCTransferStorageError::ErrorSaving(desc)
+            },
+        }
+    }
+}
+
+impl From<InitDbError> for IBCTransferStorageError {
+    fn from(e: InitDbError) -> Self { IBCTransferStorageError::Internal(e.to_string()) }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct IBCTransferTable {
+    /// [`IBCTransferRecord::tx_hash`].
+    tx_hash: String,
+    /// [`IBCTransferRecord::platform_coin`].
+    platform_coin: String,
+    /// Whether [`IBCTransferRecord::status`] is final.
+    /// Stored as an integer since booleans can't be used as IndexedDB keys.
+    is_final: u8,
+    details: IBCTransferRecord,
+}
+
+impl TableSignature for IBCTransferTable {
+    fn table_name() -> &'static str { "ibc_transfer" }
+
+    fn on_upgrade_needed(upgrader: &DbUpgrader, old_version: u32, new_version: u32) -> OnUpgradeResult<()> {
+        if let (0, 1) = (old_version, new_version) {
+            let table = upgrader.create_table(Self::table_name())?;
+            table.create_index(TX_HASH_INDEX, true)?;
+            table.create_multi_index(PLATFORM_COIN_IS_FINAL_INDEX, &["platform_coin", "is_final"], false)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<IBCTransferRecord> for IBCTransferTable {
+    fn from(details: IBCTransferRecord) -> Self {
+        IBCTransferTable {
+            tx_hash: details.tx_hash.clone(),
+            platform_coin: details.platform_coin.clone(),
+            is_final: details.status.is_final() as u8,
+            details,
+        }
+    }
+}
+
+pub struct IBCTransferDb {
+    pub(crate) inner: IndexedDb,
+}
+
+#[async_trait]
+impl DbInstance for IBCTransferDb {
+    const DB_NAME: &'static str = "ibc_transfer";
+
+    async fn init(db_id: DbIdentifier) -> InitDbResult<Self> {
+        let inner = IndexedDbBuilder::new(db_id)
+            .with_version(DB_VERSION)
+            .with_table::<IBCTransferTable>()
+            .build()
+            .await?;
+        Ok(IBCTransferDb { inner })
+    }
+}
+
+/// The wrapper over the [`CoinsContext::ibc_transfer_db`] weak pointer.
+pub struct IBCTransferIndexedDbStorage {
+    db: WeakDb<IBCTransferDb>,
+}
+
+#[async_trait]
+impl IBCTransferStorageOps for IBCTransferIndexedDbStorage {
+    async fn init(ctx: &MmArc) -> IBCTransferStorageResult<Self>
+    where
+        Self: Sized,
+    {
+        let coins_ctx = CoinsContext::from_ctx(ctx).map_to_mm(IBCTransferStorageError::Internal)?;
+        let db = SharedDb::downgrade(&coins_ctx.ibc_transfer_db);
+        Ok(IBCTransferIndexedDbStorage { db })
+    }
+
+    async fn save_transfer(&self, transfer: IBCTransferRecord) -> IBCTransferStorageResult<()> {
+        let shared_db = self.get_shared_db()?;
+        let locked_db = Self::lock_db_mutex(&shared_db).await?;
+
+        let transaction = locked_db.inner.transaction().await?;
+        let table = transaction.table::<IBCTransferTable>().await?;
+
+        let tx_hash = transfer.tx_hash.clone();
+        table
+            .replace_item_by_unique_index(TX_HASH_INDEX, tx_hash, &IBCTransferTable::from(transfer))
+            .await
+            .map(|_| ())
+            .mm_err(IBCTransferStorageError::from)
+    }
+
+    async fn load_transfer(&self, tx_hash: &str) -> IBCTransferStorageResult<Option<IBCTransferRecord>> {
+        let shared_db = self.get_shared_db()?;
+        let locked_db = Self::lock_db_mutex(&shared_db).await?;
+
+        let transaction = locked_db.inner.transaction().await?;
+        let table = transaction.table::<IBCTransferTable>().await?;
+
+        Ok(table
+            .get_item_by_unique_index(TX_HASH_INDEX, tx_hash)
+            .await?
+            .map(|(_item_id, item)| item.details))
+    }
+
+    async fn load_unfinished_transfers(&self, platform_coin: &str) -> IBCTransferStorageResult<Vec<IBCTransferRecord>> {
+        let shared_db = self.get_shared_db()?;
+        let locked_db = Self::lock_db_mutex(&shared_db).await?;
+
+        let transaction = locked_db.inner.transaction().await?;
+        let table = transaction.table::<IBCTransferTable>().await?;
+
+        let index_keys = MultiIndex::new(PLATFORM_COIN_IS_FINAL_INDEX)
+            .with_value(platform_coin)?
+            .with_value(0u8)?;
+        Ok(table
+            .get_items_by_multi_index(index_keys)
+            .await?
+            .into_iter()
+            .map(|(_item_id, item)| item.details)
+            .collect())
+    }
+}
+
+impl IBCTransferIndexedDbStorage {
+    fn get_shared_db(&self) -> IBCTransferStorageResult<SharedDb<IBCTransferDb>> {
+        self.db.upgrade().or_mm_err(|| {
+            IBCTransferStorageError::Internal("'IBCTransferIndexedDbStorage::db' doesn't exist".to_owned())
+        })
+    }
+
+    async fn lock_db_mutex(db: &SharedDb<IBCTransferDb>) -> IBCTransferStorageResult<IBCTransferDbLocked<'_>> {
+        db.get_or_initialize().await.mm_err(IBCTransferStorageError::from)
+    }
+}
@@ -0,0 +1,669 @@
+//! Tracks the outgoing ICS-20 transfers until their packets are either acknowledged or timed out.
+//!
+//! Each transfer is saved as [`IBCTransferStatus::Pending`] once it's signed by `ibc_withdraw`.
+//! Then the tracker of the platform coin polls:
+//! * the source chain for the `send_packet` event of the transfer tx to learn the packet sequence;
+//! * the counterparty chain (if its coin is activated) for the `recv_packet` event;
+//! * the source chain for the `acknowledge_packet` or `timeout_packet` events that finalize the transfer.
+//!
+//! A sent packet which is neither received nor acknowledged until [`IBC_TRANSFER_TIMEOUT_GRACE_PERIOD`]
+//! after its timeout is considered as timed out even if no relayer has submitted the timeout to the source chain.
+//!
+//! Every status change is broadcasted as the [`IBC_TRANSFER_STATUS_EVENT`] event if it's enabled,
+//! and the final status is written into the tx history.
+
+use super::ibc_proto::IBCTransferV1Proto;
+use super::transfer_storage::{IBCTransferRecord, IBCTransferStatus, IBCTransferStorage, IBCTransferStorageError,
+                              IBCTransferStorageOps, IBCTransferStorageResult};
+use crate::my_tx_history_v2::{CoinWithTxHistoryV2, TxHistoryStorage};
+use crate::tendermint::rpc::*;
+use crate::tendermint::tendermint_tx_history_v2::transfer_internal_id;
+use crate::tendermint::type_urls::IBC_TRANSFER_TYPE_URL;
+use crate::tendermint::{TendermintCoin, TendermintCoinRpcError, TendermintCommons};
+use crate::tx_history_storage::TxHistoryStorageBuilder;
+use crate::utxo::utxo_common::big_decimal_from_sat_unsigned;
+use crate::{CoinsContext, HistorySyncState, MarketCoinOps, MmCoin, MmCoinEnum, TransactionDetails, TransactionType};
+use common::executor::{AbortSettings, SpawnAbortable, Timer};
+use common::{log, now_sec};
+use cosmrs::tendermint::abci::{Code as TxCode, Event};
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_event_stream::Event as StreamEvent;
+#[cfg(test)] use mocktopus::macros::*;
+use prost::Message;
+
+/// The name of the event stream notification that is broadcasted on every IBC transfer status change.
+pub const IBC_TRANSFER_STATUS_EVENT: &str = "IBC_TRANSFER_STATUS";
+
+/// As seconds
+const IBC_TRANSFER_TRACKING_INTERVAL: f64 = 30.;
+/// As seconds. The time that relayers have to submit the timeout of the packet to the source chain.
+const IBC_TRANSFER_TIMEOUT_GRACE_PERIOD: u64 = 3600;
+
+const SEND_PACKET_EVENT: &str = "send_packet";
+const RECV_PACKET_EVENT: &str = "recv_packet";
+const ACKNOWLEDGE_PACKET_EVENT: &str = "acknowledge_packet";
+const TIMEOUT_PACKET_EVENT: &str = "timeout_packet";
+const FUNGIBLE_TOKEN_PACKET_EVENT: &str = "fungible_token_packet";
+
+const PACKET_SEQUENCE_ATTR: &str = "packet_sequence";
+const PACKET_SRC_CHANNEL_ATTR: &str = "packet_src_channel";
+const PACKET_DST_PORT_ATTR: &str = "packet_dst_port";
+const PACKET_DST_CHANNEL_ATTR: &str = "packet_dst_channel";
+const ERROR_ATTR: &str = "error";
+
+/// A successfully committed tx found by the `tx_search` query.
+struct FoundTx {
+    hash: String,
+    events: Vec<Event>,
+}
+
+/// The result of the `tx_search` query for the transfer tx itself.
+enum TransferTxSearchResult {
+    Committed(FoundTx),
+    Failed { log: String },
+    NotFound,
+}
+
+/// Whether `grace_period` seconds have passed since the packet timeout.
+/// The transfers without the timestamp timeout (`timeout_timestamp == 0`) never time out by time.
+fn is_timeout_passed(transfer: &IBCTransferRecord, grace_period: u64) -> bool {
+    if transfer.timeout_timestamp == 0 {
+        return false;
+    }
+    let now_nanos = now_sec().saturating_sub(grace_period).saturating_mul(1_000_000_000);
+    now_nanos >= transfer.timeout_timestamp
+}
+
+fn event_attribute(events: &[Event], event_type: &str, key: &str) -> Option<String> {
+    events
+        .iter()
+        .filter(|event| event.type_str == event_type)
+        .flat_map(|event| event.attributes.iter())
+        .find(|tag| tag.key.to_string() == key)
+        .map(|tag| tag.value.to_string())
+}
+
+impl TendermintCoin {
+    /// Saves the signed `MsgTransfer` tx of the given coin or token to be tracked by this platform coin.
+    pub(crate) async fn save_ibc_transfer(
+        &self,
+        ticker: &str,
+        decimals: u8,
+        tx: &TransactionDetails,
+    ) -> IBCTransferStorageResult<()> {
+        let ctx =
+            MmArc::from_weak(&self.ctx).or_mm_err(|| IBCTransferStorageError::Internal("No context".to_owned()))?;
+
+        let tx_body = cosmrs::Tx::from_bytes(&tx.tx_hex)
+            .map_to_mm(|e| IBCTransferStorageError::Internal(e.to_string()))?
+            .body;
+        let msg = tx_body
+            .messages
+            .first()
+            .filter(|msg| msg.type_url == IBC_TRANSFER_TYPE_URL)
+            .or_mm_err(|| IBCTransferStorageError::Internal("Tx doesn't contain 'MsgTransfer'".to_owned()))?;
+        let msg_transfer = IBCTransferV1Proto::decode(msg.value.as_slice())
+            .map_to_mm(|e| IBCTransferStorageError::Internal(e.to_string()))?;
+        let token = msg_transfer
+            .token
+            .or_mm_err(|| IBCTransferStorageError::Internal("'MsgTransfer::token' can't be empty".to_owned()))?;
+        let amount: u64 = token
+            .amount
+            .parse()
+            .map_to_mm(|e| IBCTransferStorageError::Internal(format!("Invalid transfer amount: {}", e)))?;
+
+        let created_at = now_sec();
+        let transfer = IBCTransferRecord {
+            tx_hash: tx.tx_hash.clone(),
+            coin: ticker.to_owned(),
+            platform_coin: self.ticker().to_owned(),
+            sender: msg_transfer.sender,
+            receiver: msg_transfer.receiver,
+            denom: token.denom,
+            amount: big_decimal_from_sat_unsigned(amount, decimals),
+            source_port: msg_transfer.source_port,
+            source_channel: msg_transfer.source_channel,
+            destination_port: None,
+            destination_channel: None,
+            sequence: None,
+            timeout_timestamp: msg_transfer.timeout_timestamp,
+            status: IBCTransferStatus::Pending,
+            recv_tx_hash: None,
+            finalize_tx_hash: None,
+            error: None,
+            created_at,
+            updated_at: created_at,
+        };
+
+        IBCTransferStorage::init(&ctx).await?.save_transfer(transfer).await
+    }
+
+    pub(crate) async fn ibc_transfer(&self, tx_hash: &str) -> IBCTransferStorageResult<Option<IBCTransferRecord>> {
+        let ctx =
+            MmArc::from_weak(&self.ctx).or_mm_err(|| IBCTransferStorageError::Internal("No context".to_owned()))?;
+        IBCTransferStorage::init(&ctx).await?.load_transfer(tx_hash).await
+    }
+
+    /// Spawns the loop tracking the unfinished IBC transfers of this coin and its tokens.
+    /// The loop is aborted on the coin deactivation.
+    pub fn spawn_ibc_transfer_tracker(&self) {
+        let fut = self.clone().ibc_transfer_tracking_loop();
+        let settings = AbortSettings::info_on_abort(format!("IBC transfer tracking stopped for {}", self.ticker()));
+        self.spawner().spawn_with_settings(fut, settings);
+    }
+
+    async fn ibc_transfer_tracking_loop(self) {
+        let ctx = match MmArc::from_weak(&self.ctx) {
+            Some(ctx) => ctx,
+            None => return,
+        };
+
+        let storage = match IBCTransferStorage::init(&ctx).await {
+            Ok(storage) => storage,
+            Err(e) => {
+                log::error!("Error initializing IBC transfer storage for {}: {}", self.ticker(), e);
+                return;
+            },
+        };
+
+        loop {
+            let transfers = match storage.load_unfinished_transfers(self.ticker()).await {
+                Ok(transfers) => transfers,
+                Err(e) => {
+                    log::error!("Error loading unfinished IBC transfers of {}: {}", self.ticker(), e);
+                    Vec::new()
+                },
+            };
+
+            for transfer in transfers {
+                let tx_hash = transfer.tx_hash.clone();
+                let updated = match self.poll_ibc_transfer(&ctx, transfer).await {
+                    Ok(Some(updated)) => updated,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        log::warn!("Error polling IBC transfer {} of {}: {}", tx_hash, self.ticker(), e);
+                        continue;
+                    },
+                };
+
+                if let Err(e) = storage.save_transfer(updated.clone()).await {
+                    log::error!("Error saving IBC transfer {}: {}", tx_hash, e);
+                    continue;
+                }
+
+                self.notify_ibc_transfer_status(&ctx, &updated).await;
+                if updated.status.is_final() {
+                    self.update_ibc_transfer_in_history(&ctx, &updated).await;
+                }
+            }
+
+            Timer::sleep(IBC_TRANSFER_TRACKING_INTERVAL).await;
+        }
+    }
+
+    /// Returns the updated transfer if its status has changed.
+    async fn poll_ibc_transfer(
+        &self,
+        ctx: &MmArc,
+        mut transfer: IBCTransferRecord,
+    ) -> MmResult<Option<IBCTransferRecord>, TendermintCoinRpcError> {
+        match transfer.sequence {
+            None => match self.search_transfer_tx(&transfer.tx_hash).await? {
+                TransferTxSearchResult::Committed(tx) => {
+                    let sequence = event_attribute(&tx.events, SEND_PACKET_EVENT, PACKET_SEQUENCE_ATTR)
+                        .and_then(|sequence| sequence.parse().ok())
+                        .or_mm_err(|| {
+                            TendermintCoinRpcError::InvalidResponse(format!(
+                                "No '{}' event found in tx {}",
+                                SEND_PACKET_EVENT, tx.hash
+                            ))
+                        })?;
+                    transfer.sequence = Some(sequence);
+                    transfer.destination_port = event_attribute(&tx.events, SEND_PACKET_EVENT, PACKET_DST_PORT_ATTR);
+                    transfer.destination_channel =
+                        event_attribute(&tx.events, SEND_PACKET_EVENT, PACKET_DST_CHANNEL_ATTR);
+                    transfer.status = IBCTransferStatus::Sent;
+                },
+                TransferTxSearchResult::Failed { log } => {
+                    transfer.error = Some(log);
+                    transfer.status = IBCTransferStatus::Failed;
+                },
+                TransferTxSearchResult::NotFound => {
+                    if !is_timeout_passed(&transfer, 0) {
+                        return Ok(None);
+                    }
+                    transfer.status = IBCTransferStatus::Expired;
+                },
+            },
+            Some(sequence) => {
+                if let Some(ack_tx) = self
+                    .search_packet_tx(
+                        ACKNOWLEDGE_PACKET_EVENT,
+                        (PACKET_SRC_CHANNEL_ATTR, &transfer.source_channel),
+                        sequence,
+                    )
+                    .await?
+                {
+                    transfer.error = event_attribute(&ack_tx.events, FUNGIBLE_TOKEN_PACKET_EVENT, ERROR_ATTR);
+                    transfer.status = if transfer.error.is_some() {
+                        IBCTransferStatus::Failed
+                    } else {
+                        IBCTransferStatus::Acknowledged
+                    };
+                    transfer.finalize_tx_hash = Some(ack_tx.hash);
+                } else if let Some(timeout_tx) = self
+                    .search_packet_tx(
+                        TIMEOUT_PACKET_EVENT,
+                        (PACKET_SRC_CHANNEL_ATTR, &transfer.source_channel),
+                        sequence,
+                    )
+                    .await?
+                {
+                    transfer.status = IBCTransferStatus::TimedOut;
+                    transfer.finalize_tx_hash = Some(timeout_tx.hash);
+                } else if transfer.status == IBCTransferStatus::Sent {
+                    match self.search_recv_packet_tx(ctx, &transfer, sequence).await {
+                        Ok(Some(recv_tx)) => {
+                            transfer.status = IBCTransferStatus::Received;
+                            transfer.recv_tx_hash = Some(recv_tx.hash);
+                        },
+                        // The packet can't be received after the timeout, so it's timed out even if the timeout
+                        // hasn't been relayed to the source chain within the grace period.
+                        Ok(None) if is_timeout_passed(&transfer, IBC_TRANSFER_TIMEOUT_GRACE_PERIOD) => {
+                            transfer.status = IBCTransferStatus::TimedOut;
+                        },
+                        Ok(None) => return Ok(None),
+                        // The counterparty chain is optional, so its errors don't interrupt the tracking.
+                        Err(e) => {
+                            log::debug!("Error searching for the received IBC packet: {}", e);
+                            return Ok(None);
+                        },
+                    }
+                } else {
+                    return Ok(None);
+                }
+            },
+        }
+
+        transfer.updated_at = now_sec();
+        Ok(Some(transfer))
+    }
+}
+
+#[cfg_attr(test, mockable)]
+impl TendermintCoin {
+    async fn search_transfer_tx(&self, tx_hash: &str) -> MmResult<TransferTxSearchResult, TendermintCoinRpcError> {
+        let query = format!("tx.hash='{}'", tx_hash);
+        let response = self
+            .rpc_client()
+            .await?
+            .perform(TxSearchRequest::new(
+                query,
+                false,
+                1,
+                1,
+                TendermintResultOrder::Ascending.into(),
+            ))
+            .await?;
+
+        Ok(match response.txs.into_iter().next() {
+            Some(tx) if tx.tx_result.code == TxCode::Ok => TransferTxSearchResult::Committed(FoundTx {
+                hash: tx.hash.to_string(),
+                events: tx.tx_result.events,
+            }),
+            Some(tx) => TransferTxSearchResult::Failed {
+                log: tx.tx_result.log.to_string(),
+            },
+            None => TransferTxSearchResult::NotFound,
+        })
+    }
+
+    /// Searches for a successful tx of this chain that has emitted the given packet event.
+    async fn search_packet_tx(
+        &self,
+        event_type: &str,
+        channel_attr: (&str, &str),
+        sequence: u64,
+    ) -> MmResult<Option<FoundTx>, TendermintCoinRpcError> {
+        let (channel_key, channel) = channel_attr;
+        let query =
+            format!("{event_type}.{channel_key}='{channel}' AND {event_type}.{PACKET_SEQUENCE_ATTR}='{sequence}'");
+        let response = self
+            .rpc_client()
+            .await?
+            .perform(TxSearchRequest::new(
+                query,
+                false,
+                1,
+                1,
+                TendermintResultOrder::Ascending.into(),
+            ))
+            .await?;
+
+        Ok(response
+            .txs
+            .into_iter()
+            .find(|tx| tx.tx_result.code == TxCode::Ok)
+            .map(|tx| FoundTx {
+                hash: tx.hash.to_string(),
+                events: tx.tx_result.events,
+            }))
+    }
+
+    /// Searches for the `recv_packet` event on the counterparty chain,
+    /// which is identified by the receiver's account prefix among the activated Tendermint coins.
+    async fn search_recv_packet_tx(
+        &self,
+        ctx: &MmArc,
+        transfer: &IBCTransferRecord,
+        sequence: u64,
+    ) -> MmResult<Option<FoundTx>, TendermintCoinRpcError> {
+        let destination_channel = match &transfer.destination_channel {
+            Some(channel) => channel,
+            None => return Ok(None),
+        };
+        let receiver_prefix = match transfer.receiver.split_once('1') {
+            Some((prefix, _)) => prefix,
+            None => return Ok(None),
+        };
+
+        let coins_ctx = CoinsContext::from_ctx(ctx).map_to_mm(TendermintCoinRpcError::InternalError)?;
+        let counterparty = coins_ctx
+            .coins
+            .lock()
+            .await
+            .values()
+            .find_map(|coin| match &coin.inner {
+                MmCoinEnum::Tendermint(coin) if coin.account_prefix == receiver_prefix => Some(coin.clone()),
+                _ => None,
+            });
+
+        match counterparty {
+            Some(counterparty) => {
+                counterparty
+                    .search_packet_tx(
+                        RECV_PACKET_EVENT,
+                        (PACKET_DST_CHANNEL_ATTR, destination_channel),
+                        sequence,
+                    )
+                    .await
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl TendermintCoin {
+    async fn notify_ibc_transfer_status(&self, ctx: &MmArc, transfer: &IBCTransferRecord) {
+        let is_event_active = ctx
+            .event_stream_configuration
+            .as_ref()
+            .map_or(false, |config| config.get_event(IBC_TRANSFER_STATUS_EVENT).is_some());
+        if !is_event_active {
+            return;
+        }
+
+        let payload = json!(transfer);
+        ctx.stream_channel_controller
+            .broadcast(StreamEvent::new(
+                IBC_TRANSFER_STATUS_EVENT.to_string(),
+                payload.to_string(),
+            ))
+            .await;
+    }
+
+    /// Writes the final status into the tx history entries of the transfer tx if the tx history is enabled.
+    ///
+    /// The tx history keeps every transfer event of the tx as a separate entry, see [`transfer_internal_id`].
+    /// The entries of the transferred amounts are not necessarily the first ones, e.g. the token transfers
+    /// or the txs with several messages, so all the `TendermintIBCTransfer` entries of the tx are updated.
+    async fn update_ibc_transfer_in_history(&self, ctx: &MmArc, transfer: &IBCTransferRecord) {
+        if matches!(*self.history_sync_state.lock().unwrap(), HistorySyncState::NotEnabled) {
+            return;
+        }
+
+        // The number of the tx events limits the number of its transfer entries.
+        let events_count = match self.search_transfer_tx(&transfer.tx_hash).await {
+            Ok(TransferTxSearchResult::Committed(tx)) => tx.events.len(),
+            // The failed and not broadcasted txs are not added to the history.
+            Ok(_) => return,
+            Err(e) => {
+                log::error!("Error searching for tx {}: {}", transfer.tx_hash, e);
+                return;
+            },
+        };
+
+        let storage = match TxHistoryStorageBuilder::new(ctx).build() {
+            Ok(storage) => storage,
+            Err(e) => {
+                log::error!("Error creating tx history storage: {}", e);
+                return;
+            },
+        };
+
+        let wallet_id = self.history_wallet_id();
+        for index in 0..events_count {
+            let internal_id = transfer_internal_id(&transfer.tx_hash, index);
+            let mut details = match storage.get_tx_from_history(&wallet_id, &internal_id).await {
+                Ok(Some(details)) => details,
+                // The history entries will be created with the actual status once the tx is fetched.
+                Ok(None) => continue,
+                Err(e) => {
+                    log::error!("Error loading tx {} from history: {:?}", transfer.tx_hash, e);
+                    return;
+                },
+            };
+
+            if let TransactionType::TendermintIBCTransfer { status, .. } = &mut details.transaction_type {
+                *status = transfer.status;
+                if let Err(e) = storage.update_tx_in_history(&wallet_id, &details).await {
+                    log::error!("Error updating tx {} in history: {:?}", transfer.tx_hash, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::tendermint::tendermint_coin_tests::iris_coin_for_test;
+    use common::block_on;
+    use cosmrs::tendermint::abci::tag::{Key, Tag, Value};
+    use mm2_core::mm_ctx::MmCtxBuilder;
+    use mocktopus::mocking::*;
+    use std::str::FromStr;
+
+    const SEQUENCE: u64 = 42;
+
+    fn event(type_str: &str, attributes: &[(&str, &str)]) -> Event {
+        Event {
+            type_str: type_str.to_owned(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| Tag {
+                    key: Key::from_str(key).unwrap(),
+                    value: Value::from_str(value).unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    fn found_tx(hash: &str, events: Vec<Event>) -> FoundTx {
+        FoundTx {
+            hash: hash.to_owned(),
+            events,
+        }
+    }
+
+    fn transfer_for_test(status: IBCTransferStatus, timeout_timestamp: u64) -> IBCTransferRecord {
+        let sequence = match status {
+            IBCTransferStatus::Pending => None,
+            _ => Some(SEQUENCE),
+        };
+        IBCTransferRecord {
+            tx_hash: "AA".to_owned(),
+            coin: "IRIS".to_owned(),
+            platform_coin: "IRIS".to_owned(),
+            sender: "iaa1sender".to_owned(),
+            receiver: "cosmos1receiver".to_owned(),
+            denom: "unyan".to_owned(),
+            amount: "0.1".parse().unwrap(),
+            source_port: "transfer".to_owned(),
+            source_channel: "channel-0".to_owned(),
+            destination_port: sequence.map(|_| "transfer".to_owned()),
+            destination_channel: sequence.map(|_| "channel-1".to_owned()),
+            sequence,
+            timeout_timestamp,
+            status,
+            recv_tx_hash: None,
+            finalize_tx_hash: None,
+            error: None,
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_000,
+        }
+    }
+
+    /// The packet timeout which is not reached yet.
+    fn future_timeout() -> u64 { (now_sec() + 600) * 1_000_000_000 }
+
+    /// The packet timeout which has passed more than the grace period ago.
+    fn expired_timeout() -> u64 { (now_sec() - IBC_TRANSFER_TIMEOUT_GRACE_PERIOD - 600) * 1_000_000_000 }
+
+    /// Mocks the packet searches, `found` returns the tx of the given packet event type if it's found.
+    fn mock_packet_search(found: fn(&str) -> Option<FoundTx>) {
+        TendermintCoin::search_packet_tx.mock_safe(move |_, event_type, _, sequence| {
+            assert_eq!(sequence, SEQUENCE);
+            let tx = found(event_type);
+            MockResult::Return(Box::pin(async move { Ok(tx) }))
+        });
+    }
+
+    fn mock_recv_packet_search(found: Option<&'static str>) {
+        TendermintCoin::search_recv_packet_tx.mock_safe(move |_, _, _, sequence| {
+            assert_eq!(sequence, SEQUENCE);
+            let tx = found.map(|hash| found_tx(hash, vec![]));
+            MockResult::Return(Box::pin(async move { Ok(tx) }))
+        });
+    }
+
+    fn poll(transfer: IBCTransferRecord) -> Option<IBCTransferRecord> {
+        let ctx = MmCtxBuilder::default().into_mm_arc();
+        let coin = iris_coin_for_test();
+        block_on(coin.poll_ibc_transfer(&ctx, transfer)).unwrap()
+    }
+
+    #[test]
+    fn test_pending_to_sent() {
+        TendermintCoin::search_transfer_tx.mock_safe(|_, _| {
+            let tx = found_tx("AA", vec![event(SEND_PACKET_EVENT, &[
+                (PACKET_SEQUENCE_ATTR, "42"),
+                (PACKET_DST_PORT_ATTR, "transfer"),
+                (PACKET_DST_CHANNEL_ATTR, "channel-1"),
+            ])]);
+            MockResult::Return(Box::pin(async move { Ok(TransferTxSearchResult::Committed(tx)) }))
+        });
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Pending, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Sent);
+        assert_eq!(updated.sequence, Some(SEQUENCE));
+        assert_eq!(updated.destination_port.as_deref(), Some("transfer"));
+        assert_eq!(updated.destination_channel.as_deref(), Some("channel-1"));
+    }
+
+    #[test]
+    fn test_pending_to_failed() {
+        TendermintCoin::search_transfer_tx.mock_safe(|_, _| {
+            let result = TransferTxSearchResult::Failed {
+                log: "insufficient funds".to_owned(),
+            };
+            MockResult::Return(Box::pin(async move { Ok(result) }))
+        });
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Pending, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Failed);
+        assert_eq!(updated.error.as_deref(), Some("insufficient funds"));
+    }
+
+    #[test]
+    fn test_pending_not_found() {
+        TendermintCoin::search_transfer_tx
+            .mock_safe(|_, _| MockResult::Return(Box::pin(async { Ok(TransferTxSearchResult::NotFound) })));
+
+        // the tx can be still broadcasted until the packet timeout
+        assert_eq!(
+            poll(transfer_for_test(IBCTransferStatus::Pending, future_timeout())),
+            None
+        );
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Pending, expired_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Expired);
+    }
+
+    #[test]
+    fn test_sent_to_acknowledged() {
+        mock_packet_search(|event_type| (event_type == ACKNOWLEDGE_PACKET_EVENT).then(|| found_tx("ACK", vec![])));
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Sent, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Acknowledged);
+        assert_eq!(updated.finalize_tx_hash.as_deref(), Some("ACK"));
+        assert_eq!(updated.error, None);
+    }
+
+    #[test]
+    fn test_received_to_error_acknowledgement() {
+        mock_packet_search(|event_type| {
+            (event_type == ACKNOWLEDGE_PACKET_EVENT).then(|| {
+                found_tx("ACK", vec![event(FUNGIBLE_TOKEN_PACKET_EVENT, &[(
+                    ERROR_ATTR,
+                    "invalid receiver",
+                )])])
+            })
+        });
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Received, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Failed);
+        assert_eq!(updated.finalize_tx_hash.as_deref(), Some("ACK"));
+        assert_eq!(updated.error.as_deref(), Some("invalid receiver"));
+    }
+
+    #[test]
+    fn test_sent_to_timed_out() {
+        mock_packet_search(|event_type| (event_type == TIMEOUT_PACKET_EVENT).then(|| found_tx("TIMEOUT", vec![])));
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Sent, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::TimedOut);
+        assert_eq!(updated.finalize_tx_hash.as_deref(), Some("TIMEOUT"));
+    }
+
+    #[test]
+    fn test_sent_to_received() {
+        mock_packet_search(|_| None);
+        mock_recv_packet_search(Some("RECV"));
+
+        let updated = poll(transfer_for_test(IBCTransferStatus::Sent, future_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::Received);
+        assert_eq!(updated.recv_tx_hash.as_deref(), Some("RECV"));
+
+        // the received packet waits for the acknowledgement
+        assert_eq!(poll(updated), None);
+    }
+
+    #[test]
+    fn test_sent_timeout_not_relayed() {
+        mock_packet_search(|_| None);
+        mock_recv_packet_search(None);
+
+        // the packet is waiting to be relayed
+        assert_eq!(poll(transfer_for_test(IBCTransferStatus::Sent, future_timeout())), None);
+
+        // neither the packet nor its timeout has been relayed within the grace period
+        let updated = poll(transfer_for_test(IBCTransferStatus::Sent, expired_timeout())).unwrap();
+        assert_eq!(updated.status, IBCTransferStatus::TimedOut);
+        assert_eq!(updated.finalize_tx_hash, None);
+
+        // the transfers without the timestamp timeout are tracked until the packet is finalized
+        assert_eq!(poll(transfer_for_test(IBCTransferStatus::Sent, 0)), None);
+    }
+}
@@ -2,15 +2,24 @@
 // Useful resources
 // https://docs.cosmos.network/
 
+mod cosmwasm;
 mod ibc;
 mod iris;
 mod rpc;
 mod tendermint_balance_events;
 mod tendermint_coin;
+mod tendermint_cosmwasm_htlc;
+mod tendermint_governance;
+mod tendermint_hd_wallet;
+mod tendermint_staking;
 mod tendermint_token;
 pub mod tendermint_tx_history_v2;
 
+#[cfg(target_arch = "wasm32")] pub use ibc::IBCTransferDb;
+pub use ibc::{IBCTransferRecord, IBCTransferStatus, IBCTransferStorageError, IBC_TRANSFER_STATUS_EVENT};
 pub use tendermint_coin::*;
+pub use tendermint_hd_wallet::*;
+pub use tendermint_staking::*;
 pub use tendermint_token::*;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -21,6 +30,8 @@ pub enum CustomTendermintMsgType {
     ClaimHtlcAmount,
     /// Claim HTLC for reciever
     SignClaimHtlc,
+    /// Vote on a governance proposal
+    GovernanceVote,
 }
 
 pub(crate) const TENDERMINT_COIN_PROTOCOL_TYPE: &str = "TENDERMINT";
@@ -31,4 +42,15 @@ pub(crate) mod type_urls {
 
     pub(crate) const CREATE_HTLC_TYPE_URL: &str = "/irismod.htlc.MsgCreateHTLC";
     pub(crate) const CLAIM_HTLC_TYPE_URL: &str = "/irismod.htlc.MsgClaimHTLC";
+
+    pub(crate) const EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+    pub(crate) const DELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegate";
+    pub(crate) const UNDELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgUndelegate";
+    pub(crate) const BEGIN_REDELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgBeginRedelegate";
+    pub(crate) const WITHDRAW_DELEGATOR_REWARD_TYPE_URL: &str =
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward";
+
+    pub(crate) const VOTE_TYPE_URL: &str = "/cosmos.gov.v1beta1.MsgVote";
+    pub(crate) const VOTE_WEIGHTED_TYPE_URL: &str = "/cosmos.gov.v1beta1.MsgVoteWeighted";
 }
@@ -5,6 +5,8 @@ use super::iris::htlc::{IrisHtlc, MsgClaimHtlc, MsgCreateHtlc, HTLC_STATE_COMPLE
                         HTLC_STATE_REFUNDED};
 use super::iris::htlc_proto::{CreateHtlcProtoRep, QueryHtlcRequestProto, QueryHtlcResponseProto};
 use super::rpc::*;
+use super::tendermint_cosmwasm_htlc::extract_cosmwasm_secret;
+use super::tendermint_hd_wallet::{init_hd_wallet, TendermintHDWallet};
 use crate::coin_errors::{MyAddressError, ValidatePaymentError};
 use crate::rpc_command::tendermint::{IBCChainRegistriesResponse, IBCChainRegistriesResult, IBCChainsRequestError,
                                      IBCTransferChannel, IBCTransferChannelTag, IBCTransferChannelsRequest,
@@ -15,20 +17,20 @@ use crate::tendermint::ibc::IBC_OUT_SOURCE_PORT;
 use crate::utxo::sat_from_big_decimal;
 use crate::utxo::utxo_common::big_decimal_from_sat;
 use crate::{big_decimal_from_sat_unsigned, BalanceError, BalanceFut, BigDecimal, CheckIfMyPaymentSentArgs,
-            CoinBalance, CoinFutSpawner, ConfirmPaymentInput, DexFee, FeeApproxStage, FoundSwapTxSpend,
-            HistorySyncState, MakerSwapTakerCoin, MarketCoinOps, MmCoin, MmCoinEnum, NegotiateSwapContractAddrErr,
-            PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr, PrivKeyBuildPolicy, PrivKeyPolicy,
-            PrivKeyPolicyNotAllowed, RawTransactionError, RawTransactionFut, RawTransactionRequest, RawTransactionRes,
-            RefundError, RefundPaymentArgs, RefundResult, RpcCommonOps, SearchForSwapTxSpendInput,
-            SendMakerPaymentSpendPreimageInput, SendPaymentArgs, SignatureError, SignatureResult, SpendPaymentArgs,
-            SwapOps, TakerSwapMakerCoin, TradeFee, TradePreimageError, TradePreimageFut, TradePreimageResult,
-            TradePreimageValue, TransactionDetails, TransactionEnum, TransactionErr, TransactionFut,
-            TransactionResult, TransactionType, TxFeeDetails, TxMarshalingErr, UnexpectedDerivationMethod,
-            ValidateAddressResult, ValidateFeeArgs, ValidateInstructionsErr, ValidateOtherPubKeyErr,
-            ValidatePaymentFut, ValidatePaymentInput, ValidateWatcherSpendInput, VerificationError,
-            VerificationResult, WaitForHTLCTxSpendArgs, WatcherOps, WatcherReward, WatcherRewardError,
-            WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput, WatcherValidateTakerFeeInput,
-            WithdrawError, WithdrawFee, WithdrawFrom, WithdrawFut, WithdrawRequest};
+            CoinBalance, CoinFutSpawner, ConfirmPaymentInput, DerivationMethod, DexFee, FeeApproxStage,
+            FoundSwapTxSpend, HistorySyncState, MakerSwapTakerCoin, MarketCoinOps, MmCoin, MmCoinEnum,
+            NegotiateSwapContractAddrErr, PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr,
+            PrivKeyBuildPolicy, PrivKeyPolicy, PrivKeyPolicyNotAllowed, RawTransactionError, RawTransactionFut,
+            RawTransactionRequest, RawTransactionRes, RefundError, RefundPaymentArgs, RefundResult, RpcCommonOps,
+            SearchForSwapTxSpendInput, SendMakerPaymentSpendPreimageInput, SendPaymentArgs, SignatureError,
+            SignatureResult, SpendPaymentArgs, SwapOps, TakerSwapMakerCoin, TradeFee, TradePreimageError,
+            TradePreimageFut, TradePreimageResult, TradePreimageValue, TransactionDetails, TransactionEnum,
+            TransactionErr, TransactionFut, TransactionResult, TransactionType, TxFeeDetails, TxMarshalingErr,
+            UnexpectedDerivationMethod, ValidateAddressResult, ValidateFeeArgs, ValidateInstructionsErr,
+            ValidateOtherPubKeyErr, ValidatePaymentFut, ValidatePaymentInput, ValidateWatcherSpendInput,
+            VerificationError, VerificationResult, WaitForHTLCTxSpendArgs, WatcherOps, WatcherReward,
+            WatcherRewardError, WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput,
+            WatcherValidateTakerFeeInput, WithdrawError, WithdrawFee, WithdrawFut, WithdrawRequest};
 use async_std::prelude::FutureExt as AsyncStdFutureExt;
 use async_trait::async_trait;
 use bitcrypto::{dhash160, sha256};
@@ -85,13 +87,13 @@ const ABCI_QUERY_ACCOUNT_PATH: &str = "/cosmos.auth.v1beta1.Query/Account";
 const ABCI_QUERY_BALANCE_PATH: &str = "/cosmos.bank.v1beta1.Query/Balance";
 const ABCI_GET_TX_PATH: &str = "/cosmos.tx.v1beta1.Service/GetTx";
 const ABCI_QUERY_HTLC_PATH: &str = "/irismod.htlc.Query/HTLC";
-const ABCI_GET_TXS_EVENT_PATH: &str = "/cosmos.tx.v1beta1.Service/GetTxsEvent";
+pub(super) const ABCI_GET_TXS_EVENT_PATH: &str = "/cosmos.tx.v1beta1.Service/GetTxsEvent";
 
 pub(crate) const MIN_TX_SATOSHIS: i64 = 1;
 
 // ABCI Request Defaults
-const ABCI_REQUEST_HEIGHT: Option<Height> = None;
-const ABCI_REQUEST_PROVE: bool = false;
+pub(super) const ABCI_REQUEST_HEIGHT: Option<Height> = None;
+pub(super) const ABCI_REQUEST_PROVE: bool = false;
 
 /// 0.25 is good average gas price on atom and iris
 const DEFAULT_GAS_PRICE: f64 = 0.25;
@@ -137,6 +139,19 @@ pub struct TendermintProtocolInfo {
     chain_id: String,
     gas_price: Option<f64>,
     chain_registry_name: Option<String>,
+    #[serde(default)]
+    htlc_backend: TendermintHtlcBackend,
+}
+
+/// The HTLC implementation that is used for atomic swaps on the chain.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", content = "params")]
+pub enum TendermintHtlcBackend {
+    /// The `irismod` HTLC module that is available on Iris network only.
+    #[default]
+    Iris,
+    /// A deployed instance of the `cw20-atomic-swap` CosmWasm contract.
+    CosmWasm { contract_address: String },
 }
 
 #[derive(Clone)]
@@ -226,6 +241,7 @@ pub struct TendermintCoinImpl {
     pub account_id: AccountId,
     pub(super) account_prefix: String,
     pub(super) priv_key_policy: TendermintPrivKeyPolicy,
+    pub(super) derivation_method: DerivationMethod<AccountId, TendermintHDWallet>,
     pub(crate) decimals: u8,
     pub(super) denom: Denom,
     chain_id: ChainId,
@@ -237,6 +253,8 @@ pub struct TendermintCoinImpl {
     pub(crate) history_sync_state: Mutex<HistorySyncState>,
     client: TendermintRpcClient,
     chain_registry_name: Option<String>,
+    /// The HTLC contract address if the swaps are performed by [`TendermintHtlcBackend::CosmWasm`].
+    pub(super) cosmwasm_htlc_contract: Option<AccountId>,
     pub(crate) ctx: MmWeak,
 }
 
@@ -281,6 +299,12 @@ pub enum TendermintInitErrorKind {
     #[display(fmt = "avg_blocktime must be in-between '0' and '255'.")]
     AvgBlockTimeInvalid,
     BalanceStreamInitError(String),
+    #[display(fmt = "Error initializing HD wallet storage: {}", _0)]
+    HDWalletStorageError(String),
+    #[display(fmt = "Error requesting HD wallet balance: {}", _0)]
+    HDWalletBalanceError(String),
+    #[display(fmt = "Invalid HTLC contract address: {}", _0)]
+    InvalidHtlcContractAddress(String),
 }
 
 #[derive(Display, Debug)]
@@ -398,12 +422,13 @@ pub struct AllBalancesResult {
 }
 
 #[derive(Debug, Display)]
-enum SearchForSwapTxSpendErr {
+pub(super) enum SearchForSwapTxSpendErr {
     Cosmrs(ErrorReport),
     Rpc(TendermintCoinRpcError),
     TxMessagesEmpty,
     ClaimHtlcTxNotFound,
     UnexpectedHtlcState(i32),
+    UnexpectedHtlcMsg(String),
     Proto(DecodeError),
 }
 
@@ -440,21 +465,7 @@ impl TendermintCommons for TendermintCoin {
             .account_balance_for_denom(&self.account_id, self.denom.to_string())
             .await?;
         let platform_balance = big_decimal_from_sat_unsigned(platform_balance_denom, self.decimals);
-        let ibc_assets_info = self.tokens_info.lock().clone();
-
-        let mut requests = Vec::new();
-        for (denom, info) in ibc_assets_info {
-            let fut = async move {
-                let balance_denom = self
-                    .account_balance_for_denom(&self.account_id, denom)
-                    .await
-                    .map_err(|e| e.into_inner())?;
-                let balance_decimal = big_decimal_from_sat_unsigned(balance_denom, info.decimals);
-                Ok::<_, TendermintCoinRpcError>((info.ticker, balance_decimal))
-            };
-            requests.push(fut);
-        }
-        let tokens_balances = try_join_all(requests).await?.into_iter().collect();
+        let tokens_balances = self.address_tokens_balances(&self.account_id).await?;
 
         Ok(AllBalancesResult {
             platform_balance,
@@ -515,12 +526,48 @@ impl TendermintCoin {
             kind: TendermintInitErrorKind::InvalidDenom(e.to_string()),
         })?;
 
+        let cosmwasm_htlc_contract = match protocol_info.htlc_backend {
+            TendermintHtlcBackend::Iris => None,
+            TendermintHtlcBackend::CosmWasm { contract_address } => {
+                let contract = AccountId::from_str(&contract_address).map_to_mm(|e| TendermintInitError {
+                    ticker: ticker.clone(),
+                    kind: TendermintInitErrorKind::InvalidHtlcContractAddress(e.to_string()),
+                })?;
+                if contract.prefix() != protocol_info.account_prefix {
+                    return MmError::err(TendermintInitError {
+                        ticker,
+                        kind: TendermintInitErrorKind::InvalidHtlcContractAddress(format!(
+                            "Expected '{}' address prefix, found '{}'",
+                            protocol_info.account_prefix,
+                            contract.prefix()
+                        )),
+                    });
+                }
+                Some(contract)
+            },
+        };
+
         let history_sync_state = if tx_history {
             HistorySyncState::NotStarted
         } else {
             HistorySyncState::NotEnabled
         };
 
+        let derivation_method = match priv_key_policy {
+            PrivKeyPolicy::HDWallet {
+                ref derivation_path, ..
+            } => {
+                let hd_wallet = init_hd_wallet(ctx, ticker.clone(), derivation_path.clone())
+                    .await
+                    .mm_err(|e| TendermintInitError {
+                        ticker: ticker.clone(),
+                        kind: TendermintInitErrorKind::HDWalletStorageError(e.to_string()),
+                    })?;
+                DerivationMethod::HDWallet(hd_wallet)
+            },
+            _ => DerivationMethod::SingleAddress(account_id.clone()),
+        };
+
         // Create an abortable system linked to the `MmCtx` so if the context is stopped via `MmArc::stop`,
         // all spawned futures related to `TendermintCoin` will be aborted as well.
         let abortable_system = ctx
@@ -536,6 +583,7 @@ impl TendermintCoin {
             account_id,
             account_prefix: protocol_info.account_prefix,
             priv_key_policy,
+            derivation_method,
             decimals: protocol_info.decimals,
             denom,
             chain_id,
@@ -546,33 +594,40 @@ impl TendermintCoin {
             history_sync_state: Mutex::new(history_sync_state),
             client: TendermintRpcClient(AsyncMutex::new(client_impl)),
             chain_registry_name: protocol_info.chain_registry_name,
+            cosmwasm_htlc_contract,
             ctx: ctx.weak(),
         })))
     }
 
+    /// Requests the balances of all activated IBC/native assets of the given `account_id`.
+    pub(super) async fn address_tokens_balances(
+        &self,
+        account_id: &AccountId,
+    ) -> MmResult<HashMap<String, BigDecimal>, TendermintCoinRpcError> {
+        let ibc_assets_info = self.tokens_info.lock().clone();
+
+        let mut requests = Vec::new();
+        for (denom, info) in ibc_assets_info {
+            let fut = async move {
+                let balance_denom = self
+                    .account_balance_for_denom(account_id, denom)
+                    .await
+                    .map_err(|e| e.into_inner())?;
+                let balance_decimal = big_decimal_from_sat_unsigned(balance_denom, info.decimals);
+                Ok::<_, TendermintCoinRpcError>((info.ticker, balance_decimal))
+            };
+            requests.push(fut);
+        }
+        Ok(try_join_all(requests).await?.into_iter().collect())
+    }
+
     pub fn ibc_withdraw(&self, req: IBCWithdrawRequest) -> WithdrawFut {
         let coin = self.clone();
         let fut = async move {
             let to_address =
                 AccountId::from_str(&req.to).map_to_mm(|e| WithdrawError::InvalidAddress(e.to_string()))?;
 
-            let (account_id, priv_key) = match req.from {
-                Some(WithdrawFrom::HDWalletAddress(ref path_to_address)) => {
-                    let priv_key = coin
-                        .priv_key_policy
-                        .hd_wallet_derived_priv_key_or_err(path_to_address)?;
-                    let account_id = account_id_from_privkey(priv_key.as_slice(), &coin.account_prefix)
-                        .map_err(|e| WithdrawError::InternalError(e.to_string()))?;
-                    (account_id, priv_key)
-                },
-                Some(WithdrawFrom::AddressId(_)) | Some(WithdrawFrom::DerivationPath { .. }) => {
-                    return MmError::err(WithdrawError::UnexpectedFromAddress(
-                        "Withdraw from 'AddressId' or 'DerivationPath' is not supported yet for Tendermint!"
-                            .to_string(),
-                    ))
-                },
-                None => (coin.account_id.clone(), *coin.priv_key_policy.activated_key_or_err()?),
-            };
+            let (account_id, priv_key) = coin.withdraw_sender(req.from.as_ref()).await?;
 
             let (balance_denom, balance_dec) = coin
                 .get_balance_as_unsigned_and_decimal(&account_id, &coin.denom, coin.decimals())
@@ -798,7 +853,7 @@ impl TendermintCoin {
     }
 
     #[inline(always)]
-    fn gas_price(&self) -> f64 { self.gas_price.unwrap_or(DEFAULT_GAS_PRICE) }
+    pub(super) fn gas_price(&self) -> f64 { self.gas_price.unwrap_or(DEFAULT_GAS_PRICE) }
 
     #[allow(unused)]
     async fn get_latest_block(&self) -> MmResult<GetLatestBlockResponse, TendermintCoinRpcError> {
@@ -863,7 +918,7 @@ impl TendermintCoin {
     /// Refs:
     ///  - Main algorithm: https://github.com/irisnet/irismod/blob/main/modules/htlc/types/htlc.go#L157
     ///  - Coins string building https://github.com/cosmos/cosmos-sdk/blob/main/types/coin.go#L210-L225
-    fn calculate_htlc_id(
+    pub(super) fn calculate_htlc_id(
         &self,
         from_address: &AccountId,
         to_address: &AccountId,
@@ -1198,6 +1253,17 @@ impl TendermintCoin {
         secret_hash: &[u8],
         amount: &BigDecimal,
     ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
+        if let Some(contract) = self.cosmwasm_htlc_contract() {
+            return self.check_if_my_cosmwasm_payment_sent_for_denom(
+                contract.clone(),
+                decimals,
+                denom,
+                other_pub,
+                secret_hash,
+                amount,
+            );
+        }
+
         let amount = try_fus!(sat_from_big_decimal(amount, decimals));
         let amount = vec![Coin {
             denom,
@@ -1264,15 +1330,29 @@ impl TendermintCoin {
         Box::new(fut.boxed().compat())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn send_htlc_for_denom(
         &self,
         time_lock_duration: u64,
+        time_lock: u64,
         other_pub: &[u8],
         secret_hash: &[u8],
         amount: BigDecimal,
         denom: Denom,
         decimals: u8,
     ) -> TransactionFut {
+        if let Some(contract) = self.cosmwasm_htlc_contract() {
+            return self.send_cosmwasm_htlc_for_denom(
+                contract.clone(),
+                time_lock,
+                other_pub,
+                secret_hash,
+                amount,
+                denom,
+                decimals,
+            );
+        }
+
         let pubkey_hash = dhash160(other_pub);
         let to = try_tx_fus!(AccountId::new(&self.account_prefix, pubkey_hash.as_slice()));
 
@@ -1476,6 +1556,10 @@ impl TendermintCoin {
         denom: Denom,
         decimals: u8,
     ) -> ValidatePaymentFut<()> {
+        if let Some(contract) = self.cosmwasm_htlc_contract() {
+            return self.validate_cosmwasm_payment_for_denom(contract.clone(), input, denom, decimals);
+        }
+
         let coin = self.clone();
         let fut = async move {
             let tx = cosmrs::Tx::from_bytes(&input.payment_tx)
@@ -1567,14 +1651,27 @@ impl TendermintCoin {
 
         let amount = sat_from_big_decimal(&amount, decimals)?;
 
-        let create_htlc_tx = self
-            .gen_create_htlc_tx(denom, &to_address, amount.into(), sha256(&sec).as_slice(), TIME_LOCK)
-            .map_err(|e| {
-                MmError::new(TradePreimageError::InternalError(format!(
-                    "Could not create HTLC. {:?}",
-                    e.into_inner()
-                )))
-            })?;
+        let create_htlc_payload = match self.cosmwasm_htlc_contract() {
+            Some(contract) => self
+                .gen_cosmwasm_create_htlc_tx(
+                    contract,
+                    denom,
+                    &to_address,
+                    amount.into(),
+                    sha256(&sec).as_slice(),
+                    now_sec() + TIME_LOCK * self.avg_blocktime as u64,
+                )
+                .map(|htlc| htlc.msg_payload),
+            None => self
+                .gen_create_htlc_tx(denom, &to_address, amount.into(), sha256(&sec).as_slice(), TIME_LOCK)
+                .map(|htlc| htlc.msg_payload),
+        }
+        .map_err(|e| {
+            MmError::new(TradePreimageError::InternalError(format!(
+                "Could not create HTLC. {:?}",
+                e.into_inner()
+            )))
+        })?;
 
         let current_block = self.current_block().compat().await.map_err(|e| {
             MmError::new(TradePreimageError::InternalError(format!(
@@ -1591,7 +1688,7 @@ impl TendermintCoin {
                 self.priv_key_policy
                     .activated_key_or_err()
                     .mm_err(|e| TradePreimageError::InternalError(e.to_string()))?,
-                create_htlc_tx.msg_payload.clone(),
+                create_htlc_payload,
                 timeout_height,
                 TX_DEFAULT_MEMO.to_owned(),
                 None,
@@ -1671,7 +1768,7 @@ impl TendermintCoin {
         Ok((denom_ubalance, denom_balance_dec))
     }
 
-    async fn request_tx(&self, hash: String) -> MmResult<Tx, TendermintCoinRpcError> {
+    pub(super) async fn request_tx(&self, hash: String) -> MmResult<Tx, TendermintCoinRpcError> {
         let path = AbciPath::from_str(ABCI_GET_TX_PATH).expect("valid path");
         let request = GetTxRequest { hash };
         let response = self
@@ -1750,6 +1847,10 @@ impl TendermintCoin {
         &self,
         input: SearchForSwapTxSpendInput<'_>,
     ) -> MmResult<Option<FoundSwapTxSpend>, SearchForSwapTxSpendErr> {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self.search_for_cosmwasm_swap_tx_spend(input).await;
+        }
+
         let tx = cosmrs::Tx::from_bytes(input.tx)?;
         let first_message = tx
             .body
@@ -1922,23 +2023,7 @@ impl MmCoin for TendermintCoin {
                 )));
             }
 
-            let (account_id, priv_key) = match req.from {
-                Some(WithdrawFrom::HDWalletAddress(ref path_to_address)) => {
-                    let priv_key = coin
-                        .priv_key_policy
-                        .hd_wallet_derived_priv_key_or_err(path_to_address)?;
-                    let account_id = account_id_from_privkey(priv_key.as_slice(), &coin.account_prefix)
-                        .map_err(|e| WithdrawError::InternalError(e.to_string()))?;
-                    (account_id, priv_key)
-                },
-                Some(WithdrawFrom::AddressId(_)) | Some(WithdrawFrom::DerivationPath { .. }) => {
-                    return MmError::err(WithdrawError::UnexpectedFromAddress(
-                        "Withdraw from 'AddressId' or 'DerivationPath' is not supported yet for Tendermint!"
-                            .to_string(),
-                    ))
-                },
-                None => (coin.account_id.clone(), *coin.priv_key_policy.activated_key_or_err()?),
-            };
+            let (account_id, priv_key) = coin.withdraw_sender(req.from.as_ref()).await?;
 
             let (balance_denom, balance_dec) = coin
                 .get_balance_as_unsigned_and_decimal(&account_id, &coin.denom, coin.decimals())
@@ -2340,6 +2425,10 @@ impl MarketCoinOps for TendermintCoin {
     }
 
     fn wait_for_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self.wait_for_cosmwasm_htlc_tx_spend(args);
+        }
+
         let tx = try_tx_fus!(cosmrs::Tx::from_bytes(args.tx_bytes));
         let first_message = try_tx_fus!(tx.body.messages.first().ok_or("Tx body couldn't be read."));
         let htlc_proto = try_tx_fus!(CreateHtlcProtoRep::decode(first_message.value.as_slice()));
@@ -2434,6 +2523,7 @@ impl SwapOps for TendermintCoin {
     fn send_maker_payment(&self, maker_payment_args: SendPaymentArgs) -> TransactionFut {
         self.send_htlc_for_denom(
             maker_payment_args.time_lock_duration,
+            maker_payment_args.time_lock,
             maker_payment_args.other_pubkey,
             maker_payment_args.secret_hash,
             maker_payment_args.amount,
@@ -2445,6 +2535,7 @@ impl SwapOps for TendermintCoin {
     fn send_taker_payment(&self, taker_payment_args: SendPaymentArgs) -> TransactionFut {
         self.send_htlc_for_denom(
             taker_payment_args.time_lock_duration,
+            taker_payment_args.time_lock,
             taker_payment_args.other_pubkey,
             taker_payment_args.secret_hash,
             taker_payment_args.amount,
@@ -2454,6 +2545,13 @@ impl SwapOps for TendermintCoin {
     }
 
     fn send_maker_spends_taker_payment(&self, maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self.send_cosmwasm_spend_htlc(
+                maker_spends_payment_args.other_payment_tx,
+                maker_spends_payment_args.secret,
+            );
+        }
+
         let tx = try_tx_fus!(cosmrs::Tx::from_bytes(maker_spends_payment_args.other_payment_tx));
         let msg = try_tx_fus!(tx.body.messages.first().ok_or("Tx body couldn't be read."));
         let htlc_proto: CreateHtlcProtoRep = try_tx_fus!(prost::Message::decode(msg.value.as_slice()));
@@ -2507,6 +2605,13 @@ impl SwapOps for TendermintCoin {
     }
 
     fn send_taker_spends_maker_payment(&self, taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self.send_cosmwasm_spend_htlc(
+                taker_spends_payment_args.other_payment_tx,
+                taker_spends_payment_args.secret,
+            );
+        }
+
         let tx = try_tx_fus!(cosmrs::Tx::from_bytes(taker_spends_payment_args.other_payment_tx));
         let msg = try_tx_fus!(tx.body.messages.first().ok_or("Tx body couldn't be read."));
         let htlc_proto: CreateHtlcProtoRep = try_tx_fus!(prost::Message::decode(msg.value.as_slice()));
@@ -2560,12 +2665,24 @@ impl SwapOps for TendermintCoin {
     }
 
     async fn send_taker_refunds_payment(&self, taker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self
+                .send_cosmwasm_refund_htlc(taker_refunds_payment_args.payment_tx)
+                .await;
+        }
+
         Err(TransactionErr::Plain(
             "Doesn't need transaction broadcast to refund IRIS HTLC".into(),
         ))
     }
 
     async fn send_maker_refunds_payment(&self, maker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return self
+                .send_cosmwasm_refund_htlc(maker_refunds_payment_args.payment_tx)
+                .await;
+        }
+
         Err(TransactionErr::Plain(
             "Doesn't need transaction broadcast to refund IRIS HTLC".into(),
         ))
@@ -2624,6 +2741,10 @@ impl SwapOps for TendermintCoin {
         spend_tx: &[u8],
         watcher_reward: bool,
     ) -> Result<Vec<u8>, String> {
+        if self.cosmwasm_htlc_contract().is_some() {
+            return extract_cosmwasm_secret(spend_tx);
+        }
+
         let tx = try_s!(cosmrs::Tx::from_bytes(spend_tx));
         let msg = try_s!(tx.body.messages.first().ok_or("Tx body couldn't be read."));
         let htlc_proto: super::iris::htlc_proto::ClaimHtlcProtoRep =
@@ -2838,6 +2959,8 @@ pub fn tendermint_priv_key_policy(
 pub mod tendermint_coin_tests {
     use super::*;
 
+    use crate::tendermint::cosmwasm::htlc::{CosmWasmHtlcExecuteMsg, MsgExecuteContract};
+    use crate::tendermint::cosmwasm::wasm_proto::MsgExecuteContractProto;
     use common::{block_on, wait_until_ms, DEX_FEE_ADDR_RAW_PUBKEY};
     use cosmrs::proto::cosmos::tx::v1beta1::{GetTxRequest, GetTxResponse, GetTxsEventResponse};
     use crypto::privkey::key_pair_from_seed;
@@ -2888,6 +3011,7 @@ pub mod tendermint_coin_tests {
             chain_id: String::from("nyancat-9"),
             gas_price: None,
             chain_registry_name: None,
+            htlc_backend: TendermintHtlcBackend::Iris,
         }
     }
 
@@ -2899,6 +3023,68 @@ pub mod tendermint_coin_tests {
             chain_id: String::from("nyancat-9"),
             gas_price: None,
             chain_registry_name: None,
+            htlc_backend: TendermintHtlcBackend::Iris,
+        }
+    }
+
+    /// Creates an IRIS testnet coin. The coin doesn't connect to the RPC until the first request.
+    pub fn iris_coin_for_test() -> TendermintCoin {
+        let ctx = mm2_core::mm_ctx::MmCtxBuilder::default().into_mm_arc();
+        let conf = TendermintConf {
+            avg_blocktime: AVG_BLOCKTIME,
+            derivation_path: None,
+        };
+        let key_pair = key_pair_from_seed(IRIS_TESTNET_HTLC_PAIR1_SEED).unwrap();
+        let priv_key_policy = TendermintPrivKeyPolicy::Iguana(key_pair.private().secret);
+
+        block_on(TendermintCoin::init(
+            &ctx,
+            "IRIS".to_string(),
+            conf,
+            get_iris_protocol(),
+            vec![IRIS_TESTNET_RPC_URL.to_string()],
+            false,
+            priv_key_policy,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cosmwasm_htlc_id_fits_contract_limit() {
+        let coin = iris_coin_for_test();
+        let contract = AccountId::new("iaa", &[1; 32]).unwrap();
+        let to = AccountId::from_str(IRIS_TESTNET_HTLC_PAIR2_ADDRESS).unwrap();
+        let denom = Denom::from_str("unyan").unwrap();
+        let secret_hash = sha256(&[1; 32]);
+        let amount = vec![Coin {
+            denom: denom.clone(),
+            amount: 1000u64.into(),
+        }];
+
+        let htlc = coin
+            .gen_cosmwasm_create_htlc_tx(
+                &contract,
+                denom,
+                &to,
+                1000u64.into(),
+                secret_hash.as_slice(),
+                1_700_000_000,
+            )
+            .unwrap();
+        // the contract accepts the IDs of 3..=20 bytes only
+        assert_eq!(htlc.id.len(), 20);
+        // the same ID must be derived by the validation and the payment search
+        let expected_id =
+            coin.calculate_cosmwasm_htlc_id(&coin.account_id, &to, amount.clone(), secret_hash.as_slice());
+        assert_eq!(htlc.id, expected_id);
+        let full_id = coin.calculate_htlc_id(&coin.account_id, &to, amount, secret_hash.as_slice());
+        assert!(full_id.starts_with(&htlc.id));
+
+        let msg = MsgExecuteContractProto::decode(htlc.msg_payload.value.as_slice()).unwrap();
+        let msg = MsgExecuteContract::try_from(msg).unwrap();
+        match msg.htlc_msg().unwrap() {
+            CosmWasmHtlcExecuteMsg::Create(create) => assert_eq!(create.id, htlc.id),
+            other => panic!("Expected create HTLC message, found {:?}", other),
         }
     }
 
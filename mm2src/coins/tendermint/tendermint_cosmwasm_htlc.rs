@@ -0,0 +1,463 @@
+//! Atomic swaps on top of a CosmWasm HTLC contract for the chains without the Iris HTLC module.
+//! See [`super::cosmwasm::htlc`] for the contract interface.
+
+use super::cosmwasm::htlc::{CosmWasmCreateHtlc, CosmWasmExpiration, CosmWasmHtlc, CosmWasmHtlcBalance,
+                            CosmWasmHtlcDetails, CosmWasmHtlcExecuteMsg, CosmWasmHtlcQueryMsg, CosmWasmNativeCoin,
+                            MsgExecuteContract, CW_HTLC_CREATE_ACTION, CW_HTLC_ID_LEN, CW_HTLC_REFUND_ACTION,
+                            CW_HTLC_RELEASE_ACTION};
+use super::cosmwasm::wasm_proto::{MsgExecuteContractProto, QuerySmartContractStateRequestProto,
+                                  QuerySmartContractStateResponseProto};
+use super::rpc::*;
+use super::{CosmosTransaction, SearchForSwapTxSpendErr, TendermintCoin, TendermintCoinRpcError, TendermintCommons,
+            ABCI_GET_TXS_EVENT_PATH, ABCI_REQUEST_HEIGHT, ABCI_REQUEST_PROVE, TIMEOUT_HEIGHT_DELTA, TX_DEFAULT_MEMO};
+use crate::coin_errors::ValidatePaymentError;
+use crate::utxo::sat_from_big_decimal;
+use crate::{FoundSwapTxSpend, MarketCoinOps, SearchForSwapTxSpendInput, TransactionEnum, TransactionErr,
+            TransactionFut, TransactionResult, TxMarshalingErr, ValidatePaymentFut, ValidatePaymentInput,
+            WaitForHTLCTxSpendArgs, WithdrawFee};
+use bitcrypto::{dhash160, sha256};
+use common::executor::Timer;
+use common::{get_utc_timestamp, Future01CompatExt};
+use cosmrs::proto::cosmos::tx::v1beta1::{GetTxsEventRequest, GetTxsEventResponse, TxRaw};
+use cosmrs::tx::Msg;
+use cosmrs::{AccountId, Any, Coin, Denom};
+use futures::{FutureExt, TryFutureExt};
+use futures01::Future;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use prost::Message;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const ABCI_QUERY_SMART_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+
+/// Contract executions take more gas than the Iris HTLC module messages.
+pub(crate) const COSMWASM_HTLC_GAS_LIMIT: u64 = 300_000;
+
+/// The error returned by `cw-storage-plus` when the HTLC doesn't exist in the contract state,
+/// i.e. it has never been created or has been already released/refunded.
+const HTLC_NOT_FOUND_ERR: &str = "not found";
+
+impl TendermintCoin {
+    /// Returns the address of the CosmWasm HTLC contract if the coin is configured to use it for swaps.
+    #[inline]
+    pub(super) fn cosmwasm_htlc_contract(&self) -> Option<&AccountId> { self.cosmwasm_htlc_contract.as_ref() }
+
+    /// Calculates the HTLC ID as [`TendermintCoin::calculate_htlc_id`] does, but truncates the hex encoded hash
+    /// to [`CW_HTLC_ID_LEN`] characters, since the contract rejects the longer IDs.
+    pub(super) fn calculate_cosmwasm_htlc_id(
+        &self,
+        from_address: &AccountId,
+        to_address: &AccountId,
+        amount: Vec<Coin>,
+        secret_hash: &[u8],
+    ) -> String {
+        let mut id = self.calculate_htlc_id(from_address, to_address, amount, secret_hash);
+        id.truncate(CW_HTLC_ID_LEN);
+        id
+    }
+
+    fn gen_execute_htlc_contract_msg(
+        &self,
+        contract: &AccountId,
+        msg: &CosmWasmHtlcExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> MmResult<Any, TxMarshalingErr> {
+        let msg = serde_json::to_vec(msg).map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))?;
+        MsgExecuteContract {
+            sender: self.account_id.clone(),
+            contract: contract.clone(),
+            msg,
+            funds,
+        }
+        .to_any()
+        .map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))
+    }
+
+    pub(super) fn gen_cosmwasm_create_htlc_tx(
+        &self,
+        contract: &AccountId,
+        denom: Denom,
+        to: &AccountId,
+        amount: cosmrs::Decimal,
+        secret_hash: &[u8],
+        time_lock: u64,
+    ) -> MmResult<CosmWasmHtlc, TxMarshalingErr> {
+        let amount = vec![Coin { denom, amount }];
+        let id = self.calculate_cosmwasm_htlc_id(&self.account_id, to, amount.clone(), secret_hash);
+
+        let msg = CosmWasmHtlcExecuteMsg::Create(CosmWasmCreateHtlc {
+            id: id.clone(),
+            hash: hex::encode(secret_hash),
+            recipient: to.to_string(),
+            expires: CosmWasmExpiration::at_timestamp_secs(time_lock),
+        });
+        let msg_payload = self.gen_execute_htlc_contract_msg(contract, &msg, amount)?;
+
+        Ok(CosmWasmHtlc { id, msg_payload })
+    }
+
+    /// Broadcasts the given HTLC contract execution with the gas limit that is enough for the contract calls.
+    async fn broadcast_cosmwasm_htlc_payload(&self, msg_payload: Any) -> TransactionResult {
+        let current_block = try_tx_s!(self.current_block().compat().await);
+        let timeout_height = current_block + TIMEOUT_HEIGHT_DELTA;
+
+        let withdraw_fee = WithdrawFee::CosmosGas {
+            gas_price: self.gas_price(),
+            gas_limit: COSMWASM_HTLC_GAS_LIMIT,
+        };
+        let fee = try_tx_s!(
+            self.calculate_fee(
+                msg_payload.clone(),
+                timeout_height,
+                TX_DEFAULT_MEMO.to_owned(),
+                Some(withdraw_fee)
+            )
+            .await
+        );
+
+        let (_tx_id, tx_raw) = try_tx_s!(
+            self.seq_safe_send_raw_tx_bytes(msg_payload, fee, timeout_height, TX_DEFAULT_MEMO.into())
+                .await
+        );
+
+        Ok(TransactionEnum::CosmosTransaction(CosmosTransaction {
+            data: tx_raw.into(),
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn send_cosmwasm_htlc_for_denom(
+        &self,
+        contract: AccountId,
+        time_lock: u64,
+        other_pub: &[u8],
+        secret_hash: &[u8],
+        amount: BigDecimal,
+        denom: Denom,
+        decimals: u8,
+    ) -> TransactionFut {
+        let pubkey_hash = dhash160(other_pub);
+        let to = try_tx_fus!(AccountId::new(&self.account_prefix, pubkey_hash.as_slice()));
+
+        let amount_as_u64 = try_tx_fus!(sat_from_big_decimal(&amount, decimals));
+        let amount = cosmrs::Decimal::from(amount_as_u64);
+
+        let create_htlc_tx =
+            try_tx_fus!(self.gen_cosmwasm_create_htlc_tx(&contract, denom, &to, amount, secret_hash, time_lock));
+
+        let coin = self.clone();
+        let fut = async move { coin.broadcast_cosmwasm_htlc_payload(create_htlc_tx.msg_payload).await };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn send_cosmwasm_spend_htlc(&self, other_payment_tx: &[u8], secret: &[u8]) -> TransactionFut {
+        let (execute_msg, create_htlc) = try_tx_fus!(cosmwasm_create_htlc_from_tx(other_payment_tx));
+        let release_msg = CosmWasmHtlcExecuteMsg::Release {
+            id: create_htlc.id,
+            preimage: hex::encode(secret),
+        };
+        let msg_payload = try_tx_fus!(self.gen_execute_htlc_contract_msg(&execute_msg.contract, &release_msg, vec![]));
+
+        let coin = self.clone();
+        let fut = async move { coin.broadcast_cosmwasm_htlc_payload(msg_payload).await };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) async fn send_cosmwasm_refund_htlc(&self, payment_tx: &[u8]) -> TransactionResult {
+        let (execute_msg, create_htlc) = try_tx_s!(cosmwasm_create_htlc_from_tx(payment_tx));
+        let refund_msg = CosmWasmHtlcExecuteMsg::Refund { id: create_htlc.id };
+        let msg_payload = try_tx_s!(self.gen_execute_htlc_contract_msg(&execute_msg.contract, &refund_msg, vec![]));
+
+        self.broadcast_cosmwasm_htlc_payload(msg_payload).await
+    }
+
+    pub(super) fn validate_cosmwasm_payment_for_denom(
+        &self,
+        contract: AccountId,
+        input: ValidatePaymentInput,
+        denom: Denom,
+        decimals: u8,
+    ) -> ValidatePaymentFut<()> {
+        let coin = self.clone();
+        let fut = async move {
+            let (execute_msg, create_htlc) = cosmwasm_create_htlc_from_tx(&input.payment_tx)
+                .mm_err(|e| ValidatePaymentError::WrongPaymentTx(format!("{:?}", e)))?;
+
+            if execute_msg.contract != contract {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Payment is sent to wrong contract {}, expected {}",
+                    execute_msg.contract, contract
+                )));
+            }
+
+            let sender_pubkey_hash = dhash160(&input.other_pub);
+            let sender = AccountId::new(&coin.account_prefix, sender_pubkey_hash.as_slice())
+                .map_to_mm(|e| ValidatePaymentError::InvalidParameter(e.to_string()))?;
+            if execute_msg.sender != sender {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Invalid payment sender {}, expected {}",
+                    execute_msg.sender, sender
+                )));
+            }
+
+            let amount = sat_from_big_decimal(&input.amount, decimals)?;
+            let expected_balance = CosmWasmHtlcBalance::Native(vec![CosmWasmNativeCoin {
+                denom: denom.to_string(),
+                amount: amount.to_string(),
+            }]);
+            let amount = vec![Coin {
+                denom,
+                amount: amount.into(),
+            }];
+            if execute_msg.funds != amount {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Invalid payment funds {:?}, expected {:?}",
+                    execute_msg.funds, amount
+                )));
+            }
+
+            let expected_create_htlc = CosmWasmCreateHtlc {
+                id: coin.calculate_cosmwasm_htlc_id(&sender, &coin.account_id, amount, &input.secret_hash),
+                hash: hex::encode(&input.secret_hash),
+                recipient: coin.account_id.to_string(),
+                expires: CosmWasmExpiration::at_timestamp_secs(input.time_lock),
+            };
+            if create_htlc != expected_create_htlc {
+                return MmError::err(ValidatePaymentError::WrongPaymentTx(format!(
+                    "Incorrect create HTLC message {:?}, expected {:?}",
+                    create_htlc, expected_create_htlc
+                )));
+            }
+
+            let hash = hex::encode_upper(sha256(&input.payment_tx).as_slice());
+            let tx_from_rpc = coin.request_tx(hash).await?;
+            if input.payment_tx != tx_from_rpc.encode_to_vec() {
+                return MmError::err(ValidatePaymentError::InvalidRpcResponse(
+                    "Tx from RPC doesn't match the input".into(),
+                ));
+            }
+
+            // The contract state is the source of truth: the HTLC must be still open and locked for us.
+            let details = coin
+                .query_cosmwasm_htlc(&contract, create_htlc.id.clone())
+                .await?
+                .or_mm_err(|| {
+                    ValidatePaymentError::UnexpectedPaymentState(format!(
+                        "HTLC {} is not open in the contract {}",
+                        create_htlc.id, contract
+                    ))
+                })?;
+            if details.hash != create_htlc.hash
+                || details.recipient != create_htlc.recipient
+                || details.source != sender.to_string()
+                || details.balance != expected_balance
+                || details.expires != create_htlc.expires
+            {
+                return MmError::err(ValidatePaymentError::UnexpectedPaymentState(format!(
+                    "Unexpected HTLC state {:?}",
+                    details
+                )));
+            }
+
+            Ok(())
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) fn check_if_my_cosmwasm_payment_sent_for_denom(
+        &self,
+        contract: AccountId,
+        decimals: u8,
+        denom: Denom,
+        other_pub: &[u8],
+        secret_hash: &[u8],
+        amount: &BigDecimal,
+    ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
+        let amount = try_fus!(sat_from_big_decimal(amount, decimals));
+        let amount = vec![Coin {
+            denom,
+            amount: amount.into(),
+        }];
+
+        let pubkey_hash = dhash160(other_pub);
+        let to_address = try_fus!(AccountId::new(&self.account_prefix, pubkey_hash.as_slice()));
+
+        let htlc_id = self.calculate_cosmwasm_htlc_id(&self.account_id, &to_address, amount, secret_hash);
+
+        let coin = self.clone();
+        let fut = async move {
+            let tx = try_s!(
+                coin.find_cosmwasm_htlc_tx(&contract, CW_HTLC_CREATE_ACTION, &htlc_id)
+                    .await
+            );
+            Ok(tx.map(|data| TransactionEnum::CosmosTransaction(CosmosTransaction { data })))
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    pub(super) async fn search_for_cosmwasm_swap_tx_spend(
+        &self,
+        input: SearchForSwapTxSpendInput<'_>,
+    ) -> MmResult<Option<FoundSwapTxSpend>, SearchForSwapTxSpendErr> {
+        let (execute_msg, create_htlc) = cosmwasm_create_htlc_from_tx(input.tx)
+            .mm_err(|e| SearchForSwapTxSpendErr::UnexpectedHtlcMsg(format!("{:?}", e)))?;
+        let contract = execute_msg.contract;
+
+        if self
+            .query_cosmwasm_htlc(&contract, create_htlc.id.clone())
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        // The contract removes the HTLC from its state once it is released or refunded.
+        if let Some(data) = self
+            .find_cosmwasm_htlc_tx(&contract, CW_HTLC_RELEASE_ACTION, &create_htlc.id)
+            .await?
+        {
+            let tx = TransactionEnum::CosmosTransaction(CosmosTransaction { data });
+            return Ok(Some(FoundSwapTxSpend::Spent(tx)));
+        }
+
+        if let Some(data) = self
+            .find_cosmwasm_htlc_tx(&contract, CW_HTLC_REFUND_ACTION, &create_htlc.id)
+            .await?
+        {
+            let tx = TransactionEnum::CosmosTransaction(CosmosTransaction { data });
+            return Ok(Some(FoundSwapTxSpend::Refunded(tx)));
+        }
+
+        // The payment is likely not confirmed yet.
+        Ok(None)
+    }
+
+    pub(super) fn wait_for_cosmwasm_htlc_tx_spend(&self, args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        let (execute_msg, create_htlc) = try_tx_fus!(cosmwasm_create_htlc_from_tx(args.tx_bytes));
+
+        let coin = self.clone();
+        let wait_until = args.wait_until;
+        let check_every = args.check_every;
+        let fut = async move {
+            loop {
+                let spend_tx = try_tx_s!(
+                    coin.find_cosmwasm_htlc_tx(&execute_msg.contract, CW_HTLC_RELEASE_ACTION, &create_htlc.id)
+                        .await
+                );
+                if let Some(data) = spend_tx {
+                    return Ok(TransactionEnum::CosmosTransaction(CosmosTransaction { data }));
+                }
+
+                Timer::sleep(check_every).await;
+                if get_utc_timestamp() > wait_until as i64 {
+                    return Err(TransactionErr::Plain("Waited too long".into()));
+                }
+            }
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    /// Returns `None` if there is no open HTLC with the given `id` in the contract state.
+    async fn query_cosmwasm_htlc(
+        &self,
+        contract: &AccountId,
+        id: String,
+    ) -> MmResult<Option<CosmWasmHtlcDetails>, TendermintCoinRpcError> {
+        let path = AbciPath::from_str(ABCI_QUERY_SMART_CONTRACT_STATE_PATH).expect("valid path");
+        let query_data = serde_json::to_vec(&CosmWasmHtlcQueryMsg::Details { id })
+            .map_to_mm(|e| TendermintCoinRpcError::InternalError(e.to_string()))?;
+        let request = AbciRequest::new(
+            Some(path),
+            QuerySmartContractStateRequestProto {
+                address: contract.to_string(),
+                query_data,
+            }
+            .encode_to_vec(),
+            ABCI_REQUEST_HEIGHT,
+            ABCI_REQUEST_PROVE,
+        );
+
+        let response = self.rpc_client().await?.perform(request).await?;
+        if let cosmrs::tendermint::abci::Code::Err(code) = response.response.code {
+            let log = response.response.log.to_string();
+            if log.contains(HTLC_NOT_FOUND_ERR) {
+                return Ok(None);
+            }
+            return MmError::err(TendermintCoinRpcError::InvalidResponse(format!(
+                "Contract query failed with code {}: {}",
+                code, log
+            )));
+        }
+
+        let response = QuerySmartContractStateResponseProto::decode(response.response.value.as_slice())?;
+        let details = serde_json::from_slice(&response.data)
+            .map_to_mm(|e| TendermintCoinRpcError::InvalidResponse(format!("Invalid HTLC details: {}", e)))?;
+        Ok(Some(details))
+    }
+
+    /// Searches for the first tx that executed the given `action` for the HTLC with the given `id`.
+    async fn find_cosmwasm_htlc_tx(
+        &self,
+        contract: &AccountId,
+        action: &str,
+        id: &str,
+    ) -> MmResult<Option<TxRaw>, TendermintCoinRpcError> {
+        let request = GetTxsEventRequest {
+            events: vec![
+                format!("wasm._contract_address='{}'", contract),
+                format!("wasm.action='{}'", action),
+                format!("wasm.id='{}'", id),
+            ],
+            pagination: None,
+            order_by: TendermintResultOrder::Ascending as i32,
+        };
+        let response: GetTxsEventResponse = self.abci_query(ABCI_GET_TXS_EVENT_PATH, request).await?;
+
+        Ok(response.txs.first().map(|tx| TxRaw {
+            body_bytes: tx.body.as_ref().map(Message::encode_to_vec).unwrap_or_default(),
+            auth_info_bytes: tx.auth_info.as_ref().map(Message::encode_to_vec).unwrap_or_default(),
+            signatures: tx.signatures.clone(),
+        }))
+    }
+}
+
+/// Decodes the create HTLC contract execution from the given payment tx.
+fn cosmwasm_create_htlc_from_tx(tx: &[u8]) -> MmResult<(MsgExecuteContract, CosmWasmCreateHtlc), TxMarshalingErr> {
+    let tx = cosmrs::Tx::from_bytes(tx).map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))?;
+    if tx.body.messages.len() != 1 {
+        return MmError::err(TxMarshalingErr::InvalidInput(
+            "Payment tx must have exactly one message".into(),
+        ));
+    }
+
+    let execute_msg = MsgExecuteContractProto::decode(tx.body.messages[0].value.as_slice())
+        .map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))?;
+    let execute_msg =
+        MsgExecuteContract::try_from(execute_msg).map_to_mm(|e| TxMarshalingErr::InvalidInput(e.to_string()))?;
+
+    match execute_msg.htlc_msg() {
+        Ok(CosmWasmHtlcExecuteMsg::Create(create_htlc)) => Ok((execute_msg, create_htlc)),
+        Ok(other) => MmError::err(TxMarshalingErr::InvalidInput(format!(
+            "Expected create HTLC message, found {:?}",
+            other
+        ))),
+        Err(e) => MmError::err(TxMarshalingErr::InvalidInput(e.to_string())),
+    }
+}
+
+/// Extracts the secret from the release HTLC contract execution.
+pub(super) fn extract_cosmwasm_secret(spend_tx: &[u8]) -> Result<Vec<u8>, String> {
+    let tx = try_s!(cosmrs::Tx::from_bytes(spend_tx));
+    let msg = try_s!(tx.body.messages.first().ok_or("Tx body couldn't be read."));
+    let execute_msg = try_s!(MsgExecuteContract::try_from(try_s!(MsgExecuteContractProto::decode(
+        msg.value.as_slice()
+    ))));
+
+    match try_s!(execute_msg.htlc_msg()) {
+        CosmWasmHtlcExecuteMsg::Release { preimage, .. } => Ok(try_s!(hex::decode(preimage))),
+        other => ERR!("Expected release HTLC message, found {:?}", other),
+    }
+}
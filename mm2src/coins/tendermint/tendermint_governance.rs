@@ -0,0 +1,421 @@
+use super::tendermint_staking::{sdk_dec_from_str, sdk_dec_to_string, MsgTxBalanceChange, MsgTxFee};
+use super::type_urls::{VOTE_TYPE_URL, VOTE_WEIGHTED_TYPE_URL};
+use super::{CustomTendermintMsgType, TendermintCoin, TendermintCoinRpcError};
+use crate::rpc_command::tendermint::{GovernanceError, GovernanceResult, ProposalStatus, ProposalStatusFilter,
+                                     TendermintProposal, TendermintTally, TendermintVote, TendermintVoteRequest,
+                                     VoteOption, WeightedVoteOption};
+use crate::{MarketCoinOps, TransactionDetails, TransactionType};
+use common::log::warn;
+use cosmrs::proto::cosmos::base::query::v1beta1::PageRequest;
+use cosmrs::proto::cosmos::gov::v1beta1::{MsgVote, MsgVoteWeighted, Proposal, QueryProposalRequest,
+                                          QueryProposalResponse, QueryProposalsRequest, QueryProposalsResponse,
+                                          QueryTallyResultRequest, QueryTallyResultResponse, QueryVoteRequest,
+                                          QueryVoteResponse, TallyResult, TextProposal,
+                                          WeightedVoteOption as WeightedVoteOptionProto};
+use cosmrs::Any;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use prost::Message;
+
+const ABCI_QUERY_PROPOSAL_PATH: &str = "/cosmos.gov.v1beta1.Query/Proposal";
+const ABCI_QUERY_PROPOSALS_PATH: &str = "/cosmos.gov.v1beta1.Query/Proposals";
+const ABCI_QUERY_TALLY_RESULT_PATH: &str = "/cosmos.gov.v1beta1.Query/TallyResult";
+const ABCI_QUERY_VOTE_PATH: &str = "/cosmos.gov.v1beta1.Query/Vote";
+
+impl TendermintCoin {
+    /// Returns the latest `limit` proposals with the given status, the newest first.
+    pub async fn proposals(
+        &self,
+        filter_by_status: ProposalStatusFilter,
+        limit: u64,
+    ) -> MmResult<Vec<TendermintProposal>, TendermintCoinRpcError> {
+        let request = QueryProposalsRequest {
+            proposal_status: filter_by_status.as_proposal_status(),
+            voter: String::new(),
+            depositor: String::new(),
+            pagination: Some(PageRequest {
+                limit,
+                reverse: true,
+                ..PageRequest::default()
+            }),
+        };
+        let response: QueryProposalsResponse = self.abci_query(ABCI_QUERY_PROPOSALS_PATH, request).await?;
+
+        let mut proposals = Vec::with_capacity(response.proposals.len());
+        for proposal in response.proposals {
+            proposals.push(self.proposal_details(proposal).await?);
+        }
+        Ok(proposals)
+    }
+
+    /// Signs `MsgVote` or `MsgVoteWeighted`, it should be sent to the `send_raw_transaction` RPC to broadcast.
+    pub async fn vote(&self, req: TendermintVoteRequest) -> GovernanceResult<TransactionDetails> {
+        let proposal = self.query_proposal(req.proposal_id).await?;
+        if ProposalStatus::from(proposal.status) != ProposalStatus::VotingPeriod {
+            return MmError::err(GovernanceError::ProposalNotInVotingPeriod(req.proposal_id));
+        }
+
+        let msg = self.vote_msg(req.proposal_id, req.vote)?;
+        let memo = req.memo.unwrap_or_default();
+        let tx_fee = self
+            .calculate_msg_fee::<GovernanceError>(msg.clone(), &memo, req.fee)
+            .await?;
+
+        let (balance_u64, balance_dec) = self
+            .get_balance_as_unsigned_and_decimal(&self.account_id, &self.denom, self.decimals)
+            .await?;
+        self.check_balance_for_vote_fee(balance_u64, balance_dec, &tx_fee)?;
+
+        let spent_by_me = tx_fee.fee_details.amount.clone();
+        self.sign_msg_tx(msg, memo, tx_fee, MsgTxBalanceChange {
+            to: self.account_id.to_string(),
+            total_amount: spent_by_me.clone(),
+            spent_by_me,
+            received_by_me: BigDecimal::default(),
+            transaction_type: TransactionType::CustomTendermintMsg {
+                msg_type: CustomTendermintMsgType::GovernanceVote,
+                token_id: None,
+            },
+        })
+        .await
+    }
+
+    fn vote_msg(&self, proposal_id: u64, vote: TendermintVote) -> GovernanceResult<Any> {
+        let voter = self.account_id.to_string();
+        match vote {
+            TendermintVote::Option(option) => {
+                if option == VoteOption::Unspecified {
+                    return MmError::err(GovernanceError::InvalidVote("Vote option must be specified".to_owned()));
+                }
+                Ok(self.proto_to_any(VOTE_TYPE_URL, MsgVote {
+                    proposal_id,
+                    voter,
+                    option: option.as_proto(),
+                }))
+            },
+            TendermintVote::Weighted(options) => {
+                validate_weighted_vote(&options)?;
+                Ok(self.proto_to_any(VOTE_WEIGHTED_TYPE_URL, MsgVoteWeighted {
+                    proposal_id,
+                    voter,
+                    options: options
+                        .iter()
+                        .map(|option| WeightedVoteOptionProto {
+                            option: option.option.as_proto(),
+                            weight: sdk_dec_to_string(&option.weight),
+                        })
+                        .collect(),
+                }))
+            },
+        }
+    }
+
+    fn check_balance_for_vote_fee(
+        &self,
+        balance_u64: u64,
+        balance_dec: BigDecimal,
+        tx_fee: &MsgTxFee,
+    ) -> GovernanceResult<()> {
+        if balance_u64 < tx_fee.fee_details.uamount {
+            return MmError::err(GovernanceError::NotSufficientBalance {
+                coin: self.ticker().to_owned(),
+                available: balance_dec,
+                required: tx_fee.fee_details.amount.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn proposal_details(&self, proposal: Proposal) -> MmResult<TendermintProposal, TendermintCoinRpcError> {
+        let status = ProposalStatus::from(proposal.status);
+        // The final tally is only set once the voting period ends, so query the current one while voting.
+        let (tally, my_vote) = if status == ProposalStatus::VotingPeriod {
+            let tally = self.query_tally(proposal.proposal_id).await?;
+            let my_vote = self.query_my_vote(proposal.proposal_id).await?;
+            (tally, my_vote)
+        } else {
+            (proposal.final_tally_result, None)
+        };
+
+        let content_type_url = proposal.content.as_ref().map(|content| content.type_url.clone());
+        let proposal_id = proposal.proposal_id;
+        let content = proposal
+            .content
+            .and_then(|content| decode_proposal_content(proposal_id, content));
+
+        let mut total_deposit = BigDecimal::default();
+        for coin in proposal
+            .total_deposit
+            .iter()
+            .filter(|coin| coin.denom == self.denom.as_ref())
+        {
+            total_deposit += self.platform_int_amount(&coin.amount)?;
+        }
+
+        Ok(TendermintProposal {
+            id: proposal.proposal_id,
+            title: content.as_ref().map(|content| content.title.clone()),
+            description: content.map(|content| content.description),
+            content_type_url,
+            status,
+            tally: tally
+                .map(|tally| self.tally_details(tally))
+                .transpose()?
+                .unwrap_or_default(),
+            total_deposit,
+            submit_time: proposal.submit_time.map(|t| t.seconds),
+            deposit_end_time: proposal.deposit_end_time.map(|t| t.seconds),
+            voting_start_time: proposal.voting_start_time.map(|t| t.seconds),
+            voting_end_time: proposal.voting_end_time.map(|t| t.seconds),
+            my_vote,
+        })
+    }
+
+    fn tally_details(&self, tally: TallyResult) -> MmResult<TendermintTally, TendermintCoinRpcError> {
+        Ok(TendermintTally {
+            yes: self.platform_int_amount(&tally.yes)?,
+            abstain: self.platform_int_amount(&tally.abstain)?,
+            no: self.platform_int_amount(&tally.no)?,
+            no_with_veto: self.platform_int_amount(&tally.no_with_veto)?,
+        })
+    }
+
+    async fn query_proposal(&self, proposal_id: u64) -> MmResult<Proposal, TendermintCoinRpcError> {
+        let response: QueryProposalResponse = self
+            .abci_query(ABCI_QUERY_PROPOSAL_PATH, QueryProposalRequest { proposal_id })
+            .await?;
+        response.proposal.or_mm_err(|| {
+            TendermintCoinRpcError::InvalidResponse(format!("Proposal {} is missing in the response", proposal_id))
+        })
+    }
+
+    async fn query_tally(&self, proposal_id: u64) -> MmResult<Option<TallyResult>, TendermintCoinRpcError> {
+        let response: QueryTallyResultResponse = self
+            .abci_query(ABCI_QUERY_TALLY_RESULT_PATH, QueryTallyResultRequest { proposal_id })
+            .await?;
+        Ok(response.tally)
+    }
+
+    async fn query_my_vote(
+        &self,
+        proposal_id: u64,
+    ) -> MmResult<Option<Vec<WeightedVoteOption>>, TendermintCoinRpcError> {
+        let request = QueryVoteRequest {
+            proposal_id,
+            voter: self.account_id.to_string(),
+        };
+        // The query fails with the `NotFound` code if we haven't voted yet.
+        let response: Option<QueryVoteResponse> = self.abci_query_optional(ABCI_QUERY_VOTE_PATH, request).await?;
+
+        let vote = match response.and_then(|response| response.vote) {
+            Some(vote) => vote,
+            None => return Ok(None),
+        };
+        let options = vote
+            .options
+            .into_iter()
+            .map(|option| {
+                Ok(WeightedVoteOption {
+                    option: VoteOption::from(option.option),
+                    weight: sdk_dec_from_str(&option.weight, 0)?,
+                })
+            })
+            .collect::<MmResult<_, TendermintCoinRpcError>>()?;
+        Ok(Some(options))
+    }
+}
+
+/// Every `v1beta1` proposal content starts with the `title` and `description` fields,
+/// but the custom contents of some chains don't follow it, such proposals are listed without them.
+fn decode_proposal_content(proposal_id: u64, content: Any) -> Option<TextProposal> {
+    match TextProposal::decode(content.value.as_slice()) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            warn!(
+                "Couldn't decode the '{}' content of proposal {}: {}",
+                content.type_url, proposal_id, e
+            );
+            None
+        },
+    }
+}
+
+fn validate_weighted_vote(options: &[WeightedVoteOption]) -> MmResult<(), GovernanceError> {
+    if options.is_empty() {
+        return MmError::err(GovernanceError::InvalidVote(
+            "At least one vote option must be given".to_owned(),
+        ));
+    }
+
+    let mut total_weight = BigDecimal::default();
+    for (i, option) in options.iter().enumerate() {
+        if option.option == VoteOption::Unspecified {
+            return MmError::err(GovernanceError::InvalidVote("Vote option must be specified".to_owned()));
+        }
+        if options[..i].iter().any(|prev| prev.option == option.option) {
+            return MmError::err(GovernanceError::InvalidVote(format!(
+                "Duplicate vote option {:?}",
+                option.option
+            )));
+        }
+        if option.weight <= BigDecimal::default() || option.weight > BigDecimal::from(1) {
+            return MmError::err(GovernanceError::InvalidVote(format!(
+                "Weight of {:?} must be greater than 0 and not greater than 1, got {}",
+                option.option, option.weight
+            )));
+        }
+        total_weight += &option.weight;
+    }
+
+    if total_weight != BigDecimal::from(1) {
+        return MmError::err(GovernanceError::InvalidVote(format!(
+            "Total weight must be 1, got {}",
+            total_weight
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tendermint::tendermint_coin_tests::iris_coin_for_test;
+    use crate::tendermint::tendermint_staking::STAKING_GAS_LIMIT_DEFAULT;
+    use crate::WithdrawFee;
+    use std::str::FromStr;
+
+    fn weighted(option: VoteOption, weight: &str) -> WeightedVoteOption {
+        WeightedVoteOption {
+            option,
+            weight: BigDecimal::from_str(weight).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_weighted_vote() {
+        let valid = [weighted(VoteOption::Yes, "0.7"), weighted(VoteOption::No, "0.3")];
+        validate_weighted_vote(&valid).unwrap();
+
+        let invalid_sum = [weighted(VoteOption::Yes, "0.7"), weighted(VoteOption::No, "0.2")];
+        validate_weighted_vote(&invalid_sum).unwrap_err();
+
+        let duplicate = [weighted(VoteOption::Yes, "0.5"), weighted(VoteOption::Yes, "0.5")];
+        validate_weighted_vote(&duplicate).unwrap_err();
+
+        let zero_weight = [weighted(VoteOption::Yes, "1"), weighted(VoteOption::No, "0")];
+        validate_weighted_vote(&zero_weight).unwrap_err();
+
+        validate_weighted_vote(&[]).unwrap_err();
+    }
+
+    #[test]
+    fn test_sdk_dec_weight_roundtrip() {
+        let weight = BigDecimal::from_str("0.7").unwrap();
+        let encoded = sdk_dec_to_string(&weight);
+        assert_eq!(encoded, "700000000000000000");
+        assert_eq!(sdk_dec_from_str(&encoded, 0).unwrap(), weight);
+    }
+
+    #[test]
+    fn test_vote_msg() {
+        let coin = iris_coin_for_test();
+
+        let msg = coin
+            .vote_msg(7, TendermintVote::Option(VoteOption::NoWithVeto))
+            .unwrap();
+        assert_eq!(msg.type_url, VOTE_TYPE_URL);
+        let decoded = MsgVote::decode(msg.value.as_slice()).unwrap();
+        assert_eq!(decoded, MsgVote {
+            proposal_id: 7,
+            voter: coin.account_id.to_string(),
+            option: VoteOption::NoWithVeto.as_proto(),
+        });
+
+        let error = coin
+            .vote_msg(7, TendermintVote::Option(VoteOption::Unspecified))
+            .unwrap_err()
+            .into_inner();
+        assert!(matches!(error, GovernanceError::InvalidVote(_)), "{:?}", error);
+    }
+
+    #[test]
+    fn test_vote_weighted_msg() {
+        let coin = iris_coin_for_test();
+        let options = vec![weighted(VoteOption::Yes, "0.75"), weighted(VoteOption::Abstain, "0.25")];
+
+        let msg = coin.vote_msg(8, TendermintVote::Weighted(options)).unwrap();
+        assert_eq!(msg.type_url, VOTE_WEIGHTED_TYPE_URL);
+        let decoded = MsgVoteWeighted::decode(msg.value.as_slice()).unwrap();
+        assert_eq!(decoded, MsgVoteWeighted {
+            proposal_id: 8,
+            voter: coin.account_id.to_string(),
+            options: vec![
+                WeightedVoteOptionProto {
+                    option: VoteOption::Yes.as_proto(),
+                    weight: "750000000000000000".to_owned(),
+                },
+                WeightedVoteOptionProto {
+                    option: VoteOption::Abstain.as_proto(),
+                    weight: "250000000000000000".to_owned(),
+                },
+            ],
+        });
+
+        let invalid = vec![weighted(VoteOption::Yes, "0.5")];
+        let error = coin
+            .vote_msg(8, TendermintVote::Weighted(invalid))
+            .unwrap_err()
+            .into_inner();
+        assert!(matches!(error, GovernanceError::InvalidVote(_)), "{:?}", error);
+    }
+
+    #[test]
+    fn test_vote_fee() {
+        let coin = iris_coin_for_test();
+
+        let tx_fee = coin.msg_tx_fee(25_000, &None, 100);
+        assert_eq!(tx_fee.fee_details.uamount, 25_000);
+        assert_eq!(tx_fee.fee_details.amount, BigDecimal::from_str("0.025").unwrap());
+        assert_eq!(tx_fee.fee_details.gas_limit, STAKING_GAS_LIMIT_DEFAULT);
+
+        let custom_fee = Some(WithdrawFee::CosmosGas {
+            gas_price: 0.25,
+            gas_limit: 150_000,
+        });
+        let tx_fee = coin.msg_tx_fee(25_000, &custom_fee, 100);
+        assert_eq!(tx_fee.fee_details.gas_limit, 150_000);
+
+        let balance = BigDecimal::from_str("0.025").unwrap();
+        coin.check_balance_for_vote_fee(25_000, balance, &tx_fee).unwrap();
+        let balance = BigDecimal::from_str("0.024999").unwrap();
+        let error = coin
+            .check_balance_for_vote_fee(24_999, balance.clone(), &tx_fee)
+            .unwrap_err()
+            .into_inner();
+        assert_eq!(error, GovernanceError::NotSufficientBalance {
+            coin: coin.ticker().to_owned(),
+            available: balance,
+            required: BigDecimal::from_str("0.025").unwrap(),
+        });
+    }
+
+    #[test]
+    fn test_decode_proposal_content() {
+        let text = TextProposal {
+            title: "Title".to_owned(),
+            description: "Description".to_owned(),
+        };
+        let content = Any {
+            type_url: "/cosmos.gov.v1beta1.TextProposal".to_owned(),
+            value: text.encode_to_vec(),
+        };
+        assert_eq!(decode_proposal_content(1, content), Some(text));
+
+        // the title field of a custom content is encoded as varint
+        let content = Any {
+            type_url: "/custom.v1.Proposal".to_owned(),
+            value: vec![0x08, 0x01],
+        };
+        assert_eq!(decode_proposal_content(2, content), None);
+    }
+}
@@ -0,0 +1,864 @@
+//! HD wallet support of Cosmos coins derived from the seed phrase the MarketMaker is initialized with.
+//!
+//! Hardware wallets are not supported yet: the coin can't be activated with `PrivKeyBuildPolicy::Trezor`,
+//! since the Trezor client doesn't implement the Cosmos `GetPublicKey`/`SignTx` messages.
+//! So [`SeedXPubExtractor`] returns `HDExtractPubkeyError::CoinDoesntSupportTrezor`
+//! if an account's extended public key is requested from a device.
+
+use super::rpc::TendermintResultOrder;
+use super::{account_id_from_privkey, TendermintCoin, TendermintCoinRpcError, ABCI_GET_TXS_EVENT_PATH};
+use crate::coin_balance::{self, CoinBalanceReport, EnableCoinBalanceError, EnableCoinBalanceOps,
+                          EnabledCoinBalanceParams, HDAccountBalance, HDAddressBalance, HDAddressBalanceScanner,
+                          HDWalletBalance, HDWalletBalanceOps};
+use crate::hd_confirm_address::HDConfirmAddress;
+use crate::hd_pubkey::{HDExtractPubkeyError, HDXPubExtractor};
+use crate::hd_wallet::{AccountUpdatingError, AddressDerivingError, AddressDerivingResult, AsyncMutexGuard,
+                       HDAccountMut, HDAccountOps, HDAccountsMap, HDAccountsMutex, HDAddress, HDAddressId,
+                       HDWalletCoinOps, HDWalletOps, InvalidBip44ChainError, NewAccountCreatingError,
+                       NewAddressDeriveConfirmError};
+use crate::hd_wallet_storage::{HDAccountStorageItem, HDWalletCoinStorage, HDWalletCoinWithStorageOps,
+                               HDWalletStorageResult};
+use crate::rpc_command::account_balance::{self, AccountBalanceParams, AccountBalanceRpcOps, HDAccountBalanceResponse};
+use crate::rpc_command::get_new_address::{self, GetNewAddressParams, GetNewAddressResponse, GetNewAddressRpcError,
+                                          GetNewAddressRpcOps};
+use crate::rpc_command::hd_account_balance_rpc_error::HDAccountBalanceRpcError;
+use crate::rpc_command::init_account_balance::{self, InitAccountBalanceParams, InitAccountBalanceRpcOps};
+use crate::rpc_command::init_create_account::{self, CreateAccountRpcError, CreateAccountState, CreateNewAccountParams,
+                                              InitCreateAccountRpcOps};
+use crate::rpc_command::init_scan_for_new_addresses::{self, InitScanAddressesRpcOps, ScanAddressesParams,
+                                                      ScanAddressesResponse};
+use crate::utxo::utxo_common::scan_for_new_addresses_impl;
+use crate::{big_decimal_from_sat_unsigned, BalanceError, BalanceResult, CoinBalance, CoinWithDerivationMethod,
+            DerivationMethod, WithdrawError, WithdrawFrom};
+use async_trait::async_trait;
+use bitcrypto::dhash160;
+use common::log::warn;
+use cosmrs::proto::cosmos::base::query::v1beta1::PageRequest;
+use cosmrs::proto::cosmos::tx::v1beta1::{GetTxsEventRequest, GetTxsEventResponse};
+use cosmrs::AccountId;
+use crypto::{Bip32DerPathOps, Bip44Chain, ChildNumber, DerivationPath, Secp256k1ExtendedPublicKey, Secp256k1Secret,
+             StandardHDCoinAddress, StandardHDPath, StandardHDPathError, StandardHDPathToAccount,
+             StandardHDPathToCoin, XPub};
+use futures::future::try_join_all;
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use primitives::hash::{H160, H264};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const DEFAULT_GAP_LIMIT: u32 = 20;
+
+pub type TendermintHDAddress = HDAddress<AccountId, H264>;
+
+#[derive(Debug)]
+pub struct TendermintHDWallet {
+    pub hd_wallet_rmd160: H160,
+    pub hd_wallet_storage: HDWalletCoinStorage,
+    /// Derivation path of the coin.
+    /// This derivation path consists of `purpose` and `coin_type` only
+    /// where the full `BIP44` address has the following structure:
+    /// `m/purpose'/coin_type'/account'/change/address_index`.
+    pub derivation_path: StandardHDPathToCoin,
+    /// User accounts.
+    pub accounts: HDAccountsMutex<TendermintHDAccount>,
+    // The max number of empty addresses in a row.
+    // If transactions were sent to an address outside the `gap_limit`, they will not be identified.
+    pub gap_limit: u32,
+}
+
+impl HDWalletOps for TendermintHDWallet {
+    type HDAccount = TendermintHDAccount;
+
+    fn coin_type(&self) -> u32 { self.derivation_path.coin_type() }
+
+    fn gap_limit(&self) -> u32 { self.gap_limit }
+
+    fn get_accounts_mutex(&self) -> &HDAccountsMutex<Self::HDAccount> { &self.accounts }
+}
+
+/// Cosmos wallets use [`Bip44Chain::External`] addresses only,
+/// so an account doesn't keep track of [`Bip44Chain::Internal`] addresses.
+#[derive(Clone, Debug)]
+pub struct TendermintHDAccount {
+    pub account_id: u32,
+    /// [Extended public key](https://learnmeabitcoin.com/technical/extended-keys) that corresponds to the derivation path:
+    /// `m/purpose'/coin_type'/account'`.
+    pub extended_pubkey: Secp256k1ExtendedPublicKey,
+    /// [`TendermintHDWallet::derivation_path`] derived by [`TendermintHDAccount::account_id`].
+    pub account_derivation_path: StandardHDPathToAccount,
+    /// The number of addresses that we know have been used by the user.
+    pub external_addresses_number: u32,
+}
+
+impl HDAccountOps for TendermintHDAccount {
+    fn known_addresses_number(&self, chain: Bip44Chain) -> MmResult<u32, InvalidBip44ChainError> {
+        match chain {
+            Bip44Chain::External => Ok(self.external_addresses_number),
+            Bip44Chain::Internal => MmError::err(InvalidBip44ChainError { chain }),
+        }
+    }
+
+    fn account_derivation_path(&self) -> DerivationPath { self.account_derivation_path.to_derivation_path() }
+
+    fn account_id(&self) -> u32 { self.account_id }
+}
+
+impl TendermintHDAccount {
+    pub fn try_from_storage_item(
+        wallet_der_path: &StandardHDPathToCoin,
+        account_info: &HDAccountStorageItem,
+    ) -> HDWalletStorageResult<TendermintHDAccount> {
+        const ACCOUNT_CHILD_HARDENED: bool = true;
+
+        let account_child = ChildNumber::new(account_info.account_id, ACCOUNT_CHILD_HARDENED)?;
+        let account_derivation_path = wallet_der_path
+            .derive(account_child)
+            .map_to_mm(StandardHDPathError::from)?;
+        let extended_pubkey = Secp256k1ExtendedPublicKey::from_str(&account_info.account_xpub)?;
+        Ok(TendermintHDAccount {
+            account_id: account_info.account_id,
+            extended_pubkey,
+            account_derivation_path,
+            external_addresses_number: account_info.external_addresses_number,
+        })
+    }
+
+    pub fn to_storage_item(&self) -> HDAccountStorageItem {
+        HDAccountStorageItem {
+            account_id: self.account_id,
+            account_xpub: self.extended_pubkey.to_string(bip32::Prefix::XPUB),
+            external_addresses_number: self.external_addresses_number,
+            internal_addresses_number: 0,
+        }
+    }
+}
+
+/// Initializes an HD wallet derived from the seed that the MarketMaker is initialized with.
+pub(super) async fn init_hd_wallet(
+    ctx: &MmArc,
+    ticker: String,
+    derivation_path: StandardHDPathToCoin,
+) -> HDWalletStorageResult<TendermintHDWallet> {
+    // There is no Hardware Wallet device to identify the wallet by,
+    // so the accounts are stored under the RIPEMD160(SHA256(x)) of the MarketMaker pubkey derived from the seed.
+    let hd_wallet_rmd160 = *ctx.rmd160();
+    let hd_wallet_storage = HDWalletCoinStorage::init_with_rmd160(ctx, ticker, hd_wallet_rmd160).await?;
+    let accounts = load_hd_accounts_from_storage(&hd_wallet_storage, &derivation_path).await?;
+    Ok(TendermintHDWallet {
+        hd_wallet_rmd160,
+        hd_wallet_storage,
+        derivation_path,
+        accounts: HDAccountsMutex::new(accounts),
+        gap_limit: DEFAULT_GAP_LIMIT,
+    })
+}
+
+async fn load_hd_accounts_from_storage(
+    hd_wallet_storage: &HDWalletCoinStorage,
+    derivation_path: &StandardHDPathToCoin,
+) -> HDWalletStorageResult<HDAccountsMap<TendermintHDAccount>> {
+    let accounts = hd_wallet_storage.load_all_accounts().await?;
+    let res: HDWalletStorageResult<HDAccountsMap<TendermintHDAccount>> = accounts
+        .iter()
+        .map(|account_info| {
+            let account = TendermintHDAccount::try_from_storage_item(derivation_path, account_info)?;
+            Ok((account.account_id, account))
+        })
+        .collect();
+    match res {
+        Ok(accounts) => Ok(accounts),
+        Err(e) if e.get_inner().is_deserializing_err() => {
+            warn!("Error loading HD accounts from the storage: '{}'. Clear accounts", e);
+            hd_wallet_storage.clear_accounts().await?;
+            Ok(HDAccountsMap::new())
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Cosmos HD accounts are derived from the seed the coin is activated with,
+/// so an extended public key is never requested from a hardware wallet.
+/// This extractor should be replaced with `RpcTaskXPubExtractor` once Trezor supports Cosmos.
+struct SeedXPubExtractor;
+
+#[async_trait]
+impl HDXPubExtractor for SeedXPubExtractor {
+    async fn extract_utxo_xpub(
+        &self,
+        _trezor_utxo_coin: String,
+        _derivation_path: DerivationPath,
+    ) -> MmResult<XPub, HDExtractPubkeyError> {
+        MmError::err(HDExtractPubkeyError::CoinDoesntSupportTrezor)
+    }
+}
+
+/// Checks if an address has been used by looking up the first transaction that transferred funds to it.
+pub struct TendermintAddressScanner {
+    coin: TendermintCoin,
+}
+
+#[async_trait]
+impl HDAddressBalanceScanner for TendermintAddressScanner {
+    type Address = AccountId;
+
+    async fn is_address_used(&self, address: &Self::Address) -> BalanceResult<bool> {
+        let request = GetTxsEventRequest {
+            events: vec![format!("transfer.recipient='{}'", address)],
+            pagination: Some(PageRequest {
+                limit: 1,
+                ..PageRequest::default()
+            }),
+            order_by: TendermintResultOrder::Ascending as i32,
+        };
+        let response: GetTxsEventResponse = self.coin.abci_query(ABCI_GET_TXS_EVENT_PATH, request).await?;
+        Ok(!response.txs.is_empty())
+    }
+}
+
+impl TendermintCoin {
+    /// Requests the balance of the given `address` in the given `denom`.
+    async fn address_balance_for_denom(
+        &self,
+        address: &AccountId,
+        denom: String,
+        decimals: u8,
+    ) -> MmResult<CoinBalance, TendermintCoinRpcError> {
+        let balance_denom = self.account_balance_for_denom(address, denom).await?;
+        Ok(CoinBalance {
+            spendable: big_decimal_from_sat_unsigned(balance_denom, decimals),
+            unspendable: BigDecimal::default(),
+        })
+    }
+
+    /// Returns the platform balance report of every known HD address (or of the single address),
+    /// scanning for new addresses if it's prescribed by the given `params`.
+    pub async fn enable_wallet_balance(
+        &self,
+        params: EnabledCoinBalanceParams,
+    ) -> MmResult<CoinBalanceReport, EnableCoinBalanceError> {
+        self.enable_coin_balance(&SeedXPubExtractor, params).await
+    }
+
+    /// Requests the balances of all activated IBC/native assets of every address of the given `wallet_balance`.
+    /// Returns a map where the key is address, and the value is the address's assets balances by their tickers.
+    pub async fn addresses_tokens_balances(
+        &self,
+        wallet_balance: &CoinBalanceReport,
+    ) -> MmResult<HashMap<String, HashMap<String, BigDecimal>>, TendermintCoinRpcError> {
+        let mut result = HashMap::new();
+        for address in wallet_balance.to_addresses_total_balances().into_keys() {
+            let account_id =
+                AccountId::from_str(&address).map_to_mm(|e| TendermintCoinRpcError::InternalError(e.to_string()))?;
+            let tokens_balances = self.address_tokens_balances(&account_id).await?;
+            result.insert(address, tokens_balances);
+        }
+        Ok(result)
+    }
+
+    /// Resolves the address and the private key the withdrawal should be sent from.
+    /// The activated address is used if `from` is not set.
+    pub(super) async fn withdraw_sender(
+        &self,
+        from: Option<&WithdrawFrom>,
+    ) -> MmResult<(AccountId, Secp256k1Secret), WithdrawError> {
+        let path_to_address = match from {
+            None => return Ok((self.account_id.clone(), *self.priv_key_policy.activated_key_or_err()?)),
+            Some(WithdrawFrom::HDWalletAddress(path_to_address)) => path_to_address.clone(),
+            Some(WithdrawFrom::AddressId(address_id)) => {
+                self.activated_hd_address(address_id.account_id, address_id.chain, address_id.address_id)
+                    .await?
+            },
+            Some(WithdrawFrom::DerivationPath { derivation_path }) => {
+                let derivation_path = StandardHDPath::from_str(derivation_path)
+                    .map_to_mm(StandardHDPathError::from)
+                    .mm_err(|e| WithdrawError::UnexpectedFromAddress(e.to_string()))?;
+                let expected_coin_type = self.derivation_method.hd_wallet_or_err()?.coin_type();
+                if derivation_path.coin_type() != expected_coin_type {
+                    let error = format!(
+                        "Derivation path '{}' must has '{}' coin type",
+                        derivation_path, expected_coin_type
+                    );
+                    return MmError::err(WithdrawError::UnexpectedFromAddress(error));
+                }
+                self.activated_hd_address(
+                    derivation_path.account_id(),
+                    derivation_path.chain(),
+                    derivation_path.address_id(),
+                )
+                .await?
+            },
+        };
+
+        let priv_key = self
+            .priv_key_policy
+            .hd_wallet_derived_priv_key_or_err(&path_to_address)?;
+        let account_id = account_id_from_privkey(priv_key.as_slice(), &self.account_prefix)
+            .map_err(|e| WithdrawError::InternalError(e.to_string()))?;
+        Ok((account_id, priv_key))
+    }
+
+    /// Checks if the given HD address is known and returns its path.
+    async fn activated_hd_address(
+        &self,
+        account_id: u32,
+        chain: Bip44Chain,
+        address_id: u32,
+    ) -> MmResult<StandardHDCoinAddress, WithdrawError> {
+        let hd_wallet = self.derivation_method.hd_wallet_or_err()?;
+        let hd_account = hd_wallet
+            .get_account(account_id)
+            .await
+            .or_mm_err(|| WithdrawError::UnknownAccount { account_id })?;
+        let hd_address = self.derive_address(&hd_account, chain, address_id).await?;
+
+        let is_address_activated = hd_account
+            .is_address_activated(chain, address_id)
+            // If [`HDWalletCoinOps::derive_address`] succeeds, [`HDAccountOps::is_address_activated`] shouldn't fails with an `InvalidBip44ChainError`.
+            .mm_err(|e| WithdrawError::InternalError(e.to_string()))?;
+        if !is_address_activated {
+            let error = format!("'{}' address is not activated", hd_address.address);
+            return MmError::err(WithdrawError::UnexpectedFromAddress(error));
+        }
+
+        Ok(StandardHDCoinAddress {
+            account: account_id,
+            is_change: chain == Bip44Chain::Internal,
+            address_index: address_id,
+        })
+    }
+}
+
+impl CoinWithDerivationMethod for TendermintCoin {
+    type Address = AccountId;
+    type HDWallet = TendermintHDWallet;
+
+    fn derivation_method(&self) -> &DerivationMethod<Self::Address, Self::HDWallet> { &self.derivation_method }
+}
+
+#[async_trait]
+impl HDWalletCoinOps for TendermintCoin {
+    type Address = AccountId;
+    type Pubkey = H264;
+    type HDWallet = TendermintHDWallet;
+    type HDAccount = TendermintHDAccount;
+
+    async fn derive_addresses<Ids>(
+        &self,
+        hd_account: &Self::HDAccount,
+        address_ids: Ids,
+    ) -> AddressDerivingResult<Vec<HDAddress<Self::Address, Self::Pubkey>>>
+    where
+        Ids: Iterator<Item = HDAddressId> + Send,
+    {
+        address_ids
+            .map(
+                |HDAddressId { chain, address_id }| -> AddressDerivingResult<TendermintHDAddress> {
+                    if chain != Bip44Chain::External {
+                        return MmError::err(AddressDerivingError::InvalidBip44Chain { chain });
+                    }
+
+                    let change_child = chain.to_child_number();
+                    let address_id_child = ChildNumber::from(address_id);
+
+                    let derived_pubkey = hd_account
+                        .extended_pubkey
+                        .derive_child(change_child)?
+                        .derive_child(address_id_child)?;
+                    let pubkey = H264::from(derived_pubkey.public_key().serialize());
+                    let address = AccountId::new(&self.account_prefix, dhash160(pubkey.as_slice()).as_slice())
+                        .map_to_mm(|e| AddressDerivingError::Internal(e.to_string()))?;
+
+                    let mut derivation_path = hd_account.account_derivation_path.to_derivation_path();
+                    derivation_path.push(change_child);
+                    derivation_path.push(address_id_child);
+
+                    Ok(HDAddress {
+                        address,
+                        pubkey,
+                        derivation_path,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// There is no hardware device to confirm the address on,
+    /// since Tendermint addresses are derived from the activated seed.
+    async fn generate_and_confirm_new_address<ConfirmAddress>(
+        &self,
+        hd_wallet: &Self::HDWallet,
+        hd_account: &mut Self::HDAccount,
+        chain: Bip44Chain,
+        _confirm_address: &ConfirmAddress,
+    ) -> MmResult<HDAddress<Self::Address, Self::Pubkey>, NewAddressDeriveConfirmError>
+    where
+        ConfirmAddress: HDConfirmAddress,
+    {
+        Ok(self.generate_new_address(hd_wallet, hd_account, chain).await?)
+    }
+
+    async fn create_new_account<'a, XPubExtractor>(
+        &self,
+        hd_wallet: &'a Self::HDWallet,
+        _xpub_extractor: &XPubExtractor,
+    ) -> MmResult<HDAccountMut<'a, Self::HDAccount>, NewAccountCreatingError>
+    where
+        XPubExtractor: HDXPubExtractor,
+    {
+        const INIT_ACCOUNT_ID: u32 = 0;
+        let new_account_id = hd_wallet
+            .accounts
+            .lock()
+            .await
+            .iter()
+            // The last element of the BTreeMap has the max account index.
+            .last()
+            .map(|(account_id, _account)| *account_id + 1)
+            .unwrap_or(INIT_ACCOUNT_ID);
+        let max_accounts_number = hd_wallet.account_limit();
+        if new_account_id >= max_accounts_number {
+            return MmError::err(NewAccountCreatingError::AccountLimitReached { max_accounts_number });
+        }
+
+        let account_child_hardened = true;
+        let account_child = ChildNumber::new(new_account_id, account_child_hardened)
+            .map_to_mm(|e| NewAccountCreatingError::Internal(e.to_string()))?;
+
+        let account_derivation_path: StandardHDPathToAccount = hd_wallet.derivation_path.derive(account_child)?;
+        let mut account_priv_key = self
+            .priv_key_policy
+            .bip39_secp_priv_key_or_err()
+            .mm_err(|_| NewAccountCreatingError::HDWalletUnavailable)?
+            .clone();
+        for child in account_derivation_path.to_derivation_path() {
+            account_priv_key = account_priv_key
+                .derive_child(child)
+                .map_to_mm(|e| NewAccountCreatingError::Internal(e.to_string()))?;
+        }
+
+        let new_account = TendermintHDAccount {
+            account_id: new_account_id,
+            extended_pubkey: account_priv_key.public_key(),
+            account_derivation_path,
+            // We don't know how many addresses are used by the user at this moment.
+            external_addresses_number: 0,
+        };
+
+        let accounts = hd_wallet.accounts.lock().await;
+        if accounts.contains_key(&new_account_id) {
+            let error = format!(
+                "Account '{}' has been activated while we proceed the 'create_new_account' function",
+                new_account_id
+            );
+            return MmError::err(NewAccountCreatingError::Internal(error));
+        }
+
+        self.upload_new_account(hd_wallet, new_account.to_storage_item())
+            .await?;
+
+        Ok(AsyncMutexGuard::map(accounts, |accounts| {
+            accounts
+                .entry(new_account_id)
+                // the `entry` method should return [`Entry::Vacant`] due to the checks above
+                .or_insert(new_account)
+        }))
+    }
+
+    async fn set_known_addresses_number(
+        &self,
+        hd_wallet: &Self::HDWallet,
+        hd_account: &mut Self::HDAccount,
+        chain: Bip44Chain,
+        new_known_addresses_number: u32,
+    ) -> MmResult<(), AccountUpdatingError> {
+        let max_addresses_number = hd_wallet.address_limit();
+        if new_known_addresses_number >= max_addresses_number {
+            return MmError::err(AccountUpdatingError::AddressLimitReached { max_addresses_number });
+        }
+        match chain {
+            Bip44Chain::External => {
+                self.update_external_addresses_number(hd_wallet, hd_account.account_id, new_known_addresses_number)
+                    .await?;
+                hd_account.external_addresses_number = new_known_addresses_number;
+                Ok(())
+            },
+            Bip44Chain::Internal => MmError::err(AccountUpdatingError::InvalidBip44Chain(InvalidBip44ChainError {
+                chain,
+            })),
+        }
+    }
+}
+
+impl HDWalletCoinWithStorageOps for TendermintCoin {
+    fn hd_wallet_storage<'a>(&self, hd_wallet: &'a Self::HDWallet) -> &'a HDWalletCoinStorage {
+        &hd_wallet.hd_wallet_storage
+    }
+}
+
+#[async_trait]
+impl HDWalletBalanceOps for TendermintCoin {
+    type HDAddressScanner = TendermintAddressScanner;
+
+    async fn produce_hd_address_scanner(&self) -> BalanceResult<Self::HDAddressScanner> {
+        Ok(TendermintAddressScanner { coin: self.clone() })
+    }
+
+    async fn enable_hd_wallet<XPubExtractor>(
+        &self,
+        hd_wallet: &Self::HDWallet,
+        xpub_extractor: &XPubExtractor,
+        params: EnabledCoinBalanceParams,
+    ) -> MmResult<HDWalletBalance, EnableCoinBalanceError>
+    where
+        XPubExtractor: HDXPubExtractor,
+    {
+        coin_balance::common_impl::enable_hd_wallet(self, hd_wallet, xpub_extractor, params).await
+    }
+
+    async fn scan_for_new_addresses(
+        &self,
+        hd_wallet: &Self::HDWallet,
+        hd_account: &mut Self::HDAccount,
+        address_scanner: &Self::HDAddressScanner,
+        gap_limit: u32,
+    ) -> BalanceResult<Vec<HDAddressBalance>> {
+        scan_for_new_addresses_impl(
+            self,
+            hd_wallet,
+            hd_account,
+            address_scanner,
+            Bip44Chain::External,
+            gap_limit,
+        )
+        .await
+    }
+
+    async fn all_known_addresses_balances(&self, hd_account: &Self::HDAccount) -> BalanceResult<Vec<HDAddressBalance>> {
+        let external_addresses = hd_account.external_addresses_number;
+        self.known_addresses_balances_with_ids(hd_account, Bip44Chain::External, 0..external_addresses)
+            .await
+    }
+
+    async fn known_address_balance(&self, address: &Self::Address) -> BalanceResult<CoinBalance> {
+        Ok(self
+            .address_balance_for_denom(address, self.denom.to_string(), self.decimals)
+            .await?)
+    }
+
+    async fn known_addresses_balances(
+        &self,
+        addresses: Vec<Self::Address>,
+    ) -> BalanceResult<Vec<(Self::Address, CoinBalance)>> {
+        let requests = addresses.into_iter().map(|address| async move {
+            let balance = self.known_address_balance(&address).await?;
+            Ok::<_, MmError<BalanceError>>((address, balance))
+        });
+        // `try_join_all` keeps the order in which the balances were requested.
+        try_join_all(requests).await
+    }
+}
+
+#[async_trait]
+impl GetNewAddressRpcOps for TendermintCoin {
+    async fn get_new_address_rpc_without_conf(
+        &self,
+        params: GetNewAddressParams,
+    ) -> MmResult<GetNewAddressResponse, GetNewAddressRpcError> {
+        get_new_address::common_impl::get_new_address_rpc_without_conf(self, params).await
+    }
+
+    async fn get_new_address_rpc<ConfirmAddress>(
+        &self,
+        params: GetNewAddressParams,
+        confirm_address: &ConfirmAddress,
+    ) -> MmResult<GetNewAddressResponse, GetNewAddressRpcError>
+    where
+        ConfirmAddress: HDConfirmAddress,
+    {
+        get_new_address::common_impl::get_new_address_rpc(self, params, confirm_address).await
+    }
+}
+
+#[async_trait]
+impl AccountBalanceRpcOps for TendermintCoin {
+    async fn account_balance_rpc(
+        &self,
+        params: AccountBalanceParams,
+    ) -> MmResult<HDAccountBalanceResponse, HDAccountBalanceRpcError> {
+        account_balance::common_impl::account_balance_rpc(self, params).await
+    }
+}
+
+#[async_trait]
+impl InitAccountBalanceRpcOps for TendermintCoin {
+    async fn init_account_balance_rpc(
+        &self,
+        params: InitAccountBalanceParams,
+    ) -> MmResult<HDAccountBalance, HDAccountBalanceRpcError> {
+        init_account_balance::common_impl::init_account_balance_rpc(self, params).await
+    }
+}
+
+#[async_trait]
+impl InitScanAddressesRpcOps for TendermintCoin {
+    async fn init_scan_for_new_addresses_rpc(
+        &self,
+        params: ScanAddressesParams,
+    ) -> MmResult<ScanAddressesResponse, HDAccountBalanceRpcError> {
+        init_scan_for_new_addresses::common_impl::scan_for_new_addresses_rpc(self, params).await
+    }
+}
+
+#[async_trait]
+impl InitCreateAccountRpcOps for TendermintCoin {
+    async fn init_create_account_rpc<XPubExtractor>(
+        &self,
+        params: CreateNewAccountParams,
+        state: CreateAccountState,
+        xpub_extractor: &XPubExtractor,
+    ) -> MmResult<HDAccountBalance, CreateAccountRpcError>
+    where
+        XPubExtractor: HDXPubExtractor,
+    {
+        init_create_account::common_impl::init_create_new_account_rpc(self, params, state, xpub_extractor).await
+    }
+
+    async fn revert_creating_account(&self, account_id: u32) {
+        init_create_account::common_impl::revert_creating_account(self, account_id).await
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::tendermint::{tendermint_priv_key_policy, TendermintConf, TendermintInitErrorKind,
+                            TendermintProtocolInfo};
+    use crate::{PrivKeyBuildPolicy, PrivKeyPolicyNotAllowed};
+    use common::block_on;
+    use crypto::{derive_secp256k1_secret, CryptoCtx};
+    use mm2_test_helpers::for_tests::mm_ctx_with_custom_db;
+
+    /// The addresses are derived by the `m/44'/118'/account'/0/address_index` path, see cosmjs tests.
+    const PASSPHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const ACCOUNT0_ADDRESS0: &str = "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4";
+    const ACCOUNT0_ADDRESS1: &str = "cosmos1jrkmdcwgq94uaamx6zax2luewlhf7u4kucx3kz";
+    const ACCOUNT1_ADDRESS0: &str = "cosmos1tehv5km5e9y706rc2gzk9yyun9dljjjnvyt3u0";
+
+    fn atom_conf() -> TendermintConf {
+        let conf = json!({
+            "avg_blocktime": 7,
+            "derivation_path": "m/44'/118'",
+        });
+        TendermintConf::try_from_json("ATOM", &conf).unwrap()
+    }
+
+    fn atom_protocol() -> TendermintProtocolInfo {
+        json::from_value(json!({
+            "decimals": 6,
+            "denom": "uatom",
+            "account_prefix": "cosmos",
+            "chain_id": "cosmoshub-4",
+        }))
+        .unwrap()
+    }
+
+    fn atom_hd_coin(path_to_address: StandardHDCoinAddress) -> TendermintCoin {
+        let ctx = mm_ctx_with_custom_db();
+        CryptoCtx::init_with_global_hd_account(ctx.clone(), PASSPHRASE).unwrap();
+        let priv_key_build_policy = PrivKeyBuildPolicy::detect_priv_key_policy(&ctx).unwrap();
+        let conf = atom_conf();
+        let priv_key_policy =
+            tendermint_priv_key_policy(&conf, "ATOM", priv_key_build_policy, path_to_address).unwrap();
+
+        block_on(TendermintCoin::init(
+            &ctx,
+            "ATOM".to_string(),
+            conf,
+            atom_protocol(),
+            vec!["http://127.0.0.1:26657".to_string()],
+            false,
+            priv_key_policy,
+        ))
+        .unwrap()
+    }
+
+    fn create_account(coin: &TendermintCoin) -> TendermintHDAccount {
+        let hd_wallet = coin.derivation_method.hd_wallet().unwrap();
+        let account = block_on(coin.create_new_account(hd_wallet, &SeedXPubExtractor))
+            .unwrap_or_else(|e| panic!("Error creating account: {}", e));
+        account.clone()
+    }
+
+    #[test]
+    fn test_derive_addresses() {
+        let coin = atom_hd_coin(StandardHDCoinAddress::default());
+        assert_eq!(coin.account_id.to_string(), ACCOUNT0_ADDRESS0);
+
+        let account0 = create_account(&coin);
+        assert_eq!(account0.account_id, 0);
+        assert_eq!(account0.account_derivation_path.to_string(), "m/44'/118'/0'");
+        let addresses = block_on(coin.derive_addresses(
+            &account0,
+            (0..2).map(|address_id| HDAddressId {
+                chain: Bip44Chain::External,
+                address_id,
+            }),
+        ))
+        .unwrap();
+        let actual: Vec<_> = addresses.iter().map(|address| address.address.to_string()).collect();
+        assert_eq!(actual, vec![ACCOUNT0_ADDRESS0, ACCOUNT0_ADDRESS1]);
+        assert_eq!(addresses[1].derivation_path.to_string(), "m/44'/118'/0'/0/1");
+
+        let account1 = create_account(&coin);
+        assert_eq!(account1.account_id, 1);
+        let address = block_on(coin.derive_address(&account1, Bip44Chain::External, 0)).unwrap();
+        assert_eq!(address.address.to_string(), ACCOUNT1_ADDRESS0);
+    }
+
+    /// The addresses derived from the account xpub should match the ones derived from the private keys.
+    #[test]
+    fn test_derived_address_matches_priv_key() {
+        let coin = atom_hd_coin(StandardHDCoinAddress::default());
+        let hd_wallet = coin.derivation_method.hd_wallet().unwrap();
+        let account = create_account(&coin);
+        let bip39_secp_priv_key = coin.priv_key_policy.bip39_secp_priv_key_or_err().unwrap();
+
+        for address_index in [0, 5, 19] {
+            let path_to_address = StandardHDCoinAddress {
+                account: 0,
+                is_change: false,
+                address_index,
+            };
+            let priv_key = derive_secp256k1_secret(
+                bip39_secp_priv_key.clone(),
+                &hd_wallet.derivation_path,
+                &path_to_address,
+            )
+            .unwrap();
+            let expected = account_id_from_privkey(priv_key.as_slice(), "cosmos").unwrap();
+            let derived = block_on(coin.derive_address(&account, Bip44Chain::External, address_index)).unwrap();
+            assert_eq!(derived.address, expected);
+        }
+    }
+
+    #[test]
+    fn test_activated_address_by_path() {
+        let coin = atom_hd_coin(StandardHDCoinAddress {
+            account: 1,
+            is_change: false,
+            address_index: 0,
+        });
+        assert_eq!(coin.account_id.to_string(), ACCOUNT1_ADDRESS0);
+    }
+
+    #[test]
+    fn test_internal_chain_not_supported() {
+        let coin = atom_hd_coin(StandardHDCoinAddress::default());
+        let account = create_account(&coin);
+
+        let error = block_on(coin.derive_address(&account, Bip44Chain::Internal, 0))
+            .err()
+            .expect("Internal addresses shouldn't be derived");
+        assert!(matches!(error.into_inner(), AddressDerivingError::InvalidBip44Chain {
+            chain: Bip44Chain::Internal
+        }));
+        account.known_addresses_number(Bip44Chain::Internal).unwrap_err();
+    }
+
+    #[test]
+    fn test_account_storage_item() {
+        let coin = atom_hd_coin(StandardHDCoinAddress::default());
+        let hd_wallet = coin.derivation_method.hd_wallet().unwrap();
+        let mut account = create_account(&coin);
+        account.external_addresses_number = 3;
+
+        let item = account.to_storage_item();
+        assert_eq!(item.account_id, 0);
+        assert_eq!(item.external_addresses_number, 3);
+        assert_eq!(item.internal_addresses_number, 0);
+
+        let restored = TendermintHDAccount::try_from_storage_item(&hd_wallet.derivation_path, &item).unwrap();
+        assert_eq!(restored.account_id, account.account_id);
+        assert_eq!(restored.account_derivation_path, account.account_derivation_path);
+        assert_eq!(restored.external_addresses_number, 3);
+        assert_eq!(
+            restored.extended_pubkey.to_string(bip32::Prefix::XPUB),
+            item.account_xpub
+        );
+
+        // the account is stored once it's created
+        let stored = block_on(hd_wallet.hd_wallet_storage.load_all_accounts()).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].account_xpub, item.account_xpub);
+    }
+
+    #[test]
+    fn test_withdraw_sender() {
+        let coin = atom_hd_coin(StandardHDCoinAddress::default());
+        let hd_wallet = coin.derivation_method.hd_wallet().unwrap();
+        {
+            let mut account = block_on(coin.create_new_account(hd_wallet, &SeedXPubExtractor))
+                .unwrap_or_else(|e| panic!("Error creating account: {}", e));
+            block_on(coin.set_known_addresses_number(hd_wallet, &mut account, Bip44Chain::External, 2))
+                .unwrap_or_else(|e| panic!("Error setting known addresses: {}", e));
+        }
+
+        let (address, _) = block_on(coin.withdraw_sender(None)).unwrap();
+        assert_eq!(address.to_string(), ACCOUNT0_ADDRESS0);
+
+        let from = WithdrawFrom::DerivationPath {
+            derivation_path: "m/44'/118'/0'/0/1".to_owned(),
+        };
+        let (address, priv_key) = block_on(coin.withdraw_sender(Some(&from))).unwrap();
+        assert_eq!(address.to_string(), ACCOUNT0_ADDRESS1);
+        assert_eq!(account_id_from_privkey(priv_key.as_slice(), "cosmos").unwrap(), address);
+
+        // the address hasn't been activated yet
+        let from = WithdrawFrom::DerivationPath {
+            derivation_path: "m/44'/118'/0'/0/2".to_owned(),
+        };
+        let error = block_on(coin.withdraw_sender(Some(&from))).unwrap_err().into_inner();
+        assert!(matches!(error, WithdrawError::UnexpectedFromAddress(_)), "{:?}", error);
+
+        // the coin type is different
+        let from = WithdrawFrom::DerivationPath {
+            derivation_path: "m/44'/60'/0'/0/0".to_owned(),
+        };
+        let error = block_on(coin.withdraw_sender(Some(&from))).unwrap_err().into_inner();
+        assert!(matches!(error, WithdrawError::UnexpectedFromAddress(_)), "{:?}", error);
+
+        let from = WithdrawFrom::HDWalletAddress(StandardHDCoinAddress {
+            account: 1,
+            is_change: false,
+            address_index: 0,
+        });
+        let error = block_on(coin.withdraw_sender(Some(&from))).unwrap_err().into_inner();
+        assert!(
+            matches!(error, WithdrawError::UnknownAccount { account_id: 1 }),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_trezor_not_supported() {
+        let conf = atom_conf();
+        let error = tendermint_priv_key_policy(&conf, "ATOM", PrivKeyBuildPolicy::Trezor, Default::default())
+            .err()
+            .expect("Trezor shouldn't be supported");
+        assert!(matches!(
+            error.into_inner().kind,
+            TendermintInitErrorKind::PrivKeyPolicyNotAllowed(PrivKeyPolicyNotAllowed::HardwareWalletNotSupported)
+        ));
+
+        let error = block_on(SeedXPubExtractor.extract_utxo_xpub("ATOM".to_owned(), DerivationPath::default()))
+            .err()
+            .expect("Trezor shouldn't be supported");
+        assert!(matches!(
+            error.into_inner(),
+            HDExtractPubkeyError::CoinDoesntSupportTrezor
+        ));
+    }
+}
@@ -0,0 +1,929 @@
+//! Cosmos SDK staking: delegating to validators, undelegating, redelegating and claiming the staking rewards.
+
+use super::rpc::*;
+use super::type_urls::{BEGIN_REDELEGATE_TYPE_URL, DELEGATE_TYPE_URL, UNDELEGATE_TYPE_URL,
+                       WITHDRAW_DELEGATOR_REWARD_TYPE_URL};
+use super::{TendermintCoin, TendermintCoinRpcError, TendermintCommons, TendermintFeeDetails, ABCI_REQUEST_HEIGHT,
+            ABCI_REQUEST_PROVE, MIN_TX_SATOSHIS, TIMEOUT_HEIGHT_DELTA, TX_DEFAULT_MEMO};
+use crate::rpc_command::tendermint::{TendermintClaimRewardsRequest, TendermintRedelegateRequest, TendermintValidator,
+                                     ValidatorStatus, ValidatorStatusFilter};
+use crate::utxo::sat_from_big_decimal;
+use crate::{big_decimal_from_sat_unsigned, DelegationError, DelegationResult, MarketCoinOps, PrivKeyPolicyNotAllowed,
+            StakingInfos, StakingInfosError, StakingInfosResult, TransactionDetails, TransactionType, TxFeeDetails,
+            WithdrawFee};
+use bitcrypto::sha256;
+use common::Future01CompatExt;
+use cosmrs::proto::cosmos::base::query::v1beta1::{PageRequest, PageResponse};
+use cosmrs::proto::cosmos::base::v1beta1::{Coin as CoinProto, DecCoin};
+use cosmrs::proto::cosmos::distribution::v1beta1::{MsgWithdrawDelegatorReward, QueryDelegationTotalRewardsRequest,
+                                                   QueryDelegationTotalRewardsResponse};
+use cosmrs::proto::cosmos::staking::v1beta1::{DelegationResponse, MsgBeginRedelegate, MsgDelegate, MsgUndelegate,
+                                              QueryDelegatorDelegationsRequest, QueryDelegatorDelegationsResponse,
+                                              QueryDelegatorUnbondingDelegationsRequest,
+                                              QueryDelegatorUnbondingDelegationsResponse, QueryValidatorsRequest,
+                                              QueryValidatorsResponse, UnbondingDelegation, Validator};
+use cosmrs::tx::Fee;
+use cosmrs::{AccountId, Any, Coin};
+use mm2_err_handle::prelude::*;
+use mm2_number::{BigDecimal, BigInt};
+use prost::Message;
+use std::str::FromStr;
+
+// ABCI Request Paths
+const ABCI_QUERY_VALIDATORS_PATH: &str = "/cosmos.staking.v1beta1.Query/Validators";
+const ABCI_QUERY_DELEGATOR_DELEGATIONS_PATH: &str = "/cosmos.staking.v1beta1.Query/DelegatorDelegations";
+const ABCI_QUERY_DELEGATOR_UNBONDING_DELEGATIONS_PATH: &str =
+    "/cosmos.staking.v1beta1.Query/DelegatorUnbondingDelegations";
+const ABCI_QUERY_DELEGATION_TOTAL_REWARDS_PATH: &str = "/cosmos.distribution.v1beta1.Query/DelegationTotalRewards";
+
+/// Redelegations and undelegations with many unbonding entries take noticeably more gas than bank transfers.
+pub(crate) const STAKING_GAS_LIMIT_DEFAULT: u64 = 400_000;
+/// `sdk.Dec` values are encoded in protobuf as integers multiplied by 10^18.
+const SDK_DEC_PRECISION: i64 = 18;
+/// The Cosmos SDK converts the gRPC `NotFound` status of the queries sent over ABCI to `ErrKeyNotFound`.
+const SDK_ROOT_CODESPACE: &str = "sdk";
+const SDK_ERR_KEY_NOT_FOUND_CODE: u32 = 38;
+/// The suffix of the validator operator address prefix, e.g. `cosmosvaloper` for the `cosmos` account prefix.
+const VALIDATOR_PREFIX_SUFFIX: &str = "valoper";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CosmosDelegationRequest {
+    /// The validator operator address, e.g. `cosmosvaloper1...`.
+    pub validator_address: String,
+    #[serde(default)]
+    pub amount: BigDecimal,
+    /// Delegate the whole balance or undelegate everything delegated to the validator.
+    #[serde(default)]
+    pub max: bool,
+    pub memo: Option<String>,
+    pub fee: Option<WithdrawFee>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CosmosStakingInfosDetails {
+    pub total_delegated: BigDecimal,
+    /// The rewards in the platform coin that can be claimed from all the validators.
+    pub total_rewards: BigDecimal,
+    pub delegations: Vec<CosmosDelegation>,
+    pub unbondings: Vec<CosmosUnbonding>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CosmosDelegation {
+    pub validator_address: String,
+    pub amount: BigDecimal,
+    pub rewards: BigDecimal,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CosmosUnbonding {
+    pub validator_address: String,
+    pub amount: BigDecimal,
+    pub creation_height: i64,
+    /// The timestamp in seconds when the coins become available.
+    pub completion_time: Option<i64>,
+}
+
+impl From<TendermintCoinRpcError> for DelegationError {
+    fn from(err: TendermintCoinRpcError) -> Self {
+        match err {
+            TendermintCoinRpcError::InvalidResponse(e)
+            | TendermintCoinRpcError::PerformError(e)
+            | TendermintCoinRpcError::RpcClientError(e) => DelegationError::Transport(e),
+            TendermintCoinRpcError::Prost(e) => DelegationError::Transport(e.to_string()),
+            TendermintCoinRpcError::InternalError(e) => DelegationError::InternalError(e),
+        }
+    }
+}
+
+impl From<TendermintCoinRpcError> for StakingInfosError {
+    fn from(err: TendermintCoinRpcError) -> Self {
+        match err {
+            TendermintCoinRpcError::InvalidResponse(e)
+            | TendermintCoinRpcError::PerformError(e)
+            | TendermintCoinRpcError::RpcClientError(e) => StakingInfosError::Transport(e),
+            TendermintCoinRpcError::Prost(e) => StakingInfosError::Transport(e.to_string()),
+            TendermintCoinRpcError::InternalError(e) => StakingInfosError::Internal(e),
+        }
+    }
+}
+
+/// The fee of a staking or governance transaction calculated by simulating it.
+pub(super) struct MsgTxFee {
+    fee: Fee,
+    pub(super) fee_details: TendermintFeeDetails,
+    timeout_height: u64,
+}
+
+/// The parts of [`TransactionDetails`] that differ between the staking and governance operations.
+pub(super) struct MsgTxBalanceChange {
+    pub(super) to: String,
+    pub(super) total_amount: BigDecimal,
+    pub(super) spent_by_me: BigDecimal,
+    pub(super) received_by_me: BigDecimal,
+    pub(super) transaction_type: TransactionType,
+}
+
+impl TendermintCoin {
+    pub async fn delegate(&self, req: CosmosDelegationRequest) -> DelegationResult {
+        let validator = self.validator_account_id(&req.validator_address)?;
+        let (balance_u64, balance_dec) = self
+            .get_balance_as_unsigned_and_decimal(&self.account_id, &self.denom, self.decimals)
+            .await?;
+        let amount_u64 = if req.max {
+            balance_u64
+        } else {
+            sat_from_big_decimal(&req.amount, self.decimals)?
+        };
+        self.check_staking_amount(amount_u64)?;
+
+        let memo = req.memo.unwrap_or_else(|| TX_DEFAULT_MEMO.into());
+        let msg = self.delegate_msg(&validator, amount_u64);
+        let tx_fee = self.calculate_msg_fee::<DelegationError>(msg, &memo, req.fee).await?;
+        let fee_u64 = tx_fee.fee_details.uamount;
+
+        let amount_u64 = if req.max {
+            let amount_u64 = balance_u64.checked_sub(fee_u64).unwrap_or_default();
+            self.check_staking_amount(amount_u64)?;
+            amount_u64
+        } else {
+            let required = big_decimal_from_sat_unsigned(amount_u64, self.decimals) + &tx_fee.fee_details.amount;
+            if balance_dec < required {
+                return MmError::err(DelegationError::NotSufficientBalance {
+                    coin: self.ticker().to_owned(),
+                    available: balance_dec,
+                    required,
+                });
+            }
+            amount_u64
+        };
+
+        let msg = self.delegate_msg(&validator, amount_u64);
+        let spent_by_me = big_decimal_from_sat_unsigned(amount_u64 + fee_u64, self.decimals);
+        let balance_change = MsgTxBalanceChange {
+            to: validator.to_string(),
+            total_amount: spent_by_me.clone(),
+            spent_by_me,
+            received_by_me: BigDecimal::default(),
+            transaction_type: TransactionType::StakingDelegation,
+        };
+        self.sign_msg_tx(msg, memo, tx_fee, balance_change).await
+    }
+
+    pub async fn undelegate(&self, req: CosmosDelegationRequest) -> DelegationResult {
+        let validator = self.validator_account_id(&req.validator_address)?;
+        let amount_u64 = self.amount_to_move_from(&validator, &req.amount, req.max).await?;
+
+        let memo = req.memo.unwrap_or_else(|| TX_DEFAULT_MEMO.into());
+        let msg = self.proto_to_any(UNDELEGATE_TYPE_URL, MsgUndelegate {
+            delegator_address: self.account_id.to_string(),
+            validator_address: validator.to_string(),
+            amount: Some(self.platform_coin_proto(amount_u64)),
+        });
+        let tx_fee = self
+            .calculate_msg_fee::<DelegationError>(msg.clone(), &memo, req.fee)
+            .await?;
+        self.check_balance_for_staking_fee(&tx_fee).await?;
+
+        // The undelegated coins are returned to the balance once the unbonding period ends.
+        let balance_change = MsgTxBalanceChange {
+            to: validator.to_string(),
+            total_amount: big_decimal_from_sat_unsigned(amount_u64, self.decimals),
+            spent_by_me: tx_fee.fee_details.amount.clone(),
+            received_by_me: BigDecimal::default(),
+            transaction_type: TransactionType::RemoveDelegation,
+        };
+        self.sign_msg_tx(msg, memo, tx_fee, balance_change).await
+    }
+
+    pub async fn redelegate(&self, req: TendermintRedelegateRequest) -> DelegationResult {
+        let validator_src = self.validator_account_id(&req.validator_src_address)?;
+        let validator_dst = self.validator_account_id(&req.validator_dst_address)?;
+        let amount_u64 = self.amount_to_move_from(&validator_src, &req.amount, req.max).await?;
+
+        let memo = req.memo.unwrap_or_else(|| TX_DEFAULT_MEMO.into());
+        let msg = self.proto_to_any(BEGIN_REDELEGATE_TYPE_URL, MsgBeginRedelegate {
+            delegator_address: self.account_id.to_string(),
+            validator_src_address: validator_src.to_string(),
+            validator_dst_address: validator_dst.to_string(),
+            amount: Some(self.platform_coin_proto(amount_u64)),
+        });
+        let tx_fee = self
+            .calculate_msg_fee::<DelegationError>(msg.clone(), &memo, req.fee)
+            .await?;
+        self.check_balance_for_staking_fee(&tx_fee).await?;
+
+        let balance_change = MsgTxBalanceChange {
+            to: validator_dst.to_string(),
+            total_amount: big_decimal_from_sat_unsigned(amount_u64, self.decimals),
+            spent_by_me: tx_fee.fee_details.amount.clone(),
+            received_by_me: BigDecimal::default(),
+            transaction_type: TransactionType::StakingDelegation,
+        };
+        self.sign_msg_tx(msg, memo, tx_fee, balance_change).await
+    }
+
+    pub async fn claim_delegation_rewards(&self, req: TendermintClaimRewardsRequest) -> DelegationResult {
+        let validator = self.validator_account_id(&req.validator_address)?;
+        let rewards = self.query_delegation_rewards().await?;
+        let reward = rewards
+            .rewards
+            .iter()
+            .find(|reward| reward.validator_address == validator.to_string())
+            .map(|reward| self.platform_dec_coins_amount(&reward.reward))
+            .transpose()?
+            .unwrap_or_default();
+        // Only the integer part of the rewards is withdrawn, the remainder stays with the validator.
+        let reward = reward.with_scale(self.decimals as i64);
+        let min_tx_amount = big_decimal_from_sat_unsigned(MIN_TX_SATOSHIS as u64, self.decimals);
+        if reward < min_tx_amount {
+            return MmError::err(DelegationError::AmountTooLow {
+                amount: reward,
+                threshold: min_tx_amount,
+            });
+        }
+
+        let memo = req.memo.unwrap_or_else(|| TX_DEFAULT_MEMO.into());
+        let msg = self.proto_to_any(WITHDRAW_DELEGATOR_REWARD_TYPE_URL, MsgWithdrawDelegatorReward {
+            delegator_address: self.account_id.to_string(),
+            validator_address: validator.to_string(),
+        });
+        let tx_fee = self
+            .calculate_msg_fee::<DelegationError>(msg.clone(), &memo, req.fee)
+            .await?;
+        self.check_balance_for_staking_fee(&tx_fee).await?;
+
+        let balance_change = MsgTxBalanceChange {
+            to: self.account_id.to_string(),
+            total_amount: reward.clone(),
+            spent_by_me: tx_fee.fee_details.amount.clone(),
+            received_by_me: reward,
+            transaction_type: TransactionType::ClaimDelegationRewards,
+        };
+        self.sign_msg_tx(msg, memo, tx_fee, balance_change).await
+    }
+
+    pub async fn get_staking_infos(&self) -> StakingInfosResult {
+        let delegations = self.query_delegations().await?;
+        let unbondings = self.query_unbonding_delegations().await?;
+        let rewards = self.query_delegation_rewards().await?;
+
+        let staking_infos = self.staking_infos_from_responses(delegations, unbondings, rewards)?;
+        Ok(StakingInfos {
+            staking_infos_details: staking_infos.into(),
+        })
+    }
+
+    /// Collects the delegations, unbonding entries and the pending rewards from the query responses.
+    fn staking_infos_from_responses(
+        &self,
+        delegations: Vec<DelegationResponse>,
+        unbondings: Vec<UnbondingDelegation>,
+        rewards: QueryDelegationTotalRewardsResponse,
+    ) -> MmResult<CosmosStakingInfosDetails, StakingInfosError> {
+        let mut total_delegated = BigDecimal::default();
+        let mut cosmos_delegations = Vec::with_capacity(delegations.len());
+        for delegation_response in delegations {
+            let validator_address = delegation_response
+                .delegation
+                .map(|delegation| delegation.validator_address)
+                .or_mm_err(|| StakingInfosError::Transport("Delegation is None".to_owned()))?;
+            let amount = match delegation_response.balance {
+                Some(balance) => self.platform_int_amount(&balance.amount)?,
+                None => BigDecimal::default(),
+            };
+            let rewards = rewards
+                .rewards
+                .iter()
+                .find(|reward| reward.validator_address == validator_address)
+                .map(|reward| self.platform_dec_coins_amount(&reward.reward))
+                .transpose()?
+                .unwrap_or_default();
+            total_delegated += &amount;
+            cosmos_delegations.push(CosmosDelegation {
+                validator_address,
+                amount,
+                rewards,
+            });
+        }
+
+        let mut cosmos_unbondings = Vec::new();
+        for unbonding in unbondings {
+            for entry in unbonding.entries {
+                cosmos_unbondings.push(CosmosUnbonding {
+                    validator_address: unbonding.validator_address.clone(),
+                    amount: self.platform_int_amount(&entry.balance)?,
+                    creation_height: entry.creation_height,
+                    completion_time: entry.completion_time.map(|time| time.seconds),
+                });
+            }
+        }
+
+        Ok(CosmosStakingInfosDetails {
+            total_delegated,
+            total_rewards: self.platform_dec_coins_amount(&rewards.total)?,
+            delegations: cosmos_delegations,
+            unbondings: cosmos_unbondings,
+        })
+    }
+
+    pub async fn validators(
+        &self,
+        filter_by_status: ValidatorStatusFilter,
+    ) -> MmResult<Vec<TendermintValidator>, StakingInfosError> {
+        let status = filter_by_status.as_bond_status().to_owned();
+        let validators: Vec<Validator> = self
+            .query_all_pages(
+                ABCI_QUERY_VALIDATORS_PATH,
+                |pagination| QueryValidatorsRequest {
+                    status: status.clone(),
+                    pagination,
+                },
+                |response: QueryValidatorsResponse| (response.validators, response.pagination),
+            )
+            .await?;
+
+        validators
+            .into_iter()
+            .map(|validator| -> MmResult<TendermintValidator, StakingInfosError> {
+                let description = validator.description.unwrap_or_default();
+                let commission_rate = validator
+                    .commission
+                    .and_then(|commission| commission.commission_rates)
+                    .map(|rates| sdk_dec_from_str(&rates.rate, 0))
+                    .transpose()?
+                    .unwrap_or_default();
+                Ok(TendermintValidator {
+                    tokens: self.platform_int_amount(&validator.tokens)?,
+                    operator_address: validator.operator_address,
+                    moniker: description.moniker,
+                    website: description.website,
+                    jailed: validator.jailed,
+                    status: ValidatorStatus::from(validator.status),
+                    commission_rate,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that the address is a validator operator address of this chain.
+    fn validator_account_id(&self, address: &str) -> MmResult<AccountId, DelegationError> {
+        let validator = AccountId::from_str(address).map_to_mm(|e| DelegationError::AddressError(e.to_string()))?;
+        let expected_prefix = format!("{}{}", self.account_prefix, VALIDATOR_PREFIX_SUFFIX);
+        if validator.prefix() != expected_prefix {
+            return MmError::err(DelegationError::AddressError(format!(
+                "expected {} validator address prefix",
+                expected_prefix
+            )));
+        }
+        Ok(validator)
+    }
+
+    fn check_staking_amount(&self, amount_u64: u64) -> MmResult<(), DelegationError> {
+        if amount_u64 < MIN_TX_SATOSHIS as u64 {
+            return MmError::err(DelegationError::AmountTooLow {
+                amount: big_decimal_from_sat_unsigned(amount_u64, self.decimals),
+                threshold: self.min_tx_amount(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the amount to undelegate or redelegate checking that it doesn't exceed the delegated one.
+    async fn amount_to_move_from(
+        &self,
+        validator: &AccountId,
+        amount: &BigDecimal,
+        max: bool,
+    ) -> MmResult<u64, DelegationError> {
+        let validator_address = validator.to_string();
+        let delegated_u64 = match self
+            .query_delegations()
+            .await?
+            .into_iter()
+            .find(|response| {
+                response
+                    .delegation
+                    .as_ref()
+                    .map_or(false, |delegation| delegation.validator_address == validator_address)
+            })
+            .and_then(|response| response.balance)
+        {
+            Some(balance) => balance
+                .amount
+                .parse()
+                .map_to_mm(|e| DelegationError::Transport(format!("delegated amount is not u64, err {}", e)))?,
+            None => 0,
+        };
+
+        let amount_u64 = if max {
+            delegated_u64
+        } else {
+            sat_from_big_decimal(amount, self.decimals)?
+        };
+        if amount_u64 > delegated_u64 {
+            return MmError::err(DelegationError::NotSufficientDelegation {
+                validator: validator_address,
+                available: big_decimal_from_sat_unsigned(delegated_u64, self.decimals),
+                required: big_decimal_from_sat_unsigned(amount_u64, self.decimals),
+            });
+        }
+        self.check_staking_amount(amount_u64)?;
+        Ok(amount_u64)
+    }
+
+    async fn check_balance_for_staking_fee(&self, tx_fee: &MsgTxFee) -> MmResult<(), DelegationError> {
+        let (balance_u64, balance_dec) = self
+            .get_balance_as_unsigned_and_decimal(&self.account_id, &self.denom, self.decimals)
+            .await?;
+        if balance_u64 < tx_fee.fee_details.uamount {
+            return MmError::err(DelegationError::NotSufficientBalance {
+                coin: self.ticker().to_owned(),
+                available: balance_dec,
+                required: tx_fee.fee_details.amount.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn delegate_msg(&self, validator: &AccountId, amount_u64: u64) -> Any {
+        self.proto_to_any(DELEGATE_TYPE_URL, MsgDelegate {
+            delegator_address: self.account_id.to_string(),
+            validator_address: validator.to_string(),
+            amount: Some(self.platform_coin_proto(amount_u64)),
+        })
+    }
+
+    fn platform_coin_proto(&self, amount_u64: u64) -> CoinProto {
+        CoinProto {
+            denom: self.denom.to_string(),
+            amount: amount_u64.to_string(),
+        }
+    }
+
+    pub(super) fn proto_to_any<M: Message>(&self, type_url: &str, msg: M) -> Any {
+        Any {
+            type_url: type_url.to_owned(),
+            value: msg.encode_to_vec(),
+        }
+    }
+
+    pub(super) async fn calculate_msg_fee<E>(
+        &self,
+        msg: Any,
+        memo: &str,
+        withdraw_fee: Option<WithdrawFee>,
+    ) -> MmResult<MsgTxFee, E>
+    where
+        E: From<TendermintCoinRpcError> + From<PrivKeyPolicyNotAllowed> + NotMmError,
+    {
+        let priv_key = self.priv_key_policy.activated_key_or_err()?;
+        let current_block = self
+            .current_block()
+            .compat()
+            .await
+            .map_to_mm(TendermintCoinRpcError::PerformError)?;
+        let timeout_height = current_block + TIMEOUT_HEIGHT_DELTA;
+
+        let fee_amount_u64 = self
+            .calculate_account_fee_amount_as_u64(
+                &self.account_id,
+                priv_key,
+                msg,
+                timeout_height,
+                memo.to_owned(),
+                withdraw_fee.clone(),
+            )
+            .await?;
+        Ok(self.msg_tx_fee(fee_amount_u64, &withdraw_fee, timeout_height))
+    }
+
+    /// Builds the fee of the simulated `fee_amount_u64`, the gas limit is taken from `withdraw_fee` if it's set.
+    pub(super) fn msg_tx_fee(
+        &self,
+        fee_amount_u64: u64,
+        withdraw_fee: &Option<WithdrawFee>,
+        timeout_height: u64,
+    ) -> MsgTxFee {
+        let (_, gas_limit) = self.gas_info_for_withdraw(withdraw_fee, STAKING_GAS_LIMIT_DEFAULT);
+        let fee_amount = Coin {
+            denom: self.denom.clone(),
+            amount: fee_amount_u64.into(),
+        };
+
+        MsgTxFee {
+            fee: Fee::from_amount_and_gas(fee_amount, gas_limit),
+            fee_details: TendermintFeeDetails {
+                coin: self.ticker().to_owned(),
+                amount: big_decimal_from_sat_unsigned(fee_amount_u64, self.decimals),
+                uamount: fee_amount_u64,
+                gas_limit,
+            },
+            timeout_height,
+        }
+    }
+
+    /// Signs the transaction, it should be sent to the `send_raw_transaction` RPC to broadcast.
+    pub(super) async fn sign_msg_tx<E>(
+        &self,
+        msg: Any,
+        memo: String,
+        tx_fee: MsgTxFee,
+        balance_change: MsgTxBalanceChange,
+    ) -> MmResult<TransactionDetails, E>
+    where
+        E: From<TendermintCoinRpcError> + From<PrivKeyPolicyNotAllowed> + NotMmError,
+    {
+        let priv_key = self.priv_key_policy.activated_key_or_err()?;
+        let account_info = self.account_info(&self.account_id).await?;
+        let tx_raw = self
+            .any_to_signed_raw_tx(
+                priv_key,
+                account_info,
+                msg,
+                tx_fee.fee,
+                tx_fee.timeout_height,
+                memo.clone(),
+            )
+            .map_to_mm(|e| TendermintCoinRpcError::InternalError(e.to_string()))?;
+        let tx_bytes = tx_raw
+            .to_bytes()
+            .map_to_mm(|e| TendermintCoinRpcError::InternalError(e.to_string()))?;
+
+        let hash = sha256(&tx_bytes);
+        Ok(TransactionDetails {
+            tx_hash: hex::encode_upper(hash.as_slice()),
+            tx_hex: tx_bytes.into(),
+            from: vec![self.account_id.to_string()],
+            to: vec![balance_change.to],
+            my_balance_change: &balance_change.received_by_me - &balance_change.spent_by_me,
+            total_amount: balance_change.total_amount,
+            spent_by_me: balance_change.spent_by_me,
+            received_by_me: balance_change.received_by_me,
+            block_height: 0,
+            timestamp: 0,
+            fee_details: Some(TxFeeDetails::Tendermint(tx_fee.fee_details)),
+            coin: self.ticker().to_owned(),
+            internal_id: hash.to_vec().into(),
+            kmd_rewards: None,
+            transaction_type: balance_change.transaction_type,
+            memo: Some(memo),
+        })
+    }
+
+    async fn query_delegations(&self) -> MmResult<Vec<DelegationResponse>, TendermintCoinRpcError> {
+        let delegator_addr = self.account_id.to_string();
+        self.query_all_pages(
+            ABCI_QUERY_DELEGATOR_DELEGATIONS_PATH,
+            |pagination| QueryDelegatorDelegationsRequest {
+                delegator_addr: delegator_addr.clone(),
+                pagination,
+            },
+            |response: QueryDelegatorDelegationsResponse| (response.delegation_responses, response.pagination),
+        )
+        .await
+    }
+
+    async fn query_unbonding_delegations(&self) -> MmResult<Vec<UnbondingDelegation>, TendermintCoinRpcError> {
+        let delegator_addr = self.account_id.to_string();
+        self.query_all_pages(
+            ABCI_QUERY_DELEGATOR_UNBONDING_DELEGATIONS_PATH,
+            |pagination| QueryDelegatorUnbondingDelegationsRequest {
+                delegator_addr: delegator_addr.clone(),
+                pagination,
+            },
+            |response: QueryDelegatorUnbondingDelegationsResponse| (response.unbonding_responses, response.pagination),
+        )
+        .await
+    }
+
+    async fn query_delegation_rewards(&self) -> MmResult<QueryDelegationTotalRewardsResponse, TendermintCoinRpcError> {
+        let request = QueryDelegationTotalRewardsRequest {
+            delegator_address: self.account_id.to_string(),
+        };
+        self.abci_query(ABCI_QUERY_DELEGATION_TOTAL_REWARDS_PATH, request).await
+    }
+
+    /// Requests all the pages of a paginated query.
+    async fn query_all_pages<Req, Res, T, F, G>(
+        &self,
+        path: &str,
+        make_request: F,
+        into_page: G,
+    ) -> MmResult<Vec<T>, TendermintCoinRpcError>
+    where
+        Req: Message,
+        Res: Message + Default,
+        F: Fn(Option<PageRequest>) -> Req,
+        G: Fn(Res) -> (Vec<T>, Option<PageResponse>),
+    {
+        let mut items = Vec::new();
+        let mut pagination = None;
+        loop {
+            let response = self.abci_query(path, make_request(pagination)).await?;
+            let (page, page_response) = into_page(response);
+            items.extend(page);
+            match page_response {
+                Some(PageResponse { next_key, .. }) if !next_key.is_empty() => {
+                    pagination = Some(PageRequest {
+                        key: next_key,
+                        ..PageRequest::default()
+                    });
+                },
+                _ => break Ok(items),
+            }
+        }
+    }
+
+    pub(super) async fn abci_query<Req: Message, Res: Message + Default>(
+        &self,
+        path: &str,
+        request: Req,
+    ) -> MmResult<Res, TendermintCoinRpcError> {
+        self.abci_query_optional(path, request).await?.or_mm_err(|| {
+            TendermintCoinRpcError::InvalidResponse(format!("Query {} failed: the requested item is not found", path))
+        })
+    }
+
+    /// Returns `None` if the gRPC query has failed with the `NotFound` code, e.g. if the queried vote doesn't exist.
+    pub(super) async fn abci_query_optional<Req: Message, Res: Message + Default>(
+        &self,
+        path: &str,
+        request: Req,
+    ) -> MmResult<Option<Res>, TendermintCoinRpcError> {
+        let path = AbciPath::from_str(path).expect("valid path");
+        let request = AbciRequest::new(
+            Some(path),
+            request.encode_to_vec(),
+            ABCI_REQUEST_HEIGHT,
+            ABCI_REQUEST_PROVE,
+        );
+
+        let response = self.rpc_client().await?.perform(request).await?.response;
+        match response.code {
+            cosmrs::tendermint::abci::Code::Ok => Ok(Some(Res::decode(response.value.as_slice())?)),
+            cosmrs::tendermint::abci::Code::Err(code) if is_not_found_query_error(&response.codespace, code) => {
+                Ok(None)
+            },
+            cosmrs::tendermint::abci::Code::Err(code) => MmError::err(TendermintCoinRpcError::InvalidResponse(
+                format!("Query failed with code {}: {}", code, response.log),
+            )),
+        }
+    }
+
+    pub(super) fn platform_int_amount(&self, amount: &str) -> MmResult<BigDecimal, TendermintCoinRpcError> {
+        let amount = BigInt::from_str(amount)
+            .map_to_mm(|e| TendermintCoinRpcError::InvalidResponse(format!("Invalid amount '{}': {}", amount, e)))?;
+        Ok(BigDecimal::new(amount, self.decimals as i64))
+    }
+
+    /// Sums the amounts of the platform denom ignoring the rewards paid in other denoms.
+    fn platform_dec_coins_amount(&self, coins: &[DecCoin]) -> MmResult<BigDecimal, TendermintCoinRpcError> {
+        let mut total = BigDecimal::default();
+        for coin in coins.iter().filter(|coin| coin.denom == self.denom.as_ref()) {
+            total += sdk_dec_from_str(&coin.amount, self.decimals)?;
+        }
+        Ok(total)
+    }
+}
+
+/// Converts `sdk.Dec` protobuf representation to the decimal amount of the coin with the given decimals.
+pub(super) fn sdk_dec_from_str(dec: &str, decimals: u8) -> MmResult<BigDecimal, TendermintCoinRpcError> {
+    let dec_int = BigInt::from_str(dec)
+        .map_to_mm(|e| TendermintCoinRpcError::InvalidResponse(format!("Invalid decimal '{}': {}", dec, e)))?;
+    Ok(BigDecimal::new(dec_int, SDK_DEC_PRECISION + decimals as i64))
+}
+
+/// Converts the decimal to `sdk.Dec` protobuf representation.
+pub(super) fn sdk_dec_to_string(dec: &BigDecimal) -> String {
+    let scaled = dec.with_scale(SDK_DEC_PRECISION);
+    let (dec_int, _scale) = scaled.as_bigint_and_exponent();
+    dec_int.to_string()
+}
+
+fn is_not_found_query_error(codespace: &str, code: u32) -> bool {
+    codespace == SDK_ROOT_CODESPACE && code == SDK_ERR_KEY_NOT_FOUND_CODE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tendermint::tendermint_coin_tests::iris_coin_for_test;
+    use cosmrs::proto::cosmos::distribution::v1beta1::DelegationDelegatorReward;
+    use cosmrs::proto::cosmos::staking::v1beta1::{Delegation, UnbondingDelegationEntry};
+
+    fn validator_address(coin: &TendermintCoin, seed: u8) -> String {
+        AccountId::new(
+            &format!("{}{}", coin.account_prefix, VALIDATOR_PREFIX_SUFFIX),
+            &[seed; 20],
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    fn dec_coin(denom: &str, amount: &str) -> DecCoin {
+        DecCoin {
+            denom: denom.to_owned(),
+            amount: amount.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_sdk_dec_conversion() {
+        // 1.5 micro units with 18 digits precision
+        let dec = sdk_dec_from_str("1500000000000000000", 6).unwrap();
+        assert_eq!(dec, BigDecimal::from_str("0.0000015").unwrap());
+        let dec = sdk_dec_from_str("0", 6).unwrap();
+        assert_eq!(dec, BigDecimal::default());
+        sdk_dec_from_str("1.5", 6).unwrap_err();
+
+        let rate = BigDecimal::from_str("0.05").unwrap();
+        assert_eq!(sdk_dec_to_string(&rate), "50000000000000000");
+        assert_eq!(sdk_dec_from_str(&sdk_dec_to_string(&rate), 0).unwrap(), rate);
+    }
+
+    #[test]
+    fn test_delegate_msg() {
+        let coin = iris_coin_for_test();
+        let validator = AccountId::from_str(&validator_address(&coin, 1)).unwrap();
+
+        let msg = coin.delegate_msg(&validator, 1000);
+        assert_eq!(msg.type_url, DELEGATE_TYPE_URL);
+        let decoded = MsgDelegate::decode(msg.value.as_slice()).unwrap();
+        assert_eq!(decoded.delegator_address, coin.account_id.to_string());
+        assert_eq!(decoded.validator_address, validator.to_string());
+        assert_eq!(
+            decoded.amount,
+            Some(CoinProto {
+                denom: "unyan".to_owned(),
+                amount: "1000".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validator_account_id() {
+        let coin = iris_coin_for_test();
+
+        let validator = validator_address(&coin, 1);
+        assert_eq!(coin.validator_account_id(&validator).unwrap().to_string(), validator);
+
+        // an account address instead of the validator one
+        let error = coin
+            .validator_account_id(&coin.account_id.to_string())
+            .unwrap_err()
+            .into_inner();
+        assert!(matches!(error, DelegationError::AddressError(_)), "{:?}", error);
+        // a validator of another chain
+        let cosmos_validator = AccountId::new("cosmosvaloper", &[1; 20]).unwrap().to_string();
+        coin.validator_account_id(&cosmos_validator).unwrap_err();
+        coin.validator_account_id("invalid").unwrap_err();
+    }
+
+    #[test]
+    fn test_check_staking_amount() {
+        let coin = iris_coin_for_test();
+        coin.check_staking_amount(1).unwrap();
+        match coin.check_staking_amount(0).unwrap_err().into_inner() {
+            DelegationError::AmountTooLow { amount, threshold } => {
+                assert_eq!(amount, BigDecimal::default());
+                assert_eq!(threshold, coin.min_tx_amount());
+            },
+            e => panic!("Unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_platform_dec_coins_amount() {
+        let coin = iris_coin_for_test();
+        let coins = vec![
+            dec_coin("unyan", "1000000000000000000000"),
+            // rewards in other denoms are ignored
+            dec_coin(
+                "ibc/5C465997B4F582F602CD64E12031C6A6E18CAF1E6EDC9B5D808822DC0B5F850C",
+                "5000000000000000000",
+            ),
+            dec_coin("unyan", "500000000000000000"),
+        ];
+        assert_eq!(
+            coin.platform_dec_coins_amount(&coins).unwrap(),
+            BigDecimal::from_str("0.0010005").unwrap()
+        );
+        assert_eq!(coin.platform_dec_coins_amount(&[]).unwrap(), BigDecimal::default());
+        coin.platform_dec_coins_amount(&[dec_coin("unyan", "abc")]).unwrap_err();
+    }
+
+    #[test]
+    fn test_staking_infos_from_responses() {
+        let coin = iris_coin_for_test();
+        let validator_1 = validator_address(&coin, 1);
+        let validator_2 = validator_address(&coin, 2);
+
+        let delegation = |validator: &str, amount: &str| DelegationResponse {
+            delegation: Some(Delegation {
+                delegator_address: coin.account_id.to_string(),
+                validator_address: validator.to_owned(),
+                shares: format!("{}000000000000000000", amount),
+            }),
+            balance: Some(CoinProto {
+                denom: "unyan".to_owned(),
+                amount: amount.to_owned(),
+            }),
+        };
+        let delegations = vec![delegation(&validator_1, "1500000"), delegation(&validator_2, "250")];
+
+        let mut entry = UnbondingDelegationEntry {
+            creation_height: 100,
+            initial_balance: "2000000".to_owned(),
+            balance: "1000000".to_owned(),
+            ..Default::default()
+        };
+        entry.completion_time = Some(Default::default());
+        entry.completion_time.as_mut().unwrap().seconds = 1700000000;
+        let unbondings = vec![UnbondingDelegation {
+            delegator_address: coin.account_id.to_string(),
+            validator_address: validator_2.clone(),
+            entries: vec![entry, UnbondingDelegationEntry {
+                creation_height: 200,
+                balance: "3".to_owned(),
+                ..Default::default()
+            }],
+        }];
+
+        // there are no rewards from the second validator yet
+        let rewards = QueryDelegationTotalRewardsResponse {
+            rewards: vec![DelegationDelegatorReward {
+                validator_address: validator_1.clone(),
+                reward: vec![dec_coin("unyan", "1234500000000000000000")],
+            }],
+            total: vec![dec_coin("unyan", "1234500000000000000000")],
+        };
+
+        let actual = coin
+            .staking_infos_from_responses(delegations, unbondings, rewards)
+            .unwrap();
+        let expected = CosmosStakingInfosDetails {
+            total_delegated: BigDecimal::from_str("1.50025").unwrap(),
+            total_rewards: BigDecimal::from_str("0.0012345").unwrap(),
+            delegations: vec![
+                CosmosDelegation {
+                    validator_address: validator_1,
+                    amount: BigDecimal::from_str("1.5").unwrap(),
+                    rewards: BigDecimal::from_str("0.0012345").unwrap(),
+                },
+                CosmosDelegation {
+                    validator_address: validator_2.clone(),
+                    amount: BigDecimal::from_str("0.00025").unwrap(),
+                    rewards: BigDecimal::default(),
+                },
+            ],
+            unbondings: vec![
+                CosmosUnbonding {
+                    validator_address: validator_2.clone(),
+                    amount: BigDecimal::from_str("1").unwrap(),
+                    creation_height: 100,
+                    completion_time: Some(1700000000),
+                },
+                CosmosUnbonding {
+                    validator_address: validator_2,
+                    amount: BigDecimal::from_str("0.000003").unwrap(),
+                    creation_height: 200,
+                    completion_time: None,
+                },
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_staking_infos_invalid_response() {
+        let coin = iris_coin_for_test();
+        let delegations = vec![DelegationResponse {
+            delegation: None,
+            balance: None,
+        }];
+        coin.staking_infos_from_responses(delegations, vec![], Default::default())
+            .unwrap_err();
+
+        let delegations = vec![DelegationResponse {
+            delegation: Some(Delegation {
+                validator_address: validator_address(&coin, 1),
+                ..Default::default()
+            }),
+            balance: Some(CoinProto {
+                denom: "unyan".to_owned(),
+                amount: "1.5".to_owned(),
+            }),
+        }];
+        coin.staking_infos_from_responses(delegations, vec![], Default::default())
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_is_not_found_query_error() {
+        assert!(is_not_found_query_error("sdk", 38));
+        // `ErrInvalidRequest` returned for the `InvalidArgument` status
+        assert!(!is_not_found_query_error("sdk", 18));
+        assert!(!is_not_found_query_error("gov", 38));
+    }
+}
@@ -5,7 +5,6 @@ use super::ibc::IBC_GAS_LIMIT_DEFAULT;
 use super::{TendermintCoin, TendermintFeeDetails, GAS_LIMIT_DEFAULT, MIN_TX_SATOSHIS, TIMEOUT_HEIGHT_DELTA,
             TX_DEFAULT_MEMO};
 use crate::rpc_command::tendermint::IBCWithdrawRequest;
-use crate::tendermint::account_id_from_privkey;
 use crate::utxo::utxo_common::big_decimal_from_sat;
 use crate::{big_decimal_from_sat_unsigned, utxo::sat_from_big_decimal, BalanceFut, BigDecimal,
             CheckIfMyPaymentSentArgs, CoinBalance, CoinFutSpawner, ConfirmPaymentInput, FeeApproxStage,
@@ -19,7 +18,7 @@ use crate::{big_decimal_from_sat_unsigned, utxo::sat_from_big_decimal, BalanceFu
             ValidateInstructionsErr, ValidateOtherPubKeyErr, ValidatePaymentError, ValidatePaymentFut,
             ValidatePaymentInput, VerificationResult, WaitForHTLCTxSpendArgs, WatcherOps,
             WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput, WatcherValidateTakerFeeInput,
-            WithdrawError, WithdrawFrom, WithdrawFut, WithdrawRequest};
+            WithdrawError, WithdrawFut, WithdrawRequest};
 use crate::{DexFee, MmCoinEnum, PaymentInstructionArgs, ValidateWatcherSpendInput, WatcherReward, WatcherRewardError};
 use async_trait::async_trait;
 use bitcrypto::sha256;
@@ -111,26 +110,7 @@ impl TendermintToken {
             let to_address =
                 AccountId::from_str(&req.to).map_to_mm(|e| WithdrawError::InvalidAddress(e.to_string()))?;
 
-            let (account_id, priv_key) = match req.from {
-                Some(WithdrawFrom::HDWalletAddress(ref path_to_address)) => {
-                    let priv_key = platform
-                        .priv_key_policy
-                        .hd_wallet_derived_priv_key_or_err(path_to_address)?;
-                    let account_id = account_id_from_privkey(priv_key.as_slice(), &platform.account_prefix)
-                        .map_err(|e| WithdrawError::InternalError(e.to_string()))?;
-                    (account_id, priv_key)
-                },
-                Some(WithdrawFrom::AddressId(_)) | Some(WithdrawFrom::DerivationPath { .. }) => {
-                    return MmError::err(WithdrawError::UnexpectedFromAddress(
-                        "Withdraw from 'AddressId' or 'DerivationPath' is not supported yet for Tendermint!"
-                            .to_string(),
-                    ))
-                },
-                None => (
-                    platform.account_id.clone(),
-                    *platform.priv_key_policy.activated_key_or_err()?,
-                ),
-            };
+            let (account_id, priv_key) = platform.withdraw_sender(req.from.as_ref()).await?;
 
             let (base_denom_balance, base_denom_balance_dec) = platform
                 .get_balance_as_unsigned_and_decimal(&account_id, &platform.denom, token.decimals())
@@ -281,6 +261,7 @@ impl SwapOps for TendermintToken {
     fn send_maker_payment(&self, maker_payment_args: SendPaymentArgs) -> TransactionFut {
         self.platform_coin.send_htlc_for_denom(
             maker_payment_args.time_lock_duration,
+            maker_payment_args.time_lock,
             maker_payment_args.other_pubkey,
             maker_payment_args.secret_hash,
             maker_payment_args.amount,
@@ -292,6 +273,7 @@ impl SwapOps for TendermintToken {
     fn send_taker_payment(&self, taker_payment_args: SendPaymentArgs) -> TransactionFut {
         self.platform_coin.send_htlc_for_denom(
             taker_payment_args.time_lock_duration,
+            taker_payment_args.time_lock,
             taker_payment_args.other_pubkey,
             taker_payment_args.secret_hash,
             taker_payment_args.amount,
@@ -311,12 +293,26 @@ impl SwapOps for TendermintToken {
     }
 
     async fn send_taker_refunds_payment(&self, taker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        if self.platform_coin.cosmwasm_htlc_contract().is_some() {
+            return self
+                .platform_coin
+                .send_cosmwasm_refund_htlc(taker_refunds_payment_args.payment_tx)
+                .await;
+        }
+
         Err(TransactionErr::Plain(
             "Doesn't need transaction broadcast to be refunded".into(),
         ))
     }
 
     async fn send_maker_refunds_payment(&self, maker_refunds_payment_args: RefundPaymentArgs<'_>) -> TransactionResult {
+        if self.platform_coin.cosmwasm_htlc_contract().is_some() {
+            return self
+                .platform_coin
+                .send_cosmwasm_refund_htlc(maker_refunds_payment_args.payment_tx)
+                .await;
+        }
+
         Err(TransactionErr::Plain(
             "Doesn't need transaction broadcast to be refunded".into(),
         ))
@@ -630,26 +626,7 @@ impl MmCoin for TendermintToken {
                 )));
             }
 
-            let (account_id, priv_key) = match req.from {
-                Some(WithdrawFrom::HDWalletAddress(ref path_to_address)) => {
-                    let priv_key = platform
-                        .priv_key_policy
-                        .hd_wallet_derived_priv_key_or_err(path_to_address)?;
-                    let account_id = account_id_from_privkey(priv_key.as_slice(), &platform.account_prefix)
-                        .map_err(|e| WithdrawError::InternalError(e.to_string()))?;
-                    (account_id, priv_key)
-                },
-                Some(WithdrawFrom::AddressId(_)) | Some(WithdrawFrom::DerivationPath { .. }) => {
-                    return MmError::err(WithdrawError::UnexpectedFromAddress(
-                        "Withdraw from 'AddressId' or 'DerivationPath' is not supported yet for Tendermint!"
-                            .to_string(),
-                    ))
-                },
-                None => (
-                    platform.account_id.clone(),
-                    *platform.priv_key_policy.activated_key_or_err()?,
-                ),
-            };
+            let (account_id, priv_key) = platform.withdraw_sender(req.from.as_ref()).await?;
 
             let (base_denom_balance, base_denom_balance_dec) = platform
                 .get_balance_as_unsigned_and_decimal(&account_id, &platform.denom, token.decimals())
@@ -1,4 +1,5 @@
-use super::{rpc::*, AllBalancesResult, TendermintCoin, TendermintCommons, TendermintToken};
+use super::type_urls::IBC_TRANSFER_TYPE_URL;
+use super::{rpc::*, AllBalancesResult, IBCTransferStatus, TendermintCoin, TendermintCommons, TendermintToken};
 
 use crate::my_tx_history_v2::{CoinWithTxHistoryV2, MyTxHistoryErrorV2, MyTxHistoryTarget, TxHistoryStorage};
 use crate::tendermint::{CustomTendermintMsgType, TendermintFeeDetails};
@@ -70,8 +71,33 @@ macro_rules! some_or_return {
     };
 }
 
-trait CoinCapabilities: TendermintCommons + CoinWithTxHistoryV2 + MmCoin + MarketCoinOps {}
-impl CoinCapabilities for TendermintCoin {}
+#[async_trait]
+trait CoinCapabilities: TendermintCommons + CoinWithTxHistoryV2 + MmCoin + MarketCoinOps {
+    /// Returns the last known status of the IBC transfer sent by the given tx.
+    async fn ibc_transfer_status(&self, tx_hash: &str) -> IBCTransferStatus;
+}
+
+#[async_trait]
+impl CoinCapabilities for TendermintCoin {
+    async fn ibc_transfer_status(&self, tx_hash: &str) -> IBCTransferStatus {
+        match self.ibc_transfer(tx_hash).await {
+            Ok(Some(transfer)) => transfer.status,
+            // The transfer is committed, but it's not tracked (e.g. it was sent from another wallet app).
+            Ok(None) => IBCTransferStatus::Sent,
+            Err(e) => {
+                log::debug!("Error loading IBC transfer '{}': {}", tx_hash, e);
+                IBCTransferStatus::Sent
+            },
+        }
+    }
+}
+
+/// Internal id of the tx history entry that represents the `index`th transfer of the given tx.
+pub(crate) fn transfer_internal_id(tx_hash: &str, index: usize) -> BytesJson {
+    let mut internal_id_hash = index.to_le_bytes().to_vec();
+    internal_id_hash.extend_from_slice(tx_hash.as_bytes());
+    H256::from(internal_id_hash.as_slice()).reversed().to_vec().into()
+}
 
 #[async_trait]
 impl CoinWithTxHistoryV2 for TendermintCoin {
@@ -623,12 +649,17 @@ where
                         "Could not deserialize transaction"
                     );
 
-                    let msg = try_or_continue!(
+                    let first_msg = try_or_continue!(
                         deserialized_tx.body.messages.first().ok_or("Tx body couldn't be read."),
                         "Tx body messages is empty"
-                    )
-                    .value
-                    .as_slice();
+                    );
+                    let msg = first_msg.value.as_slice();
+
+                    let ibc_transfer_status = if first_msg.type_url == IBC_TRANSFER_TYPE_URL {
+                        Some(coin.ibc_transfer_status(&tx_hash).await)
+                    } else {
+                        None
+                    };
 
                     let fee_data = match deserialized_tx.auth_info.fee.amount.first() {
                         Some(data) => data,
@@ -661,7 +692,7 @@ where
                         internal_id_hash.extend_from_slice(tx_hash.as_bytes());
                         drop_mutability!(internal_id_hash);
 
-                        let internal_id = H256::from(internal_id_hash.as_slice()).reversed().to_vec().into();
+                        let internal_id = transfer_internal_id(&tx_hash, index);
 
                         if let Ok(Some(_)) = storage
                             .get_tx_from_history(&coin.history_wallet_id(), &internal_id)
@@ -706,11 +737,17 @@ where
                             false => None,
                         };
 
-                        let transaction_type = get_transaction_type(
-                            &transfer_details.transfer_event_type,
-                            token_id.clone(),
-                            is_sign_claim_htlc,
-                        );
+                        let transaction_type = match ibc_transfer_status {
+                            Some(status) if tx_sent_by_me => TransactionType::TendermintIBCTransfer {
+                                token_id: token_id.clone(),
+                                status,
+                            },
+                            _ => get_transaction_type(
+                                &transfer_details.transfer_event_type,
+                                token_id.clone(),
+                                is_sign_claim_htlc,
+                            ),
+                        };
 
                         let details = TransactionDetails {
                             from,
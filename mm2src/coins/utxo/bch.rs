@@ -3,6 +3,7 @@ use crate::coin_errors::MyAddressError;
 use crate::my_tx_history_v2::{CoinWithTxHistoryV2, MyTxHistoryErrorV2, MyTxHistoryTarget, TxDetailsBuilder,
                               TxHistoryStorage};
 use crate::tx_history_storage::{GetTxHistoryFilters, WalletId};
+use crate::utxo::cash_token::{CashTokenInfo, CashTokenUnspent};
 use crate::utxo::rpc_clients::UtxoRpcFut;
 use crate::utxo::slp::{parse_slp_script, SlpGenesisParams, SlpTokenInfo, SlpTransaction, SlpUnspent};
 use crate::utxo::utxo_builder::{UtxoArcBuilder, UtxoCoinBuilder};
@@ -20,6 +21,7 @@ use crate::{BlockHeightAndTime, CanRefundHtlc, CheckIfMyPaymentSentArgs, CoinBal
             ValidatePaymentError, ValidatePaymentFut, ValidatePaymentInput, ValidateWatcherSpendInput,
             VerificationResult, WaitForHTLCTxSpendArgs, WatcherOps, WatcherReward, WatcherRewardError,
             WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput, WatcherValidateTakerFeeInput, WithdrawFut};
+use chain::CashTokenData;
 use common::executor::{AbortableSystem, AbortedError};
 use common::log::warn;
 use derive_more::Display;
@@ -31,7 +33,7 @@ pub use keys::NetworkPrefix as CashAddrPrefix;
 use mm2_metrics::MetricsArc;
 use mm2_number::MmNumber;
 use serde_json::{self as json, Value as Json};
-use serialization::{deserialize, CoinVariant};
+use serialization::{deserialize_with_coin_variant, CoinVariant};
 use std::sync::MutexGuard;
 
 pub type BchUnspentMap = HashMap<Address, BchUnspents>;
@@ -75,6 +77,7 @@ pub struct BchCoin {
     slp_addr_prefix: CashAddrPrefix,
     bchd_urls: Vec<String>,
     slp_tokens_infos: Arc<Mutex<HashMap<String, SlpTokenInfo>>>,
+    cash_tokens_infos: Arc<Mutex<HashMap<String, CashTokenInfo>>>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -94,6 +97,9 @@ pub struct BchUnspents {
     /// The unspents of transaction with an undetermined protocol (OP_RETURN in 0 output but not SLP)
     /// DO NOT ever use them to avoid burning users funds
     undetermined: Vec<UnspentInfo>,
+    /// CashTokens carrying UTXOs grouped by the token category, both fungible tokens and NFTs.
+    /// DO NOT use them as standard BCH UTXOs, the tokens are burned if they are not sent to the outputs explicitly
+    cash_tokens: HashMap<H256, Vec<CashTokenUnspent>>,
 }
 
 impl BchUnspents {
@@ -111,6 +117,14 @@ impl BchUnspents {
 
     fn add_undetermined(&mut self, utxo: UnspentInfo) { self.undetermined.push(utxo) }
 
+    fn add_cash_token(&mut self, bch_unspent: UnspentInfo, token: CashTokenData) {
+        let cash_token_unspent = CashTokenUnspent { bch_unspent, token };
+        self.cash_tokens
+            .entry(cash_token_unspent.token.category)
+            .or_insert_with(Vec::new)
+            .push(cash_token_unspent);
+    }
+
     pub fn platform_balance(&self, decimals: u8) -> CoinBalance {
         let spendable_sat = total_unspent_value(&self.standard);
 
@@ -122,7 +136,13 @@ impl BchUnspents {
         let unspendable_slp_batons = total_unspent_value(&self.slp_batons);
         let unspendable_undetermined = total_unspent_value(&self.undetermined);
 
-        let total_unspendable = unspendable_slp + unspendable_slp_batons + unspendable_undetermined;
+        let unspendable_cash_tokens = self.cash_tokens.iter().fold(0, |cur, (_, cash_token_unspents)| {
+            let bch_value = total_unspent_value(cash_token_unspents.iter().map(|unspent| &unspent.bch_unspent));
+            cur + bch_value
+        });
+
+        let total_unspendable =
+            unspendable_slp + unspendable_slp_batons + unspendable_undetermined + unspendable_cash_tokens;
         CoinBalance {
             spendable: big_decimal_from_sat_unsigned(spendable_sat, decimals),
             unspendable: big_decimal_from_sat_unsigned(total_unspendable, decimals),
@@ -141,6 +161,26 @@ impl BchUnspents {
             })
             .unwrap_or_default()
     }
+
+    /// Fungible tokens held together with an NFT are considered unspendable as the NFT is not supported yet.
+    pub fn cash_token_balance(&self, category: &H256, decimals: u8) -> CoinBalance {
+        self.cash_tokens
+            .get(category)
+            .map(|unspents| {
+                let (spendable_sat, unspendable_sat) =
+                    unspents
+                        .iter()
+                        .fold((0, 0), |(spendable, unspendable), unspent| match unspent.token.nft {
+                            Some(_) => (spendable, unspendable + unspent.token.amount),
+                            None => (spendable + unspent.token.amount, unspendable),
+                        });
+                CoinBalance {
+                    spendable: big_decimal_from_sat_unsigned(spendable_sat, decimals),
+                    unspendable: big_decimal_from_sat_unsigned(unspendable_sat, decimals),
+                }
+            })
+            .unwrap_or_default()
+    }
 }
 
 impl From<UtxoRpcError> for IsSlpUtxoError {
@@ -167,23 +207,11 @@ impl BchCoin {
 
     async fn utxos_into_bch_unspents(&self, utxos: Vec<UnspentInfo>) -> UtxoRpcResult<BchUnspents> {
         let mut result = BchUnspents::default();
-        let mut temporary_undetermined = Vec::new();
 
+        // Every UTXO including the zero output can carry CashTokens, so all the previous transactions are required.
         let to_verbose: HashSet<H256Json> = utxos
-            .into_iter()
-            .filter_map(|unspent| {
-                if unspent.outpoint.index == 0 {
-                    // Zero output is reserved for OP_RETURN of specific protocols
-                    // so if we get it we can safely consider this as standard BCH UTXO.
-                    // There is no need to request verbose transaction for such UTXO.
-                    result.add_standard(unspent);
-                    None
-                } else {
-                    let hash = unspent.outpoint.hash.reversed().into();
-                    temporary_undetermined.push(unspent);
-                    Some(hash)
-                }
-            })
+            .iter()
+            .map(|unspent| unspent.outpoint.hash.reversed().into())
             .collect();
 
         let verbose_txs = self
@@ -191,7 +219,7 @@ impl BchCoin {
             .compat()
             .await?;
 
-        for unspent in temporary_undetermined {
+        for unspent in utxos {
             let prev_tx_hash = unspent.outpoint.hash.reversed().into();
             let prev_tx_bytes = verbose_txs
                 .get(&prev_tx_hash)
@@ -202,7 +230,7 @@ impl BchCoin {
                     ))
                 })?
                 .to_inner();
-            let prev_tx: UtxoTx = match deserialize(prev_tx_bytes.hex.as_slice()) {
+            let prev_tx: UtxoTx = match deserialize_with_coin_variant(prev_tx_bytes.hex.as_slice(), CoinVariant::BCH) {
                 Ok(b) => b,
                 Err(e) => {
                     warn!(
@@ -214,12 +242,27 @@ impl BchCoin {
                 },
             };
 
-            if prev_tx.outputs.is_empty() {
-                warn!(
-                    "Prev_tx {:?} outputs are empty, considering {:?} as undetermined",
-                    prev_tx_bytes, unspent
-                );
-                result.add_undetermined(unspent);
+            let prev_output = match prev_tx.outputs.get(unspent.outpoint.index as usize) {
+                Some(output) => output,
+                None => {
+                    warn!(
+                        "Prev_tx {:?} has no output {}, considering {:?} as undetermined",
+                        prev_tx_bytes, unspent.outpoint.index, unspent
+                    );
+                    result.add_undetermined(unspent);
+                    continue;
+                },
+            };
+
+            if let Some(token) = &prev_output.token {
+                result.add_cash_token(unspent, token.clone());
+                continue;
+            }
+
+            if unspent.outpoint.index == 0 {
+                // Zero output is reserved for OP_RETURN of specific protocols
+                // so if we get it we can safely consider this as standard BCH UTXO.
+                result.add_standard(unspent);
                 continue;
             }
 
@@ -337,6 +380,30 @@ impl BchCoin {
         Ok((slp_unspents, standard_utxos))
     }
 
+    /// Returns fungible-only unspents of the CashToken category sorted by the token amount,
+    /// plus plain BCH UTXOs plus RecentlySpentOutPoints mutex guard
+    pub async fn get_cash_token_utxos_for_spend(
+        &self,
+        category: &H256,
+    ) -> UtxoRpcResult<(Vec<CashTokenUnspent>, Vec<UnspentInfo>, RecentlySpentOutPointsGuard<'_>)> {
+        let my_address = self
+            .as_ref()
+            .derivation_method
+            .single_addr_or_err()
+            .mm_err(|e| UtxoRpcError::Internal(e.to_string()))?;
+        let (mut bch_unspents, recently_spent) = self.bch_unspents_for_spend(my_address).await?;
+        let mut token_unspents: Vec<_> = bch_unspents
+            .cash_tokens
+            .remove(category)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|unspent| unspent.token.nft.is_none())
+            .collect();
+
+        token_unspents.sort_by(|a, b| a.token.amount.cmp(&b.token.amount));
+        Ok((token_unspents, bch_unspents.standard, recently_spent))
+    }
+
     pub fn add_slp_token_info(&self, ticker: String, info: SlpTokenInfo) {
         self.slp_tokens_infos.lock().unwrap().insert(ticker, info);
     }
@@ -345,6 +412,14 @@ impl BchCoin {
         self.slp_tokens_infos.lock().unwrap()
     }
 
+    pub fn add_cash_token_info(&self, ticker: String, info: CashTokenInfo) {
+        self.cash_tokens_infos.lock().unwrap().insert(ticker, info);
+    }
+
+    pub fn get_cash_tokens_infos(&self) -> MutexGuard<'_, HashMap<String, CashTokenInfo>> {
+        self.cash_tokens_infos.lock().unwrap()
+    }
+
     pub fn get_my_slp_address(&self) -> Result<CashAddress, String> {
         let my_address = try_s!(self.as_ref().derivation_method.single_addr_or_err());
         let slp_address = my_address.to_cashaddress(
@@ -371,6 +446,7 @@ impl BchCoin {
                 params.my_addresses,
             )
             .await?;
+        let tx_fee = bch_tx_details.fee_details.clone();
         let maybe_op_return: Script = tx
             .outputs
             .get(0)
@@ -381,6 +457,8 @@ impl BchCoin {
             .script_pubkey
             .clone()
             .into();
+
+        let mut details = vec![bch_tx_details];
         if !(maybe_op_return.is_pay_to_public_key_hash()
             || maybe_op_return.is_pay_to_public_key()
             || maybe_op_return.is_pay_to_script_hash())
@@ -391,16 +469,27 @@ impl BchCoin {
                         &tx,
                         slp_details.transaction,
                         params.block_height_and_time,
-                        bch_tx_details.fee_details.clone(),
+                        tx_fee.clone(),
                         params.storage,
                         params.my_addresses,
                     )
                     .await?;
-                return Ok(vec![bch_tx_details, slp_tx_details]);
+                details.push(slp_tx_details);
             }
         }
 
-        Ok(vec![bch_tx_details])
+        let cash_tokens_tx_details = self
+            .cash_tokens_tx_details(
+                &tx,
+                params.block_height_and_time,
+                tx_fee,
+                params.storage,
+                params.my_addresses,
+            )
+            .await?;
+        details.extend(cash_tokens_tx_details);
+
+        Ok(details)
     }
 
     async fn bch_tx_details<T: TxHistoryStorage>(
@@ -604,6 +693,105 @@ impl BchCoin {
         Ok(slp_tx_details_builder.build())
     }
 
+    /// Returns the category, the owner and the fungible amount of the CashTokens carried by the output if any.
+    fn cash_token_transfer(
+        &self,
+        tx: &UtxoTx,
+        output: &TransactionOutput,
+    ) -> MmResult<Option<(H256, Address, u64)>, UtxoTxDetailsError> {
+        let token = match &output.token {
+            Some(token) if token.amount > 0 => token,
+            _ => return Ok(None),
+        };
+
+        let mut addresses = self
+            .addresses_from_script(&output.script_pubkey.clone().into())
+            .map_to_mm(UtxoTxDetailsError::TxAddressDeserializationError)?;
+        if addresses.len() != 1 {
+            let msg = format!(
+                "{} tx {:?} output script resulted into unexpected number of addresses",
+                self.ticker(),
+                tx.hash().reversed(),
+            );
+            return MmError::err(UtxoTxDetailsError::TxAddressDeserializationError(msg));
+        }
+        Ok(Some((token.category, addresses.remove(0), token.amount)))
+    }
+
+    /// Returns the details of the fungible CashTokens transfers occurred in the transaction.
+    /// Only the activated tokens are taken into account as the decimals of other categories are unknown.
+    async fn cash_tokens_tx_details<Storage: TxHistoryStorage>(
+        &self,
+        tx: &UtxoTx,
+        height_and_time: Option<BlockHeightAndTime>,
+        tx_fee: Option<TxFeeDetails>,
+        storage: &Storage,
+        my_addresses: &HashSet<Address>,
+    ) -> MmResult<Vec<TransactionDetails>, UtxoTxDetailsError> {
+        let tokens_decimals: HashMap<H256, u8> = self
+            .get_cash_tokens_infos()
+            .values()
+            .map(|info| (info.category, info.decimals))
+            .collect();
+        if tokens_decimals.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut transferred_to = Vec::new();
+        for output in &tx.outputs {
+            if let Some(transfer) = self.cash_token_transfer(tx, output)? {
+                transferred_to.push(transfer);
+            }
+        }
+
+        let mut transferred_from = Vec::new();
+        for input in &tx.inputs {
+            let prev_tx = self
+                .tx_from_storage_or_rpc(&input.previous_output.hash.reversed().into(), storage)
+                .await?;
+            let prev_output = prev_tx
+                .outputs
+                .get(input.previous_output.index as usize)
+                .or_mm_err(|| {
+                    let error = format!(
+                        "Unexpected '{}' output index at {} TX",
+                        input.previous_output.index,
+                        prev_tx.hash().reversed()
+                    );
+                    UtxoTxDetailsError::InvalidTransaction(error)
+                })?;
+            if let Some(transfer) = self.cash_token_transfer(&prev_tx, prev_output)? {
+                transferred_from.push(transfer);
+            }
+        }
+
+        let mut result = Vec::new();
+        for (category, decimals) in tokens_decimals {
+            let is_transferred = transferred_to
+                .iter()
+                .chain(transferred_from.iter())
+                .any(|(transfer_category, _, _)| *transfer_category == category);
+            if !is_transferred {
+                continue;
+            }
+
+            let mut tx_details_builder =
+                TxDetailsBuilder::new(self.ticker().to_owned(), tx, height_and_time, my_addresses.clone());
+            for (_, address, amount) in transferred_to.iter().filter(|(c, _, _)| *c == category) {
+                tx_details_builder.transferred_to(address.clone(), &big_decimal_from_sat_unsigned(*amount, decimals));
+            }
+            for (_, address, amount) in transferred_from.iter().filter(|(c, _, _)| *c == category) {
+                tx_details_builder.transferred_from(address.clone(), &big_decimal_from_sat_unsigned(*amount, decimals));
+            }
+
+            let token_id = category.reversed().take().to_vec().into();
+            tx_details_builder.set_transaction_type(TransactionType::TokenTransfer(token_id));
+            tx_details_builder.set_tx_fee(tx_fee.clone());
+            result.push(tx_details_builder.build());
+        }
+        Ok(result)
+    }
+
     pub async fn get_block_timestamp(&self, height: u64) -> Result<u64, MmError<GetBlockHeaderError>> {
         self.as_ref().rpc_client.get_block_timestamp(height).await
     }
@@ -627,12 +815,14 @@ pub async fn bch_coin_with_policy(
 
     let bchd_urls = params.bchd_urls;
     let slp_tokens_infos = Arc::new(Mutex::new(HashMap::new()));
+    let cash_tokens_infos = Arc::new(Mutex::new(HashMap::new()));
     let constructor = {
         move |utxo_arc| BchCoin {
             utxo_arc,
             slp_addr_prefix: slp_addr_prefix.clone(),
             bchd_urls: bchd_urls.clone(),
             slp_tokens_infos: slp_tokens_infos.clone(),
+            cash_tokens_infos: cash_tokens_infos.clone(),
         }
     };
 
@@ -1312,6 +1502,9 @@ impl MmCoin for BchCoin {
         if let Ok(tokens) = self.slp_tokens_infos.lock().as_deref_mut() {
             tokens.remove(ticker);
         };
+        if let Ok(tokens) = self.cash_tokens_infos.lock().as_deref_mut() {
+            tokens.remove(ticker);
+        };
     }
 }
 
@@ -1368,7 +1561,10 @@ impl UtxoTxHistoryOps for BchCoin {
         tx_hash: &H256Json,
         storage: &Storage,
     ) -> MmResult<UtxoTx, UtxoTxDetailsError> {
-        utxo_common::utxo_tx_history_v2_common::tx_from_storage_or_rpc(self, tx_hash, storage).await
+        let tx_bytes =
+            utxo_common::utxo_tx_history_v2_common::tx_bytes_from_storage_or_rpc(self, tx_hash, storage).await?;
+        let tx = deserialize_with_coin_variant(tx_bytes.0.as_slice(), CoinVariant::BCH)?;
+        Ok(tx)
     }
 
     async fn request_tx_history(
@@ -1471,6 +1667,7 @@ mod bch_tests {
     use super::*;
     use crate::my_tx_history_v2::for_tests::init_storage_for;
     use crate::{TransactionType, TxFeeDetails};
+    use chain::{CashTokenNft, NftCapability};
     use common::block_on;
 
     #[test]
@@ -1569,6 +1766,46 @@ mod bch_tests {
         assert_eq!(coin.ticker(), slp_tx_details.coin);
     }
 
+    #[test]
+    fn test_cash_token_balance() {
+        let category: H256 = [0xbb; 32].into();
+        let unspent = |index, value| UnspentInfo {
+            outpoint: OutPoint { hash: 1.into(), index },
+            value,
+            height: None,
+        };
+        let token = |amount, nft| CashTokenData { category, amount, nft };
+        let nft = CashTokenNft {
+            capability: NftCapability::Mutable,
+            commitment: Bytes::default(),
+        };
+
+        let mut bch_unspents = BchUnspents::default();
+        bch_unspents.add_standard(unspent(0, 100000));
+        bch_unspents.add_cash_token(unspent(1, 1000), token(150, None));
+        bch_unspents.add_cash_token(unspent(2, 1000), token(250, None));
+        bch_unspents.add_cash_token(unspent(3, 1000), token(1000, Some(nft.clone())));
+        bch_unspents.add_cash_token(unspent(4, 1000), token(0, Some(nft)));
+
+        // The fungible tokens held together with an NFT are unspendable.
+        let expected = CoinBalance {
+            spendable: "4".parse().unwrap(),
+            unspendable: "10".parse().unwrap(),
+        };
+        assert_eq!(bch_unspents.cash_token_balance(&category, 2), expected);
+        assert_eq!(
+            bch_unspents.cash_token_balance(&[0xcc; 32].into(), 2),
+            CoinBalance::default()
+        );
+
+        // The BCH value of the token outputs is unspendable.
+        let expected = CoinBalance {
+            spendable: "0.001".parse().unwrap(),
+            unspendable: "0.00004".parse().unwrap(),
+        };
+        assert_eq!(bch_unspents.platform_balance(8), expected);
+    }
+
     #[test]
     fn test_sign_message() {
         let (_ctx, coin) = tbch_coin_for_test();
@@ -0,0 +1,981 @@
+//! The module implementing Bitcoin Cash CashTokens support.
+//! CashTokens are the native tokens of the Bitcoin Cash blockchain carried by the outputs' token prefix.
+//! Only the fungible tokens can be sent for now, the outputs carrying NFTs are protected from spending.
+//! More info about the protocol can be found at https://github.com/cashtokens/cashtokens
+
+use crate::coin_errors::{MyAddressError, ValidatePaymentError, ValidatePaymentFut};
+use crate::my_tx_history_v2::{CoinWithTxHistoryV2, MyTxHistoryErrorV2, MyTxHistoryTarget};
+use crate::tx_history_storage::{GetTxHistoryFilters, WalletId};
+use crate::utxo::bch::BchCoin;
+use crate::utxo::rpc_clients::{UnspentInfo, UtxoRpcError, UtxoRpcResult};
+use crate::utxo::utxo_common::{self, big_decimal_from_sat_unsigned, UtxoTxBuilder};
+use crate::utxo::{output_script, sat_from_big_decimal, ActualTxFee, UtxoCoinConf, UtxoCoinFields, UtxoCommonOps,
+                  UtxoFeeDetails};
+use crate::{BalanceFut, CheckIfMyPaymentSentArgs, CoinBalance, CoinFutSpawner, ConfirmPaymentInput, DexFee,
+            FeeApproxStage, FoundSwapTxSpend, HistorySyncState, MakerSwapTakerCoin, MarketCoinOps, MmCoin, MmCoinEnum,
+            NegotiateSwapContractAddrErr, PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr,
+            RawTransactionFut, RawTransactionRequest, RefundError, RefundPaymentArgs, RefundResult,
+            SearchForSwapTxSpendInput, SendMakerPaymentSpendPreimageInput, SendPaymentArgs, SignatureResult,
+            SpendPaymentArgs, SwapOps, TakerSwapMakerCoin, TradeFee, TradePreimageError, TradePreimageFut,
+            TradePreimageResult, TradePreimageValue, TransactionDetails, TransactionEnum, TransactionErr,
+            TransactionFut, TransactionResult, TransactionType, TxMarshalingErr, UnexpectedDerivationMethod,
+            ValidateAddressResult, ValidateFeeArgs, ValidateInstructionsErr, ValidateOtherPubKeyErr,
+            ValidatePaymentInput, ValidateWatcherSpendInput, VerificationResult, WaitForHTLCTxSpendArgs, WatcherOps,
+            WatcherReward, WatcherRewardError, WatcherSearchForSwapTxSpendInput, WatcherValidatePaymentInput,
+            WatcherValidateTakerFeeInput, WithdrawError, WithdrawFee, WithdrawFut, WithdrawRequest};
+use async_trait::async_trait;
+use chain::{CashTokenData, OutPoint, TransactionOutput};
+use common::executor::{abortable_queue::AbortableQueue, AbortableSystem, AbortedError};
+use common::log::warn;
+use common::now_sec;
+use derive_more::Display;
+use futures::{FutureExt, TryFutureExt};
+use futures01::Future;
+use keys::{KeyPair, Type as ScriptType};
+use mm2_core::mm_ctx::MmArc;
+use mm2_err_handle::prelude::*;
+use mm2_number::{BigDecimal, MmNumber};
+use primitives::hash::H256;
+use rpc::v1::types::{Bytes as BytesJson, ToTxHash};
+use script::bytes::Bytes;
+use script::Builder as ScriptBuilder;
+use serde_json::Value as Json;
+use serialization::serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use utxo_signer::with_key_pair::sign_tx;
+
+/// The BCH value of the token outputs created by the wallet.
+/// Token outputs are larger than the standard ones, so the standard dust amount may be not enough to relay them.
+pub const CASH_TOKEN_OUTPUT_VALUE: u64 = 1000;
+
+#[derive(Debug, Display)]
+pub enum EnableCashTokenError {
+    UnexpectedDerivationMethod(String),
+    Internal(String),
+}
+
+impl From<MyAddressError> for EnableCashTokenError {
+    fn from(err: MyAddressError) -> Self {
+        match err {
+            MyAddressError::UnexpectedDerivationMethod(der) => EnableCashTokenError::UnexpectedDerivationMethod(der),
+            MyAddressError::InternalError(internal) => EnableCashTokenError::Internal(internal),
+        }
+    }
+}
+
+impl From<AbortedError> for EnableCashTokenError {
+    fn from(e: AbortedError) -> Self { EnableCashTokenError::Internal(e.to_string()) }
+}
+
+pub struct CashTokenFields {
+    decimals: u8,
+    ticker: String,
+    /// The token category in the transaction hash (internal) byte order.
+    category: H256,
+    required_confirmations: AtomicU64,
+    /// This abortable system is used to spawn coin's related futures that should be aborted on coin deactivation
+    /// and on [`MmArc::stop`].
+    abortable_system: AbortableQueue,
+}
+
+/// Minimalistic info that is used to be stored outside of the token's context
+/// E.g. in the platform BCHCoin
+#[derive(Debug)]
+pub struct CashTokenInfo {
+    pub category: H256,
+    pub decimals: u8,
+}
+
+#[derive(Clone)]
+pub struct CashToken {
+    conf: Arc<CashTokenFields>,
+    platform_coin: BchCoin,
+}
+
+#[derive(Clone, Debug)]
+pub struct CashTokenUnspent {
+    pub bch_unspent: UnspentInfo,
+    pub token: CashTokenData,
+}
+
+#[derive(Debug)]
+pub struct CashTokenProtocolConf {
+    pub platform_coin_ticker: String,
+    /// The token category in the transaction hash (internal) byte order.
+    pub category: H256,
+    pub decimals: u8,
+    pub required_confirmations: Option<u64>,
+}
+
+impl CashToken {
+    pub fn new(
+        decimals: u8,
+        ticker: String,
+        category: H256,
+        platform_coin: BchCoin,
+        required_confirmations: u64,
+    ) -> MmResult<CashToken, EnableCashTokenError> {
+        // Create an abortable system linked to `platform_coin` so if the platform coin is disabled,
+        // all spawned futures related to `CashToken` will be aborted as well.
+        let abortable_system = platform_coin.as_ref().abortable_system.create_subsystem()?;
+
+        let conf = Arc::new(CashTokenFields {
+            decimals,
+            ticker,
+            category,
+            required_confirmations: AtomicU64::new(required_confirmations),
+            abortable_system,
+        });
+        Ok(CashToken { conf, platform_coin })
+    }
+
+    pub fn decimals(&self) -> u8 { self.conf.decimals }
+
+    pub fn category(&self) -> &H256 { &self.conf.category }
+
+    /// Returns the category in the display byte order as it's shown by the explorers.
+    pub fn token_id(&self) -> H256 { self.conf.category.reversed() }
+
+    pub fn platform_decimals(&self) -> u8 { self.platform_coin.as_ref().decimals }
+
+    fn platform_conf(&self) -> &UtxoCoinConf { &self.platform_coin.as_ref().conf }
+
+    pub async fn my_coin_balance(&self) -> UtxoRpcResult<CoinBalance> {
+        let my_address = self
+            .platform_coin
+            .as_ref()
+            .derivation_method
+            .single_addr_or_err()
+            .mm_err(|e| UtxoRpcError::Internal(e.to_string()))?;
+        let bch_unspents = self.platform_coin.bch_unspents_for_display(my_address).await?;
+        Ok(bch_unspents.cash_token_balance(self.category(), self.decimals()))
+    }
+
+    pub fn get_info(&self) -> CashTokenInfo {
+        CashTokenInfo {
+            category: self.conf.category,
+            decimals: self.conf.decimals,
+        }
+    }
+
+    fn token_output(&self, script_pubkey: Bytes, amount: u64) -> TransactionOutput {
+        TransactionOutput {
+            value: CASH_TOKEN_OUTPUT_VALUE,
+            script_pubkey,
+            token: Some(CashTokenData {
+                category: self.conf.category,
+                amount,
+                nft: None,
+            }),
+        }
+    }
+
+    fn swaps_not_supported(&self) -> String { format!("{} doesn't support swaps", self.ticker()) }
+
+    fn swaps_not_supported_tx_fut(&self) -> TransactionFut {
+        Box::new(futures01::future::err(TransactionErr::Plain(
+            self.swaps_not_supported(),
+        )))
+    }
+
+    fn swaps_not_supported_validation_fut(&self) -> ValidatePaymentFut<()> {
+        Box::new(futures01::future::err(MmError::new(
+            ValidatePaymentError::InternalError(self.swaps_not_supported()),
+        )))
+    }
+}
+
+impl AsRef<UtxoCoinFields> for CashToken {
+    fn as_ref(&self) -> &UtxoCoinFields { self.platform_coin.as_ref() }
+}
+
+impl MarketCoinOps for CashToken {
+    fn ticker(&self) -> &str { &self.conf.ticker }
+
+    fn my_address(&self) -> MmResult<String, MyAddressError> { self.platform_coin.my_address() }
+
+    fn get_public_key(&self) -> Result<String, MmError<UnexpectedDerivationMethod>> {
+        self.platform_coin.get_public_key()
+    }
+
+    fn sign_message_hash(&self, message: &str) -> Option<[u8; 32]> { self.platform_coin.sign_message_hash(message) }
+
+    fn sign_message(&self, message: &str) -> SignatureResult<String> { self.platform_coin.sign_message(message) }
+
+    fn verify_message(&self, signature: &str, message: &str, address: &str) -> VerificationResult<bool> {
+        self.platform_coin.verify_message(signature, message, address)
+    }
+
+    fn my_balance(&self) -> BalanceFut<CoinBalance> {
+        let coin = self.clone();
+        let fut = async move { Ok(coin.my_coin_balance().await?) };
+        Box::new(fut.boxed().compat())
+    }
+
+    fn base_coin_balance(&self) -> BalanceFut<BigDecimal> {
+        Box::new(self.platform_coin.my_balance().map(|res| res.spendable))
+    }
+
+    fn platform_ticker(&self) -> &str { self.platform_coin.ticker() }
+
+    /// Receives raw transaction bytes in hexadecimal format as input and returns tx hash in hexadecimal format
+    fn send_raw_tx(&self, tx: &str) -> Box<dyn Future<Item = String, Error = String> + Send> {
+        self.platform_coin.send_raw_tx(tx)
+    }
+
+    fn send_raw_tx_bytes(&self, tx: &[u8]) -> Box<dyn Future<Item = String, Error = String> + Send> {
+        self.platform_coin.send_raw_tx_bytes(tx)
+    }
+
+    fn wait_for_confirmations(&self, input: ConfirmPaymentInput) -> Box<dyn Future<Item = (), Error = String> + Send> {
+        self.platform_coin.wait_for_confirmations(input)
+    }
+
+    fn wait_for_htlc_tx_spend(&self, _args: WaitForHTLCTxSpendArgs<'_>) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn tx_enum_from_bytes(&self, bytes: &[u8]) -> Result<TransactionEnum, MmError<TxMarshalingErr>> {
+        self.platform_coin.tx_enum_from_bytes(bytes)
+    }
+
+    fn current_block(&self) -> Box<dyn Future<Item = u64, Error = String> + Send> { self.platform_coin.current_block() }
+
+    fn display_priv_key(&self) -> Result<String, String> { self.platform_coin.display_priv_key() }
+
+    fn min_tx_amount(&self) -> BigDecimal { big_decimal_from_sat_unsigned(1, self.decimals()) }
+
+    fn min_trading_vol(&self) -> MmNumber { big_decimal_from_sat_unsigned(1, self.decimals()).into() }
+}
+
+/// CashTokens can't participate in the swaps as [`CashToken::wallet_only`] returns `true`,
+/// so the swap related methods return the "swaps are not supported" error.
+#[async_trait]
+impl SwapOps for CashToken {
+    fn send_taker_fee(&self, _fee_addr: &[u8], _dex_fee: DexFee, _uuid: &[u8]) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_maker_payment(&self, _maker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_taker_payment(&self, _taker_payment_args: SendPaymentArgs) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_maker_spends_taker_payment(&self, _maker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_taker_spends_maker_payment(&self, _taker_spends_payment_args: SpendPaymentArgs) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    async fn send_taker_refunds_payment(
+        &self,
+        _taker_refunds_payment_args: RefundPaymentArgs<'_>,
+    ) -> TransactionResult {
+        Err(TransactionErr::Plain(self.swaps_not_supported()))
+    }
+
+    async fn send_maker_refunds_payment(
+        &self,
+        _maker_refunds_payment_args: RefundPaymentArgs<'_>,
+    ) -> TransactionResult {
+        Err(TransactionErr::Plain(self.swaps_not_supported()))
+    }
+
+    fn validate_fee(&self, _validate_fee_args: ValidateFeeArgs) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    fn validate_maker_payment(&self, _input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    fn validate_taker_payment(&self, _input: ValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    fn check_if_my_payment_sent(
+        &self,
+        _if_my_payment_sent_args: CheckIfMyPaymentSentArgs,
+    ) -> Box<dyn Future<Item = Option<TransactionEnum>, Error = String> + Send> {
+        Box::new(futures01::future::err(self.swaps_not_supported()))
+    }
+
+    async fn search_for_swap_tx_spend_my(
+        &self,
+        _input: SearchForSwapTxSpendInput<'_>,
+    ) -> Result<Option<FoundSwapTxSpend>, String> {
+        Err(self.swaps_not_supported())
+    }
+
+    async fn search_for_swap_tx_spend_other(
+        &self,
+        _input: SearchForSwapTxSpendInput<'_>,
+    ) -> Result<Option<FoundSwapTxSpend>, String> {
+        Err(self.swaps_not_supported())
+    }
+
+    fn check_tx_signed_by_pub(&self, _tx: &[u8], _expected_pub: &[u8]) -> Result<bool, MmError<ValidatePaymentError>> {
+        MmError::err(ValidatePaymentError::InternalError(self.swaps_not_supported()))
+    }
+
+    async fn extract_secret(
+        &self,
+        _secret_hash: &[u8],
+        _spend_tx: &[u8],
+        _watcher_reward: bool,
+    ) -> Result<Vec<u8>, String> {
+        Err(self.swaps_not_supported())
+    }
+
+    fn is_auto_refundable(&self) -> bool { false }
+
+    async fn wait_for_htlc_refund(&self, _tx: &[u8], _locktime: u64) -> RefundResult<()> {
+        MmError::err(RefundError::Internal(
+            "wait_for_htlc_refund is not supported for this coin!".into(),
+        ))
+    }
+
+    #[inline]
+    fn negotiate_swap_contract_addr(
+        &self,
+        _other_side_address: Option<&[u8]>,
+    ) -> Result<Option<BytesJson>, MmError<NegotiateSwapContractAddrErr>> {
+        Ok(None)
+    }
+
+    fn derive_htlc_key_pair(&self, swap_unique_data: &[u8]) -> KeyPair {
+        utxo_common::derive_htlc_key_pair(self.platform_coin.as_ref(), swap_unique_data)
+    }
+
+    fn derive_htlc_pubkey(&self, swap_unique_data: &[u8]) -> Vec<u8> {
+        utxo_common::derive_htlc_pubkey(self, swap_unique_data)
+    }
+
+    #[inline]
+    fn validate_other_pubkey(&self, raw_pubkey: &[u8]) -> MmResult<(), ValidateOtherPubKeyErr> {
+        utxo_common::validate_other_pubkey(raw_pubkey)
+    }
+
+    async fn maker_payment_instructions(
+        &self,
+        _args: PaymentInstructionArgs<'_>,
+    ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
+        Ok(None)
+    }
+
+    async fn taker_payment_instructions(
+        &self,
+        _args: PaymentInstructionArgs<'_>,
+    ) -> Result<Option<Vec<u8>>, MmError<PaymentInstructionsErr>> {
+        Ok(None)
+    }
+
+    fn validate_maker_payment_instructions(
+        &self,
+        _instructions: &[u8],
+        _args: PaymentInstructionArgs,
+    ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
+    }
+
+    fn validate_taker_payment_instructions(
+        &self,
+        _instructions: &[u8],
+        _args: PaymentInstructionArgs,
+    ) -> Result<PaymentInstructions, MmError<ValidateInstructionsErr>> {
+        MmError::err(ValidateInstructionsErr::UnsupportedCoin(self.ticker().to_string()))
+    }
+}
+
+#[async_trait]
+impl TakerSwapMakerCoin for CashToken {
+    async fn on_taker_payment_refund_start(&self, _maker_payment: &[u8]) -> RefundResult<()> { Ok(()) }
+
+    async fn on_taker_payment_refund_success(&self, _maker_payment: &[u8]) -> RefundResult<()> { Ok(()) }
+}
+
+#[async_trait]
+impl MakerSwapTakerCoin for CashToken {
+    async fn on_maker_payment_refund_start(&self, _taker_payment: &[u8]) -> RefundResult<()> { Ok(()) }
+
+    async fn on_maker_payment_refund_success(&self, _taker_payment: &[u8]) -> RefundResult<()> { Ok(()) }
+}
+
+#[async_trait]
+impl WatcherOps for CashToken {
+    fn create_maker_payment_spend_preimage(
+        &self,
+        _maker_payment_tx: &[u8],
+        _time_lock: u64,
+        _maker_pub: &[u8],
+        _secret_hash: &[u8],
+        _swap_unique_data: &[u8],
+    ) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_maker_payment_spend_preimage(&self, _input: SendMakerPaymentSpendPreimageInput) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn create_taker_payment_refund_preimage(
+        &self,
+        _taker_payment_tx: &[u8],
+        _time_lock: u64,
+        _maker_pub: &[u8],
+        _secret_hash: &[u8],
+        _swap_contract_address: &Option<BytesJson>,
+        _swap_unique_data: &[u8],
+    ) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn send_taker_payment_refund_preimage(&self, _watcher_refunds_payment_args: RefundPaymentArgs) -> TransactionFut {
+        self.swaps_not_supported_tx_fut()
+    }
+
+    fn watcher_validate_taker_fee(&self, _input: WatcherValidateTakerFeeInput) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    fn watcher_validate_taker_payment(&self, _input: WatcherValidatePaymentInput) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    fn taker_validates_payment_spend_or_refund(&self, _input: ValidateWatcherSpendInput) -> ValidatePaymentFut<()> {
+        self.swaps_not_supported_validation_fut()
+    }
+
+    async fn watcher_search_for_swap_tx_spend(
+        &self,
+        _input: WatcherSearchForSwapTxSpendInput<'_>,
+    ) -> Result<Option<FoundSwapTxSpend>, String> {
+        Err(self.swaps_not_supported())
+    }
+
+    async fn get_taker_watcher_reward(
+        &self,
+        _other_coin: &MmCoinEnum,
+        _coin_amount: Option<BigDecimal>,
+        _other_coin_amount: Option<BigDecimal>,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
+    ) -> Result<WatcherReward, MmError<WatcherRewardError>> {
+        MmError::err(WatcherRewardError::InternalError(self.swaps_not_supported()))
+    }
+
+    async fn get_maker_watcher_reward(
+        &self,
+        _other_coin: &MmCoinEnum,
+        _reward_amount: Option<BigDecimal>,
+        _wait_until: u64,
+    ) -> Result<Option<WatcherReward>, MmError<WatcherRewardError>> {
+        MmError::err(WatcherRewardError::InternalError(self.swaps_not_supported()))
+    }
+}
+
+#[async_trait]
+impl MmCoin for CashToken {
+    fn is_asset_chain(&self) -> bool { false }
+
+    /// CashTokens swaps are not supported yet.
+    fn wallet_only(&self, _ctx: &MmArc) -> bool { true }
+
+    fn spawner(&self) -> CoinFutSpawner { CoinFutSpawner::new(&self.conf.abortable_system) }
+
+    fn get_raw_transaction(&self, req: RawTransactionRequest) -> RawTransactionFut {
+        Box::new(
+            utxo_common::get_raw_transaction(self.platform_coin.as_ref(), req)
+                .boxed()
+                .compat(),
+        )
+    }
+
+    fn get_tx_hex_by_hash(&self, tx_hash: Vec<u8>) -> RawTransactionFut {
+        Box::new(
+            utxo_common::get_tx_hex_by_hash(self.platform_coin.as_ref(), tx_hash)
+                .boxed()
+                .compat(),
+        )
+    }
+
+    fn withdraw(&self, req: WithdrawRequest) -> WithdrawFut {
+        let coin = self.clone();
+        let fut = async move {
+            let my_address = coin.platform_coin.as_ref().derivation_method.single_addr_or_err()?;
+            let key_pair = coin.platform_coin.as_ref().priv_key_policy.activated_key_or_err()?;
+
+            let to = coin.platform_coin.address_from_str(&req.to)?;
+            let conf = coin.platform_conf();
+            let is_p2pkh = to.prefix == conf.pub_addr_prefix && to.t_addr_prefix == conf.pub_t_addr_prefix;
+            let is_p2sh = to.prefix == conf.p2sh_addr_prefix && to.t_addr_prefix == conf.p2sh_t_addr_prefix;
+            let script_type = if is_p2pkh {
+                ScriptType::P2PKH
+            } else if is_p2sh {
+                ScriptType::P2SH
+            } else {
+                return MmError::err(WithdrawError::InvalidAddress("Expected either P2PKH or P2SH".into()));
+            };
+
+            let (token_unspents, available_bch_inputs, _recently_spent) = coin
+                .platform_coin
+                .get_cash_token_utxos_for_spend(coin.category())
+                .await?;
+            let token_balance = token_unspents.iter().fold(0, |cur, unspent| cur + unspent.token.amount);
+            let amount = if req.max {
+                if token_balance == 0 {
+                    return MmError::err(WithdrawError::ZeroBalanceToWithdrawMax);
+                }
+                token_balance
+            } else {
+                let amount = sat_from_big_decimal(&req.amount, coin.decimals())?;
+                // Token outputs with zero fungible amount and without an NFT are invalid.
+                if amount == 0 {
+                    return MmError::err(WithdrawError::AmountTooLow {
+                        amount: req.amount,
+                        threshold: coin.min_tx_amount(),
+                    });
+                }
+                amount
+            };
+
+            let mut total_token_input = 0;
+            let mut token_inputs = Vec::new();
+            for unspent in token_unspents {
+                if total_token_input >= amount {
+                    break;
+                }
+                total_token_input += unspent.token.amount;
+                token_inputs.push(unspent);
+            }
+            if total_token_input < amount {
+                return MmError::err(WithdrawError::NotSufficientBalance {
+                    coin: coin.ticker().into(),
+                    available: big_decimal_from_sat_unsigned(total_token_input, coin.decimals()),
+                    required: big_decimal_from_sat_unsigned(amount, coin.decimals()),
+                });
+            }
+
+            let mut outputs = vec![coin.token_output(output_script(&to, script_type).to_bytes(), amount)];
+            let change = total_token_input - amount;
+            if change > 0 {
+                let my_script = ScriptBuilder::build_p2pkh(&my_address.hash).to_bytes();
+                outputs.push(coin.token_output(my_script, change));
+            }
+
+            let inputs_tokens: HashMap<OutPoint, CashTokenData> = token_inputs
+                .iter()
+                .map(|unspent| (unspent.bch_unspent.outpoint, unspent.token.clone()))
+                .collect();
+            let mut tx_builder = UtxoTxBuilder::new(&coin.platform_coin)
+                .add_required_inputs(token_inputs.into_iter().map(|unspent| unspent.bch_unspent))
+                .add_available_inputs(available_bch_inputs)
+                .add_outputs(outputs);
+
+            let platform_decimals = coin.platform_decimals();
+            match req.fee {
+                Some(WithdrawFee::UtxoFixed { amount }) => {
+                    let fixed = sat_from_big_decimal(&amount, platform_decimals)?;
+                    tx_builder = tx_builder.with_fee(ActualTxFee::FixedPerKb(fixed))
+                },
+                Some(WithdrawFee::UtxoPerKbyte { amount }) => {
+                    let dynamic = sat_from_big_decimal(&amount, platform_decimals)?;
+                    tx_builder = tx_builder.with_fee(ActualTxFee::Dynamic(dynamic));
+                },
+                Some(fee_policy) => {
+                    let error = format!(
+                        "Expected 'UtxoFixed' or 'UtxoPerKbyte' fee types, found {:?}",
+                        fee_policy
+                    );
+                    return MmError::err(WithdrawError::InvalidFeePolicy(error));
+                },
+                None => (),
+            };
+
+            let (mut unsigned, tx_data) = tx_builder.build().await.mm_err(|gen_tx_error| {
+                WithdrawError::from_generate_tx_error(gen_tx_error, coin.platform_ticker().into(), platform_decimals)
+            })?;
+            // The tokens of the spent outputs are committed to by the signature.
+            for input in unsigned.inputs.iter_mut() {
+                input.token = inputs_tokens.get(&input.previous_output).cloned();
+            }
+
+            let prev_script = ScriptBuilder::build_p2pkh(&my_address.hash);
+            let signed = sign_tx(
+                unsigned,
+                key_pair,
+                prev_script,
+                coin.platform_conf().signature_version,
+                coin.platform_conf().fork_id,
+            )?;
+            let fee_details = UtxoFeeDetails {
+                coin: Some(coin.platform_ticker().into()),
+                amount: big_decimal_from_sat_unsigned(tx_data.fee_amount, platform_decimals),
+            };
+            let my_address_string = coin.my_address()?;
+            let to_address = to.display_address().map_to_mm(WithdrawError::InternalError)?;
+
+            let total_amount = big_decimal_from_sat_unsigned(amount, coin.decimals());
+            let spent_by_me = total_amount.clone();
+            let (received_by_me, my_balance_change) = if my_address_string == to_address {
+                (total_amount.clone(), 0.into())
+            } else {
+                (0.into(), &total_amount * &BigDecimal::from(-1))
+            };
+
+            let tx_hash: BytesJson = signed.hash().reversed().take().to_vec().into();
+            let token_id: BytesJson = coin.token_id().take().to_vec().into();
+            let details = TransactionDetails {
+                tx_hex: serialize(&signed).into(),
+                internal_id: tx_hash.clone(),
+                tx_hash: tx_hash.to_tx_hash(),
+                from: vec![my_address_string],
+                to: vec![to_address],
+                total_amount,
+                spent_by_me,
+                received_by_me,
+                my_balance_change,
+                block_height: 0,
+                timestamp: now_sec(),
+                fee_details: Some(fee_details.into()),
+                coin: coin.ticker().into(),
+                kmd_rewards: None,
+                transaction_type: TransactionType::TokenTransfer(token_id),
+                memo: None,
+            };
+            Ok(details)
+        };
+        Box::new(fut.boxed().compat())
+    }
+
+    fn decimals(&self) -> u8 { self.decimals() }
+
+    fn convert_to_address(&self, from: &str, to_address_format: Json) -> Result<String, String> {
+        utxo_common::convert_to_address(&self.platform_coin, from, to_address_format)
+    }
+
+    fn validate_address(&self, address: &str) -> ValidateAddressResult { self.platform_coin.validate_address(address) }
+
+    fn process_history_loop(&self, _ctx: MmArc) -> Box<dyn Future<Item = (), Error = ()> + Send> {
+        warn!("process_history_loop is not implemented for CashTokens, the history is fetched by the platform coin");
+        Box::new(futures01::future::err(()))
+    }
+
+    fn history_sync_status(&self) -> HistorySyncState { self.platform_coin.history_sync_status() }
+
+    /// Get fee to be paid per 1 swap transaction
+    fn get_trade_fee(&self) -> Box<dyn Future<Item = TradeFee, Error = String> + Send> {
+        Box::new(futures01::future::err(self.swaps_not_supported()))
+    }
+
+    async fn get_sender_trade_fee(
+        &self,
+        _value: TradePreimageValue,
+        _stage: FeeApproxStage,
+    ) -> TradePreimageResult<TradeFee> {
+        MmError::err(TradePreimageError::InternalError(self.swaps_not_supported()))
+    }
+
+    fn get_receiver_trade_fee(&self, _stage: FeeApproxStage) -> TradePreimageFut<TradeFee> {
+        let error = TradePreimageError::InternalError(self.swaps_not_supported());
+        Box::new(futures01::future::err(MmError::new(error)))
+    }
+
+    async fn get_fee_to_send_taker_fee(
+        &self,
+        _dex_fee_amount: DexFee,
+        _stage: FeeApproxStage,
+    ) -> TradePreimageResult<TradeFee> {
+        MmError::err(TradePreimageError::InternalError(self.swaps_not_supported()))
+    }
+
+    fn required_confirmations(&self) -> u64 { self.conf.required_confirmations.load(AtomicOrdering::Relaxed) }
+
+    fn requires_notarization(&self) -> bool { false }
+
+    fn set_required_confirmations(&self, confirmations: u64) {
+        self.conf
+            .required_confirmations
+            .store(confirmations, AtomicOrdering::Relaxed);
+    }
+
+    fn set_requires_notarization(&self, _requires_nota: bool) {
+        warn!("set_requires_notarization has no effect on CASHTOKEN!")
+    }
+
+    fn swap_contract_address(&self) -> Option<BytesJson> { None }
+
+    fn fallback_swap_contract(&self) -> Option<BytesJson> { None }
+
+    fn mature_confirmations(&self) -> Option<u32> { self.platform_coin.mature_confirmations() }
+
+    fn coin_protocol_info(&self, _amount_to_receive: Option<MmNumber>) -> Vec<u8> { Vec::new() }
+
+    fn is_coin_protocol_supported(
+        &self,
+        _info: &Option<Vec<u8>>,
+        _amount_to_send: Option<MmNumber>,
+        _locktime: u64,
+        _is_maker: bool,
+    ) -> bool {
+        false
+    }
+
+    fn on_disabled(&self) -> Result<(), AbortedError> { self.conf.abortable_system.abort_all() }
+
+    fn on_token_deactivated(&self, _ticker: &str) {}
+}
+
+#[async_trait]
+impl CoinWithTxHistoryV2 for CashToken {
+    fn history_wallet_id(&self) -> WalletId { WalletId::new(self.platform_ticker().to_owned()) }
+
+    async fn get_tx_history_filters(
+        &self,
+        target: MyTxHistoryTarget,
+    ) -> MmResult<GetTxHistoryFilters, MyTxHistoryErrorV2> {
+        match target {
+            MyTxHistoryTarget::Iguana => (),
+            target => return MmError::err(MyTxHistoryErrorV2::with_expected_target(target, "Iguana")),
+        }
+        let my_address = self.my_address()?;
+        Ok(GetTxHistoryFilters::for_address(my_address).with_token_id(self.token_id().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod cash_token_tests {
+    use super::*;
+    use crate::utxo::bch::bch_coin_for_test;
+    use crate::utxo::rpc_clients::{ElectrumClient, UtxoRpcClientOps, VerboseTransactionFrom};
+    use crate::utxo::UtxoTx;
+    use chain::constants::SEQUENCE_FINAL;
+    use chain::{CashTokenNft, NftCapability, TransactionInput};
+    use common::block_on;
+    use mocktopus::mocking::{MockResult, Mockable};
+    use rpc::v1::types::Transaction as RpcTransaction;
+    use serialization::{deserialize_with_coin_variant, CoinVariant};
+
+    const TOKEN_DECIMALS: u8 = 2;
+
+    fn category() -> H256 { H256::from([0xbb; 32]) }
+
+    struct TestUnspents {
+        standard: OutPoint,
+        fungible: Vec<OutPoint>,
+        nft: OutPoint,
+    }
+
+    fn token_output(script_pubkey: Bytes, amount: u64, nft: Option<CashTokenNft>) -> TransactionOutput {
+        TransactionOutput {
+            value: CASH_TOKEN_OUTPUT_VALUE,
+            script_pubkey,
+            token: Some(CashTokenData {
+                category: category(),
+                amount,
+                nft,
+            }),
+        }
+    }
+
+    /// Mocks the wallet UTXOs: a plain BCH output, two outputs with 3 and 5 fungible tokens
+    /// and an output carrying an NFT together with 10 fungible tokens.
+    fn mock_unspents(coin: &BchCoin) -> TestUnspents {
+        let my_address = coin.as_ref().derivation_method.single_addr_or_err().unwrap();
+        let my_script: Bytes = ScriptBuilder::build_p2pkh(&my_address.hash).to_bytes();
+
+        let funding_input = TransactionInput {
+            previous_output: OutPoint {
+                hash: 1.into(),
+                index: 0,
+            },
+            script_sig: Bytes::default(),
+            sequence: SEQUENCE_FINAL,
+            script_witness: vec![],
+        };
+        let prev_tx = UtxoTx {
+            version: 2,
+            inputs: vec![funding_input],
+            outputs: vec![
+                TransactionOutput {
+                    value: 1000000,
+                    script_pubkey: my_script.clone(),
+                    token: None,
+                },
+                token_output(my_script.clone(), 300, None),
+                token_output(my_script.clone(), 500, None),
+                token_output(
+                    my_script,
+                    1000,
+                    Some(CashTokenNft {
+                        capability: NftCapability::None,
+                        commitment: Bytes::default(),
+                    }),
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let prev_tx_hash = prev_tx.hash();
+        let outpoint = |index| OutPoint {
+            hash: prev_tx_hash,
+            index,
+        };
+        let unspents: Vec<_> = prev_tx
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| UnspentInfo {
+                outpoint: outpoint(index as u32),
+                value: output.value,
+                height: Some(1),
+            })
+            .collect();
+        ElectrumClient::list_unspent
+            .mock_safe(move |_, _, _| MockResult::Return(Box::new(futures01::future::ok(unspents.clone()))));
+
+        let verbose = RpcTransaction {
+            hex: serialize(&prev_tx).into(),
+            txid: prev_tx_hash.reversed().into(),
+            hash: None,
+            size: Default::default(),
+            vsize: Default::default(),
+            version: prev_tx.version,
+            locktime: prev_tx.lock_time,
+            vin: vec![],
+            vout: vec![],
+            blockhash: Default::default(),
+            confirmations: 1,
+            rawconfirmations: None,
+            time: 0,
+            blocktime: 0,
+            height: Some(1),
+        };
+        BchCoin::get_verbose_transactions_from_cache_or_rpc.mock_safe(move |_, tx_ids| {
+            let result: HashMap<_, _> = tx_ids
+                .into_iter()
+                .map(|tx_id| {
+                    assert_eq!(tx_id, verbose.txid);
+                    (tx_id, VerboseTransactionFrom::Cache(verbose.clone()))
+                })
+                .collect();
+            MockResult::Return(Box::new(futures01::future::ok(result)))
+        });
+
+        TestUnspents {
+            standard: outpoint(0),
+            fungible: vec![outpoint(1), outpoint(2)],
+            nft: outpoint(3),
+        }
+    }
+
+    fn cash_token_for_test() -> CashToken {
+        let platform_coin = bch_coin_for_test();
+        CashToken::new(TOKEN_DECIMALS, "CASHTOKEN".into(), category(), platform_coin, 1).unwrap()
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_cash_token_balance() {
+        let coin = cash_token_for_test();
+        mock_unspents(&coin.platform_coin);
+
+        // The fungible tokens held together with the NFT are unspendable.
+        let balance = coin.my_balance().wait().unwrap();
+        assert_eq!(balance.spendable, "8".parse().unwrap());
+        assert_eq!(balance.unspendable, "10".parse().unwrap());
+
+        let my_address = coin
+            .platform_coin
+            .as_ref()
+            .derivation_method
+            .single_addr_or_err()
+            .unwrap();
+        let bch_unspents = block_on(coin.platform_coin.bch_unspents_for_display(my_address)).unwrap();
+        let other_category_balance = bch_unspents.cash_token_balance(&H256::from([0xcc; 32]), TOKEN_DECIMALS);
+        assert_eq!(other_category_balance, CoinBalance::default());
+
+        // The BCH value of the token outputs is unspendable on the platform coin.
+        let platform_balance = coin.platform_coin.my_balance().wait().unwrap();
+        assert_eq!(platform_balance.spendable, "0.01".parse().unwrap());
+        assert_eq!(platform_balance.unspendable, "0.00003".parse().unwrap());
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_withdraw_with_token_change() {
+        let coin = cash_token_for_test();
+        let unspents = mock_unspents(&coin.platform_coin);
+        let my_address = coin.my_address().unwrap();
+
+        let withdraw_req = WithdrawRequest {
+            amount: "6".parse().unwrap(),
+            from: None,
+            to: my_address.clone(),
+            coin: coin.ticker().into(),
+            max: false,
+            fee: Some(WithdrawFee::UtxoFixed {
+                amount: "0.00001".parse().unwrap(),
+            }),
+            memo: None,
+        };
+        let tx_details = coin.withdraw(withdraw_req).wait().unwrap();
+        assert_eq!(tx_details.total_amount, "6".parse().unwrap());
+        assert_eq!(tx_details.to, vec![my_address]);
+        assert_eq!(tx_details.my_balance_change, 0.into());
+
+        let tx: UtxoTx = deserialize_with_coin_variant(tx_details.tx_hex.0.as_slice(), CoinVariant::BCH).unwrap();
+        let spent: Vec<_> = tx.inputs.iter().map(|input| input.previous_output).collect();
+        // Both fungible-only outputs are required to send 6 tokens, the BCH output pays the fee.
+        assert!(unspents.fungible.iter().all(|outpoint| spent.contains(outpoint)));
+        assert!(spent.contains(&unspents.standard));
+        // The NFT carrying output must never be spent as the NFT would be burned.
+        assert!(!spent.contains(&unspents.nft));
+
+        let tokens: Vec<_> = tx.outputs.iter().map(|output| output.token.clone()).collect();
+        let expected_token = |amount| {
+            Some(CashTokenData {
+                category: category(),
+                amount,
+                nft: None,
+            })
+        };
+        // The sent tokens, the token change and the BCH change.
+        assert_eq!(tokens, vec![expected_token(600), expected_token(200), None]);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_withdraw_nft_held_tokens_not_sufficient() {
+        let coin = cash_token_for_test();
+        mock_unspents(&coin.platform_coin);
+
+        // The 10 tokens held together with the NFT can't be sent.
+        let withdraw_req = WithdrawRequest {
+            amount: "9".parse().unwrap(),
+            from: None,
+            to: coin.my_address().unwrap(),
+            coin: coin.ticker().into(),
+            max: false,
+            fee: None,
+            memo: None,
+        };
+        let error = coin.withdraw(withdraw_req).wait().unwrap_err().into_inner();
+        match error {
+            WithdrawError::NotSufficientBalance {
+                available, required, ..
+            } => {
+                assert_eq!(available, "8".parse().unwrap());
+                assert_eq!(required, "9".parse().unwrap());
+            },
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
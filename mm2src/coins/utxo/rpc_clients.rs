@@ -550,6 +550,28 @@ pub struct GetAddressInfoRes {
     pub is_watch_only: bool,
 }
 
+/// The response of the zcashd `z_gettreestate` RPC.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ZTreeStateRes {
+    pub hash: H256Json,
+    pub height: u32,
+    pub time: u32,
+    #[serde(default)]
+    pub sapling: ZSaplingTreeState,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ZSaplingTreeState {
+    /// Is absent if there are no Sapling commitments at the requested height yet.
+    pub commitments: Option<ZSaplingCommitments>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ZSaplingCommitments {
+    #[serde(rename = "finalState")]
+    pub final_state: BytesJson,
+}
+
 #[derive(Debug)]
 pub enum EstimateFeeMethod {
     /// estimatefee, deprecated in many coins: https://bitcoincore.org/en/doc/0.16.0/rpc/util/estimatefee/
@@ -1068,6 +1090,11 @@ impl NativeClientImpl {
         rpc_func!(self, "getrawtransaction", txid, verbose)
     }
 
+    /// https://zcash.github.io/rpc/z_gettreestate.html
+    pub fn z_get_tree_state(&self, height: u64) -> RpcRes<ZTreeStateRes> {
+        rpc_func!(self, "z_gettreestate", height.to_string())
+    }
+
     /// https://developer.bitcoin.org/reference/rpc/estimatefee.html
     /// It is recommended to set n_blocks as low as possible.
     /// However, in some cases, n_blocks = 1 leads to an unreasonably high fee estimation.
@@ -239,6 +239,7 @@ fn slp_send_output(token_id: &H256, amounts: &[u64]) -> TransactionOutput {
     TransactionOutput {
         value: 0,
         script_pubkey: script_builder.into_bytes(),
+        token: None,
     }
 }
 
@@ -285,6 +286,7 @@ pub fn slp_genesis_output(
     TransactionOutput {
         value: 0,
         script_pubkey: script_builder.into_bytes(),
+        token: None,
     }
 }
 
@@ -381,6 +383,7 @@ impl SlpToken {
         outputs.extend(slp_outputs.into_iter().map(|spend_to| TransactionOutput {
             value: self.platform_dust(),
             script_pubkey: spend_to.script_pubkey,
+            token: None,
         }));
 
         if change > 0 {
@@ -388,6 +391,7 @@ impl SlpToken {
             let slp_change_out = TransactionOutput {
                 value: self.platform_dust(),
                 script_pubkey: ScriptBuilder::build_p2pkh(&my_public_key.address_hash().into()).to_bytes(),
+                token: None,
             };
             outputs.push(slp_change_out);
         }
@@ -636,6 +640,7 @@ impl SlpToken {
         let slp_output = TransactionOutput {
             value: self.platform_dust(),
             script_pubkey: my_script_pubkey.to_bytes(),
+            token: None,
         };
         outputs.push(slp_output);
 
@@ -2023,6 +2028,7 @@ mod slp_tests {
         let expected_output = TransactionOutput {
             value: 0,
             script_pubkey: expected_script.into(),
+            token: None,
         };
 
         let actual_output = slp_send_output(
@@ -2036,6 +2042,7 @@ mod slp_tests {
         let expected_output = TransactionOutput {
             value: 0,
             script_pubkey: expected_script.into(),
+            token: None,
         };
 
         let actual_output = slp_send_output(
@@ -2054,6 +2061,7 @@ mod slp_tests {
         let expected_output = TransactionOutput {
             value: 0,
             script_pubkey: expected_script.into(),
+            token: None,
         };
 
         let actual_output = slp_genesis_output("ADEX", "ADEX", None, None, 8, None, 1000_0000_0000);
@@ -2065,6 +2073,7 @@ mod slp_tests {
         let expected_output = TransactionOutput {
             value: 0,
             script_pubkey: expected_script.into(),
+            token: None,
         };
 
         let actual_output = slp_genesis_output(
@@ -2149,6 +2158,7 @@ mod slp_tests {
         let invalid_slp_send_out = TransactionOutput {
             value: 1000,
             script_pubkey: ScriptBuilder::build_p2sh(&dhash160(&htlc_script).into()).into(),
+            token: None,
         };
 
         let tx_err = block_on(generate_and_send_tx(
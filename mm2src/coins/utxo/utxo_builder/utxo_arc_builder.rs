@@ -167,7 +167,11 @@ async fn merge_utxo_loop<T>(
             info!("Trying to merge {} UTXOs of coin {}", unspents.len(), ticker);
             let value = unspents.iter().fold(0, |sum, unspent| sum + unspent.value);
             let script_pubkey = Builder::build_p2pkh(&my_address.hash).to_bytes();
-            let output = TransactionOutput { value, script_pubkey };
+            let output = TransactionOutput {
+                value,
+                script_pubkey,
+                token: None,
+            };
             let merge_tx_fut = generate_and_send_tx(
                 &coin,
                 unspents,
@@ -809,6 +809,7 @@ impl<'a, T: AsRef<UtxoCoinFields> + UtxoTxGenerationOps> UtxoTxBuilder<'a, T> {
                 sequence: SEQUENCE_FINAL,
                 amount: input.value,
                 witness: Vec::new(),
+                token: None,
             }));
         self
     }
@@ -978,6 +979,7 @@ impl<'a, T: AsRef<UtxoCoinFields> + UtxoTxGenerationOps> UtxoTxBuilder<'a, T> {
                 sequence: SEQUENCE_FINAL,
                 amount: utxo.value,
                 witness: vec![],
+                token: None,
             });
             self.sum_inputs += utxo.value;
 
@@ -1016,6 +1018,7 @@ impl<'a, T: AsRef<UtxoCoinFields> + UtxoTxGenerationOps> UtxoTxBuilder<'a, T> {
                 TransactionOutput {
                     value: change,
                     script_pubkey: change_script_pubkey.clone(),
+                    token: None,
                 }
             });
             received_by_me += change;
@@ -1084,6 +1087,7 @@ pub async fn calc_interest_if_required<T: UtxoCommonOps>(
                     let change_output = TransactionOutput {
                         script_pubkey: my_script_pub,
                         value: maybe_change_output_value,
+                        token: None,
                     };
                     unsigned.outputs.push(change_output);
                     data.unused_change = 0;
@@ -1166,6 +1170,7 @@ async fn p2sh_spending_tx_preimage<T: UtxoCommonOps>(
             },
             amount,
             witness: Vec::new(),
+            token: None,
         }],
         outputs,
         expiry_height: 0,
@@ -1271,6 +1276,7 @@ async fn gen_taker_funding_spend_preimage<T: UtxoCommonOps>(
     let payment_output = TransactionOutput {
         value: funding_amount - fee,
         script_pubkey: Builder::build_p2sh(&AddressHashEnum::AddressHash(dhash160(&payment_redeem_script))).to_bytes(),
+        token: None,
     };
 
     p2sh_spending_tx_preimage(
@@ -1501,6 +1507,7 @@ async fn gen_taker_payment_spend_preimage<T: UtxoCommonOps>(
     let dex_fee_output = TransactionOutput {
         value: dex_fee_sat,
         script_pubkey: Builder::build_p2pkh(&dex_fee_address.hash).to_bytes(),
+        token: None,
     };
 
     p2sh_spending_tx_preimage(
@@ -1629,6 +1636,7 @@ pub async fn sign_and_broadcast_taker_payment_spend<T: UtxoCommonOps>(
     let maker_output = TransactionOutput {
         value: maker_sat - miner_fee,
         script_pubkey: output_script(maker_address, ScriptType::P2PKH).to_bytes(),
+        token: None,
     };
     signer.outputs.push(maker_output);
     drop_mutability!(signer);
@@ -1700,12 +1708,14 @@ fn generate_taker_fee_tx_outputs(
     let mut outputs = vec![TransactionOutput {
         value: fee_amount,
         script_pubkey: Builder::build_p2pkh(address_hash).to_bytes(),
+        token: None,
     }];
 
     if let Some(burn_amount) = dex_fee.burn_uamount(decimals)? {
         outputs.push(TransactionOutput {
             value: burn_amount,
             script_pubkey: Builder::default().push_opcode(Opcode::OP_RETURN).into_bytes(),
+            token: None,
         });
     }
 
@@ -1820,6 +1830,7 @@ pub fn send_maker_spends_taker_payment<T: UtxoCommonOps + SwapOps>(coin: T, args
         let output = TransactionOutput {
             value: payment_value - fee,
             script_pubkey,
+            token: None,
         };
 
         let input = P2SHSpendingTxInput {
@@ -1926,6 +1937,7 @@ pub fn create_maker_payment_spend_preimage<T: UtxoCommonOps + SwapOps>(
         let output = TransactionOutput {
             value: payment_value - fee,
             script_pubkey,
+            token: None,
         };
 
         let input = P2SHSpendingTxInput {
@@ -1985,6 +1997,7 @@ pub fn create_taker_payment_refund_preimage<T: UtxoCommonOps + SwapOps>(
         let output = TransactionOutput {
             value: payment_value - fee,
             script_pubkey,
+            token: None,
         };
 
         let input = P2SHSpendingTxInput {
@@ -2042,6 +2055,7 @@ pub fn send_taker_spends_maker_payment<T: UtxoCommonOps + SwapOps>(coin: T, args
         let output = TransactionOutput {
             value: payment_value - fee,
             script_pubkey,
+            token: None,
         };
 
         let input = P2SHSpendingTxInput {
@@ -2108,6 +2122,7 @@ async fn refund_htlc_payment<T: UtxoCommonOps + SwapOps>(
     let output = TransactionOutput {
         value: payment_value - fee,
         script_pubkey,
+        token: None,
     };
 
     let input = P2SHSpendingTxInput {
@@ -4543,6 +4558,7 @@ where
     let htlc_out = TransactionOutput {
         value: amount,
         script_pubkey: Builder::build_p2sh(&redeem_script_hash.into()).into(),
+        token: None,
     };
     // record secret hash to blockchain too making it impossible to lose
     // lock time may be easily brute forced so it is not mandatory to record it
@@ -4560,6 +4576,7 @@ where
     let op_return_out = TransactionOutput {
         value: 0,
         script_pubkey: op_return_script,
+        token: None,
     };
 
     let payment_address = Address {
@@ -4971,6 +4988,7 @@ where
     let output = TransactionOutput {
         value: payment_value - fee,
         script_pubkey,
+        token: None,
     };
 
     let input = P2SHSpendingTxInput {
@@ -5014,6 +5032,7 @@ where
     let expected_output = TransactionOutput {
         value: expected_amount_sat,
         script_pubkey: Builder::build_p2sh(&AddressHashEnum::AddressHash(dhash160(&redeem_script))).into(),
+        token: None,
     };
 
     if args.funding_tx.outputs.get(0) != Some(&expected_output) {
@@ -19,7 +19,7 @@ use keys::{Address, Type as ScriptType};
 use mm2_err_handle::prelude::*;
 use mm2_metrics::MetricsArc;
 use mm2_number::BigDecimal;
-use rpc::v1::types::{TransactionInputEnum, H256 as H256Json};
+use rpc::v1::types::{Bytes as BytesJson, TransactionInputEnum, H256 as H256Json};
 use serialization::deserialize;
 use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
@@ -258,6 +258,21 @@ pub async fn tx_from_storage_or_rpc<Coin, Storage>(
     tx_hash: &H256Json,
     storage: &Storage,
 ) -> MmResult<UtxoTx, UtxoTxDetailsError>
+where
+    Coin: CoinWithTxHistoryV2 + UtxoCommonOps,
+    Storage: TxHistoryStorage,
+{
+    let tx_bytes = tx_bytes_from_storage_or_rpc(coin, tx_hash, storage).await?;
+    let tx = deserialize(tx_bytes.0.as_slice())?;
+    Ok(tx)
+}
+
+/// Returns the transaction bytes from the storage cache or requests them via RPC and caches them.
+pub async fn tx_bytes_from_storage_or_rpc<Coin, Storage>(
+    coin: &Coin,
+    tx_hash: &H256Json,
+    storage: &Storage,
+) -> MmResult<BytesJson, UtxoTxDetailsError>
 where
     Coin: CoinWithTxHistoryV2 + UtxoCommonOps,
     Storage: TxHistoryStorage,
@@ -272,8 +287,7 @@ where
             tx_bytes
         },
     };
-    let tx = deserialize(tx_bytes.0.as_slice())?;
-    Ok(tx)
+    Ok(tx_bytes)
 }
 
 /// [`UtxoTxHistoryOps::my_addresses_balances`] implementation.
@@ -199,6 +199,7 @@ fn test_generate_transaction() {
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 999,
+        token: None,
     }];
 
     let builder = UtxoTxBuilder::new(&coin)
@@ -217,6 +218,7 @@ fn test_generate_transaction() {
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 98001,
+        token: None,
     }];
 
     let builder = UtxoTxBuilder::new(&coin)
@@ -241,6 +243,7 @@ fn test_generate_transaction() {
     let outputs = vec![TransactionOutput {
         script_pubkey: Builder::build_p2pkh(&coin.as_ref().derivation_method.unwrap_single_addr().hash).to_bytes(),
         value: 100000,
+        token: None,
     }];
 
     // test that fee is properly deducted from output amount equal to input amount (max withdraw case)
@@ -267,6 +270,7 @@ fn test_generate_transaction() {
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 100000,
+        token: None,
     }];
 
     // test that generate_transaction returns an error when input amount is not sufficient to cover output + fee
@@ -960,6 +964,7 @@ fn test_utxo_lock() {
     let output = TransactionOutput {
         value: 1000000,
         script_pubkey: Builder::build_p2pkh(&coin.as_ref().derivation_method.unwrap_single_addr().hash).to_bytes(),
+        token: None,
     };
     let mut futures = vec![];
     for _ in 0..5 {
@@ -1142,6 +1147,7 @@ fn test_generate_transaction_relay_fee_is_used_when_dynamic_fee_is_lower() {
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 900000000,
+        token: None,
     }];
 
     let builder = UtxoTxBuilder::new(&coin)
@@ -1184,6 +1190,7 @@ fn test_generate_transaction_relay_fee_is_used_when_dynamic_fee_is_lower_and_ded
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 1000000000,
+        token: None,
     }];
 
     let tx_builder = UtxoTxBuilder::new(&coin)
@@ -1232,6 +1239,7 @@ fn test_generate_tx_fee_is_correct_when_dynamic_fee_is_larger_than_relay() {
     let outputs = vec![TransactionOutput {
         script_pubkey: vec![].into(),
         value: 19000000000,
+        token: None,
     }];
 
     let builder = UtxoTxBuilder::new(&coin)
@@ -1535,6 +1543,7 @@ fn test_spam_rick() {
     let output = TransactionOutput {
         value: 1000000,
         script_pubkey: Builder::build_p2pkh(&coin.as_ref().derivation_method.unwrap_single_addr().hash).to_bytes(),
+        token: None,
     };
     let mut futures = vec![];
     for _ in 0..5 {
@@ -2711,6 +2720,7 @@ fn test_generate_tx_doge_fee() {
     let outputs = vec![TransactionOutput {
         value: 100000000,
         script_pubkey: vec![0; 26].into(),
+        token: None,
     }];
     let builder = UtxoTxBuilder::new(&doge)
         .add_available_inputs(unspents)
@@ -2728,6 +2738,7 @@ fn test_generate_tx_doge_fee() {
         TransactionOutput {
             value: 100000000,
             script_pubkey: vec![0; 26].into(),
+            token: None,
         };
         40
     ];
@@ -2748,6 +2759,7 @@ fn test_generate_tx_doge_fee() {
         TransactionOutput {
             value: 100000000,
             script_pubkey: vec![0; 26].into(),
+            token: None,
         };
         60
     ];
@@ -3325,6 +3337,7 @@ fn test_split_qtum() {
         TransactionOutput {
             value: 100_000_000,
             script_pubkey: script.to_bytes(),
+            token: None,
         };
         40
     ];
@@ -150,7 +150,11 @@ where
             let value = sat_from_big_decimal(&req.amount, decimals)?;
             (value, FeePolicy::SendExact)
         };
-        let outputs = vec![TransactionOutput { value, script_pubkey }];
+        let outputs = vec![TransactionOutput {
+            value,
+            script_pubkey,
+            token: None,
+        }];
 
         let mut tx_builder = UtxoTxBuilder::new(coin)
             .with_from_address(self.sender_address())
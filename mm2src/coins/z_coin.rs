@@ -2,7 +2,7 @@ use crate::coin_errors::MyAddressError;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::my_tx_history_v2::{MyTxHistoryErrorV2, MyTxHistoryRequestV2, MyTxHistoryResponseV2};
 #[cfg(not(target_arch = "wasm32"))]
-use crate::rpc_command::init_withdraw::{InitWithdrawCoin, WithdrawInProgressStatus, WithdrawTaskHandle};
+use crate::rpc_command::init_withdraw::{WithdrawInProgressStatus, WithdrawTaskHandle};
 use crate::utxo::rpc_clients::{ElectrumRpcRequest, UnspentInfo, UtxoRpcClientEnum, UtxoRpcError, UtxoRpcFut,
                                UtxoRpcResult};
 use crate::utxo::utxo_builder::UtxoCoinBuildError;
@@ -35,7 +35,7 @@ use common::executor::{AbortableSystem, AbortedError};
 use common::sha256_digest;
 use common::{log, one_thousand_u32};
 use crypto::privkey::{key_pair_from_secret, secp_privkey_from_hash};
-use crypto::{Bip32DerPathOps, GlobalHDAccountArc};
+use crypto::{Bip32DerPathOps, ChildNumber, DerivationPath, GlobalHDAccountArc, RpcDerivationPath};
 use crypto::{StandardHDCoinAddress, StandardHDPathToCoin};
 use futures::compat::Future01CompatExt;
 use futures::lock::Mutex as AsyncMutex;
@@ -53,7 +53,7 @@ use script::{Builder as ScriptBuilder, Opcode, Script, TransactionInputSigner};
 use serde_json::Value as Json;
 use serialization::CoinVariant;
 use std::collections::{HashMap, HashSet};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::iter;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -61,15 +61,15 @@ use std::sync::Arc;
 use z_coin_errors::ZCoinBalanceError;
 use z_rpc::{SaplingSyncConnector, SaplingSyncGuard};
 use zcash_client_backend::encoding::{decode_payment_address, encode_extended_spending_key, encode_payment_address};
-use zcash_client_backend::wallet::SpendableNote;
+use zcash_client_backend::wallet::{AccountId, SpendableNote};
 use zcash_primitives::consensus::{BlockHeight, NetworkUpgrade, Parameters, H0};
-use zcash_primitives::memo::MemoBytes;
+use zcash_primitives::memo::{Memo, MemoBytes};
 use zcash_primitives::sapling::keys::OutgoingViewingKey;
 use zcash_primitives::sapling::note_encryption::try_sapling_output_recovery;
 use zcash_primitives::transaction::components::{Amount, TxOut};
 use zcash_primitives::transaction::Transaction as ZTransaction;
 use zcash_primitives::zip32::ChildIndex as Zip32Child;
-use zcash_primitives::{constants::mainnet as z_mainnet_constants, sapling::PaymentAddress,
+use zcash_primitives::{constants::mainnet as z_mainnet_constants, sapling::Diversifier, sapling::PaymentAddress,
                        zip32::ExtendedFullViewingKey, zip32::ExtendedSpendingKey};
 use zcash_proofs::prover::LocalTxProver;
 
@@ -80,6 +80,11 @@ mod z_rpc;
 use z_rpc::init_light_client;
 pub use z_rpc::{FirstSyncBlock, SyncStatus};
 
+mod z_accounts;
+pub use z_accounts::{ZAccountError, ZCoinAccount};
+
+#[cfg(not(target_arch = "wasm32"))] mod z_shield;
+
 cfg_native!(
     use crate::{NumConversError, TransactionDetails, TxFeeDetails};
     use crate::utxo::{UtxoFeeDetails, sat_from_big_decimal};
@@ -90,7 +95,6 @@ cfg_native!(
     use db_common::sqlite::rusqlite::{Error as SqlError, Row};
     use db_common::sqlite::sql_builder::{name, SqlBuilder, SqlName};
     use zcash_client_backend::data_api::WalletRead;
-    use zcash_client_backend::wallet::{AccountId};
     use zcash_client_sqlite::error::SqliteClientError as ZcashClientError;
     use zcash_client_sqlite::wallet::{get_balance};
     use zcash_client_sqlite::wallet::transact::get_spendable_notes;
@@ -98,6 +102,7 @@ cfg_native!(
     use zcash_primitives::transaction::builder::Builder as ZTxBuilder;
     use zcash_proofs::default_params_folder;
     use z_rpc::{init_native_client};
+    use z_accounts::set_wallet_account_ids;
 );
 
 #[allow(unused)] mod z_coin_errors;
@@ -136,6 +141,8 @@ const SAPLING_OUTPUT_EXPECTED_HASH: &str = "2f0ebbcbb9bb0bcffe95a397e7eba89c29eb
 cfg_native!(
     const BLOCKS_TABLE: &str = "blocks";
     const TRANSACTIONS_TABLE: &str = "transactions";
+    const RECEIVED_MEMOS_QUERY: &str = "SELECT account, diversifier, memo FROM received_notes
+        WHERE tx = ?1 AND memo IS NOT NULL ORDER BY output_index;";
 );
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -206,6 +213,8 @@ pub struct ZCoinFields {
     light_wallet_db: WalletDbShared,
     consensus_params: ZcoinConsensusParams,
     sync_state_connector: AsyncMutex<SaplingSyncConnector>,
+    /// The primary account goes first, its keys and default address are also kept in the fields above.
+    z_accounts: Vec<ZCoinAccount>,
 }
 
 impl Transaction for ZTransaction {
@@ -235,6 +244,43 @@ pub struct ZOutput {
     pub memo: Option<MemoBytes>,
 }
 
+/// The outgoing viewing key a shielded output is encrypted with.
+/// It allows the holder of the key to recover the output details, e.g. to show it in the sender's history.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZOutgoingViewingKey {
+    /// The wallet's own outgoing viewing key.
+    #[default]
+    Sender,
+    /// The output can't be recovered by the sender.
+    None,
+    /// Hex-encoded 32-byte outgoing viewing key, e.g. of an auditor.
+    Custom(String),
+}
+
+/// An additional recipient of the ZCoin withdrawal.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ZWithdrawOutput {
+    pub to: String,
+    pub amount: BigDecimal,
+    /// Text or `0x`-prefixed hex memo.
+    pub memo: Option<String>,
+}
+
+/// The shielded params of the ZCoin withdrawal, nested into the `task::withdraw::init` request.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ZWithdrawParams {
+    /// The ZIP32 account to spend the notes from, the primary account is used if not set.
+    #[serde(default)]
+    pub z_account: Option<u32>,
+    /// The outgoing viewing key the shielded outputs are encrypted with.
+    #[serde(default)]
+    pub viewing_key: ZOutgoingViewingKey,
+    /// Additional shielded outputs, each with its own memo.
+    #[serde(default)]
+    pub z_outputs: Vec<ZWithdrawOutput>,
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 struct ZCoinSqlTxHistoryItem {
     tx_hash: Vec<u8>,
@@ -243,6 +289,35 @@ struct ZCoinSqlTxHistoryItem {
     timestamp: i64,
     received_amount: i64,
     spent_amount: i64,
+    /// The memos of the notes received in this transaction, decrypted during the sync.
+    received_memos: Vec<ZReceivedMemo>,
+}
+
+/// The memo of a received note along with the account and the diversifier of the address it was sent to.
+#[cfg(not(target_arch = "wasm32"))]
+struct ZReceivedMemo {
+    account: AccountId,
+    diversifier: Diversifier,
+    memo: MemoBytes,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ZReceivedMemo {
+    /// Returns `None` if the stored diversifier or memo is malformed.
+    fn try_from_sql_row(row: &Row<'_>) -> Result<Option<Self>, SqlError> {
+        let account: u32 = row.get(0)?;
+        let diversifier: Vec<u8> = row.get(1)?;
+        let memo: Vec<u8> = row.get(2)?;
+        let diversifier = match diversifier.try_into() {
+            Ok(diversifier) => Diversifier(diversifier),
+            Err(_) => return Ok(None),
+        };
+        Ok(MemoBytes::from_bytes(&memo).ok().map(|memo| ZReceivedMemo {
+            account: AccountId(account),
+            diversifier,
+            memo,
+        }))
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -257,6 +332,7 @@ impl ZCoinSqlTxHistoryItem {
             timestamp: row.get(3)?,
             received_amount: row.get(4)?,
             spent_amount: row.get(5)?,
+            received_memos: Vec::new(),
         })
     }
 }
@@ -292,6 +368,16 @@ pub struct ZcoinTxDetails {
     coin: String,
     /// Internal MM2 id used for internal transaction identification, for some coins it might be equal to transaction hash
     internal_id: i64,
+    /// The memos of the received outputs and the sent outputs recovered with our outgoing viewing key
+    memos: Vec<ZcoinTxMemo>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ZcoinTxMemo {
+    /// The recipient of the output the memo is attached to
+    address: String,
+    /// The memo text or `0x`-prefixed hex if the memo contains arbitrary data
+    memo: String,
 }
 
 impl ZCoin {
@@ -347,19 +433,28 @@ impl ZCoin {
 
     #[cfg(not(target_arch = "wasm32"))]
     async fn my_balance_sat(&self) -> Result<u64, MmError<ZcashClientError>> {
+        self.account_balance_sat(self.primary_z_account().wallet_account_id)
+            .await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn my_balance_sat(&self) -> Result<u64, MmError<ZCoinBalanceError>> { todo!() }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn account_balance_sat(&self, account: AccountId) -> Result<u64, MmError<ZcashClientError>> {
         let wallet_db = self.z_fields.light_wallet_db.clone();
         async_blocking(move || {
-            let balance = get_balance(&wallet_db.db.lock(), AccountId::default())?.into();
+            let balance = get_balance(&wallet_db.db.lock(), account)?.into();
             Ok(balance)
         })
         .await
     }
 
-    #[cfg(target_arch = "wasm32")]
-    async fn my_balance_sat(&self) -> Result<u64, MmError<ZCoinBalanceError>> { todo!() }
-
     #[cfg(not(target_arch = "wasm32"))]
-    async fn get_spendable_notes(&self) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> {
+    async fn get_spendable_notes(
+        &self,
+        account: AccountId,
+    ) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> {
         let wallet_db = self.z_fields.light_wallet_db.clone();
         async_blocking(move || {
             let guard = wallet_db.db.lock();
@@ -370,7 +465,7 @@ impl ZCoin {
                 Some((_, latest)) => latest,
                 None => return Ok(Vec::new()),
             };
-            get_spendable_notes(&guard, AccountId::default(), latest_db_block)
+            get_spendable_notes(&guard, account, latest_db_block)
                 .map_err(|err| MmError::new(SpendableNotesError::DBClientError(err.to_string())))
         })
         .await
@@ -378,12 +473,20 @@ impl ZCoin {
 
     #[cfg(target_arch = "wasm32")]
     #[allow(unused)]
-    async fn get_spendable_notes(&self) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> { todo!() }
+    async fn get_spendable_notes(
+        &self,
+        _account: AccountId,
+    ) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> {
+        todo!()
+    }
 
-    /// Returns spendable notes
+    /// Returns spendable notes of the account
     #[allow(unused)]
-    async fn spendable_notes_ordered(&self) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> {
-        let mut unspents = self.get_spendable_notes().await?;
+    async fn spendable_notes_ordered(
+        &self,
+        account: AccountId,
+    ) -> Result<Vec<SpendableNote>, MmError<SpendableNotesError>> {
+        let mut unspents = self.get_spendable_notes(account).await?;
 
         unspents.sort_unstable_by(|a, b| a.note_value.cmp(&b.note_value));
         Ok(unspents)
@@ -398,12 +501,23 @@ impl ZCoin {
         }
     }
 
-    /// Generates a tx sending outputs from our address
-    #[cfg(not(target_arch = "wasm32"))]
+    /// Generates a tx sending outputs from our primary account
     async fn gen_tx(
         &self,
         t_outputs: Vec<TxOut>,
         z_outputs: Vec<ZOutput>,
+    ) -> Result<(ZTransaction, AdditionalTxData, SaplingSyncGuard<'_>), MmError<GenTxError>> {
+        self.gen_tx_from_account(self.primary_z_account(), t_outputs, z_outputs)
+            .await
+    }
+
+    /// Generates a tx spending the notes of the given account, the change is sent to the account's default address
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn gen_tx_from_account(
+        &self,
+        account: &ZCoinAccount,
+        t_outputs: Vec<TxOut>,
+        z_outputs: Vec<ZOutput>,
     ) -> Result<(ZTransaction, AdditionalTxData, SaplingSyncGuard<'_>), MmError<GenTxError>> {
         let sync_guard = self.wait_for_gen_tx_blockchain_sync().await?;
 
@@ -415,7 +529,7 @@ impl ZCoin {
         let total_required = &total_output + &tx_fee;
 
         let spendable_notes = self
-            .spendable_notes_ordered()
+            .spendable_notes_ordered(account.wallet_account_id)
             .await
             .map_err(|err| GenTxError::SpendableNotesError(err.to_string()))?;
         let mut total_input_amount = BigDecimal::from(0);
@@ -428,14 +542,17 @@ impl ZCoin {
         for spendable_note in spendable_notes {
             total_input_amount += big_decimal_from_sat_unsigned(spendable_note.note_value.into(), self.decimals());
 
-            let note = self
-                .z_fields
-                .my_z_addr
-                .create_note(spendable_note.note_value.into(), spendable_note.rseed)
+            // The note may be received to any diversified address of the account.
+            let note = account
+                .evk
+                .fvk
+                .vk
+                .to_payment_address(spendable_note.diversifier)
+                .and_then(|address| address.create_note(spendable_note.note_value.into(), spendable_note.rseed))
                 .or_mm_err(|| GenTxError::FailedToCreateNote)?;
             tx_builder.add_sapling_spend(
-                self.z_fields.z_spending_key.clone(),
-                *self.z_fields.my_z_addr.diversifier(),
+                account.spending_key.clone(),
+                spendable_note.diversifier,
                 note,
                 spendable_note
                     .witness
@@ -458,7 +575,7 @@ impl ZCoin {
         }
 
         for z_out in z_outputs {
-            if z_out.to_addr == self.z_fields.my_z_addr {
+            if self.is_my_z_address(&z_out.to_addr) {
                 received_by_me += u64::from(z_out.amount);
             }
 
@@ -470,8 +587,8 @@ impl ZCoin {
             received_by_me += change_sat;
 
             tx_builder.add_sapling_output(
-                Some(self.z_fields.evk.fvk.ovk),
-                self.z_fields.my_z_addr.clone(),
+                Some(account.evk.fvk.ovk),
+                account.default_address.clone(),
                 Amount::from_u64(change_sat).map_to_mm(|_| {
                     GenTxError::NumConversion(NumConversError(format!(
                         "Failed to get ZCash amount from {}",
@@ -503,8 +620,9 @@ impl ZCoin {
     }
 
     #[cfg(target_arch = "wasm32")]
-    async fn gen_tx(
+    async fn gen_tx_from_account(
         &self,
+        _account: &ZCoinAccount,
         _t_outputs: Vec<TxOut>,
         _z_outputs: Vec<ZOutput>,
     ) -> Result<(ZTransaction, AdditionalTxData, SaplingSyncGuard<'_>), MmError<GenTxError>> {
@@ -580,11 +698,19 @@ impl ZCoin {
                 .sql()
                 .expect("valid query");
 
-            let sql_items = conn
+            let mut sql_items = conn
                 .prepare(&sql)?
                 .query_map([], ZCoinSqlTxHistoryItem::try_from_sql_row)?
                 .collect::<Result<Vec<_>, _>>()?;
 
+            let mut memos_stmt = conn.prepare(RECEIVED_MEMOS_QUERY)?;
+            for item in sql_items.iter_mut() {
+                item.received_memos = memos_stmt
+                    .query_map([item.internal_id], ZReceivedMemo::try_from_sql_row)?
+                    .filter_map(Result::transpose)
+                    .collect::<Result<_, _>>()?;
+            }
+
             Ok(SqlTxHistoryRes {
                 transactions: sql_items,
                 total_tx_count,
@@ -662,17 +788,39 @@ impl ZCoin {
             to.insert(self.my_z_address_encoded());
         }
 
+        let hrp = self.consensus_params_ref().hrp_sapling_payment_address();
+        let mut memos: Vec<_> = sql_item
+            .received_memos
+            .iter()
+            .filter_map(|received| {
+                let memo = memo_to_string(&received.memo)?;
+                // The note could be sent to a diversified address of any activated account.
+                let address = received_note_address(self.z_accounts(), received.account, received.diversifier)
+                    .map(|address| encode_payment_address(hrp, &address))
+                    .unwrap_or_else(|| self.my_z_address_encoded());
+                Some(ZcoinTxMemo { address, memo })
+            })
+            .collect();
+
         for z_out in z_tx.shielded_outputs.iter() {
-            if let Some((_, address, _)) = try_sapling_output_recovery(
+            if let Some((_, address, memo)) = try_sapling_output_recovery(
                 self.consensus_params_ref(),
                 BlockHeight::from_u32(current_block as u32),
                 &self.z_fields.evk.fvk.ovk,
                 z_out,
             ) {
-                to.insert(encode_payment_address(
-                    self.consensus_params_ref().hrp_sapling_payment_address(),
-                    &address,
-                ));
+                // The memos of the outputs sent to ourselves are already known from the received notes.
+                let is_my_address = self.is_my_z_address(&address);
+                let address = encode_payment_address(hrp, &address);
+                if !is_my_address {
+                    if let Some(memo) = memo_to_string(&memo) {
+                        memos.push(ZcoinTxMemo {
+                            address: address.clone(),
+                            memo,
+                        });
+                    }
+                }
+                to.insert(address);
             }
 
             if let Some((_, address, _)) = try_sapling_output_recovery(
@@ -703,6 +851,7 @@ impl ZCoin {
             transaction_fee: big_decimal_from_sat(fee_amount.into(), self.decimals()),
             coin: self.ticker().into(),
             internal_id: sql_item.internal_id,
+            memos,
         })
     }
 
@@ -806,6 +955,9 @@ pub struct ZcoinActivationParams {
     pub scan_interval_ms: u64,
     #[serde(default)]
     pub account: u32,
+    /// ZIP32 accounts to track besides the primary `account`, they're only available with the global HD seed.
+    #[serde(default)]
+    pub additional_accounts: Vec<u32>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -895,18 +1047,10 @@ impl<'a> UtxoCoinBuilder for ZCoinBuilder<'a> {
         let utxo = self.build_utxo_fields().await?;
         let utxo_arc = UtxoArc::new(utxo);
 
-        let z_spending_key = match self.z_spending_key {
-            Some(ref z_spending_key) => z_spending_key.clone(),
-            None => extended_spending_key_from_protocol_info_and_policy(
-                &self.protocol_info,
-                &self.priv_key_policy,
-                self.z_coin_params.account,
-            )?,
-        };
-
-        let (_, my_z_addr) = z_spending_key
-            .default_address()
-            .map_err(|_| MmError::new(ZCoinBuildError::GetAddressError))?;
+        let z_accounts = self.z_accounts()?;
+        let z_spending_key = z_accounts[0].spending_key.clone();
+        let my_z_addr = z_accounts[0].default_address.clone();
+        let evks: Vec<_> = z_accounts.iter().map(|account| account.evk.clone()).collect();
 
         let dex_fee_addr = decode_payment_address(
             self.protocol_info.consensus_params.hrp_sapling_payment_address(),
@@ -926,23 +1070,16 @@ impl<'a> UtxoCoinBuilder for ZCoinBuilder<'a> {
             #[cfg(not(target_arch = "wasm32"))]
             ZcoinRpcMode::Native => {
                 let native_client = self.native_client()?;
-                init_native_client(&self, native_client, blocks_db, &z_spending_key).await?
+                init_native_client(&self, native_client, blocks_db, &evks).await?
             },
             ZcoinRpcMode::Light {
                 light_wallet_d_servers,
                 sync_params,
                 ..
-            } => {
-                init_light_client(
-                    &self,
-                    light_wallet_d_servers.clone(),
-                    blocks_db,
-                    sync_params,
-                    &z_spending_key,
-                )
-                .await?
-            },
+            } => init_light_client(&self, light_wallet_d_servers.clone(), blocks_db, sync_params, &evks).await?,
         };
+        #[cfg(not(target_arch = "wasm32"))]
+        let z_accounts = set_wallet_account_ids(&light_wallet_db, &self.protocol_info.consensus_params, z_accounts)?;
         let z_fields = ZCoinFields {
             dex_fee_addr,
             my_z_addr,
@@ -953,6 +1090,7 @@ impl<'a> UtxoCoinBuilder for ZCoinBuilder<'a> {
             light_wallet_db,
             consensus_params: self.protocol_info.consensus_params,
             sync_state_connector,
+            z_accounts,
         };
 
         let z_coin = ZCoin {
@@ -1019,6 +1157,69 @@ impl<'a> ZCoinBuilder<'a> {
             .await
     }
 
+    /// The primary account goes first, followed by the `additional_accounts`.
+    /// The WalletDb ids are set by [`set_wallet_account_ids`] once WalletDb is initialized.
+    fn z_accounts(&self) -> MmResult<Vec<ZCoinAccount>, ZCoinBuildError> {
+        let primary_index = self.z_coin_params.account;
+        let primary_key = match self.z_spending_key {
+            Some(ref z_spending_key) => z_spending_key.clone(),
+            None => extended_spending_key_from_protocol_info_and_policy(
+                &self.protocol_info,
+                &self.priv_key_policy,
+                primary_index,
+            )?,
+        };
+        let mut z_accounts = vec![ZCoinAccount::new(
+            primary_index,
+            AccountId(0),
+            self.z_account_derivation_path(primary_index)?,
+            primary_key,
+        )?];
+
+        if self.z_coin_params.additional_accounts.is_empty() {
+            return Ok(z_accounts);
+        }
+        // Additional accounts can't be derived from an iguana or a forced spending key.
+        let global_hd = match (&self.z_spending_key, &self.priv_key_policy) {
+            (None, PrivKeyBuildPolicy::GlobalHDAccount(global_hd)) => global_hd,
+            _ => return MmError::err(ZCoinBuildError::AdditionalAccountsNotSupported),
+        };
+        for account_index in self.z_coin_params.additional_accounts.iter().copied() {
+            if z_accounts.iter().any(|account| account.account_index == account_index) {
+                continue;
+            }
+            let spending_key =
+                extended_spending_key_from_global_hd_account(&self.protocol_info, global_hd, account_index)?;
+            let wallet_account_id = AccountId(z_accounts.len() as u32);
+            z_accounts.push(ZCoinAccount::new(
+                account_index,
+                wallet_account_id,
+                self.z_account_derivation_path(account_index)?,
+                spending_key,
+            )?);
+        }
+        Ok(z_accounts)
+    }
+
+    /// Returns `m` if the coin isn't activated with the global HD seed.
+    fn z_account_derivation_path(&self, account_index: u32) -> MmResult<RpcDerivationPath, ZCoinBuildError> {
+        const ACCOUNT_CHILD_HARDENED: bool = true;
+
+        let path_to_coin = match (
+            &self.z_spending_key,
+            &self.priv_key_policy,
+            &self.protocol_info.z_derivation_path,
+        ) {
+            (None, PrivKeyBuildPolicy::GlobalHDAccount(_), Some(path_to_coin)) => path_to_coin,
+            _ => return Ok(RpcDerivationPath(DerivationPath::default())),
+        };
+        let mut derivation_path = path_to_coin.to_derivation_path();
+        let account_child = ChildNumber::new(account_index, ACCOUNT_CHILD_HARDENED)
+            .map_to_mm(|_| ZCoinBuildError::InvalidAccountIndex(account_index))?;
+        derivation_path.push(account_child);
+        Ok(RpcDerivationPath(derivation_path))
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     async fn z_tx_prover(&self) -> Result<LocalTxProver, MmError<ZCoinBuildError>> {
         let params_dir = match &self.z_coin_params.zcash_params_path {
@@ -1919,12 +2120,12 @@ impl UtxoCommonOps for ZCoin {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-#[async_trait]
-impl InitWithdrawCoin for ZCoin {
-    async fn init_withdraw(
+impl ZCoin {
+    /// Sends the requested amount to `req.to` and to the additional shielded outputs of `z_params`.
+    pub async fn init_withdraw(
         &self,
-        _ctx: MmArc,
         req: WithdrawRequest,
+        z_params: ZWithdrawParams,
         task_handle: &WithdrawTaskHandle,
     ) -> Result<TransactionDetails, MmError<WithdrawError>> {
         if req.fee.is_some() {
@@ -1939,31 +2140,31 @@ impl InitWithdrawCoin for ZCoin {
             ));
         }
 
-        let to_addr = decode_payment_address(z_mainnet_constants::HRP_SAPLING_PAYMENT_ADDRESS, &req.to)
-            .map_to_mm(|e| WithdrawError::InvalidAddress(format!("{}", e)))?
-            .or_mm_err(|| WithdrawError::InvalidAddress(format!("Address {} decoded to None", req.to)))?;
+        let viewing_key = self.outgoing_viewing_key(&z_params.viewing_key)?;
+        let mut extra_outputs = Vec::with_capacity(z_params.z_outputs.len());
+        let mut extra_amount = BigDecimal::from(0);
+        for output in z_params.z_outputs.iter() {
+            extra_amount += &output.amount;
+            extra_outputs.push(self.z_output(&output.to, &output.amount, viewing_key, output.memo.as_deref())?);
+        }
+
+        let account = self.z_account(z_params.z_account)?;
         let amount = if req.max {
             let fee = self.get_one_kbyte_tx_fee().await?;
-            let balance = self.my_balance().compat().await?;
-            balance.spendable - fee
+            let balance_sat = self
+                .account_balance_sat(account.wallet_account_id)
+                .await
+                .mm_err(|e| WithdrawError::InternalError(e.to_string()))?;
+            big_decimal_from_sat_unsigned(balance_sat, self.decimals()) - fee - extra_amount
         } else {
             req.amount
         };
 
         task_handle.update_in_progress_status(WithdrawInProgressStatus::GeneratingTransaction)?;
-        let satoshi = sat_from_big_decimal(&amount, self.decimals())?;
-
-        let memo = req.memo.as_deref().map(interpret_memo_string).transpose()?;
-        let z_output = ZOutput {
-            to_addr,
-            amount: Amount::from_u64(satoshi)
-                .map_to_mm(|_| NumConversError(format!("Failed to get ZCash amount from {}", amount)))?,
-            // TODO add optional viewing_key and memo fields to the WithdrawRequest
-            viewing_key: Some(self.z_fields.evk.fvk.ovk),
-            memo,
-        };
+        let mut z_outputs = vec![self.z_output(&req.to, &amount, viewing_key, req.memo.as_deref())?];
+        z_outputs.extend(extra_outputs);
 
-        let (tx, data, _sync_guard) = self.gen_tx(vec![], vec![z_output]).await?;
+        let (tx, data, _sync_guard) = self.gen_tx_from_account(account, vec![], z_outputs).await?;
         let mut tx_bytes = Vec::with_capacity(1024);
         tx.write(&mut tx_bytes)
             .map_to_mm(|e| WithdrawError::InternalError(e.to_string()))?;
@@ -1976,8 +2177,13 @@ impl InitWithdrawCoin for ZCoin {
         Ok(TransactionDetails {
             tx_hex: tx_bytes.into(),
             tx_hash: hex::encode(&tx_hash),
-            from: vec![self.z_fields.my_z_addr_encoded.clone()],
-            to: vec![req.to],
+            from: vec![encode_payment_address(
+                self.consensus_params_ref().hrp_sapling_payment_address(),
+                &account.default_address,
+            )],
+            to: iter::once(req.to)
+                .chain(z_params.z_outputs.into_iter().map(|output| output.to))
+                .collect(),
             my_balance_change: &received_by_me - &spent_by_me,
             total_amount: spent_by_me.clone(),
             spent_by_me,
@@ -1995,6 +2201,70 @@ impl InitWithdrawCoin for ZCoin {
             memo: req.memo,
         })
     }
+
+    /// Resets the wallet and the blocks cache to the given birthday height and restarts the sync from it.
+    /// The progress is available via [`ZCoin::sync_status`].
+    pub async fn rescan_from_height(&self, birthday: u64) -> MmResult<FirstSyncBlock, UpdateBlocksCacheErr> {
+        let mut sync_guard = self
+            .wait_for_gen_tx_blockchain_sync()
+            .await
+            .mm_err(|_| UpdateBlocksCacheErr::InternalError("Blockchain scan process stopped".to_owned()))?;
+        // The sync loop is respawned once the guard is dropped.
+        sync_guard.respawn_guard.rescan_from(birthday).await
+    }
+
+    fn z_output(
+        &self,
+        to: &str,
+        amount: &BigDecimal,
+        viewing_key: Option<OutgoingViewingKey>,
+        memo: Option<&str>,
+    ) -> MmResult<ZOutput, WithdrawError> {
+        let to_addr = decode_payment_address(z_mainnet_constants::HRP_SAPLING_PAYMENT_ADDRESS, to)
+            .map_to_mm(|e| WithdrawError::InvalidAddress(format!("{}", e)))?
+            .or_mm_err(|| WithdrawError::InvalidAddress(format!("Address {} decoded to None", to)))?;
+        let satoshi = sat_from_big_decimal(amount, self.decimals())?;
+        Ok(ZOutput {
+            to_addr,
+            amount: Amount::from_u64(satoshi)
+                .map_to_mm(|_| NumConversError(format!("Failed to get ZCash amount from {}", amount)))?,
+            viewing_key,
+            memo: memo.map(interpret_memo_string).transpose()?,
+        })
+    }
+
+    fn outgoing_viewing_key(
+        &self,
+        viewing_key: &ZOutgoingViewingKey,
+    ) -> MmResult<Option<OutgoingViewingKey>, WithdrawError> {
+        match viewing_key {
+            ZOutgoingViewingKey::Sender => Ok(Some(self.z_fields.evk.fvk.ovk)),
+            ZOutgoingViewingKey::None => Ok(None),
+            ZOutgoingViewingKey::Custom(key_hex) => parse_outgoing_viewing_key(key_hex).map(Some),
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_outgoing_viewing_key(key_hex: &str) -> MmResult<OutgoingViewingKey, WithdrawError> {
+    let key: [u8; 32] = hex::decode(key_hex)
+        .map_to_mm(|e| WithdrawError::InvalidViewingKey(e.to_string()))?
+        .try_into()
+        .map_to_mm(|_| WithdrawError::InvalidViewingKey("Expected 32 bytes".to_owned()))?;
+    Ok(OutgoingViewingKey(key))
+}
+
+/// Restores the address the note was received to from the account's viewing key and the note's diversifier.
+#[cfg(not(target_arch = "wasm32"))]
+fn received_note_address(
+    accounts: &[ZCoinAccount],
+    account: AccountId,
+    diversifier: Diversifier,
+) -> Option<PaymentAddress> {
+    let z_account = accounts
+        .iter()
+        .find(|z_account| z_account.wallet_account_id.0 == account.0)?;
+    z_account.evk.fvk.vk.to_payment_address(diversifier)
 }
 
 /// Interpret a string or hex-encoded memo, and return a Memo object.
@@ -2015,6 +2285,20 @@ pub fn interpret_memo_string(memo_str: &str) -> MmResult<MemoBytes, WithdrawErro
     })
 }
 
+/// Renders the memo as text, or as `0x`-prefixed hex if it contains arbitrary data.
+/// Returns `None` for the empty memo.
+pub fn memo_to_string(memo: &MemoBytes) -> Option<String> {
+    match Memo::try_from(memo.clone()) {
+        Ok(Memo::Empty) => None,
+        Ok(Memo::Text(text)) => Some(text.to_string()),
+        _ => {
+            let bytes = memo.as_slice();
+            let len = bytes.iter().rposition(|byte| *byte != 0).map_or(0, |pos| pos + 1);
+            Some(format!("0x{}", hex::encode(&bytes[..len])))
+        },
+    }
+}
+
 fn extended_spending_key_from_protocol_info_and_policy(
     protocol_info: &ZcoinProtocolInfo,
     priv_key_policy: &PrivKeyBuildPolicy,
@@ -2095,7 +2379,6 @@ fn derive_z_key_from_mm_seed() {
 #[test]
 fn test_interpret_memo_string() {
     use std::str::FromStr;
-    use zcash_primitives::memo::Memo;
 
     let actual = interpret_memo_string("68656c6c6f207a63617368").unwrap();
     let expected = Memo::from_str("68656c6c6f207a63617368").unwrap().encode();
@@ -2109,3 +2392,97 @@ fn test_interpret_memo_string() {
     let expected = MemoBytes::from_bytes(&hex::decode("68656c6c6f207a63617368").unwrap()).unwrap();
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn test_memo_to_string() {
+    assert_eq!(memo_to_string(&MemoBytes::empty()), None);
+
+    let text = interpret_memo_string("A custom memo").unwrap();
+    assert_eq!(memo_to_string(&text), Some("A custom memo".to_owned()));
+
+    let arbitrary = MemoBytes::from_bytes(&[0xff, 0x01, 0x02]).unwrap();
+    assert_eq!(memo_to_string(&arbitrary), Some("0xff0102".to_owned()));
+}
+
+#[test]
+fn test_parse_outgoing_viewing_key() {
+    let key = parse_outgoing_viewing_key("0707070707070707070707070707070707070707070707070707070707070707").unwrap();
+    assert_eq!(key.0, DEX_FEE_OVK.0);
+
+    let error = parse_outgoing_viewing_key("not a hex").unwrap_err().into_inner();
+    assert!(matches!(error, WithdrawError::InvalidViewingKey(_)), "{:?}", error);
+    // 31 bytes
+    let error = parse_outgoing_viewing_key("07070707070707070707070707070707070707070707070707070707070707")
+        .unwrap_err()
+        .into_inner();
+    assert!(matches!(error, WithdrawError::InvalidViewingKey(_)), "{:?}", error);
+}
+
+#[test]
+fn test_withdraw_params_deserialize() {
+    use std::str::FromStr;
+
+    let params: ZWithdrawParams = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(params.z_account, None);
+    assert_eq!(params.viewing_key, ZOutgoingViewingKey::Sender);
+    assert!(params.z_outputs.is_empty());
+
+    let params: ZWithdrawParams = serde_json::from_value(json!({
+        "z_account": 2,
+        "viewing_key": { "custom": "0707070707070707070707070707070707070707070707070707070707070707" },
+        "z_outputs": [
+            {
+                "to": "zs1funuwrjr2stlr6fnhkdh7fyz3p7n0p8rxase9jnezdhc286v5mhs6q3myw0phzvad5mvqgfxpam",
+                "amount": "0.1",
+                "memo": "deposit 42",
+            },
+            {
+                "to": "zs182ht30wnnnr8jjhj2j9v5dkx3qsknnr5r00jfwk2nczdtqy7w0v836kyy840kv2r8xle5gcl549",
+                "amount": 1,
+            },
+        ],
+    }))
+    .unwrap();
+    assert_eq!(params.z_account, Some(2));
+    assert_eq!(
+        params.viewing_key,
+        ZOutgoingViewingKey::Custom("0707070707070707070707070707070707070707070707070707070707070707".to_owned())
+    );
+    assert_eq!(params.z_outputs.len(), 2);
+    assert_eq!(params.z_outputs[0].amount, BigDecimal::from_str("0.1").unwrap());
+    assert_eq!(params.z_outputs[0].memo.as_deref(), Some("deposit 42"));
+    assert_eq!(params.z_outputs[1].memo, None);
+
+    let params: ZWithdrawParams = serde_json::from_value(json!({ "viewing_key": "none" })).unwrap();
+    assert_eq!(params.viewing_key, ZOutgoingViewingKey::None);
+}
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_received_note_address() {
+    let new_account = |account_index: u32, seed: u8| {
+        let spending_key = ExtendedSpendingKey::master(&[seed; 32]);
+        let derivation_path = RpcDerivationPath(DerivationPath::default());
+        ZCoinAccount::new(account_index, AccountId(account_index), derivation_path, spending_key).unwrap()
+    };
+    let accounts = vec![new_account(0, 1), new_account(1, 2)];
+
+    let mut index = accounts[0].default_diversifier_index;
+    index.increment().unwrap();
+    let (_, diversified_address) = accounts[0].evk.address(index).unwrap();
+    assert_ne!(diversified_address, accounts[0].default_address);
+
+    let actual = received_note_address(&accounts, AccountId(0), *diversified_address.diversifier());
+    assert_eq!(actual, Some(diversified_address.clone()));
+    let actual = received_note_address(&accounts, AccountId(0), *accounts[0].default_address.diversifier());
+    assert_eq!(actual, Some(accounts[0].default_address.clone()));
+
+    // The same diversifier gives another address of the second account.
+    let actual = received_note_address(&accounts, AccountId(1), *diversified_address.diversifier()).unwrap();
+    assert_ne!(actual, diversified_address);
+
+    assert_eq!(
+        received_note_address(&accounts, AccountId(2), *diversified_address.diversifier()),
+        None
+    );
+}
@@ -1,6 +1,6 @@
 use crate::z_coin::{ZCoinBuilder, ZcoinClientInitError};
 use mm2_err_handle::prelude::*;
-use zcash_primitives::zip32::ExtendedSpendingKey;
+use zcash_primitives::zip32::ExtendedFullViewingKey;
 
 cfg_native!(
     use crate::z_coin::{CheckPointBlockInfo, ZcoinConsensusParams};
@@ -9,7 +9,6 @@ cfg_native!(
     use parking_lot::Mutex;
     use std::sync::Arc;
     use zcash_client_sqlite::WalletDb;
-    use zcash_primitives::zip32::ExtendedFullViewingKey;
 );
 
 cfg_wasm32!(
@@ -39,7 +38,7 @@ impl<'a> WalletDbShared {
     pub async fn new(
         zcoin_builder: &ZCoinBuilder<'a>,
         checkpoint_block: Option<CheckPointBlockInfo>,
-        z_spending_key: &ExtendedSpendingKey,
+        evks: &[ExtendedFullViewingKey],
         continue_from_prev_sync: bool,
     ) -> MmResult<Self, WalletDbError> {
         let wallet_db = create_wallet_db(
@@ -48,7 +47,7 @@ impl<'a> WalletDbShared {
                 .join(format!("{}_wallet.db", zcoin_builder.ticker)),
             zcoin_builder.protocol_info.consensus_params.clone(),
             checkpoint_block,
-            ExtendedFullViewingKey::from(z_spending_key),
+            evks.to_vec(),
             continue_from_prev_sync,
         )
         .await
@@ -70,7 +69,7 @@ cfg_wasm32!(
     impl<'a> WalletDbShared {
         pub async fn new(
             zcoin_builder: &ZCoinBuilder<'a>,
-            _z_spending_key: &ExtendedSpendingKey,
+            _evks: &[ExtendedFullViewingKey],
         ) -> MmResult<Self, WalletDbError> {
             Ok(Self {
                 db: ConstructibleDb::new(zcoin_builder.ctx).into_shared(),
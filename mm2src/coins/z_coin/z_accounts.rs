@@ -0,0 +1,576 @@
+//! ZIP32 shielded accounts of `ZCoin` and their diversified payment addresses.
+//!
+//! Every account is added to the WalletDb `accounts` table once and is never removed from it,
+//! so the notes, the balances and the diversified addresses are tracked per account
+//! even if the coin is activated with a different set of accounts next time.
+//! A new diversified address is the next valid diversifier of the account's full viewing key,
+//! all of them are decrypted with the same incoming viewing key during the sync.
+
+use super::{ZCoin, ZCoinBuildError};
+use crate::coin_balance::{HDAccountBalance, HDAddressBalance};
+use crate::hd_confirm_address::HDConfirmAddress;
+use crate::rpc_command::get_new_address::{GetNewAddressParams, GetNewAddressResponse, GetNewAddressRpcError,
+                                          GetNewAddressRpcOps};
+use crate::{CoinBalance, WithdrawError};
+use async_trait::async_trait;
+use crypto::{Bip44Chain, RpcDerivationPath};
+use mm2_err_handle::prelude::*;
+use zcash_client_backend::wallet::AccountId;
+use zcash_primitives::sapling::PaymentAddress;
+use zcash_primitives::zip32::{DiversifierIndex, ExtendedFullViewingKey, ExtendedSpendingKey};
+
+cfg_native!(
+    use crate::utxo::utxo_common::big_decimal_from_sat_unsigned;
+    use crate::MarketCoinOps;
+
+    use crate::z_coin::storage::WalletDbShared;
+    use crate::z_coin::ZcoinConsensusParams;
+
+    use common::async_blocking;
+    use db_common::sqlite::rusqlite::{params, Connection, Error as SqliteError, OptionalExtension};
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+    use zcash_client_backend::data_api::WalletRead;
+    use zcash_client_backend::encoding::{decode_payment_address, encode_extended_full_viewing_key, encode_payment_address};
+    use zcash_client_sqlite::wallet::get_balance;
+    use zcash_primitives::consensus::Parameters;
+);
+
+cfg_native!(
+    const DIVERSIFIED_ADDRESSES_TABLE: &str = "z_diversified_addresses";
+);
+
+#[derive(Debug, Display)]
+pub enum ZAccountError {
+    #[display(fmt = "ZIP32 account '{}' is not activated", _0)]
+    UnknownAccount(u32),
+    #[display(fmt = "No more diversified addresses are available for the account '{}'", _0)]
+    DiversifiersExhausted(u32),
+    #[display(fmt = "WalletDb error: {}", _0)]
+    WalletDbError(String),
+    #[display(fmt = "{} is not supported in the browser yet", _0)]
+    NotSupported(String),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<SqliteError> for ZAccountError {
+    fn from(e: SqliteError) -> Self { ZAccountError::WalletDbError(e.to_string()) }
+}
+
+impl From<ZAccountError> for GetNewAddressRpcError {
+    fn from(e: ZAccountError) -> Self {
+        match e {
+            ZAccountError::UnknownAccount(account_id) => GetNewAddressRpcError::UnknownAccount { account_id },
+            ZAccountError::DiversifiersExhausted(_) => GetNewAddressRpcError::ErrorDerivingAddress(e.to_string()),
+            ZAccountError::WalletDbError(db) => GetNewAddressRpcError::WalletStorageError(db),
+            ZAccountError::NotSupported(_) => GetNewAddressRpcError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<ZAccountError> for WithdrawError {
+    fn from(e: ZAccountError) -> Self {
+        match e {
+            ZAccountError::UnknownAccount(account_id) => WithdrawError::UnknownAccount { account_id },
+            ZAccountError::DiversifiersExhausted(_)
+            | ZAccountError::WalletDbError(_)
+            | ZAccountError::NotSupported(_) => WithdrawError::InternalError(e.to_string()),
+        }
+    }
+}
+
+/// The ZIP32 shielded account activated along with the coin.
+#[derive(Clone)]
+pub struct ZCoinAccount {
+    /// The ZIP32 account index.
+    pub(super) account_index: u32,
+    /// The account's id in WalletDb.
+    pub(super) wallet_account_id: AccountId,
+    /// `m/purpose'/coin'/account'` if the account is derived from the global HD seed,
+    /// `m` if the coin is activated with an iguana or a forced spending key.
+    pub(super) derivation_path: RpcDerivationPath,
+    pub(super) spending_key: ExtendedSpendingKey,
+    pub(super) evk: ExtendedFullViewingKey,
+    pub(super) default_diversifier_index: DiversifierIndex,
+    pub(super) default_address: PaymentAddress,
+}
+
+impl ZCoinAccount {
+    pub(super) fn new(
+        account_index: u32,
+        wallet_account_id: AccountId,
+        derivation_path: RpcDerivationPath,
+        spending_key: ExtendedSpendingKey,
+    ) -> MmResult<Self, ZCoinBuildError> {
+        let (default_diversifier_index, default_address) = spending_key
+            .default_address()
+            .map_to_mm(|_| ZCoinBuildError::GetAddressError)?;
+        Ok(ZCoinAccount {
+            account_index,
+            wallet_account_id,
+            derivation_path,
+            evk: ExtendedFullViewingKey::from(&spending_key),
+            spending_key,
+            default_diversifier_index,
+            default_address,
+        })
+    }
+
+    /// Finds the first valid diversified address after the given diversifier index.
+    fn next_address(&self, after: DiversifierIndex) -> MmResult<(DiversifierIndex, PaymentAddress), ZAccountError> {
+        let mut index = after;
+        index
+            .increment()
+            .map_to_mm(|_| ZAccountError::DiversifiersExhausted(self.account_index))?;
+        self.evk
+            .address(index)
+            .map_to_mm(|_| ZAccountError::DiversifiersExhausted(self.account_index))
+    }
+}
+
+impl ZCoin {
+    /// The activated accounts, the primary one goes first.
+    #[inline]
+    pub fn z_accounts(&self) -> &[ZCoinAccount] { &self.z_fields.z_accounts }
+
+    #[inline]
+    pub(super) fn primary_z_account(&self) -> &ZCoinAccount { &self.z_fields.z_accounts[0] }
+
+    /// Checks whether the address is the default or a diversified address of any activated account.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(super) fn is_my_z_address(&self, address: &PaymentAddress) -> bool {
+        self.z_accounts()
+            .iter()
+            .any(|account| account.evk.fvk.vk.to_payment_address(*address.diversifier()).as_ref() == Some(address))
+    }
+
+    /// Returns the primary account if `account_index` is not specified.
+    pub(super) fn z_account(&self, account_index: Option<u32>) -> MmResult<&ZCoinAccount, ZAccountError> {
+        let account_index = match account_index {
+            Some(account_index) => account_index,
+            None => return Ok(self.primary_z_account()),
+        };
+        self.z_accounts()
+            .iter()
+            .find(|account| account.account_index == account_index)
+            .or_mm_err(|| ZAccountError::UnknownAccount(account_index))
+    }
+
+    /// Generates a new diversified address of the account and stores it in WalletDb.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn new_z_address(&self, account_index: u32) -> MmResult<String, ZAccountError> {
+        let account = self.z_account(Some(account_index))?.clone();
+        let wallet_db = self.z_fields.light_wallet_db.clone();
+        let hrp = self.consensus_params_ref().hrp_sapling_payment_address().to_owned();
+        async_blocking(move || {
+            let conn = wallet_db.db.lock();
+            let last_index = last_diversifier_index(conn.sql_conn(), account.wallet_account_id)?
+                .unwrap_or(account.default_diversifier_index);
+            let (index, address) = account.next_address(last_index)?;
+            let address = encode_payment_address(&hrp, &address);
+            insert_diversified_address(conn.sql_conn(), account.wallet_account_id, index, &address)?;
+            Ok(address)
+        })
+        .await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn new_z_address(&self, _account_index: u32) -> MmResult<String, ZAccountError> {
+        MmError::err(ZAccountError::NotSupported("Generating a new z-address".to_owned()))
+    }
+
+    /// Returns the balances of every account and of its default and diversified addresses.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn z_accounts_balance(&self) -> MmResult<Vec<HDAccountBalance>, ZAccountError> {
+        let accounts = self.z_accounts().to_vec();
+        let wallet_db = self.z_fields.light_wallet_db.clone();
+        let consensus_params = self.consensus_params();
+        let decimals = self.decimals();
+        async_blocking(move || {
+            let conn = wallet_db.db.lock();
+            let hrp = consensus_params.hrp_sapling_payment_address();
+            let to_balance = |sat: u64| CoinBalance::new(big_decimal_from_sat_unsigned(sat, decimals));
+
+            let mut result = Vec::with_capacity(accounts.len());
+            for account in accounts {
+                let total: u64 = get_balance(&conn, account.wallet_account_id)
+                    .map_to_mm(|e| ZAccountError::WalletDbError(e.to_string()))?
+                    .into();
+
+                let mut addresses = vec![encode_payment_address(hrp, &account.default_address)];
+                addresses.extend(diversified_addresses(conn.sql_conn(), account.wallet_account_id)?);
+                let addresses = addresses
+                    .into_iter()
+                    .map(|address| {
+                        let payment_address = decode_payment_address(hrp, &address)
+                            .ok()
+                            .flatten()
+                            .or_mm_err(|| ZAccountError::WalletDbError(format!("Invalid address {}", address)))?;
+                        let balance = address_balance(conn.sql_conn(), account.wallet_account_id, &payment_address)?;
+                        Ok(HDAddressBalance {
+                            address,
+                            derivation_path: account.derivation_path.clone(),
+                            chain: Bip44Chain::External,
+                            balance: to_balance(balance),
+                        })
+                    })
+                    .collect::<MmResult<_, ZAccountError>>()?;
+
+                result.push(HDAccountBalance {
+                    account_index: account.account_index,
+                    derivation_path: account.derivation_path,
+                    total_balance: to_balance(total),
+                    addresses,
+                });
+            }
+            Ok(result)
+        })
+        .await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn z_accounts_balance(&self) -> MmResult<Vec<HDAccountBalance>, ZAccountError> {
+        MmError::err(ZAccountError::NotSupported(
+            "Requesting the ZIP32 accounts balance".to_owned(),
+        ))
+    }
+}
+
+#[async_trait]
+impl GetNewAddressRpcOps for ZCoin {
+    async fn get_new_address_rpc_without_conf(
+        &self,
+        params: GetNewAddressParams,
+    ) -> MmResult<GetNewAddressResponse, GetNewAddressRpcError> {
+        // Sapling addresses have no change chain, every diversified address is a receiver one.
+        if let Some(chain @ Bip44Chain::Internal) = params.chain {
+            return MmError::err(GetNewAddressRpcError::InvalidBip44Chain { chain });
+        }
+
+        let address = self.new_z_address(params.account_id).await?;
+        let account = self.z_account(Some(params.account_id))?;
+        Ok(GetNewAddressResponse {
+            new_address: HDAddressBalance {
+                address,
+                derivation_path: account.derivation_path.clone(),
+                chain: Bip44Chain::External,
+                // The address has just been generated, so nothing could be received to it yet.
+                balance: CoinBalance::default(),
+            },
+        })
+    }
+
+    /// The spending keys are derived from the activated seed, so there is no device to confirm the address on.
+    async fn get_new_address_rpc<ConfirmAddress>(
+        &self,
+        params: GetNewAddressParams,
+        _confirm_address: &ConfirmAddress,
+    ) -> MmResult<GetNewAddressResponse, GetNewAddressRpcError>
+    where
+        ConfirmAddress: HDConfirmAddress,
+    {
+        self.get_new_address_rpc_without_conf(params).await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn init_diversified_addresses_table(conn: &Connection) -> Result<(), SqliteError> {
+    let sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (
+            account INTEGER NOT NULL,
+            diversifier_index BLOB NOT NULL,
+            address TEXT NOT NULL UNIQUE,
+            PRIMARY KEY (account, diversifier_index)
+        );",
+        DIVERSIFIED_ADDRESSES_TABLE
+    );
+    conn.execute(&sql, []).map(|_| ())
+}
+
+/// Adds the activated accounts missing in WalletDb after the stored ones.
+/// The stored accounts are kept along with their notes and diversified addresses even if they aren't activated.
+/// Returns whether any account has been added, so the already synced blocks need to be rescanned.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn add_wallet_accounts(
+    conn: &Connection,
+    consensus_params: &ZcoinConsensusParams,
+    stored_evks: &HashMap<AccountId, ExtendedFullViewingKey>,
+    evks: &[ExtendedFullViewingKey],
+) -> Result<bool, ZAccountError> {
+    let evk_hrp = consensus_params.hrp_sapling_extended_full_viewing_key();
+    let mut next_account = stored_evks
+        .keys()
+        .map(|account| account.0 + 1)
+        .max()
+        .unwrap_or_default();
+    let mut added = false;
+    for evk in evks {
+        if find_wallet_account(evk_hrp, stored_evks, evk).is_some() {
+            continue;
+        }
+        let (_, address) = evk
+            .default_address()
+            .map_err(|_| ZAccountError::WalletDbError("Couldn't get the default address of an account".to_owned()))?;
+        // The same row as `init_accounts_table` inserts, which can only be used with an empty table.
+        conn.execute(
+            "INSERT INTO accounts (account, extfvk, address) VALUES (?1, ?2, ?3);",
+            params![
+                next_account,
+                encode_extended_full_viewing_key(evk_hrp, evk),
+                encode_payment_address(consensus_params.hrp_sapling_payment_address(), &address)
+            ],
+        )?;
+        next_account += 1;
+        added = true;
+    }
+    Ok(added)
+}
+
+/// Sets the WalletDb ids of the activated accounts, which don't match their activation order
+/// if some of the stored accounts aren't activated this time.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn set_wallet_account_ids(
+    wallet_db: &WalletDbShared,
+    consensus_params: &ZcoinConsensusParams,
+    z_accounts: Vec<ZCoinAccount>,
+) -> MmResult<Vec<ZCoinAccount>, ZCoinBuildError> {
+    let evk_hrp = consensus_params.hrp_sapling_extended_full_viewing_key();
+    let stored_evks = wallet_db
+        .db
+        .lock()
+        .get_extended_full_viewing_keys()
+        .map_to_mm(|e| ZCoinBuildError::ZcashDBError(e.to_string()))?;
+    z_accounts
+        .into_iter()
+        .map(|mut account| {
+            account.wallet_account_id = find_wallet_account(evk_hrp, &stored_evks, &account.evk).or_mm_err(|| {
+                ZCoinBuildError::ZcashDBError(format!("ZIP32 account '{}' is not stored", account.account_index))
+            })?;
+            Ok(account)
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn find_wallet_account(
+    evk_hrp: &str,
+    stored_evks: &HashMap<AccountId, ExtendedFullViewingKey>,
+    evk: &ExtendedFullViewingKey,
+) -> Option<AccountId> {
+    let encoded = encode_extended_full_viewing_key(evk_hrp, evk);
+    stored_evks
+        .iter()
+        .find(|(_, stored)| encode_extended_full_viewing_key(evk_hrp, stored) == encoded)
+        .map(|(account, _)| *account)
+}
+
+/// The diversifier indexes only grow, so the last inserted one is the greatest.
+#[cfg(not(target_arch = "wasm32"))]
+fn last_diversifier_index(conn: &Connection, account: AccountId) -> Result<Option<DiversifierIndex>, ZAccountError> {
+    let sql = format!(
+        "SELECT diversifier_index FROM {} WHERE account = ?1 ORDER BY rowid DESC LIMIT 1;",
+        DIVERSIFIED_ADDRESSES_TABLE
+    );
+    let index = conn
+        .query_row(&sql, [account.0], |row| row.get::<_, Vec<u8>>(0))
+        .optional()?;
+    index
+        .map(|bytes| {
+            let index: [u8; 11] = bytes
+                .try_into()
+                .map_err(|_| ZAccountError::WalletDbError("Invalid diversifier index length".to_owned()))?;
+            Ok(DiversifierIndex(index))
+        })
+        .transpose()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn insert_diversified_address(
+    conn: &Connection,
+    account: AccountId,
+    index: DiversifierIndex,
+    address: &str,
+) -> Result<(), SqliteError> {
+    let sql = format!(
+        "INSERT INTO {} (account, diversifier_index, address) VALUES (?1, ?2, ?3);",
+        DIVERSIFIED_ADDRESSES_TABLE
+    );
+    conn.execute(&sql, params![account.0, index.0.to_vec(), address])
+        .map(|_| ())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn diversified_addresses(conn: &Connection, account: AccountId) -> Result<Vec<String>, SqliteError> {
+    let sql = format!(
+        "SELECT address FROM {} WHERE account = ?1 ORDER BY rowid;",
+        DIVERSIFIED_ADDRESSES_TABLE
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let addresses = stmt
+        .query_map([account.0], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(addresses)
+}
+
+/// Sums up the unspent notes received to the address in the mined transactions, as `get_balance` does.
+#[cfg(not(target_arch = "wasm32"))]
+fn address_balance(conn: &Connection, account: AccountId, address: &PaymentAddress) -> Result<u64, SqliteError> {
+    const QUERY: &str = "SELECT COALESCE(SUM(received_notes.value), 0) FROM received_notes
+        INNER JOIN transactions ON transactions.id_tx = received_notes.tx
+        WHERE received_notes.account = ?1 AND received_notes.diversifier = ?2
+        AND received_notes.spent IS NULL AND transactions.block IS NOT NULL;";
+    conn.query_row(QUERY, params![account.0, address.diversifier().0.to_vec()], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|balance| balance as u64)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::z_coin::z_rpc::create_wallet_db;
+    use common::block_on;
+    use crypto::DerivationPath;
+    use mm2_test_helpers::for_tests::zombie_conf;
+    use std::path::PathBuf;
+    use zcash_client_sqlite::WalletDb;
+
+    fn zombie_consensus_params() -> ZcoinConsensusParams {
+        serde_json::from_value(zombie_conf()["protocol"]["protocol_data"]["consensus_params"].clone()).unwrap()
+    }
+
+    fn new_account(account_index: u32, seed: u8) -> ZCoinAccount {
+        let spending_key = ExtendedSpendingKey::master(&[seed; 32]);
+        let derivation_path = RpcDerivationPath(DerivationPath::default());
+        ZCoinAccount::new(account_index, AccountId(account_index), derivation_path, spending_key).unwrap()
+    }
+
+    fn new_wallet_db(accounts: &[ZCoinAccount]) -> WalletDb<ZcoinConsensusParams> {
+        let evks = accounts.iter().map(|account| account.evk.clone()).collect();
+        block_on(create_wallet_db(
+            PathBuf::from(":memory:"),
+            zombie_consensus_params(),
+            None,
+            evks,
+            false,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_add_wallet_accounts() {
+        let params = zombie_consensus_params();
+        let evk_hrp = params.hrp_sapling_extended_full_viewing_key();
+        let accounts = [new_account(0, 1), new_account(1, 2), new_account(2, 3)];
+        let db = new_wallet_db(&accounts[..2]);
+
+        let stored_evks = db.get_extended_full_viewing_keys().unwrap();
+        assert_eq!(stored_evks.len(), 2);
+        assert_eq!(
+            find_wallet_account(evk_hrp, &stored_evks, &accounts[0].evk),
+            Some(AccountId(0))
+        );
+        assert_eq!(
+            find_wallet_account(evk_hrp, &stored_evks, &accounts[1].evk),
+            Some(AccountId(1))
+        );
+        assert_eq!(find_wallet_account(evk_hrp, &stored_evks, &accounts[2].evk), None);
+
+        let (index, address) = accounts[1].next_address(accounts[1].default_diversifier_index).unwrap();
+        let address = encode_payment_address(params.hrp_sapling_payment_address(), &address);
+        insert_diversified_address(db.sql_conn(), AccountId(1), index, &address).unwrap();
+
+        // The second account isn't activated anymore, but it's kept along with its addresses.
+        let evks = [accounts[0].evk.clone(), accounts[2].evk.clone()];
+        assert!(add_wallet_accounts(db.sql_conn(), &params, &stored_evks, &evks).unwrap());
+        let stored_evks = db.get_extended_full_viewing_keys().unwrap();
+        assert_eq!(stored_evks.len(), 3);
+        assert_eq!(
+            find_wallet_account(evk_hrp, &stored_evks, &accounts[0].evk),
+            Some(AccountId(0))
+        );
+        assert_eq!(
+            find_wallet_account(evk_hrp, &stored_evks, &accounts[2].evk),
+            Some(AccountId(2))
+        );
+        assert_eq!(diversified_addresses(db.sql_conn(), AccountId(1)).unwrap(), vec![
+            address
+        ]);
+
+        // Nothing to add if all the accounts are stored.
+        assert!(!add_wallet_accounts(db.sql_conn(), &params, &stored_evks, &evks).unwrap());
+        assert_eq!(db.get_extended_full_viewing_keys().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_last_diversifier_index() {
+        let hrp = zombie_consensus_params().hrp_sapling_payment_address().to_owned();
+        let accounts = [new_account(0, 1), new_account(1, 2)];
+        let db = new_wallet_db(&accounts);
+        let conn = db.sql_conn();
+        assert_eq!(last_diversifier_index(conn, AccountId(0)).unwrap(), None);
+
+        let mut last_index = accounts[0].default_diversifier_index;
+        for _ in 0..3 {
+            let (index, address) = accounts[0].next_address(last_index).unwrap();
+            assert!(index.0 > last_index.0);
+            insert_diversified_address(conn, AccountId(0), index, &encode_payment_address(&hrp, &address)).unwrap();
+            last_index = index;
+        }
+        let actual = last_diversifier_index(conn, AccountId(0)).unwrap().unwrap();
+        assert_eq!(actual.0, last_index.0);
+        assert_eq!(diversified_addresses(conn, AccountId(0)).unwrap().len(), 3);
+
+        // The indexes are tracked per account.
+        assert_eq!(last_diversifier_index(conn, AccountId(1)).unwrap(), None);
+        assert!(diversified_addresses(conn, AccountId(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_address_balance() {
+        let accounts = [new_account(0, 1), new_account(1, 2)];
+        let db = new_wallet_db(&accounts);
+        let conn = db.sql_conn();
+
+        let default_address = accounts[0].default_address.clone();
+        let (_, diversified_address) = accounts[0].next_address(accounts[0].default_diversifier_index).unwrap();
+
+        conn.execute(
+            "INSERT INTO blocks (height, hash, time, sapling_tree) VALUES (100, x'00', 0, x'00');",
+            [],
+        )
+        .unwrap();
+        // The first two transactions are mined, the last one isn't.
+        conn.execute_batch(
+            "INSERT INTO transactions (id_tx, txid, block) VALUES (1, x'01', 100);
+            INSERT INTO transactions (id_tx, txid, block) VALUES (2, x'02', 100);
+            INSERT INTO transactions (id_tx, txid, block) VALUES (3, x'03', NULL);",
+        )
+        .unwrap();
+        let insert_note = |id: u32, tx: u32, account: u32, address: &PaymentAddress, value: i64, spent: Option<u32>| {
+            conn.execute(
+                "INSERT INTO received_notes (id_note, tx, output_index, account, diversifier, value, rcm, nf, is_change, spent)
+                VALUES (?1, ?2, ?1, ?3, ?4, ?5, x'00', ?6, 0, ?7);",
+                params![id, tx, account, address.diversifier().0.to_vec(), value, vec![id as u8], spent],
+            )
+            .unwrap();
+        };
+        insert_note(1, 1, 0, &default_address, 10_000, None);
+        insert_note(2, 1, 0, &default_address, 20_000, Some(2));
+        insert_note(3, 2, 0, &diversified_address, 30_000, None);
+        insert_note(4, 3, 0, &diversified_address, 40_000, None);
+        // The same diversifier of the other account.
+        insert_note(5, 2, 1, &diversified_address, 50_000, None);
+
+        assert_eq!(address_balance(conn, AccountId(0), &default_address).unwrap(), 10_000);
+        assert_eq!(
+            address_balance(conn, AccountId(0), &diversified_address).unwrap(),
+            30_000
+        );
+        assert_eq!(
+            address_balance(conn, AccountId(1), &diversified_address).unwrap(),
+            50_000
+        );
+        assert_eq!(address_balance(conn, AccountId(1), &default_address).unwrap(), 0);
+    }
+}
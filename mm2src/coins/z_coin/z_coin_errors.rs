@@ -230,6 +230,10 @@ pub enum ZCoinBuildError {
     ZCashParamsNotFound,
     ZDerivationPathNotSet,
     SaplingParamsInvalidChecksum,
+    #[display(fmt = "Additional ZIP32 accounts can only be activated with the global HD seed")]
+    AdditionalAccountsNotSupported,
+    #[display(fmt = "Invalid ZIP32 account index {}", _0)]
+    InvalidAccountIndex(u32),
 }
 
 #[cfg(not(target_arch = "wasm32"))]
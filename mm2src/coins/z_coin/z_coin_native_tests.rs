@@ -9,7 +9,7 @@ use zcash_client_backend::encoding::decode_extended_spending_key;
 use super::{z_coin_from_conf_and_params_with_z_key, z_mainnet_constants, Future, PrivKeyBuildPolicy,
             RefundPaymentArgs, SendPaymentArgs, SpendPaymentArgs, SwapOps, ValidateFeeArgs, ValidatePaymentError,
             ZTransaction};
-use crate::z_coin::{z_htlc::z_send_dex_fee, ZcoinActivationParams, ZcoinRpcMode};
+use crate::z_coin::{z_htlc::z_send_dex_fee, SyncStatus, ZcoinActivationParams, ZcoinRpcMode};
 use crate::CoinProtocol;
 use crate::DexFee;
 use mm2_number::MmNumber;
@@ -195,6 +195,64 @@ fn prepare_zombie_sapling_cache() {
     }
 }
 
+#[test]
+fn zombie_coin_rescan_from_height() {
+    let ctx = MmCtxBuilder::default().into_mm_arc();
+    let mut conf = zombie_conf();
+    let params = default_zcoin_activation_params();
+    let priv_key = PrivKeyBuildPolicy::IguanaPrivKey([1; 32].into());
+    let db_dir = PathBuf::from("./for_tests");
+    let z_key = decode_extended_spending_key(z_mainnet_constants::HRP_SAPLING_EXTENDED_SPENDING_KEY, "secret-extended-key-main1q0k2ga2cqqqqpq8m8j6yl0say83cagrqp53zqz54w38ezs8ly9ly5ptamqwfpq85u87w0df4k8t2lwyde3n9v0gcr69nu4ryv60t0kfcsvkr8h83skwqex2nf0vr32794fmzk89cpmjptzc22lgu5wfhhp8lgf3f5vn2l3sge0udvxnm95k6dtxj2jwlfyccnum7nz297ecyhmd5ph526pxndww0rqq0qly84l635mec0x4yedf95hzn6kcgq8yxts26k98j9g32kjc8y83fe").unwrap().unwrap();
+    let protocol_info = match serde_json::from_value::<CoinProtocol>(conf["protocol"].take()).unwrap() {
+        CoinProtocol::ZHTLC(protocol_info) => protocol_info,
+        other_protocol => panic!("Failed to get protocol from config: {:?}", other_protocol),
+    };
+
+    let coin = block_on(z_coin_from_conf_and_params_with_z_key(
+        &ctx,
+        "ZOMBIE",
+        &conf,
+        &params,
+        priv_key,
+        db_dir,
+        z_key,
+        protocol_info,
+    ))
+    .unwrap();
+
+    let synced_height = loop {
+        if let Ok(SyncStatus::Finished { block_number, .. }) = block_on(coin.sync_status()) {
+            break block_number;
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    };
+
+    let birthday = synced_height - 10;
+    let first_sync_block = block_on(coin.rescan_from_height(birthday)).unwrap();
+    assert_eq!(first_sync_block.requested, birthday);
+    assert_eq!(first_sync_block.actual, birthday);
+    assert!(!first_sync_block.is_pre_sapling);
+
+    let rescanned_height = loop {
+        if let Ok(SyncStatus::Finished {
+            block_number,
+            first_sync_block,
+        }) = block_on(coin.sync_status())
+        {
+            assert_eq!(first_sync_block.actual, birthday);
+            break block_number;
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    };
+    assert!(rescanned_height >= synced_height);
+
+    // A birthday before the Sapling activation is bumped to it.
+    let first_sync_block = block_on(coin.rescan_from_height(0)).unwrap();
+    assert_eq!(first_sync_block.requested, 0);
+    assert_eq!(first_sync_block.actual, 1);
+    assert!(first_sync_block.is_pre_sapling);
+}
+
 #[test]
 fn zombie_coin_validate_dex_fee() {
     let ctx = MmCtxBuilder::default().into_mm_arc();
@@ -292,5 +350,6 @@ fn default_zcoin_activation_params() -> ZcoinActivationParams {
         scan_blocks_per_iteration: 0,
         scan_interval_ms: 0,
         account: 0,
+        additional_accounts: Vec::new(),
     }
 }
@@ -7,12 +7,13 @@ use futures::channel::mpsc::{Receiver as AsyncReceiver, Sender as AsyncSender};
 use futures::channel::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use futures::lock::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
 use futures::StreamExt;
+use mm2_core::mm_ctx::MmWeak;
 use mm2_err_handle::prelude::*;
 use parking_lot::Mutex;
 use std::sync::Arc;
 use zcash_primitives::consensus::BlockHeight;
 use zcash_primitives::transaction::TxId;
-use zcash_primitives::zip32::ExtendedSpendingKey;
+use zcash_primitives::zip32::ExtendedFullViewingKey;
 
 cfg_native!(
     use crate::{RpcCommonOps, ZTransaction};
@@ -20,11 +21,14 @@ cfg_native!(
     use crate::utxo::utxo_builder::{UtxoCoinBuilderCommonOps, DAY_IN_SECONDS};
     use crate::z_coin::storage::BlockDbError;
     use crate::z_coin::CheckPointBlockInfo;
+    use crate::z_coin::z_accounts::{add_wallet_accounts, init_diversified_addresses_table};
+    use mm2_core::mm_ctx::MmArc;
+    use mm2_event_stream::Event;
 
-    use db_common::sqlite::rusqlite::Connection;
+    use db_common::sqlite::rusqlite::{named_params, Connection, Error as SqliteError};
     use db_common::sqlite::{query_single_row, run_optimization_pragmas};
-    use common::{async_blocking, now_sec};
-    use common::executor::Timer;
+    use common::{async_blocking, now_ms, now_sec};
+    use common::executor::{spawn, Timer};
     use common::log::{debug, error, info, LogOnError};
     use common::Future01CompatExt;
     use futures::channel::mpsc::channel;
@@ -43,9 +47,11 @@ cfg_native!(
     use zcash_client_backend::data_api::chain::{scan_cached_blocks, validate_chain};
     use zcash_client_backend::data_api::error::Error as ChainError;
     use zcash_primitives::block::BlockHash;
-    use zcash_primitives::zip32::ExtendedFullViewingKey;
+    use zcash_primitives::consensus::Parameters;
+    use zcash_primitives::memo::MemoBytes;
+    use zcash_primitives::sapling::note_encryption::try_sapling_note_decryption;
     use zcash_client_sqlite::error::SqliteClientError as ZcashClientError;
-    use zcash_client_sqlite::wallet::init::{init_accounts_table, init_blocks_table, init_wallet_db};
+    use zcash_client_sqlite::wallet::init::{init_blocks_table, init_wallet_db};
     use zcash_client_sqlite::WalletDb;
 
     mod z_coin_grpc {
@@ -65,6 +71,45 @@ pub type OnCompactBlockFn<'a> = dyn FnMut(TonicCompactBlock) -> Result<(), MmErr
 #[allow(unused)]
 pub type OnCompactBlockFn<'a> = dyn FnMut(String) -> Result<(), MmError<UpdateBlocksCacheErr>> + Send + 'a;
 
+/// The event published on every change of the ZCoin sync status if it's enabled in the event stream configuration.
+pub const Z_COIN_SYNC_STATUS_EVENT: &str = "ZCOIN_SYNC_STATUS";
+/// The blocks cache is updated block by block, so the progress events are published not more often than this.
+#[cfg(not(target_arch = "wasm32"))]
+const SYNC_PROGRESS_EVENT_INTERVAL_MS: u64 = 1000;
+
+/// The payload of the [`Z_COIN_SYNC_STATUS_EVENT`].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum ZCoinSyncEvent {
+    UpdatingBlocksCache {
+        current_height: u64,
+        target_height: u64,
+    },
+    BuildingWalletDb {
+        current_height: u64,
+        target_height: u64,
+        notes_found: u64,
+    },
+    Finished {
+        current_height: u64,
+        notes_found: u64,
+    },
+    TemporaryError {
+        error: String,
+    },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ZCoinSyncEvent {
+    fn is_progress(&self) -> bool {
+        matches!(
+            self,
+            ZCoinSyncEvent::UpdatingBlocksCache { .. } | ZCoinSyncEvent::BuildingWalletDb { .. }
+        )
+    }
+}
+
 /// ZRpcOps trait provides asynchronous methods for performing various operations related to
 /// Zcoin blockchain and wallet synchronization.
 #[async_trait]
@@ -90,6 +135,10 @@ pub trait ZRpcOps {
 
     async fn check_tx_existence(&mut self, tx_id: TxId) -> bool;
 
+    /// Asynchronously retrieve the full serialized transaction, e.g. to decrypt the memos of its shielded outputs.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn get_raw_transaction(&mut self, tx_id: TxId) -> Result<Vec<u8>, MmError<UpdateBlocksCacheErr>>;
+
     /// Retrieves checkpoint block information from the database at a specific height.
     ///
     /// checkpoint_block_from_height retrieves tree state information from rpc corresponding to the given
@@ -216,6 +265,22 @@ impl ZRpcOps for LightRpcClient {
         true
     }
 
+    async fn get_raw_transaction(&mut self, tx_id: TxId) -> Result<Vec<u8>, MmError<UpdateBlocksCacheErr>> {
+        let request = tonic::Request::new(TxFilter {
+            block: None,
+            index: 0,
+            hash: tx_id.0.into(),
+        });
+        Ok(self
+            .get_live_client()
+            .await?
+            .get_transaction(request)
+            .await
+            .map_to_mm(UpdateBlocksCacheErr::GrpcError)?
+            .into_inner()
+            .data)
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     async fn checkpoint_block_from_height(
         &mut self,
@@ -346,12 +411,32 @@ impl ZRpcOps for NativeClient {
         true
     }
 
+    async fn get_raw_transaction(&mut self, tx_id: TxId) -> Result<Vec<u8>, MmError<UpdateBlocksCacheErr>> {
+        let tx_bytes = self
+            .get_raw_transaction_bytes(&H256Json::from(tx_id.0))
+            .compat()
+            .await?;
+        Ok(tx_bytes.into_vec())
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     async fn checkpoint_block_from_height(
         &mut self,
-        _height: u64,
+        height: u64,
     ) -> MmResult<Option<CheckPointBlockInfo>, UpdateBlocksCacheErr> {
-        todo!()
+        let tree_state = self.z_get_tree_state(height).compat().await?;
+        let sapling_tree = tree_state
+            .sapling
+            .commitments
+            .map(|commitments| commitments.final_state)
+            .unwrap_or_default();
+
+        Ok(Some(CheckPointBlockInfo {
+            height: tree_state.height,
+            hash: tree_state.hash.reversed(),
+            time: tree_state.time,
+            sapling_tree,
+        }))
     }
 }
 
@@ -364,62 +449,81 @@ pub async fn create_wallet_db(
     wallet_db_path: PathBuf,
     consensus_params: ZcoinConsensusParams,
     checkpoint_block: Option<CheckPointBlockInfo>,
-    evk: ExtendedFullViewingKey,
+    evks: Vec<ExtendedFullViewingKey>,
     continue_from_prev_sync: bool,
 ) -> Result<WalletDb<ZcoinConsensusParams>, MmError<ZcoinClientInitError>> {
     async_blocking({
         move || -> Result<WalletDb<ZcoinConsensusParams>, MmError<ZcoinClientInitError>> {
-            let db = WalletDb::for_path(wallet_db_path, consensus_params)
+            let db = WalletDb::for_path(wallet_db_path, consensus_params.clone())
                 .map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
+            init_wallet_db(&db).map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
+            init_diversified_addresses_table(db.sql_conn())
+                .map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
+
+            let stored_evks = db.get_extended_full_viewing_keys()?;
+            let accounts_added = add_wallet_accounts(db.sql_conn(), &consensus_params, &stored_evks, &evks)
+                .map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
+
             let extrema = db.block_height_extrema()?;
             let min_sync_height = extrema.map(|(min, _)| u32::from(min));
             let init_block_height = checkpoint_block.clone().map(|block| block.height);
 
             run_optimization_pragmas(db.sql_conn())
                 .map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
-            init_wallet_db(&db).map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
 
             // Check if the initial block height is less than the previous synchronization height and
             // Rewind walletdb to the minimum possible height.
-            if db.get_extended_full_viewing_keys()?.is_empty()
-                || (!continue_from_prev_sync && init_block_height != min_sync_height)
-            {
+            if stored_evks.is_empty() || (!continue_from_prev_sync && init_block_height != min_sync_height) {
                 // let user know we're clearing cache and resyncing from new provided height.
                 if min_sync_height.unwrap_or(0) > 0 {
                     info!("Older/Newer sync height detected!, rewinding walletdb to new height: {init_block_height:?}");
                 }
 
-                let mut wallet_ops = db.get_update_ops().expect("get_update_ops always returns Ok");
-                wallet_ops
-                    .rewind_to_height(u32::MIN.into())
-                    .map_to_mm(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
-                if let Some(block) = checkpoint_block.clone() {
-                    init_blocks_table(
-                        &db,
-                        BlockHeight::from_u32(block.height),
-                        BlockHash(block.hash.0),
-                        block.time,
-                        &block.sapling_tree.0,
-                    )?;
+                reset_wallet_db(&db, checkpoint_block)?;
+            } else if accounts_added {
+                // The notes of the new accounts can only be found by rescanning the already synced blocks.
+                info!("New ZIP32 accounts are activated, rescanning walletdb from the height {min_sync_height:?}");
+                match min_sync_height {
+                    Some(min_sync_height) => {
+                        let mut wallet_ops = db.get_update_ops().expect("get_update_ops always returns Ok");
+                        wallet_ops.rewind_to_height(min_sync_height.into())?;
+                    },
+                    None => reset_wallet_db(&db, checkpoint_block)?,
                 }
             }
-
-            if db.get_extended_full_viewing_keys()?.is_empty() {
-                init_accounts_table(&db, &[evk])?;
-            }
             Ok(db)
         }
     })
     .await
 }
 
+/// Removes all the scanned blocks and notes from WalletDb and sets the checkpoint block to start the sync from.
+#[cfg(not(target_arch = "wasm32"))]
+fn reset_wallet_db(
+    db: &WalletDb<ZcoinConsensusParams>,
+    checkpoint_block: Option<CheckPointBlockInfo>,
+) -> Result<(), ZcashClientError> {
+    let mut wallet_ops = db.get_update_ops().expect("get_update_ops always returns Ok");
+    wallet_ops.rewind_to_height(u32::MIN.into())?;
+    if let Some(block) = checkpoint_block {
+        init_blocks_table(
+            db,
+            BlockHeight::from_u32(block.height),
+            BlockHash(block.hash.0),
+            block.time,
+            &block.sapling_tree.0,
+        )?;
+    }
+    Ok(())
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub(super) async fn init_light_client<'a>(
     builder: &ZCoinBuilder<'a>,
     lightwalletd_urls: Vec<String>,
     blocks_db: BlockDbImpl,
     sync_params: &Option<SyncStartPoint>,
-    z_spending_key: &ExtendedSpendingKey,
+    evks: &[ExtendedFullViewingKey],
 ) -> Result<(AsyncMutex<SaplingSyncConnector>, WalletDbShared), MmError<ZcoinClientInitError>> {
     let coin = builder.ticker.to_string();
     let (sync_status_notifier, sync_watcher) = channel(1);
@@ -487,7 +591,7 @@ pub(super) async fn init_light_client<'a>(
     let min_height = blocks_db.get_earliest_block().await?;
     // check if no sync_params was provided and continue syncing from last height in db if it's > 0.
     let continue_from_prev_sync = min_height > 0 && sync_params.is_none();
-    let wallet_db = WalletDbShared::new(builder, maybe_checkpoint_block, z_spending_key, continue_from_prev_sync)
+    let wallet_db = WalletDbShared::new(builder, maybe_checkpoint_block, evks, continue_from_prev_sync)
         .await
         .mm_err(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
 
@@ -507,16 +611,14 @@ pub(super) async fn init_light_client<'a>(
         blocks_db,
         wallet_db: wallet_db.clone(),
         consensus_params: builder.protocol_info.consensus_params.clone(),
+        ctx: builder.ctx.weak(),
+        last_sync_event_ms: 0,
         sync_status_notifier,
         on_tx_gen_watcher,
         watch_for_tx: None,
         scan_blocks_per_iteration: builder.z_coin_params.scan_blocks_per_iteration,
         scan_interval_ms: builder.z_coin_params.scan_interval_ms,
-        first_sync_block: FirstSyncBlock {
-            requested: sync_height,
-            is_pre_sapling: sync_height < sapling_activation_height,
-            actual: sync_height.max(sapling_activation_height),
-        },
+        first_sync_block: FirstSyncBlock::new(sync_height, sapling_activation_height),
     };
 
     let abort_handle = spawn_abortable(light_wallet_db_sync_loop(sync_handle, Box::new(light_rpc_clients)));
@@ -534,7 +636,7 @@ pub(super) async fn init_light_client<'a>(
     _lightwalletd_urls: Vec<String>,
     _blocks_db: BlockDbImpl,
     _sync_params: &Option<SyncStartPoint>,
-    z_spending_key: &ExtendedSpendingKey,
+    evks: &[ExtendedFullViewingKey],
 ) -> Result<(AsyncMutex<SaplingSyncConnector>, WalletDbShared), MmError<ZcoinClientInitError>> {
     todo!()
 }
@@ -544,7 +646,7 @@ pub(super) async fn init_native_client<'a>(
     builder: &ZCoinBuilder<'a>,
     native_client: NativeClient,
     blocks_db: BlockDbImpl,
-    z_spending_key: &ExtendedSpendingKey,
+    evks: &[ExtendedFullViewingKey],
 ) -> Result<(AsyncMutex<SaplingSyncConnector>, WalletDbShared), MmError<ZcoinClientInitError>> {
     let coin = builder.ticker.to_string();
     let (sync_status_notifier, sync_watcher) = channel(1);
@@ -557,7 +659,7 @@ pub(super) async fn init_native_client<'a>(
         is_pre_sapling: false,
         actual: checkpoint_height,
     };
-    let wallet_db = WalletDbShared::new(builder, checkpoint_block, z_spending_key, true)
+    let wallet_db = WalletDbShared::new(builder, checkpoint_block, evks, true)
         .await
         .mm_err(|err| ZcoinClientInitError::ZcashDBError(err.to_string()))?;
 
@@ -567,6 +669,8 @@ pub(super) async fn init_native_client<'a>(
         blocks_db,
         wallet_db: wallet_db.clone(),
         consensus_params: builder.protocol_info.consensus_params.clone(),
+        ctx: builder.ctx.weak(),
+        last_sync_event_ms: 0,
         sync_status_notifier,
         on_tx_gen_watcher,
         watch_for_tx: None,
@@ -587,7 +691,7 @@ pub(super) async fn _init_native_client<'a>(
     _builder: &ZCoinBuilder<'a>,
     mut _native_client: NativeClient,
     _blocks_db: BlockDbImpl,
-    _z_spending_key: &ExtendedSpendingKey,
+    _evks: &[ExtendedFullViewingKey],
 ) -> Result<(AsyncMutex<SaplingSyncConnector>, WalletDbShared), MmError<ZcoinClientInitError>> {
     todo!()
 }
@@ -601,6 +705,53 @@ fn is_tx_imported(conn: &Connection, tx_id: TxId) -> bool {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn count_received_notes(conn: &Connection) -> Result<u64, SqliteError> {
+    const QUERY: &str = "SELECT COUNT(id_note) FROM received_notes;";
+    conn.query_row(QUERY, [], |row| row.get::<_, i64>(0))
+        .map(|count| count as u64)
+}
+
+/// Returns the transactions having received notes which memos weren't decrypted yet.
+#[cfg(not(target_arch = "wasm32"))]
+fn txs_with_unknown_memos(conn: &Connection) -> Result<Vec<(TxId, BlockHeight)>, SqliteError> {
+    const QUERY: &str = "SELECT DISTINCT txes.txid, txes.block FROM received_notes rn
+        JOIN transactions txes ON rn.tx = txes.id_tx
+        WHERE rn.memo IS NULL AND txes.block IS NOT NULL;";
+    let mut stmt = conn.prepare(QUERY)?;
+    let rows = stmt.query_map([], |row| {
+        let txid: Vec<u8> = row.get(0)?;
+        let mut tx_id = [0; 32];
+        tx_id.copy_from_slice(&txid);
+        Ok((TxId(tx_id), BlockHeight::from_u32(row.get(1)?)))
+    })?;
+    rows.collect()
+}
+
+/// Stores the decrypted memos of the transaction outputs.
+/// The memos of the notes that couldn't be decrypted are set empty, so the transaction isn't requested again.
+#[cfg(not(target_arch = "wasm32"))]
+fn store_received_memos(conn: &Connection, tx_id: TxId, memos: Vec<(usize, MemoBytes)>) -> Result<(), SqliteError> {
+    const UPDATE_MEMO: &str = "UPDATE received_notes SET memo = :memo
+        WHERE tx = (SELECT id_tx FROM transactions WHERE txid = :txid) AND output_index = :output_index;";
+    const UPDATE_UNKNOWN_MEMOS: &str = "UPDATE received_notes SET memo = :memo
+        WHERE tx = (SELECT id_tx FROM transactions WHERE txid = :txid) AND memo IS NULL;";
+
+    let txid = tx_id.0.to_vec();
+    for (output_index, memo) in memos {
+        conn.execute(UPDATE_MEMO, named_params! {
+            ":memo": memo.as_slice(),
+            ":txid": txid,
+            ":output_index": output_index as i64,
+        })?;
+    }
+    conn.execute(UPDATE_UNKNOWN_MEMOS, named_params! {
+        ":memo": MemoBytes::empty().as_slice(),
+        ":txid": txid,
+    })?;
+    Ok(())
+}
+
 #[cfg(target_arch = "wasm32")]
 #[allow(unused)]
 fn is_tx_imported(_conn: String, _tx_id: TxId) -> bool { todo!() }
@@ -630,6 +781,13 @@ impl SaplingSyncRespawnGuard {
     pub(super) fn current_block(&self) -> BlockHeight {
         self.sync_handle.as_ref().expect("always Some").0.current_block
     }
+
+    /// Resets the sync state to the birthday height, the chain is rescanned once the guard is dropped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(super) async fn rescan_from(&mut self, birthday: u64) -> Result<FirstSyncBlock, MmError<UpdateBlocksCacheErr>> {
+        let (handle, rpc) = self.sync_handle.as_mut().expect("always Some");
+        handle.reset_to_birthday(rpc.as_mut(), birthday).await
+    }
 }
 
 /// `SyncStatus` enumerates different states that may occur during the execution of
@@ -677,6 +835,49 @@ pub struct FirstSyncBlock {
     pub actual: u64,
 }
 
+impl FirstSyncBlock {
+    /// The sync can't start before the Sapling activation, so the `actual` height is bumped to it if needed.
+    fn new(requested: u64, sapling_activation_height: u64) -> Self {
+        FirstSyncBlock {
+            requested,
+            is_pre_sapling: requested < sapling_activation_height,
+            actual: requested.max(sapling_activation_height),
+        }
+    }
+}
+
+/// Broadcasts the [`Z_COIN_SYNC_STATUS_EVENT`] if it's enabled in the event stream configuration.
+/// Progress events are throttled to one per [`SYNC_PROGRESS_EVENT_INTERVAL_MS`].
+/// Returns whether the event has been broadcast.
+#[cfg(not(target_arch = "wasm32"))]
+fn broadcast_sync_event(ctx: &MmArc, ticker: &str, event: ZCoinSyncEvent, last_sync_event_ms: &mut u64) -> bool {
+    let is_event_active = ctx
+        .event_stream_configuration
+        .as_ref()
+        .map_or(false, |config| config.get_event(Z_COIN_SYNC_STATUS_EVENT).is_some());
+    if !is_event_active {
+        return false;
+    }
+
+    let now = now_ms();
+    if event.is_progress() && now < *last_sync_event_ms + SYNC_PROGRESS_EVENT_INTERVAL_MS {
+        return false;
+    }
+    *last_sync_event_ms = now;
+
+    let payload = json!({
+        "ticker": ticker,
+        "status": event,
+    });
+    let controller = ctx.stream_channel_controller.clone();
+    spawn(async move {
+        controller
+            .broadcast(Event::new(Z_COIN_SYNC_STATUS_EVENT.to_string(), payload.to_string()))
+            .await
+    });
+    true
+}
+
 /// The `SaplingSyncLoopHandle` struct is used to manage and control Zcoin synchronization loop.
 /// It includes information about the coin being synchronized, the current block height, database access, etc.
 #[allow(unused)]
@@ -695,6 +896,9 @@ pub struct SaplingSyncLoopHandle {
     scan_blocks_per_iteration: u32,
     scan_interval_ms: u64,
     first_sync_block: FirstSyncBlock,
+    /// Used to publish [`Z_COIN_SYNC_STATUS_EVENT`]
+    ctx: MmWeak,
+    last_sync_event_ms: u64,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -702,6 +906,10 @@ impl SaplingSyncLoopHandle {
     fn first_sync_block(&self) -> FirstSyncBlock { self.first_sync_block.clone() }
 
     fn notify_blocks_cache_status(&mut self, current_scanned_block: u64, latest_block: u64) {
+        self.publish_sync_event(ZCoinSyncEvent::UpdatingBlocksCache {
+            current_height: current_scanned_block,
+            target_height: latest_block,
+        });
         self.sync_status_notifier
             .try_send(SyncStatus::UpdatingBlocksCache {
                 current_scanned_block,
@@ -711,7 +919,12 @@ impl SaplingSyncLoopHandle {
             .debug_log_with_msg("No one seems interested in SyncStatus");
     }
 
-    fn notify_building_wallet_db(&mut self, current_scanned_block: u64, latest_block: u64) {
+    fn notify_building_wallet_db(&mut self, current_scanned_block: u64, latest_block: u64, notes_found: u64) {
+        self.publish_sync_event(ZCoinSyncEvent::BuildingWalletDb {
+            current_height: current_scanned_block,
+            target_height: latest_block,
+            notes_found,
+        });
         self.sync_status_notifier
             .try_send(SyncStatus::BuildingWalletDb {
                 current_scanned_block,
@@ -722,12 +935,25 @@ impl SaplingSyncLoopHandle {
     }
 
     fn notify_on_error(&mut self, error: String) {
+        self.publish_sync_event(ZCoinSyncEvent::TemporaryError { error: error.clone() });
         self.sync_status_notifier
             .try_send(SyncStatus::TemporaryError(error))
             .debug_log_with_msg("No one seems interested in SyncStatus");
     }
 
     fn notify_sync_finished(&mut self) {
+        let wallet_db = self.wallet_db.clone();
+        let notes_found = match block_in_place(|| count_received_notes(wallet_db.db.lock().sql_conn())) {
+            Ok(notes_found) => notes_found,
+            Err(e) => {
+                error!("Error on counting received notes: {}", e);
+                0
+            },
+        };
+        self.publish_sync_event(ZCoinSyncEvent::Finished {
+            current_height: self.current_block.into(),
+            notes_found,
+        });
         self.sync_status_notifier
             .try_send(SyncStatus::Finished {
                 block_number: self.current_block.into(),
@@ -736,6 +962,39 @@ impl SaplingSyncLoopHandle {
             .debug_log_with_msg("No one seems interested in SyncStatus");
     }
 
+    fn publish_sync_event(&mut self, event: ZCoinSyncEvent) {
+        if let Some(ctx) = MmArc::from_weak(&self.ctx) {
+            broadcast_sync_event(&ctx, &self.coin, event, &mut self.last_sync_event_ms);
+        }
+    }
+
+    /// Resets WalletDb and the blocks cache to the given birthday height,
+    /// so the chain is rescanned from it once the sync loop is respawned.
+    async fn reset_to_birthday(
+        &mut self,
+        rpc: &mut (dyn ZRpcOps + Send),
+        birthday: u64,
+    ) -> Result<FirstSyncBlock, MmError<UpdateBlocksCacheErr>> {
+        let first_sync_block = FirstSyncBlock::new(birthday, self.consensus_params.sapling_activation_height as u64);
+        let checkpoint_block = rpc.checkpoint_block_from_height(first_sync_block.actual).await?;
+
+        let wallet_db = self.wallet_db.clone();
+        block_in_place(|| {
+            reset_wallet_db(&wallet_db.db.lock(), checkpoint_block)?;
+            self.blocks_db
+                .rewind_to_height(u32::MIN)
+                .map_to_mm(|err| UpdateBlocksCacheErr::ZcashDBError(err.to_string()))
+        })?;
+
+        self.current_block = BlockHeight::from_u32(0);
+        info!(
+            "{} wallet is reset to rescan from the height {}",
+            self.coin, first_sync_block.actual
+        );
+        self.first_sync_block = first_sync_block;
+        Ok(self.first_sync_block())
+    }
+
     async fn update_blocks_cache(
         &mut self,
         rpc: &mut (dyn ZRpcOps + Send),
@@ -798,15 +1057,17 @@ impl SaplingSyncLoopHandle {
 
         let current_block = BlockHeight::from_u32(self.blocks_db.get_latest_block()?);
         loop {
+            let notes_found = count_received_notes(wallet_guard.sql_conn())
+                .map_to_mm(|e| BlockDbError::SqliteError(ZcashClientError::from(e)))?;
             match wallet_ops.block_height_extrema()? {
                 Some((_, max_in_wallet)) => {
                     if max_in_wallet >= current_block {
                         break;
                     } else {
-                        self.notify_building_wallet_db(max_in_wallet.into(), current_block.into());
+                        self.notify_building_wallet_db(max_in_wallet.into(), current_block.into(), notes_found);
                     }
                 },
-                None => self.notify_building_wallet_db(0, current_block.into()),
+                None => self.notify_building_wallet_db(0, current_block.into(), notes_found),
             }
 
             scan_cached_blocks(
@@ -822,6 +1083,41 @@ impl SaplingSyncLoopHandle {
         Ok(())
     }
 
+    /// Compact blocks don't contain the memos, so the full transactions of the notes received during the scan
+    /// are requested to decrypt the memos and store them to WalletDb.
+    async fn update_received_memos(
+        &mut self,
+        rpc: &mut (dyn ZRpcOps + Send),
+    ) -> Result<(), MmError<UpdateBlocksCacheErr>> {
+        let wallet_db = self.wallet_db.clone();
+        let txs_without_memos = block_in_place(|| txs_with_unknown_memos(wallet_db.db.lock().sql_conn()))?;
+        if txs_without_memos.is_empty() {
+            return Ok(());
+        }
+
+        // The notes of the stored accounts that aren't activated anymore are still scanned, so their memos too.
+        let evks = block_in_place(|| wallet_db.db.lock().get_extended_full_viewing_keys())?;
+        let ivks: Vec<_> = evks.values().map(|evk| evk.fvk.vk.ivk()).collect();
+        for (tx_id, height) in txs_without_memos {
+            let tx_bytes = rpc.get_raw_transaction(tx_id).await?;
+            let tx = ZTransaction::read(tx_bytes.as_slice())
+                .map_to_mm(|e| UpdateBlocksCacheErr::DecodeError(e.to_string()))?;
+            let memos: Vec<_> = tx
+                .shielded_outputs
+                .iter()
+                .enumerate()
+                .filter_map(|(output_index, output)| {
+                    ivks.iter().find_map(|ivk| {
+                        try_sapling_note_decryption(&self.consensus_params, height, ivk, output)
+                            .map(|(_note, _address, memo)| (output_index, memo))
+                    })
+                })
+                .collect();
+            block_in_place(|| store_received_memos(wallet_db.db.lock().sql_conn(), tx_id, memos))?;
+        }
+        Ok(())
+    }
+
     async fn check_watch_for_tx_existence(&mut self, rpc: &mut (dyn ZRpcOps + Send)) {
         if let Some(tx_id) = self.watch_for_tx {
             if !rpc.check_tx_existence(tx_id).await {
@@ -836,7 +1132,9 @@ impl SaplingSyncLoopHandle {
 impl SaplingSyncLoopHandle {
     fn notify_blocks_cache_status(&mut self, _current_scanned_block: u64, _latest_block: u64) { todo!() }
 
-    fn notify_building_wallet_db(&mut self, _current_scanned_block: u64, _latest_block: u64) { todo!() }
+    fn notify_building_wallet_db(&mut self, _current_scanned_block: u64, _latest_block: u64, _notes_found: u64) {
+        todo!()
+    }
 
     fn notify_on_error(&mut self, _error: String) { todo!() }
 
@@ -899,6 +1197,10 @@ async fn light_wallet_db_sync_loop(mut sync_handle: SaplingSyncLoopHandle, mut c
             continue;
         }
 
+        if let Err(e) = sync_handle.update_received_memos(client.as_mut()).await {
+            error!("Error {} on received memos update", e);
+        }
+
         sync_handle.notify_sync_finished();
 
         sync_handle.check_watch_for_tx_existence(client.as_mut()).await;
@@ -981,3 +1283,164 @@ pub(super) struct SaplingSyncGuard<'a> {
     pub(super) _connector_guard: AsyncMutexGuard<'a, SaplingSyncConnector>,
     pub(super) respawn_guard: SaplingSyncRespawnGuard,
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use common::block_on;
+    use mm2_core::mm_ctx::MmCtxBuilder;
+    use mm2_test_helpers::for_tests::zombie_conf;
+    use serde_json::{self as json, Value as Json};
+    use zcash_primitives::zip32::ExtendedSpendingKey;
+
+    fn zombie_consensus_params() -> ZcoinConsensusParams {
+        json::from_value(zombie_conf()["protocol"]["protocol_data"]["consensus_params"].clone()).unwrap()
+    }
+
+    fn checkpoint_block(height: u32) -> CheckPointBlockInfo {
+        CheckPointBlockInfo {
+            height,
+            hash: H256Json::default(),
+            time: 1_700_000_000,
+            // An empty Sapling commitment tree.
+            sapling_tree: Bytes::from(vec![0, 0, 0]),
+        }
+    }
+
+    fn sync_status_ctx() -> MmArc {
+        MmCtxBuilder::new()
+            .with_conf(json!({
+                "event_stream_configuration": {
+                    "active_events": {
+                        "ZCOIN_SYNC_STATUS": {}
+                    }
+                }
+            }))
+            .into_mm_arc()
+    }
+
+    #[test]
+    fn test_first_sync_block() {
+        let first_sync_block = FirstSyncBlock::new(1000, 1);
+        assert_eq!(first_sync_block.requested, 1000);
+        assert!(!first_sync_block.is_pre_sapling);
+        assert_eq!(first_sync_block.actual, 1000);
+
+        let first_sync_block = FirstSyncBlock::new(100, 419200);
+        assert_eq!(first_sync_block.requested, 100);
+        assert!(first_sync_block.is_pre_sapling);
+        assert_eq!(first_sync_block.actual, 419200);
+    }
+
+    #[test]
+    fn test_reset_wallet_db() {
+        let evk = ExtendedFullViewingKey::from(&ExtendedSpendingKey::master(&[1; 32]));
+        let db = block_on(create_wallet_db(
+            PathBuf::from(":memory:"),
+            zombie_consensus_params(),
+            Some(checkpoint_block(100)),
+            vec![evk],
+            false,
+        ))
+        .unwrap();
+        let expected = Some((BlockHeight::from_u32(100), BlockHeight::from_u32(100)));
+        assert_eq!(db.block_height_extrema().unwrap(), expected);
+
+        // Rescan from a new birthday, the accounts must be kept.
+        reset_wallet_db(&db, Some(checkpoint_block(200))).unwrap();
+        let expected = Some((BlockHeight::from_u32(200), BlockHeight::from_u32(200)));
+        assert_eq!(db.block_height_extrema().unwrap(), expected);
+        assert_eq!(db.get_extended_full_viewing_keys().unwrap().len(), 1);
+
+        // Rescan from the Sapling activation if there is no checkpoint.
+        reset_wallet_db(&db, None).unwrap();
+        assert_eq!(db.block_height_extrema().unwrap(), None);
+        assert_eq!(db.get_extended_full_viewing_keys().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_sync_event_is_progress() {
+        assert!(ZCoinSyncEvent::UpdatingBlocksCache {
+            current_height: 1,
+            target_height: 2
+        }
+        .is_progress());
+        assert!(ZCoinSyncEvent::BuildingWalletDb {
+            current_height: 1,
+            target_height: 2,
+            notes_found: 0
+        }
+        .is_progress());
+        assert!(!ZCoinSyncEvent::Finished {
+            current_height: 2,
+            notes_found: 0
+        }
+        .is_progress());
+        assert!(!ZCoinSyncEvent::TemporaryError {
+            error: "error".to_string()
+        }
+        .is_progress());
+    }
+
+    #[test]
+    fn test_broadcast_sync_event() {
+        let ctx = sync_status_ctx();
+        let mut receiver = ctx.stream_channel_controller.clone().create_channel(4);
+        let mut last_sync_event_ms = 0;
+
+        let event = ZCoinSyncEvent::BuildingWalletDb {
+            current_height: 10,
+            target_height: 20,
+            notes_found: 1,
+        };
+        assert!(broadcast_sync_event(&ctx, "ZOMBIE", event, &mut last_sync_event_ms));
+        assert_ne!(last_sync_event_ms, 0);
+        let event = block_on(receiver.recv()).unwrap();
+        assert_eq!(event.event_type(), Z_COIN_SYNC_STATUS_EVENT);
+        let expected = json!({
+            "ticker": "ZOMBIE",
+            "status": {
+                "state": "building_wallet_db",
+                "current_height": 10,
+                "target_height": 20,
+                "notes_found": 1
+            }
+        });
+        assert_eq!(json::from_str::<Json>(event.message()).unwrap(), expected);
+
+        // The next progress event is throttled.
+        let event = ZCoinSyncEvent::UpdatingBlocksCache {
+            current_height: 11,
+            target_height: 20,
+        };
+        assert!(!broadcast_sync_event(&ctx, "ZOMBIE", event, &mut last_sync_event_ms));
+
+        // But the final one is not.
+        let event = ZCoinSyncEvent::Finished {
+            current_height: 20,
+            notes_found: 1,
+        };
+        assert!(broadcast_sync_event(&ctx, "ZOMBIE", event, &mut last_sync_event_ms));
+        let event = block_on(receiver.recv()).unwrap();
+        let expected = json!({
+            "ticker": "ZOMBIE",
+            "status": {
+                "state": "finished",
+                "current_height": 20,
+                "notes_found": 1
+            }
+        });
+        assert_eq!(json::from_str::<Json>(event.message()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_broadcast_sync_event_not_active() {
+        let ctx = MmCtxBuilder::new().into_mm_arc();
+        let mut last_sync_event_ms = 0;
+        let event = ZCoinSyncEvent::TemporaryError {
+            error: "error".to_string(),
+        };
+        assert!(!broadcast_sync_event(&ctx, "ZOMBIE", event, &mut last_sync_event_ms));
+        assert_eq!(last_sync_event_ms, 0);
+    }
+}
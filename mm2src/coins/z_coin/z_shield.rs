@@ -0,0 +1,323 @@
+//! Moving funds between the transparent address of `ZCoin` and its shielded accounts.
+//!
+//! Shielding sweeps every transparent UTXO of the coin into the default sapling address of an account,
+//! deshielding spends the notes of an account to a transparent address.
+//! Both transactions are built with the same `z_tx_builder` and prover as the regular withdrawals
+//! and are not broadcasted either, so they are sent via `send_raw_transaction` after a review.
+
+use super::{GenTxError, ZCoin, ZCoinAccount};
+use crate::rpc_command::z_coin_shield::{ZCoinDeshieldRequest, ZCoinShieldRequest};
+use crate::utxo::rpc_clients::UnspentInfo;
+use crate::utxo::utxo_common::big_decimal_from_sat_unsigned;
+use crate::utxo::{output_script, sat_from_big_decimal, ActualTxFee, AdditionalTxData, Address, GetUtxoListOps,
+                  UtxoCommonOps, UtxoFeeDetails};
+use crate::{MarketCoinOps, NumConversError, TransactionDetails, TxFeeDetails, WithdrawError};
+use chain::constants::SEQUENCE_FINAL;
+use common::async_blocking;
+use futures::compat::Future01CompatExt;
+use keys::Type as ScriptType;
+use mm2_err_handle::prelude::*;
+use mm2_number::BigDecimal;
+use secp256k1::SecretKey;
+use zcash_client_backend::encoding::encode_payment_address;
+use zcash_primitives::consensus::{self, Parameters};
+use zcash_primitives::legacy::Script as ZCashScript;
+use zcash_primitives::transaction::builder::Builder as ZTxBuilder;
+use zcash_primitives::transaction::components::{Amount, OutPoint as ZCashOutpoint, TxOut};
+use zcash_primitives::transaction::Transaction as ZTransaction;
+
+const KILO_BYTE: u64 = 1000;
+/// The serialized size of a Sapling (v4) tx without its inputs and outputs, including the binding signature.
+const SAPLING_TX_OVERHEAD_SIZE: u64 = 100;
+/// The max serialized size of a P2PKH input, with a 72-byte signature and a compressed pubkey.
+const P2PKH_INPUT_SIZE: u64 = 148;
+/// The serialized size of a Sapling output description.
+const SAPLING_OUTPUT_SIZE: u64 = 948;
+
+impl ZCoin {
+    /// Generates a tx sweeping all the transparent UTXOs of the coin into the account's default address.
+    pub async fn shield(&self, req: ZCoinShieldRequest) -> MmResult<TransactionDetails, WithdrawError> {
+        let account = self.z_account(req.z_account)?;
+        let my_t_address = self.address_from_pubkey(self.secp_keypair().public());
+        let (unspents, _recently_spent) = self
+            .get_unspent_ordered_list(&my_t_address)
+            .await
+            .mm_err(|e| WithdrawError::Transport(e.to_string()))?;
+
+        let tx_fee = self.get_tx_fee().await?;
+        let total_sat: u64 = unspents.iter().map(|unspent| unspent.value).sum();
+        let current_block = self.utxo_arc.rpc_client.get_block_count().compat().await? as u32;
+
+        // The fee is estimated by the size of the inputs and the output first,
+        // and the tx is rebuilt if its actual size requires a greater fee.
+        let mut fee_sat = fee_for_tx_size(tx_fee, shield_tx_size(unspents.len()));
+        let (tx, fee_sat) = loop {
+            if total_sat <= fee_sat {
+                return MmError::err(WithdrawError::NotSufficientBalance {
+                    coin: self.ticker().to_owned(),
+                    available: big_decimal_from_sat_unsigned(total_sat, self.decimals()),
+                    required: big_decimal_from_sat_unsigned(fee_sat, self.decimals()),
+                });
+            }
+            let tx = self
+                .build_shield_tx(account, &my_t_address, &unspents, total_sat - fee_sat, current_block)
+                .await?;
+            let actual_fee_sat = fee_for_tx_size(tx_fee, tx_size(&tx)?);
+            if actual_fee_sat <= fee_sat {
+                break (tx, fee_sat);
+            }
+            fee_sat = actual_fee_sat;
+        };
+
+        let shielded_sat = total_sat - fee_sat;
+        let data = AdditionalTxData {
+            received_by_me: shielded_sat,
+            spent_by_me: total_sat,
+            fee_amount: fee_sat,
+            unused_change: 0,
+            kmd_rewards: None,
+        };
+        let from = my_t_address.to_string();
+        let to = self.encode_z_address(account);
+        self.z_tx_details(&tx, data, from, to)
+    }
+
+    async fn build_shield_tx(
+        &self,
+        account: &ZCoinAccount,
+        my_t_address: &Address,
+        unspents: &[UnspentInfo],
+        shielded_sat: u64,
+        current_block: u32,
+    ) -> MmResult<ZTransaction, WithdrawError> {
+        let mut tx_builder = ZTxBuilder::new(self.consensus_params(), current_block.into());
+
+        let secp_secret =
+            SecretKey::from_slice(self.secp_keypair().private_ref()).expect("Keypair contains a valid secret key");
+        let script_pubkey = ZCashScript(output_script(my_t_address, ScriptType::P2PKH).to_bytes().take());
+        for unspent in unspents {
+            let outpoint = ZCashOutpoint::new(unspent.outpoint.hash.take(), unspent.outpoint.index);
+            let tx_out = TxOut {
+                value: z_amount(unspent.value)?,
+                script_pubkey: script_pubkey.clone(),
+            };
+            tx_builder
+                .add_transparent_input(secp_secret, outpoint, SEQUENCE_FINAL, ZCashScript(Vec::new()), tx_out)
+                .map_to_mm(GenTxError::from)?;
+        }
+
+        tx_builder
+            .add_sapling_output(
+                Some(account.evk.fvk.ovk),
+                account.default_address.clone(),
+                z_amount(shielded_sat)?,
+                None,
+            )
+            .map_to_mm(GenTxError::from)?;
+
+        let (tx, _) = async_blocking({
+            let prover = self.z_fields.z_tx_prover.clone();
+            move || tx_builder.build(consensus::BranchId::Sapling, prover.as_ref())
+        })
+        .await
+        .map_to_mm(GenTxError::from)?;
+        Ok(tx)
+    }
+
+    /// Generates a tx sending the shielded funds of the account to a transparent address.
+    pub async fn deshield(&self, req: ZCoinDeshieldRequest) -> MmResult<TransactionDetails, WithdrawError> {
+        let account = self.z_account(req.z_account)?;
+        let to = self
+            .address_from_str(&req.to)
+            .mm_err(|e| WithdrawError::InvalidAddress(e.to_string()))?;
+
+        let conf = &self.utxo_arc.conf;
+        let script_type = if to.prefix == conf.pub_addr_prefix && to.t_addr_prefix == conf.pub_t_addr_prefix {
+            ScriptType::P2PKH
+        } else if to.prefix == conf.p2sh_addr_prefix && to.t_addr_prefix == conf.p2sh_t_addr_prefix {
+            ScriptType::P2SH
+        } else {
+            return MmError::err(WithdrawError::InvalidAddress("Expected either P2PKH or P2SH".into()));
+        };
+
+        let amount = if req.max {
+            let fee = self.get_one_kbyte_tx_fee().await?;
+            let balance_sat = self
+                .account_balance_sat(account.wallet_account_id)
+                .await
+                .mm_err(|e| WithdrawError::InternalError(e.to_string()))?;
+            let balance = big_decimal_from_sat_unsigned(balance_sat, self.decimals());
+            max_deshield_amount(self.ticker(), balance, fee)?
+        } else {
+            validate_deshield_amount(req.amount)?
+        };
+        let amount_sat = sat_from_big_decimal(&amount, self.decimals()).mm_err(GenTxError::from)?;
+        let t_output = TxOut {
+            value: z_amount(amount_sat)?,
+            script_pubkey: ZCashScript(output_script(&to, script_type).to_bytes().take()),
+        };
+
+        let (tx, mut data, _sync_guard) = self.gen_tx_from_account(account, vec![t_output], vec![]).await?;
+        if to == self.address_from_pubkey(self.secp_keypair().public()) {
+            data.received_by_me += amount_sat;
+        }
+        self.z_tx_details(&tx, data, self.encode_z_address(account), req.to)
+    }
+
+    fn encode_z_address(&self, account: &ZCoinAccount) -> String {
+        encode_payment_address(
+            self.consensus_params_ref().hrp_sapling_payment_address(),
+            &account.default_address,
+        )
+    }
+
+    fn z_tx_details(
+        &self,
+        tx: &ZTransaction,
+        data: AdditionalTxData,
+        from: String,
+        to: String,
+    ) -> MmResult<TransactionDetails, WithdrawError> {
+        let mut tx_bytes = Vec::with_capacity(1024);
+        tx.write(&mut tx_bytes)
+            .map_to_mm(|e| WithdrawError::InternalError(e.to_string()))?;
+        let mut tx_hash = tx.txid().0.to_vec();
+        tx_hash.reverse();
+
+        let received_by_me = big_decimal_from_sat_unsigned(data.received_by_me, self.decimals());
+        let spent_by_me = big_decimal_from_sat_unsigned(data.spent_by_me, self.decimals());
+
+        Ok(TransactionDetails {
+            tx_hex: tx_bytes.into(),
+            tx_hash: hex::encode(&tx_hash),
+            from: vec![from],
+            to: vec![to],
+            my_balance_change: &received_by_me - &spent_by_me,
+            total_amount: spent_by_me.clone(),
+            spent_by_me,
+            received_by_me,
+            block_height: 0,
+            timestamp: 0,
+            fee_details: Some(TxFeeDetails::Utxo(UtxoFeeDetails {
+                coin: Some(self.ticker().to_owned()),
+                amount: big_decimal_from_sat_unsigned(data.fee_amount, self.decimals()),
+            })),
+            coin: self.ticker().to_owned(),
+            internal_id: tx_hash.into(),
+            kmd_rewards: None,
+            transaction_type: Default::default(),
+            memo: None,
+        })
+    }
+}
+
+/// Estimates the size of a tx spending the P2PKH inputs to a single Sapling output.
+fn shield_tx_size(inputs: usize) -> u64 {
+    SAPLING_TX_OVERHEAD_SIZE + inputs as u64 * P2PKH_INPUT_SIZE + SAPLING_OUTPUT_SIZE
+}
+
+fn tx_size(tx: &ZTransaction) -> MmResult<u64, WithdrawError> {
+    let mut tx_bytes = Vec::with_capacity(1024);
+    tx.write(&mut tx_bytes)
+        .map_to_mm(|e| WithdrawError::InternalError(e.to_string()))?;
+    Ok(tx_bytes.len() as u64)
+}
+
+/// Calculates the fee the same way as the UTXO coins do, see `utxo_common::get_htlc_spend_fee`.
+fn fee_for_tx_size(tx_fee: ActualTxFee, tx_size: u64) -> u64 {
+    match tx_fee {
+        ActualTxFee::Dynamic(fee_per_kb) => fee_per_kb * tx_size / KILO_BYTE,
+        ActualTxFee::FixedPerKb(fee_per_kb) => {
+            let tx_size_kb = if tx_size % KILO_BYTE == 0 {
+                tx_size / KILO_BYTE
+            } else {
+                tx_size / KILO_BYTE + 1
+            };
+            fee_per_kb * tx_size_kb
+        },
+    }
+}
+
+fn max_deshield_amount(ticker: &str, balance: BigDecimal, fee: BigDecimal) -> MmResult<BigDecimal, WithdrawError> {
+    if balance <= fee {
+        return MmError::err(WithdrawError::NotSufficientBalance {
+            coin: ticker.to_owned(),
+            available: balance,
+            required: fee,
+        });
+    }
+    Ok(balance - fee)
+}
+
+fn validate_deshield_amount(amount: BigDecimal) -> MmResult<BigDecimal, WithdrawError> {
+    if amount <= BigDecimal::from(0) {
+        return MmError::err(WithdrawError::AmountTooLow {
+            amount,
+            threshold: BigDecimal::from(0),
+        });
+    }
+    Ok(amount)
+}
+
+fn z_amount(sat: u64) -> MmResult<Amount, GenTxError> {
+    Amount::from_u64(sat)
+        .map_to_mm(|_| GenTxError::NumConversion(NumConversError(format!("Failed to get ZCash amount from {}", sat))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_shield_tx_size() {
+        assert_eq!(shield_tx_size(1), 1196);
+        assert_eq!(shield_tx_size(3), 1492);
+    }
+
+    #[test]
+    fn test_fee_for_tx_size() {
+        assert_eq!(fee_for_tx_size(ActualTxFee::Dynamic(1000), 1196), 1196);
+        assert_eq!(fee_for_tx_size(ActualTxFee::Dynamic(1000), 500), 500);
+        assert_eq!(fee_for_tx_size(ActualTxFee::FixedPerKb(1000), 1196), 2000);
+        assert_eq!(fee_for_tx_size(ActualTxFee::FixedPerKb(1000), 1000), 1000);
+        assert_eq!(fee_for_tx_size(ActualTxFee::FixedPerKb(1000), 500), 1000);
+
+        // A tx sweeping many UTXOs requires a greater fee than a 1-kbyte one.
+        let tx_size = shield_tx_size(100);
+        assert_eq!(fee_for_tx_size(ActualTxFee::FixedPerKb(1000), tx_size), 16000);
+    }
+
+    #[test]
+    fn test_max_deshield_amount() {
+        let balance = BigDecimal::from(1);
+        let fee = BigDecimal::from_str("0.00001").unwrap();
+        let actual = max_deshield_amount("ARRR", balance, fee.clone()).unwrap();
+        assert_eq!(actual, BigDecimal::from_str("0.99999").unwrap());
+
+        let error = max_deshield_amount("ARRR", fee.clone(), fee.clone())
+            .unwrap_err()
+            .into_inner();
+        let expected = WithdrawError::NotSufficientBalance {
+            coin: "ARRR".to_owned(),
+            available: fee.clone(),
+            required: fee,
+        };
+        assert_eq!(error, expected);
+    }
+
+    #[test]
+    fn test_validate_deshield_amount() {
+        let amount = BigDecimal::from_str("0.1").unwrap();
+        assert_eq!(validate_deshield_amount(amount.clone()).unwrap(), amount);
+
+        for amount in [BigDecimal::from(0), BigDecimal::from(-1)] {
+            let error = validate_deshield_amount(amount.clone()).unwrap_err().into_inner();
+            let expected = WithdrawError::AmountTooLow {
+                amount,
+                threshold: BigDecimal::from(0),
+            };
+            assert_eq!(error, expected);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::slp_token_activation::SlpActivationRequest;
 use async_trait::async_trait;
 use coins::my_tx_history_v2::TxHistoryStorage;
 use coins::utxo::bch::{bch_coin_with_policy, BchActivationRequest, BchCoin, CashAddrPrefix};
+use coins::utxo::cash_token::{CashToken, CashTokenProtocolConf, EnableCashTokenError};
 use coins::utxo::rpc_clients::UtxoRpcError;
 use coins::utxo::slp::{EnableSlpError, SlpProtocolConf, SlpToken};
 use coins::utxo::utxo_tx_history_v2::bch_and_slp_history_loop;
@@ -93,6 +94,102 @@ impl RegisterTokenInfo<SlpToken> for BchCoin {
     fn register_token_info(&self, token: &SlpToken) { self.add_slp_token_info(token.ticker().into(), token.get_info()) }
 }
 
+impl From<EnableCashTokenError> for InitTokensAsMmCoinsError {
+    fn from(e: EnableCashTokenError) -> Self {
+        match e {
+            EnableCashTokenError::UnexpectedDerivationMethod(internal) | EnableCashTokenError::Internal(internal) => {
+                InitTokensAsMmCoinsError::Internal(internal)
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CashTokenActivationRequest {
+    pub required_confirmations: Option<u64>,
+}
+
+impl TryFromCoinProtocol for CashTokenProtocolConf {
+    fn try_from_coin_protocol(proto: CoinProtocol) -> Result<Self, MmError<CoinProtocol>>
+    where
+        Self: Sized,
+    {
+        match proto {
+            CoinProtocol::CASHTOKEN {
+                platform,
+                category,
+                decimals,
+                required_confirmations,
+            } => Ok(CashTokenProtocolConf {
+                platform_coin_ticker: platform,
+                // The category is configured in the display byte order.
+                category: category.reversed().into(),
+                decimals,
+                required_confirmations,
+            }),
+            proto => MmError::err(proto),
+        }
+    }
+}
+
+pub struct CashTokenInitializer {
+    platform_coin: BchCoin,
+}
+
+impl TokenOf for CashToken {
+    type PlatformCoin = BchCoin;
+}
+
+#[async_trait]
+impl TokenInitializer for CashTokenInitializer {
+    type Token = CashToken;
+    type TokenActivationRequest = CashTokenActivationRequest;
+    type TokenProtocol = CashTokenProtocolConf;
+    type InitTokensError = EnableCashTokenError;
+
+    fn tokens_requests_from_platform_request(
+        platform_params: &BchWithTokensActivationRequest,
+    ) -> Vec<TokenActivationRequest<Self::TokenActivationRequest>> {
+        platform_params.cash_tokens_requests.clone()
+    }
+
+    async fn enable_tokens(
+        &self,
+        activation_params: Vec<TokenActivationParams<CashTokenActivationRequest, CashTokenProtocolConf>>,
+    ) -> Result<Vec<CashToken>, MmError<EnableCashTokenError>> {
+        let tokens = activation_params
+            .into_iter()
+            .map(|params| {
+                // confirmation settings from RPC request have the highest priority
+                let required_confirmations = params.activation_request.required_confirmations.unwrap_or_else(|| {
+                    params
+                        .protocol
+                        .required_confirmations
+                        .unwrap_or_else(|| self.platform_coin.required_confirmations())
+                });
+
+                CashToken::new(
+                    params.protocol.decimals,
+                    params.ticker,
+                    params.protocol.category,
+                    self.platform_coin.clone(),
+                    required_confirmations,
+                )
+            })
+            .collect::<MmResult<_, EnableCashTokenError>>()?;
+
+        Ok(tokens)
+    }
+
+    fn platform_coin(&self) -> &BchCoin { &self.platform_coin }
+}
+
+impl RegisterTokenInfo<CashToken> for BchCoin {
+    fn register_token_info(&self, token: &CashToken) {
+        self.add_cash_token_info(token.ticker().into(), token.get_info())
+    }
+}
+
 impl From<BchWithTokensActivationError> for EnablePlatformCoinWithTokensError {
     fn from(err: BchWithTokensActivationError) -> Self {
         match err {
@@ -122,6 +219,8 @@ pub struct BchWithTokensActivationRequest {
     #[serde(flatten)]
     platform_request: BchActivationRequest,
     slp_tokens_requests: Vec<TokenActivationRequest<SlpActivationRequest>>,
+    #[serde(default)]
+    cash_tokens_requests: Vec<TokenActivationRequest<CashTokenActivationRequest>>,
     #[serde(default = "true_f")]
     pub get_balances: bool,
 }
@@ -151,6 +250,8 @@ pub struct BchWithTokensActivationResult {
     current_block: u64,
     bch_addresses_infos: HashMap<String, CoinAddressInfo<CoinBalance>>,
     slp_addresses_infos: HashMap<String, CoinAddressInfo<TokenBalances>>,
+    /// CashTokens are held by the BCH address, so the keys are the same as in `bch_addresses_infos`.
+    cash_tokens_addresses_infos: HashMap<String, CoinAddressInfo<TokenBalances>>,
 }
 
 impl GetPlatformBalance for BchWithTokensActivationResult {
@@ -252,9 +353,14 @@ impl PlatformWithTokensActivationOps for BchCoin {
     fn token_initializers(
         &self,
     ) -> Vec<Box<dyn TokenAsMmCoinInitializer<PlatformCoin = Self, ActivationRequest = Self::ActivationRequest>>> {
-        vec![Box::new(SlpTokenInitializer {
-            platform_coin: self.clone(),
-        })]
+        vec![
+            Box::new(SlpTokenInitializer {
+                platform_coin: self.clone(),
+            }),
+            Box::new(CashTokenInitializer {
+                platform_coin: self.clone(),
+            }),
+        ]
     }
 
     async fn get_activation_result(
@@ -285,16 +391,27 @@ impl PlatformWithTokensActivationOps for BchCoin {
             tickers: None,
         };
 
+        let mut cash_tokens_address_info = CoinAddressInfo {
+            derivation_method: DerivationMethod::Iguana,
+            pubkey: pubkey.clone(),
+            balances: None,
+            tickers: None,
+        };
+
         if !activation_request.get_balances {
             drop_mutability!(bch_address_info);
             let tickers: HashSet<_> = self.get_slp_tokens_infos().keys().cloned().collect();
             slp_address_info.tickers = Some(tickers);
             drop_mutability!(slp_address_info);
+            let cash_tokens_tickers: HashSet<_> = self.get_cash_tokens_infos().keys().cloned().collect();
+            cash_tokens_address_info.tickers = Some(cash_tokens_tickers);
+            drop_mutability!(cash_tokens_address_info);
 
             return Ok(BchWithTokensActivationResult {
                 current_block,
                 bch_addresses_infos: HashMap::from([(my_address.to_string(), bch_address_info)]),
                 slp_addresses_infos: HashMap::from([(my_slp_address, slp_address_info)]),
+                cash_tokens_addresses_infos: HashMap::from([(my_address.to_string(), cash_tokens_address_info)]),
             });
         }
 
@@ -313,10 +430,22 @@ impl PlatformWithTokensActivationOps for BchCoin {
         slp_address_info.balances = Some(token_balances);
         drop_mutability!(slp_address_info);
 
+        let cash_tokens_balances: HashMap<_, _> = self
+            .get_cash_tokens_infos()
+            .iter()
+            .map(|(token_ticker, info)| {
+                let token_balance = bch_unspents.cash_token_balance(&info.category, info.decimals);
+                (token_ticker.clone(), token_balance)
+            })
+            .collect();
+        cash_tokens_address_info.balances = Some(cash_tokens_balances);
+        drop_mutability!(cash_tokens_address_info);
+
         Ok(BchWithTokensActivationResult {
             current_block,
             bch_addresses_infos: HashMap::from([(my_address.to_string(), bch_address_info)]),
             slp_addresses_infos: HashMap::from([(my_slp_address, slp_address_info)]),
+            cash_tokens_addresses_infos: HashMap::from([(my_address.to_string(), cash_tokens_address_info)]),
         })
     }
 
@@ -65,7 +65,7 @@ impl TryFromCoinProtocol for EthCoinType {
         Self: Sized,
     {
         match proto {
-            CoinProtocol::ETH => Ok(EthCoinType::Eth),
+            CoinProtocol::ETH(_) => Ok(EthCoinType::Eth),
             protocol => MmError::err(protocol),
         }
     }
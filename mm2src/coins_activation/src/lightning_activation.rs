@@ -351,7 +351,7 @@ async fn start_lightning(
     let logger = ctx.log.0.clone();
 
     // Initialize Persister
-    let persister = init_persister(ctx, conf.ticker.clone(), params.backup_path).await?;
+    let persister = init_persister(ctx, &platform, conf.ticker.clone(), params.backup_path).await?;
 
     // Initialize the KeysManager
     let keys_manager = init_keys_manager(&platform)?;
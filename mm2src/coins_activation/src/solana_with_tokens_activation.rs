@@ -9,9 +9,11 @@ use async_trait::async_trait;
 use coins::coin_errors::MyAddressError;
 use coins::my_tx_history_v2::TxHistoryStorage;
 use coins::solana::solana_coin_with_policy;
+use coins::solana::solana_tx_history_v2::solana_history_loop;
 use coins::solana::spl::{SplProtocolConf, SplTokenCreationError};
-use coins::{BalanceError, CoinBalance, CoinProtocol, MarketCoinOps, MmCoinEnum, PrivKeyBuildPolicy,
+use coins::{BalanceError, CoinBalance, CoinProtocol, MarketCoinOps, MmCoin, MmCoinEnum, PrivKeyBuildPolicy,
             SolanaActivationParams, SolanaCoin, SplToken};
+use common::executor::{AbortSettings, SpawnAbortable};
 use common::Future01CompatExt;
 use common::{drop_mutability, true_f};
 use crypto::CryptoCtxError;
@@ -87,7 +89,7 @@ pub struct SolanaWithTokensActivationRequest {
 }
 
 impl TxHistory for SolanaWithTokensActivationRequest {
-    fn tx_history(&self) -> bool { false }
+    fn tx_history(&self) -> bool { self.platform_request.tx_history }
 }
 
 #[derive(Debug, Serialize)]
@@ -284,10 +286,14 @@ impl PlatformWithTokensActivationOps for SolanaCoin {
 
     fn start_history_background_fetching(
         &self,
-        _ctx: MmArc,
-        _storage: impl TxHistoryStorage + Send + 'static,
-        _initial_balance: Option<BigDecimal>,
+        ctx: MmArc,
+        storage: impl TxHistoryStorage + Send + 'static,
+        initial_balance: Option<BigDecimal>,
     ) {
+        let fut = solana_history_loop(self.clone(), storage, ctx, initial_balance);
+
+        let settings = AbortSettings::info_on_abort(format!("solana_history_loop stopped for {}", self.ticker()));
+        self.spawner().spawn_with_settings(fut, settings);
     }
 
     async fn handle_balance_streaming(
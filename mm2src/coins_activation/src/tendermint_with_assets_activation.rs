@@ -4,12 +4,13 @@ use crate::platform_coin_with_tokens::{EnablePlatformCoinWithTokensError, GetPla
                                        TokenInitializer, TokenOf};
 use crate::prelude::*;
 use async_trait::async_trait;
+use coins::coin_balance::{CoinBalanceReport, EnabledCoinBalanceParams};
 use coins::my_tx_history_v2::TxHistoryStorage;
 use coins::tendermint::tendermint_tx_history_v2::tendermint_history_loop;
 use coins::tendermint::{tendermint_priv_key_policy, TendermintCoin, TendermintCommons, TendermintConf,
                         TendermintInitError, TendermintInitErrorKind, TendermintProtocolInfo, TendermintToken,
                         TendermintTokenActivationParams, TendermintTokenInitError, TendermintTokenProtocolInfo};
-use coins::{CoinBalance, CoinProtocol, MarketCoinOps, MmCoin, MmCoinEnum, PrivKeyBuildPolicy};
+use coins::{CoinBalance, CoinProtocol, CoinWithDerivationMethod, MarketCoinOps, MmCoin, MmCoinEnum, PrivKeyBuildPolicy};
 use common::executor::{AbortSettings, SpawnAbortable};
 use common::{true_f, Future01CompatExt};
 use crypto::StandardHDCoinAddress;
@@ -43,6 +44,9 @@ pub struct TendermintActivationParams {
     /// /account'/change/address_index`.
     #[serde(default)]
     pub path_to_address: StandardHDCoinAddress,
+    /// Is used to scan for the HD wallet addresses if the coin is activated with the HD wallet.
+    #[serde(default)]
+    pub enable_params: EnabledCoinBalanceParams,
 }
 
 impl TxHistory for TendermintActivationParams {
@@ -139,6 +143,24 @@ pub struct TendermintActivationResult {
     tokens_balances: Option<HashMap<String, CoinBalance>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tokens_tickers: Option<HashSet<String>>,
+    /// Platform coin balances of the HD wallet addresses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wallet_balance: Option<CoinBalanceReport>,
+    /// Assets balances of the HD wallet addresses where the key is address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    addresses_tokens_balances: Option<HashMap<String, HashMap<String, CoinBalance>>>,
+}
+
+fn spendable_balances(balances: HashMap<String, BigDecimal>) -> HashMap<String, CoinBalance> {
+    balances
+        .into_iter()
+        .map(|(ticker, balance)| {
+            (ticker, CoinBalance {
+                spendable: balance,
+                unspendable: BigDecimal::default(),
+            })
+        })
+        .collect()
 }
 
 impl CurrentBlock for TendermintActivationResult {
@@ -187,7 +209,7 @@ impl PlatformWithTokensActivationOps for TendermintCoin {
             activation_request.path_to_address,
         )?;
 
-        TendermintCoin::init(
+        let coin = TendermintCoin::init(
             &ctx,
             ticker,
             conf,
@@ -196,7 +218,9 @@ impl PlatformWithTokensActivationOps for TendermintCoin {
             activation_request.tx_history,
             priv_key_policy,
         )
-        .await
+        .await?;
+        coin.spawn_ibc_transfer_tracker();
+        Ok(coin)
     }
 
     fn try_from_mm_coin(coin: MmCoinEnum) -> Option<Self>
@@ -241,6 +265,8 @@ impl PlatformWithTokensActivationOps for TendermintCoin {
                         .map(|t| t.ticker)
                         .collect(),
                 ),
+                wallet_balance: None,
+                addresses_tokens_balances: None,
             });
         }
 
@@ -249,6 +275,29 @@ impl PlatformWithTokensActivationOps for TendermintCoin {
             kind: TendermintInitErrorKind::RpcError(e.to_string()),
         })?;
 
+        let (wallet_balance, addresses_tokens_balances) = if self.has_hd_wallet_derivation_method() {
+            let wallet_balance = self
+                .enable_wallet_balance(activation_request.enable_params.clone())
+                .await
+                .mm_err(|e| TendermintInitError {
+                    ticker: self.ticker().to_owned(),
+                    kind: TendermintInitErrorKind::HDWalletBalanceError(e.to_string()),
+                })?;
+            let addresses_tokens_balances = self
+                .addresses_tokens_balances(&wallet_balance)
+                .await
+                .mm_err(|e| TendermintInitError {
+                    ticker: self.ticker().to_owned(),
+                    kind: TendermintInitErrorKind::RpcError(e.to_string()),
+                })?
+                .into_iter()
+                .map(|(address, tokens_balances)| (address, spendable_balances(tokens_balances)))
+                .collect();
+            (Some(wallet_balance), Some(addresses_tokens_balances))
+        } else {
+            (None, None)
+        };
+
         Ok(TendermintActivationResult {
             address: self.account_id.to_string(),
             current_block,
@@ -256,20 +305,11 @@ impl PlatformWithTokensActivationOps for TendermintCoin {
                 spendable: balances.platform_balance,
                 unspendable: BigDecimal::default(),
             }),
-            tokens_balances: Some(
-                balances
-                    .tokens_balances
-                    .into_iter()
-                    .map(|(ticker, balance)| {
-                        (ticker, CoinBalance {
-                            spendable: balance,
-                            unspendable: BigDecimal::default(),
-                        })
-                    })
-                    .collect(),
-            ),
+            tokens_balances: Some(spendable_balances(balances.tokens_balances)),
             ticker: self.ticker().to_owned(),
             tokens_tickers: None,
+            wallet_balance,
+            addresses_tokens_balances,
         })
     }
 
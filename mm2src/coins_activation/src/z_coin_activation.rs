@@ -4,7 +4,7 @@ use crate::standalone_coin::{InitStandaloneCoinActivationOps, InitStandaloneCoin
                              InitStandaloneCoinInitialStatus, InitStandaloneCoinTaskHandle,
                              InitStandaloneCoinTaskManagerShared};
 use async_trait::async_trait;
-use coins::coin_balance::{CoinBalanceReport, IguanaWalletBalance};
+use coins::coin_balance::{CoinBalanceReport, HDWalletBalance, IguanaWalletBalance};
 use coins::my_tx_history_v2::TxHistoryStorage;
 use coins::tx_history_storage::CreateTxHistoryStorageError;
 use coins::z_coin::{z_coin_from_conf_and_params, BlockchainScanStopped, FirstSyncBlock, SyncStatus, ZCoin,
@@ -286,7 +286,19 @@ impl InitStandaloneCoinActivationOps for ZCoin {
             .await
             .map_to_mm(ZcoinInitError::CouldNotGetBlockCount)?;
 
-        let balance = self.my_balance().compat().await?;
+        // Additional ZIP32 accounts are reported the same way as the accounts of an HD wallet.
+        let wallet_balance = if self.z_accounts().len() > 1 {
+            let accounts = self
+                .z_accounts_balance()
+                .await
+                .mm_err(|e| ZcoinInitError::CouldNotGetBalance(e.to_string()))?;
+            CoinBalanceReport::HD(HDWalletBalance { accounts })
+        } else {
+            CoinBalanceReport::Iguana(IguanaWalletBalance {
+                address: self.my_z_address_encoded(),
+                balance: self.my_balance().compat().await?,
+            })
+        };
         let first_sync_block = match self.sync_status().await? {
             SyncStatus::Finished { first_sync_block, .. }
             | SyncStatus::BuildingWalletDb { first_sync_block, .. }
@@ -297,10 +309,7 @@ impl InitStandaloneCoinActivationOps for ZCoin {
         Ok(ZcoinActivationResult {
             ticker: self.ticker().into(),
             current_block,
-            wallet_balance: CoinBalanceReport::Iguana(IguanaWalletBalance {
-                address: self.my_z_address_encoded(),
-                balance,
-            }),
+            wallet_balance,
             first_sync_block,
         })
     }
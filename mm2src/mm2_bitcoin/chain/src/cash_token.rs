@@ -0,0 +1,239 @@
+//! Bitcoin Cash CashTokens output prefix.
+//! https://github.com/cashtokens/cashtokens#token-encoding
+
+use bytes::Bytes;
+use hash::H256;
+use ser::{serialize, CompactInteger, Deserializable, Error, Reader, Serializable, Stream};
+use std::io;
+use std::io::Read;
+
+/// The first byte of the `locking bytecode` field of an output that carries tokens.
+pub const PREFIX_TOKEN: u8 = 0xef;
+/// The maximum length of an NFT commitment.
+pub const MAX_COMMITMENT_LENGTH: usize = 40;
+
+const RESERVED_BIT: u8 = 0x80;
+const HAS_COMMITMENT_LENGTH: u8 = 0x40;
+const HAS_NFT: u8 = 0x20;
+const HAS_AMOUNT: u8 = 0x10;
+const CAPABILITY_MASK: u8 = 0x0f;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NftCapability {
+    /// Immutable NFT.
+    None,
+    /// The commitment can be changed by the spending transaction.
+    Mutable,
+    /// New NFTs of the category can be created by the spending transaction.
+    Minting,
+}
+
+impl NftCapability {
+    fn from_bits(bits: u8) -> Option<NftCapability> {
+        match bits {
+            0 => Some(NftCapability::None),
+            1 => Some(NftCapability::Mutable),
+            2 => Some(NftCapability::Minting),
+            _ => None,
+        }
+    }
+
+    fn bits(&self) -> u8 {
+        match self {
+            NftCapability::None => 0,
+            NftCapability::Mutable => 1,
+            NftCapability::Minting => 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CashTokenNft {
+    pub capability: NftCapability,
+    /// Empty if the NFT has no commitment.
+    pub commitment: Bytes,
+}
+
+/// Tokens carried by a transaction output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CashTokenData {
+    /// The category id in the transaction hash (internal) byte order, i.e. the same order as `OutPoint::hash`.
+    pub category: H256,
+    /// The fungible token amount, 0 if the output carries no fungible tokens.
+    pub amount: u64,
+    pub nft: Option<CashTokenNft>,
+}
+
+impl CashTokenData {
+    /// Splits the `locking bytecode` field of an output into the token prefix and the actual `script_pubkey`.
+    /// Returns `None` if the field has no token prefix or the prefix is invalid or not minimally encoded,
+    /// in which case the whole field should be treated as the `script_pubkey`.
+    pub fn split_prefixed_script(locking_bytecode: &[u8]) -> Option<(CashTokenData, Bytes)> {
+        if locking_bytecode.first() != Some(&PREFIX_TOKEN) {
+            return None;
+        }
+
+        let mut reader = Reader::new(locking_bytecode);
+        let token: CashTokenData = reader.read().ok()?;
+        let mut script = Vec::new();
+        reader.read_to_end(&mut script).ok()?;
+
+        let prefix = serialize(&token);
+        if prefix.len() + script.len() != locking_bytecode.len()
+            || prefix.as_slice() != &locking_bytecode[..prefix.len()]
+        {
+            return None;
+        }
+        Some((token, script.into()))
+    }
+
+    fn bitfield(&self) -> u8 {
+        let mut bitfield = 0;
+        if let Some(nft) = &self.nft {
+            bitfield |= HAS_NFT | nft.capability.bits();
+            if !nft.commitment.is_empty() {
+                bitfield |= HAS_COMMITMENT_LENGTH;
+            }
+        }
+        if self.amount > 0 {
+            bitfield |= HAS_AMOUNT;
+        }
+        bitfield
+    }
+}
+
+impl Serializable for CashTokenData {
+    fn serialize(&self, stream: &mut Stream) {
+        stream
+            .append(&PREFIX_TOKEN)
+            .append(&self.category)
+            .append(&self.bitfield());
+        if let Some(nft) = &self.nft {
+            if !nft.commitment.is_empty() {
+                stream.append(&nft.commitment);
+            }
+        }
+        if self.amount > 0 {
+            stream.append(&CompactInteger::from(self.amount));
+        }
+    }
+}
+
+impl Deserializable for CashTokenData {
+    fn deserialize<T>(reader: &mut Reader<T>) -> Result<Self, Error>
+    where
+        Self: Sized,
+        T: io::Read,
+    {
+        let prefix: u8 = reader.read()?;
+        if prefix != PREFIX_TOKEN {
+            return Err(Error::MalformedData);
+        }
+        let category: H256 = reader.read()?;
+        let bitfield: u8 = reader.read()?;
+
+        let has_commitment = bitfield & HAS_COMMITMENT_LENGTH != 0;
+        let has_nft = bitfield & HAS_NFT != 0;
+        let has_amount = bitfield & HAS_AMOUNT != 0;
+        let capability = NftCapability::from_bits(bitfield & CAPABILITY_MASK).ok_or(Error::MalformedData)?;
+        if bitfield & RESERVED_BIT != 0
+            || (!has_nft && (has_commitment || capability != NftCapability::None))
+            || (!has_nft && !has_amount)
+        {
+            return Err(Error::MalformedData);
+        }
+
+        let commitment: Bytes = if has_commitment { reader.read()? } else { Bytes::new() };
+        if has_commitment && (commitment.is_empty() || commitment.len() > MAX_COMMITMENT_LENGTH) {
+            return Err(Error::MalformedData);
+        }
+
+        let amount = if has_amount {
+            let amount: u64 = reader.read::<CompactInteger>()?.into();
+            if amount == 0 || amount > i64::MAX as u64 {
+                return Err(Error::MalformedData);
+            }
+            amount
+        } else {
+            0
+        };
+
+        let nft = if has_nft {
+            Some(CashTokenNft { capability, commitment })
+        } else {
+            None
+        };
+        Ok(CashTokenData { category, amount, nft })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P2PKH_SCRIPT: &str = "76a914404371705fa9bd789a2fcd52d2c580b65d35549d88ac";
+
+    fn category() -> H256 { H256::from([0xbb; 32]) }
+
+    #[test]
+    fn test_split_prefixed_script_fungible() {
+        let field: Bytes = format!("ef{}10fd0001{}", "bb".repeat(32), P2PKH_SCRIPT)
+            .as_str()
+            .parse()
+            .unwrap();
+        let (token, script) = CashTokenData::split_prefixed_script(&field).unwrap();
+        let expected = CashTokenData {
+            category: category(),
+            amount: 256,
+            nft: None,
+        };
+        assert_eq!(token, expected);
+        assert_eq!(script, P2PKH_SCRIPT.into());
+    }
+
+    #[test]
+    fn test_split_prefixed_script_nft_with_commitment() {
+        let field: Bytes = format!("ef{}7202cafe01{}", "bb".repeat(32), P2PKH_SCRIPT)
+            .as_str()
+            .parse()
+            .unwrap();
+        let (token, script) = CashTokenData::split_prefixed_script(&field).unwrap();
+        let expected = CashTokenData {
+            category: category(),
+            amount: 1,
+            nft: Some(CashTokenNft {
+                capability: NftCapability::Minting,
+                commitment: "cafe".into(),
+            }),
+        };
+        assert_eq!(token, expected);
+        assert_eq!(script, P2PKH_SCRIPT.into());
+        assert_eq!(serialize(&token), field.as_slice()[..38].into());
+    }
+
+    #[test]
+    fn test_split_prefixed_script_invalid() {
+        let invalid_prefixes = [
+            // no token prefix at all
+            P2PKH_SCRIPT.to_owned(),
+            // reserved bit set
+            format!("ef{}b001", "bb".repeat(32)),
+            // neither NFT nor amount
+            format!("ef{}00", "bb".repeat(32)),
+            // capability without NFT
+            format!("ef{}1101", "bb".repeat(32)),
+            // commitment without NFT
+            format!("ef{}5001aa01", "bb".repeat(32)),
+            // invalid capability
+            format!("ef{}23", "bb".repeat(32)),
+            // zero amount
+            format!("ef{}1000", "bb".repeat(32)),
+            // non-minimal amount encoding
+            format!("ef{}10fd0100", "bb".repeat(32)),
+        ];
+        for prefix in invalid_prefixes.iter() {
+            let field: Bytes = format!("{}{}", prefix, P2PKH_SCRIPT).as_str().parse().unwrap();
+            assert_eq!(CashTokenData::split_prefixed_script(&field), None, "{}", prefix);
+        }
+    }
+}
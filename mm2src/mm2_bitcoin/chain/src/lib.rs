@@ -10,6 +10,7 @@ pub mod constants;
 
 mod block;
 mod block_header;
+mod cash_token;
 mod merkle_root;
 mod raw_block;
 pub use raw_block::{RawBlockHeader, RawHeaderError};
@@ -26,6 +27,7 @@ pub use primitives::{bytes, compact, hash, U256};
 
 pub use block::Block;
 pub use block_header::{BlockHeader, BlockHeaderBits, BlockHeaderNonce};
+pub use cash_token::{CashTokenData, CashTokenNft, NftCapability, MAX_COMMITMENT_LENGTH, PREFIX_TOKEN};
 pub use merkle_root::{merkle_node_hash, merkle_root};
 pub use transaction::{JoinSplit, OutPoint, ShieldedOutput, ShieldedSpend, Transaction, TransactionInput,
                       TransactionOutput, TxHashAlgo};
@@ -2,6 +2,7 @@
 //! https://en.bitcoin.it/wiki/Protocol_documentation#tx
 
 use bytes::Bytes;
+use cash_token::CashTokenData;
 use constants::{LOCKTIME_THRESHOLD, SEQUENCE_FINAL};
 use crypto::{dhash256, sha256};
 #[cfg(not(target_arch = "wasm32"))]
@@ -87,10 +88,12 @@ impl From<TransactionInput> for TxIn {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Serializable, Deserializable)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TransactionOutput {
     pub value: u64,
     pub script_pubkey: Bytes,
+    /// BCH CashTokens carried by the output. Serialized as a prefix of the `script_pubkey` field.
+    pub token: Option<CashTokenData>,
 }
 
 impl Default for TransactionOutput {
@@ -98,6 +101,7 @@ impl Default for TransactionOutput {
         TransactionOutput {
             value: 0xffffffffffffffffu64,
             script_pubkey: Bytes::default(),
+            token: None,
         }
     }
 }
@@ -359,6 +363,51 @@ impl Deserializable for TransactionInput {
     }
 }
 
+impl Serializable for TransactionOutput {
+    fn serialize(&self, stream: &mut Stream) {
+        stream.append(&self.value);
+        match &self.token {
+            Some(token) => {
+                let prefix = serialize(token);
+                stream
+                    .append(&CompactInteger::from(prefix.len() + self.script_pubkey.len()))
+                    .append_slice(&prefix)
+                    .append_slice(&self.script_pubkey);
+            },
+            None => {
+                stream.append(&self.script_pubkey);
+            },
+        }
+    }
+}
+
+impl Deserializable for TransactionOutput {
+    fn deserialize<T>(reader: &mut Reader<T>) -> Result<Self, Error>
+    where
+        Self: Sized,
+        T: io::Read,
+    {
+        let value = reader.read()?;
+        let locking_bytecode: Bytes = reader.read()?;
+        // The token prefix is a part of the `script_pubkey` on the other chains.
+        // Outputs with an invalid token prefix are unspendable, keep them as is to preserve the tx hash.
+        let split = if reader.coin_variant().is_bch() {
+            CashTokenData::split_prefixed_script(&locking_bytecode)
+        } else {
+            None
+        };
+        let (token, script_pubkey) = match split {
+            Some((token, script_pubkey)) => (Some(token), script_pubkey),
+            None => (None, locking_bytecode),
+        };
+        Ok(TransactionOutput {
+            value,
+            script_pubkey,
+            token,
+        })
+    }
+}
+
 impl Serializable for Transaction {
     fn serialize(&self, stream: &mut Stream) {
         let include_transaction_witness = stream.include_transaction_witness() && self.has_witness();
@@ -581,27 +630,31 @@ impl Deserializable for Transaction {
         // it works properly only when buffer contains only 1 transaction bytes
         // it breaks block serialization, but block serialization is not required for AtomicDEX
         // specific use case
+        let coin_variant = *reader.coin_variant();
         let mut buffer = vec![];
         reader.read_to_end(&mut buffer)?;
-        if let Ok(t) = deserialize_tx(&mut Reader::from_read(buffer.as_slice()), TxType::PosvWithNTime) {
+        let tx_reader = || Reader::new_with_coin_variant(buffer.as_slice(), coin_variant);
+        if let Ok(t) = deserialize_tx(&mut tx_reader(), TxType::PosvWithNTime) {
             return Ok(t);
         }
-        if let Ok(t) = deserialize_tx(&mut Reader::from_read(buffer.as_slice()), TxType::StandardWithWitness) {
+        if let Ok(t) = deserialize_tx(&mut tx_reader(), TxType::StandardWithWitness) {
             return Ok(t);
         }
-        if let Ok(t) = deserialize_tx(&mut Reader::from_read(buffer.as_slice()), TxType::PosWithNTime) {
+        if let Ok(t) = deserialize_tx(&mut tx_reader(), TxType::PosWithNTime) {
             return Ok(t);
         }
-        deserialize_tx(&mut Reader::from_read(buffer.as_slice()), TxType::Zcash)
+        deserialize_tx(&mut tx_reader(), TxType::Zcash)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Bytes, ExtTransaction, OutPoint, Transaction, TransactionInput, TransactionOutput};
+    use super::{Bytes, CashTokenData, ExtTransaction, OutPoint, Transaction, TransactionInput, TransactionOutput};
+    use cash_token::{CashTokenNft, NftCapability};
     use hash::{H256, H512};
     use hex::ToHex;
-    use ser::{deserialize, serialize, serialize_with_flags, Serializable, SERIALIZE_TRANSACTION_WITNESS};
+    use ser::{deserialize, deserialize_with_coin_variant, serialize, serialize_with_flags, CoinVariant, Serializable,
+              SERIALIZE_TRANSACTION_WITNESS};
     use TxHashAlgo;
 
     // real transaction from block 80000
@@ -626,6 +679,36 @@ mod tests {
         assert!(!t.has_witness());
     }
 
+    #[test]
+    fn test_cash_token_prefix_parsed_for_bch_only() {
+        let mut tx: Transaction = "0100000001a6b97044d03da79c005b20ea9c0e1a6d9dc12d9f7b91a5911c9030a439eed8f5000000004948304502206e21798a42fae0e854281abd38bacd1aeed3ee3738d9e1446618c4571d1090db022100e2ac980643b0b82c0e88ffdfec6b64e3e6ba35e7ba5fdd7d5d6cc8d25c6b241501ffffffff0100f2052a010000001976a914404371705fa9bd789a2fcd52d2c580b65d35549d88ac00000000".into();
+        let token = CashTokenData {
+            category: H256::from([0xbb; 32]),
+            amount: 1000,
+            nft: Some(CashTokenNft {
+                capability: NftCapability::Mutable,
+                commitment: "cafe".into(),
+            }),
+        };
+        tx.outputs[0].token = Some(token.clone());
+        let raw = serialize(&tx);
+
+        let bch_tx: Transaction = deserialize_with_coin_variant(raw.as_slice(), CoinVariant::BCH).unwrap();
+        assert_eq!(bch_tx.outputs[0].token, Some(token));
+        assert_eq!(bch_tx.outputs[0].script_pubkey, tx.outputs[0].script_pubkey);
+        assert_eq!(serialize(&bch_tx), raw);
+
+        // The prefix is kept as a part of the `script_pubkey` on the other chains.
+        let other_tx: Transaction = deserialize(raw.as_slice()).unwrap();
+        assert_eq!(other_tx.outputs[0].token, None);
+        assert!(other_tx.outputs[0]
+            .script_pubkey
+            .ends_with(&tx.outputs[0].script_pubkey));
+        assert_ne!(other_tx.outputs[0].script_pubkey, tx.outputs[0].script_pubkey);
+        assert_eq!(serialize(&other_tx), raw);
+        assert_eq!(other_tx.hash(), bch_tx.hash());
+    }
+
     #[test]
     fn test_transaction_reader_v7() {
         let raw = "0700000001f87575693f4c038018628ff89f64571f0b9b48cd91a09b984d7eb018f4753bfa000000006a47304402202a3c612b11db1be51ae47fc1c23cc73e7fb14f08f10b3e71e5778d7adad494e90220636ca2580324452d8596cea7b2ebc31d796787108a7f74b676e3f136cb2c56b9012102e75e70baceb8cd5ae2bdc893d018512aafc8aac403ae8c14da66fa3ede87fcc3ffffffff0148b6eb0b000000001976a914139df01a608671fcf24db66d2d02bf2d4274e1f888ac00000000";
@@ -871,9 +954,11 @@ mod tests {
 			outputs: vec![TransactionOutput {
 				value: 0x0000000006b22c20,
 				script_pubkey: "76a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac".into(),
+				token: None,
 			}, TransactionOutput {
 				value: 0x000000000d519390,
 				script_pubkey: "76a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac".into(),
+				token: None,
 			}],
 			lock_time: 0x00000011,
 			zcash: false,
@@ -1054,12 +1139,15 @@ mod tests {
 			outputs: vec![TransactionOutput {
 				value: 275389709,
 				script_pubkey: "a91439352bc650acd5e1dfbf109841c1a97824e8b85887".into(),
+				token: None,
 			}, TransactionOutput {
 				value: 0,
 				script_pubkey: "6a14e1fdcb31df41211da0325bb6dce577b856588b78".into(),
+				token: None,
 			}, TransactionOutput {
 				value: 38721660,
 				script_pubkey: "76a914c3f710deb7320b0efa6edb14e3ebeeb9155fa90d88ac".into(),
+				token: None,
 			}],
 			lock_time: 1632875267,
 			zcash: false,
@@ -2,8 +2,8 @@
 
 use blake2b_simd::Params as Blake2b;
 use bytes::Bytes;
-use chain::{JoinSplit, OutPoint, ShieldedOutput, ShieldedSpend, Transaction, TransactionInput, TransactionOutput,
-            TxHashAlgo};
+use chain::{CashTokenData, JoinSplit, OutPoint, ShieldedOutput, ShieldedSpend, Transaction, TransactionInput,
+            TransactionOutput, TxHashAlgo};
 use crypto::{dhash256, sha256};
 use hash::{H256, H512};
 use keys::KeyPair;
@@ -104,6 +104,8 @@ pub struct UnsignedTransactionInput {
     pub sequence: u32,
     pub amount: u64,
     pub witness: Vec<Vec<u8>>,
+    /// BCH CashTokens carried by the spent output, committed to by the `ForkId` signature hash.
+    pub token: Option<CashTokenData>,
 }
 
 /// Used for resigning and loading test transactions
@@ -114,6 +116,7 @@ impl From<TransactionInput> for UnsignedTransactionInput {
             sequence: i.sequence,
             amount: 0,
             witness: i.script_witness.into_iter().map(Vec::from).collect(),
+            token: None,
         }
     }
 }
@@ -399,6 +402,9 @@ impl TransactionInputSigner {
         stream.append(&hash_prevouts);
         stream.append(&hash_sequence);
         stream.append(&self.inputs[input_index].previous_output);
+        if let Some(token) = &self.inputs[input_index].token {
+            stream.append(token);
+        }
         stream.append_list(script_pubkey);
         stream.append(&input_amount);
         stream.append(&self.inputs[input_index].sequence);
@@ -663,11 +669,13 @@ mod tests {
             },
             amount: 0,
             witness: vec![Vec::new()],
+            token: None,
         };
 
         let output = TransactionOutput {
             value,
             script_pubkey: current_output,
+            token: None,
         };
 
         let input_signer = TransactionInputSigner {
@@ -715,11 +723,13 @@ mod tests {
             },
             amount: 100,
             witness: vec![Vec::new()],
+            token: None,
         };
 
         let output = TransactionOutput {
             value,
             script_pubkey: current_output,
+            token: None,
         };
 
         let input_signer = TransactionInputSigner {
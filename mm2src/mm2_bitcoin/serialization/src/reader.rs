@@ -17,6 +17,21 @@ where
     }
 }
 
+/// Same as [`deserialize`], but the structures are read according to the `coin_variant` specifics.
+pub fn deserialize_with_coin_variant<T>(buffer: &[u8], coin_variant: CoinVariant) -> Result<T, Error>
+where
+    T: Deserializable,
+{
+    let mut reader = Reader::new_with_coin_variant(buffer, coin_variant);
+    let result = reader.read()?;
+
+    if reader.is_finished() {
+        Ok(result)
+    } else {
+        Err(Error::UnreadData)
+    }
+}
+
 pub fn deserialize_iterator<R, T>(buffer: R) -> ReadIterator<R, T>
 where
     R: io::Read,
@@ -49,7 +64,7 @@ pub trait Deserializable {
         T: io::Read;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum CoinVariant {
     // Todo: https://github.com/KomodoPlatform/atomicDEX-API/issues/1345
     BTC,
@@ -62,6 +77,8 @@ pub enum CoinVariant {
     RICK,
     /// Same reason as RICK.
     MORTY,
+    /// Needed to parse the CashTokens prefix of the transaction outputs which is valid on Bitcoin Cash only.
+    BCH,
 }
 
 impl CoinVariant {
@@ -70,6 +87,7 @@ impl CoinVariant {
     pub fn is_lbc(&self) -> bool { matches!(self, CoinVariant::LBC) }
     pub fn is_ppc(&self) -> bool { matches!(self, CoinVariant::PPC) }
     pub fn is_kmd_assetchain(&self) -> bool { matches!(self, CoinVariant::RICK | CoinVariant::MORTY) }
+    pub fn is_bch(&self) -> bool { matches!(self, CoinVariant::BCH) }
 }
 
 fn ticker_matches(ticker: &str, with: &str) -> bool {
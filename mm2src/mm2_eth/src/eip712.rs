@@ -75,17 +75,32 @@ impl ObjectType {
         self.properties.push(property);
         self
     }
+
+    /// Describes an array property of a dynamic length.
+    pub fn property_array(&mut self, property_name: &str, item_type: PropertyType) -> &mut ObjectType {
+        self.property(property_name, PropertyType::Array(Box::new(item_type), None))
+    }
 }
 
-/// Add `Int64`, `Uint64`, `Int256`, `Array` types if required.
+/// Property types supported by the EIP-712 standard:
 /// https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md#definition-of-typed-structured-data-%F0%9D%95%8A
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PropertyType {
     Bool,
     String,
     Uint256,
     Address,
     Bytes32,
+    /// `uint8` up to `uint248`, `uint256` is represented by [`PropertyType::Uint256`].
+    Uint(usize),
+    /// `int8` up to `int256`.
+    Int(usize),
+    /// `bytes1` up to `bytes31`, `bytes32` is represented by [`PropertyType::Bytes32`].
+    FixedBytes(usize),
+    /// Dynamic `bytes`.
+    Bytes,
+    /// `T[]` if the length is `None`, otherwise `T[N]`.
+    Array(Box<PropertyType>, Option<usize>),
     Custom(String),
 }
 
@@ -97,6 +112,12 @@ impl fmt::Display for PropertyType {
             PropertyType::Uint256 => write!(f, "uint256"),
             PropertyType::Address => write!(f, "address"),
             PropertyType::Bytes32 => write!(f, "bytes32"),
+            PropertyType::Uint(bits) => write!(f, "uint{bits}"),
+            PropertyType::Int(bits) => write!(f, "int{bits}"),
+            PropertyType::FixedBytes(len) => write!(f, "bytes{len}"),
+            PropertyType::Bytes => write!(f, "bytes"),
+            PropertyType::Array(item, Some(len)) => write!(f, "{item}[{len}]"),
+            PropertyType::Array(item, None) => write!(f, "{item}[]"),
             PropertyType::Custom(custom) => write!(f, "{custom}"),
         }
     }
@@ -106,18 +127,57 @@ impl FromStr for PropertyType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(without_bracket) = s.strip_suffix(']') {
+            let open_bracket = without_bracket
+                .rfind('[')
+                .ok_or_else(|| format!("Invalid array type '{s}'"))?;
+            let item = PropertyType::from_str(&without_bracket[..open_bracket])?;
+            let len = match &without_bracket[open_bracket + 1..] {
+                "" => None,
+                len => Some(
+                    len.parse::<usize>()
+                        .map_err(|e| format!("Invalid array length in '{s}': {e}"))?,
+                ),
+            };
+            return Ok(PropertyType::Array(Box::new(item), len));
+        }
+
         let property_type = match s {
             "bool" => PropertyType::Bool,
             "string" => PropertyType::String,
-            "uint256" => PropertyType::Uint256,
+            "uint256" | "uint" => PropertyType::Uint256,
+            "int" => PropertyType::Int(256),
             "address" => PropertyType::Address,
             "bytes32" => PropertyType::Bytes32,
-            custom => PropertyType::Custom(custom.to_string()),
+            "bytes" => PropertyType::Bytes,
+            other => {
+                if let Some(bits) = other.strip_prefix("uint").and_then(parse_int_bits) {
+                    PropertyType::Uint(bits)
+                } else if let Some(bits) = other.strip_prefix("int").and_then(parse_int_bits) {
+                    PropertyType::Int(bits)
+                } else if let Some(len) = other.strip_prefix("bytes").and_then(parse_bytes_len) {
+                    PropertyType::FixedBytes(len)
+                } else {
+                    PropertyType::Custom(other.to_string())
+                }
+            },
         };
         Ok(property_type)
     }
 }
 
+/// Parses the bit size of `uintN`/`intN` types where N is a multiple of 8 in the range `8..=256`.
+fn parse_int_bits(bits: &str) -> Option<usize> {
+    let bits = bits.parse::<usize>().ok()?;
+    (bits > 0 && bits <= 256 && bits % 8 == 0).then_some(bits)
+}
+
+/// Parses the length of `bytesN` types where N is in the range `1..=32`.
+fn parse_bytes_len(len: &str) -> Option<usize> {
+    let len = len.parse::<usize>().ok()?;
+    (len > 0 && len <= 32).then_some(len)
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ObjectProperty {
     pub(crate) name: String,
@@ -1,6 +1,6 @@
 //! Inspired by https://github.com/openethereum/parity-ethereum/blob/v2.7.2-stable/util/EIP-712/src/encode.rs
 
-use crate::eip712::{CustomTypes, Eip712, PropertyType, EIP712_DOMAIN};
+use crate::eip712::{CustomTypes, Eip712, ObjectProperty, PropertyType, EIP712_DOMAIN};
 use ethabi::{encode, Token};
 use indexmap::IndexSet;
 use itertools::Itertools;
@@ -24,16 +24,33 @@ where
     hash_typed_data_raw(data_raw)
 }
 
-fn hash_typed_data_raw(data: Eip712Raw) -> Result<H256> {
-    /// EIP-191 compliant.
-    const PREFIX: &[u8; 2] = b"\x19\x01";
+/// Hashes EIP-712 typed data given in the standard JSON format as accepted by `eth_signTypedData_v4`.
+/// The `EIP712Domain` type is derived from the `domain` fields if it's not specified in `types`.
+pub fn hash_typed_data_json(typed_data: Json) -> Result<H256> {
+    let data_raw = Eip712Raw::from_json(typed_data)?;
+    hash_typed_data_raw(data_raw)
+}
 
-    let domain_hash = encode_data(
+/// Calculates the EIP-712 domain separator of the typed data given in the standard JSON format.
+pub fn domain_separator_json(typed_data: Json) -> Result<H256> {
+    let data_raw = Eip712Raw::from_json(typed_data)?;
+    domain_separator(&data_raw).map(|hash| H256::from_slice(&hash))
+}
+
+fn domain_separator(data: &Eip712Raw) -> Result<H256Bytes> {
+    encode_data(
         &data.types,
         PropertyType::Custom(EIP712_DOMAIN.to_string()),
         &data.domain,
         None,
-    )?;
+    )
+}
+
+fn hash_typed_data_raw(data: Eip712Raw) -> Result<H256> {
+    /// EIP-191 compliant.
+    const PREFIX: &[u8; 2] = b"\x19\x01";
+
+    let domain_hash = domain_separator(&data)?;
     let data_hash = encode_data(
         &data.types,
         PropertyType::Custom(data.primary_type.clone()),
@@ -54,6 +71,42 @@ struct Eip712Raw {
     message: Json,
 }
 
+impl Eip712Raw {
+    fn from_json(typed_data: Json) -> Result<Eip712Raw> {
+        let mut data_raw: Eip712Raw = serde_json::from_value(typed_data)?;
+        if !data_raw.types.contains_key(EIP712_DOMAIN) {
+            let domain_type = domain_type_from_fields(&data_raw.domain)?;
+            data_raw.types.insert(EIP712_DOMAIN.to_string(), domain_type);
+        }
+        Ok(data_raw)
+    }
+}
+
+/// Builds the `EIP712Domain` type from the fields that are set in the `domain`.
+/// The fields are ordered as required by the standard:
+/// https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md#definition-of-domainseparator
+fn domain_type_from_fields(domain: &Json) -> Result<Vec<ObjectProperty>> {
+    const DOMAIN_FIELDS: [(&str, PropertyType); 5] = [
+        ("name", PropertyType::String),
+        ("version", PropertyType::String),
+        ("chainId", PropertyType::Uint256),
+        ("verifyingContract", PropertyType::Address),
+        ("salt", PropertyType::Bytes32),
+    ];
+
+    let domain_obj = domain
+        .as_object()
+        .ok_or_else(|| expected_type_error("object", domain, Some(EIP712_DOMAIN)))?;
+    Ok(DOMAIN_FIELDS
+        .iter()
+        .filter(|(name, _)| domain_obj.contains_key(*name))
+        .map(|(name, property_type)| ObjectProperty {
+            name: name.to_string(),
+            property_type: property_type.to_string(),
+        })
+        .collect())
+}
+
 impl<Domain, SignData> TryFrom<Eip712<Domain, SignData>> for Eip712Raw
 where
     Domain: Serialize,
@@ -82,11 +135,43 @@ fn encode_data(
         PropertyType::String => encode_string(data, field_name),
         PropertyType::Uint256 => encode_u256(data, field_name),
         PropertyType::Address => encode_address(data, field_name),
-        PropertyType::Bytes32 => encode_bytes32(data, field_name),
+        PropertyType::Bytes32 => encode_fixed_bytes(data, 32, field_name),
+        PropertyType::Uint(bits) => encode_uint(data, bits, field_name),
+        PropertyType::Int(bits) => encode_int(data, bits, field_name),
+        PropertyType::FixedBytes(len) => encode_fixed_bytes(data, len, field_name),
+        PropertyType::Bytes => encode_bytes(data, field_name),
+        PropertyType::Array(item_type, len) => encode_array(custom_types, *item_type, len, data, field_name),
         PropertyType::Custom(custom) => encode_custom(custom_types, &custom, data, field_name),
     }
 }
 
+/// Arrays are encoded as the `keccak256` hash of the concatenated encodings of their items.
+fn encode_array(
+    custom_types: &CustomTypes,
+    item_type: PropertyType,
+    len: Option<usize>,
+    data: &Json,
+    field_name: Option<&str>,
+) -> Result<Vec<u8>> {
+    let items = data
+        .as_array()
+        .ok_or_else(|| expected_type_error("array", data, field_name))?;
+    if let Some(len) = len {
+        if items.len() != len {
+            let error = format!("Expected an array of {len} items, found {}", items.len());
+            return Err(decode_error(error, field_name));
+        }
+    }
+
+    let mut encoded_items = Vec::with_capacity(items.len() * 32);
+    for item in items {
+        let mut encoded = encode_data(custom_types, item_type.clone(), item, field_name)?;
+        encoded_items.append(&mut encoded);
+    }
+
+    Ok(encode(&[Token::FixedBytes(keccak256(&encoded_items).to_vec())]))
+}
+
 fn encode_custom(
     custom_types: &CustomTypes,
     data_ident: &str,
@@ -110,18 +195,34 @@ fn encode_custom(
     Ok(keccak256(&encoded_tokens).as_ref().to_vec())
 }
 
-fn encode_bytes32(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
-    let string = value
-        .as_str()
-        .ok_or_else(|| expected_type_error("bytes32", value, field_name))?;
-    check_hex(string, field_name)?;
+/// `bytes1` up to `bytes32` values are encoded as is, right-padded to 32 bytes.
+fn encode_fixed_bytes(value: &Json, len: usize, field_name: Option<&str>) -> Result<Vec<u8>> {
+    let bytes = decode_hex_value(value, field_name)?;
+    if bytes.len() != len {
+        let error = format!("Expected {len} bytes, found {}", bytes.len());
+        return Err(decode_error(error, field_name));
+    }
+
+    Ok(encode(&[Token::FixedBytes(bytes)]))
+}
 
-    let bytes = hex::decode(string).map_err(|e| decode_error(e, field_name))?;
+/// Dynamic `bytes` values are encoded as the `keccak256` hash of the content.
+fn encode_bytes(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
+    let bytes = decode_hex_value(value, field_name)?;
     let hash = keccak256(&bytes).to_vec();
 
     Ok(encode(&[Token::FixedBytes(hash)]))
 }
 
+fn decode_hex_value(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
+    let string = value
+        .as_str()
+        .ok_or_else(|| expected_type_error("bytes", value, field_name))?;
+    check_hex(string, field_name)?;
+
+    hex::decode(&string[2..]).map_err(|e| decode_error(e, field_name))
+}
+
 fn encode_string(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
     let string = value
         .as_str()
@@ -150,14 +251,68 @@ fn encode_address(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
     Ok(encode(&[Token::Address(address)]))
 }
 
-fn encode_u256(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> {
+fn encode_u256(value: &Json, field_name: Option<&str>) -> Result<Vec<u8>> { encode_uint(value, 256, field_name) }
+
+fn encode_uint(value: &Json, bits: usize, field_name: Option<&str>) -> Result<Vec<u8>> {
+    let (is_negative, uint) = parse_integer(value, field_name)?;
+    if is_negative {
+        return Err(decode_error("Expected a non-negative integer", field_name));
+    }
+    if uint.bits() > bits {
+        return Err(decode_error(
+            format!("The value doesn't fit into 'uint{bits}'"),
+            field_name,
+        ));
+    }
+
+    Ok(encode(&[Token::Uint(uint)]))
+}
+
+/// Signed integers are encoded as two's complement 256-bit numbers.
+fn encode_int(value: &Json, bits: usize, field_name: Option<&str>) -> Result<Vec<u8>> {
+    let (is_negative, abs) = parse_integer(value, field_name)?;
+    // The maximum absolute value is `2^(bits-1) - 1` for positive and `2^(bits-1)` for negative numbers.
+    let limit = U256::one() << (bits - 1);
+    let fits = if is_negative { abs <= limit } else { abs < limit };
+    if !fits {
+        return Err(decode_error(
+            format!("The value doesn't fit into 'int{bits}'"),
+            field_name,
+        ));
+    }
+
+    let int = if is_negative {
+        (!abs).overflowing_add(U256::one()).0
+    } else {
+        abs
+    };
+    Ok(encode(&[Token::Int(int)]))
+}
+
+/// Parses an integer given as a JSON number, a decimal string or a 0x-prefixed hex string.
+/// Returns whether the number is negative and its absolute value.
+fn parse_integer(value: &Json, field_name: Option<&str>) -> Result<(bool, U256)> {
+    if let Some(number) = value.as_u64() {
+        return Ok((false, U256::from(number)));
+    }
+    if let Some(number) = value.as_i64() {
+        return Ok((number < 0, U256::from(number.unsigned_abs())));
+    }
+
     let string = value
         .as_str()
-        .ok_or_else(|| expected_type_error("str(u256)", value, field_name))?;
-    check_hex(string, field_name)?;
+        .ok_or_else(|| expected_type_error("integer", value, field_name))?;
+    let (is_negative, abs_str) = match string.strip_prefix('-') {
+        Some(abs_str) => (true, abs_str),
+        None => (false, string),
+    };
+    let abs = match abs_str.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| e.to_string()),
+        None => U256::from_dec_str(abs_str).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| decode_error(e, field_name))?;
 
-    let uint = U256::from_str(&string[2..]).map_err(|e| decode_error(e, field_name))?;
-    Ok(encode(&[Token::Uint(uint)]))
+    Ok((is_negative && !abs.is_zero(), abs))
 }
 
 fn encode_type(custom_types: &CustomTypes, data_type: &str) -> Result<String> {
@@ -391,4 +546,163 @@ mod tests {
             "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
         );
     }
+
+    #[test]
+    fn test_hash_data_with_arrays() {
+        const JSON: &str = r#"{
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallets": [
+                        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+                        "0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+                    ]
+                },
+                "to": [
+                    {
+                        "name": "Bob",
+                        "wallets": [
+                            "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                            "0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+                            "0xB0B0b0b0b0b0B000000000000000000000000000"
+                        ]
+                    }
+                ],
+                "contents": "Hello, Bob!"
+            },
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Group": [
+                    { "name": "name", "type": "string" },
+                    { "name": "members", "type": "Person[]" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallets", "type": "address[]" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person[]" },
+                    { "name": "contents", "type": "string" }
+                ]
+            }
+        }"#;
+
+        let typed_data = serde_json::from_str::<Json>(JSON).expect("alas error!");
+        let hash = hash_typed_data_json(typed_data).expect("alas error!");
+        assert_eq!(
+            format!("{:02x}", hash),
+            "a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2",
+        );
+    }
+
+    #[test]
+    fn test_hash_data_without_domain_type() {
+        const JSON: &str = r#"{
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": "0x1",
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+                },
+                "to": {
+                    "name": "Bob",
+                    "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+                },
+                "contents": "Hello, Bob!"
+            },
+            "types": {
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            }
+        }"#;
+
+        let typed_data = serde_json::from_str::<Json>(JSON).expect("alas error!");
+        let hash = hash_typed_data_json(typed_data).expect("alas error!");
+        assert_eq!(
+            format!("{:02x}", hash),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+        );
+    }
+
+    #[test]
+    fn test_encode_integers() {
+        let hex = encode_uint(&Json::from("0xff"), 8, None).expect("alas error!");
+        let dec = encode_uint(&Json::from("255"), 8, None).expect("alas error!");
+        let number = encode_uint(&Json::from(255), 8, None).expect("alas error!");
+        assert_eq!(hex, dec);
+        assert_eq!(hex, number);
+        encode_uint(&Json::from(256), 8, None).expect_err("'uint8' overflow is expected");
+        encode_uint(&Json::from(-1), 256, None).expect_err("negative 'uint256' is expected to fail");
+
+        let minus_one = encode_int(&Json::from(-1), 8, None).expect("alas error!");
+        assert_eq!(minus_one, vec![0xff; 32]);
+        encode_int(&Json::from(-128), 8, None).expect("alas error!");
+        encode_int(&Json::from("-129"), 8, None).expect_err("'int8' overflow is expected");
+        encode_int(&Json::from(128), 8, None).expect_err("'int8' overflow is expected");
+    }
+
+    #[test]
+    fn test_encode_bytes() {
+        let value = Json::from("0x0102");
+        let mut expected = vec![1, 2];
+        expected.resize(32, 0);
+        assert_eq!(encode_fixed_bytes(&value, 2, None).expect("alas error!"), expected);
+        encode_fixed_bytes(&value, 3, None).expect_err("length mismatch is expected");
+
+        let expected = keccak256(&[1, 2]).to_vec();
+        assert_eq!(encode_bytes(&value, None).expect("alas error!"), expected);
+    }
+
+    #[test]
+    fn test_property_type_from_str() {
+        let types = [
+            ("uint256", PropertyType::Uint256),
+            ("uint64", PropertyType::Uint(64)),
+            ("int8", PropertyType::Int(8)),
+            ("bytes4", PropertyType::FixedBytes(4)),
+            ("bytes", PropertyType::Bytes),
+            (
+                "Person[]",
+                PropertyType::Array(Box::new(PropertyType::Custom("Person".to_string())), None),
+            ),
+            (
+                "uint8[2][]",
+                PropertyType::Array(
+                    Box::new(PropertyType::Array(Box::new(PropertyType::Uint(8)), Some(2))),
+                    None,
+                ),
+            ),
+        ];
+        for (type_str, expected) in types {
+            let actual = PropertyType::from_str(type_str).expect("alas error!");
+            assert_eq!(actual, expected);
+            assert_eq!(actual.to_string(), type_str);
+        }
+    }
 }
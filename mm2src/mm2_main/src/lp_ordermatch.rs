@@ -24,6 +24,7 @@ use async_trait::async_trait;
 use best_orders::BestOrdersAction;
 use blake2::digest::{Update, VariableOutput};
 use blake2::Blake2bVar;
+use coins::eth::NftSwapToken;
 use coins::utxo::{compressed_pub_key_from_priv_raw, ChecksumType, UtxoAddressFormat};
 use coins::{coin_conf, find_pair, lp_coinfind, BalanceTradeFeeUpdatedHandler, CoinProtocol, CoinsContext,
             FeeApproxStage, MarketCoinOps, MmCoinEnum};
@@ -1124,6 +1125,11 @@ impl BalanceTradeFeeUpdatedHandler for BalanceUpdateOrdermatchHandler {
             );
             return;
         }
+        // The balance of the NFT coin isn't the balance of the token sold by an order,
+        // the maker swap checks that the token is still owned before sending it.
+        if coin.is_nft() {
+            return;
+        }
         // Get the max maker available volume to check if the wallet balances are sufficient for the issued maker orders.
         // Note although the maker orders are issued already, but they are not matched yet, so pass the `OrderIssue` stage.
         let new_volume = match calc_max_maker_vol(&ctx, coin, new_balance, FeeApproxStage::OrderIssue).await {
@@ -1307,6 +1313,16 @@ pub enum TakerOrderBuildError {
     },
     SenderPubkeyIsZero,
     ConfsSettingsNotSet,
+    /// NFTs can only be sent by makers since the taker pays the dex fee in the coin it sends
+    NftIsSentByTaker {
+        ticker: String,
+    },
+    /// NFT amount must be an integer
+    NftAmountIsNotInteger {
+        amount: MmNumber,
+    },
+    /// The unmatched part of an NFT order can't be converted to a maker order
+    NftOrderIsNotFillOrKill,
 }
 
 impl fmt::Display for TakerOrderBuildError {
@@ -1339,6 +1355,13 @@ impl fmt::Display for TakerOrderBuildError {
             ),
             TakerOrderBuildError::SenderPubkeyIsZero => write!(f, "Sender pubkey can not be zero"),
             TakerOrderBuildError::ConfsSettingsNotSet => write!(f, "Confirmation settings must be set"),
+            TakerOrderBuildError::NftIsSentByTaker { ticker } => {
+                write!(f, "NFT {} can be sent by the maker side of the swap only", ticker)
+            },
+            TakerOrderBuildError::NftAmountIsNotInteger { amount } => {
+                write!(f, "NFT amount {} must be an integer", amount.to_decimal())
+            },
+            TakerOrderBuildError::NftOrderIsNotFillOrKill => write!(f, "NFT orders must be FillOrKill"),
         }
     }
 }
@@ -1455,6 +1478,26 @@ impl<'a> TakerOrderBuilder<'a> {
             return Err(TakerOrderBuildError::ConfsSettingsNotSet);
         }
 
+        let (my_coin, other_coin, other_amount) = match &self.action {
+            TakerAction::Buy => (self.rel_coin, self.base_coin, &self.base_amount),
+            TakerAction::Sell => (self.base_coin, self.rel_coin, &self.rel_amount),
+        };
+        if my_coin.is_nft() {
+            return Err(TakerOrderBuildError::NftIsSentByTaker {
+                ticker: my_coin.ticker().to_owned(),
+            });
+        }
+        if other_coin.is_nft() {
+            if !other_amount.to_ratio().is_integer() {
+                return Err(TakerOrderBuildError::NftAmountIsNotInteger {
+                    amount: other_amount.clone(),
+                });
+            }
+            if self.order_type != OrderType::FillOrKill {
+                return Err(TakerOrderBuildError::NftOrderIsNotFillOrKill);
+            }
+        }
+
         let price = &self.rel_amount / &self.base_amount;
         let base_min_by_rel = &min_rel_amount / &price;
         let base_min_vol_threshold = min_base_amount.max(base_min_by_rel);
@@ -1475,11 +1518,6 @@ impl<'a> TakerOrderBuilder<'a> {
             });
         }
 
-        let my_coin = match &self.action {
-            TakerAction::Buy => &self.rel_coin,
-            TakerAction::Sell => &self.base_coin,
-        };
-
         let p2p_privkey = if my_coin.is_privacy() {
             Some(SerializableSecp256k1Keypair::random())
         } else {
@@ -1520,6 +1558,7 @@ impl<'a> TakerOrderBuilder<'a> {
             base_orderbook_ticker: self.base_orderbook_ticker,
             rel_orderbook_ticker: self.rel_orderbook_ticker,
             p2p_privkey,
+            nft: other_coin.nft_token(),
         })
     }
 
@@ -1560,6 +1599,7 @@ impl<'a> TakerOrderBuilder<'a> {
             base_orderbook_ticker: None,
             rel_orderbook_ticker: None,
             p2p_privkey: None,
+            nft: None,
         }
     }
 }
@@ -1581,6 +1621,9 @@ pub struct TakerOrder {
     /// A custom priv key for more privacy to prevent linking orders of the same node between each other
     /// Commonly used with privacy coins (ARRR, ZCash, etc.)
     p2p_privkey: Option<SerializableSecp256k1Keypair>,
+    /// The NFT bought by the order if the maker coin is an NFT coin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nft: Option<NftSwapToken>,
 }
 
 /// Result of match_reserved function
@@ -1712,6 +1755,9 @@ pub struct MakerOrder {
     /// A custom priv key for more privacy to prevent linking orders of the same node between each other
     /// Commonly used with privacy coins (ARRR, ZCash, etc.)
     p2p_privkey: Option<SerializableSecp256k1Keypair>,
+    /// The NFT sold by the order if the base coin is an NFT coin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nft: Option<NftSwapToken>,
 }
 
 pub struct MakerOrderBuilder<'a> {
@@ -1753,6 +1799,14 @@ pub enum MakerOrderBuildError {
         min: MmNumber,
         max: MmNumber,
     },
+    /// NFTs can only be sold by maker orders since the taker pays the dex fee in the coin it sends
+    RelIsNft {
+        ticker: String,
+    },
+    /// NFT volume must be an integer
+    NftVolumeIsNotInteger {
+        vol: MmNumber,
+    },
 }
 
 impl fmt::Display for MakerOrderBuildError {
@@ -1790,6 +1844,16 @@ impl fmt::Display for MakerOrderBuildError {
                 max.to_decimal(),
                 min.to_decimal()
             ),
+            MakerOrderBuildError::RelIsNft { ticker } => {
+                write!(
+                    f,
+                    "Rel coin {} is an NFT, NFTs can only be sold by maker orders",
+                    ticker
+                )
+            },
+            MakerOrderBuildError::NftVolumeIsNotInteger { vol } => {
+                write!(f, "NFT volume {} must be an integer", vol.to_decimal())
+            },
         }
     }
 }
@@ -1922,6 +1986,20 @@ impl<'a> MakerOrderBuilder<'a> {
             return Err(MakerOrderBuildError::ConfSettingsNotSet);
         }
 
+        if self.rel_coin.is_nft() {
+            return Err(MakerOrderBuildError::RelIsNft {
+                ticker: self.rel_coin.ticker().to_owned(),
+            });
+        }
+
+        if self.base_coin.is_nft() {
+            for vol in std::iter::once(&self.max_base_vol).chain(self.min_base_vol.as_ref()) {
+                if !vol.to_ratio().is_integer() {
+                    return Err(MakerOrderBuildError::NftVolumeIsNotInteger { vol: vol.clone() });
+                }
+            }
+        }
+
         let min_base_amount = self.base_coin.min_trading_vol();
         let min_rel_amount = self.rel_coin.min_trading_vol();
 
@@ -1967,6 +2045,7 @@ impl<'a> MakerOrderBuilder<'a> {
             base_orderbook_ticker: self.base_orderbook_ticker,
             rel_orderbook_ticker: self.rel_orderbook_ticker,
             p2p_privkey,
+            nft: self.base_coin.nft_token(),
         })
     }
 
@@ -1991,6 +2070,7 @@ impl<'a> MakerOrderBuilder<'a> {
             base_orderbook_ticker: None,
             rel_orderbook_ticker: None,
             p2p_privkey: None,
+            nft: None,
         }
     }
 }
@@ -2029,6 +2109,17 @@ impl MakerOrder {
             return OrderMatchResult::NotMatched;
         }
 
+        // NFT orders of the same pair differ by the token, the taker sends the token as the maker coin protocol info
+        if let Some(nft) = &self.nft {
+            let taker_nft = taker
+                .base_protocol_info_for_maker()
+                .as_deref()
+                .map(NftSwapToken::from_protocol_info);
+            if !matches!(taker_nft, Some(Ok(ref token)) if token == nft) {
+                return OrderMatchResult::NotMatched;
+            }
+        }
+
         match taker.action {
             TakerAction::Buy => {
                 let ticker_match = (self.base == taker.base
@@ -2121,6 +2212,8 @@ impl From<TakerOrder> for MakerOrder {
                 base_orderbook_ticker: taker_order.base_orderbook_ticker,
                 rel_orderbook_ticker: taker_order.rel_orderbook_ticker,
                 p2p_privkey: taker_order.p2p_privkey,
+                // NFT orders are FillOrKill, so they are never converted to maker orders
+                nft: None,
             },
             // The "buy" taker order is recreated with reversed pair as Maker order is always considered as "sell"
             TakerAction::Buy => {
@@ -2143,6 +2236,7 @@ impl From<TakerOrder> for MakerOrder {
                     base_orderbook_ticker: taker_order.rel_orderbook_ticker,
                     rel_orderbook_ticker: taker_order.base_orderbook_ticker,
                     p2p_privkey: taker_order.p2p_privkey,
+                    nft: None,
                 }
             },
         }
@@ -2923,6 +3017,13 @@ fn lp_connect_start_bob(ctx: MmArc, maker_match: MakerMatch, maker_order: MakerO
                 return;
             },
         };
+        let maker_coin = match maker_coin.bind_nft_token(maker_order.nft.clone()) {
+            Ok(c) => c,
+            Err(e) => {
+                error!("Error {} on binding the NFT of the order {}", e, maker_order.uuid);
+                return;
+            },
+        };
         let alice = bits256::from(maker_match.request.sender_pubkey.0);
         let maker_amount = maker_match.reserved.get_base_amount().clone();
         let taker_amount = maker_match.reserved.get_rel_amount().clone();
@@ -3075,6 +3176,16 @@ fn lp_connected_alice(ctx: MmArc, taker_order: TakerOrder, taker_match: TakerMat
                 return;
             },
         };
+        let maker_coin = match maker_coin.bind_nft_token(taker_order.nft.clone()) {
+            Ok(c) => c,
+            Err(e) => {
+                error!(
+                    "Error {} on binding the NFT of the order {}",
+                    e, taker_order.request.uuid
+                );
+                return;
+            },
+        };
 
         // lp_connected_alice is called only from process_maker_connected, which returns if CryptoCtx is not initialized
         let crypto_ctx = CryptoCtx::from_ctx(&ctx).expect("'CryptoCtx' must be initialized already");
@@ -3259,7 +3370,7 @@ pub async fn lp_ordermatch_loop(ctx: MmArc) {
                 };
 
                 let mut order = order_mutex.lock().await;
-                let (base, rel) = match find_pair(&ctx, &order.base, &order.rel).await {
+                let (base, rel) = match find_maker_order_pair(&ctx, &order).await {
                     Ok(Some(pair)) => pair,
                     _ => continue,
                 };
@@ -3505,6 +3616,14 @@ async fn process_maker_reserved(ctx: MmArc, from_pubkey: H256Json, reserved_msg:
         Ok(Some(c)) => c,
         _ => return, // attempt to match with deactivated coin
     };
+    // the maker NFT coin checks that the maker sells the token we buy
+    let base_coin = match base_coin.bind_nft_token(my_order.nft.clone()) {
+        Ok(coin) => coin,
+        Err(e) => {
+            error!("Error {} on binding the NFT of the order {}", e, uuid);
+            return;
+        },
+    };
     let mut pending_map = ordermatch_ctx.pending_maker_reserved.lock().await;
     if let Some(mut reserved_messages) = pending_map.remove(&uuid) {
         reserved_messages.sort_unstable_by_key(|r| r.price());
@@ -3635,7 +3754,7 @@ async fn process_taker_request(ctx: MmArc, from_pubkey: H256Json, taker_request:
     for (uuid, order) in filtered {
         let mut order = order.lock().await;
         if let OrderMatchResult::Matched((base_amount, rel_amount)) = order.match_with_request(&taker_request) {
-            let (base_coin, rel_coin) = match find_pair(&ctx, &order.base, &order.rel).await {
+            let (base_coin, rel_coin) = match find_maker_order_pair(&ctx, &order).await {
                 Ok(Some(c)) => c,
                 _ => return, // attempt to match with deactivated coin
             };
@@ -3782,6 +3901,8 @@ async fn process_taker_connect(ctx: MmArc, sender_pubkey: H256Json, connect_msg:
 }
 
 pub async fn buy(ctx: MmArc, req: Json) -> Result<Response<Vec<u8>>, String> {
+    // the NFT to buy, must be set if the base coin is an NFT coin
+    let nft: Option<NftSwapToken> = try_s!(json::from_value(req["nft"].clone()));
     let input: SellBuyRequest = try_s!(json::from_value(req));
     if input.base == input.rel {
         return ERR!("Base and rel must be different coins");
@@ -3790,6 +3911,7 @@ pub async fn buy(ctx: MmArc, req: Json) -> Result<Response<Vec<u8>>, String> {
     let rel_coin = try_s!(rel_coin.ok_or("Rel coin is not found or inactive"));
     let base_coin = try_s!(lp_coinfind(&ctx, &input.base).await);
     let base_coin: MmCoinEnum = try_s!(base_coin.ok_or("Base coin is not found or inactive"));
+    let base_coin = try_s!(base_coin.bind_nft_token(nft));
     if base_coin.wallet_only(&ctx) {
         return ERR!("Base coin {} is wallet only", input.base);
     }
@@ -3814,6 +3936,8 @@ pub async fn buy(ctx: MmArc, req: Json) -> Result<Response<Vec<u8>>, String> {
 }
 
 pub async fn sell(ctx: MmArc, req: Json) -> Result<Response<Vec<u8>>, String> {
+    // the NFT to buy, must be set if the rel coin is an NFT coin
+    let nft: Option<NftSwapToken> = try_s!(json::from_value(req["nft"].clone()));
     let input: SellBuyRequest = try_s!(json::from_value(req));
     if input.base == input.rel {
         return ERR!("Base and rel must be different coins");
@@ -3822,6 +3946,7 @@ pub async fn sell(ctx: MmArc, req: Json) -> Result<Response<Vec<u8>>, String> {
     let base_coin = try_s!(base_coin.ok_or("Base coin is not found or inactive"));
     let rel_coin = try_s!(lp_coinfind(&ctx, &input.rel).await);
     let rel_coin = try_s!(rel_coin.ok_or("Rel coin is not found or inactive"));
+    let rel_coin = try_s!(rel_coin.bind_nft_token(nft));
     if base_coin.wallet_only(&ctx) {
         return ERR!("Base coin {} is wallet only", input.base);
     }
@@ -4398,6 +4523,9 @@ pub struct SetPriceReq {
     rel_nota: Option<bool>,
     #[serde(default = "get_true")]
     save_in_history: bool,
+    /// The NFT to sell, must be set if the base coin is an NFT coin.
+    #[serde(default)]
+    nft: Option<NftSwapToken>,
 }
 
 #[derive(Deserialize)]
@@ -4532,6 +4660,8 @@ struct MakerOrderForRpc<'a> {
     changes_history: &'a Option<Vec<HistoricalOrder>>,
     base_orderbook_ticker: &'a Option<String>,
     rel_orderbook_ticker: &'a Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nft: &'a Option<NftSwapToken>,
 }
 
 impl<'a> From<&'a MakerOrder> for MakerOrderForRpc<'a> {
@@ -4558,6 +4688,7 @@ impl<'a> From<&'a MakerOrder> for MakerOrderForRpc<'a> {
             changes_history: &order.changes_history,
             base_orderbook_ticker: &order.base_orderbook_ticker,
             rel_orderbook_ticker: &order.rel_orderbook_ticker,
+            nft: &order.nft,
         }
     }
 }
@@ -4567,7 +4698,7 @@ impl<'a> From<&'a MakerOrder> for MakerOrderForRpc<'a> {
 async fn cancel_orders_on_error<T, E>(ctx: &MmArc, req: &SetPriceReq, error: E) -> Result<T, E> {
     if req.cancel_previous {
         let ordermatch_ctx = OrdermatchContext::from_ctx(ctx).unwrap();
-        cancel_previous_maker_orders(ctx, &ordermatch_ctx, &req.base, &req.rel).await;
+        cancel_previous_maker_orders(ctx, &ordermatch_ctx, &req.base, &req.rel, &req.nft).await;
     }
     Err(error)
 }
@@ -4604,9 +4735,19 @@ pub async fn check_balance_update_loop(ctx: MmWeak, ticker: String, balance: Opt
     }
 }
 
+/// Finds the coins of the maker order, the base NFT coin is bound to the token sold by the order.
+async fn find_maker_order_pair(ctx: &MmArc, order: &MakerOrder) -> Result<Option<(MmCoinEnum, MmCoinEnum)>, String> {
+    let (base, rel) = match try_s!(find_pair(ctx, &order.base, &order.rel).await) {
+        Some(pair) => pair,
+        None => return Ok(None),
+    };
+    let base = try_s!(base.bind_nft_token(order.nft.clone()));
+    Ok(Some((base, rel)))
+}
+
 pub async fn create_maker_order(ctx: &MmArc, req: SetPriceReq) -> Result<MakerOrder, String> {
     let base_coin: MmCoinEnum = match try_s!(lp_coinfind(ctx, &req.base).await) {
-        Some(coin) => coin,
+        Some(coin) => try_s!(coin.bind_nft_token(req.nft.clone())),
         None => return ERR!("Base coin {} is not found", req.base),
     };
 
@@ -4649,7 +4790,7 @@ pub async fn create_maker_order(ctx: &MmArc, req: SetPriceReq) -> Result<MakerOr
 
     let ordermatch_ctx = try_s!(OrdermatchContext::from_ctx(ctx));
     if req.cancel_previous {
-        cancel_previous_maker_orders(ctx, &ordermatch_ctx, &req.base, &req.rel).await;
+        cancel_previous_maker_orders(ctx, &ordermatch_ctx, &req.base, &req.rel, &req.nft).await;
     }
 
     let conf_settings = OrderConfirmationsSettings {
@@ -4717,12 +4858,14 @@ async fn cancel_previous_maker_orders(
     ordermatch_ctx: &OrdermatchContext,
     base_to_delete: &str,
     rel_to_delete: &str,
+    nft_to_delete: &Option<NftSwapToken>,
 ) {
     let my_maker_orders = ordermatch_ctx.maker_orders_ctx.lock().orders.clone();
 
     for (uuid, order) in my_maker_orders {
         let order = order.lock().await;
-        let to_delete = order.base == base_to_delete && order.rel == rel_to_delete;
+        // the orders selling other NFTs of the same coin pair are kept
+        let to_delete = order.base == base_to_delete && order.rel == rel_to_delete && order.nft == *nft_to_delete;
         if to_delete {
             let removed_order_mutex = ordermatch_ctx.maker_orders_ctx.lock().remove_order(&uuid);
             // This checks that the uuid, &order.base hasn't been removed by another process
@@ -4754,7 +4897,7 @@ pub async fn update_maker_order(ctx: &MmArc, req: MakerOrderUpdateReq) -> Result
 
     let base = order_before_update.base.as_str();
     let rel = order_before_update.rel.as_str();
-    let (base_coin, rel_coin) = match find_pair(ctx, base, rel).await {
+    let (base_coin, rel_coin) = match find_maker_order_pair(ctx, &order_before_update).await {
         Ok(Some(c)) => c,
         _ => return ERR!("Base coin {} and/or rel coin {} are not activated", base, rel),
     };
@@ -5266,6 +5409,8 @@ struct TakerOrderForRpc<'a> {
     cancellable: bool,
     base_orderbook_ticker: &'a Option<String>,
     rel_orderbook_ticker: &'a Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nft: &'a Option<NftSwapToken>,
 }
 
 #[allow(clippy::needless_borrow)]
@@ -5283,6 +5428,7 @@ impl<'a> From<&'a TakerOrder> for TakerOrderForRpc<'a> {
             order_type: &order.order_type,
             base_orderbook_ticker: &order.base_orderbook_ticker,
             rel_orderbook_ticker: &order.rel_orderbook_ticker,
+            nft: &order.nft,
         }
     }
 }
@@ -5782,9 +5928,11 @@ fn orderbook_address(
 ) -> Result<OrderbookAddress, MmError<OrderbookAddrErr>> {
     let protocol: CoinProtocol = json::from_value(conf["protocol"].clone())?;
     match protocol {
-        CoinProtocol::ERC20 { .. } | CoinProtocol::ETH => coins::eth::addr_from_pubkey_str(pubkey)
-            .map(OrderbookAddress::Transparent)
-            .map_to_mm(OrderbookAddrErr::AddrFromPubkeyError),
+        CoinProtocol::ERC20 { .. } | CoinProtocol::ETH(_) | CoinProtocol::NFT { .. } => {
+            coins::eth::addr_from_pubkey_str(pubkey)
+                .map(OrderbookAddress::Transparent)
+                .map_to_mm(OrderbookAddrErr::AddrFromPubkeyError)
+        },
         CoinProtocol::UTXO | CoinProtocol::QTUM | CoinProtocol::QRC20 { .. } | CoinProtocol::BCH { .. } => {
             coins::utxo::address_by_conf_and_pubkey_str(coin, conf, pubkey, addr_format)
                 .map(OrderbookAddress::Transparent)
@@ -5828,6 +5976,7 @@ fn orderbook_address(
                 ))),
             }
         },
+        CoinProtocol::CASHTOKEN { .. } => MmError::err(OrderbookAddrErr::CoinIsNotSupported(coin.to_owned())),
         #[cfg(all(feature = "enable-solana", not(target_arch = "wasm32")))]
         CoinProtocol::SOLANA | CoinProtocol::SPLTOKEN { .. } => {
             MmError::err(OrderbookAddrErr::CoinIsNotSupported(coin.to_owned()))
@@ -574,6 +574,7 @@ async fn create_single_order(
         rel_confs: cfg.rel_confs,
         rel_nota: cfg.rel_nota,
         save_in_history: true,
+        nft: None,
     };
 
     let resp = create_maker_order(&ctx, req)
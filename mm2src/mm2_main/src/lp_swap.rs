@@ -1358,6 +1358,13 @@ async fn kickstart_thread_handler(ctx: MmArc, swap: SavedSwap, maker_coin_ticker
             },
         };
     };
+    let maker_coin = match swap.maker_coin_nft().and_then(|nft| maker_coin.bind_nft_token(nft)) {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error {} binding the NFT of swap {}", e, swap.uuid());
+            return;
+        },
+    };
     match swap {
         SavedSwap::Maker(saved_swap) => {
             run_maker_swap(
@@ -1516,6 +1523,10 @@ pub fn detect_secret_hash_algo(maker_coin: &MmCoinEnum, taker_coin: &MmCoinEnum)
         },
         // If taker is lightning coin the SHA256 of the secret will be sent as part of the maker signed invoice
         (_, MmCoinEnum::Tendermint(_) | MmCoinEnum::TendermintToken(_)) => SecretHashAlgo::SHA256,
+        // Solana HTLC program locks the payments with the SHA256 of the secret
+        #[cfg(all(feature = "enable-solana", not(target_os = "ios"), not(target_os = "android")))]
+        (MmCoinEnum::SolanaCoin(_) | MmCoinEnum::SplToken(_), _)
+        | (_, MmCoinEnum::SolanaCoin(_) | MmCoinEnum::SplToken(_)) => SecretHashAlgo::SHA256,
         (_, _) => SecretHashAlgo::DHASH160,
     }
 }
@@ -14,6 +14,7 @@ use crate::mm2::lp_dispatcher::{DispatcherContext, LpEvents};
 use crate::mm2::lp_network::subscribe_to_topic;
 use crate::mm2::lp_ordermatch::MakerOrderBuilder;
 use crate::mm2::lp_swap::{broadcast_swap_message, taker_payment_spend_duration, MAX_STARTED_AT_DIFF};
+use coins::eth::NftSwapToken;
 use coins::lp_price::fetch_swap_coins_price;
 use coins::{CanRefundHtlc, CheckIfMyPaymentSentArgs, ConfirmPaymentInput, FeeApproxStage, FoundSwapTxSpend, MmCoin,
             MmCoinEnum, PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr, RefundPaymentArgs,
@@ -172,6 +173,9 @@ pub struct MakerSwapData {
     pub taker_coin_htlc_pubkey: Option<H264Json>,
     /// Temporary privkey used to sign P2P messages when applicable
     pub p2p_privkey: Option<SerializableSecp256k1Keypair>,
+    /// The NFT traded by the swap if the maker coin is an NFT coin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maker_coin_nft: Option<NftSwapToken>,
 }
 
 pub struct MakerSwapMut {
@@ -568,6 +572,7 @@ impl MakerSwap {
             maker_coin_htlc_pubkey: Some(maker_coin_htlc_pubkey.as_slice().into()),
             taker_coin_htlc_pubkey: Some(taker_coin_htlc_pubkey.as_slice().into()),
             p2p_privkey: self.p2p_privkey.map(SerializableSecp256k1Keypair::from),
+            maker_coin_nft: self.maker_coin.nft_token(),
         };
 
         // This will be done during order match
@@ -1828,6 +1833,7 @@ impl MakerSavedSwap {
                     maker_coin_htlc_pubkey: None,
                     taker_coin_htlc_pubkey: None,
                     p2p_privkey: None,
+                    maker_coin_nft: None,
                 }),
             },
             MakerSavedEvent {
@@ -1865,6 +1871,16 @@ impl MakerSavedSwap {
         }
     }
 
+    pub fn maker_coin_nft(&self) -> Result<Option<NftSwapToken>, String> {
+        match self.events.first() {
+            Some(event) => match &event.event {
+                MakerSwapEvent::Started(data) => Ok(data.maker_coin_nft.clone()),
+                _ => ERR!("First swap event must be Started"),
+            },
+            None => ERR!("Can't get maker coin NFT, events are empty"),
+        }
+    }
+
     pub fn taker_coin(&self) -> Result<String, String> {
         match self.events.first() {
             Some(event) => match &event.event {
@@ -2,6 +2,7 @@ use crate::mm2::lp_swap::maker_swap::{MakerSavedSwap, MakerSwap, MakerSwapEvent}
 use crate::mm2::lp_swap::taker_swap::{TakerSavedSwap, TakerSwap, TakerSwapEvent};
 use crate::mm2::lp_swap::{MySwapInfo, RecoveredSwap};
 use async_trait::async_trait;
+use coins::eth::NftSwapToken;
 use coins::lp_coinfind;
 use derive_more::Display;
 use mm2_core::mm_ctx::MmArc;
@@ -77,6 +78,13 @@ impl SavedSwap {
         }
     }
 
+    pub fn maker_coin_nft(&self) -> Result<Option<NftSwapToken>, String> {
+        match self {
+            SavedSwap::Maker(swap) => swap.maker_coin_nft(),
+            SavedSwap::Taker(swap) => swap.maker_coin_nft(),
+        }
+    }
+
     pub fn taker_coin_ticker(&self) -> Result<String, String> {
         match self {
             SavedSwap::Maker(swap) => swap.taker_coin(),
@@ -98,6 +106,7 @@ impl SavedSwap {
             Ok(None) => return ERR!("Coin {} is not activated", maker_ticker),
             Err(e) => return ERR!("Error {} on {} coin find attempt", e, maker_ticker),
         };
+        let maker_coin = try_s!(maker_coin.bind_nft_token(try_s!(self.maker_coin_nft())));
 
         let taker_ticker = try_s!(self.taker_coin_ticker());
         let taker_coin = match lp_coinfind(&ctx, &taker_ticker).await {
@@ -15,6 +15,7 @@ use crate::mm2::lp_ordermatch::TakerOrderBuilder;
 use crate::mm2::lp_swap::taker_restart::get_command_based_on_watcher_activity;
 use crate::mm2::lp_swap::{broadcast_p2p_tx_msg, broadcast_swap_msg_every_delayed, tx_helper_topic,
                           wait_for_maker_payment_conf_duration, TakerSwapWatcherData, MAX_STARTED_AT_DIFF};
+use coins::eth::NftSwapToken;
 use coins::lp_price::fetch_swap_coins_price;
 use coins::{lp_coinfind, CanRefundHtlc, CheckIfMyPaymentSentArgs, ConfirmPaymentInput, FeeApproxStage,
             FoundSwapTxSpend, MmCoin, MmCoinEnum, PaymentInstructionArgs, PaymentInstructions, PaymentInstructionsErr,
@@ -221,6 +222,16 @@ impl TakerSavedSwap {
         }
     }
 
+    pub fn maker_coin_nft(&self) -> Result<Option<NftSwapToken>, String> {
+        match self.events.first() {
+            Some(event) => match &event.event {
+                TakerSwapEvent::Started(data) => Ok(data.maker_coin_nft.clone()),
+                _ => ERR!("First swap event must be Started"),
+            },
+            None => ERR!("Can't get maker coin NFT, events are empty"),
+        }
+    }
+
     pub fn taker_coin(&self) -> Result<String, String> {
         match self.events.first() {
             Some(event) => match &event.event {
@@ -540,6 +551,9 @@ pub struct TakerSwapData {
     pub taker_coin_htlc_pubkey: Option<H264Json>,
     /// Temporary privkey used to sign P2P messages when applicable
     pub p2p_privkey: Option<SerializableSecp256k1Keypair>,
+    /// The NFT traded by the swap if the maker coin is an NFT coin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maker_coin_nft: Option<NftSwapToken>,
 }
 
 pub struct TakerSwapMut {
@@ -1100,6 +1114,7 @@ impl TakerSwap {
             maker_coin_htlc_pubkey: Some(maker_coin_htlc_pubkey.as_slice().into()),
             taker_coin_htlc_pubkey: Some(taker_coin_htlc_pubkey.as_slice().into()),
             p2p_privkey: self.p2p_privkey.map(SerializableSecp256k1Keypair::from),
+            maker_coin_nft: self.maker_coin.nft_token(),
         };
 
         // This will be done during order match
@@ -35,6 +35,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -73,6 +74,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -111,6 +113,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -149,6 +152,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -187,6 +191,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -225,6 +230,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let request = TakerRequest {
@@ -265,6 +271,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
     let request = TakerRequest {
         base: "KMD".to_owned(),
@@ -305,6 +312,7 @@ fn test_match_maker_order_and_taker_request() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
     let request = TakerRequest {
         base: "REL".to_owned(),
@@ -327,6 +335,65 @@ fn test_match_maker_order_and_taker_request() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+fn test_match_nft_maker_order_and_taker_request() {
+    let nft_token = |token_id: &str| -> NftSwapToken {
+        json::from_value(json!({
+            "token_address": "0x2b3b1f3b5c4c2f4e3e7b7e2e9d7a1c2b3a4d5e6f",
+            "token_id": token_id,
+            "contract_type": "ERC1155",
+        }))
+        .unwrap()
+    };
+    let maker = MakerOrder {
+        base: "NFT_ETH".into(),
+        rel: "REL".into(),
+        created_at: now_ms(),
+        updated_at: Some(now_ms()),
+        max_base_vol: 2.into(),
+        min_base_vol: 0.into(),
+        price: 10.into(),
+        matches: HashMap::new(),
+        started_swaps: Vec::new(),
+        uuid: new_uuid(),
+        conf_settings: None,
+        changes_history: None,
+        save_in_history: false,
+        base_orderbook_ticker: None,
+        rel_orderbook_ticker: None,
+        p2p_privkey: None,
+        nft: Some(nft_token("1")),
+    };
+
+    let mut request = TakerRequest {
+        base: "NFT_ETH".into(),
+        rel: "REL".into(),
+        uuid: new_uuid(),
+        dest_pub_key: H256Json::default(),
+        sender_pubkey: H256Json::default(),
+        base_amount: 2.into(),
+        rel_amount: 20.into(),
+        action: TakerAction::Buy,
+        match_by: MatchBy::Any,
+        conf_settings: None,
+        base_protocol_info: Some(nft_token("1").to_protocol_info()),
+        rel_protocol_info: None,
+    };
+    let expected = OrderMatchResult::Matched((2.into(), 20.into()));
+    assert_eq!(maker.match_with_request(&request), expected);
+
+    // another token of the same contract
+    request.base_protocol_info = Some(nft_token("2").to_protocol_info());
+    assert_eq!(maker.match_with_request(&request), OrderMatchResult::NotMatched);
+
+    // the taker doesn't trade an NFT
+    request.base_protocol_info = None;
+    assert_eq!(maker.match_with_request(&request), OrderMatchResult::NotMatched);
+
+    request.base_protocol_info = Some(vec![1, 2, 3]);
+    assert_eq!(maker.match_with_request(&request), OrderMatchResult::NotMatched);
+}
+
 // https://github.com/KomodoPlatform/atomicDEX-API/pull/739#discussion_r517275495
 #[test]
 fn maker_order_match_with_request_zero_volumes() {
@@ -376,6 +443,7 @@ fn test_maker_order_available_amount() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
     maker.matches.insert(new_uuid(), MakerMatch {
         request: TakerRequest {
@@ -477,6 +545,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -521,6 +590,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -565,6 +635,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -609,6 +680,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -653,6 +725,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -697,6 +770,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -741,6 +815,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -785,6 +860,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -829,6 +905,7 @@ fn test_taker_match_reserved() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -876,6 +953,7 @@ fn test_taker_order_cancellable() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     assert!(order.is_cancellable());
@@ -906,6 +984,7 @@ fn test_taker_order_cancellable() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     order.matches.insert(new_uuid(), TakerMatch {
@@ -963,6 +1042,7 @@ fn prepare_for_cancel_by(ctx: &MmArc) -> mpsc::Receiver<AdexBehaviourCmd> {
             base_orderbook_ticker: None,
             rel_orderbook_ticker: None,
             p2p_privkey: None,
+            nft: None,
         },
         None,
     );
@@ -985,6 +1065,7 @@ fn prepare_for_cancel_by(ctx: &MmArc) -> mpsc::Receiver<AdexBehaviourCmd> {
             base_orderbook_ticker: None,
             rel_orderbook_ticker: None,
             p2p_privkey: None,
+            nft: None,
         },
         None,
     );
@@ -1007,6 +1088,7 @@ fn prepare_for_cancel_by(ctx: &MmArc) -> mpsc::Receiver<AdexBehaviourCmd> {
             base_orderbook_ticker: None,
             rel_orderbook_ticker: None,
             p2p_privkey: None,
+            nft: None,
         },
         None,
     );
@@ -1034,6 +1116,7 @@ fn prepare_for_cancel_by(ctx: &MmArc) -> mpsc::Receiver<AdexBehaviourCmd> {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     });
     rx
 }
@@ -1132,6 +1215,7 @@ fn test_taker_order_match_by() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let reserved = MakerReserved {
@@ -1186,6 +1270,7 @@ fn test_maker_order_was_updated() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
     let mut update_msg = MakerOrderUpdated::new(maker_order.uuid);
     update_msg.with_new_price(BigRational::from_integer(2.into()));
@@ -3179,6 +3264,7 @@ fn test_maker_order_balance_loops() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     let morty_order = MakerOrder {
@@ -3198,6 +3284,7 @@ fn test_maker_order_balance_loops() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     assert!(!maker_orders_ctx.balance_loop_exists(rick_ticker));
@@ -3230,6 +3317,7 @@ fn test_maker_order_balance_loops() {
         base_orderbook_ticker: None,
         rel_orderbook_ticker: None,
         p2p_privkey: None,
+        nft: None,
     };
 
     maker_orders_ctx.add_order(ctx.weak(), rick_order_2.clone(), None);
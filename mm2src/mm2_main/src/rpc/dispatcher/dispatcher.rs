@@ -9,10 +9,15 @@ use crate::{mm2::lp_stats::{add_node_to_version_stat, remove_node_from_version_s
                             stop_version_stat_collection, update_version_stat_collection},
             mm2::lp_swap::{get_locked_amount_rpc, max_maker_vol, recreate_swap_data, trade_preimage_rpc},
             mm2::rpc::lp_commands::{get_public_key, get_public_key_hash, get_shared_db_id, trezor_connection_status}};
+use coins::eth::contract_call::contract_call;
+use coins::eth::eip712_sign::{sign_erc20_permit, sign_typed_data, verify_typed_data};
+use coins::eth::erc20_allowance::{get_token_allowances, set_token_allowance};
 use coins::eth::EthCoin;
 use coins::my_tx_history_v2::my_tx_history_v2_rpc;
 use coins::nft;
-use coins::rpc_command::tendermint::{ibc_chains, ibc_transfer_channels, ibc_withdraw};
+use coins::rpc_command::tendermint::{ibc_chains, ibc_transfer_channels, ibc_transfer_status, ibc_withdraw,
+                                     tendermint_claim_rewards, tendermint_proposals, tendermint_redelegate,
+                                     tendermint_validators, tendermint_vote};
 use coins::rpc_command::{account_balance::account_balance,
                          get_current_mtp::get_current_mtp_rpc,
                          get_enabled_coins::get_enabled_coins,
@@ -56,6 +61,8 @@ use std::net::SocketAddr;
 
 cfg_native! {
     use coins::lightning::LightningCoin;
+    use coins::rpc_command::init_z_coin_rescan::{cancel_z_coin_rescan, init_z_coin_rescan, z_coin_rescan_status};
+    use coins::rpc_command::z_coin_shield::{z_coin_deshield, z_coin_shield};
     use coins::z_coin::ZCoin;
 }
 
@@ -156,6 +163,7 @@ async fn dispatcher_v2(request: MmRpcRequest, ctx: MmArc) -> DispatcherResult<Re
         "add_delegation" => handle_mmrpc(ctx, request, add_delegation).await,
         "add_node_to_version_stat" => handle_mmrpc(ctx, request, add_node_to_version_stat).await,
         "best_orders" => handle_mmrpc(ctx, request, best_orders_rpc_v2).await,
+        "contract_call" => handle_mmrpc(ctx, request, contract_call).await,
         "enable_bch_with_tokens" => handle_mmrpc(ctx, request, enable_platform_coin_with_tokens::<BchCoin>).await,
         "enable_slp" => handle_mmrpc(ctx, request, enable_token::<SlpToken>).await,
         "enable_eth_with_tokens" => handle_mmrpc(ctx, request, enable_platform_coin_with_tokens::<EthCoin>).await,
@@ -177,6 +185,7 @@ async fn dispatcher_v2(request: MmRpcRequest, ctx: MmArc) -> DispatcherResult<Re
         "get_raw_transaction" => handle_mmrpc(ctx, request, get_raw_transaction).await,
         "get_shared_db_id" => handle_mmrpc(ctx, request, get_shared_db_id).await,
         "get_staking_infos" => handle_mmrpc(ctx, request, get_staking_infos).await,
+        "get_token_allowances" => handle_mmrpc(ctx, request, get_token_allowances).await,
         "max_maker_vol" => handle_mmrpc(ctx, request, max_maker_vol).await,
         "my_tx_history" => handle_mmrpc(ctx, request, my_tx_history_v2_rpc).await,
         "orderbook" => handle_mmrpc(ctx, request, orderbook_rpc_v2).await,
@@ -184,7 +193,10 @@ async fn dispatcher_v2(request: MmRpcRequest, ctx: MmArc) -> DispatcherResult<Re
         "refresh_nft_metadata" => handle_mmrpc(ctx, request, refresh_nft_metadata).await,
         "remove_delegation" => handle_mmrpc(ctx, request, remove_delegation).await,
         "remove_node_from_version_stat" => handle_mmrpc(ctx, request, remove_node_from_version_stat).await,
+        "set_token_allowance" => handle_mmrpc(ctx, request, set_token_allowance).await,
+        "sign_erc20_permit" => handle_mmrpc(ctx, request, sign_erc20_permit).await,
         "sign_message" => handle_mmrpc(ctx, request, sign_message).await,
+        "sign_typed_data" => handle_mmrpc(ctx, request, sign_typed_data).await,
         "start_simple_market_maker_bot" => handle_mmrpc(ctx, request, start_simple_market_maker_bot).await,
         "start_version_stat_collection" => handle_mmrpc(ctx, request, start_version_stat_collection).await,
         "stop_simple_market_maker_bot" => handle_mmrpc(ctx, request, stop_simple_market_maker_bot).await,
@@ -194,10 +206,17 @@ async fn dispatcher_v2(request: MmRpcRequest, ctx: MmArc) -> DispatcherResult<Re
         "update_nft" => handle_mmrpc(ctx, request, update_nft).await,
         "update_version_stat_collection" => handle_mmrpc(ctx, request, update_version_stat_collection).await,
         "verify_message" => handle_mmrpc(ctx, request, verify_message).await,
+        "verify_typed_data" => handle_mmrpc(ctx, request, verify_typed_data).await,
         "withdraw" => handle_mmrpc(ctx, request, withdraw).await,
         "ibc_withdraw" => handle_mmrpc(ctx, request, ibc_withdraw).await,
         "ibc_chains" => handle_mmrpc(ctx, request, ibc_chains).await,
         "ibc_transfer_channels" => handle_mmrpc(ctx, request, ibc_transfer_channels).await,
+        "ibc_transfer_status" => handle_mmrpc(ctx, request, ibc_transfer_status).await,
+        "tendermint_claim_rewards" => handle_mmrpc(ctx, request, tendermint_claim_rewards).await,
+        "tendermint_proposals" => handle_mmrpc(ctx, request, tendermint_proposals).await,
+        "tendermint_redelegate" => handle_mmrpc(ctx, request, tendermint_redelegate).await,
+        "tendermint_validators" => handle_mmrpc(ctx, request, tendermint_validators).await,
+        "tendermint_vote" => handle_mmrpc(ctx, request, tendermint_vote).await,
         "withdraw_nft" => handle_mmrpc(ctx, request, withdraw_nft).await,
         #[cfg(not(target_arch = "wasm32"))]
         native_only_methods => match native_only_methods {
@@ -207,6 +226,8 @@ async fn dispatcher_v2(request: MmRpcRequest, ctx: MmArc) -> DispatcherResult<Re
             },
             #[cfg(all(feature = "enable-solana", not(target_os = "ios"), not(target_os = "android")))]
             "enable_spl" => handle_mmrpc(ctx, request, enable_token::<SplToken>).await,
+            "z_coin_deshield" => handle_mmrpc(ctx, request, z_coin_deshield).await,
+            "z_coin_shield" => handle_mmrpc(ctx, request, z_coin_shield).await,
             "z_coin_tx_history" => handle_mmrpc(ctx, request, coins::my_tx_history_v2::z_coin_tx_history_rpc).await,
             _ => MmError::err(DispatcherError::NoSuchMethod),
         },
@@ -270,6 +291,9 @@ async fn rpc_task_dispatcher(
             "enable_z_coin::init" => handle_mmrpc(ctx, request, init_standalone_coin::<ZCoin>).await,
             "enable_z_coin::status" => handle_mmrpc(ctx, request, init_standalone_coin_status::<ZCoin>).await,
             "enable_z_coin::user_action" => handle_mmrpc(ctx, request, init_standalone_coin_user_action::<ZCoin>).await,
+            "z_coin_rescan::cancel" => handle_mmrpc(ctx, request, cancel_z_coin_rescan).await,
+            "z_coin_rescan::init" => handle_mmrpc(ctx, request, init_z_coin_rescan).await,
+            "z_coin_rescan::status" => handle_mmrpc(ctx, request, z_coin_rescan_status).await,
             _ => MmError::err(DispatcherError::NoSuchMethod),
         },
         #[cfg(target_arch = "wasm32")]
@@ -321,10 +345,11 @@ async fn lightning_dispatcher(
     ctx: MmArc,
     lightning_method: &str,
 ) -> DispatcherResult<Response<Vec<u8>>> {
-    use coins::rpc_command::lightning::{channels, nodes, payments};
+    use coins::rpc_command::lightning::{channels, forwarding, nodes, payments};
 
     match lightning_method {
         "channels::close_channel" => handle_mmrpc(ctx, request, channels::close_channel).await,
+        "channels::export_channel_backup" => handle_mmrpc(ctx, request, channels::export_channel_backup).await,
         "channels::get_channel_details" => handle_mmrpc(ctx, request, channels::get_channel_details).await,
         "channels::get_claimable_balances" => handle_mmrpc(ctx, request, channels::get_claimable_balances).await,
         "channels::list_closed_channels_by_filter" => {
@@ -334,7 +359,11 @@ async fn lightning_dispatcher(
             handle_mmrpc(ctx, request, channels::list_open_channels_by_filter).await
         },
         "channels::open_channel" => handle_mmrpc(ctx, request, channels::open_channel).await,
+        "channels::restore_channel_backup" => handle_mmrpc(ctx, request, channels::restore_channel_backup).await,
+        "channels::update_all_channels" => handle_mmrpc(ctx, request, channels::update_all_channels).await,
         "channels::update_channel" => handle_mmrpc(ctx, request, channels::update_channel).await,
+        "forwarding::get_forwarding_earnings" => handle_mmrpc(ctx, request, forwarding::get_forwarding_earnings).await,
+        "forwarding::list_forwarded_payments" => handle_mmrpc(ctx, request, forwarding::list_forwarded_payments).await,
         "nodes::add_trusted_node" => handle_mmrpc(ctx, request, nodes::add_trusted_node).await,
         "nodes::connect_to_node" => handle_mmrpc(ctx, request, nodes::connect_to_node).await,
         "nodes::list_trusted_nodes" => handle_mmrpc(ctx, request, nodes::list_trusted_nodes).await,
@@ -0,0 +1,284 @@
+use crate::docker_tests::docker_tests_common::*;
+use coins::tendermint::account_id_from_pubkey_hex;
+use crypto::privkey::key_pair_from_secret;
+use crypto::Secp256k1Secret;
+use mm2_test_helpers::for_tests::enable_tendermint;
+use serde_json::{self as json, Value as Json};
+use std::process::{Command, Output};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const WASMD_DOCKER_IMAGE: &str = "docker.io/cosmwasm/wasmd:v0.45.0";
+pub const WASMD_RPC_URL: &str = "http://127.0.0.1:26657";
+const GIT_DOCKER_IMAGE: &str = "docker.io/alpine/git:2.43.0";
+const WORKSPACE_OPTIMIZER_DOCKER_IMAGE: &str = "docker.io/cosmwasm/workspace-optimizer:0.15.0";
+const CW_TOKENS_REPO: &str = "https://github.com/CosmWasm/cw-tokens";
+const CW_ATOMIC_SWAP_WASM: &str = "cw20_atomic_swap.wasm";
+const WASMD_CHAIN_ID: &str = "testing";
+const WASMD_DENOM: &str = "ucosm";
+/// The passphrase of the validator key, it is used by the setup script of the image.
+const WASMD_KEYRING_PASSWORD: &str = "1234567890";
+/// The number of the addresses that are funded in the genesis block.
+const WASMD_PREFUNDED_KEYS_COUNT: usize = 4;
+
+lazy_static! {
+    /// The private keys that are funded with `ucosm` in the genesis block, every test takes its own keys.
+    static ref WASMD_PREFUNDED_KEYS: Mutex<Vec<Secp256k1Secret>> =
+        Mutex::new((0..WASMD_PREFUNDED_KEYS_COUNT).map(|_| random_secp256k1_secret()).collect());
+    static ref CW_ATOMIC_SWAP_CONTRACT: Mutex<Option<String>> = Mutex::new(None);
+}
+
+pub static mut WASMD_CONTAINER_ID: Option<String> = None;
+
+fn wasm_address(secret: &Secp256k1Secret) -> String {
+    let key_pair = key_pair_from_secret(secret.as_slice()).unwrap();
+    account_id_from_pubkey_hex("wasm", &hex::encode(key_pair.public().to_vec()))
+        .unwrap()
+        .to_string()
+}
+
+/// Builds the `cw20-atomic-swap` contract from the sources by the CosmWasm optimizer
+/// and returns the directory that contains the optimized `.wasm` artifact.
+pub fn build_cw_atomic_swap_contract() -> String {
+    let build_dir = env::temp_dir().join("mm2_cw_tokens");
+    let build_dir = build_dir.to_str().unwrap().to_owned();
+    if !std::path::Path::new(&format!("{}/Cargo.toml", build_dir)).exists() {
+        let output = Command::new("docker")
+            .args(["run", "--rm", "-v"])
+            .arg(format!("{}:/git", env::temp_dir().to_str().unwrap()))
+            .arg(GIT_DOCKER_IMAGE)
+            .args(["clone", "--depth", "1", CW_TOKENS_REPO, "/git/mm2_cw_tokens"])
+            .output()
+            .expect("Failed to execute docker command");
+        assert!(output.status.success(), "!git clone: {:?}", output);
+    }
+
+    let output = Command::new("docker")
+        .args(["run", "--rm", "-v"])
+        .arg(format!("{}:/code", build_dir))
+        .arg(WORKSPACE_OPTIMIZER_DOCKER_IMAGE)
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!workspace-optimizer: {:?}", output);
+    let artifacts_dir = format!("{}/artifacts", build_dir);
+    assert!(
+        std::path::Path::new(&format!("{}/{}", artifacts_dir, CW_ATOMIC_SWAP_WASM)).exists(),
+        "{} is not built",
+        CW_ATOMIC_SWAP_WASM
+    );
+    artifacts_dir
+}
+
+/// Runs a single validator `wasmd` chain, the prefunded test addresses are added to the genesis.
+pub fn wasmd_docker_node(port: u16, artifacts_dir: &str) {
+    let prefunded: Vec<_> = WASMD_PREFUNDED_KEYS.lock().unwrap().iter().map(wasm_address).collect();
+    let setup = format!("/opt/setup_wasmd.sh {} && /opt/run_wasmd.sh", prefunded.join(" "));
+    let output = Command::new("docker")
+        .args(["run", "-d", "--rm", "-p"])
+        .arg(format!("127.0.0.1:{}:26657", port))
+        .arg("-v")
+        .arg(format!("{}:/artifacts", artifacts_dir))
+        .args(["-e", &format!("PASSWORD={}", WASMD_KEYRING_PASSWORD)])
+        .args(["-e", &format!("CHAIN_ID={}", WASMD_CHAIN_ID)])
+        .args(["-e", &format!("FEE_TOKEN={}", WASMD_DENOM)])
+        .arg(WASMD_DOCKER_IMAGE)
+        .args(["sh", "-c", &setup])
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!docker run wasmd: {:?}", output);
+    let container_id = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+    unsafe { WASMD_CONTAINER_ID = Some(container_id) };
+}
+
+/// Runs the shell command in the `wasmd` container, the validator key passphrase is piped to stdin.
+fn wasmd_exec(cmd: &str) -> Output {
+    let container_id = unsafe { WASMD_CONTAINER_ID.as_ref().expect("Wasmd container is not started yet") };
+    Command::new("docker")
+        .args(["exec", container_id, "sh", "-c"])
+        .arg(format!("echo {} | {}", WASMD_KEYRING_PASSWORD, cmd))
+        .output()
+        .expect("Failed to execute docker command")
+}
+
+pub fn wait_for_wasmd_node_ready() {
+    let timeout = wait_until_ms(120000);
+    loop {
+        let output = wasmd_exec("wasmd status");
+        if output.status.success() {
+            // some `wasmd` versions print the status to stderr
+            let status = if output.stdout.is_empty() {
+                output.stderr
+            } else {
+                output.stdout
+            };
+            let status: Json = json::from_slice(&status).unwrap_or_default();
+            let height = status["sync_info"]["latest_block_height"]
+                .as_str()
+                .and_then(|height| height.parse::<u64>().ok())
+                .unwrap_or_default();
+            if height > 0 {
+                break;
+            }
+        }
+        assert!(now_ms() < timeout, "Test timed out");
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Sends the tx signed by the validator and waits for it to be included into a block.
+fn wasmd_validator_tx(args: &str) {
+    let output = wasmd_exec(&format!(
+        "wasmd tx {} --from validator --chain-id {} --gas auto --gas-adjustment 1.5 --fees 1000000{} \
+         --broadcast-mode sync -y --output json",
+        args, WASMD_CHAIN_ID, WASMD_DENOM
+    ));
+    assert!(output.status.success(), "!wasmd tx {}: {:?}", args, output);
+    let response: Json = json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["code"], 0, "!wasmd tx {}: {}", args, response);
+    let tx_hash = response["txhash"].as_str().unwrap().to_owned();
+
+    let timeout = wait_until_ms(60000);
+    while !wasmd_exec(&format!("wasmd query tx {} --output json", tx_hash))
+        .status
+        .success()
+    {
+        assert!(now_ms() < timeout, "Tx {} is not confirmed in time", tx_hash);
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Stores and instantiates the `cw20-atomic-swap` contract, the address is used by the `WASM` coin config.
+pub fn deploy_cw_atomic_swap_contract() {
+    wasmd_validator_tx(&format!("wasm store /artifacts/{}", CW_ATOMIC_SWAP_WASM));
+    wasmd_validator_tx("wasm instantiate 1 '{}' --label cw20_atomic_swap --no-admin");
+
+    let output = wasmd_exec("wasmd query wasm list-contract-by-code 1 --output json");
+    assert!(output.status.success(), "!wasmd query wasm: {:?}", output);
+    let contracts: Json = json::from_slice(&output.stdout).unwrap();
+    let contract = contracts["contracts"][0].as_str().unwrap().to_owned();
+    log!("cw20-atomic-swap is deployed at {}", contract);
+    *CW_ATOMIC_SWAP_CONTRACT.lock().unwrap() = Some(contract);
+}
+
+fn wasm_coin_conf() -> Json {
+    let contract = CW_ATOMIC_SWAP_CONTRACT
+        .lock()
+        .unwrap()
+        .clone()
+        .expect("cw20-atomic-swap is not deployed yet");
+    json!({
+        "coin": "WASM",
+        "avg_blocktime": 5,
+        "protocol": {
+            "type": "TENDERMINT",
+            "protocol_data": {
+                "decimals": 6,
+                "denom": WASMD_DENOM,
+                "account_prefix": "wasm",
+                "chain_id": WASMD_CHAIN_ID,
+                "htlc_backend": {
+                    "type": "CosmWasm",
+                    "params": { "contract_address": contract },
+                },
+            },
+        },
+        "derivation_path": "m/44'/118'",
+    })
+}
+
+fn take_prefunded_wasmd_key() -> Secp256k1Secret {
+    WASMD_PREFUNDED_KEYS
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("No prefunded wasmd keys left")
+}
+
+fn start_wasm_node(priv_key: Secp256k1Secret, seednodes: Option<&MarketMakerIt>) -> MarketMakerIt {
+    let coins = json!([
+        wasm_coin_conf(),
+        {"coin":"MYCOIN","asset":"MYCOIN","required_confirmations":0,"txversion":4,"overwintered":1,"txfee":1000,"protocol":{"type":"UTXO"}},
+    ]);
+    let mut conf = json!({
+        "gui": "nogui",
+        "netid": 9000,
+        "dht": "on",  // Enable DHT without delay.
+        "passphrase": format!("0x{}", hex::encode(priv_key)),
+        "coins": coins,
+        "rpc_password": "pass",
+    });
+    match seednodes {
+        Some(seed) => conf["seednodes"] = json!([seed.ip.to_string()]),
+        None => conf["i_am_seed"] = true.into(),
+    }
+    let mut mm = MarketMakerIt::start(conf, "pass".to_string(), None).unwrap();
+    block_on(mm.wait_for_log(22., |log| log.contains(">>>>>>>>> DEX stats "))).unwrap();
+
+    log!("{:?}", block_on(enable_native(&mm, "MYCOIN", &[], None)));
+    log!(
+        "{:?}",
+        block_on(enable_tendermint(&mm, "WASM", &[], &[WASMD_RPC_URL], false))
+    );
+    mm
+}
+
+/// Bob sells WASM for MYCOIN, so the maker payment is locked in the `cw20-atomic-swap` contract,
+/// validated by Alice and released by her with the secret.
+#[test]
+fn test_cosmwasm_htlc_swap() {
+    let bob_priv_key = take_prefunded_wasmd_key();
+    // Alice pays the dex fee and the taker payment in MYCOIN and the release fee in WASM
+    let alice_priv_key = take_prefunded_wasmd_key();
+    generate_utxo_coin_with_privkey("MYCOIN", 10.into(), alice_priv_key);
+
+    let mut mm_bob = start_wasm_node(bob_priv_key, None);
+    let mut mm_alice = start_wasm_node(alice_priv_key, Some(&mm_bob));
+
+    let rc = block_on(mm_bob.rpc(&json!({
+        "userpass": mm_bob.userpass,
+        "method": "setprice",
+        "base": "WASM",
+        "rel": "MYCOIN",
+        "price": 1,
+        "volume": "3",
+    })))
+    .unwrap();
+    assert!(rc.0.is_success(), "!setprice: {}", rc.1);
+    thread::sleep(Duration::from_secs(1));
+
+    let rc = block_on(mm_alice.rpc(&json!({
+        "userpass": mm_alice.userpass,
+        "method": "buy",
+        "base": "WASM",
+        "rel": "MYCOIN",
+        "price": 1,
+        "volume": "2",
+    })))
+    .unwrap();
+    assert!(rc.0.is_success(), "!buy: {}", rc.1);
+    let buy_json: Json = json::from_str(&rc.1).unwrap();
+    let uuid = buy_json["result"]["uuid"].as_str().unwrap().to_owned();
+
+    block_on(mm_bob.wait_for_log(22., |log| log.contains("Entering the maker_swap_loop WASM/MYCOIN"))).unwrap();
+    block_on(mm_alice.wait_for_log(22., |log| log.contains("Entering the taker_swap_loop WASM/MYCOIN"))).unwrap();
+
+    block_on(mm_bob.wait_for_log(600., |log| log.contains(&format!("[swap uuid={}] Finished", uuid)))).unwrap();
+    block_on(mm_alice.wait_for_log(600., |log| log.contains(&format!("[swap uuid={}] Finished", uuid)))).unwrap();
+
+    // `check_my_swap_status` fails if any of the swap steps, including the maker payment validation, failed
+    block_on(check_my_swap_status(
+        &mm_alice,
+        &uuid,
+        "2".parse().unwrap(),
+        "2".parse().unwrap(),
+    ));
+    block_on(check_my_swap_status(
+        &mm_bob,
+        &uuid,
+        "2".parse().unwrap(),
+        "2".parse().unwrap(),
+    ));
+
+    block_on(mm_bob.stop()).unwrap();
+    block_on(mm_alice.stop()).unwrap();
+}
@@ -156,7 +156,7 @@ pub fn eth_distributor() -> EthCoin {
         "ETH",
         &eth_testnet_conf(),
         &req,
-        CoinProtocol::ETH,
+        CoinProtocol::ETH(None),
         priv_key_policy,
     ))
     .unwrap()
@@ -185,7 +185,7 @@ pub fn generate_eth_coin_with_seed(seed: &str) -> EthCoin {
         "ETH",
         &eth_testnet_conf(),
         &req,
-        CoinProtocol::ETH,
+        CoinProtocol::ETH(None),
         priv_key_policy,
     ))
     .unwrap()
@@ -243,6 +243,7 @@ impl BchDockerOps {
         let slp_genesis = TransactionOutput {
             value: self.coin.as_ref().dust_amount,
             script_pubkey: Builder::build_p2pkh(&self.coin.my_public_key().unwrap().address_hash().into()).to_bytes(),
+            token: None,
         };
 
         let mut bch_outputs = vec![slp_genesis_op_ret, slp_genesis];
@@ -270,6 +271,7 @@ impl BchDockerOps {
             bch_outputs.push(TransactionOutput {
                 value: 1000_00000000,
                 script_pubkey: script_pubkey.to_bytes(),
+                token: None,
             });
 
             slp_outputs.push(SlpOutput {
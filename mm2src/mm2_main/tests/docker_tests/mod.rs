@@ -1,7 +1,10 @@
 pub mod docker_tests_common;
 
+pub mod cosmwasm_htlc_tests;
 mod docker_ordermatch_tests;
 mod docker_tests_inner;
+pub mod nft_indexer_tests;
+mod nft_swap_tests;
 pub mod qrc20_tests;
 mod slp_tests;
 mod swap_proto_v2_tests;
@@ -9,7 +12,7 @@ mod swap_watcher_tests;
 mod swaps_confs_settings_sync_tests;
 mod swaps_file_lock_tests;
 
-#[cfg(feature = "enable-solana")] mod solana_tests;
+#[cfg(feature = "enable-solana")] pub mod solana_tests;
 
 // dummy test helping IDE to recognize this as test module
 #[test]
@@ -0,0 +1,405 @@
+use crate::docker_tests::docker_tests_common::*;
+use http::StatusCode;
+use mm2_test_helpers::for_tests::{enable_eth_coin, Mm2TestConf};
+use mm2_test_helpers::get_passphrase;
+use serde_json::{self as json, Value as Json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::Command;
+use std::time::Duration;
+
+pub const GETH_DOCKER_IMAGE: &str = "docker.io/ethereum/client-go:v1.12.2";
+pub const GETH_LOCAL_URL: &str = "http://127.0.0.1:8545";
+const GETH_IPC_PATH: &str = "/geth/geth.ipc";
+pub const SOLC_DOCKER_IMAGE: &str = "docker.io/ethereum/solc:0.8.19";
+/// The sources of the test contracts, they are compiled by `compile_nft_test_contracts` before the tests start.
+const NFT_TEST_CONTRACTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/docker_tests/nft_test_contracts");
+
+lazy_static! {
+    /// The antispam API mock which is used as the Moralis URL too, since the logs indexer doesn't request Moralis.
+    static ref NFT_ANTISPAM_MOCK_URL: String = start_antispam_mock();
+}
+
+pub static mut GETH_CONTAINER_ID: Option<String> = None;
+
+/// Runs geth in the dev mode which mines a block as soon as a transaction is received.
+/// `GenericImage` args are passed to `docker run` and can't be used for geth flags, so the container is started directly.
+pub fn geth_docker_node(port: u16) {
+    let output = Command::new("docker")
+        .args(["run", "-d", "--rm", "-p"])
+        .arg(format!("127.0.0.1:{}:{}", port, port))
+        .arg(GETH_DOCKER_IMAGE)
+        .args(["--dev", "--datadir", "/geth", "--http", "--http.addr", "0.0.0.0"])
+        .args(["--http.port", &port.to_string(), "--http.api", "eth,net,web3"])
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!docker run geth: {:?}", output);
+    let container_id = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+    unsafe { GETH_CONTAINER_ID = Some(container_id) };
+}
+
+/// Evaluates the JS in the geth console of the dev node and returns the trimmed output.
+fn geth_exec(js: &str) -> String {
+    let container_id = unsafe { GETH_CONTAINER_ID.as_ref().expect("Geth container is not started yet") };
+    let output = Command::new("docker")
+        .args(["exec", container_id, "geth", "attach", "--exec", js, GETH_IPC_PATH])
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!geth attach: {:?}", output);
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .trim()
+        .trim_matches('"')
+        .to_owned()
+}
+
+pub fn wait_for_geth_node_ready() {
+    let timeout = wait_until_ms(120000);
+    loop {
+        let container_id = unsafe { GETH_CONTAINER_ID.as_ref().unwrap() };
+        let status = Command::new("docker")
+            .args([
+                "exec",
+                container_id,
+                "geth",
+                "attach",
+                "--exec",
+                "eth.blockNumber",
+                GETH_IPC_PATH,
+            ])
+            .output()
+            .expect("Failed to execute docker command")
+            .status;
+        if status.success() {
+            break;
+        }
+        assert!(now_ms() < timeout, "Test timed out");
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Sends the transaction with the given `tx_fields` from the prefunded dev account,
+/// waits for the receipt and returns the `field` of the receipt.
+fn geth_send_dev_tx(tx_fields: &str, field: &str) -> String {
+    let js = format!(
+        "var h = eth.sendTransaction({{from: eth.accounts[0], {}, gas: 6000000}}); \
+         while (!eth.getTransactionReceipt(h)) {{ admin.sleep(0.1) }}; \
+         var r = eth.getTransactionReceipt(h); r.status == '0x1' ? r.{} : 'failed'",
+        tx_fields, field
+    );
+    let res = geth_exec(&js);
+    assert_ne!(res, "failed", "Transaction failed: {}", js);
+    res
+}
+
+/// Sends `amount` ETH from the prefunded dev account to `to`.
+pub fn fill_eth_address(to: &str, amount: u64) {
+    geth_send_dev_tx(
+        &format!("to: '{}', value: web3.toWei({}, 'ether')", to, amount),
+        "transactionHash",
+    );
+}
+
+/// Compiles the test contracts with solc, the hex encoded creation bytecode of every contract
+/// is written to `build/<ContractName>.bin`.
+pub fn compile_nft_test_contracts() {
+    let sources: Vec<_> = std::fs::read_dir(NFT_TEST_CONTRACTS_DIR)
+        .unwrap()
+        .filter_map(|entry| {
+            let file_name = entry.unwrap().file_name().into_string().unwrap();
+            file_name.ends_with(".sol").then(|| format!("/contracts/{}", file_name))
+        })
+        .collect();
+    let output = Command::new("docker")
+        .args(["run", "--rm", "-v"])
+        .arg(format!("{}:/contracts", NFT_TEST_CONTRACTS_DIR))
+        .arg(SOLC_DOCKER_IMAGE)
+        .args(["--optimize", "--bin", "--overwrite", "-o", "/contracts/build"])
+        .args(&sources)
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!solc: {:?}", output);
+}
+
+pub fn deploy_nft_test_contract(name: &str) -> String {
+    let bytecode = std::fs::read_to_string(format!("{}/build/{}.bin", NFT_TEST_CONTRACTS_DIR, name)).unwrap();
+    let bytecode = bytecode.trim().trim_start_matches("0x");
+    let address = geth_send_dev_tx(&format!("data: '0x{}'", bytecode), "contractAddress");
+    log!("{} is deployed at {}", name, address);
+    address
+}
+
+/// Reads the HTTP request from the stream, the request itself is not needed by the mock.
+fn read_http_request(stream: &mut TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or_default();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)
+}
+
+/// Starts the HTTP server which answers every antispam request with an empty result,
+/// so no contract or domain is reported as spam or phishing. Returns the URL of the server.
+fn start_antispam_mock() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            if read_http_request(&mut stream).is_err() {
+                continue;
+            }
+            let body = r#"{"result":{}}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .ok();
+        }
+    });
+    url
+}
+
+fn function_selector(signature: &str) -> String { geth_exec(&format!("web3.sha3('{}').slice(0, 10)", signature)) }
+
+pub fn mint_erc721(contract: &str, to: &str, token_id: u64) {
+    let data = format!(
+        "{}{:0>64}{:064x}",
+        function_selector("mint(address,uint256)"),
+        to.trim_start_matches("0x"),
+        token_id
+    );
+    geth_send_dev_tx(&format!("to: '{}', data: '{}'", contract, data), "transactionHash");
+}
+
+pub fn mint_erc1155(contract: &str, to: &str, token_id: u64, amount: u64) {
+    let data = format!(
+        "{}{:0>64}{:064x}{:064x}",
+        function_selector("mint(address,uint256,uint256)"),
+        to.trim_start_matches("0x"),
+        token_id,
+        amount
+    );
+    geth_send_dev_tx(&format!("to: '{}', data: '{}'", contract, data), "transactionHash");
+}
+
+/// Returns the owner of the ERC721 token.
+pub fn erc721_owner(contract: &str, token_id: u64) -> String {
+    let data = format!("{}{:064x}", function_selector("ownerOf(uint256)"), token_id);
+    let res = geth_exec(&format!("eth.call({{to: '{}', data: '{}'}})", contract, data));
+    // the address is the last 20 bytes of the 32 bytes word
+    format!("0x{}", &res.trim_start_matches("0x")[24..])
+}
+
+/// Returns the ERC1155 token balance of `owner`.
+pub fn erc1155_balance(contract: &str, owner: &str, token_id: u64) -> u64 {
+    let data = format!(
+        "{}{:0>64}{:064x}",
+        function_selector("balanceOf(address,uint256)"),
+        owner.trim_start_matches("0x"),
+        token_id
+    );
+    let res = geth_exec(&format!("eth.call({{to: '{}', data: '{}'}})", contract, data));
+    // the balances of the tests fit into the last 8 bytes of the 32 bytes word
+    u64::from_str_radix(&res[res.len() - 16..], 16).unwrap()
+}
+
+/// Updates the NFT list of the chain from the logs of the blocks having at least `confirmations` confirmations.
+fn update_nft_from_logs(mm: &MarketMakerIt, chain: &str, confirmations: u64) {
+    let update = block_on(mm.rpc(&json!({
+        "userpass": mm.userpass,
+        "method": "update_nft",
+        "mmrpc": "2.0",
+        "params": {
+            "chains": [chain],
+            "url": NFT_ANTISPAM_MOCK_URL.as_str(),
+            "url_antispam": NFT_ANTISPAM_MOCK_URL.as_str(),
+            "indexers": {
+                chain: { "type": "Logs", "from_block": 0, "confirmations": confirmations },
+            },
+        },
+    })))
+    .unwrap();
+    assert_eq!(update.0, StatusCode::OK, "!update_nft: {}", update.1);
+}
+
+fn get_nft_list_rpc(mm: &MarketMakerIt, chain: &str) -> (StatusCode, String) {
+    let list = block_on(mm.rpc(&json!({
+        "userpass": mm.userpass,
+        "method": "get_nft_list",
+        "mmrpc": "2.0",
+        "params": {
+            "chains": [chain],
+            "max": true,
+        },
+    })))
+    .unwrap();
+    (list.0, list.1)
+}
+
+fn get_nft_list(mm: &MarketMakerIt, chain: &str) -> Vec<Json> {
+    let list = get_nft_list_rpc(mm, chain);
+    assert_eq!(list.0, StatusCode::OK, "!get_nft_list: {}", list.1);
+    let list: Json = json::from_str(&list.1).unwrap();
+    list["result"]["nfts"].as_array().unwrap().clone()
+}
+
+fn find_nft<'a>(nfts: &'a [Json], token_address: &str, token_id: &str) -> &'a Json {
+    nfts.iter()
+        .find(|nft| {
+            nft["token_address"]
+                .as_str()
+                .unwrap()
+                .eq_ignore_ascii_case(token_address)
+                && nft["token_id"].as_str().unwrap() == token_id
+        })
+        .unwrap_or_else(|| panic!("NFT {} {} not found in {:?}", token_address, token_id, nfts))
+}
+
+#[test]
+fn test_update_nft_from_logs() {
+    let erc721 = deploy_nft_test_contract("Erc721Test");
+    let erc1155 = deploy_nft_test_contract("Erc1155Test");
+
+    let mut eth_conf = eth_testnet_conf();
+    eth_conf["protocol"]["protocol_data"] = json!({"nft": true});
+    let coins = json!([eth_conf]);
+    let conf = Mm2TestConf::seednode(&get_passphrase!(".env.client", "ALICE_PASSPHRASE").unwrap(), &coins);
+    let mm = MarketMakerIt::start(conf.conf, conf.rpc_password, None).unwrap();
+    let enable = block_on(enable_eth_coin(
+        &mm,
+        "ETH",
+        &[GETH_LOCAL_URL],
+        // swaps are not used in this test
+        &erc721,
+        None,
+        false,
+    ));
+    let my_address = enable["address"].as_str().unwrap().to_owned();
+
+    mint_erc721(&erc721, &my_address, 1);
+    mint_erc1155(&erc1155, &my_address, 2, 3);
+    update_nft_from_logs(&mm, "ETH", 0);
+
+    let nfts = get_nft_list(&mm, "ETH");
+    assert_eq!(nfts.len(), 2, "{:?}", nfts);
+    assert_eq!(find_nft(&nfts, &erc721, "1")["contract_type"], "ERC721");
+    let erc1155_nft = find_nft(&nfts, &erc1155, "2");
+    assert_eq!(erc1155_nft["contract_type"], "ERC1155");
+    assert_eq!(erc1155_nft["amount"], "3");
+
+    // the next update continues from the last scanned block
+    mint_erc1155(&erc1155, &my_address, 2, 2);
+    update_nft_from_logs(&mm, "ETH", 0);
+
+    let nfts = get_nft_list(&mm, "ETH");
+    assert_eq!(nfts.len(), 2, "{:?}", nfts);
+    assert_eq!(find_nft(&nfts, &erc1155, "2")["amount"], "5");
+}
+
+#[test]
+fn test_update_nft_from_logs_custom_chain() {
+    let erc721 = deploy_nft_test_contract("Erc721Test");
+
+    let coins = json!([{
+        "coin": "ETH-DEV",
+        "name": "ethereum_dev",
+        "mm2": 1,
+        "chain_id": 1337,
+        "derivation_path": "m/44'/60'",
+        "protocol": {
+            "type": "ETH",
+            "protocol_data": {
+                "nft": true
+            }
+        }
+    }]);
+    let conf = Mm2TestConf::seednode(&get_passphrase!(".env.client", "ALICE_PASSPHRASE").unwrap(), &coins);
+    let mm = MarketMakerIt::start(conf.conf, conf.rpc_password, None).unwrap();
+    let enable = block_on(enable_eth_coin(
+        &mm,
+        "ETH-DEV",
+        &[GETH_LOCAL_URL],
+        // swaps are not used in this test
+        &erc721,
+        None,
+        false,
+    ));
+    let my_address = enable["address"].as_str().unwrap().to_owned();
+
+    mint_erc721(&erc721, &my_address, 1);
+    update_nft_from_logs(&mm, "ETH-DEV", 0);
+
+    let nfts = get_nft_list(&mm, "ETH-DEV");
+    assert_eq!(nfts.len(), 1, "{:?}", nfts);
+    assert_eq!(find_nft(&nfts, &erc721, "1")["chain"], "ETH-DEV");
+}
+
+#[test]
+fn test_update_nft_from_logs_skips_unconfirmed_blocks() {
+    let erc721 = deploy_nft_test_contract("Erc721Test");
+
+    let mut eth_conf = eth_testnet_conf();
+    eth_conf["protocol"]["protocol_data"] = json!({"nft": true});
+    let coins = json!([eth_conf]);
+    let conf = Mm2TestConf::seednode(&get_passphrase!(".env.client", "ALICE_PASSPHRASE").unwrap(), &coins);
+    let mm = MarketMakerIt::start(conf.conf, conf.rpc_password, None).unwrap();
+    let enable = block_on(enable_eth_coin(
+        &mm,
+        "ETH",
+        &[GETH_LOCAL_URL],
+        // swaps are not used in this test
+        &erc721,
+        None,
+        false,
+    ));
+    let my_address = enable["address"].as_str().unwrap().to_owned();
+
+    // the dev node mines a block per transaction, so the mint block is the latest one
+    mint_erc721(&erc721, &my_address, 1);
+    update_nft_from_logs(&mm, "ETH", 1);
+    let nfts = get_nft_list(&mm, "ETH");
+    assert!(
+        !nfts
+            .iter()
+            .any(|nft| nft["token_address"].as_str().unwrap().eq_ignore_ascii_case(&erc721)),
+        "{:?}",
+        nfts
+    );
+
+    // the previously unconfirmed block is scanned by the next update
+    update_nft_from_logs(&mm, "ETH", 0);
+    let nfts = get_nft_list(&mm, "ETH");
+    assert_eq!(find_nft(&nfts, &erc721, "1")["contract_type"], "ERC721");
+}
+
+#[test]
+fn test_nft_chain_is_not_enabled() {
+    // the legacy ETH chain is enabled by default, so it's disabled explicitly
+    let mut eth_conf = eth_testnet_conf();
+    eth_conf["protocol"]["protocol_data"] = json!({"nft": false});
+    let coins = json!([eth_conf]);
+    let conf = Mm2TestConf::seednode(&get_passphrase!(".env.client", "ALICE_PASSPHRASE").unwrap(), &coins);
+    let mm = MarketMakerIt::start(conf.conf, conf.rpc_password, None).unwrap();
+
+    let (status, body) = get_nft_list_rpc(&mm, "ETH");
+    assert_eq!(status, StatusCode::BAD_REQUEST, "get_nft_list should fail: {}", body);
+    assert!(body.contains("CoinDoesntSupportNft"), "{}", body);
+}
@@ -0,0 +1,270 @@
+use crate::docker_tests::docker_tests_common::*;
+use crate::docker_tests::nft_indexer_tests::{deploy_nft_test_contract, erc1155_balance, erc721_owner,
+                                             fill_eth_address, mint_erc1155, mint_erc721, GETH_LOCAL_URL};
+use crate::generate_utxo_coin_with_random_privkey;
+use mm2_test_helpers::for_tests::{enable_eth_coin, my_balance, mycoin_conf, wait_for_swaps_finish_and_check_status};
+use serde_json::Value as Json;
+use std::time::Duration;
+
+const NFT_TICKER: &str = "NFT_ETH";
+const NFT_TOKEN_ID: u64 = 1;
+
+fn geth_eth_conf() -> Json {
+    json!({
+        "coin": "ETH",
+        "name": "ethereum",
+        "mm2": 1,
+        "chain_id": 1337,
+        "derivation_path": "m/44'/60'",
+        "protocol": {
+            "type": "ETH"
+        }
+    })
+}
+
+/// A single NFT coin per chain, the traded token is set by the orders.
+fn nft_conf() -> Json {
+    json!({
+        "coin": NFT_TICKER,
+        "name": "nft_eth",
+        "mm2": 1,
+        "chain_id": 1337,
+        "protocol": {
+            "type": "NFT",
+            "protocol_data": {
+                "platform": "ETH"
+            }
+        }
+    })
+}
+
+fn nft_order_token(contract_address: &str, contract_type: &str) -> Json {
+    json!({
+        "token_address": contract_address,
+        "token_id": NFT_TOKEN_ID.to_string(),
+        "contract_type": contract_type
+    })
+}
+
+/// Returns the amount of the test NFT owned by `owner`.
+fn nft_balance(contract: &str, contract_type: &str, owner: &str) -> u64 {
+    match contract_type {
+        "ERC721" => (erc721_owner(contract, NFT_TOKEN_ID) == owner.to_lowercase()) as u64,
+        _ => erc1155_balance(contract, owner, NFT_TOKEN_ID),
+    }
+}
+
+/// Runs a swap in which Bob sells `volume` units of the NFT for MYCOIN and Alice buys them with a FillOrKill order.
+/// `mint` is called with the NFT contract and Bob's ETH address to give Bob the token before placing the order.
+/// The NFT balances are checked on-chain since the balance of the NFT coin isn't bound to a token.
+fn swap_nft_for_mycoin(contract_name: &str, contract_type: &str, volume: u64, mint: impl Fn(&str, &str)) {
+    let nft_contract = deploy_nft_test_contract(contract_name);
+    let swap_contract = deploy_nft_test_contract("EtomicSwapNft");
+
+    let (_ctx, _, bob_priv_key) = generate_utxo_coin_with_random_privkey("MYCOIN", 1000.into());
+    let (_ctx, _, alice_priv_key) = generate_utxo_coin_with_random_privkey("MYCOIN", 1000.into());
+    let coins = json!([geth_eth_conf(), nft_conf(), mycoin_conf(1000)]);
+
+    let mut mm_bob = MarketMakerIt::start(
+        json!({
+            "gui": "nogui",
+            "netid": 9000,
+            "dht": "on",  // Enable DHT without delay.
+            "passphrase": format!("0x{}", hex::encode(bob_priv_key)),
+            "coins": coins,
+            "rpc_password": "pass",
+            "i_am_seed": true,
+        }),
+        "pass".to_string(),
+        None,
+    )
+    .unwrap();
+    let (_bob_dump_log, _bob_dump_dashboard) = mm_dump(&mm_bob.log_path);
+
+    let mut mm_alice = MarketMakerIt::start(
+        json!({
+            "gui": "nogui",
+            "netid": 9000,
+            "dht": "on",  // Enable DHT without delay.
+            "passphrase": format!("0x{}", hex::encode(alice_priv_key)),
+            "coins": coins,
+            "rpc_password": "pass",
+            "seednodes": vec![format!("{}", mm_bob.ip)],
+        }),
+        "pass".to_string(),
+        None,
+    )
+    .unwrap();
+    let (_alice_dump_log, _alice_dump_dashboard) = mm_dump(&mm_alice.log_path);
+
+    for mm in [&mm_bob, &mm_alice].iter() {
+        log!("{:?}", block_on(enable_native(mm, "MYCOIN", &[], None)));
+        let enable = block_on(enable_eth_coin(
+            mm,
+            "ETH",
+            &[GETH_LOCAL_URL],
+            &swap_contract,
+            None,
+            false,
+        ));
+        fill_eth_address(enable["address"].as_str().unwrap(), 10);
+        log!(
+            "{:?}",
+            block_on(enable_eth_coin(
+                mm,
+                NFT_TICKER,
+                &[GETH_LOCAL_URL],
+                &swap_contract,
+                None,
+                false
+            ))
+        );
+    }
+
+    let bob_eth_address = block_on(my_balance(&mm_bob, "ETH")).address;
+    let alice_eth_address = block_on(my_balance(&mm_alice, "ETH")).address;
+    mint(&nft_contract, &bob_eth_address);
+    let bob_nft_balance = nft_balance(&nft_contract, contract_type, &bob_eth_address);
+
+    let rc = block_on(mm_bob.rpc(&json!({
+        "userpass": mm_bob.userpass,
+        "method": "setprice",
+        "base": NFT_TICKER,
+        "rel": "MYCOIN",
+        "price": 10,
+        "volume": volume,
+        "nft": nft_order_token(&nft_contract, contract_type),
+    })))
+    .unwrap();
+    assert!(rc.0.is_success(), "!setprice: {}", rc.1);
+
+    // trigger the taker subscription to the orderbook topic first
+    let rc = block_on(mm_alice.rpc(&json!({
+        "userpass": mm_alice.userpass,
+        "method": "orderbook",
+        "base": NFT_TICKER,
+        "rel": "MYCOIN",
+    })))
+    .unwrap();
+    assert!(rc.0.is_success(), "!orderbook: {}", rc.1);
+    thread::sleep(Duration::from_secs(1));
+
+    let rc = block_on(mm_alice.rpc(&json!({
+        "userpass": mm_alice.userpass,
+        "method": "buy",
+        "base": NFT_TICKER,
+        "rel": "MYCOIN",
+        "price": 10,
+        "volume": volume,
+        "nft": nft_order_token(&nft_contract, contract_type),
+        "order_type": {
+            "type": "FillOrKill"
+        },
+    })))
+    .unwrap();
+    assert!(rc.0.is_success(), "!buy: {}", rc.1);
+    let buy: Json = serde_json::from_str(&rc.1).unwrap();
+    let uuid = buy["result"]["uuid"].as_str().unwrap().to_owned();
+
+    block_on(wait_for_swaps_finish_and_check_status(
+        &mut mm_bob,
+        &mut mm_alice,
+        &[&uuid],
+        volume as f64,
+        10.,
+    ));
+
+    assert_eq!(nft_balance(&nft_contract, contract_type, &alice_eth_address), volume);
+    assert_eq!(
+        nft_balance(&nft_contract, contract_type, &bob_eth_address),
+        bob_nft_balance - volume
+    );
+
+    block_on(mm_bob.stop()).unwrap();
+    block_on(mm_alice.stop()).unwrap();
+}
+
+#[test]
+fn test_swap_erc721_for_mycoin() {
+    swap_nft_for_mycoin("Erc721Test", "ERC721", 1, |contract, to| mint_erc721(contract, to, 1));
+}
+
+#[test]
+fn test_swap_erc1155_for_mycoin() {
+    swap_nft_for_mycoin("Erc1155Test", "ERC1155", 2, |contract, to| {
+        mint_erc1155(contract, to, 1, 3)
+    });
+}
+
+#[test]
+fn test_nft_taker_order_must_be_fill_or_kill() {
+    let (_ctx, _, alice_priv_key) = generate_utxo_coin_with_random_privkey("MYCOIN", 1000.into());
+    let nft_contract = deploy_nft_test_contract("Erc721Test");
+    let swap_contract = deploy_nft_test_contract("EtomicSwapNft");
+    let coins = json!([geth_eth_conf(), nft_conf(), mycoin_conf(1000)]);
+
+    let mm = MarketMakerIt::start(
+        json!({
+            "gui": "nogui",
+            "netid": 9000,
+            "dht": "on",  // Enable DHT without delay.
+            "passphrase": format!("0x{}", hex::encode(alice_priv_key)),
+            "coins": coins,
+            "rpc_password": "pass",
+            "i_am_seed": true,
+        }),
+        "pass".to_string(),
+        None,
+    )
+    .unwrap();
+    let (_dump_log, _dump_dashboard) = mm_dump(&mm.log_path);
+
+    log!("{:?}", block_on(enable_native(&mm, "MYCOIN", &[], None)));
+    block_on(enable_eth_coin(
+        &mm,
+        "ETH",
+        &[GETH_LOCAL_URL],
+        &swap_contract,
+        None,
+        false,
+    ));
+    block_on(enable_eth_coin(
+        &mm,
+        NFT_TICKER,
+        &[GETH_LOCAL_URL],
+        &swap_contract,
+        None,
+        false,
+    ));
+
+    let rc = block_on(mm.rpc(&json!({
+        "userpass": mm.userpass,
+        "method": "buy",
+        "base": NFT_TICKER,
+        "rel": "MYCOIN",
+        "price": 10,
+        "volume": 1,
+        "nft": nft_order_token(&nft_contract, "ERC721"),
+    })))
+    .unwrap();
+    assert!(!rc.0.is_success(), "buy success, but should fail: {}", rc.1);
+    assert!(rc.1.contains("FillOrKill"), "{}", rc.1);
+
+    // NFT can't be sent by the taker
+    let rc = block_on(mm.rpc(&json!({
+        "userpass": mm.userpass,
+        "method": "sell",
+        "base": NFT_TICKER,
+        "rel": "MYCOIN",
+        "price": 10,
+        "volume": 1,
+        "nft": nft_order_token(&nft_contract, "ERC721"),
+        "order_type": {
+            "type": "FillOrKill"
+        },
+    })))
+    .unwrap();
+    assert!(!rc.0.is_success(), "sell success, but should fail: {}", rc.1);
+
+    block_on(mm.stop()).unwrap();
+}
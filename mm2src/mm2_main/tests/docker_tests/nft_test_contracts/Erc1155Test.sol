@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC1155Receiver {
+    function onERC1155Received(address operator, address from, uint256 id, uint256 value, bytes calldata data)
+        external
+        returns (bytes4);
+
+    function onERC1155BatchReceived(
+        address operator,
+        address from,
+        uint256[] calldata ids,
+        uint256[] calldata values,
+        bytes calldata data
+    ) external returns (bytes4);
+}
+
+/// Minimal ERC1155 token of the NFT docker tests, anyone can mint.
+contract Erc1155Test {
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(
+        address indexed operator,
+        address indexed from,
+        address indexed to,
+        uint256[] ids,
+        uint256[] values
+    );
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+
+    string public name = "Erc1155Test";
+    string public symbol = "ERC1155TEST";
+
+    mapping(uint256 => mapping(address => uint256)) private balances;
+    mapping(address => mapping(address => bool)) private operatorApprovals;
+
+    function supportsInterface(bytes4 interfaceId) external pure returns (bool) {
+        // ERC165, ERC1155 and ERC1155MetadataURI
+        return interfaceId == 0x01ffc9a7 || interfaceId == 0xd9b67a26 || interfaceId == 0x0e89341c;
+    }
+
+    /// The tokens have no metadata, so the tests don't depend on external services.
+    function uri(uint256) external pure returns (string memory) {
+        return "";
+    }
+
+    function balanceOf(address account, uint256 id) public view returns (uint256) {
+        require(account != address(0), "ERC1155: address zero is not a valid owner");
+        return balances[id][account];
+    }
+
+    function balanceOfBatch(address[] calldata accounts, uint256[] calldata ids)
+        external
+        view
+        returns (uint256[] memory)
+    {
+        require(accounts.length == ids.length, "ERC1155: accounts and ids length mismatch");
+        uint256[] memory batchBalances = new uint256[](accounts.length);
+        for (uint256 i = 0; i < accounts.length; ++i) {
+            batchBalances[i] = balanceOf(accounts[i], ids[i]);
+        }
+        return batchBalances;
+    }
+
+    function mint(address to, uint256 id, uint256 value) external {
+        require(to != address(0), "ERC1155: mint to the zero address");
+        balances[id][to] += value;
+        emit TransferSingle(msg.sender, address(0), to, id, value);
+        checkOnReceived(address(0), to, id, value, "");
+    }
+
+    function setApprovalForAll(address operator, bool approved) external {
+        operatorApprovals[msg.sender][operator] = approved;
+        emit ApprovalForAll(msg.sender, operator, approved);
+    }
+
+    function isApprovedForAll(address account, address operator) public view returns (bool) {
+        return operatorApprovals[account][operator];
+    }
+
+    function safeTransferFrom(address from, address to, uint256 id, uint256 value, bytes calldata data) external {
+        require(from == msg.sender || isApprovedForAll(from, msg.sender), "ERC1155: caller is not token owner or approved");
+        require(to != address(0), "ERC1155: transfer to the zero address");
+        moveTokens(from, to, id, value);
+        emit TransferSingle(msg.sender, from, to, id, value);
+        checkOnReceived(from, to, id, value, data);
+    }
+
+    function safeBatchTransferFrom(
+        address from,
+        address to,
+        uint256[] calldata ids,
+        uint256[] calldata values,
+        bytes calldata data
+    ) external {
+        require(from == msg.sender || isApprovedForAll(from, msg.sender), "ERC1155: caller is not token owner or approved");
+        require(to != address(0), "ERC1155: transfer to the zero address");
+        require(ids.length == values.length, "ERC1155: ids and values length mismatch");
+        for (uint256 i = 0; i < ids.length; ++i) {
+            moveTokens(from, to, ids[i], values[i]);
+        }
+        emit TransferBatch(msg.sender, from, to, ids, values);
+        if (to.code.length > 0) {
+            require(
+                IERC1155Receiver(to).onERC1155BatchReceived(msg.sender, from, ids, values, data) ==
+                    IERC1155Receiver.onERC1155BatchReceived.selector,
+                "ERC1155: transfer to non-ERC1155Receiver implementer"
+            );
+        }
+    }
+
+    function moveTokens(address from, address to, uint256 id, uint256 value) private {
+        require(balances[id][from] >= value, "ERC1155: insufficient balance for transfer");
+        balances[id][from] -= value;
+        balances[id][to] += value;
+    }
+
+    function checkOnReceived(address from, address to, uint256 id, uint256 value, bytes memory data) private {
+        if (to.code.length > 0) {
+            require(
+                IERC1155Receiver(to).onERC1155Received(msg.sender, from, id, value, data) ==
+                    IERC1155Receiver.onERC1155Received.selector,
+                "ERC1155: transfer to non-ERC1155Receiver implementer"
+            );
+        }
+    }
+}
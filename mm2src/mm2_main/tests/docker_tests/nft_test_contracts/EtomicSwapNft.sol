@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IErc721Transfer {
+    function safeTransferFrom(address from, address to, uint256 tokenId) external;
+}
+
+interface IErc1155Transfer {
+    function safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes calldata data) external;
+}
+
+/// HTLC of ERC721/ERC1155 tokens used by the NFT swap docker tests.
+/// The payment id, the payment states and the events are the same as in the `EtomicSwap` contract.
+/// The sender must approve the contract to transfer the token with `setApprovalForAll` before the payment.
+contract EtomicSwapNft {
+    enum PaymentState {
+        Uninitialized,
+        PaymentSent,
+        ReceiverSpent,
+        SenderRefunded
+    }
+
+    struct Payment {
+        bytes20 paymentHash;
+        uint64 lockTime;
+        PaymentState state;
+    }
+
+    mapping(bytes32 => Payment) public payments;
+
+    event PaymentSent(bytes32 id);
+    event ReceiverSpent(bytes32 id, bytes32 secret);
+    event SenderRefunded(bytes32 id);
+
+    function erc1155Payment(
+        bytes32 _id,
+        uint256 _amount,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _receiver,
+        bytes20 _secretHash,
+        uint64 _lockTime
+    ) external {
+        require(_receiver != address(0) && _amount > 0, "Invalid payment arguments");
+        require(payments[_id].state == PaymentState.Uninitialized, "Payment already exists");
+
+        bytes20 paymentHash = erc1155PaymentHash(_receiver, msg.sender, _secretHash, _tokenAddress, _tokenId, _amount);
+        payments[_id] = Payment(paymentHash, _lockTime, PaymentState.PaymentSent);
+        emit PaymentSent(_id);
+
+        IErc1155Transfer(_tokenAddress).safeTransferFrom(msg.sender, address(this), _tokenId, _amount, "");
+    }
+
+    function erc721Payment(
+        bytes32 _id,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _receiver,
+        bytes20 _secretHash,
+        uint64 _lockTime
+    ) external {
+        require(_receiver != address(0), "Invalid payment arguments");
+        require(payments[_id].state == PaymentState.Uninitialized, "Payment already exists");
+
+        bytes20 paymentHash = erc721PaymentHash(_receiver, msg.sender, _secretHash, _tokenAddress, _tokenId);
+        payments[_id] = Payment(paymentHash, _lockTime, PaymentState.PaymentSent);
+        emit PaymentSent(_id);
+
+        IErc721Transfer(_tokenAddress).safeTransferFrom(msg.sender, address(this), _tokenId);
+    }
+
+    function receiverSpendErc1155(
+        bytes32 _id,
+        uint256 _amount,
+        bytes32 _secret,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _sender
+    ) external {
+        require(payments[_id].state == PaymentState.PaymentSent, "Invalid payment state");
+
+        bytes20 paymentHash = erc1155PaymentHash(msg.sender, _sender, secretHash(_secret), _tokenAddress, _tokenId, _amount);
+        require(paymentHash == payments[_id].paymentHash, "Invalid paymentHash");
+        payments[_id].state = PaymentState.ReceiverSpent;
+        emit ReceiverSpent(_id, _secret);
+
+        IErc1155Transfer(_tokenAddress).safeTransferFrom(address(this), msg.sender, _tokenId, _amount, "");
+    }
+
+    function receiverSpendErc721(
+        bytes32 _id,
+        bytes32 _secret,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _sender
+    ) external {
+        require(payments[_id].state == PaymentState.PaymentSent, "Invalid payment state");
+
+        bytes20 paymentHash = erc721PaymentHash(msg.sender, _sender, secretHash(_secret), _tokenAddress, _tokenId);
+        require(paymentHash == payments[_id].paymentHash, "Invalid paymentHash");
+        payments[_id].state = PaymentState.ReceiverSpent;
+        emit ReceiverSpent(_id, _secret);
+
+        IErc721Transfer(_tokenAddress).safeTransferFrom(address(this), msg.sender, _tokenId);
+    }
+
+    function senderRefundErc1155(
+        bytes32 _id,
+        uint256 _amount,
+        bytes20 _paymentHash,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _receiver
+    ) external {
+        require(payments[_id].state == PaymentState.PaymentSent, "Invalid payment state");
+        require(block.timestamp >= payments[_id].lockTime, "Current timestamp didn't exceed payment lock time");
+
+        bytes20 paymentHash = erc1155PaymentHash(_receiver, msg.sender, _paymentHash, _tokenAddress, _tokenId, _amount);
+        require(paymentHash == payments[_id].paymentHash, "Invalid paymentHash");
+        payments[_id].state = PaymentState.SenderRefunded;
+        emit SenderRefunded(_id);
+
+        IErc1155Transfer(_tokenAddress).safeTransferFrom(address(this), msg.sender, _tokenId, _amount, "");
+    }
+
+    function senderRefundErc721(
+        bytes32 _id,
+        bytes20 _paymentHash,
+        address _tokenAddress,
+        uint256 _tokenId,
+        address _receiver
+    ) external {
+        require(payments[_id].state == PaymentState.PaymentSent, "Invalid payment state");
+        require(block.timestamp >= payments[_id].lockTime, "Current timestamp didn't exceed payment lock time");
+
+        bytes20 paymentHash = erc721PaymentHash(_receiver, msg.sender, _paymentHash, _tokenAddress, _tokenId);
+        require(paymentHash == payments[_id].paymentHash, "Invalid paymentHash");
+        payments[_id].state = PaymentState.SenderRefunded;
+        emit SenderRefunded(_id);
+
+        IErc721Transfer(_tokenAddress).safeTransferFrom(address(this), msg.sender, _tokenId);
+    }
+
+    /// Only the transfers made by the contract itself are accepted, so the tokens can't be locked without a payment.
+    function onERC1155Received(address operator, address, uint256, uint256, bytes calldata)
+        external
+        view
+        returns (bytes4)
+    {
+        require(operator == address(this), "Tokens must be sent by the payment methods");
+        return this.onERC1155Received.selector;
+    }
+
+    function onERC721Received(address operator, address, uint256, bytes calldata) external view returns (bytes4) {
+        require(operator == address(this), "Tokens must be sent by the payment methods");
+        return this.onERC721Received.selector;
+    }
+
+    /// The same hash as the `EtomicSwap` contract one: RIPEMD160(SHA256(secret)).
+    function secretHash(bytes32 _secret) private pure returns (bytes20) {
+        return ripemd160(abi.encodePacked(sha256(abi.encodePacked(_secret))));
+    }
+
+    function erc1155PaymentHash(
+        address _receiver,
+        address _sender,
+        bytes20 _secretHash,
+        address _tokenAddress,
+        uint256 _tokenId,
+        uint256 _amount
+    ) private pure returns (bytes20) {
+        return ripemd160(abi.encodePacked(_receiver, _sender, _secretHash, _tokenAddress, _tokenId, _amount));
+    }
+
+    function erc721PaymentHash(
+        address _receiver,
+        address _sender,
+        bytes20 _secretHash,
+        address _tokenAddress,
+        uint256 _tokenId
+    ) private pure returns (bytes20) {
+        return ripemd160(abi.encodePacked(_receiver, _sender, _secretHash, _tokenAddress, _tokenId));
+    }
+}
@@ -1,9 +1,258 @@
 use crate::docker_tests::docker_tests_common::*;
+use coins::solana::solana_coin_with_policy;
+use coins::{MarketCoinOps, PrivKeyBuildPolicy};
+use crypto::Secp256k1Secret;
+use http::StatusCode;
+use mm2_core::mm_ctx::MmCtxBuilder;
 use mm2_number::bigdecimal::Zero;
-use mm2_test_helpers::for_tests::{disable_coin, enable_solana_with_tokens, enable_spl, sign_message, verify_message};
+use mm2_test_helpers::for_tests::{disable_coin, enable_solana_with_tokens, enable_spl, mycoin_conf, sign_message,
+                                  start_swaps, verify_message, wait_for_swaps_finish_and_check_status, Mm2TestConf};
 use mm2_test_helpers::structs::{EnableSolanaWithTokensResponse, EnableSplResponse, RpcV2Response, SignatureResponse,
                                 VerificationResponse};
-use serde_json as json;
+use serde_json::{self as json, Value as Json};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::Duration;
+use testcontainers::clients::Cli;
+use testcontainers::images::generic::GenericImage;
+use testcontainers::Docker;
+
+pub const SOLANA_DOCKER_IMAGE: &str = "docker.io/solanalabs/solana:v1.9.20";
+const SOLANA_LOCAL_URL: &str = "http://127.0.0.1:8899";
+const SOLANA_LOCAL_TICKER: &str = "SOL-LOCAL";
+const SPL_LOCAL_TICKER: &str = "SPL-LOCAL";
+const SPL_LOCAL_DECIMALS: u8 = 6;
+/// The directory with the prebuilt HTLC program `atomic_swap.so`, the program is built in docker if it's not set.
+const SOLANA_PROGRAMS_PATH_ENV: &str = "SOLANA_PROGRAMS_PATH";
+const SOLANA_PROGRAM_SRC_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../coins/solana/atomic_swap_program");
+/// The output directory of `mm2src/coins/solana/atomic_swap_program/build.sh`.
+const SOLANA_PROGRAMS_DEFAULT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../coins/solana/atomic_swap_program/target/deploy"
+);
+const SOLANA_SWAP_PROGRAM_NAME: &str = "atomic_swap";
+/// The Solana tool suite of the validator version is installed into this image to build the HTLC program.
+const SOLANA_PROGRAM_BUILDER_IMAGE: &str = "docker.io/library/rust:1.59";
+const SOLANA_TOOL_SUITE_INSTALLER: &str = "https://release.solana.com/v1.9.20/install";
+
+/// The dex fee address of `SOL-LOCAL`, the address of the `[7; 32]` pubkey.
+const SOLANA_LOCAL_DEX_FEE_ADDRESS: &str = "US517G5965aydkZ46HS38QLi7UQiSojurfbQfKCELFx";
+
+pub static mut SOLANA_CONTAINER_ID: Option<String> = None;
+pub static mut SOLANA_SWAP_PROGRAM_ID: Option<String> = None;
+
+fn is_solana_swap_program_built(programs_path: &Path) -> bool {
+    programs_path.join(format!("{}.so", SOLANA_SWAP_PROGRAM_NAME)).exists()
+}
+
+/// Returns the directory with the built HTLC program, the program is built in docker if it's not built yet.
+/// Panics if the program can't be built, so the tests that need it aren't silently skipped.
+pub fn build_solana_swap_program() -> PathBuf {
+    if let Ok(programs_path) = env::var(SOLANA_PROGRAMS_PATH_ENV) {
+        let programs_path = PathBuf::from(programs_path);
+        assert!(
+            is_solana_swap_program_built(&programs_path),
+            "{}.so is not found in {}={}",
+            SOLANA_SWAP_PROGRAM_NAME,
+            SOLANA_PROGRAMS_PATH_ENV,
+            programs_path.display()
+        );
+        return programs_path;
+    }
+
+    let programs_path = PathBuf::from(SOLANA_PROGRAMS_DEFAULT_PATH);
+    if is_solana_swap_program_built(&programs_path) {
+        return programs_path;
+    }
+
+    let build = format!(
+        "sh -c \"$(curl -sSfL {})\" && PATH=/root/.local/share/solana/install/active_release/bin:$PATH ./build.sh",
+        SOLANA_TOOL_SUITE_INSTALLER
+    );
+    let output = Command::new("docker")
+        .args(["run", "--rm", "-v"])
+        .arg(format!("{}:/program", SOLANA_PROGRAM_SRC_PATH))
+        .args(["-w", "/program"])
+        .arg(SOLANA_PROGRAM_BUILDER_IMAGE)
+        .args(["sh", "-c", &build])
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!build Solana HTLC program: {:?}", output);
+    assert!(
+        is_solana_swap_program_built(&programs_path),
+        "{}.so is not built",
+        SOLANA_SWAP_PROGRAM_NAME
+    );
+    programs_path
+}
+
+pub fn solana_docker_node(docker: &Cli, port: u16, programs_path: &Path) -> UtxoDockerNode {
+    let args = vec![
+        "-v".into(),
+        format!("{}:/programs", programs_path.display()),
+        "-p".into(),
+        format!("127.0.0.1:{}:{}", port, port),
+    ];
+    let image = GenericImage::new(SOLANA_DOCKER_IMAGE).with_args(args);
+    let container = docker.run(image);
+
+    unsafe { SOLANA_CONTAINER_ID = Some(container.id().to_owned()) };
+    UtxoDockerNode {
+        container,
+        ticker: SOLANA_LOCAL_TICKER.to_owned(),
+        port,
+    }
+}
+
+/// Runs the command in the Solana container against the local validator.
+fn solana_docker_exec(args: &[&str]) -> Output {
+    let container_id = unsafe {
+        SOLANA_CONTAINER_ID
+            .as_ref()
+            .expect("Solana container is not started yet")
+    };
+    Command::new("docker")
+        .arg("exec")
+        .arg(container_id)
+        .args(args)
+        .args(["--url", SOLANA_LOCAL_URL])
+        .output()
+        .expect("Failed to execute docker command")
+}
+
+pub fn wait_for_solana_node_ready() {
+    let timeout = wait_until_ms(120000);
+    while !solana_docker_exec(&["solana", "cluster-version"]).status.success() {
+        assert!(now_ms() < timeout, "Test timed out");
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Deploys the HTLC program, the program id is generated on every run.
+pub fn deploy_solana_swap_program() {
+    let payer = "/root/payer.json";
+    let output = Command::new("docker")
+        .args(["exec", unsafe { SOLANA_CONTAINER_ID.as_ref().unwrap() }])
+        .args([
+            "solana-keygen",
+            "new",
+            "--no-bip39-passphrase",
+            "--silent",
+            "--force",
+            "-o",
+            payer,
+        ])
+        .output()
+        .expect("Failed to execute docker command");
+    assert!(output.status.success(), "!solana-keygen: {:?}", output);
+    let output = solana_docker_exec(&["solana", "airdrop", "100", "--keypair", payer]);
+    assert!(output.status.success(), "!solana airdrop: {:?}", output);
+
+    let program_path = format!("/programs/{}.so", SOLANA_SWAP_PROGRAM_NAME);
+    let output = solana_docker_exec(&["solana", "program", "deploy", &program_path, "--keypair", payer]);
+    assert!(output.status.success(), "!solana program deploy: {:?}", output);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let program_id = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Program Id:"))
+        .unwrap_or_else(|| panic!("No program id in the deploy output: {}", stdout))
+        .trim()
+        .to_owned();
+    log!("Solana swap program is deployed at {}", program_id);
+    unsafe { SOLANA_SWAP_PROGRAM_ID = Some(program_id) };
+}
+
+fn solana_swap_program_id() -> String {
+    unsafe {
+        SOLANA_SWAP_PROGRAM_ID
+            .clone()
+            .expect("Solana swap program is not deployed yet")
+    }
+}
+
+fn fill_solana_address(address: &str, amount: u64) {
+    let output = solana_docker_exec(&["solana", "airdrop", &amount.to_string(), address]);
+    assert!(output.status.success(), "!solana airdrop: {:?}", output);
+}
+
+fn solana_local_conf() -> Json {
+    json!({
+        "coin": SOLANA_LOCAL_TICKER,
+        "name": "solana",
+        "mm2": 1,
+        "required_confirmations": 1,
+        "dex_fee_address": SOLANA_LOCAL_DEX_FEE_ADDRESS,
+        "protocol": {"type": "SOLANA"},
+    })
+}
+
+fn spl_local_conf(token_contract_address: &str) -> Json {
+    json!({
+        "coin": SPL_LOCAL_TICKER,
+        "mm2": 1,
+        "protocol": {
+            "type": "SPLTOKEN",
+            "protocol_data": {
+                "decimals": SPL_LOCAL_DECIMALS,
+                "token_contract_address": token_contract_address,
+                "platform": SOLANA_LOCAL_TICKER,
+            }
+        },
+    })
+}
+
+fn solana_local_activation_params() -> Json {
+    json!({
+        "confirmation_commitment": "confirmed",
+        "client_url": SOLANA_LOCAL_URL,
+        "swap_program_id": solana_swap_program_id(),
+    })
+}
+
+/// Enables `SOL-LOCAL` with the swap program and funds the address with the `amount` of SOL.
+/// Returns the address.
+fn enable_solana_local_and_fill(mm: &MarketMakerIt, tokens: &[&str], amount: u64) -> String {
+    let spl_requests: Vec<_> = tokens.iter().map(|ticker| json!({ "ticker": ticker })).collect();
+    let mut params = solana_local_activation_params();
+    params["ticker"] = SOLANA_LOCAL_TICKER.into();
+    params["tx_history"] = false.into();
+    params["spl_tokens_requests"] = spl_requests.into();
+
+    let enable = block_on(mm.rpc(&json!({
+        "userpass": mm.userpass,
+        "method": "enable_solana_with_tokens",
+        "mmrpc": "2.0",
+        "params": params,
+    })))
+    .unwrap();
+    assert_eq!(enable.0, StatusCode::OK, "!enable_solana_with_tokens: {}", enable.1);
+    let enable: RpcV2Response<EnableSolanaWithTokensResponse> = json::from_str(&enable.1).unwrap();
+
+    let address = enable.result.solana_addresses_infos.into_keys().next().unwrap();
+    fill_solana_address(&address, amount);
+    address
+}
+
+/// Creates an SPL token and mints the `amount` of it to the address of the `priv_key`.
+/// The address is funded with SOL to pay for the token creation.
+fn create_spl_token(priv_key: Secp256k1Secret, amount: u64) -> String {
+    let ctx = MmCtxBuilder::new().into_mm_arc();
+    let params = json::from_value(solana_local_activation_params()).unwrap();
+    let coin = block_on(solana_coin_with_policy(
+        &ctx,
+        SOLANA_LOCAL_TICKER,
+        &solana_local_conf(),
+        params,
+        PrivKeyBuildPolicy::IguanaPrivKey(priv_key),
+    ))
+    .unwrap();
+    let my_address = coin.my_address().unwrap();
+    fill_solana_address(&my_address, 10);
+    block_on(coin.create_spl_token_for_tests(SPL_LOCAL_DECIMALS, &my_address, amount))
+        .unwrap()
+        .to_string()
+}
 
 #[test]
 fn test_solana_and_spl_balance_enable_spl_v2() {
@@ -142,3 +391,102 @@ fn test_disable_solana_platform_coin_with_tokens() {
     let res = block_on(disable_coin(&mm, "SOL-DEVNET", true));
     assert!(!res.passivized);
 }
+
+fn start_solana_swap_nodes(coins: &Json, bob_priv_key: Secp256k1Secret) -> (MarketMakerIt, MarketMakerIt) {
+    let (_ctx, _, alice_priv_key) = generate_utxo_coin_with_random_privkey(MYCOIN, 1000.into());
+
+    let bob_conf = Mm2TestConf::seednode(&format!("0x{}", hex::encode(bob_priv_key)), coins);
+    let mm_bob = MarketMakerIt::start(bob_conf.conf, bob_conf.rpc_password, None).unwrap();
+    let alice_conf = Mm2TestConf::light_node(&format!("0x{}", hex::encode(alice_priv_key)), coins, &[&mm_bob
+        .ip
+        .to_string()]);
+    let mm_alice = MarketMakerIt::start(alice_conf.conf, alice_conf.rpc_password, None).unwrap();
+
+    log!("{:?}", block_on(enable_native(&mm_bob, MYCOIN, &[], None)));
+    log!("{:?}", block_on(enable_native(&mm_alice, MYCOIN, &[], None)));
+    (mm_bob, mm_alice)
+}
+
+fn solana_swap_test(pairs: &[(&'static str, &'static str)]) {
+    let (_ctx, _, bob_priv_key) = generate_utxo_coin_with_random_privkey(MYCOIN, 1000.into());
+    let coins = json!([solana_local_conf(), mycoin_conf(1000)]);
+    let (mut mm_bob, mut mm_alice) = start_solana_swap_nodes(&coins, bob_priv_key);
+    let (_bob_dump_log, _bob_dump_dashboard) = mm_dump(&mm_bob.log_path);
+    let (_alice_dump_log, _alice_dump_dashboard) = mm_dump(&mm_alice.log_path);
+
+    enable_solana_local_and_fill(&mm_bob, &[], 10);
+    enable_solana_local_and_fill(&mm_alice, &[], 10);
+
+    let uuids = block_on(start_swaps(&mut mm_bob, &mut mm_alice, pairs, 1., 1., 1.));
+    block_on(wait_for_swaps_finish_and_check_status(
+        &mut mm_bob,
+        &mut mm_alice,
+        &uuids,
+        1.,
+        1.,
+    ));
+
+    block_on(mm_bob.stop()).unwrap();
+    block_on(mm_alice.stop()).unwrap();
+}
+
+#[test]
+fn test_solana_maker_swap() { solana_swap_test(&[(SOLANA_LOCAL_TICKER, MYCOIN)]) }
+
+#[test]
+fn test_solana_taker_swap() { solana_swap_test(&[(MYCOIN, SOLANA_LOCAL_TICKER)]) }
+
+#[test]
+fn test_spl_token_swaps() {
+    // The token address is a part of the coins config, so the token is created before the nodes are started.
+    let (_ctx, _, bob_priv_key) = generate_utxo_coin_with_random_privkey(MYCOIN, 1000.into());
+    let token_contract_address = create_spl_token(bob_priv_key, 1000 * 10u64.pow(SPL_LOCAL_DECIMALS as u32));
+
+    let coins = json!([
+        solana_local_conf(),
+        spl_local_conf(&token_contract_address),
+        mycoin_conf(1000)
+    ]);
+    let (mut mm_bob, mut mm_alice) = start_solana_swap_nodes(&coins, bob_priv_key);
+    let (_bob_dump_log, _bob_dump_dashboard) = mm_dump(&mm_bob.log_path);
+    let (_alice_dump_log, _alice_dump_dashboard) = mm_dump(&mm_alice.log_path);
+
+    enable_solana_local_and_fill(&mm_bob, &[SPL_LOCAL_TICKER], 10);
+    enable_solana_local_and_fill(&mm_alice, &[SPL_LOCAL_TICKER], 10);
+
+    // Bob sells the token for MYCOIN and then buys a half of it back, so the token is both sent and received by each side.
+    let uuids = block_on(start_swaps(
+        &mut mm_bob,
+        &mut mm_alice,
+        &[(SPL_LOCAL_TICKER, MYCOIN)],
+        1.,
+        1.,
+        10.,
+    ));
+    block_on(wait_for_swaps_finish_and_check_status(
+        &mut mm_bob,
+        &mut mm_alice,
+        &uuids,
+        10.,
+        1.,
+    ));
+
+    let uuids = block_on(start_swaps(
+        &mut mm_bob,
+        &mut mm_alice,
+        &[(MYCOIN, SPL_LOCAL_TICKER)],
+        1.,
+        1.,
+        5.,
+    ));
+    block_on(wait_for_swaps_finish_and_check_status(
+        &mut mm_bob,
+        &mut mm_alice,
+        &uuids,
+        5.,
+        1.,
+    ));
+
+    block_on(mm_bob.stop()).unwrap();
+    block_on(mm_alice.stop()).unwrap();
+}
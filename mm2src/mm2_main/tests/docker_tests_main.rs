@@ -27,8 +27,15 @@ use std::process::Command;
 use test::{test_main, StaticBenchFn, StaticTestFn, TestDescAndFn};
 use testcontainers::clients::Cli;
 mod docker_tests;
+use docker_tests::cosmwasm_htlc_tests::{build_cw_atomic_swap_contract, deploy_cw_atomic_swap_contract,
+                                        wait_for_wasmd_node_ready, wasmd_docker_node, WASMD_DOCKER_IMAGE};
 use docker_tests::docker_tests_common::*;
+use docker_tests::nft_indexer_tests::{compile_nft_test_contracts, geth_docker_node, wait_for_geth_node_ready,
+                                      GETH_DOCKER_IMAGE, SOLC_DOCKER_IMAGE};
 use docker_tests::qrc20_tests::{qtum_docker_node, QtumDockerOps, QTUM_REGTEST_DOCKER_IMAGE};
+#[cfg(feature = "enable-solana")]
+use docker_tests::solana_tests::{build_solana_swap_program, deploy_solana_swap_program, solana_docker_node,
+                                 wait_for_solana_node_ready, SOLANA_DOCKER_IMAGE};
 #[allow(dead_code)] mod integration_tests_common;
 
 // AP: custom test runner is intended to initialize the required environment (e.g. coin daemons in the docker containers)
@@ -47,8 +54,13 @@ pub fn docker_tests_runner(tests: &[&TestDescAndFn]) {
     if std::env::var("_MM2_TEST_CONF").is_err() {
         pull_docker_image(UTXO_ASSET_DOCKER_IMAGE);
         pull_docker_image(QTUM_REGTEST_DOCKER_IMAGE);
+        pull_docker_image(GETH_DOCKER_IMAGE);
+        pull_docker_image(SOLC_DOCKER_IMAGE);
+        pull_docker_image(WASMD_DOCKER_IMAGE);
         remove_docker_containers(UTXO_ASSET_DOCKER_IMAGE);
         remove_docker_containers(QTUM_REGTEST_DOCKER_IMAGE);
+        remove_docker_containers(GETH_DOCKER_IMAGE);
+        remove_docker_containers(WASMD_DOCKER_IMAGE);
 
         let utxo_node = utxo_asset_docker_node(&docker, "MYCOIN", 7000);
         let utxo_node1 = utxo_asset_docker_node(&docker, "MYCOIN1", 8000);
@@ -71,6 +83,26 @@ pub fn docker_tests_runner(tests: &[&TestDescAndFn]) {
         containers.push(utxo_node1);
         containers.push(qtum_node);
         containers.push(for_slp_node);
+
+        compile_nft_test_contracts();
+        geth_docker_node(8545);
+        wait_for_geth_node_ready();
+
+        let cw_artifacts_dir = build_cw_atomic_swap_contract();
+        wasmd_docker_node(26657, &cw_artifacts_dir);
+        wait_for_wasmd_node_ready();
+        deploy_cw_atomic_swap_contract();
+
+        #[cfg(feature = "enable-solana")]
+        {
+            let programs_path = build_solana_swap_program();
+            pull_docker_image(SOLANA_DOCKER_IMAGE);
+            remove_docker_containers(SOLANA_DOCKER_IMAGE);
+            let solana_node = solana_docker_node(&docker, 8899, &programs_path);
+            wait_for_solana_node_ready();
+            deploy_solana_swap_program();
+            containers.push(solana_node);
+        }
     }
     // detect if docker is installed
     // skip the tests that use docker if not installed
@@ -90,6 +122,11 @@ pub fn docker_tests_runner(tests: &[&TestDescAndFn]) {
         .collect();
     let args: Vec<String> = std::env::args().collect();
     test_main(&args, owned_tests, None);
+    // geth and wasmd containers are not managed by testcontainers, so they should be removed explicitly
+    if std::env::var("_MM2_TEST_CONF").is_err() {
+        remove_docker_containers(GETH_DOCKER_IMAGE);
+        remove_docker_containers(WASMD_DOCKER_IMAGE);
+    }
 }
 
 fn pull_docker_image(name: &str) {
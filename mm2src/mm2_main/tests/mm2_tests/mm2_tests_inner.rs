@@ -16,14 +16,15 @@ use mm2_test_helpers::for_tests::check_stats_swap_status;
 use mm2_test_helpers::for_tests::{btc_segwit_conf, btc_with_spv_conf, btc_with_sync_starting_header,
                                   check_recent_swaps, enable_eth_coin, enable_qrc20, eth_jst_testnet_conf,
                                   eth_testnet_conf, find_metrics_in_json, from_env_file, get_shared_db_id, mm_spat,
-                                  morty_conf, rick_conf, sign_message, start_swaps, tbtc_segwit_conf,
+                                  morty_conf, rick_conf, sign_message, sign_typed_data, start_swaps, tbtc_segwit_conf,
                                   tbtc_with_spv_conf, test_qrc20_history_impl, tqrc20_conf, verify_message,
-                                  wait_for_swap_contract_negotiation, wait_for_swap_negotiation_failure,
-                                  wait_for_swaps_finish_and_check_status, wait_till_history_has_records,
-                                  MarketMakerIt, Mm2InitPrivKeyPolicy, Mm2TestConf, Mm2TestConfForSwap, RaiiDump,
-                                  DOC_ELECTRUM_ADDRS, ETH_DEV_NODES, ETH_DEV_SWAP_CONTRACT, ETH_DEV_TOKEN_CONTRACT,
-                                  ETH_MAINNET_NODE, ETH_MAINNET_SWAP_CONTRACT, MARTY_ELECTRUM_ADDRS, MORTY,
-                                  QRC20_ELECTRUMS, RICK, RICK_ELECTRUM_ADDRS, TBTC_ELECTRUMS, T_BCH_ELECTRUMS};
+                                  verify_typed_data, wait_for_swap_contract_negotiation,
+                                  wait_for_swap_negotiation_failure, wait_for_swaps_finish_and_check_status,
+                                  wait_till_history_has_records, MarketMakerIt, Mm2InitPrivKeyPolicy, Mm2TestConf,
+                                  Mm2TestConfForSwap, RaiiDump, DOC_ELECTRUM_ADDRS, ETH_DEV_NODES,
+                                  ETH_DEV_SWAP_CONTRACT, ETH_DEV_TOKEN_CONTRACT, ETH_MAINNET_NODE,
+                                  ETH_MAINNET_SWAP_CONTRACT, MARTY_ELECTRUM_ADDRS, MORTY, QRC20_ELECTRUMS, RICK,
+                                  RICK_ELECTRUM_ADDRS, TBTC_ELECTRUMS, T_BCH_ELECTRUMS};
 use mm2_test_helpers::get_passphrase;
 use mm2_test_helpers::structs::*;
 use serde_json::{self as json, json, Value as Json};
@@ -6948,6 +6949,93 @@ fn test_sign_verify_message_eth() {
     assert!(response.is_valid);
 }
 
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_sign_verify_typed_data_eth() {
+    let seed = "spice describe gravity federal blast come thank unfair canal monkey style afraid";
+    let coins = json!([eth_testnet_conf()]);
+
+    let conf = Mm2TestConf::seednode(seed, &coins);
+    let mm = MarketMakerIt::start(conf.conf, conf.rpc_password, None).unwrap();
+    let (_dump_log, _dump_dashboard) = mm.mm_dump();
+    log!("log path: {}", mm.log_path.display());
+
+    log!("{:?}", block_on(enable_native(&mm, "ETH", ETH_DEV_NODES, None)));
+
+    // The example from the EIP-712 specification.
+    let typed_data = json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    });
+
+    let response = block_on(sign_typed_data(&mm, "ETH", typed_data.clone()));
+    let response = &response["result"];
+    assert_eq!(
+        response["hash"],
+        "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+    );
+    assert_eq!(response["address"], "0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29");
+    let signature = response["signature"].as_str().unwrap();
+
+    let response = block_on(verify_typed_data(
+        &mm,
+        "ETH",
+        typed_data.clone(),
+        signature,
+        "0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29",
+    ));
+    assert_eq!(response["result"]["is_valid"], true);
+
+    // the signature doesn't match another address
+    let response = block_on(verify_typed_data(
+        &mm,
+        "ETH",
+        typed_data.clone(),
+        signature,
+        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+    ));
+    assert_eq!(response["result"]["is_valid"], false);
+
+    // the signature doesn't match modified data
+    let mut modified = typed_data;
+    modified["message"]["contents"] = json!("Hello, Alice!");
+    let response = block_on(verify_typed_data(
+        &mm,
+        "ETH",
+        modified,
+        signature,
+        "0xbAB36286672fbdc7B250804bf6D14Be0dF69fa29",
+    ));
+    assert_eq!(response["result"]["is_valid"], false);
+}
+
 #[test]
 #[cfg(not(target_arch = "wasm32"))]
 fn test_no_login() {
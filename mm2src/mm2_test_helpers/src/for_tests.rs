@@ -2540,6 +2540,50 @@ pub async fn verify_message(mm: &MarketMakerIt, coin: &str, signature: &str, add
     json::from_str(&request.1).unwrap()
 }
 
+pub async fn sign_typed_data(mm: &MarketMakerIt, coin: &str, typed_data: Json) -> Json {
+    let request = mm
+        .rpc(&json!({
+            "userpass": mm.userpass,
+            "method": "sign_typed_data",
+            "mmrpc": "2.0",
+            "id": 0,
+            "params": {
+                "coin": coin,
+                "typed_data": typed_data,
+            }
+        }))
+        .await
+        .unwrap();
+    assert_eq!(request.0, StatusCode::OK, "'sign_typed_data' failed: {}", request.1);
+    json::from_str(&request.1).unwrap()
+}
+
+pub async fn verify_typed_data(
+    mm: &MarketMakerIt,
+    coin: &str,
+    typed_data: Json,
+    signature: &str,
+    address: &str,
+) -> Json {
+    let request = mm
+        .rpc(&json!({
+            "userpass": mm.userpass,
+            "method": "verify_typed_data",
+            "mmrpc": "2.0",
+            "id": 0,
+            "params": {
+                "coin": coin,
+                "typed_data": typed_data,
+                "signature": signature,
+                "address": address,
+            }
+        }))
+        .await
+        .unwrap();
+    assert_eq!(request.0, StatusCode::OK, "'verify_typed_data' failed: {}", request.1);
+    json::from_str(&request.1).unwrap()
+}
+
 pub async fn send_raw_transaction(mm: &MarketMakerIt, coin: &str, tx: &str) -> Json {
     let request = mm
         .rpc(&json!({